@@ -10,6 +10,24 @@ impl EnvVars {
     pub const TERM: &'static str = "TERM";
     pub const CI: &'static str = "CI";
 
+    // Terminal related
+    pub const TERM_PROGRAM: &'static str = "TERM_PROGRAM";
+    pub const VTE_VERSION: &'static str = "VTE_VERSION";
+    pub const WT_SESSION: &'static str = "WT_SESSION";
+    pub const KONSOLE_VERSION: &'static str = "KONSOLE_VERSION";
+    pub const FORCE_COLOR: &'static str = "FORCE_COLOR";
+    pub const CLICOLOR_FORCE: &'static str = "CLICOLOR_FORCE";
+    pub const PY_COLORS: &'static str = "PY_COLORS";
+
+    // CI related
+    pub const GITHUB_ACTIONS: &'static str = "GITHUB_ACTIONS";
+    pub const GITHUB_BASE_REF: &'static str = "GITHUB_BASE_REF";
+    pub const GITLAB_CI: &'static str = "GITLAB_CI";
+    pub const CI_MERGE_REQUEST_TARGET_BRANCH_NAME: &'static str =
+        "CI_MERGE_REQUEST_TARGET_BRANCH_NAME";
+    pub const BITBUCKET_BUILD_NUMBER: &'static str = "BITBUCKET_BUILD_NUMBER";
+    pub const BITBUCKET_PR_DESTINATION_BRANCH: &'static str = "BITBUCKET_PR_DESTINATION_BRANCH";
+
     // Git related
     pub const GIT_DIR: &'static str = "GIT_DIR";
     pub const GIT_WORK_TREE: &'static str = "GIT_WORK_TREE";
@@ -19,20 +37,34 @@ impl EnvVars {
 
     // PREK specific environment variables, public for users
     pub const PREK_HOME: &'static str = "PREK_HOME";
+    pub const PREK_HOME_OVERLAY: &'static str = "PREK_HOME_OVERLAY";
+    pub const PREK_DATA_DIR: &'static str = "PREK_DATA_DIR";
+    pub const PREK_CACHE_DIR: &'static str = "PREK_CACHE_DIR";
     pub const PREK_COLOR: &'static str = "PREK_COLOR";
+    pub const PREK_WARNINGS: &'static str = "PREK_WARNINGS";
     pub const PREK_SKIP: &'static str = "PREK_SKIP";
+    pub const PREK_HOOK_TYPES: &'static str = "PREK_HOOK_TYPES";
     pub const PREK_ALLOW_NO_CONFIG: &'static str = "PREK_ALLOW_NO_CONFIG";
     pub const PREK_NO_CONCURRENCY: &'static str = "PREK_NO_CONCURRENCY";
     pub const PREK_NO_FAST_PATH: &'static str = "PREK_NO_FAST_PATH";
     pub const PREK_UV_SOURCE: &'static str = "PREK_UV_SOURCE";
+    pub const PREK_NODE_MIRROR: &'static str = "PREK_NODE_MIRROR";
+    pub const PREK_GO_MIRROR: &'static str = "PREK_GO_MIRROR";
+    pub const PREK_PYTHON_MIRROR: &'static str = "PREK_PYTHON_MIRROR";
+    pub const PREK_TOOLCHAIN_CHECKSUMS: &'static str = "PREK_TOOLCHAIN_CHECKSUMS";
     pub const PREK_NATIVE_TLS: &'static str = "PREK_NATIVE_TLS";
-    pub const SSL_CERT_FILE: &'static str = "SSL_CERT_FILE";
+    pub const PREK_HTTP_RETRIES: &'static str = "PREK_HTTP_RETRIES";
+    pub const PREK_OFFLINE: &'static str = "PREK_OFFLINE";
+    pub const PREK_STATS: &'static str = "PREK_STATS";
+    pub const PREK_NO_PUSH_CACHE: &'static str = "PREK_NO_PUSH_CACHE";
     pub const PREK_CONTAINER_RUNTIME: &'static str = "PREK_CONTAINER_RUNTIME";
+    pub const PREK_HYPERLINKS: &'static str = "PREK_HYPERLINKS";
 
     // PREK internal environment variables
     pub const PREK_INTERNAL__TEST_DIR: &'static str = "PREK_INTERNAL__TEST_DIR";
     pub const PREK_INTERNAL__SORT_FILENAMES: &'static str = "PREK_INTERNAL__SORT_FILENAMES";
     pub const PREK_INTERNAL__SKIP_POST_CHECKOUT: &'static str = "PREK_INTERNAL__SKIP_POST_CHECKOUT";
+    pub const PREK_INTERNAL__WORK_TREE_LOCK: &'static str = "PREK_INTERNAL__WORK_TREE_LOCK";
     pub const PREK_INTERNAL__RUN_ORIGINAL_PRE_COMMIT: &'static str =
         "PREK_INTERNAL__RUN_ORIGINAL_PRE_COMMIT";
     pub const PREK_INTERNAL__GO_BINARY_NAME: &'static str = "PREK_INTERNAL__GO_BINARY_NAME";
@@ -46,13 +78,22 @@ impl EnvVars {
     pub const UV_PYTHON: &'static str = "UV_PYTHON";
     pub const UV_CACHE_DIR: &'static str = "UV_CACHE_DIR";
     pub const UV_PYTHON_INSTALL_DIR: &'static str = "UV_PYTHON_INSTALL_DIR";
+    pub const UV_PYTHON_INSTALL_MIRROR: &'static str = "UV_PYTHON_INSTALL_MIRROR";
     pub const UV_MANAGED_PYTHON: &'static str = "UV_MANAGED_PYTHON";
     pub const UV_NO_MANAGED_PYTHON: &'static str = "UV_NO_MANAGED_PYTHON";
+    pub const UV_OFFLINE: &'static str = "UV_OFFLINE";
+    pub const PIP_NO_INDEX: &'static str = "PIP_NO_INDEX";
 
     // Node/Npm related
     pub const NPM_CONFIG_USERCONFIG: &'static str = "NPM_CONFIG_USERCONFIG";
     pub const NPM_CONFIG_PREFIX: &'static str = "NPM_CONFIG_PREFIX";
+    pub const NPM_CONFIG_OFFLINE: &'static str = "NPM_CONFIG_OFFLINE";
     pub const NODE_PATH: &'static str = "NODE_PATH";
+    pub const COREPACK_HOME: &'static str = "COREPACK_HOME";
+    pub const COREPACK_ENABLE_DOWNLOAD_PROMPT: &'static str = "COREPACK_ENABLE_DOWNLOAD_PROMPT";
+    pub const COREPACK_ENABLE_STRICT: &'static str = "COREPACK_ENABLE_STRICT";
+    pub const YARN_NODE_LINKER: &'static str = "YARN_NODE_LINKER";
+    pub const YARN_ENABLE_GLOBAL_CACHE: &'static str = "YARN_ENABLE_GLOBAL_CACHE";
 
     // Go related
     pub const GOTOOLCHAIN: &'static str = "GOTOOLCHAIN";
@@ -60,11 +101,38 @@ impl EnvVars {
     pub const GOPATH: &'static str = "GOPATH";
     pub const GOBIN: &'static str = "GOBIN";
     pub const GOFLAGS: &'static str = "GOFLAGS";
+    pub const GOPROXY: &'static str = "GOPROXY";
+    pub const GOPRIVATE: &'static str = "GOPRIVATE";
+    pub const GONOPROXY: &'static str = "GONOPROXY";
+    pub const GONOSUMDB: &'static str = "GONOSUMDB";
+    pub const GOINSECURE: &'static str = "GOINSECURE";
+    pub const GOAUTH: &'static str = "GOAUTH";
+    pub const GOWORK: &'static str = "GOWORK";
+    pub const NETRC: &'static str = "NETRC";
+
+    // Network related
+    pub const HTTP_PROXY: &'static str = "HTTP_PROXY";
+    pub const HTTPS_PROXY: &'static str = "HTTPS_PROXY";
+    pub const NO_PROXY: &'static str = "NO_PROXY";
+    pub const SSL_CERT_FILE: &'static str = "SSL_CERT_FILE";
+    pub const GIT_SSL_CAINFO: &'static str = "GIT_SSL_CAINFO";
+    pub const REQUESTS_CA_BUNDLE: &'static str = "REQUESTS_CA_BUNDLE";
+    pub const NODE_EXTRA_CA_CERTS: &'static str = "NODE_EXTRA_CA_CERTS";
+    pub const CARGO_HTTP_CAINFO: &'static str = "CARGO_HTTP_CAINFO";
+
+    // Java related
+    pub const JAVA_HOME: &'static str = "JAVA_HOME";
+    pub const COURSIER_CACHE: &'static str = "COURSIER_CACHE";
 
     // Lua related
     pub const LUA_PATH: &'static str = "LUA_PATH";
     pub const LUA_CPATH: &'static str = "LUA_CPATH";
 
+    // R related
+    pub const R_HOME: &'static str = "R_HOME";
+    pub const R_LIBS: &'static str = "R_LIBS";
+    pub const R_LIBS_USER: &'static str = "R_LIBS_USER";
+
     // Ruby related
     pub const GEM_HOME: &'static str = "GEM_HOME";
     pub const GEM_PATH: &'static str = "GEM_PATH";
@@ -76,6 +144,8 @@ impl EnvVars {
     pub const RUSTUP_AUTO_INSTALL: &'static str = "RUSTUP_AUTO_INSTALL";
     pub const CARGO_HOME: &'static str = "CARGO_HOME";
     pub const RUSTUP_HOME: &'static str = "RUSTUP_HOME";
+    pub const CARGO_NET_OFFLINE: &'static str = "CARGO_NET_OFFLINE";
+    pub const RUSTC_WRAPPER: &'static str = "RUSTC_WRAPPER";
 }
 
 impl EnvVars {
@@ -8,6 +8,7 @@ use env_vars::EnvVars;
 pub const CONFIG_FILE: &str = ".pre-commit-config.yaml";
 pub const ALT_CONFIG_FILE: &str = ".pre-commit-config.yml";
 pub const MANIFEST_FILE: &str = ".pre-commit-hooks.yaml";
+pub const TOML_MANIFEST_FILE: &str = "prek-hooks.toml";
 
 /// Prepend paths to the current $PATH, returning the joined result.
 ///
@@ -38,6 +38,11 @@ fn main() {
         .to_path_buf();
 
     commit_info(&workspace_root);
+
+    // `prek self update` downloads the release archive of the target prek was built for.
+    #[allow(clippy::disallowed_methods)]
+    let target = std::env::var("TARGET").unwrap();
+    println!("cargo:rustc-env=PREK_TARGET_TRIPLE={target}");
 }
 
 fn commit_info(workspace_root: &Path) {
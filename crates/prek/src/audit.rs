@@ -0,0 +1,325 @@
+//! Audit of commits that skipped the `pre-commit` hook, e.g. with `git commit --no-verify`.
+//!
+//! Every time the `pre-commit` hook passes, the tree of the index is recorded in
+//! `.git/prek/verified-trees`. Commits are matched to their verification by tree rather than by
+//! commit id, so signing a commit with GPG or SSH, which only happens after the hook ran, doesn't
+//! count as skipping the hook. Each hook run, and `prek audit`, then checks the commits added to
+//! `HEAD` since the last check: a commit made locally whose tree was never verified is appended to
+//! `.git/prek/audit.log`.
+//!
+//! Only commits committed by the configured `user.email` whose author and commit dates are equal
+//! are checked, so pulled, rebased and cherry-picked commits, which never run `pre-commit`, are not
+//! reported. Merge commits are not checked either. Nothing is reported until the `pre-commit` hook
+//! passed once.
+
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use rustc_hash::FxHashSet;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::config::HookType;
+use crate::git::{self, GIT_COMMON_DIR};
+
+/// The number of verified trees kept, older ones are dropped.
+const MAX_VERIFIED_TREES: usize = 1000;
+
+/// The maximum number of new commits checked at once, e.g. after a long-running branch landed.
+const MAX_CHECKED_COMMITS: usize = 100;
+
+/// A commit that landed without the `pre-commit` hook passing for its tree.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct AuditEntry {
+    pub(crate) commit: String,
+    pub(crate) author: String,
+    /// The commit date, in ISO 8601 format.
+    pub(crate) date: String,
+    pub(crate) subject: String,
+    /// Unix timestamp of when the commit was found, in seconds.
+    pub(crate) detected_at: u64,
+    /// The hook, or `audit` for `prek audit`, that found the commit.
+    pub(crate) detected_by: String,
+}
+
+enum CheckedHead {
+    Commit(String),
+    /// The branch had no commit yet.
+    Unborn,
+}
+
+pub(crate) struct Audit {
+    dir: PathBuf,
+}
+
+impl Audit {
+    /// The audit files of the current repository.
+    pub(crate) fn new() -> Result<Self> {
+        Ok(Self {
+            dir: GIT_COMMON_DIR.as_ref()?.join("prek"),
+        })
+    }
+
+    pub(crate) fn log_file(&self) -> PathBuf {
+        self.dir.join("audit.log")
+    }
+
+    fn verified_trees_file(&self) -> PathBuf {
+        self.dir.join("verified-trees")
+    }
+
+    fn checked_head_file(&self) -> PathBuf {
+        self.dir.join("audit-head")
+    }
+
+    /// Where `HEAD` was on the last check, or `None` if nothing was checked yet.
+    fn checked_head(&self) -> Result<Option<CheckedHead>> {
+        match fs_err::read_to_string(self.checked_head_file()) {
+            Ok(content) => Ok(Some(match content.trim() {
+                "" => CheckedHead::Unborn,
+                head => CheckedHead::Commit(head.to_string()),
+            })),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn write_checked_head(&self, head: Option<&str>) -> Result<()> {
+        fs_err::create_dir_all(&self.dir)?;
+        fs_err::write(
+            self.checked_head_file(),
+            head.map(|head| format!("{head}\n")).unwrap_or_default(),
+        )?;
+        Ok(())
+    }
+
+    /// Record that the `pre-commit` hook passed for the tree of the index.
+    pub(crate) async fn record_verified(&self) -> Result<()> {
+        let tree = git::write_tree().await?;
+
+        // Start checking from the current commit, which the upcoming commit is built on.
+        if self.checked_head()?.is_none() {
+            self.write_checked_head(head_commit().await?.as_deref())?;
+        }
+
+        let path = self.verified_trees_file();
+        let mut trees = read_lines(&path)?.into_iter().collect::<VecDeque<_>>();
+        if trees.contains(&tree) {
+            return Ok(());
+        }
+        trees.push_back(tree);
+        while trees.len() > MAX_VERIFIED_TREES {
+            trees.pop_front();
+        }
+
+        fs_err::create_dir_all(&self.dir)?;
+        let mut content = trees.into_iter().collect::<Vec<_>>().join("\n");
+        content.push('\n');
+        fs_err::write(path, content)?;
+        Ok(())
+    }
+
+    /// Check the commits added to `HEAD` since the last check, and log the ones that skipped the
+    /// `pre-commit` hook.
+    ///
+    /// Until the `pre-commit` hook passed once, checks only remember `HEAD`, so existing history
+    /// and repositories without the hook installed are never reported.
+    pub(crate) async fn check(&self, detected_by: &str) -> Result<Vec<AuditEntry>> {
+        let checked = self.checked_head()?;
+        let Some(head) = head_commit().await? else {
+            if checked.is_none() {
+                self.write_checked_head(None)?;
+            }
+            return Ok(Vec::new());
+        };
+        if matches!(&checked, Some(CheckedHead::Commit(checked)) if *checked == head) {
+            return Ok(Vec::new());
+        }
+
+        let verified = read_lines(&self.verified_trees_file())?
+            .into_iter()
+            .collect::<FxHashSet<_>>();
+        let mut entries = Vec::new();
+        if let Some(checked) = checked.filter(|_| !verified.is_empty()) {
+            let email = user_email().await?;
+            let detected_at = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default();
+
+            let checked = match &checked {
+                CheckedHead::Commit(checked) => Some(checked.as_str()),
+                CheckedHead::Unborn => None,
+            };
+            for commit in new_commits(checked, &head).await? {
+                if !commit.is_local(email.as_deref()) || verified.contains(&commit.tree) {
+                    continue;
+                }
+                debug!("Commit `{}` skipped the pre-commit hook", commit.id);
+                entries.push(AuditEntry {
+                    commit: commit.id,
+                    author: commit.author,
+                    date: commit.date,
+                    subject: commit.subject,
+                    detected_at,
+                    detected_by: detected_by.to_string(),
+                });
+            }
+        }
+
+        fs_err::create_dir_all(&self.dir)?;
+        if !entries.is_empty() {
+            let mut content = Vec::new();
+            // Oldest first, like the rest of the log.
+            for entry in entries.iter().rev() {
+                serde_json::to_writer(&mut content, entry)?;
+                content.push(b'\n');
+            }
+            let mut file = fs_err::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(self.log_file())?;
+            file.write_all(&content)?;
+        }
+        self.write_checked_head(Some(&head))?;
+
+        Ok(entries)
+    }
+
+    /// Read the log, oldest entry first, skipping entries that can't be parsed.
+    pub(crate) fn load(&self) -> Result<Vec<AuditEntry>> {
+        Ok(read_lines(&self.log_file())?
+            .iter()
+            .filter_map(|line| match serde_json::from_str(line) {
+                Ok(entry) => Some(entry),
+                Err(err) => {
+                    debug!(%err, "Skipping invalid audit entry");
+                    None
+                }
+            })
+            .collect())
+    }
+}
+
+/// Check for commits that skipped the hooks from a hook run, without ever failing the hook.
+pub(crate) async fn check_from_hook(hook_type: HookType) {
+    let result = async { Audit::new()?.check(hook_type.as_str()).await }.await;
+    if let Err(err) = result {
+        debug!("Failed to check for commits that skipped hooks: {err}");
+    }
+}
+
+/// Record that the `pre-commit` hook passed, without ever failing the hook.
+pub(crate) async fn record_verified_from_hook() {
+    let result = async { Audit::new()?.record_verified().await }.await;
+    if let Err(err) = result {
+        debug!("Failed to record the verified tree: {err}");
+    }
+}
+
+fn read_lines(path: &Path) -> Result<Vec<String>> {
+    let content = match fs_err::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(ToString::to_string)
+        .collect())
+}
+
+struct Commit {
+    id: String,
+    tree: String,
+    parents: usize,
+    author: String,
+    committer_email: String,
+    author_time: String,
+    committer_time: String,
+    date: String,
+    subject: String,
+}
+
+impl Commit {
+    /// Whether the commit was made in this repository by running `git commit`.
+    fn is_local(&self, email: Option<&str>) -> bool {
+        self.parents <= 1
+            && self.author_time == self.committer_time
+            && email.is_some_and(|email| email == self.committer_email)
+    }
+}
+
+async fn head_commit() -> Result<Option<String>> {
+    let output = git::git_cmd("get head commit")?
+        .arg("rev-parse")
+        .arg("--verify")
+        .arg("--quiet")
+        .arg("HEAD")
+        .check(false)
+        .output()
+        .await?;
+    if !output.status.success() {
+        // No commit yet.
+        return Ok(None);
+    }
+    Ok(Some(
+        String::from_utf8_lossy(&output.stdout)
+            .trim_ascii()
+            .to_string(),
+    ))
+}
+
+async fn user_email() -> Result<Option<String>> {
+    let output = git::git_cmd("get user email")?
+        .arg("config")
+        .arg("user.email")
+        .check(false)
+        .output()
+        .await?;
+    let email = String::from_utf8_lossy(&output.stdout)
+        .trim_ascii()
+        .to_string();
+    Ok((output.status.success() && !email.is_empty()).then_some(email))
+}
+
+/// The commits reachable from `head` but not from `checked`, newest first.
+async fn new_commits(checked: Option<&str>, head: &str) -> Result<Vec<Commit>> {
+    let mut cmd = git::git_cmd("list new commits")?;
+    cmd.arg("log")
+        .arg(format!("--max-count={MAX_CHECKED_COMMITS}"))
+        .arg("--format=%H%x00%T%x00%P%x00%an%x00%ce%x00%at%x00%ct%x00%cI%x00%s")
+        .arg(head);
+    if let Some(checked) = checked {
+        cmd.arg(format!("^{checked}"));
+    }
+    let output = cmd.arg("--").check(false).output().await?;
+    if !output.status.success() {
+        // The checked commit may not exist anymore, e.g. after a garbage collection.
+        debug!("Failed to list the commits since `{checked:?}`");
+        return Ok(Vec::new());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\0');
+            Some(Commit {
+                id: fields.next()?.to_string(),
+                tree: fields.next()?.to_string(),
+                parents: fields.next()?.split_whitespace().count(),
+                author: fields.next()?.to_string(),
+                committer_email: fields.next()?.to_string(),
+                author_time: fields.next()?.to_string(),
+                committer_time: fields.next()?.to_string(),
+                date: fields.next()?.to_string(),
+                subject: fields.next()?.to_string(),
+            })
+        })
+        .collect())
+}
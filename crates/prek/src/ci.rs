@@ -0,0 +1,52 @@
+use prek_consts::env_vars::EnvVars;
+
+/// The CI provider prek is running in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CiProvider {
+    GitHubActions,
+    GitLabCi,
+    BitbucketPipelines,
+    /// Any other CI, detected from the generic `CI` variable.
+    Other,
+}
+
+impl CiProvider {
+    /// Detect the CI provider from its environment variables, `None` when not running in CI.
+    pub(crate) fn detect() -> Option<Self> {
+        if EnvVars::is_set(EnvVars::GITHUB_ACTIONS) {
+            Some(Self::GitHubActions)
+        } else if EnvVars::is_set(EnvVars::GITLAB_CI) {
+            Some(Self::GitLabCi)
+        } else if EnvVars::is_set(EnvVars::BITBUCKET_BUILD_NUMBER) {
+            Some(Self::BitbucketPipelines)
+        } else if EnvVars::is_set(EnvVars::CI) {
+            Some(Self::Other)
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::GitHubActions => "GitHub Actions",
+            Self::GitLabCi => "GitLab CI",
+            Self::BitbucketPipelines => "Bitbucket Pipelines",
+            Self::Other => "CI",
+        }
+    }
+}
+
+/// Variables holding the branch a pull request is going to be merged into, by CI provider.
+pub(crate) const BASE_BRANCH_VARS: [&str; 3] = [
+    EnvVars::GITHUB_BASE_REF,
+    EnvVars::CI_MERGE_REQUEST_TARGET_BRANCH_NAME,
+    EnvVars::BITBUCKET_PR_DESTINATION_BRANCH,
+];
+
+/// The branch the changes are going to be merged into, as set by the CI provider for pull
+/// requests (merge requests in GitLab).
+pub(crate) fn base_branch() -> Option<String> {
+    BASE_BRANCH_VARS
+        .into_iter()
+        .find_map(|name| EnvVars::var(name).ok().filter(|branch| !branch.is_empty()))
+}
@@ -1,7 +1,22 @@
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+
+use tokio_util::sync::CancellationToken;
+
+use crate::process::kill_process_trees;
 
 static CLEANUP_HOOKS: Mutex<Vec<Box<dyn Fn() + Send>>> = Mutex::new(Vec::new());
 
+/// Cancelled when prek is interrupted while a [`GracefulShutdown`] guard is alive.
+static CANCELLATION: LazyLock<CancellationToken> = LazyLock::new(CancellationToken::new);
+
+static GRACEFUL: AtomicBool = AtomicBool::new(false);
+
+tokio::task_local! {
+    /// Cancelled, along with a graceful shutdown, to stop the tasks run with [`cancellable`].
+    static SCOPE: CancellationToken;
+}
+
 /// Run all cleanup functions.
 pub fn cleanup() {
     let mut cleanup = CLEANUP_HOOKS.lock().unwrap();
@@ -15,3 +30,70 @@ pub fn add_cleanup<F: Fn() + Send + 'static>(f: F) {
     let mut cleanup = CLEANUP_HOOKS.lock().unwrap();
     cleanup.push(Box::new(f));
 }
+
+/// The token that is cancelled when the current task should stop: when a graceful shutdown is
+/// requested, or when the scope it runs in with [`cancellable`] is cancelled.
+pub fn cancellation_token() -> CancellationToken {
+    SCOPE
+        .try_with(CancellationToken::clone)
+        .unwrap_or_else(|_| CANCELLATION.clone())
+}
+
+/// Whether the current task should stop, see [`cancellation_token`].
+pub fn is_cancelled() -> bool {
+    SCOPE
+        .try_with(CancellationToken::is_cancelled)
+        .unwrap_or_else(|_| CANCELLATION.is_cancelled())
+}
+
+/// A token cancelled along with a graceful shutdown, that can also be cancelled on its own to
+/// stop the tasks run with it in [`cancellable`].
+pub fn scope_token() -> CancellationToken {
+    CANCELLATION.child_token()
+}
+
+/// Run `future` with `token` as its [`cancellation_token`], so cancelling it terminates the
+/// commands of `future` the same way a graceful shutdown does.
+pub async fn cancellable<F: Future>(token: CancellationToken, future: F) -> F::Output {
+    SCOPE.scope(token, future).await
+}
+
+/// Handle SIGINT/SIGTERM (or Ctrl-C on Windows).
+///
+/// If a [`GracefulShutdown`] guard is alive, the first signal cancels the token so that the
+/// running pipeline can stop spawning new work, terminate its children, and unwind normally.
+/// Otherwise, or on a repeated signal, run the cleanup functions and exit immediately.
+pub fn handle_interrupt() {
+    if GRACEFUL.load(Ordering::SeqCst) && !CANCELLATION.is_cancelled() {
+        CANCELLATION.cancel();
+        return;
+    }
+
+    // Kill the running hooks first, so they can't modify files after the stash is restored.
+    kill_process_trees();
+    cleanup();
+
+    #[allow(clippy::exit, clippy::cast_possible_wrap)]
+    std::process::exit(if cfg!(windows) {
+        0xC000_013A_u32 as i32
+    } else {
+        130
+    });
+}
+
+/// While alive, interrupts request a graceful shutdown through the cancellation token
+/// instead of exiting the process.
+pub struct GracefulShutdown(());
+
+impl GracefulShutdown {
+    pub fn enable() -> Self {
+        GRACEFUL.store(true, Ordering::SeqCst);
+        Self(())
+    }
+}
+
+impl Drop for GracefulShutdown {
+    fn drop(&mut self) {
+        GRACEFUL.store(false, Ordering::SeqCst);
+    }
+}
@@ -0,0 +1,55 @@
+use std::fmt::Write;
+
+use anyhow::Result;
+use owo_colors::OwoColorize;
+
+use crate::audit::Audit;
+use crate::cli::{ExitStatus, ListOutputFormat};
+use crate::fs::Simplified;
+use crate::printer::Printer;
+
+/// Show the commits that skipped the `pre-commit` hook, most recent first.
+pub(crate) async fn audit(
+    limit: usize,
+    output_format: ListOutputFormat,
+    printer: Printer,
+) -> Result<ExitStatus> {
+    let audit = Audit::new()?;
+    // Pick up the commits made since the last hook run.
+    audit.check("audit").await?;
+
+    let mut entries = audit.load()?;
+    entries.reverse();
+    if limit > 0 {
+        entries.truncate(limit);
+    }
+
+    if matches!(output_format, ListOutputFormat::Json) {
+        let json_output = serde_json::to_string_pretty(&entries)?;
+        writeln!(printer.stdout(), "{json_output}")?;
+        return Ok(ExitStatus::Success);
+    }
+
+    if entries.is_empty() {
+        writeln!(printer.stdout(), "No commits skipped the pre-commit hook")?;
+        return Ok(ExitStatus::Success);
+    }
+
+    for entry in &entries {
+        writeln!(
+            printer.stdout(),
+            "{}  {}  {}  {}",
+            entry.commit.get(..10).unwrap_or(&entry.commit).yellow(),
+            entry.date.get(..10).unwrap_or(&entry.date),
+            entry.author.cyan(),
+            entry.subject,
+        )?;
+    }
+    writeln!(
+        printer.stderr(),
+        "{}",
+        format!("Logged in `{}`", audit.log_file().user_display()).dimmed()
+    )?;
+
+    Ok(ExitStatus::Success)
+}
@@ -8,7 +8,7 @@ use futures::StreamExt;
 use itertools::Itertools;
 use lazy_regex::regex;
 use owo_colors::OwoColorize;
-use prek_consts::MANIFEST_FILE;
+use prek_consts::{MANIFEST_FILE, TOML_MANIFEST_FILE};
 use rustc_hash::FxHashMap;
 use rustc_hash::FxHashSet;
 use tracing::{debug, trace};
@@ -16,21 +16,37 @@ use tracing::{debug, trace};
 use crate::cli::ExitStatus;
 use crate::cli::reporter::AutoUpdateReporter;
 use crate::cli::run::Selectors;
-use crate::config::{RemoteRepo, Repo};
+use crate::config::{RemoteRepo, Repo, UpdatePolicy};
 use crate::fs::{CWD, Simplified};
+use crate::languages::REQWEST_CLIENT;
 use crate::printer::Printer;
 use crate::run::CONCURRENCY;
 use crate::store::Store;
 use crate::workspace::{Project, Workspace};
 use crate::yaml::serialize_yaml_scalar;
-use crate::{config, git};
+use crate::{config, git, offline};
 
 #[derive(Default, Clone)]
 struct Revision {
     rev: String,
     frozen: Option<String>,
+    /// Release notes of the tags between the current and the new revision, newest first.
+    release_notes: Vec<ReleaseNotes>,
 }
 
+#[derive(Clone)]
+struct ReleaseNotes {
+    tag: String,
+    notes: String,
+}
+
+/// Where to read release notes from, if at all.
+struct ReleaseNotesSource<'a> {
+    /// A GitHub token, to read the release notes of GitHub releases.
+    github_token: Option<&'a str>,
+}
+
+#[allow(clippy::fn_params_excessive_bools)]
 pub(crate) async fn auto_update(
     store: &Store,
     config: Option<PathBuf>,
@@ -40,6 +56,8 @@ pub(crate) async fn auto_update(
     jobs: usize,
     dry_run: bool,
     cooldown_days: u8,
+    release_notes: bool,
+    github_token: Option<String>,
     printer: Printer,
 ) -> Result<ExitStatus> {
     struct RepoInfo<'a> {
@@ -48,34 +66,41 @@ pub(crate) async fn auto_update(
         remote_index: usize,
     }
 
+    offline::ensure_online(|| "Updating hook repositories".to_string())?;
+
     let workspace_root = Workspace::find_root(config.as_deref(), &CWD)?;
     // TODO: support selectors?
     let selectors = Selectors::default();
-    let workspace = Workspace::discover(store, workspace_root, config, Some(&selectors), true)?;
+    let workspace =
+        Workspace::discover(store, workspace_root, config, Some(&selectors), true, false)?;
 
-    // Collect repos and deduplicate by RemoteRepo
+    // Collect repos and deduplicate by RemoteRepo and update policy
     #[allow(clippy::mutable_key_type)]
-    let mut repo_updates: FxHashMap<&RemoteRepo, Vec<RepoInfo>> = FxHashMap::default();
+    let mut repo_updates: FxHashMap<(&RemoteRepo, Option<&UpdatePolicy>), Vec<RepoInfo>> =
+        FxHashMap::default();
 
     for project in workspace.projects() {
-        let remote_size = project
-            .config()
-            .repos
-            .iter()
-            .filter(|r| matches!(r, Repo::Remote(_)))
-            .count();
-
-        let mut remote_index = 0;
-        for repo in &project.config().repos {
-            if let Repo::Remote(remote_repo) = repo {
-                let updates = repo_updates.entry(remote_repo).or_default();
-                updates.push(RepoInfo {
-                    project,
-                    remote_size,
-                    remote_index,
-                });
-                remote_index += 1;
-            }
+        // Only the repos declared in the config file itself are updated, the repos of the
+        // configs it extends are updated in their own files.
+        let declared = config::declared_remote_repos(project.config_file())?;
+        let remote_size = declared.len();
+
+        for (remote_index, (repo, rev)) in declared.iter().enumerate() {
+            // Repos of the extended configs with the same `repo` and `rev` are merged into it.
+            let Some(remote_repo) = project.config().repos.iter().find_map(|r| match r {
+                Repo::Remote(remote) if remote.repo == *repo && remote.rev == *rev => Some(remote),
+                _ => None,
+            }) else {
+                continue;
+            };
+            let updates = repo_updates
+                .entry((remote_repo, remote_repo.update_policy.as_ref()))
+                .or_default();
+            updates.push(RepoInfo {
+                project,
+                remote_size,
+                remote_index,
+            });
         }
     }
 
@@ -89,8 +114,11 @@ pub(crate) async fn auto_update(
         .max(1);
 
     let reporter = AutoUpdateReporter::new(printer);
+    let notes_source = release_notes.then_some(ReleaseNotesSource {
+        github_token: github_token.as_deref(),
+    });
 
-    let mut tasks = futures::stream::iter(repo_updates.iter().filter(|(remote_repo, _)| {
+    let mut tasks = futures::stream::iter(repo_updates.keys().filter(|(remote_repo, _)| {
         // Filter by user specified repositories
         if filter_repos.is_empty() {
             true
@@ -98,21 +126,29 @@ pub(crate) async fn auto_update(
             filter_repos.iter().any(|r| r == remote_repo.repo.as_str())
         }
     }))
-    .map(async |(remote_repo, _)| {
+    .map(async |key| {
+        let (remote_repo, _) = *key;
         let progress = reporter.on_update_start(&remote_repo.to_string());
 
-        let result = update_repo(remote_repo, bleeding_edge, freeze, cooldown_days).await;
+        let result = update_repo(
+            remote_repo,
+            bleeding_edge,
+            freeze,
+            cooldown_days,
+            notes_source.as_ref(),
+        )
+        .await;
 
         reporter.on_update_complete(progress);
 
-        (*remote_repo, result)
+        (key, result)
     })
     .buffer_unordered(jobs)
     .collect::<Vec<_>>()
     .await;
 
     // Sort tasks by repository URL for consistent output order
-    tasks.sort_by(|(a, _), (b, _)| a.repo.cmp(&b.repo));
+    tasks.sort_by(|((a, _), _), ((b, _), _)| a.repo.cmp(&b.repo));
 
     reporter.on_complete();
 
@@ -121,7 +157,8 @@ pub(crate) async fn auto_update(
     let mut project_updates: FxHashMap<&Project, Vec<Option<Revision>>> = FxHashMap::default();
     let mut failure = false;
 
-    for (remote_repo, result) in tasks {
+    for (key, result) in tasks {
+        let (remote_repo, _) = *key;
         match result {
             Ok(new_rev) => {
                 let is_changed = remote_repo.rev != new_rev.rev;
@@ -134,6 +171,7 @@ pub(crate) async fn auto_update(
                         remote_repo.rev,
                         new_rev.rev
                     )?;
+                    write_release_notes(printer, &new_rev.release_notes)?;
                 } else {
                     writeln!(
                         printer.stdout(),
@@ -143,7 +181,7 @@ pub(crate) async fn auto_update(
                 }
 
                 // Apply this update to all projects that reference this repo
-                if is_changed && let Some(projects) = repo_updates.get(&remote_repo) {
+                if is_changed && let Some(projects) = repo_updates.get(key) {
                     for RepoInfo {
                         project,
                         remote_size,
@@ -189,6 +227,7 @@ async fn update_repo(
     bleeding_edge: bool,
     freeze: bool,
     cooldown_days: u8,
+    notes_source: Option<&ReleaseNotesSource<'_>>,
 ) -> Result<Revision> {
     let tmp_dir = tempfile::tempdir()?;
     let repo_path = tmp_dir.path();
@@ -201,16 +240,30 @@ async fn update_repo(
 
     setup_and_fetch_repo(repo.repo.as_str(), repo_path).await?;
 
-    let rev = resolve_revision(repo_path, &repo.rev, bleeding_edge, cooldown_days).await?;
+    let rev = resolve_revision(
+        repo_path,
+        &repo.rev,
+        repo.update_policy.as_ref(),
+        bleeding_edge,
+        cooldown_days,
+    )
+    .await?;
 
     let Some(rev) = rev else {
         debug!("No suitable revision found for repo `{}`", repo.repo);
         return Ok(Revision {
             rev: repo.rev.clone(),
-            frozen: None,
+            ..Revision::default()
         });
     };
 
+    let release_notes = match notes_source {
+        Some(source) if rev != repo.rev => {
+            collect_release_notes(repo_path, &repo.repo, &repo.rev, &rev, source).await
+        }
+        _ => Vec::new(),
+    };
+
     let (rev, frozen) = if freeze && let Some(exact) = freeze_revision(repo_path, &rev).await? {
         debug!("Freezing revision `{rev}` to `{exact}`");
         (exact, Some(rev))
@@ -220,7 +273,11 @@ async fn update_repo(
 
     checkout_and_validate_manifest(repo_path, &rev, repo).await?;
 
-    Ok(Revision { rev, frozen })
+    Ok(Revision {
+        rev,
+        frozen,
+        release_notes,
+    })
 }
 
 async fn setup_and_fetch_repo(repo_url: &str, repo_path: &Path) -> Result<()> {
@@ -317,10 +374,12 @@ async fn get_tag_timestamps(repo: &Path) -> Result<Vec<(String, u64)>> {
 async fn resolve_revision(
     repo_path: &Path,
     current_rev: &str,
+    update_policy: Option<&UpdatePolicy>,
     bleeding_edge: bool,
     cooldown_days: u8,
 ) -> Result<Option<String>> {
-    if bleeding_edge {
+    // The update policy of the repo takes precedence over `--bleeding-edge`.
+    if bleeding_edge && update_policy.is_none() {
         return resolve_bleeding_edge(repo_path).await;
     }
 
@@ -330,6 +389,11 @@ async fn resolve_revision(
     let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
     let cutoff = now.saturating_sub(cutoff_secs);
 
+    if let Some(policy) = update_policy {
+        return resolve_constrained_revision(repo_path, current_rev, policy, &tags_with_ts, cutoff)
+            .await;
+    }
+
     // tags_with_ts is sorted newest -> oldest; find the first bucket where ts <= cutoff.
     let left = match tags_with_ts.binary_search_by(|(_, ts)| ts.cmp(&cutoff).reverse()) {
         Ok(i) | Err(i) => i,
@@ -350,6 +414,95 @@ async fn resolve_revision(
     Ok(Some(best))
 }
 
+/// Pick the highest version tag allowed by the update policy among the tags older than `cutoff`.
+///
+/// Returns `None` if there is no such tag, or if it is older than the current revision.
+async fn resolve_constrained_revision(
+    repo_path: &Path,
+    current_rev: &str,
+    policy: &UpdatePolicy,
+    tags_with_ts: &[(String, u64)],
+    cutoff: u64,
+) -> Result<Option<String>> {
+    let current = current_version(repo_path, current_rev).await?;
+    if current.is_none() && matches!(policy, UpdatePolicy::PinMajor | UpdatePolicy::PinMinor) {
+        anyhow::bail!(
+            "Cannot apply update policy `{policy}`, revision `{current_rev}` is not a version"
+        );
+    }
+
+    let allows = |version: &semver::Version| match policy {
+        UpdatePolicy::PinMajor => current
+            .as_ref()
+            .is_some_and(|current| version.major == current.major && version.pre.is_empty()),
+        UpdatePolicy::PinMinor => current.as_ref().is_some_and(|current| {
+            version.major == current.major
+                && version.minor == current.minor
+                && version.pre.is_empty()
+        }),
+        UpdatePolicy::Range(req) => req.matches(version),
+    };
+
+    let Some((tag, version)) = tags_with_ts
+        .iter()
+        .filter(|(_, ts)| *ts <= cutoff)
+        .filter_map(|(tag, _)| Some((tag, parse_tag_version(tag)?)))
+        .filter(|(_, version)| allows(version))
+        .max_by(|(_, a), (_, b)| a.cmp(b))
+    else {
+        trace!("No tags satisfy update policy `{policy}`");
+        return Ok(None);
+    };
+
+    if current.is_some_and(|current| version < current) {
+        debug!("Tag `{tag}` satisfying update policy `{policy}` is older than `{current_rev}`");
+        return Ok(None);
+    }
+
+    debug!("Using tag `{tag}` satisfying update policy `{policy}`");
+    Ok(Some(tag.clone()))
+}
+
+/// The version of the current revision, from its name or from the tags pointing at it.
+async fn current_version(repo_path: &Path, current_rev: &str) -> Result<Option<semver::Version>> {
+    if let Some(version) = parse_tag_version(current_rev) {
+        return Ok(Some(version));
+    }
+
+    let output = git::git_cmd("git tag")?
+        .arg("tag")
+        .arg("--points-at")
+        .arg(format!("{current_rev}^{{}}"))
+        .check(false)
+        .current_dir(repo_path)
+        .remove_git_envs()
+        .output()
+        .await?;
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(parse_tag_version)
+        .max())
+}
+
+/// Parse a version tag like `v1.4.2`, `1.4` or `v2.0.0-rc1`.
+fn parse_tag_version(tag: &str) -> Option<semver::Version> {
+    let version = tag.strip_prefix(['v', 'V']).unwrap_or(tag);
+    if let Ok(version) = semver::Version::parse(version) {
+        return Some(version);
+    }
+
+    // Allow versions with fewer than three components.
+    let (core, rest) = version
+        .find(['-', '+'])
+        .map_or((version, ""), |i| version.split_at(i));
+    let components = core.split('.').count();
+    if components >= 3 {
+        return None;
+    }
+    semver::Version::parse(&format!("{core}{}{rest}", ".0".repeat(3 - components))).ok()
+}
+
 async fn freeze_revision(repo_path: &Path, rev: &str) -> Result<Option<String>> {
     let exact = git::git_cmd("git rev-parse")?
         .arg("rev-parse")
@@ -372,34 +525,42 @@ async fn checkout_and_validate_manifest(
     rev: &str,
     repo: &RemoteRepo,
 ) -> Result<()> {
-    // Workaround for Windows: https://github.com/pre-commit/pre-commit/issues/2865,
-    // https://github.com/j178/prek/issues/614
-    if cfg!(windows) {
-        git::git_cmd("git show")?
-            .arg("show")
-            .arg(format!("{rev}:{MANIFEST_FILE}"))
+    // Check out `prek-hooks.toml` if the revision has one, `.pre-commit-hooks.yaml` otherwise.
+    for (manifest_file, last) in [(TOML_MANIFEST_FILE, false), (MANIFEST_FILE, true)] {
+        // Workaround for Windows: https://github.com/pre-commit/pre-commit/issues/2865,
+        // https://github.com/j178/prek/issues/614
+        if cfg!(windows) {
+            git::git_cmd("git show")?
+                .arg("show")
+                .arg(format!("{rev}:{manifest_file}"))
+                .current_dir(repo_path)
+                .remove_git_envs()
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .check(last)
+                .status()
+                .await?;
+        }
+
+        let status = git::git_cmd("git checkout")?
+            .arg("checkout")
+            .arg("--quiet")
+            .arg(rev)
+            .arg("--")
+            .arg(manifest_file)
             .current_dir(repo_path)
             .remove_git_envs()
             .stdout(Stdio::null())
             .stderr(Stdio::null())
+            .check(last)
             .status()
             .await?;
+        if status.success() {
+            break;
+        }
     }
 
-    git::git_cmd("git checkout")?
-        .arg("checkout")
-        .arg("--quiet")
-        .arg(rev)
-        .arg("--")
-        .arg(MANIFEST_FILE)
-        .current_dir(repo_path)
-        .remove_git_envs()
-        .stdout(Stdio::null())
-        .stderr(Stdio::null())
-        .status()
-        .await?;
-
-    let manifest = config::read_manifest(&repo_path.join(MANIFEST_FILE))?;
+    let manifest = config::read_manifest(&config::manifest_path(repo_path))?;
     let new_hook_ids = manifest
         .hooks
         .into_iter()
@@ -451,6 +612,186 @@ async fn get_best_candidate_tag(repo: &Path, rev: &str, current_rev: &str) -> Re
         .ok_or_else(|| anyhow::anyhow!("No tags found for revision {rev}"))
 }
 
+/// Release notes are shown for at most this many tags per repository.
+const MAX_RELEASE_NOTES_TAGS: usize = 5;
+/// Release notes are condensed to at most this many lines per tag.
+const MAX_RELEASE_NOTES_LINES: usize = 8;
+
+/// Collect the release notes of the tags between `current_rev` and `new_rev`, newest first.
+///
+/// Notes are read from the GitHub release of a tag when the repository is on GitHub and a token
+/// is available, otherwise from the message of annotated tags. Failures are not fatal.
+async fn collect_release_notes(
+    repo_path: &Path,
+    repo_url: &str,
+    current_rev: &str,
+    new_rev: &str,
+    source: &ReleaseNotesSource<'_>,
+) -> Vec<ReleaseNotes> {
+    let tags = match tags_between(repo_path, current_rev, new_rev).await {
+        Ok(tags) => tags,
+        Err(err) => {
+            debug!("Failed to list tags between `{current_rev}` and `{new_rev}`: {err}");
+            vec![new_rev.to_string()]
+        }
+    };
+
+    let github = source.github_token.zip(github_repo(repo_url));
+
+    let mut release_notes = Vec::new();
+    for tag in tags.into_iter().take(MAX_RELEASE_NOTES_TAGS) {
+        let mut notes = None;
+        if let Some((token, (owner, name))) = github {
+            match github_release_notes(owner, name, &tag, token).await {
+                Ok(body) => notes = body,
+                Err(err) => debug!("Failed to fetch GitHub release notes of `{tag}`: {err}"),
+            }
+        }
+        if notes.is_none() {
+            notes = tag_annotation(repo_path, &tag).await.unwrap_or_else(|err| {
+                debug!("Failed to read annotation of tag `{tag}`: {err}");
+                None
+            });
+        }
+        if let Some(notes) = notes.map(|notes| condense_release_notes(&notes)) {
+            if !notes.is_empty() {
+                release_notes.push(ReleaseNotes { tag, notes });
+            }
+        }
+    }
+
+    release_notes
+}
+
+/// Tags reachable from `new_rev` but not from `current_rev`, newest first.
+async fn tags_between(repo_path: &Path, current_rev: &str, new_rev: &str) -> Result<Vec<String>> {
+    let output = git::git_cmd("git tag")?
+        .arg("tag")
+        .arg("--merged")
+        .arg(new_rev)
+        .arg("--no-merged")
+        .arg(current_rev)
+        .arg("--sort=-creatordate")
+        .check(true)
+        .current_dir(repo_path)
+        .remove_git_envs()
+        .output()
+        .await?;
+
+    let mut tags = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|tag| *tag == new_rev || parse_tag_version(tag).is_some())
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    // A rev that is not a tag, like a commit SHA, has no release notes of its own.
+    if let Some(pos) = tags.iter().position(|tag| tag == new_rev) {
+        let tag = tags.remove(pos);
+        tags.insert(0, tag);
+    }
+    Ok(tags)
+}
+
+/// The message of an annotated tag, if it says more than the tag name.
+async fn tag_annotation(repo_path: &Path, tag: &str) -> Result<Option<String>> {
+    let output = git::git_cmd("git for-each-ref")?
+        .arg("for-each-ref")
+        .arg("--format=%(objecttype)%00%(contents)")
+        .arg(format!("refs/tags/{tag}"))
+        .check(true)
+        .current_dir(repo_path)
+        .remove_git_envs()
+        .output()
+        .await?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let Some(("tag", contents)) = stdout.split_once('\0') else {
+        return Ok(None);
+    };
+    // Drop the signature of signed tags.
+    let contents = contents
+        .split("-----BEGIN PGP SIGNATURE-----")
+        .next()
+        .unwrap_or_default()
+        .split("-----BEGIN SSH SIGNATURE-----")
+        .next()
+        .unwrap_or_default()
+        .trim();
+    if contents.is_empty() || contents == tag {
+        return Ok(None);
+    }
+    Ok(Some(contents.to_string()))
+}
+
+/// The owner and name of a repository hosted on GitHub.
+fn github_repo(url: &str) -> Option<(&str, &str)> {
+    let path = url
+        .strip_prefix("https://github.com/")
+        .or_else(|| url.strip_prefix("git@github.com:"))
+        .or_else(|| url.strip_prefix("ssh://git@github.com/"))?;
+    let path = path.trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    let (owner, name) = path.split_once('/')?;
+    if owner.is_empty() || name.is_empty() || name.contains('/') {
+        return None;
+    }
+    Some((owner, name))
+}
+
+async fn github_release_notes(
+    owner: &str,
+    name: &str,
+    tag: &str,
+    token: &str,
+) -> Result<Option<String>> {
+    #[derive(serde::Deserialize)]
+    struct Release {
+        body: Option<String>,
+    }
+
+    let response = REQWEST_CLIENT
+        .get(format!(
+            "https://api.github.com/repos/{owner}/{name}/releases/tags/{tag}"
+        ))
+        .bearer_auth(token)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    let release: Release = response.error_for_status()?.json().await?;
+    Ok(release.body.filter(|body| !body.trim().is_empty()))
+}
+
+/// Keep the first non-blank lines of release notes.
+fn condense_release_notes(notes: &str) -> String {
+    let lines = notes
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.trim().is_empty())
+        .collect::<Vec<_>>();
+    let mut condensed = lines.iter().take(MAX_RELEASE_NOTES_LINES).join("\n");
+    if lines.len() > MAX_RELEASE_NOTES_LINES {
+        let _ = write!(
+            condensed,
+            "\n... ({} more lines)",
+            lines.len() - MAX_RELEASE_NOTES_LINES
+        );
+    }
+    condensed
+}
+
+fn write_release_notes(printer: Printer, release_notes: &[ReleaseNotes]) -> Result<()> {
+    for ReleaseNotes { tag, notes } in release_notes {
+        writeln!(printer.stdout(), "  {}", tag.bold())?;
+        for line in notes.lines() {
+            writeln!(printer.stdout(), "    {}", line.dimmed())?;
+        }
+    }
+    Ok(())
+}
+
 async fn write_new_config(path: &Path, revisions: &[Option<Revision>]) -> Result<()> {
     let mut lines = fs_err::tokio::read_to_string(path)
         .await?
@@ -763,7 +1104,9 @@ mod tests {
         create_backdated_commit(repo, "latest", 1).await;
         create_lightweight_tag(repo, "v2.0.0").await;
 
-        let rev = resolve_revision(repo, "v2.0.0", false, 3).await.unwrap();
+        let rev = resolve_revision(repo, "v2.0.0", None, false, 3)
+            .await
+            .unwrap();
 
         assert_eq!(rev, Some("v2.0.0-rc1".to_string()));
     }
@@ -779,7 +1122,9 @@ mod tests {
         create_backdated_commit(repo, "recent-2", 1).await;
         create_lightweight_tag(repo, "v1.1.0").await;
 
-        let rev = resolve_revision(repo, "v1.1.0", false, 5).await.unwrap();
+        let rev = resolve_revision(repo, "v1.1.0", None, false, 5)
+            .await
+            .unwrap();
 
         assert_eq!(rev, None);
     }
@@ -798,7 +1143,9 @@ mod tests {
         create_backdated_commit(repo, "newest", 1).await;
         create_lightweight_tag(repo, "v1.2.0").await;
 
-        let rev = resolve_revision(repo, "v1.2.0", false, 5).await.unwrap();
+        let rev = resolve_revision(repo, "v1.2.0", None, false, 5)
+            .await
+            .unwrap();
 
         assert_eq!(rev, Some("v1.0.0".to_string()));
     }
@@ -814,7 +1161,7 @@ mod tests {
 
         // Even though the current rev matches the moving tag exactly, the dotted tag
         // should be preferred.
-        let rev = resolve_revision(repo, "moving-tag", false, 1)
+        let rev = resolve_revision(repo, "moving-tag", None, false, 1)
             .await
             .unwrap();
 
@@ -831,8 +1178,132 @@ mod tests {
         create_lightweight_tag(repo, "foo-1.2.0").await;
         create_lightweight_tag(repo, "v2.0.0").await;
 
-        let rev = resolve_revision(repo, "v1.2.3", false, 1).await.unwrap();
+        let rev = resolve_revision(repo, "v1.2.3", None, false, 1)
+            .await
+            .unwrap();
 
         assert_eq!(rev, Some("v1.2.0".to_string()));
     }
+
+    #[tokio::test]
+    async fn test_resolve_revision_respects_update_policy() {
+        let tmp = setup_test_repo().await;
+        let repo = tmp.path();
+
+        create_backdated_commit(repo, "v1.4", 10).await;
+        create_lightweight_tag(repo, "v1.4.0").await;
+        create_backdated_commit(repo, "v1.5", 9).await;
+        create_lightweight_tag(repo, "v1.5.0").await;
+        create_backdated_commit(repo, "v1.9", 8).await;
+        create_lightweight_tag(repo, "v1.9.1").await;
+        create_backdated_commit(repo, "v2.0", 7).await;
+        create_lightweight_tag(repo, "v2.0.0").await;
+        create_backdated_commit(repo, "v2.1-rc", 6).await;
+        create_lightweight_tag(repo, "v2.1.0-rc1").await;
+
+        let resolve = async |current: &str, policy: &str| {
+            let policy = policy.parse::<UpdatePolicy>().unwrap();
+            resolve_revision(repo, current, Some(&policy), false, 0)
+                .await
+                .unwrap()
+        };
+
+        assert_eq!(resolve("v1.4.0", "pin-major").await, Some("v1.9.1".into()));
+        assert_eq!(resolve("v1.4.0", "pin-minor").await, Some("v1.4.0".into()));
+        assert_eq!(resolve("v1.4.0", "<2.0").await, Some("v1.9.1".into()));
+        assert_eq!(resolve("v1.4.0", "~=1.4").await, Some("v1.9.1".into()));
+        assert_eq!(resolve("v1.4.0", "~=1.4.0").await, Some("v1.4.0".into()));
+        assert_eq!(resolve("v1.4.0", ">=2").await, Some("v2.0.0".into()));
+        // Never moves back to an older version.
+        assert_eq!(resolve("v2.0.0", "<2.0").await, None);
+    }
+
+    #[test]
+    fn test_parse_tag_version() {
+        assert_eq!(
+            parse_tag_version("v1.4.2"),
+            Some(semver::Version::new(1, 4, 2))
+        );
+        assert_eq!(
+            parse_tag_version("1.4"),
+            Some(semver::Version::new(1, 4, 0))
+        );
+        assert_eq!(
+            parse_tag_version("v2.0-rc1"),
+            semver::Version::parse("2.0.0-rc1").ok()
+        );
+        assert_eq!(parse_tag_version("moving-tag"), None);
+        assert_eq!(parse_tag_version("v1.2.3.4"), None);
+    }
+
+    #[tokio::test]
+    async fn test_collect_release_notes_from_tag_annotations() {
+        let tmp = setup_test_repo().await;
+        let repo = tmp.path();
+
+        create_commit(repo, "v1").await;
+        create_annotated_tag(repo, "v1.0.0", 0).await;
+        create_commit(repo, "v1.1").await;
+        git::git_cmd("git tag")
+            .unwrap()
+            .args(["tag", "--no-sign", "-m", "Add foo\n\nFix bar", "v1.1.0"])
+            .current_dir(repo)
+            .remove_git_envs()
+            .output()
+            .await
+            .unwrap();
+        create_commit(repo, "v1.2").await;
+        git::git_cmd("git tag")
+            .unwrap()
+            .args(["tag", "--no-sign", "-m", "Add baz", "v1.2.0"])
+            .current_dir(repo)
+            .remove_git_envs()
+            .output()
+            .await
+            .unwrap();
+
+        let source = ReleaseNotesSource { github_token: None };
+        let notes = collect_release_notes(repo, "", "v1.0.0", "v1.2.0", &source).await;
+        let notes = notes
+            .iter()
+            .map(|notes| (notes.tag.as_str(), notes.notes.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            notes,
+            vec![("v1.2.0", "Add baz"), ("v1.1.0", "Add foo\nFix bar")]
+        );
+    }
+
+    #[test]
+    fn test_condense_release_notes() {
+        let notes = (1..=10).map(|i| format!("- change {i}\n")).join("\n");
+        assert_eq!(
+            condense_release_notes(&notes),
+            indoc::indoc! {"
+                - change 1
+                - change 2
+                - change 3
+                - change 4
+                - change 5
+                - change 6
+                - change 7
+                - change 8
+                ... (2 more lines)"
+            }
+        );
+    }
+
+    #[test]
+    fn test_github_repo() {
+        assert_eq!(
+            github_repo("https://github.com/astral-sh/ruff-pre-commit"),
+            Some(("astral-sh", "ruff-pre-commit"))
+        );
+        assert_eq!(
+            github_repo("git@github.com:j178/prek.git"),
+            Some(("j178", "prek"))
+        );
+        assert_eq!(github_repo("https://gitlab.com/foo/bar"), None);
+        assert_eq!(github_repo("https://github.com/foo"), None);
+    }
 }
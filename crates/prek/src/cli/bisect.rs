@@ -0,0 +1,271 @@
+use std::fmt::Write as _;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use itertools::Itertools;
+use owo_colors::OwoColorize;
+use rustc_hash::FxHashSet;
+
+use crate::cli::ExitStatus;
+use crate::cli::reporter::{HookInitReporter, HookInstallReporter, HookRunReporter};
+use crate::cli::run::{CollectOptions, FileFilter, Selectors, collect_files, install_hooks};
+use crate::config::Language;
+use crate::fs::CWD;
+use crate::git;
+use crate::hook::{Hook, InstalledHook};
+use crate::printer::Printer;
+use crate::store::Store;
+use crate::workspace::Workspace;
+
+/// Find the smallest set of files a hook fails on, by running it on halves of its files.
+pub(crate) async fn bisect(
+    store: &Store,
+    config: Option<PathBuf>,
+    selector: String,
+    opts: CollectOptions,
+    refresh: bool,
+    printer: Printer,
+) -> Result<ExitStatus> {
+    let workspace_root = Workspace::find_root(config.as_deref(), &CWD)?;
+    let selectors = Selectors::load(std::slice::from_ref(&selector), &[], &workspace_root)?;
+    let mut workspace = Workspace::discover(
+        store,
+        workspace_root,
+        config,
+        Some(&selectors),
+        refresh,
+        false,
+    )?;
+
+    let reporter = HookInitReporter::new(printer);
+    let lock = store.lock_shared_async().await?;
+    let hooks = workspace
+        .init_hooks(store, Some(&reporter))
+        .await
+        .context("Failed to init hooks")?;
+
+    let mut hooks = hooks
+        .into_iter()
+        .filter(|hook| selectors.matches_hook(hook) && hook.stages.contains(opts.hook_stage))
+        .collect::<Vec<_>>();
+    selectors.report_unused();
+    let hook = match hooks.len() {
+        0 => anyhow::bail!("No hook found for `{selector}`"),
+        1 => hooks.pop().expect("one hook"),
+        _ => anyhow::bail!(
+            "`{selector}` matches {} hooks, select one of them with `project-path:hook-id`: `{}`",
+            hooks.len(),
+            hooks.iter().map(Hook::full_id).join("`, `")
+        ),
+    };
+    if !hook.pass_filenames {
+        anyhow::bail!("Hook `{hook}` is not passed filenames, so its files can't be bisected");
+    }
+    if !Language::supported(hook.language) {
+        anyhow::bail!(
+            "Hook `{hook}` uses the unsupported language `{}`",
+            hook.language
+        );
+    }
+
+    let files = files_for_hook(&workspace, &hook, opts).await?;
+    if files.is_empty() {
+        anyhow::bail!("Hook `{hook}` has no files to run on");
+    }
+
+    let reporter = HookInstallReporter::new(printer);
+    let mut hooks = install_hooks(vec![Arc::new(hook)], store, &reporter, false).await?;
+    drop(lock);
+    let hook = hooks.pop().expect("one installed hook");
+
+    let reporter = HookRunReporter::new(printer, 0);
+    let mut bisector = Bisector {
+        hook: &hook,
+        store,
+        reporter: &reporter,
+        printer,
+        runs: 0,
+        failed: Vec::new(),
+        output: Vec::new(),
+    };
+    let files = files.iter().map(PathBuf::as_path).collect::<Vec<_>>();
+    if !bisector.fails(&files).await? {
+        writeln!(
+            printer.stderr(),
+            "Hook `{}` passes on all {} files",
+            hook.id.cyan(),
+            files.len()
+        )?;
+        return Ok(ExitStatus::Failure);
+    }
+    let culprits = bisector.minimize(&[], &files).await?;
+    // Show what the hook reports on the culprits alone.
+    if bisector.failed != culprits {
+        bisector.fails(&culprits).await?;
+    }
+
+    writeln!(
+        printer.stderr(),
+        "Hook `{}` fails on {} of {} files, found in {} runs:",
+        hook.id.cyan(),
+        culprits.len().bold(),
+        files.len(),
+        bisector.runs
+    )?;
+    for file in &culprits {
+        writeln!(
+            printer.stdout(),
+            "{}",
+            hook.project().relative_path().join(file).display()
+        )?;
+    }
+    if !bisector.output.is_empty() {
+        writeln!(printer.stderr(), "\n{}", "Hook output:".bold())?;
+        std::io::stderr().write_all(&bisector.output)?;
+    }
+
+    Ok(ExitStatus::Success)
+}
+
+struct Bisector<'a> {
+    hook: &'a InstalledHook,
+    store: &'a Store,
+    reporter: &'a HookRunReporter,
+    printer: Printer,
+    /// The number of times the hook was run.
+    runs: usize,
+    /// The files the hook was last run on when it failed, and its output.
+    failed: Vec<PathBuf>,
+    output: Vec<u8>,
+}
+
+impl Bisector<'_> {
+    /// Find a minimal subset of `files` the hook still fails on when it's run on it together with
+    /// `context`, assuming it fails on `context` and `files` together.
+    ///
+    /// If neither half of `files` is enough, each half is minimized while keeping the other.
+    async fn minimize<'f>(
+        &mut self,
+        context: &[&'f Path],
+        files: &[&'f Path],
+    ) -> Result<Vec<&'f Path>> {
+        if files.len() <= 1 {
+            return Ok(files.to_vec());
+        }
+
+        let (left, right) = files.split_at(files.len() / 2);
+        if self.fails(&[context, left].concat()).await? {
+            return Box::pin(self.minimize(context, left)).await;
+        }
+        if self.fails(&[context, right].concat()).await? {
+            return Box::pin(self.minimize(context, right)).await;
+        }
+
+        let left = Box::pin(self.minimize(&[context, right].concat(), left)).await?;
+        let right = Box::pin(self.minimize(&[context, &left].concat(), right)).await?;
+        Ok([left, right].concat())
+    }
+
+    /// Run the hook on `files` and restore the files it modified.
+    async fn fails(&mut self, files: &[&Path]) -> Result<bool> {
+        let hook = self.hook;
+        let paths = files
+            .iter()
+            .map(|file| hook.work_dir().join(file))
+            .collect::<Vec<_>>();
+        let contents = paths
+            .iter()
+            .map(|path| fs_err::read(path).ok())
+            .collect::<Vec<_>>();
+
+        self.runs += 1;
+        let (exit_code, output) = hook
+            .language
+            .run(hook, files, self.store, self.reporter)
+            .await
+            .with_context(|| format!("Failed to run hook `{hook}`"))?;
+        let failed = !hook.success_exit_codes.contains(&exit_code)
+            || (hook.fail_on_output && !output.trim_ascii().is_empty());
+
+        for (path, content) in paths.iter().zip(contents) {
+            let Some(content) = content else {
+                continue;
+            };
+            if fs_err::read(path).ok().as_ref() != Some(&content) {
+                fs_err::write(path, content)?;
+            }
+        }
+
+        self.reporter.suspend(|| {
+            writeln!(
+                self.printer.stderr(),
+                "Run {}: {} files, {}",
+                self.runs,
+                files.len(),
+                if failed {
+                    "failed".red().to_string()
+                } else {
+                    "passed".green().to_string()
+                }
+            )
+        })?;
+        if failed {
+            self.failed = files.iter().map(|file| file.to_path_buf()).collect();
+            self.output = output;
+        }
+
+        Ok(failed)
+    }
+}
+
+/// The files the hook would run on, relative to its project.
+async fn files_for_hook(
+    workspace: &Workspace,
+    hook: &Hook,
+    opts: CollectOptions,
+) -> Result<Vec<PathBuf>> {
+    let opts = CollectOptions {
+        recurse_submodules: workspace.recurse_submodules(),
+        ..opts
+    };
+    let filenames = collect_files(workspace.root(), opts)
+        .await
+        .context("Failed to collect files")?;
+    // Files are identified relative to the workspace root.
+    std::env::set_current_dir(workspace.root()).with_context(|| {
+        format!(
+            "Failed to change directory to `{}`",
+            workspace.root().display()
+        )
+    })?;
+    let skip_worktree = if filenames.is_empty() {
+        FxHashSet::default()
+    } else {
+        git::ls_skip_worktree_files(workspace.root()).await?
+    };
+
+    // Filters must be computed in workspace order, so that nested orphan projects claim
+    // their files before their parents.
+    let mut consumed_files = FxHashSet::default();
+    for project in workspace.all_projects() {
+        let filter = FileFilter::for_project(
+            filenames.iter(),
+            project,
+            Some(&mut consumed_files),
+            Some(&skip_worktree),
+        );
+        if hook.project() != project {
+            continue;
+        }
+        let mut files = filter.for_hook(hook);
+        if let Some(max_file_size) = filter.max_file_size(hook) {
+            filter.take_oversized(&mut files, max_file_size);
+        }
+        files.sort_unstable();
+        return Ok(files.into_iter().map(Path::to_path_buf).collect());
+    }
+
+    Ok(Vec::new())
+}
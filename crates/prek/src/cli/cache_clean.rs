@@ -7,6 +7,7 @@ use owo_colors::OwoColorize;
 use tracing::error;
 
 use crate::cli::ExitStatus;
+use crate::fs::Simplified;
 use crate::printer::Printer;
 use crate::store::{CacheBucket, Store};
 
@@ -24,9 +25,18 @@ pub(crate) fn cache_clean(store: &Store, printer: Printer) -> Result<ExitStatus>
     writeln!(
         printer.stdout(),
         "Cleaned `{}`",
-        store.path().display().cyan()
+        store.path().simplified_display().cyan()
     )?;
 
+    if store.has_separate_cache_dir() && store.cache_dir().exists() {
+        fs_err::remove_dir_all(store.cache_dir())?;
+        writeln!(
+            printer.stdout(),
+            "Cleaned `{}`",
+            store.cache_dir().simplified_display().cyan()
+        )?;
+    }
+
     Ok(ExitStatus::Success)
 }
 
@@ -0,0 +1,163 @@
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use async_compression::tokio::write::GzipEncoder;
+use owo_colors::OwoColorize;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio_tar::{Builder, Header};
+use tracing::debug;
+
+use crate::archive::ArchiveExtension;
+use crate::cli::{CacheCategory, ExitStatus};
+use crate::fs::Simplified;
+use crate::printer::Printer;
+use crate::store::Store;
+
+/// The file at the root of an exported archive describing where it was exported from.
+pub(crate) const EXPORT_MANIFEST: &str = ".prek-export.json";
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ExportManifest {
+    /// The path of the store the archive was exported from, used to relocate paths on import.
+    pub(crate) store: PathBuf,
+}
+
+impl CacheCategory {
+    pub(crate) const ALL: [Self; 3] = [Self::Repos, Self::HookEnvs, Self::Toolchains];
+
+    /// The path of the category's directory, relative to the store.
+    pub(crate) fn dir_name(self) -> &'static str {
+        match self {
+            Self::Repos => "repos",
+            Self::HookEnvs => "hooks",
+            Self::Toolchains => "tools",
+        }
+    }
+
+    /// The entries of the category that are exported and imported as a whole, relative to the store.
+    ///
+    /// Repositories and hook environments are the directories directly in their category directory,
+    /// along with the object stores shared by the revisions of repositories in `repo-objects`.
+    /// Toolchains are the entries of each tool directory, e.g. `tools/python/cpython-3.12.0-...`.
+    pub(crate) fn entries(self, root: &Path) -> Vec<PathBuf> {
+        let dir = PathBuf::from(self.dir_name());
+        let entries = visible_children(&root.join(&dir))
+            .into_iter()
+            .map(|name| dir.join(name));
+        match self {
+            Self::Repos => {
+                let objects_dir = PathBuf::from("repo-objects");
+                entries
+                    .chain(
+                        visible_children(&root.join(&objects_dir))
+                            .into_iter()
+                            .map(|name| objects_dir.join(name)),
+                    )
+                    .collect()
+            }
+            Self::HookEnvs => entries.collect(),
+            Self::Toolchains => entries
+                .flat_map(|tool| {
+                    visible_children(&root.join(&tool))
+                        .into_iter()
+                        .map(move |name| tool.join(name))
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The names of the entries in a directory, skipping hidden ones such as locks and caches.
+fn visible_children(dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs_err::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut names = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| !name.starts_with('.'))
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+    names
+}
+
+/// Package cached repositories, hook environments and toolchains into an archive.
+pub(crate) async fn cache_export(
+    store: &Store,
+    output: &Path,
+    categories: Vec<CacheCategory>,
+    printer: Printer,
+) -> Result<ExitStatus> {
+    let categories = if categories.is_empty() {
+        CacheCategory::ALL.to_vec()
+    } else {
+        categories
+    };
+
+    let writer: Box<dyn AsyncWrite + Unpin + Send> = match ArchiveExtension::from_path(output) {
+        Ok(ArchiveExtension::Tar) => Box::new(create(output).await?),
+        Ok(ArchiveExtension::TarGz) => Box::new(GzipEncoder::new(create(output).await?)),
+        _ => anyhow::bail!(
+            "Unsupported archive format `{}`, expected a `.tar` or `.tar.gz` file",
+            output.user_display()
+        ),
+    };
+
+    let _lock = store.lock_async().await?;
+
+    let mut builder = Builder::new(writer);
+    // Keep symlinks as they are, they are relocated on import.
+    builder.follow_symlinks(false);
+
+    let manifest = serde_json::to_vec_pretty(&ExportManifest {
+        store: store.path().to_path_buf(),
+    })?;
+    let mut header = Header::new_gnu();
+    header.set_size(manifest.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, EXPORT_MANIFEST, manifest.as_slice())
+        .await?;
+
+    let mut exported = 0;
+    for category in categories {
+        for entry in category.entries(store.path()) {
+            let path = store.path().join(&entry);
+            debug!("Exporting `{}`", path.display());
+            if path.is_dir() {
+                builder.append_dir_all(&entry, &path).await?;
+            } else {
+                builder.append_path_with_name(&path, &entry).await?;
+            }
+            exported += 1;
+        }
+    }
+
+    let mut writer = builder.into_inner().await?;
+    writer.shutdown().await?;
+
+    writeln!(
+        printer.stdout(),
+        "Exported {} cache entr{} to `{}`",
+        exported.cyan(),
+        if exported == 1 { "y" } else { "ies" },
+        output.user_display().cyan(),
+    )?;
+
+    Ok(ExitStatus::Success)
+}
+
+async fn create(path: &Path) -> Result<fs_err::tokio::File> {
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs_err::tokio::create_dir_all(parent).await?;
+    }
+    fs_err::tokio::File::create(path)
+        .await
+        .with_context(|| format!("Failed to create `{}`", path.user_display()))
+}
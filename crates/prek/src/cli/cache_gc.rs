@@ -0,0 +1,533 @@
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use anyhow::Result;
+use owo_colors::OwoColorize;
+use rustc_hash::FxHashSet;
+use tracing::debug;
+
+use crate::cli::ExitStatus;
+use crate::cli::cache_size::{dir_size, human_readable_bytes};
+use crate::config_refs::ConfigRefs;
+use crate::fs::Simplified;
+use crate::hook::{HOOK_MARKER, InstallInfo};
+use crate::printer::Printer;
+use crate::settings;
+use crate::store::{REPO_MARKER, Store, ToolBucket};
+
+/// Lock files not modified for this long are left over by processes that are gone.
+const STALE_LOCK_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+/// Stashed changes are kept for this long, so they can be recovered after a crashed run.
+const STALE_PATCH_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Remove cached data that is no longer used, or with `dry_run`, show what would be removed.
+pub(crate) async fn cache_gc(store: &Store, dry_run: bool, printer: Printer) -> Result<ExitStatus> {
+    // Installs and clones hold the store lock, so nothing is being written while we hold it.
+    let _lock = store.lock_async().await?;
+
+    let mut gc = Collector {
+        dry_run,
+        printer,
+        removed: FxHashSet::default(),
+        reclaimed: 0,
+        moved: 0,
+    };
+
+    let envs = incomplete_dirs(&store.hooks_dir(), HOOK_MARKER);
+    gc.remove(envs, "incomplete hook environment").await?;
+
+    let repos = incomplete_dirs(&store.repos_dir(), REPO_MARKER);
+    gc.remove(repos, "incomplete repository clone").await?;
+
+    let refs = ConfigRefs::load(&store.config_refs_file())?.map(|mut refs| {
+        let forgotten = refs.prune();
+        (refs, forgotten)
+    });
+    // Entries created before the local repositories were recorded, for example by a version
+    // that didn't record them, may still be used by repositories that haven't run since.
+    if let Some((refs, forgotten)) = &refs {
+        gc.report_forgotten(forgotten)?;
+        if let Some(since) = refs.since {
+            let referenced = refs.entries();
+            let unreferenced = |dir: &Path, marker: &str| {
+                subdirs(dir)
+                    .into_iter()
+                    .filter(|path| {
+                        created_since(path, marker, since)
+                            && store
+                                .entry_name(path)
+                                .is_some_and(|name| !referenced.contains(name.as_str()))
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let repos = unreferenced(&store.repos_dir(), REPO_MARKER);
+            gc.remove(repos, "unreferenced repository clone").await?;
+            let envs = unreferenced(&store.hooks_dir(), HOOK_MARKER);
+            gc.remove(envs, "unreferenced hook environment").await?;
+        }
+    }
+
+    gc.share_repo_objects(store).await?;
+    let repo_objects = unused_repo_objects(store, &gc.removed);
+    gc.remove(repo_objects, "unused repository object store")
+        .await?;
+
+    let locks = stale_lock_files(store);
+    gc.remove(locks, "stale lock file").await?;
+
+    let patches = stale_patches(&store.patches_dir());
+    gc.remove(patches, "orphaned patch file").await?;
+
+    if let Some(max_size) = settings::get().cache.max_size {
+        gc.shrink_hook_envs(store, max_size.0).await?;
+    }
+
+    let kept = store
+        .installed_hooks()
+        .await
+        .into_iter()
+        .filter(|info| !gc.removed.contains(&info.env_path))
+        .collect::<Vec<_>>();
+    for (bucket, what) in [
+        (ToolBucket::Python, "unused managed Python installation"),
+        (ToolBucket::Node, "unused Node.js installation"),
+        (ToolBucket::Go, "unused Go installation"),
+        (ToolBucket::Jdk, "unused JDK installation"),
+    ] {
+        let toolchains = unused_toolchains(&store.tools_path(bucket), &kept);
+        gc.remove(toolchains, what).await?;
+    }
+
+    if let Some((refs, forgotten)) = &refs {
+        if dry_run {
+            gc.report_kept(store, refs)?;
+        } else if !forgotten.is_empty() {
+            refs.save(&store.config_refs_file())?;
+        }
+    }
+
+    if gc.removed.is_empty()
+        && gc.moved == 0
+        && refs.is_none_or(|(_, forgotten)| forgotten.is_empty())
+    {
+        writeln!(printer.stdout(), "Nothing to clean")?;
+    } else if dry_run && !gc.removed.is_empty() {
+        let (bytes, unit) = human_readable_bytes(gc.reclaimed);
+        writeln!(
+            printer.stdout(),
+            "Would reclaim {}",
+            format!("{bytes:.1}{unit}").cyan().bold()
+        )?;
+    }
+
+    Ok(ExitStatus::Success)
+}
+
+/// Removes cached data, or only reports what it would remove in a dry run.
+struct Collector {
+    dry_run: bool,
+    printer: Printer,
+    /// Paths removed so far, or that would be removed in a dry run.
+    removed: FxHashSet<PathBuf>,
+    /// Bytes reclaimed so far, or that would be reclaimed in a dry run.
+    reclaimed: u64,
+    /// Repository clones whose objects were moved into shared object stores.
+    moved: usize,
+}
+
+impl Collector {
+    /// Remove files or directories, and report how many `what` were removed.
+    async fn remove(&mut self, paths: Vec<PathBuf>, what: &str) -> Result<()> {
+        let paths = paths
+            .into_iter()
+            .filter(|path| !self.removed.contains(path))
+            .collect::<Vec<_>>();
+        if paths.is_empty() {
+            return Ok(());
+        }
+
+        let mut reclaimed = 0;
+        for path in &paths {
+            reclaimed += dir_size(path);
+            if self.dry_run {
+                continue;
+            }
+            debug!("Removing {what}: `{}`", path.display());
+            if path.is_dir() {
+                fs_err::tokio::remove_dir_all(path).await?;
+            } else {
+                fs_err::tokio::remove_file(path).await?;
+            }
+        }
+        self.reclaimed += reclaimed;
+
+        let (bytes, unit) = human_readable_bytes(reclaimed);
+        writeln!(
+            self.printer.stdout(),
+            "{} {} {what}{} ({})",
+            if self.dry_run {
+                "Would remove"
+            } else {
+                "Removed"
+            },
+            paths.len().cyan(),
+            if paths.len() == 1 { "" } else { "s" },
+            format!("{bytes:.1}{unit}").cyan(),
+        )?;
+        if self.dry_run {
+            for path in &paths {
+                writeln!(
+                    self.printer.stdout(),
+                    "  {}",
+                    path.simplified_display().dimmed()
+                )?;
+            }
+        }
+
+        self.removed.extend(paths);
+        Ok(())
+    }
+
+    /// Move the objects of repositories cloned with objects of their own, such as clones made
+    /// before the revisions of a repository shared an object store, into the shared object store.
+    async fn share_repo_objects(&mut self, store: &Store) -> Result<()> {
+        let mut shared = 0;
+        for path in subdirs(&store.repos_dir()) {
+            if self.removed.contains(&path) || alternates_file(&path).exists() {
+                continue;
+            }
+            let Some(url) = fs_err::read_to_string(path.join(REPO_MARKER))
+                .ok()
+                .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+                .and_then(|marker| Some(marker.get("repo")?.as_str()?.to_string()))
+            else {
+                continue;
+            };
+
+            if self.dry_run {
+                shared += 1;
+                continue;
+            }
+            let before = dir_size(&path);
+            match store.share_repo_objects(&path, &url).await {
+                Ok(()) => {
+                    shared += 1;
+                    debug!(
+                        "Moved objects of `{}` into the shared object store, {} bytes smaller",
+                        path.display(),
+                        before.saturating_sub(dir_size(&path))
+                    );
+                }
+                Err(err) => {
+                    debug!("Failed to share the objects of `{}`: {err}", path.display());
+                }
+            }
+        }
+
+        if shared > 0 {
+            writeln!(
+                self.printer.stdout(),
+                "{} the objects of {} repository clone{} into shared object stores",
+                if self.dry_run { "Would move" } else { "Moved" },
+                shared.cyan(),
+                if shared == 1 { "" } else { "s" },
+            )?;
+            self.moved += shared;
+        }
+        Ok(())
+    }
+
+    /// Remove the oldest hook environments until the store fits in `max_size`.
+    ///
+    /// Hook environments are recreated on demand, so they are the cheapest data to drop.
+    async fn shrink_hook_envs(&mut self, store: &Store, max_size: u64) -> Result<()> {
+        let mut total =
+            dir_size(store.path()).saturating_sub(if self.dry_run { self.reclaimed } else { 0 });
+        if total <= max_size {
+            return Ok(());
+        }
+
+        let mut envs = subdirs(&store.hooks_dir())
+            .into_iter()
+            .filter(|path| !self.removed.contains(path))
+            .map(|path| {
+                let modified = path
+                    .metadata()
+                    .and_then(|metadata| metadata.modified())
+                    .unwrap_or(SystemTime::UNIX_EPOCH);
+                (modified, path)
+            })
+            .collect::<Vec<_>>();
+        envs.sort();
+
+        let mut removed = Vec::new();
+        let mut reclaimed = 0;
+        for (_, path) in envs {
+            if total <= max_size {
+                break;
+            }
+            let size = dir_size(&path);
+            if !self.dry_run {
+                debug!("Removing hook environment: `{}`", path.display());
+                fs_err::tokio::remove_dir_all(&path).await?;
+            }
+            total = total.saturating_sub(size);
+            reclaimed += size;
+            removed.push(path);
+        }
+
+        if !removed.is_empty() {
+            let (bytes, unit) = human_readable_bytes(reclaimed);
+            let (max_bytes, max_unit) = human_readable_bytes(max_size);
+            writeln!(
+                self.printer.stdout(),
+                "{} {} hook environment{} ({}) to fit in `cache.max-size` of {}",
+                if self.dry_run {
+                    "Would remove"
+                } else {
+                    "Removed"
+                },
+                removed.len().cyan(),
+                if removed.len() == 1 { "" } else { "s" },
+                format!("{bytes:.1}{unit}").cyan(),
+                format!("{max_bytes:.1}{max_unit}").cyan(),
+            )?;
+            if self.dry_run {
+                for path in &removed {
+                    writeln!(
+                        self.printer.stdout(),
+                        "  {}",
+                        path.simplified_display().dimmed()
+                    )?;
+                }
+            }
+            self.reclaimed += reclaimed;
+            self.removed.extend(removed);
+        }
+
+        Ok(())
+    }
+
+    /// Report the deleted local repositories whose references were dropped.
+    fn report_forgotten(&self, forgotten: &[PathBuf]) -> Result<()> {
+        if forgotten.is_empty() {
+            return Ok(());
+        }
+        writeln!(
+            self.printer.stdout(),
+            "{} the references of {} deleted repositor{}",
+            if self.dry_run {
+                "Would drop"
+            } else {
+                "Dropped"
+            },
+            forgotten.len().cyan(),
+            if forgotten.len() == 1 { "y" } else { "ies" },
+        )?;
+        if self.dry_run {
+            for repo in forgotten {
+                writeln!(
+                    self.printer.stdout(),
+                    "  {}",
+                    repo.simplified_display().dimmed()
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// List the local repositories that reference the repository clones and hook environments
+    /// that are kept.
+    fn report_kept(&self, store: &Store, refs: &ConfigRefs) -> Result<()> {
+        let mut repos = Vec::new();
+        for repo in refs.repos.keys() {
+            let entries = refs
+                .repo_entries(repo)
+                .map(|name| store.path().join(name))
+                .filter(|path| path.exists() && !self.removed.contains(path))
+                .collect::<FxHashSet<_>>();
+            if !entries.is_empty() {
+                repos.push((repo, entries.len()));
+            }
+        }
+        if repos.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(
+            self.printer.stdout(),
+            "Would keep entries used by {} repositor{}:",
+            repos.len().cyan(),
+            if repos.len() == 1 { "y" } else { "ies" },
+        )?;
+        for (repo, entries) in repos {
+            writeln!(
+                self.printer.stdout(),
+                "  {} ({} entr{})",
+                repo.simplified_display(),
+                entries.cyan(),
+                if entries == 1 { "y" } else { "ies" },
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Directories in `dir`.
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs_err::read_dir(dir) else {
+        return vec![];
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect()
+}
+
+/// Whether the `marker` file of a store entry, written when it is complete, is newer than `since`.
+fn created_since(entry: &Path, marker: &str, since: SystemTime) -> bool {
+    fs_err::metadata(entry.join(marker))
+        .and_then(|metadata| metadata.modified())
+        .is_ok_and(|modified| modified >= since)
+}
+
+/// Directories in `dir` without the `marker` file written when they are complete,
+/// left behind by an interrupted install or clone.
+fn incomplete_dirs(dir: &Path, marker: &str) -> Vec<PathBuf> {
+    let Ok(entries) = fs_err::read_dir(dir) else {
+        return vec![];
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir() && !path.join(marker).exists())
+        .collect()
+}
+
+/// The alternates file through which a cloned repository reads shared objects.
+fn alternates_file(repo: &Path) -> PathBuf {
+    repo.join(".git")
+        .join("objects")
+        .join("info")
+        .join("alternates")
+}
+
+/// Shared object stores of repositories no kept repository clone reads objects from.
+fn unused_repo_objects(store: &Store, removed: &FxHashSet<PathBuf>) -> Vec<PathBuf> {
+    let used = subdirs(&store.repos_dir())
+        .into_iter()
+        .filter(|path| !removed.contains(path))
+        .filter_map(|path| fs_err::read_to_string(alternates_file(&path)).ok())
+        .flat_map(|alternates| {
+            alternates
+                .lines()
+                .filter_map(|line| {
+                    let mut components = Path::new(line.trim()).components().rev();
+                    components.next();
+                    components
+                        .next()
+                        .map(|name| name.as_os_str().to_os_string())
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<FxHashSet<_>>();
+
+    let Ok(entries) = fs_err::read_dir(store.repo_objects_dir()) else {
+        return vec![];
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| !used.contains(&entry.file_name()))
+        .map(|entry| entry.path())
+        .collect()
+}
+
+/// Toolchains downloaded into `dir` that none of the `kept` hook environments uses.
+fn unused_toolchains(dir: &Path, kept: &[Arc<InstallInfo>]) -> Vec<PathBuf> {
+    let Ok(entries) = fs_err::read_dir(dir) else {
+        return vec![];
+    };
+
+    // uv may link minor versions (e.g. `cpython-3.12-...`) to the patch installation,
+    // so resolve links before matching toolchains to installation directories.
+    let canonical_dir = std::fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+    let mut in_use = FxHashSet::default();
+    for info in kept {
+        let canonical = std::fs::canonicalize(&info.toolchain).ok();
+        let candidates = [
+            info.toolchain.strip_prefix(dir).ok(),
+            canonical
+                .as_deref()
+                .and_then(|path| path.strip_prefix(&canonical_dir).ok()),
+        ];
+        for path in candidates.into_iter().flatten() {
+            if let Some(name) = path.components().next() {
+                in_use.insert(name.as_os_str().to_os_string());
+            }
+        }
+    }
+
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+        // Skip bookkeeping directories, such as uv's `.lock`, `.cache` and `.temp`.
+        .filter(|entry| {
+            let name = entry.file_name();
+            !name.to_string_lossy().starts_with('.') && !in_use.contains(&name)
+        })
+        .map(|entry| entry.path())
+        .collect()
+}
+
+/// Whether `path` was last modified longer than `age` ago.
+fn is_older_than(path: &Path, age: Duration) -> bool {
+    path.metadata()
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|elapsed| elapsed > age)
+}
+
+/// Lock files of toolchains, repos and hook environments that are old and not held by any process.
+fn stale_lock_files(store: &Store) -> Vec<PathBuf> {
+    let tool_locks = walkdir::WalkDir::new(store.tools_dir())
+        .min_depth(1)
+        .max_depth(2)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file() && entry.file_name() == ".lock")
+        .map(walkdir::DirEntry::into_path);
+    let entry_locks = fs_err::read_dir(store.locks_dir())
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "lock"));
+
+    tool_locks
+        .chain(entry_locks)
+        .filter(|path| is_older_than(path, STALE_LOCK_AGE))
+        .filter(|path| fs_err::File::open(path).is_ok_and(|file| file.file().try_lock().is_ok()))
+        .collect()
+}
+
+/// Patches of unstaged changes stashed by runs long ago.
+///
+/// The working tree is restored from the patch when a run finishes, the patch is only kept
+/// to recover the changes if that failed.
+fn stale_patches(patches_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs_err::read_dir(patches_dir) else {
+        return vec![];
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension().is_some_and(|ext| ext == "patch")
+                && is_older_than(path, STALE_PATCH_AGE)
+        })
+        .collect()
+}
@@ -0,0 +1,78 @@
+use std::fmt::Write;
+
+use anyhow::Result;
+use owo_colors::OwoColorize;
+use rustc_hash::FxHashSet;
+
+use crate::cli::ExitStatus;
+use crate::languages::{BuiltImage, Docker};
+use crate::printer::Printer;
+use crate::store::Store;
+
+/// List the images prek built for `docker` hooks, or remove them with `prune`.
+pub(crate) async fn cache_images(
+    store: &Store,
+    prune: bool,
+    all: bool,
+    printer: Printer,
+) -> Result<ExitStatus> {
+    let images = Docker::list_built_images().await?;
+
+    // Untagged images, left over when a rebuild moved their tag to a newer image, are never used.
+    let used = store
+        .installed_hooks()
+        .await
+        .iter()
+        .filter_map(|info| Docker::built_image(info).map(|tag| format!("{tag}:latest")))
+        .collect::<FxHashSet<_>>();
+    // Podman names local images `localhost/<name>`.
+    let is_used = |image: &BuiltImage| used.contains(image.name.trim_start_matches("localhost/"));
+
+    if prune {
+        let ids = images
+            .iter()
+            .filter(|image| all || !is_used(image))
+            .map(|image| image.id.as_str())
+            .collect::<Vec<_>>();
+        if ids.is_empty() {
+            writeln!(printer.stdout(), "No images to remove")?;
+            return Ok(ExitStatus::Success);
+        }
+
+        Docker::remove_images(&ids).await?;
+        writeln!(
+            printer.stdout(),
+            "Removed {} image{}",
+            ids.len().cyan(),
+            if ids.len() == 1 { "" } else { "s" },
+        )?;
+        return Ok(ExitStatus::Success);
+    }
+
+    if images.is_empty() {
+        writeln!(printer.stdout(), "No images built by prek")?;
+        return Ok(ExitStatus::Success);
+    }
+
+    let width = images
+        .iter()
+        .map(|image| image.name.len())
+        .max()
+        .unwrap_or_default();
+    for image in &images {
+        write!(
+            printer.stdout(),
+            "{:width$}  {}  {:>8}  {}",
+            image.name.bold(),
+            image.id.dimmed(),
+            image.size,
+            image.created.dimmed(),
+        )?;
+        if !is_used(image) {
+            write!(printer.stdout(), "  {}", "(unused)".yellow())?;
+        }
+        writeln!(printer.stdout())?;
+    }
+
+    Ok(ExitStatus::Success)
+}
@@ -0,0 +1,218 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use async_compression::tokio::bufread::{GzipDecoder, XzDecoder};
+use bstr::ByteSlice;
+use owo_colors::OwoColorize;
+use tokio::io::{AsyncRead, BufReader};
+use tokio_tar::ArchiveBuilder;
+use tracing::debug;
+
+use crate::archive::ArchiveExtension;
+use crate::cli::CacheCategory;
+use crate::cli::ExitStatus;
+use crate::cli::cache_export::{EXPORT_MANIFEST, ExportManifest};
+use crate::fs::Simplified;
+use crate::printer::Printer;
+use crate::store::Store;
+
+/// Unpack an archive created by `prek cache export` into the store.
+///
+/// Entries that already exist in the store are kept as they are. Paths pointing into the store
+/// the archive was exported from are rewritten to point into this store.
+pub(crate) async fn cache_import(
+    store: &Store,
+    archive: &Path,
+    printer: Printer,
+) -> Result<ExitStatus> {
+    let file = fs_err::tokio::File::open(archive)
+        .await
+        .with_context(|| format!("Failed to open `{}`", archive.user_display()))?;
+    let reader = BufReader::new(file);
+    let reader: Box<dyn AsyncRead + Unpin> = match ArchiveExtension::from_path(archive) {
+        Ok(ArchiveExtension::Tar) => Box::new(reader),
+        Ok(ArchiveExtension::TarGz) => Box::new(GzipDecoder::new(reader)),
+        Ok(ArchiveExtension::TarXz) => Box::new(XzDecoder::new(reader)),
+        _ => anyhow::bail!(
+            "Unsupported archive format `{}`, expected a `.tar` or `.tar.gz` file",
+            archive.user_display()
+        ),
+    };
+
+    let _lock = store.lock_async().await?;
+
+    let scratch = store.scratch_path();
+    fs_err::tokio::create_dir_all(&scratch).await?;
+    let staging = tempfile::tempdir_in(&scratch)?;
+
+    // Hook environments link to the interpreters of their toolchains by absolute paths,
+    // these links are relocated below.
+    ArchiveBuilder::new(reader)
+        .set_preserve_mtime(true)
+        .set_preserve_permissions(true)
+        .set_allow_external_symlinks(true)
+        .build()
+        .unpack(staging.path())
+        .await
+        .with_context(|| format!("Failed to unpack `{}`", archive.user_display()))?;
+
+    let manifest = fs_err::tokio::read(staging.path().join(EXPORT_MANIFEST))
+        .await
+        .context("Not an archive created by `prek cache export`")?;
+    let manifest: ExportManifest = serde_json::from_slice(&manifest)?;
+
+    let mut imported = 0;
+    let mut skipped = 0;
+    for category in CacheCategory::ALL {
+        for entry in category.entries(staging.path()) {
+            let target = store.path().join(&entry);
+            let source = staging.path().join(&entry);
+            if target.exists() {
+                // The imported clones need the objects of their revisions in the store.
+                if entry.starts_with("repo-objects") {
+                    debug!("Merging objects into `{}`", target.display());
+                    merge_repo_objects(&source, &target)?;
+                    imported += 1;
+                } else {
+                    debug!("`{}` already exists, skipping", target.display());
+                    skipped += 1;
+                }
+                continue;
+            }
+
+            relocate(&source, &manifest.store, store.path())?;
+            if let Some(parent) = target.parent() {
+                fs_err::tokio::create_dir_all(parent).await?;
+            }
+            fs_err::tokio::rename(&source, &target).await?;
+            imported += 1;
+        }
+    }
+
+    write!(
+        printer.stdout(),
+        "Imported {} cache entr{}",
+        imported.cyan(),
+        if imported == 1 { "y" } else { "ies" },
+    )?;
+    if skipped > 0 {
+        write!(printer.stdout(), ", skipped {} existing", skipped.cyan())?;
+    }
+    writeln!(printer.stdout())?;
+
+    Ok(ExitStatus::Success)
+}
+
+/// Add the objects, refs and shallow commits of the `source` repository object store that
+/// `target` doesn't have. Pack indexes are copied after their packs, so git never sees a pack
+/// that isn't there yet.
+fn merge_repo_objects(source: &Path, target: &Path) -> Result<()> {
+    let mut files = ["objects", "refs"]
+        .iter()
+        .flat_map(|dir| walkdir::WalkDir::new(source.join(dir)))
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .map(walkdir::DirEntry::into_path)
+        .collect::<Vec<_>>();
+    files.sort_by_key(|path| path.extension().is_some_and(|ext| ext == "idx"));
+
+    for file in files {
+        let relative = file.strip_prefix(source)?;
+        let dest = target.join(relative);
+        if dest.exists() {
+            continue;
+        }
+        if let Some(parent) = dest.parent() {
+            fs_err::create_dir_all(parent)?;
+        }
+        fs_err::copy(&file, &dest)?;
+    }
+
+    if let Ok(source_shallow) = fs_err::read_to_string(source.join("shallow")) {
+        let target_shallow = fs_err::read_to_string(target.join("shallow")).unwrap_or_default();
+        let mut commits = target_shallow.lines().collect::<Vec<_>>();
+        for commit in source_shallow.lines() {
+            if !commits.contains(&commit) {
+                commits.push(commit);
+            }
+        }
+        fs_err::write(target.join("shallow"), format!("{}\n", commits.join("\n")))?;
+    }
+
+    Ok(())
+}
+
+/// Rewrite the paths pointing into the `from` store to point into the `to` store.
+///
+/// This covers absolute symlinks, and text files such as shebangs, `pyvenv.cfg` and the
+/// `.prek-hook.json` markers. Binary files are left untouched.
+fn relocate(path: &Path, from: &Path, to: &Path) -> Result<()> {
+    if from == to {
+        return Ok(());
+    }
+    // Paths appear as is, and escaped in JSON files (backslashes on Windows).
+    let mut replacements = vec![(
+        from.as_os_str().as_encoded_bytes().to_vec(),
+        to.as_os_str().as_encoded_bytes().to_vec(),
+    )];
+    if let (Ok(from_json), Ok(to_json)) = (serde_json::to_string(from), serde_json::to_string(to)) {
+        let unquote = |s: &str| s.as_bytes()[1..s.len() - 1].to_vec();
+        if unquote(&from_json) != replacements[0].0 {
+            replacements.push((unquote(&from_json), unquote(&to_json)));
+        }
+    }
+
+    for entry in walkdir::WalkDir::new(path).follow_links(false) {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = entry.file_type();
+
+        if file_type.is_symlink() {
+            let link = fs_err::read_link(path)?;
+            if let Ok(rest) = link.strip_prefix(from) {
+                let link = to.join(rest);
+                debug!(
+                    "Relocating symlink `{}` to `{}`",
+                    path.display(),
+                    link.display()
+                );
+                fs_err::remove_file(path)?;
+                symlink(&link, path)?;
+            }
+        } else if file_type.is_file() {
+            let mut content = fs_err::read(path)?;
+            // Treat files with a NUL byte near the start as binary, as git does.
+            if content[..content.len().min(8000)].contains(&0) {
+                continue;
+            }
+            let mut changed = false;
+            for (from, to) in &replacements {
+                if content.find(from).is_some() {
+                    content = content.replace(from, to);
+                    changed = true;
+                }
+            }
+            if changed {
+                debug!("Relocating paths in `{}`", path.display());
+                fs_err::write(path, content)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn symlink(original: &Path, link: &Path) -> std::io::Result<()> {
+    fs_err::os::unix::fs::symlink(original, link)
+}
+
+#[cfg(windows)]
+fn symlink(original: &Path, link: &Path) -> std::io::Result<()> {
+    if original.is_dir() {
+        fs_err::os::windows::fs::symlink_dir(original, link)
+    } else {
+        fs_err::os::windows::fs::symlink_file(original, link)
+    }
+}
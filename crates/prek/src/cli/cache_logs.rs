@@ -0,0 +1,76 @@
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use itertools::Itertools;
+use owo_colors::OwoColorize;
+
+use crate::cli::ExitStatus;
+use crate::cli::reporter::HookInitReporter;
+use crate::cli::run::Selectors;
+use crate::fs::{CWD, Simplified};
+use crate::hook::Repo;
+use crate::printer::Printer;
+use crate::store::Store;
+use crate::workspace::Workspace;
+
+pub(crate) async fn cache_logs(
+    store: &Store,
+    config: Option<PathBuf>,
+    hook: String,
+    refresh: bool,
+    printer: Printer,
+) -> Result<ExitStatus> {
+    let workspace_root = Workspace::find_root(config.as_deref(), &CWD)?;
+    let selectors = Selectors::load(std::slice::from_ref(&hook), &[], &workspace_root)?;
+    let mut workspace = Workspace::discover(
+        store,
+        workspace_root,
+        config,
+        Some(&selectors),
+        refresh,
+        false,
+    )?;
+
+    let reporter = HookInitReporter::new(printer);
+    let lock = store.lock_shared_async().await?;
+    let hooks = workspace
+        .init_hooks(store, Some(&reporter))
+        .await
+        .context("Failed to init hooks")?;
+    drop(lock);
+
+    // Hooks sharing an environment share its build log.
+    let logs = hooks
+        .iter()
+        .filter(|h| selectors.matches_hook(h))
+        .filter(|h| !matches!(h.repo(), Repo::Meta { .. } | Repo::Builtin { .. }))
+        .map(|h| (h, store.env_log_file(&h.env_key())))
+        .filter(|(_, path)| path.is_file())
+        .unique_by(|(_, path)| path.clone())
+        .collect::<Vec<_>>();
+
+    selectors.report_unused();
+
+    if logs.is_empty() {
+        anyhow::bail!("No build log found for `{hook}`");
+    }
+
+    for (i, (hook, path)) in logs.iter().enumerate() {
+        if logs.len() > 1 {
+            if i > 0 {
+                writeln!(printer.stdout())?;
+            }
+            writeln!(
+                printer.stdout(),
+                "{} {}",
+                hook.full_id().bold(),
+                format!("({})", path.simplified_display()).dimmed()
+            )?;
+        }
+        let log = fs_err::read(path)?;
+        write!(printer.stdout(), "{}", String::from_utf8_lossy(&log))?;
+    }
+
+    Ok(ExitStatus::Success)
+}
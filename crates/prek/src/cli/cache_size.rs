@@ -1,28 +1,241 @@
 use std::fmt::Write;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
+use itertools::Itertools;
+use owo_colors::OwoColorize;
+use serde::Serialize;
 
-use crate::cli::ExitStatus;
+use crate::cli::{ExitStatus, ListOutputFormat};
+use crate::config::Language;
 use crate::printer::Printer;
-use crate::store::Store;
+use crate::store::{REPO_MARKER, Store};
 
-/// Display the total size of the cache.
-pub(crate) fn cache_size(
+#[derive(Serialize)]
+struct CacheSize {
+    total: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    categories: Option<Vec<Category>>,
+}
+
+#[derive(Serialize)]
+struct Category {
+    name: &'static str,
+    path: PathBuf,
+    size: u64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    entries: Vec<Entry>,
+}
+
+#[derive(Serialize)]
+struct Entry {
+    name: String,
+    path: PathBuf,
+    size: u64,
+    #[serde(flatten)]
+    env: Option<HookEnv>,
+}
+
+#[derive(Serialize)]
+struct HookEnv {
+    language: Language,
+    language_version: String,
+    dependencies: Vec<String>,
+}
+
+/// Display the total size of the cache, optionally broken down by category and entry.
+pub(crate) async fn cache_size(
     store: &Store,
     human_readable: bool,
+    breakdown: bool,
+    output_format: ListOutputFormat,
     printer: Printer,
 ) -> Result<ExitStatus> {
-    if !store.path().exists() {
-        if human_readable {
-            writeln!(printer.stdout_important(), "0B")?;
-        } else {
-            writeln!(printer.stdout_important(), "0")?;
+    let exists = store.path().exists();
+    let mut total = if exists { dir_size(store.path()) } else { 0 };
+    if store.has_separate_cache_dir() {
+        total += dir_size(&store.cache_dir());
+    }
+    let categories = if breakdown && exists {
+        Some(categories(store, total).await)
+    } else if breakdown {
+        Some(Vec::new())
+    } else {
+        None
+    };
+    let size = CacheSize { total, categories };
+
+    match output_format {
+        ListOutputFormat::Text => {
+            let format = |bytes: u64| {
+                if human_readable {
+                    let (bytes, unit) = human_readable_bytes(bytes);
+                    format!("{bytes:.1}{unit}")
+                } else {
+                    bytes.to_string()
+                }
+            };
+
+            for category in size.categories.iter().flatten() {
+                writeln!(
+                    printer.stdout_important(),
+                    "{}: {}",
+                    category.name.bold(),
+                    format(category.size).cyan()
+                )?;
+                for entry in &category.entries {
+                    writeln!(
+                        printer.stdout_important(),
+                        "  {}: {}",
+                        entry.name,
+                        format(entry.size).cyan()
+                    )?;
+                }
+            }
+            if size.categories.is_some() {
+                writeln!(
+                    printer.stdout_important(),
+                    "{}: {}",
+                    "total".bold(),
+                    format(size.total).cyan()
+                )?;
+            } else {
+                writeln!(printer.stdout_important(), "{}", format(size.total))?;
+            }
+        }
+        ListOutputFormat::Json => {
+            let json_output = serde_json::to_string_pretty(&size)?;
+            writeln!(printer.stdout_important(), "{json_output}")?;
         }
-        return Ok(ExitStatus::Success);
     }
 
-    // Walk the entire cache root
-    let total_bytes: u64 = walkdir::WalkDir::new(store.path())
+    Ok(ExitStatus::Success)
+}
+
+/// Split the cache into its categories, each sorted from the largest entry to the smallest.
+async fn categories(store: &Store, total: u64) -> Vec<Category> {
+    let repos = subdirs(&store.repos_dir())
+        .into_iter()
+        .map(|path| {
+            let name = repo_name(&path).unwrap_or_else(|| file_name(&path));
+            Entry {
+                name,
+                size: dir_size(&path),
+                path,
+                env: None,
+            }
+        })
+        .collect();
+
+    let hook_envs = store
+        .installed_hooks()
+        .await
+        .into_iter()
+        .map(|info| {
+            let mut dependencies = info.dependencies.iter().cloned().collect::<Vec<_>>();
+            dependencies.sort_unstable();
+            let mut env = format!("{} {}", info.language, info.language_version);
+            if !dependencies.is_empty() {
+                write!(env, " with {}", dependencies.join(", ")).expect("write to string");
+            }
+            let name = format!("{} ({env})", file_name(&info.env_path));
+            Entry {
+                name,
+                size: dir_size(&info.env_path),
+                path: info.env_path.clone(),
+                env: Some(HookEnv {
+                    language: info.language,
+                    language_version: info.language_version.to_string(),
+                    dependencies,
+                }),
+            }
+        })
+        .collect();
+
+    let mut categories = vec![
+        category("repos", store.repos_dir(), repos),
+        category("repo-objects", store.repo_objects_dir(), Vec::new()),
+        category("hook-envs", store.hooks_dir(), hook_envs),
+        category(
+            "toolchains",
+            store.tools_dir(),
+            subdir_entries(&store.tools_dir()),
+        ),
+        category(
+            "caches",
+            store.cache_dir(),
+            subdir_entries(&store.cache_dir()),
+        ),
+        category("patches", store.patches_dir(), Vec::new()),
+        category("logs", store.log_file(), Vec::new()),
+        category("build-logs", store.logs_dir(), Vec::new()),
+        category("scratch", store.scratch_path(), Vec::new()),
+    ];
+
+    let accounted = categories.iter().map(|category| category.size).sum::<u64>();
+    categories.push(Category {
+        name: "other",
+        path: store.path().to_path_buf(),
+        size: total.saturating_sub(accounted),
+        entries: Vec::new(),
+    });
+
+    categories
+}
+
+fn category(name: &'static str, path: PathBuf, mut entries: Vec<Entry>) -> Category {
+    entries.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+    Category {
+        name,
+        size: dir_size(&path),
+        path,
+        entries,
+    }
+}
+
+fn subdirs(path: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs_err::read_dir(path) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+        .map(|entry| entry.path())
+        .collect_vec()
+}
+
+fn subdir_entries(path: &Path) -> Vec<Entry> {
+    subdirs(path)
+        .into_iter()
+        .map(|path| Entry {
+            name: file_name(&path),
+            size: dir_size(&path),
+            path,
+            env: None,
+        })
+        .collect()
+}
+
+/// The `repo@rev` a cloned repository was cloned from.
+fn repo_name(path: &Path) -> Option<String> {
+    let content = fs_err::read_to_string(path.join(REPO_MARKER)).ok()?;
+    let repo: serde_json::Value = serde_json::from_str(&content).ok()?;
+    Some(format!(
+        "{}@{}",
+        repo.get("repo")?.as_str()?,
+        repo.get("rev")?.as_str()?
+    ))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Returns the total size of the files under `path`, in bytes.
+pub(crate) fn dir_size(path: &Path) -> u64 {
+    walkdir::WalkDir::new(path)
         .follow_links(false)
         .into_iter()
         .filter_map(Result::ok)
@@ -30,16 +243,7 @@ pub(crate) fn cache_size(
             Ok(metadata) if metadata.is_file() => Some(metadata.len()),
             _ => None,
         })
-        .sum();
-
-    if human_readable {
-        let (bytes, unit) = human_readable_bytes(total_bytes);
-        writeln!(printer.stdout_important(), "{bytes:.1}{unit}")?;
-    } else {
-        writeln!(printer.stdout_important(), "{total_bytes}")?;
-    }
-
-    Ok(ExitStatus::Success)
+        .sum()
 }
 
 /// Formats a number of bytes into a human readable SI-prefixed size (binary units).
@@ -2,10 +2,11 @@ use std::collections::{BTreeMap, BTreeSet};
 use std::ffi::OsStr;
 use std::path::Path;
 
+use clap::ValueEnum;
 use clap::builder::StyledStr;
 use clap_complete::CompletionCandidate;
 
-use crate::config;
+use crate::config::{self, Language};
 use crate::fs::CWD;
 use crate::store::Store;
 use crate::workspace::{Project, Workspace};
@@ -20,12 +21,38 @@ pub(crate) fn selector_completer(current: &OsStr) -> Vec<CompletionCandidate> {
         return vec![];
     };
     let Ok(workspace) = Workspace::find_root(None, &CWD)
-        .and_then(|root| Workspace::discover(&store, root, None, None, false))
+        .and_then(|root| Workspace::discover(&store, root, None, None, false, false))
     else {
         return vec![];
     };
 
+    // A leading `!` negates the selector, complete the rest and put it back.
+    if let Some(negated) = current_str.strip_prefix('!') {
+        return selector_candidates(negated, &workspace)
+            .into_iter()
+            .map(|candidate| {
+                let value = format!("!{}", candidate.get_value().to_string_lossy());
+                CompletionCandidate::new(value).help(candidate.get_help().cloned())
+            })
+            .collect();
+    }
+    selector_candidates(current_str, &workspace)
+}
+
+fn selector_candidates(current_str: &str, workspace: &Workspace) -> Vec<CompletionCandidate> {
+    // `language:<language>` selects the hooks of a language.
+    if let Some(language_prefix) = current_str.strip_prefix("language:") {
+        return Language::value_variants()
+            .iter()
+            .filter(|language| language.as_str().starts_with(language_prefix))
+            .map(|language| CompletionCandidate::new(format!("language:{language}")))
+            .collect();
+    }
+
     let mut candidates: Vec<CompletionCandidate> = vec![];
+    if !current_str.is_empty() && "language:".starts_with(current_str) {
+        candidates.push(CompletionCandidate::new("language:"));
+    }
 
     // Support optional `path:hook_prefix` form while typing.
     let (path_part, hook_prefix_opt) = match current_str.split_once(':') {
@@ -50,9 +77,9 @@ pub(crate) fn selector_completer(current: &OsStr) -> Vec<CompletionCandidate> {
         };
         let mut had_children = false;
         if hook_prefix_opt.is_none() {
-            let mut child_dirs = list_subdirs(&base_dir, &shown_prefix, &filter_prefix, &workspace);
+            let mut child_dirs = list_subdirs(&base_dir, &shown_prefix, &filter_prefix, workspace);
             let mut child_colons =
-                list_direct_project_colons(&base_dir, &shown_prefix, &filter_prefix, &workspace);
+                list_direct_project_colons(&base_dir, &shown_prefix, &filter_prefix, workspace);
             had_children = !(child_dirs.is_empty() && child_colons.is_empty());
             candidates.append(&mut child_dirs);
             candidates.append(&mut child_colons);
@@ -104,14 +131,9 @@ pub(crate) fn selector_completer(current: &OsStr) -> Vec<CompletionCandidate> {
     }
 
     // No slash: match subdirectories under cwd and hook ids across workspace
-    candidates.extend(list_subdirs(&CWD, "", current_str, &workspace));
+    candidates.extend(list_subdirs(&CWD, "", current_str, workspace));
     // Also suggest immediate child project roots as `name:`
-    candidates.extend(list_direct_project_colons(
-        &CWD,
-        "",
-        current_str,
-        &workspace,
-    ));
+    candidates.extend(list_direct_project_colons(&CWD, "", current_str, workspace));
 
     // If the input ends with `:`, suggest hooks for that project
     if let Some(hook_prefix) = hook_prefix_opt {
@@ -0,0 +1,110 @@
+use std::borrow::Cow;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use path_clean::PathClean;
+use prek_consts::{ALT_CONFIG_FILE, CONFIG_FILE};
+
+use crate::cli::ExitStatus;
+use crate::cli::reporter::{HookInitReporter, HookInstallReporter, HookRunReporter};
+use crate::cli::run::install_hooks;
+use crate::config::Language;
+use crate::fs::{CWD, Simplified};
+use crate::printer::Printer;
+use crate::store::Store;
+use crate::workspace::Project;
+
+/// Run a hook directly on the given files, without git or the hook's file filters.
+pub(crate) async fn exec(
+    store: &Store,
+    config: Option<PathBuf>,
+    hook_id: String,
+    files: Vec<PathBuf>,
+    printer: Printer,
+) -> Result<ExitStatus> {
+    // Unlike `run`, the project isn't required to be in a git repository.
+    let config = match config {
+        Some(config) => std::path::absolute(config)?,
+        None => CWD
+            .ancestors()
+            .flat_map(|dir| [dir.join(CONFIG_FILE), dir.join(ALT_CONFIG_FILE)])
+            .find(|path| path.is_file())
+            .with_context(|| {
+                format!("No `{CONFIG_FILE}` found in the current directory or parent directories")
+            })?,
+    };
+    let mut project = Project::from_config_file(Cow::Owned(config), None)?;
+
+    let reporter = HookInitReporter::new(printer);
+    let lock = store.lock_shared_async().await?;
+    let hooks: Vec<_> = project
+        .init_hooks(store, Some(&reporter))
+        .await
+        .context("Failed to init hooks")?
+        .into_iter()
+        .filter(|hook| hook.id == hook_id || hook.alias == hook_id)
+        .map(Arc::new)
+        .collect();
+    if hooks.is_empty() {
+        anyhow::bail!(
+            "No hook found with id or alias `{hook_id}` in `{}`",
+            project.config_file().user_display()
+        );
+    }
+
+    let reporter = HookInstallReporter::new(printer);
+    let hooks = install_hooks(hooks, store, &reporter, false).await?;
+    drop(lock);
+
+    // Hooks run from the project root, like with `run`.
+    let files = files
+        .iter()
+        .map(|file| {
+            let file = std::path::absolute(file)?.clean();
+            Ok(match file.strip_prefix(project.path()) {
+                Ok(relative) => relative.to_path_buf(),
+                Err(_) => file,
+            })
+        })
+        .collect::<Result<Vec<_>, std::io::Error>>()?;
+    std::env::set_current_dir(project.path()).with_context(|| {
+        format!(
+            "Failed to change directory to `{}`",
+            project.path().display()
+        )
+    })?;
+
+    let reporter = HookRunReporter::new(printer, 0);
+    let mut status = ExitStatus::Success;
+    for hook in hooks {
+        if !Language::supported(hook.language) {
+            anyhow::bail!(
+                "Hook `{}` uses the unsupported language `{}`",
+                hook.id,
+                hook.language
+            );
+        }
+        let filenames: Vec<&Path> = if hook.pass_filenames {
+            files.iter().map(PathBuf::as_path).collect()
+        } else {
+            vec![]
+        };
+
+        let (exit_code, output) = hook
+            .language
+            .run(&hook, &filenames, store, &reporter)
+            .await
+            .with_context(|| format!("Failed to run hook `{hook}`"))?;
+        std::io::stdout().write_all(&output)?;
+
+        if !hook.success_exit_codes.contains(&exit_code)
+            || (hook.fail_on_output && !output.trim_ascii().is_empty())
+        {
+            status = ExitStatus::Failure;
+        }
+    }
+
+    Ok(status)
+}
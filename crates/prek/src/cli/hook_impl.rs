@@ -8,9 +8,12 @@ use anstream::eprintln;
 use anyhow::Result;
 use itertools::Itertools;
 use owo_colors::OwoColorize;
+use tracing::debug;
 
 use prek_consts::env_vars::EnvVars;
 
+use crate::audit;
+use crate::cli::run::PushRange;
 use crate::cli::{self, ExitStatus, RunArgs};
 use crate::config::HookType;
 use crate::fs::CWD;
@@ -22,6 +25,42 @@ use crate::workspace::Project;
 use crate::{git, warn_user};
 
 pub(crate) async fn hook_impl(
+    store: &Store,
+    config: Option<PathBuf>,
+    includes: Vec<String>,
+    skips: Vec<String>,
+    hook_type: HookType,
+    hook_dir: PathBuf,
+    skip_on_missing_config: bool,
+    script_version: Option<usize>,
+    args: Vec<OsString>,
+    printer: Printer,
+) -> Result<ExitStatus> {
+    // Find the commits that landed since the last hook run without the `pre-commit` hook.
+    audit::check_from_hook(hook_type).await;
+
+    let status = run_hook(
+        store,
+        config,
+        includes,
+        skips,
+        hook_type,
+        hook_dir,
+        skip_on_missing_config,
+        script_version,
+        args,
+        printer,
+    )
+    .await?;
+
+    if matches!(hook_type, HookType::PreCommit) && status == ExitStatus::Success {
+        audit::record_verified_from_hook().await;
+    }
+    Ok(status)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_hook(
     store: &Store,
     config: Option<PathBuf>,
     includes: Vec<String>,
@@ -119,7 +158,9 @@ pub(crate) async fn hook_impl(
         config,
         includes,
         skips,
+        false,
         Some(hook_type.into()),
+        None,
         run_args.from_ref,
         run_args.to_ref,
         run_args.all_files,
@@ -127,8 +168,22 @@ pub(crate) async fn hook_impl(
         vec![],
         false,
         false,
+        false,
+        false,
+        false,
+        false,
+        None,
+        false,
+        None,
+        false,
+        false,
         run_args.fail_fast,
+        run_args.modified_exit_code,
+        run_args.autofix_stage,
+        run_args.serial_projects,
         false,
+        None,
+        None,
         false,
         run_args.extra,
         false,
@@ -140,16 +195,31 @@ pub(crate) async fn hook_impl(
 async fn to_run_args(hook_type: HookType, args: &[OsString]) -> Option<RunArgs> {
     let mut run_args = RunArgs::default();
 
+    let stdin = if hook_type.forwards_stdin() {
+        let mut buffer = Vec::new();
+        if std::io::stdin().read_to_end(&mut buffer).is_err() {
+            buffer.clear();
+        }
+        crate::run::set_hook_stdin(hook_type.into(), buffer.clone());
+        buffer
+    } else {
+        Vec::new()
+    };
+
     match hook_type {
         HookType::PrePush => {
             // https://git-scm.com/docs/githooks#_pre_push
             run_args.extra.remote_name = Some(args[0].to_string_lossy().into_owned());
             run_args.extra.remote_url = Some(args[1].to_string_lossy().into_owned());
 
-            if let Some(push_info) = parse_pre_push_info(&args[0].to_string_lossy()).await {
-                run_args.from_ref = push_info.from_ref;
-                run_args.to_ref = push_info.to_ref;
-                run_args.all_files = push_info.all_files;
+            let refs = String::from_utf8_lossy(&stdin);
+            if let Some(push_info) = parse_pre_push_info(&args[0].to_string_lossy(), &refs).await {
+                // `PRE_COMMIT_FROM_REF` and `PRE_COMMIT_TO_REF` come from the first pushed ref,
+                // while hooks run on the files of all pushed refs.
+                let first = &push_info.ranges[0];
+                run_args.from_ref.clone_from(&first.from);
+                run_args.to_ref = Some(first.to.clone());
+                run_args.extra.push_ranges = push_info.ranges;
                 run_args.extra.remote_branch = push_info.remote_branch;
                 run_args.extra.local_branch = push_info.local_branch;
             } else {
@@ -185,7 +255,20 @@ async fn to_run_args(hook_type: HookType, args: &[OsString]) -> Option<RunArgs>
                 run_args.extra.pre_rebase_branch = Some(args[1].to_string_lossy().into_owned());
             }
         }
-        HookType::PostCommit | HookType::PreMergeCommit | HookType::PreCommit => {}
+        HookType::PostIndexChange => {
+            // https://git-scm.com/docs/githooks#_post_index_change
+            run_args.extra.is_workdir_updated = args[0] == "1";
+            run_args.extra.is_skip_worktree_changed = args[1] == "1";
+        }
+        HookType::ReferenceTransaction => {
+            // https://git-scm.com/docs/githooks#_reference_transaction
+            run_args.extra.reference_transaction_state =
+                Some(args[0].to_string_lossy().into_owned());
+        }
+        HookType::PostCommit
+        | HookType::PreMergeCommit
+        | HookType::PreCommit
+        | HookType::PreAutoGc => {}
     }
 
     Some(run_args)
@@ -193,23 +276,17 @@ async fn to_run_args(hook_type: HookType, args: &[OsString]) -> Option<RunArgs>
 
 #[derive(Debug)]
 struct PushInfo {
-    from_ref: Option<String>,
-    to_ref: Option<String>,
-    all_files: bool,
+    /// The commit ranges of all pushed refs.
+    ranges: Vec<PushRange>,
     remote_branch: Option<String>,
     local_branch: Option<String>,
 }
 
-async fn parse_pre_push_info(remote_name: &str) -> Option<PushInfo> {
-    // Read from stdin
-    let mut stdin = std::io::stdin();
-    let mut buffer = String::new();
-
-    if stdin.read_to_string(&mut buffer).is_err() {
-        return None;
-    }
+/// Compute the commits being pushed for each ref `git push` writes to stdin.
+async fn parse_pre_push_info(remote_name: &str, refs: &str) -> Option<PushInfo> {
+    let mut info: Option<PushInfo> = None;
 
-    for line in buffer.lines() {
+    for line in refs.lines() {
         let parts: Vec<&str> = line.rsplitn(4, ' ').collect();
         if parts.len() != 4 {
             continue;
@@ -220,59 +297,61 @@ async fn parse_pre_push_info(remote_name: &str) -> Option<PushInfo> {
         let remote_branch = parts[1];
         let remote_sha = parts[0];
 
-        // Skip if local_sha is all zeros
-        if local_sha.bytes().all(|b| b == b'0') {
+        let Some(range) = push_range(remote_name, local_sha, remote_sha).await else {
             continue;
-        }
+        };
+        debug!("Pushing `{local_branch}` to `{remote_branch}`: {range:?}");
 
-        // If remote_sha exists and is not all zeros
-        if !remote_sha.bytes().all(|b| b == b'0')
-            && git::rev_exists(remote_sha).await.unwrap_or(false)
-        {
-            return Some(PushInfo {
-                from_ref: Some(remote_sha.to_string()),
-                to_ref: Some(local_sha.to_string()),
-                all_files: false,
-                remote_branch: Some(remote_branch.to_string()),
-                local_branch: Some(local_branch.to_string()),
-            });
-        }
+        // Like `pre-commit`, the branches of the first pushed ref are exposed to hooks.
+        let info = info.get_or_insert_with(|| PushInfo {
+            ranges: Vec::new(),
+            remote_branch: Some(remote_branch.to_string()),
+            local_branch: Some(local_branch.to_string()),
+        });
+        info.ranges.push(range);
+    }
 
-        // Find ancestors that don't exist in remote
-        let ancestors = git::get_ancestors_not_in_remote(local_sha, remote_name)
-            .await
-            .unwrap_or_default();
-        if ancestors.is_empty() {
-            continue;
-        }
+    // Nothing to push if `None`
+    info
+}
 
-        let first_ancestor = &ancestors[0];
-        let roots = git::get_root_commits(local_sha).await.unwrap_or_default();
-
-        if roots.contains(first_ancestor) {
-            // Pushing the whole tree including root commit
-            return Some(PushInfo {
-                from_ref: None,
-                to_ref: Some(local_sha.to_string()),
-                all_files: true,
-                remote_branch: Some(remote_branch.to_string()),
-                local_branch: Some(local_branch.to_string()),
-            });
-        }
-        // Find the source (first_ancestor^)
-        if let Ok(Some(source)) = git::get_parent_commit(first_ancestor).await {
-            return Some(PushInfo {
-                from_ref: Some(source),
-                to_ref: Some(local_sha.to_string()),
-                all_files: false,
-                remote_branch: Some(remote_branch.to_string()),
-                local_branch: Some(local_branch.to_string()),
-            });
-        }
+/// Compute the commits being pushed from a local commit to a remote one.
+async fn push_range(remote_name: &str, local_sha: &str, remote_sha: &str) -> Option<PushRange> {
+    // A deleted ref pushes no commits.
+    if local_sha.bytes().all(|b| b == b'0') {
+        return None;
+    }
+
+    // If remote_sha exists and is not all zeros
+    if !remote_sha.bytes().all(|b| b == b'0') && git::rev_exists(remote_sha).await.unwrap_or(false)
+    {
+        return Some(PushRange {
+            from: Some(remote_sha.to_string()),
+            to: local_sha.to_string(),
+        });
     }
 
-    // Nothing to push
-    None
+    // A new branch, or a remote commit we don't have: push the commits no remote tracking
+    // branch has seen.
+    let ancestors = git::get_ancestors_not_in_remote(local_sha, remote_name)
+        .await
+        .unwrap_or_default();
+    let first_ancestor = ancestors.first()?;
+
+    let roots = git::get_root_commits(local_sha).await.unwrap_or_default();
+    if roots.contains(first_ancestor) {
+        // Pushing the whole history including the root commit
+        return Some(PushRange {
+            from: None,
+            to: local_sha.to_string(),
+        });
+    }
+    // Find the source (first_ancestor^)
+    let source = git::get_parent_commit(first_ancestor).await.ok()??;
+    Some(PushRange {
+        from: Some(source),
+        to: local_sha.to_string(),
+    })
 }
 
 fn format_expected_args(range: RangeInclusive<usize>) -> String {
@@ -0,0 +1,515 @@
+use std::fmt::Write;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use itertools::Itertools;
+use owo_colors::OwoColorize;
+use rustc_hash::FxHashMap;
+
+use crate::cli::ExitStatus;
+use crate::cli::reporter::HookInitReporter;
+use crate::cli::run::{Selectors, find_installed_env, needs_env};
+use crate::config::{
+    self, FailFast, FilePattern, HookArch, HookKind, HookOptions, HookOs, Language, ManifestHook,
+    Stage,
+};
+use crate::fs::{CWD, Simplified};
+use crate::hook::{Hook, Repo, Stages};
+use crate::printer::Printer;
+use crate::store::Store;
+use crate::workspace::Workspace;
+
+/// Show where a hook comes from, how its options are resolved, and its environment.
+pub(crate) async fn info(
+    store: &Store,
+    config: Option<PathBuf>,
+    selector: String,
+    refresh: bool,
+    printer: Printer,
+) -> Result<ExitStatus> {
+    let workspace_root = Workspace::find_root(config.as_deref(), &CWD)?;
+    let selectors = Selectors::load(std::slice::from_ref(&selector), &[], &workspace_root)?;
+    let mut workspace = Workspace::discover(
+        store,
+        workspace_root,
+        config,
+        Some(&selectors),
+        refresh,
+        false,
+    )?;
+
+    let reporter = HookInitReporter::new(printer);
+    let lock = store.lock_shared_async().await?;
+    let hooks = workspace
+        .init_hooks(store, Some(&reporter))
+        .await
+        .context("Failed to init hooks")?;
+
+    let mut hooks = hooks
+        .into_iter()
+        .filter(|hook| selectors.matches_hook(hook))
+        .collect::<Vec<_>>();
+    selectors.report_unused();
+    let hook = match hooks.len() {
+        0 => anyhow::bail!("No hook found for `{selector}`"),
+        1 => hooks.pop().expect("one hook"),
+        _ => anyhow::bail!(
+            "`{selector}` matches {} hooks, select one of them with `project-path:hook-id`: `{}`",
+            hooks.len(),
+            hooks.iter().map(Hook::full_id).join("`, `")
+        ),
+    };
+
+    let environment = if needs_env(&hook) {
+        match find_installed_env(store, &hook).await {
+            Some(info) => format!(
+                "{} ({} {})",
+                info.env_path.user_display(),
+                hook.language,
+                info.language_version
+            ),
+            None => "not installed".to_string(),
+        }
+    } else {
+        "not needed".to_string()
+    };
+    drop(lock);
+
+    let mut stdout = printer.stdout();
+    writeln!(stdout, "{}", hook.full_id().bold())?;
+    let field = |name: &str| format!("{name}:").bold().cyan().to_string();
+    match hook.repo() {
+        Repo::Remote { url, rev, path, .. } => {
+            writeln!(stdout, "  {} {url}", field("Repo"))?;
+            writeln!(stdout, "  {} {rev}", field("Rev"))?;
+            writeln!(
+                stdout,
+                "  {} {}",
+                field("Manifest"),
+                config::manifest_path(path).user_display()
+            )?;
+        }
+        repo => writeln!(stdout, "  {} {repo}", field("Repo"))?,
+    }
+    writeln!(
+        stdout,
+        "  {} {}",
+        field("Config"),
+        hook.project().config_file().user_display()
+    )?;
+    writeln!(stdout, "  {} {}", field("Command"), command(&hook)?)?;
+    writeln!(stdout, "  {} {environment}", field("Environment"))?;
+    writeln!(stdout, "  {}", field("Options"))?;
+    for (key, value, source) in resolve_options(&hook) {
+        writeln!(
+            stdout,
+            "    {key}: {value} {}",
+            format!("({source})").dimmed()
+        )?;
+    }
+
+    Ok(ExitStatus::Success)
+}
+
+/// The command the hook runs, with the files appended as `<files>` when they are passed to it.
+fn command(hook: &Hook) -> Result<String> {
+    let mut command = hook.entry.split()?;
+    command.extend(hook.args.iter().cloned());
+    let mut command =
+        shlex::try_join(command.iter().map(String::as_str)).unwrap_or_else(|_| command.join(" "));
+    if hook.pass_filenames {
+        command.push_str(" <files>");
+    }
+    Ok(command)
+}
+
+fn format_stages(stages: &Stages) -> String {
+    match stages {
+        Stages::All => "all".to_string(),
+        Stages::Some(stages) => Stage::value_variants()
+            .iter()
+            .filter(|stage| stages.contains(stage))
+            .map(Stage::as_str)
+            .join(", "),
+    }
+}
+
+/// Options by key, with their values formatted like in the config file.
+type Values = Vec<(&'static str, String)>;
+
+/// Where the value of an option comes from.
+enum Source {
+    /// The built-in default of the option.
+    Default,
+    /// The hook in the manifest of its repository.
+    Manifest,
+    /// The hook in the config file, with the value of the manifest it overrides.
+    Config(Option<String>),
+    /// A project-wide default of the config file, e.g. `default_stages`.
+    ProjectDefault(&'static str),
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Default => f.write_str("default"),
+            Source::Manifest => f.write_str("manifest"),
+            Source::Config(None) => f.write_str("config"),
+            Source::Config(Some(manifest)) => write!(f, "config, manifest: {manifest}"),
+            Source::ProjectDefault(key) => f.write_str(key),
+        }
+    }
+}
+
+/// The final options of the hook, with where each of them is set.
+fn resolve_options(hook: &Hook) -> Vec<(&'static str, String, String)> {
+    let (manifest, config, repo_filters) = option_sources(hook);
+    let manifest: FxHashMap<_, _> = manifest.into_iter().collect();
+    let config: FxHashMap<_, _> = config.into_iter().collect();
+    let default_language_version = hook
+        .project()
+        .config()
+        .default_language_version
+        .as_ref()
+        .and_then(|versions| versions.get(&hook.language));
+    let language_version = config
+        .get("language_version")
+        .or(manifest.get("language_version"))
+        .or(default_language_version);
+
+    final_options(hook, language_version)
+        .into_iter()
+        .map(|(key, value)| {
+            let source = if config.contains_key(key) {
+                Source::Config(manifest.get(key).cloned())
+            } else if manifest.contains_key(key) {
+                Source::Manifest
+            } else if key == "stages" && hook.project().config().default_stages.is_some() {
+                Source::ProjectDefault("default_stages")
+            } else if key == "language_version" && default_language_version.is_some() {
+                Source::ProjectDefault("default_language_version")
+            } else {
+                Source::Default
+            };
+            // The `files` and `exclude` of the repo are combined with the ones of the hook.
+            let source = match source {
+                Source::Default if repo_filters.contains(&key) => "repo".to_string(),
+                source if repo_filters.contains(&key) => format!("{source}, repo"),
+                source => source.to_string(),
+            };
+            (key, value, source)
+        })
+        .collect()
+}
+
+/// The options set by the manifest and by the config file for the hook, and the file
+/// patterns set for its whole repo.
+fn option_sources(hook: &Hook) -> (Values, Values, Vec<&'static str>) {
+    let repo_config = hook
+        .project()
+        .config()
+        .repos
+        .iter()
+        .flat_map(|repo| {
+            let hooks = match repo {
+                config::Repo::Remote(repo) => repo.hooks.len(),
+                config::Repo::Local(repo) => repo.hooks.len(),
+                config::Repo::Meta(repo) => repo.hooks.len(),
+                config::Repo::Builtin(repo) => repo.hooks.len(),
+            };
+            (0..hooks).map(move |idx| (repo, idx))
+        })
+        .nth(hook.idx);
+    let Some((repo_config, idx)) = repo_config else {
+        return (Vec::new(), Vec::new(), Vec::new());
+    };
+
+    match repo_config {
+        config::Repo::Remote(repo) => {
+            let manifest = hook
+                .repo()
+                .get_hook(&hook.id)
+                .map(manifest_values)
+                .unwrap_or_default();
+            let config = &repo.hooks[idx];
+            let mut values = Vec::new();
+            values.extend(config.name.as_ref().map(|name| ("name", name.format())));
+            values.extend(config.entry.as_ref().map(|entry| ("entry", entry.format())));
+            values.extend(
+                config
+                    .language
+                    .map(|language| ("language", language.format())),
+            );
+            values.extend(option_values(&config.options));
+            let filters = repo_filters(repo.files.as_ref(), repo.exclude.as_ref());
+            (manifest, values, filters)
+        }
+        config::Repo::Local(repo) => {
+            let filters = repo_filters(repo.files.as_ref(), repo.exclude.as_ref());
+            (Vec::new(), manifest_values(&repo.hooks[idx]), filters)
+        }
+        // The options of meta and builtin hooks are merged into their predefined ones,
+        // the config sets those that differ.
+        config::Repo::Meta(repo) => {
+            let manifest = config::MetaHook::from_id(&hook.id)
+                .map(|predefined| manifest_values(&predefined.0))
+                .unwrap_or_default();
+            let merged = manifest_values(&repo.hooks[idx].0);
+            (manifest.clone(), overridden(&manifest, merged), Vec::new())
+        }
+        config::Repo::Builtin(repo) => {
+            let manifest = config::BuiltinHook::from_id(&hook.id)
+                .map(|predefined| manifest_values(&predefined.0))
+                .unwrap_or_default();
+            let merged = manifest_values(&repo.hooks[idx].0);
+            (manifest.clone(), overridden(&manifest, merged), Vec::new())
+        }
+    }
+}
+
+fn repo_filters(files: Option<&FilePattern>, exclude: Option<&FilePattern>) -> Vec<&'static str> {
+    let mut filters = Vec::new();
+    if files.is_some() {
+        filters.push("files");
+    }
+    if exclude.is_some() {
+        filters.push("exclude");
+    }
+    filters
+}
+
+/// The merged options that differ from the predefined ones.
+fn overridden(manifest: &[(&'static str, String)], merged: Values) -> Values {
+    merged
+        .into_iter()
+        .filter(|value| !manifest.contains(value))
+        .collect()
+}
+
+fn manifest_values(hook: &ManifestHook) -> Values {
+    let mut values = vec![
+        ("name", hook.name.format()),
+        ("entry", hook.entry.format()),
+        ("language", hook.language.format()),
+    ];
+    values.extend(option_values(&hook.options));
+    values
+}
+
+fn option_values(options: &HookOptions) -> Values {
+    let mut values = Vec::new();
+    macro_rules! push_if_some {
+        ($($field:ident),* $(,)?) => {
+            $(
+            if let Some(value) = &options.$field {
+                values.push((stringify!($field), value.format()));
+            }
+            )*
+        };
+    }
+
+    push_if_some!(
+        alias,
+        files,
+        exclude,
+        types,
+        types_or,
+        exclude_types,
+        max_file_size,
+        additional_dependencies,
+        args,
+        env,
+        always_run,
+        fail_fast,
+        pass_filenames,
+        pass_push_refs,
+        success_exit_codes,
+        fail_on_output,
+        retries,
+        retry_delay,
+        os,
+        arch,
+        kind,
+        description,
+        language_version,
+        log_file,
+        require_serial,
+        max_files_per_invocation,
+        priority,
+        after,
+        before,
+        stages,
+        verbose,
+        minimum_prek_version,
+        image_digest,
+        goflags,
+    );
+    values
+}
+
+/// The options of the built hook, leaving out the unset ones.
+fn final_options(hook: &Hook, language_version: Option<&String>) -> Values {
+    let non_empty = |values: &[String]| (!values.is_empty()).then(|| values.format());
+    let dependencies = hook
+        .additional_dependencies
+        .iter()
+        .sorted()
+        .cloned()
+        .collect::<Vec<_>>();
+
+    [
+        ("name", Some(hook.name.format())),
+        ("entry", Some(hook.entry.raw().format())),
+        ("language", Some(hook.language.format())),
+        (
+            "alias",
+            (!hook.alias.is_empty()).then(|| hook.alias.format()),
+        ),
+        ("files", hook.files.as_ref().map(OptionValue::format)),
+        ("exclude", hook.exclude.as_ref().map(OptionValue::format)),
+        ("types", Some(hook.types.format())),
+        ("types_or", Some(hook.types_or.format())),
+        ("exclude_types", Some(hook.exclude_types.format())),
+        (
+            "max_file_size",
+            hook.max_file_size.map(|size| size.format()),
+        ),
+        ("additional_dependencies", Some(dependencies.format())),
+        ("args", Some(hook.args.format())),
+        ("env", (!hook.env.is_empty()).then(|| hook.env.format())),
+        ("always_run", Some(hook.always_run.format())),
+        ("fail_fast", Some(hook.fail_fast.format())),
+        ("pass_filenames", Some(hook.pass_filenames.format())),
+        ("pass_push_refs", Some(hook.pass_push_refs.format())),
+        ("success_exit_codes", Some(hook.success_exit_codes.format())),
+        ("fail_on_output", Some(hook.fail_on_output.format())),
+        ("retries", Some(hook.retries.format())),
+        ("retry_delay", Some(hook.retry_delay.as_secs_f64().format())),
+        ("os", (!hook.os.is_empty()).then(|| hook.os.format())),
+        ("arch", (!hook.arch.is_empty()).then(|| hook.arch.format())),
+        ("kind", hook.kind.map(|kind| kind.format())),
+        (
+            "description",
+            hook.description.as_ref().map(OptionValue::format),
+        ),
+        (
+            "language_version",
+            Some(language_version.map_or_else(|| "default".to_string(), OptionValue::format)),
+        ),
+        ("log_file", hook.log_file.as_ref().map(OptionValue::format)),
+        ("require_serial", Some(hook.require_serial.format())),
+        (
+            "max_files_per_invocation",
+            hook.max_files_per_invocation.map(|max| max.format()),
+        ),
+        ("priority", Some(hook.priority.format())),
+        ("after", non_empty(&hook.after)),
+        ("before", non_empty(&hook.before)),
+        ("stages", Some(format_stages(&hook.stages))),
+        ("verbose", Some(hook.verbose.format())),
+        (
+            "minimum_prek_version",
+            hook.minimum_prek_version.as_ref().map(OptionValue::format),
+        ),
+        (
+            "image_digest",
+            hook.image_digest.as_ref().map(OptionValue::format),
+        ),
+        ("goflags", non_empty(&hook.goflags)),
+    ]
+    .into_iter()
+    .filter_map(|(key, value)| Some((key, value?)))
+    .collect()
+}
+
+/// Format an option value like it is written in the config file.
+trait OptionValue {
+    fn format(&self) -> String;
+}
+
+impl OptionValue for String {
+    fn format(&self) -> String {
+        self.clone()
+    }
+}
+
+impl OptionValue for &str {
+    fn format(&self) -> String {
+        (*self).to_string()
+    }
+}
+
+macro_rules! impl_option_value_display {
+    ($($ty:ty),* $(,)?) => {
+        $(
+        impl OptionValue for $ty {
+            fn format(&self) -> String {
+                self.to_string()
+            }
+        }
+        )*
+    };
+}
+
+impl_option_value_display!(bool, u32, u64, i32, f64, NonZeroUsize, FilePattern);
+
+impl OptionValue for Language {
+    fn format(&self) -> String {
+        self.as_str().to_string()
+    }
+}
+
+impl OptionValue for Stage {
+    fn format(&self) -> String {
+        self.as_str().to_string()
+    }
+}
+
+impl OptionValue for FailFast {
+    fn format(&self) -> String {
+        match self {
+            FailFast::Off => "false",
+            FailFast::Group => "group",
+            FailFast::Project => "project",
+            FailFast::Workspace => "workspace",
+        }
+        .to_string()
+    }
+}
+
+impl OptionValue for HookKind {
+    fn format(&self) -> String {
+        format!("{self:?}").to_lowercase()
+    }
+}
+
+impl OptionValue for HookOs {
+    fn format(&self) -> String {
+        format!("{self:?}").to_lowercase()
+    }
+}
+
+impl OptionValue for HookArch {
+    fn format(&self) -> String {
+        format!("{self:?}").to_lowercase()
+    }
+}
+
+impl<T: OptionValue> OptionValue for [T] {
+    fn format(&self) -> String {
+        format!("[{}]", self.iter().map(OptionValue::format).join(", "))
+    }
+}
+
+impl OptionValue for FxHashMap<String, String> {
+    fn format(&self) -> String {
+        let entries = self
+            .iter()
+            .sorted()
+            .map(|(key, value)| format!("{key}: {value}"))
+            .join(", ");
+        format!("{{{entries}}}")
+    }
+}
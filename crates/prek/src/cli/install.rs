@@ -5,6 +5,7 @@ use std::sync::Arc;
 
 use anyhow::{Context, Result};
 use bstr::ByteSlice;
+use itertools::Itertools;
 use owo_colors::OwoColorize;
 use prek_consts::{ALT_CONFIG_FILE, CONFIG_FILE};
 use same_file::is_same_file;
@@ -13,10 +14,11 @@ use crate::cli::reporter::{HookInitReporter, HookInstallReporter};
 use crate::cli::run;
 use crate::cli::run::{SelectorSource, Selectors};
 use crate::cli::{ExitStatus, HookType};
-use crate::config::load_config;
+use crate::config::{Config, HookOptions, Repo as ConfigRepo, Stage, load_config};
 use crate::fs::{CWD, Simplified};
 use crate::git::{GIT_ROOT, git_cmd};
 use crate::printer::Printer;
+use crate::process::Cmd;
 use crate::store::Store;
 use crate::workspace::{Project, Workspace};
 use crate::{git, warn_user};
@@ -34,6 +36,7 @@ pub(crate) async fn install(
     refresh: bool,
     printer: Printer,
     git_dir: Option<&Path>,
+    bootstrap: Option<&str>,
 ) -> Result<ExitStatus> {
     if git_dir.is_none() && git::has_hooks_path_set().await? {
         anyhow::bail!(
@@ -45,6 +48,9 @@ pub(crate) async fn install(
 
     let project = Project::discover(config.as_deref(), &CWD).ok();
     let hook_types = get_hook_types(hook_types, project.as_ref(), config.as_deref());
+    if let Some(project) = &project {
+        warn_untriggered_hooks(project.config(), &hook_types);
+    }
 
     let hooks_path = if let Some(dir) = git_dir {
         dir.join("hooks")
@@ -70,32 +76,161 @@ pub(crate) async fn install(
             &hooks_path,
             overwrite,
             allow_missing_config,
+            bootstrap,
             printer,
         )?;
     }
 
     if install_hook_environments {
-        install_hooks(store, config, includes, skips, refresh, printer).await?;
+        install_hooks(
+            store, config, includes, skips, false, false, refresh, printer,
+        )
+        .await?;
     }
 
     Ok(ExitStatus::Success)
 }
 
+/// Install hooks into every git repository with a config file found under `directory`.
+///
+/// `prek install` runs as a separate process in each repository, as the git and project
+/// discovery of a run is tied to the current directory.
+pub(crate) async fn install_recursive(
+    directory: &Path,
+    hook_types: Vec<HookType>,
+    install_hook_environments: bool,
+    overwrite: bool,
+    refresh: bool,
+    printer: Printer,
+) -> Result<ExitStatus> {
+    if !directory.is_dir() {
+        anyhow::bail!("Directory `{}` does not exist", directory.user_display());
+    }
+
+    let repos = find_repos_with_config(directory);
+    if repos.is_empty() {
+        writeln!(
+            printer.stdout(),
+            "No git repositories with a `{}` found under `{}`",
+            CONFIG_FILE.cyan(),
+            directory.user_display().cyan()
+        )?;
+        return Ok(ExitStatus::Success);
+    }
+
+    let prek = std::env::current_exe()?;
+    let mut results = Vec::with_capacity(repos.len());
+    for repo in repos {
+        let mut cmd = Cmd::new(&prek, "prek install");
+        cmd.current_dir(&repo).arg("install").check(false);
+        for hook_type in &hook_types {
+            cmd.arg("--hook-type").arg(hook_type.as_str());
+        }
+        if install_hook_environments {
+            cmd.arg("--install-hooks");
+        }
+        if overwrite {
+            cmd.arg("--overwrite");
+        }
+        if refresh {
+            cmd.arg("--refresh");
+        }
+
+        let output = cmd.output().await?;
+        let name = match repo.strip_prefix(directory) {
+            Ok(relative) if !relative.as_os_str().is_empty() => relative.display().to_string(),
+            _ => ".".to_string(),
+        };
+        let error = (!output.status.success())
+            .then(|| String::from_utf8_lossy(output.stderr.trim()).to_string());
+        results.push((name, error));
+    }
+
+    let width = results
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or_default()
+        .max("Repository".len());
+    writeln!(
+        printer.stdout(),
+        "{}",
+        format!("{:<width$}  Status", "Repository").bold()
+    )?;
+    for (name, error) in &results {
+        let status = if error.is_some() {
+            "failed".red().to_string()
+        } else {
+            "installed".green().to_string()
+        };
+        writeln!(printer.stdout(), "{name:<width$}  {status}")?;
+    }
+
+    let failed = results
+        .iter()
+        .filter_map(|(name, error)| Some((name, error.as_ref()?)))
+        .collect::<Vec<_>>();
+    for (name, error) in &failed {
+        writeln!(
+            printer.stderr(),
+            "{}: Failed to install hooks in `{}`:\n{error}",
+            "error".red().bold(),
+            name.cyan()
+        )?;
+    }
+
+    if failed.is_empty() {
+        Ok(ExitStatus::Success)
+    } else {
+        Ok(ExitStatus::Failure)
+    }
+}
+
+/// Find the git repositories under `directory` that have a config file at their root.
+fn find_repos_with_config(directory: &Path) -> Vec<PathBuf> {
+    let mut repos = ignore::WalkBuilder::new(directory)
+        .follow_links(false)
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_dir())
+        })
+        .map(ignore::DirEntry::into_path)
+        .filter(|path| {
+            path.join(".git").exists()
+                && (path.join(CONFIG_FILE).is_file() || path.join(ALT_CONFIG_FILE).is_file())
+        })
+        .collect::<Vec<_>>();
+    repos.sort();
+    repos
+}
+
+#[allow(clippy::fn_params_excessive_bools)]
 pub(crate) async fn install_hooks(
     store: &Store,
     config: Option<PathBuf>,
     includes: Vec<String>,
     skips: Vec<String>,
+    force: bool,
+    dry_run: bool,
     refresh: bool,
     printer: Printer,
 ) -> Result<ExitStatus> {
     let workspace_root = Workspace::find_root(config.as_deref(), &CWD)?;
     let selectors = Selectors::load(&includes, &skips, &workspace_root)?;
-    let mut workspace =
-        Workspace::discover(store, workspace_root, config, Some(&selectors), refresh)?;
+    let mut workspace = Workspace::discover(
+        store,
+        workspace_root,
+        config,
+        Some(&selectors),
+        refresh,
+        false,
+    )?;
 
     let reporter = HookInitReporter::new(printer);
-    let _lock = store.lock_async().await?;
+    let _lock = store.lock_shared_async().await?;
 
     let hooks = workspace
         .init_hooks(store, Some(&reporter))
@@ -103,12 +238,30 @@ pub(crate) async fn install_hooks(
         .context("Failed to init hooks")?;
     let filtered_hooks: Vec<_> = hooks
         .into_iter()
-        .filter(|h| selectors.matches_hook(h))
+        .filter(|h| selectors.matches_hook(h) && h.supports_current_platform())
         .map(Arc::new)
         .collect();
 
+    let plan = run::plan_install(&filtered_hooks, store, force).await;
+    writeln!(
+        printer.stdout(),
+        "Hooks: {} up-to-date, {} to build",
+        plan.up_to_date.len().cyan(),
+        plan.to_build.len().cyan(),
+    )?;
+
+    if dry_run {
+        for hook in &plan.to_build {
+            writeln!(printer.stdout(), "Would build `{hook:#}`")?;
+        }
+        return Ok(ExitStatus::Success);
+    }
+    if plan.to_build.is_empty() {
+        return Ok(ExitStatus::Success);
+    }
+
     let reporter = HookInstallReporter::new(printer);
-    run::install_hooks(filtered_hooks, store, &reporter).await?;
+    run::install_hooks(plan.to_build, store, &reporter, force).await?;
 
     Ok(ExitStatus::Success)
 }
@@ -148,6 +301,87 @@ fn get_hook_types(
     hook_types
 }
 
+/// Warn about hooks configured only for stages that none of the installed hook types trigger.
+///
+/// Only the `stages` set in the config are known here, the stages from the manifests of remote
+/// hooks are not checked.
+fn warn_untriggered_hooks(config: &Config, hook_types: &[HookType]) {
+    let triggered: Vec<Stage> = hook_types.iter().copied().map(Stage::from).collect();
+
+    let hooks = config
+        .repos
+        .iter()
+        .flat_map(|repo| -> Vec<(&str, &HookOptions)> {
+            match repo {
+                ConfigRepo::Remote(repo) => repo
+                    .hooks
+                    .iter()
+                    .map(|hook| (hook.id.as_str(), &hook.options))
+                    .collect(),
+                ConfigRepo::Local(repo) => repo
+                    .hooks
+                    .iter()
+                    .map(|hook| (hook.id.as_str(), &hook.options))
+                    .collect(),
+                ConfigRepo::Meta(repo) => repo
+                    .hooks
+                    .iter()
+                    .map(|hook| (hook.0.id.as_str(), &hook.0.options))
+                    .collect(),
+                ConfigRepo::Builtin(repo) => repo
+                    .hooks
+                    .iter()
+                    .map(|hook| (hook.0.id.as_str(), &hook.0.options))
+                    .collect(),
+            }
+        });
+
+    let mut untriggered = Vec::new();
+    let mut missing = Vec::new();
+    for (id, options) in hooks {
+        let Some(stages) = options.stages.as_ref().or(config.default_stages.as_ref()) else {
+            continue;
+        };
+        // Hooks only run manually are not meant to be triggered by git.
+        let stages: Vec<_> = stages
+            .iter()
+            .filter(|stage| **stage != Stage::Manual)
+            .collect();
+        if stages.is_empty() || stages.iter().any(|stage| triggered.contains(stage)) {
+            continue;
+        }
+        untriggered.push(id);
+        for stage in stages {
+            if !missing.contains(stage) {
+                missing.push(*stage);
+            }
+        }
+    }
+
+    if !untriggered.is_empty() {
+        let hooks = if untriggered.len() == 1 {
+            "Hook"
+        } else {
+            "Hooks"
+        };
+        let runs = if untriggered.len() == 1 {
+            "runs"
+        } else {
+            "run"
+        };
+        warn_user!(
+            "{hooks} {} only {runs} in stages no installed hook type triggers: {}\nhint: Install the hook types with `{}`",
+            untriggered.iter().map(|id| format!("`{id}`")).join(", "),
+            missing.iter().map(|stage| format!("`{stage}`")).join(", "),
+            format!(
+                "prek install {}",
+                missing.iter().map(|stage| format!("-t {stage}")).join(" ")
+            )
+            .cyan(),
+        );
+    }
+}
+
 fn install_hook_script(
     project: Option<&Project>,
     config: Option<PathBuf>,
@@ -156,6 +390,7 @@ fn install_hook_script(
     hooks_path: &Path,
     overwrite: bool,
     skip_on_missing_config: bool,
+    bootstrap: Option<&str>,
     printer: Printer,
 ) -> Result<()> {
     let hook_path = hooks_path.join(hook_type.as_str());
@@ -185,8 +420,18 @@ fn install_hook_script(
 
     // Add include/skip selectors.
     if let Some(selectors) = selectors {
+        // Glob and negated selectors need quoting in the hook script.
+        let quote = |flag: String| {
+            let plain = flag
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-_./:=@,+%".contains(c));
+            match shlex::try_quote(&flag) {
+                Ok(quoted) if !plain => quoted.into_owned(),
+                _ => flag,
+            }
+        };
         for include in selectors.includes() {
-            args.push(include.as_normalized_flag());
+            args.push(quote(include.as_normalized_flag()));
         }
 
         // Find any skip selectors from environment variables.
@@ -205,7 +450,7 @@ fn install_hook_script(
 
         for skip in selectors.skips() {
             if matches!(skip.source(), SelectorSource::CliFlag(_)) {
-                args.push(skip.as_normalized_flag());
+                args.push(quote(skip.as_normalized_flag()));
             }
         }
     }
@@ -252,7 +497,13 @@ fn install_hook_script(
     let hook_script = HOOK_TMPL
         .replace("[CUR_SCRIPT_VERSION]", &CUR_SCRIPT_VERSION.to_string())
         .replace("[PREK_PATH]", &format!(r#""{prek}""#))
-        .replace("[PREK_ARGS]", &args.join(" "));
+        .replace("[PREK_ARGS]", &args.join(" "))
+        .replace(
+            "[PREK_BOOTSTRAP]",
+            &bootstrap.map_or(String::new(), |version| {
+                BOOTSTRAP_TMPL.replace("[PREK_VERSION]", version)
+            }),
+        );
 
     fs_err::OpenOptions::new()
         .write(true)
@@ -291,7 +542,61 @@ if [ ! -x "$PREK" ]; then
     PREK="prek"
 fi
 
-exec "$PREK" hook-impl --hook-dir "$HERE" --script-version [CUR_SCRIPT_VERSION] [PREK_ARGS] -- "$@"
+[PREK_BOOTSTRAP]exec "$PREK" hook-impl --hook-dir "$HERE" --script-version [CUR_SCRIPT_VERSION] [PREK_ARGS] -- "$@"
+
+"#;
+
+/// Downloads a pinned prek release to `$PREK_HOME/bin` when prek is not installed,
+/// added to the hook script by `prek init-template-dir --bootstrap`.
+static BOOTSTRAP_TMPL: &str = r#"# Download prek if it is not installed.
+if ! command -v "$PREK" >/dev/null 2>&1; then
+    PREK_VERSION="[PREK_VERSION]"
+    PREK_BIN_DIR="${PREK_HOME:-${XDG_CACHE_HOME:-$HOME/.cache}/prek}/bin"
+    PREK="$PREK_BIN_DIR/prek-$PREK_VERSION"
+    if [ ! -x "$PREK" ]; then
+        case "$(uname -s)-$(uname -m)" in
+            Linux-x86_64) TARGET="x86_64-unknown-linux-musl" ;;
+            Linux-aarch64 | Linux-arm64) TARGET="aarch64-unknown-linux-musl" ;;
+            Linux-armv7l) TARGET="armv7-unknown-linux-musleabihf" ;;
+            Linux-i686 | Linux-i386) TARGET="i686-unknown-linux-musl" ;;
+            Darwin-x86_64) TARGET="x86_64-apple-darwin" ;;
+            Darwin-arm64) TARGET="aarch64-apple-darwin" ;;
+            *)
+                echo "prek: no prebuilt prek for $(uname -s) $(uname -m), install it manually" >&2
+                exit 1
+                ;;
+        esac
+        ARCHIVE="prek-$TARGET.tar.gz"
+        URL="https://github.com/j178/prek/releases/download/v$PREK_VERSION/$ARCHIVE"
+        TMP="$(mktemp -d)"
+        echo "prek: downloading prek $PREK_VERSION from $URL" >&2
+        if command -v curl >/dev/null 2>&1; then
+            DOWNLOAD="curl -fsSL -o"
+        else
+            DOWNLOAD="wget -q -O"
+        fi
+        if ! $DOWNLOAD "$TMP/$ARCHIVE" "$URL" || ! $DOWNLOAD "$TMP/$ARCHIVE.sha256" "$URL.sha256"; then
+            rm -rf "$TMP"
+            echo "prek: failed to download $URL" >&2
+            exit 1
+        fi
+        EXPECTED="$(cut -d ' ' -f 1 < "$TMP/$ARCHIVE.sha256")"
+        if command -v sha256sum >/dev/null 2>&1; then
+            ACTUAL="$(sha256sum "$TMP/$ARCHIVE" | cut -d ' ' -f 1)"
+        else
+            ACTUAL="$(shasum -a 256 "$TMP/$ARCHIVE" | cut -d ' ' -f 1)"
+        fi
+        if [ -z "$EXPECTED" ] || [ "$EXPECTED" != "$ACTUAL" ]; then
+            rm -rf "$TMP"
+            echo "prek: checksum mismatch for $ARCHIVE" >&2
+            exit 1
+        fi
+        mkdir -p "$PREK_BIN_DIR"
+        tar -xzf "$TMP/$ARCHIVE" -C "$TMP"
+        mv "$TMP/prek-$TARGET/prek" "$PREK"
+        rm -rf "$TMP"
+    fi
+fi
 
 "#;
 
@@ -361,9 +666,15 @@ pub(crate) async fn init_template_dir(
     config: Option<PathBuf>,
     hook_types: Vec<HookType>,
     requires_config: bool,
+    bootstrap: Option<String>,
     refresh: bool,
     printer: Printer,
 ) -> Result<ExitStatus> {
+    if let Some(version) = &bootstrap {
+        semver::Version::parse(version)
+            .with_context(|| format!("Invalid prek version `{version}` to bootstrap"))?;
+    }
+
     install(
         store,
         config,
@@ -376,6 +687,7 @@ pub(crate) async fn init_template_dir(
         refresh,
         printer,
         Some(&directory),
+        bootstrap.as_deref(),
     )
     .await?;
 
@@ -0,0 +1,146 @@
+use std::fmt::Write;
+
+use anyhow::Result;
+use owo_colors::OwoColorize;
+
+use crate::cli::run::summary::{RunSummary, SummaryStatus};
+use crate::cli::{ExitStatus, ListOutputFormat};
+use crate::last_run::{LastRun, LastRunHook};
+use crate::printer::Printer;
+use crate::store::Store;
+
+fn full_id(hook: &LastRunHook) -> String {
+    format!("{}:{}", hook.summary.project, hook.summary.id)
+}
+
+fn status(hook: &LastRunHook) -> String {
+    match (hook.summary.status, hook.summary.skip_reason) {
+        (_, Some(reason)) => format!("skipped ({})", reason.as_str()),
+        (SummaryStatus::Failed, None) if hook.summary.exit_code != 0 => {
+            format!("failed (exit code {})", hook.summary.exit_code)
+        }
+        (status, None) => status.as_str().to_string(),
+    }
+}
+
+/// Show the summary of the last run.
+pub(crate) fn last(
+    store: &Store,
+    failures: bool,
+    output_format: ListOutputFormat,
+    printer: Printer,
+) -> Result<ExitStatus> {
+    let last_run = LastRun::load(&store.last_run_file())?;
+
+    let Some(last_run) = last_run else {
+        match output_format {
+            ListOutputFormat::Text => writeln!(printer.stdout(), "No run recorded yet")?,
+            ListOutputFormat::Json => writeln!(printer.stdout(), "null")?,
+        }
+        return Ok(ExitStatus::Success);
+    };
+
+    if failures {
+        let files = last_run.failed_files();
+        match output_format {
+            ListOutputFormat::Text => {
+                for file in files {
+                    writeln!(printer.stdout(), "{}", file.display())?;
+                }
+            }
+            ListOutputFormat::Json => {
+                let json_output = serde_json::to_string_pretty(&files)?;
+                writeln!(printer.stdout(), "{json_output}")?;
+            }
+        }
+        return Ok(ExitStatus::Success);
+    }
+
+    if matches!(output_format, ListOutputFormat::Json) {
+        let json_output = serde_json::to_string_pretty(&last_run)?;
+        writeln!(printer.stdout(), "{json_output}")?;
+        return Ok(ExitStatus::Success);
+    }
+
+    writeln!(
+        printer.stdout(),
+        "Last run in `{}` on {} file{} ({})",
+        last_run.workspace.display().cyan(),
+        last_run.files,
+        if last_run.files == 1 { "" } else { "s" },
+        last_run.stage,
+    )?;
+    if !last_run.includes.is_empty() {
+        writeln!(
+            printer.stdout(),
+            "Selected: `{}`",
+            last_run.includes.join("`, `")
+        )?;
+    }
+    if !last_run.skips.is_empty() {
+        writeln!(
+            printer.stdout(),
+            "Skipped: `{}`",
+            last_run.skips.join("`, `")
+        )?;
+    }
+
+    if !last_run.hooks.is_empty() {
+        let width = last_run
+            .hooks
+            .iter()
+            .map(|hook| full_id(hook).len())
+            .max()
+            .unwrap_or_default()
+            .max("Hook".len());
+        let status_width = last_run
+            .hooks
+            .iter()
+            .map(|hook| status(hook).len())
+            .max()
+            .unwrap_or_default()
+            .max("Status".len());
+        writeln!(printer.stdout())?;
+        writeln!(
+            printer.stdout(),
+            "{}",
+            format!(
+                "{:<width$}  {:<status_width$}  {:>6}  {:>9}",
+                "Hook", "Status", "Files", "Duration"
+            )
+            .bold()
+        )?;
+        for hook in &last_run.hooks {
+            writeln!(
+                printer.stdout(),
+                "{:<width$}  {:<status_width$}  {:>6}  {:>9}",
+                full_id(hook),
+                status(hook),
+                hook.files,
+                format!("{:.2}s", hook.summary.duration),
+            )?;
+        }
+    }
+
+    let summary = RunSummary::new(
+        last_run
+            .hooks
+            .iter()
+            .map(|hook| hook.summary.clone())
+            .collect(),
+        last_run.status,
+        std::time::Duration::from_secs_f64(last_run.duration),
+    );
+    writeln!(printer.stdout(), "\n{}", summary.to_footer())?;
+
+    if last_run.failed_hooks().next().is_some() {
+        writeln!(
+            printer.stdout(),
+            "\n{} Run `{}` to run the failed hooks again",
+            "hint:".bold().yellow(),
+            "prek run --retry-failed".cyan()
+        )?;
+    }
+
+    Ok(ExitStatus::Success)
+}
@@ -4,14 +4,17 @@ use std::path::PathBuf;
 use anyhow::Context;
 use clap::ValueEnum;
 use owo_colors::OwoColorize;
+use rustc_hash::FxHashSet;
 use serde::Serialize;
 
 use crate::cli::reporter::HookInitReporter;
-use crate::cli::run::Selectors;
+use crate::cli::run::{CollectOptions, FileFilter, Selectors, collect_files};
+use crate::cli::try_repo::head_rev;
 use crate::cli::{ExitStatus, ListOutputFormat};
-use crate::config::{Language, Stage};
+use crate::config::{self, Language, RemoteRepo, Stage};
 use crate::fs::CWD;
-use crate::hook;
+use crate::git;
+use crate::hook::{self, Hook};
 use crate::printer::Printer;
 use crate::store::Store;
 use crate::workspace::Workspace;
@@ -25,27 +28,55 @@ struct SerializableHook {
     language: Language,
     description: Option<String>,
     stages: Vec<Stage>,
+    /// The files the hook would run on, relative to the workspace root, with `--files`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    files: Option<Vec<PathBuf>>,
 }
 
 pub(crate) async fn list(
     store: &Store,
     config: Option<PathBuf>,
+    manifest: Option<String>,
+    rev: Option<String>,
     includes: Vec<String>,
     skips: Vec<String>,
     hook_stage: Option<Stage>,
     language: Option<Language>,
+    files: Option<CollectOptions>,
     output_format: ListOutputFormat,
     refresh: bool,
     verbose: bool,
     printer: Printer,
 ) -> anyhow::Result<ExitStatus> {
+    if let Some(repo) = manifest {
+        return list_manifest(
+            store,
+            repo,
+            rev,
+            &includes,
+            &skips,
+            hook_stage,
+            language,
+            output_format,
+            verbose,
+            printer,
+        )
+        .await;
+    }
+
     let workspace_root = Workspace::find_root(config.as_deref(), &CWD)?;
     let selectors = Selectors::load(&includes, &skips, &workspace_root)?;
-    let mut workspace =
-        Workspace::discover(store, workspace_root, config, Some(&selectors), refresh)?;
+    let mut workspace = Workspace::discover(
+        store,
+        workspace_root,
+        config,
+        Some(&selectors),
+        refresh,
+        false,
+    )?;
 
     let reporter = HookInitReporter::new(printer);
-    let lock = store.lock_async().await?;
+    let lock = store.lock_shared_async().await?;
     let hooks = workspace
         .init_hooks(store, Some(&reporter))
         .await
@@ -62,11 +93,21 @@ pub(crate) async fn list(
 
     selectors.report_unused();
 
+    let hook_files = match files {
+        Some(opts) => Some(files_for_hooks(&workspace, &filtered_hooks, opts).await?),
+        None => None,
+    };
+    let width = filtered_hooks
+        .iter()
+        .map(|hook| hook.full_id().len())
+        .max()
+        .unwrap_or_default();
+
     match output_format {
         ListOutputFormat::Text => {
             if verbose {
                 // TODO: show repo path and environment path (if installed)
-                for hook in &filtered_hooks {
+                for (i, hook) in filtered_hooks.iter().enumerate() {
                     writeln!(printer.stdout(), "{}", hook.full_id().bold())?;
 
                     writeln!(printer.stdout(), "  {} {}", "ID:".bold().cyan(), hook.id)?;
@@ -104,8 +145,33 @@ pub(crate) async fn list(
                         "Stages:".bold().cyan(),
                         hook.stages
                     )?;
+                    if let Some(hook_files) = &hook_files {
+                        writeln!(
+                            printer.stdout(),
+                            "  {} {}",
+                            "Files:".bold().cyan(),
+                            hook_files[i].len()
+                        )?;
+                        for file in &hook_files[i] {
+                            writeln!(printer.stdout(), "    {}", file.display())?;
+                        }
+                    }
                     writeln!(printer.stdout())?;
                 }
+            } else if let Some(hook_files) = &hook_files {
+                for (hook, files) in filtered_hooks.iter().zip(hook_files) {
+                    writeln!(
+                        printer.stdout(),
+                        "{:width$}  {}",
+                        hook.full_id(),
+                        format!(
+                            "{} file{}",
+                            files.len(),
+                            if files.len() == 1 { "" } else { "s" }
+                        )
+                        .dimmed()
+                    )?;
+                }
             } else {
                 // TODO: add project prefix to hook id
                 for hook in &filtered_hooks {
@@ -114,6 +180,7 @@ pub(crate) async fn list(
             }
         }
         ListOutputFormat::Json => {
+            let mut hook_files = hook_files.map(Vec::into_iter);
             let serializable_hooks: Vec<_> = filtered_hooks
                 .into_iter()
                 .map(|h| {
@@ -131,7 +198,183 @@ pub(crate) async fn list(
                         language: h.language,
                         description: h.description,
                         stages,
+                        files: hook_files.as_mut().and_then(Iterator::next),
+                    }
+                })
+                .collect();
+
+            let json_output = serde_json::to_string_pretty(&serializable_hooks)?;
+            writeln!(printer.stdout(), "{json_output}")?;
+        }
+    }
+
+    Ok(ExitStatus::Success)
+}
+
+/// Select the files each hook would run on, like `prek run` does, relative to the workspace root.
+async fn files_for_hooks(
+    workspace: &Workspace,
+    hooks: &[Hook],
+    opts: CollectOptions,
+) -> anyhow::Result<Vec<Vec<PathBuf>>> {
+    let opts = CollectOptions {
+        recurse_submodules: workspace.recurse_submodules(),
+        ..opts
+    };
+    let filenames = collect_files(workspace.root(), opts)
+        .await
+        .context("Failed to collect files")?;
+    // Files are identified relative to the workspace root.
+    std::env::set_current_dir(workspace.root()).with_context(|| {
+        format!(
+            "Failed to change directory to `{}`",
+            workspace.root().display()
+        )
+    })?;
+    let skip_worktree = if filenames.is_empty() {
+        FxHashSet::default()
+    } else {
+        git::ls_skip_worktree_files(workspace.root()).await?
+    };
+
+    let mut hook_files = vec![Vec::new(); hooks.len()];
+    // Filters must be computed in workspace order, so that nested orphan projects claim
+    // their files before their parents.
+    let mut consumed_files = FxHashSet::default();
+    for project in workspace.all_projects() {
+        let filter = FileFilter::for_project(
+            filenames.iter(),
+            project,
+            Some(&mut consumed_files),
+            Some(&skip_worktree),
+        );
+        for (i, hook) in hooks.iter().enumerate() {
+            if hook.project() != project {
+                continue;
+            }
+            let mut matched = filter.for_hook(hook);
+            if let Some(max_file_size) = filter.max_file_size(hook) {
+                filter.take_oversized(&mut matched, max_file_size);
+            }
+            matched.sort_unstable();
+            hook_files[i] = matched
+                .into_iter()
+                .map(|file| project.relative_path().join(file))
+                .collect();
+        }
+    }
+
+    Ok(hook_files)
+}
+
+/// List the hooks of a remote repository from its manifest, without a config file.
+async fn list_manifest(
+    store: &Store,
+    repo: String,
+    rev: Option<String>,
+    includes: &[String],
+    skips: &[String],
+    hook_stage: Option<Stage>,
+    language: Option<Language>,
+    output_format: ListOutputFormat,
+    verbose: bool,
+    printer: Printer,
+) -> anyhow::Result<ExitStatus> {
+    let rev = match rev {
+        Some(rev) => rev,
+        None => head_rev(&repo)
+            .await
+            .with_context(|| format!("Failed to determine the HEAD revision of `{repo}`"))?,
+    };
+
+    let reporter = HookInitReporter::new(printer);
+    let lock = store.lock_shared_async().await?;
+    let repo_path = store
+        .clone_repo(&RemoteRepo::new(repo, rev, vec![]), Some(&reporter))
+        .await?;
+    drop(lock);
+
+    let manifest = config::read_manifest(&config::manifest_path(&repo_path))?;
+    let selectors = Selectors::load(includes, skips, &CWD)?;
+
+    let filtered_hooks: Vec<_> = manifest
+        .hooks
+        .into_iter()
+        .filter(|h| selectors.matches_hook_id(&h.id))
+        .filter(|h| {
+            hook_stage.is_none_or(|hook_stage| {
+                h.options
+                    .stages
+                    .as_ref()
+                    .is_none_or(|stages| stages.contains(&hook_stage))
+            })
+        })
+        .filter(|h| language.is_none_or(|lang| h.language == lang))
+        .collect();
+
+    selectors.report_unused();
+
+    match output_format {
+        ListOutputFormat::Text => {
+            if verbose {
+                for hook in &filtered_hooks {
+                    writeln!(printer.stdout(), "{}", hook.id.bold())?;
+                    writeln!(
+                        printer.stdout(),
+                        "  {} {}",
+                        "Name:".bold().cyan(),
+                        hook.name
+                    )?;
+                    if let Some(description) = &hook.options.description {
+                        writeln!(
+                            printer.stdout(),
+                            "  {} {}",
+                            "Description:".bold().cyan(),
+                            description
+                        )?;
+                    }
+                    writeln!(
+                        printer.stdout(),
+                        "  {} {}",
+                        "Language:".bold().cyan(),
+                        hook.language.as_str()
+                    )?;
+                    writeln!(printer.stdout())?;
+                }
+            } else {
+                let width = filtered_hooks
+                    .iter()
+                    .map(|h| h.id.len())
+                    .max()
+                    .unwrap_or_default();
+                for hook in &filtered_hooks {
+                    match &hook.options.description {
+                        Some(description) => writeln!(
+                            printer.stdout(),
+                            "{:width$}  {}",
+                            hook.id,
+                            description.dimmed()
+                        )?,
+                        None => writeln!(printer.stdout(), "{}", hook.id)?,
                     }
+                }
+            }
+        }
+        ListOutputFormat::Json => {
+            let serializable_hooks: Vec<_> = filtered_hooks
+                .into_iter()
+                .map(|h| SerializableHook {
+                    full_id: h.id.clone(),
+                    alias: h.options.alias.unwrap_or_default(),
+                    stages: h
+                        .options
+                        .stages
+                        .unwrap_or_else(|| Stage::value_variants().to_vec()),
+                    id: h.id,
+                    name: h.name,
+                    language: h.language,
+                    description: h.options.description,
+                    files: None,
                 })
                 .collect();
 
@@ -3,7 +3,7 @@ use std::path::PathBuf;
 use std::process::ExitCode;
 
 use clap::builder::styling::{AnsiColor, Effects};
-use clap::builder::{ArgPredicate, Styles};
+use clap::builder::{ArgPredicate, BoolishValueParser, Styles};
 use clap::{ArgAction, Args, Parser, Subcommand, ValueHint};
 use clap_complete::engine::ArgValueCompleter;
 use serde::{Deserialize, Serialize};
@@ -11,36 +11,70 @@ use serde::{Deserialize, Serialize};
 use prek_consts::CONFIG_FILE;
 use prek_consts::env_vars::EnvVars;
 
-use crate::config::{HookType, Language, Stage};
+use crate::cli::run::PushRange;
+use crate::cli::run::events::ExternalReporter;
+use crate::config::{FailFast, HookKind, HookType, Language, Stage};
+use crate::retry::DEFAULT_RETRIES;
+use crate::warnings::WarningLevel;
 
+mod audit;
 mod auto_update;
+mod bisect;
 mod cache_clean;
+mod cache_export;
+mod cache_gc;
+mod cache_images;
+mod cache_import;
+mod cache_logs;
 mod cache_size;
 mod completion;
+mod exec;
 mod hook_impl;
+mod info;
 mod install;
+mod last;
 mod list;
+mod render;
 pub mod reporter;
 pub mod run;
 mod sample_config;
 #[cfg(feature = "self-update")]
 mod self_update;
+mod serve;
+mod skip;
+mod stats;
 mod try_repo;
 mod validate;
+mod verify;
 
+pub(crate) use audit::audit;
 pub(crate) use auto_update::auto_update;
+pub(crate) use bisect::bisect;
 pub(crate) use cache_clean::cache_clean;
+pub(crate) use cache_export::cache_export;
+pub(crate) use cache_gc::cache_gc;
+pub(crate) use cache_images::cache_images;
+pub(crate) use cache_import::cache_import;
+pub(crate) use cache_logs::cache_logs;
 pub(crate) use cache_size::cache_size;
 use completion::selector_completer;
+pub(crate) use exec::exec;
 pub(crate) use hook_impl::hook_impl;
-pub(crate) use install::{init_template_dir, install, install_hooks, uninstall};
+pub(crate) use info::info;
+pub(crate) use install::{init_template_dir, install, install_hooks, install_recursive, uninstall};
+pub(crate) use last::last;
 pub(crate) use list::list;
-pub(crate) use run::run;
+pub(crate) use render::render;
+pub(crate) use run::{run, run_stdin};
 pub(crate) use sample_config::sample_config;
 #[cfg(feature = "self-update")]
 pub(crate) use self_update::self_update;
+pub(crate) use serve::serve;
+pub(crate) use skip::{skip_add, skip_list, skip_remove};
+pub(crate) use stats::stats;
 pub(crate) use try_repo::try_repo;
 pub(crate) use validate::{validate_configs, validate_manifest};
+pub(crate) use verify::verify;
 
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub(crate) enum ExitStatus {
@@ -72,9 +106,11 @@ impl From<ExitStatus> for ExitCode {
     }
 }
 
-#[derive(Debug, Copy, Clone, clap::ValueEnum)]
+#[derive(Debug, Default, Copy, Clone, clap::ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ColorChoice {
     /// Enables colored output only when the output is going to a terminal or TTY with support.
+    #[default]
     Auto,
 
     /// Enables colored output regardless of the detected environment.
@@ -144,18 +180,44 @@ pub(crate) struct GlobalArgs {
     pub(crate) cd: Option<PathBuf>,
 
     /// Whether to use color in output.
+    ///
+    /// Defaults to `auto`. With `always`, hooks are run with `FORCE_COLOR`, `CLICOLOR_FORCE` and
+    /// `PY_COLORS` set; with `never`, colors are stripped from the output of hooks.
+    #[arg(global = true, long, value_enum, env = EnvVars::PREK_COLOR)]
+    pub(crate) color: Option<ColorChoice>,
+
+    /// Refresh all cached data.
+    #[arg(global = true, long)]
+    pub(crate) refresh: bool,
+
+    /// How to treat prek's own warnings, such as unexpected config keys or mutable revs.
+    ///
+    /// Takes precedence over the `warnings` key in the config file. Defaults to `warn`.
+    #[arg(global = true, long, value_enum, env = EnvVars::PREK_WARNINGS)]
+    pub(crate) warnings: Option<WarningLevel>,
+
+    /// The number of times to retry network operations, such as cloning hook repositories
+    /// or downloading toolchains, after a transient failure.
     #[arg(
         global = true,
         long,
-        value_enum,
-        env = EnvVars::PREK_COLOR,
-        default_value_t = ColorChoice::Auto,
+        value_name = "N",
+        env = EnvVars::PREK_HTTP_RETRIES,
+        default_value_t = DEFAULT_RETRIES,
     )]
-    pub(crate) color: ColorChoice,
+    pub(crate) retries: u32,
 
-    /// Refresh all cached data.
-    #[arg(global = true, long)]
-    pub(crate) refresh: bool,
+    /// Disable network access.
+    ///
+    /// Hook repositories and toolchains that are not already cached result in an error
+    /// instead of being cloned or downloaded.
+    #[arg(
+        global = true,
+        long,
+        env = EnvVars::PREK_OFFLINE,
+        value_parser = BoolishValueParser::new(),
+    )]
+    pub(crate) offline: bool,
 
     /// Display the concise help for this command.
     #[arg(global = true, short, long, action = ArgAction::HelpShort)]
@@ -208,14 +270,49 @@ pub(crate) enum Command {
     InstallHooks(InstallHooksArgs),
     /// Run hooks.
     Run(Box<RunArgs>),
+    /// Run a single hook directly on the given files.
+    ///
+    /// The hook environment is installed if needed. The files are passed to the hook as given,
+    /// without a git repository, staging or the hook's `files`, `exclude` and `types` filters.
+    Exec(ExecArgs),
+    /// Find the smallest set of files a hook fails on.
+    ///
+    /// The hook is run on halves of its files, reusing its environment and batching, and the
+    /// half it still fails on is split again until the files that make it fail are found.
+    /// Files the hook modifies are restored after each run.
+    Bisect(BisectArgs),
+    /// Run the hooks on each commit of a revision range, reporting the commits that fail them.
+    ///
+    /// Each commit is checked out in a temporary worktree and the hooks run on the files it
+    /// changed, with the config of that commit. With `--combined`, the hooks run once on the
+    /// files changed by the whole range instead. Merge commits are not checked.
+    Verify(VerifyArgs),
+    /// Serve hook checks to editors over stdio.
+    ///
+    /// Speaks JSON-RPC with Language Server Protocol framing. The hooks are installed once at startup,
+    /// then each `prek/check` request with `{"files": [...]}` runs the matching hooks on the files
+    /// and responds with the outcome of each hook. Restart the server after changing the config.
+    Serve(ServeArgs),
     /// List available hooks.
     List(ListArgs),
+    /// Show the details of a hook.
+    ///
+    /// Shows the repository and revision the hook comes from, its final options and whether each
+    /// of them is set by the manifest of its repository, the config file or a default, the command
+    /// it runs, and its environment.
+    Info(InfoArgs),
     /// Uninstall the prek git hook.
     Uninstall(UninstallArgs),
     /// Validate `.pre-commit-config.yaml` files.
     ValidateConfig(ValidateConfigArgs),
-    /// Validate `.pre-commit-hooks.yaml` files.
+    /// Validate `.pre-commit-hooks.yaml` and `prek-hooks.toml` files.
     ValidateManifest(ValidateManifestArgs),
+    /// Print the config of the current project, with `extends` resolved.
+    ///
+    /// The configs listed in `extends` are merged under the config: repos with the same `repo`
+    /// and `rev` are merged, their hooks are merged by `id`, and the keys of the config override
+    /// the ones of the configs it extends.
+    Render,
     /// Produce a sample `.pre-commit-config.yaml` file.
     SampleConfig(SampleConfigArgs),
     /// Auto-update pre-commit config to the latest repos' versions.
@@ -223,6 +320,30 @@ pub(crate) enum Command {
     AutoUpdate(AutoUpdateArgs),
     /// Manage the prek cache.
     Cache(CacheNamespace),
+    /// Show local statistics of hook durations and outcomes.
+    ///
+    /// Hook runs are only recorded when `PREK_STATS=1` is set. The history is kept in the prek
+    /// cache directory and never leaves the machine.
+    Stats(StatsArgs),
+    /// Show the summary of the last run.
+    ///
+    /// Every run that executes hooks writes its selectors, file counts, the outcome and
+    /// duration of each hook, and the files failed hooks failed on to `$PREK_HOME/last-run.json`.
+    /// A hook failed on the files it mentions in its output, or else on all the files it ran on.
+    /// Use `prek run --retry-failed` to run the failed hooks again on those files.
+    Last(LastArgs),
+    /// Manage the hooks skipped in the local repository.
+    ///
+    /// The skip selectors are stored in `.git/prek/skips`, which is not committed, and apply to
+    /// every run in the repository in addition to `--skip` and `SKIP`. Hooks they skip are
+    /// listed as `(skipped locally)` in the output of `prek run`.
+    Skip(SkipNamespace),
+    /// Show the commits that skipped the `pre-commit` hook.
+    ///
+    /// Once the `pre-commit` hook passed in the repository, every hook run checks the new commits,
+    /// and commits made with `git commit --no-verify` or with hooks otherwise bypassed are logged to
+    /// `.git/prek/audit.log`. Only commits made locally by the configured `user.email` are checked.
+    Audit(AuditArgs),
     /// Clean unused cached repos.
     #[command(hide = true)]
     GC,
@@ -257,7 +378,10 @@ pub(crate) struct InstallArgs {
     ///
     /// - `project-path:hook-id`: Run only the specified hook from the specified project
     ///
-    /// Can be specified multiple times to select multiple hooks/projects.
+    /// - `language:name`: Run all hooks of the specified language
+    ///
+    /// Hook IDs can be glob patterns like `lint-*`, and a selector prefixed with `!` excludes
+    /// what it matches instead. Can be specified multiple times to select multiple hooks/projects.
     #[arg(
         value_name = "HOOK|PROJECT",
         value_hint = ValueHint::Other,
@@ -292,17 +416,36 @@ pub(crate) struct InstallArgs {
     /// Specifies which git hook stage(s) you want to install the hook script for.
     /// Can be specified multiple times to install hooks for multiple stages.
     ///
-    /// If not specified, uses `default_install_hook_types` from the config file,
-    /// or defaults to `pre-commit` if that is also not set.
+    /// If not specified, uses `PREK_HOOK_TYPES` (comma-separated), then `default_install_hook_types`
+    /// from the config file, or defaults to `pre-commit` if neither is set.
     ///
     /// Note: This is different from a hook's `stages` parameter in the config file,
     /// which declares which stages a hook *can* run in.
-    #[arg(short = 't', long = "hook-type", value_name = "HOOK_TYPE", value_enum)]
+    #[arg(
+        short = 't',
+        long = "hook-type",
+        value_name = "HOOK_TYPE",
+        value_enum,
+        value_delimiter = ',',
+        env = EnvVars::PREK_HOOK_TYPES
+    )]
     pub(crate) hook_types: Vec<HookType>,
 
     /// Allow a missing `pre-commit` configuration file.
     #[arg(long)]
     pub(crate) allow_missing_config: bool,
+
+    /// Install hooks into every git repository under the directory that has a config file.
+    ///
+    /// The directory is searched recursively, skipping paths ignored by `.gitignore`.
+    /// `prek install` runs in each repository found, and a summary of the results is printed.
+    #[arg(
+        long,
+        value_name = "DIR",
+        value_hint = ValueHint::DirPath,
+        conflicts_with_all = ["includes", "skips", "allow_missing_config"]
+    )]
+    pub(crate) recursive: Option<PathBuf>,
 }
 
 #[derive(Debug, Args)]
@@ -317,7 +460,10 @@ pub(crate) struct InstallHooksArgs {
     ///
     /// - `project-path:hook-id`: Run only the specified hook from the specified project
     ///
-    /// Can be specified multiple times to select multiple hooks/projects.
+    /// - `language:name`: Run all hooks of the specified language
+    ///
+    /// Hook IDs can be glob patterns like `lint-*`, and a selector prefixed with `!` excludes
+    /// what it matches instead. Can be specified multiple times to select multiple hooks/projects.
     #[arg(
         value_name = "HOOK|PROJECT",
         value_hint = ValueHint::Other,
@@ -338,6 +484,14 @@ pub(crate) struct InstallHooksArgs {
     /// Can be specified multiple times. Also accepts `PREK_SKIP` or `SKIP` environment variables (comma-delimited).
     #[arg(long = "skip", value_name = "HOOK|PROJECT", add = ArgValueCompleter::new(selector_completer))]
     pub(crate) skips: Vec<String>,
+
+    /// Rebuild the environments of all selected hooks, even if they are up-to-date.
+    #[arg(long)]
+    pub(crate) force: bool,
+
+    /// Print which hook environments are up-to-date and which would be built, without building them.
+    #[arg(long)]
+    pub(crate) dry_run: bool,
 }
 
 #[derive(Debug, Args)]
@@ -347,9 +501,16 @@ pub(crate) struct UninstallArgs {
     /// Specifies which git hook stage(s) you want to uninstall.
     /// Can be specified multiple times to uninstall hooks for multiple stages.
     ///
-    /// If not specified, uses `default_install_hook_types` from the config file,
-    /// or defaults to `pre-commit` if that is also not set.
-    #[arg(short = 't', long = "hook-type", value_name = "HOOK_TYPE", value_enum)]
+    /// If not specified, uses `PREK_HOOK_TYPES` (comma-separated), then `default_install_hook_types`
+    /// from the config file, or defaults to `pre-commit` if neither is set.
+    #[arg(
+        short = 't',
+        long = "hook-type",
+        value_name = "HOOK_TYPE",
+        value_enum,
+        value_delimiter = ',',
+        env = EnvVars::PREK_HOOK_TYPES
+    )]
     pub(crate) hook_types: Vec<HookType>,
 }
 
@@ -379,6 +540,15 @@ pub(crate) struct RunExtraArgs {
     pub(crate) is_squash_merge: bool,
     #[arg(long, hide = true)]
     pub(crate) rewrite_command: Option<String>,
+    #[arg(long, hide = true)]
+    pub(crate) is_workdir_updated: bool,
+    #[arg(long, hide = true)]
+    pub(crate) is_skip_worktree_changed: bool,
+    #[arg(long, hide = true)]
+    pub(crate) reference_transaction_state: Option<String>,
+    /// The commit ranges being pushed, set by the `pre-push` hook.
+    #[arg(skip)]
+    pub(crate) push_ranges: Vec<PushRange>,
 }
 
 #[allow(clippy::struct_excessive_bools)]
@@ -394,7 +564,10 @@ pub(crate) struct RunArgs {
     ///
     /// - `project-path:hook-id`: Run only the specified hook from the specified project
     ///
-    /// Can be specified multiple times to select multiple hooks/projects.
+    /// - `language:name`: Run all hooks of the specified language
+    ///
+    /// Hook IDs can be glob patterns like `lint-*`, and a selector prefixed with `!` excludes
+    /// what it matches instead. Can be specified multiple times to select multiple hooks/projects.
     #[arg(
         value_name = "HOOK|PROJECT",
         value_hint = ValueHint::Other,
@@ -416,6 +589,10 @@ pub(crate) struct RunArgs {
     #[arg(long = "skip", value_name = "HOOK|PROJECT", add = ArgValueCompleter::new(selector_completer))]
     pub(crate) skips: Vec<String>,
 
+    /// Also run the hooks skipped by the `default_skips` of the config.
+    #[arg(long)]
+    pub(crate) no_default_skips: bool,
+
     /// Run on all files in the repo.
     #[arg(short, long, conflicts_with_all = ["files", "from_ref", "to_ref"])]
     pub(crate) all_files: bool,
@@ -440,6 +617,28 @@ pub(crate) struct RunArgs {
     )]
     pub(crate) directory: Vec<String>,
 
+    /// Run hooks on untracked files passed with `--files` or found in `--directory`.
+    ///
+    /// By default, untracked files passed with `--files` are skipped with a warning, and untracked
+    /// files in `--directory` are skipped silently.
+    #[arg(long, conflicts_with_all = ["all_files", "from_ref", "to_ref", "last_commit", "changed_only"])]
+    pub(crate) include_untracked: bool,
+
+    /// Run hooks on files ignored by git that are passed with `--files` or found in `--directory`.
+    ///
+    /// By default, ignored files passed with `--files` are skipped with a warning, and ignored
+    /// files in `--directory` are skipped silently.
+    #[arg(long, conflicts_with_all = ["all_files", "from_ref", "to_ref", "last_commit", "changed_only"])]
+    pub(crate) include_ignored: bool,
+
+    /// Discover the configs of initialized git submodules as projects, and run hooks on the files
+    /// of the submodules.
+    ///
+    /// Only the all-files and staged-files modes include the files of submodules.
+    /// Equivalent to `recurse_submodules: true` in the config.
+    #[arg(long)]
+    pub(crate) recurse_submodules: bool,
+
     /// The original ref in a `<from_ref>...<to_ref>` diff expression.
     /// Files changed in this diff will be run through the hooks.
     #[arg(short = 's', long, alias = "source", value_hint = ValueHint::Other)]
@@ -461,6 +660,45 @@ pub(crate) struct RunArgs {
     #[arg(long, conflicts_with_all = ["all_files", "files", "directory", "from_ref", "to_ref"])]
     pub(crate) last_commit: bool,
 
+    /// Run hooks on the files changed since the branch the current changes will be merged into.
+    ///
+    /// The base branch is detected from `GITHUB_BASE_REF` in GitHub Actions,
+    /// `CI_MERGE_REQUEST_TARGET_BRANCH_NAME` in GitLab CI, `BITBUCKET_PR_DESTINATION_BRANCH` in
+    /// Bitbucket Pipelines, or else the upstream tracking branch.
+    /// It is fetched if missing, and shallow clones are deepened until the merge base is found.
+    #[arg(
+        long,
+        conflicts_with_all = ["all_files", "files", "directory", "from_ref", "to_ref", "last_commit"]
+    )]
+    pub(crate) changed_only: bool,
+
+    /// Run the hooks that failed in the last run again, on the files they failed on.
+    ///
+    /// The last run is read from `$PREK_HOME/last-run.json`, see `prek last`. It must have run
+    /// in the same workspace. Its stage is used unless `--hook-stage` is given.
+    #[arg(
+        long,
+        conflicts_with_all = ["all_files", "files", "directory", "from_ref", "to_ref", "last_commit", "changed_only"]
+    )]
+    pub(crate) retry_failed: bool,
+
+    /// Run the hooks on content read from stdin, as if it were the file given by `--stdin-filename`.
+    ///
+    /// The content is written to a temporary file next to that file, and the content after the
+    /// hooks ran is printed to stdout, while the output of the hooks goes to stderr.
+    /// Hooks that fail after modifying the content count as having fixed it.
+    /// Useful for editors to format unsaved buffers.
+    #[arg(
+        long,
+        requires = "stdin_filename",
+        conflicts_with_all = ["all_files", "files", "directory", "from_ref", "to_ref", "last_commit", "changed_only", "retry_failed", "dry_run", "write_summary", "external_reporter", "modified_exit_code", "autofix_stage"]
+    )]
+    pub(crate) stdin: bool,
+
+    /// The path of the file whose content is read with `--stdin`.
+    #[arg(long, value_name = "PATH", requires = "stdin", value_hint = ValueHint::FilePath)]
+    pub(crate) stdin_filename: Option<PathBuf>,
+
     /// The stage during which the hook is fired.
     ///
     /// When specified, only hooks configured for that stage (for example `manual`,
@@ -470,22 +708,130 @@ pub(crate) struct RunArgs {
     #[arg(long, value_enum)]
     pub(crate) hook_stage: Option<Stage>,
 
+    /// Only run hooks that fix files, i.e. hooks with `kind: fixer`.
+    ///
+    /// Useful to apply all autofixes first, before running the checkers with `--only-checkers`.
+    #[arg(long, conflicts_with = "only_checkers")]
+    pub(crate) only_fixers: bool,
+
+    /// Only run hooks that check files without modifying them, i.e. hooks with `kind: checker`.
+    #[arg(long)]
+    pub(crate) only_checkers: bool,
+
     /// When hooks fail, run `git diff` directly afterward.
     #[arg(long)]
     pub(crate) show_diff_on_failure: bool,
 
+    /// The number of context lines to show around changes in the diff printed by
+    /// `--show-diff-on-failure`.
+    ///
+    /// Defaults to git's `diff.context` setting, or 3 lines.
+    #[arg(long, value_name = "N", requires = "show_diff_on_failure")]
+    pub(crate) diff_context: Option<u32>,
+
+    /// Highlight the changed words instead of whole lines in the diff printed by
+    /// `--show-diff-on-failure`.
+    #[arg(long, requires = "show_diff_on_failure")]
+    pub(crate) word_diff: bool,
+
+    /// After the run, list the files modified by each hook with the lines added and removed.
+    ///
+    /// Unlike `--show-diff-on-failure`, only shows which files to review and stage again.
+    #[arg(long)]
+    pub(crate) show_fixes: bool,
+
+    /// Skip files larger than the given number of bytes instead of passing them to hooks.
+    ///
+    /// Takes precedence over `max_file_size` in the configuration, but not over the
+    /// `max_file_size` of a hook. `0` disables the limit.
+    #[arg(long, value_name = "BYTES")]
+    pub(crate) max_file_size: Option<u64>,
+
     /// Stop running hooks after the first failure.
+    ///
+    /// `--fail-fast` stops the whole run, `--fail-fast=project` only skips the remaining hooks
+    /// of the project of the failing hook, and `--fail-fast=group` only cancels the running hooks
+    /// of its priority group.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "SCOPE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "workspace"
+    )]
+    pub(crate) fail_fast: Option<FailFast>,
+
+    /// Exit with this code when the only failures are hooks that modified files.
+    ///
+    /// Hooks that fail without modifying files, or that have `kind: checker`, still make prek
+    /// exit with 1. Useful for scripts that stage the fixes and run the hooks again.
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u8).range(2..))]
+    pub(crate) modified_exit_code: Option<u8>,
+
+    /// Stage the files modified by hooks and run the other hooks again on them, when the only
+    /// failures are hooks that modified files.
+    ///
+    /// Only the changes made by hooks are staged: files that had unstaged changes before the
+    /// run are left alone, with a warning. Hooks with `kind: fixer` are not run again.
+    #[arg(
+        long,
+        conflicts_with_all = ["all_files", "files", "directory", "from_ref", "to_ref", "last_commit", "changed_only", "retry_failed", "dry_run"]
+    )]
+    pub(crate) autofix_stage: bool,
+
+    /// Run the hooks of workspace projects one project at a time.
+    ///
+    /// By default, projects that are not nested in each other run concurrently, and their
+    /// output is buffered and printed per project.
     #[arg(long)]
-    pub(crate) fail_fast: bool,
+    pub(crate) serial_projects: bool,
 
     /// Do not run the hooks, but print the hooks that would have been run.
+    ///
+    /// With `--verbose`, also print for each hook the command line it would run, the
+    /// environment it would run in, the environment variables set for it and the batches of
+    /// files it would be run on.
     #[arg(long)]
     pub(crate) dry_run: bool,
 
+    /// Write a summary of the run (status, hook counts, durations and prek version) to a file.
+    ///
+    /// The summary is written as Markdown if the file has a `.md` extension, and as JSON otherwise.
+    /// Useful for publishing a check summary or badge from CI.
+    #[arg(long, value_name = "PATH", value_hint = ValueHint::FilePath)]
+    pub(crate) write_summary: Option<PathBuf>,
+
+    /// Stream the events of the run to an external reporter.
+    ///
+    /// With `exec:<command>`, prek spawns the command and writes newline-delimited JSON events
+    /// to its stdin as the run progresses: `run-started`, `hook-started`, `hook-finished` and
+    /// `run-finished` with the same summary as `--write-summary`.
+    #[arg(long = "reporter", value_name = "exec:COMMAND")]
+    pub(crate) external_reporter: Option<ExternalReporter>,
+
+    /// Install the environment of the selected hook into this directory instead of the cache,
+    /// and reuse it from there, to inspect it while debugging the hook.
+    #[arg(long, value_name = "PATH", value_hint = ValueHint::DirPath, hide = true)]
+    pub(crate) env_dir: Option<PathBuf>,
+
     #[command(flatten)]
     pub(crate) extra: RunExtraArgs,
 }
 
+impl RunArgs {
+    /// The kind of hooks to run, if restricted by `--only-fixers` or `--only-checkers`.
+    pub(crate) fn hook_kind(&self) -> Option<HookKind> {
+        if self.only_fixers {
+            Some(HookKind::Fixer)
+        } else if self.only_checkers {
+            Some(HookKind::Checker)
+        } else {
+            None
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default, Args)]
 pub(crate) struct TryRepoArgs {
     /// Repository to source hooks from.
@@ -507,6 +853,99 @@ pub(crate) enum ListOutputFormat {
     Json,
 }
 
+#[derive(Debug, Args)]
+pub(crate) struct ExecArgs {
+    /// The id or alias of the hook to run.
+    #[arg(value_name = "HOOK", value_hint = ValueHint::Other)]
+    pub(crate) hook: String,
+    /// The files to run the hook on.
+    #[arg(last = true, value_name = "FILES", value_hint = ValueHint::AnyPath)]
+    pub(crate) files: Vec<PathBuf>,
+    /// Install the environment of the hook into this directory instead of the cache, and reuse
+    /// it from there, to inspect it while debugging the hook.
+    #[arg(long, value_name = "PATH", value_hint = ValueHint::DirPath, hide = true)]
+    pub(crate) env_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct InfoArgs {
+    /// The hook to show, `hook-id` or `project-path:hook-id`.
+    #[arg(
+        value_name = "HOOK",
+        value_hint = ValueHint::Other,
+        add = ArgValueCompleter::new(selector_completer)
+    )]
+    pub(crate) hook: String,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct BisectArgs {
+    /// The hook to bisect, `hook-id` or `project-path:hook-id`.
+    #[arg(
+        value_name = "HOOK",
+        value_hint = ValueHint::Other,
+        add = ArgValueCompleter::new(selector_completer)
+    )]
+    pub(crate) hook: String,
+    /// Bisect the files changed in a `<from_ref>...<to_ref>` diff instead of all files.
+    #[arg(short = 's', long, alias = "source", value_hint = ValueHint::Other)]
+    pub(crate) from_ref: Option<String>,
+    /// The destination ref in a `from_ref...to_ref` diff expression.
+    /// Defaults to `HEAD` if `from_ref` is specified.
+    #[arg(
+        short = 'o',
+        long,
+        alias = "origin",
+        requires = "from_ref",
+        value_hint = ValueHint::Other,
+        default_value_if("from_ref", ArgPredicate::IsPresent, "HEAD")
+    )]
+    pub(crate) to_ref: Option<String>,
+    /// Bisect the specified files instead of all files.
+    #[arg(
+        long,
+        conflicts_with_all = ["from_ref", "to_ref"],
+        num_args = 0..,
+        value_hint = ValueHint::AnyPath)
+    ]
+    pub(crate) files: Vec<String>,
+    /// The stage of the hook to bisect.
+    #[arg(long, value_enum)]
+    pub(crate) hook_stage: Option<Stage>,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct VerifyArgs {
+    /// The revision range to verify, like `main..HEAD`. The end defaults to `HEAD`.
+    #[arg(value_name = "FROM..TO", value_hint = ValueHint::Other)]
+    pub(crate) range: String,
+    /// Include the specified hooks or projects, with the selector syntax of `prek run`.
+    #[arg(
+        value_name = "HOOK|PROJECT",
+        value_hint = ValueHint::Other,
+        add = ArgValueCompleter::new(selector_completer)
+    )]
+    pub(crate) includes: Vec<String>,
+    /// Skip the specified hooks or projects, with the selector syntax of `prek run`.
+    #[arg(long = "skip", value_name = "HOOK|PROJECT", add = ArgValueCompleter::new(selector_completer))]
+    pub(crate) skips: Vec<String>,
+    /// Run the hooks once on the files changed by the whole range instead of on each commit.
+    #[arg(long)]
+    pub(crate) combined: bool,
+    /// The stage of the hooks to run.
+    #[arg(long, value_enum)]
+    pub(crate) hook_stage: Option<Stage>,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct ServeArgs {
+    /// Also check files when the editor saves them, publishing a diagnostic for each failed hook.
+    ///
+    /// Handles `textDocument/didSave` notifications, as sent by editors to language servers.
+    #[arg(long)]
+    pub(crate) check_on_save: bool,
+}
+
 #[derive(Debug, Clone, Default, Args)]
 pub(crate) struct ListArgs {
     /// Include the specified hooks or projects.
@@ -519,7 +958,10 @@ pub(crate) struct ListArgs {
     ///
     /// - `project-path:hook-id`: Run only the specified hook from the specified project
     ///
-    /// Can be specified multiple times to select multiple hooks/projects.
+    /// - `language:name`: Run all hooks of the specified language
+    ///
+    /// Hook IDs can be glob patterns like `lint-*`, and a selector prefixed with `!` excludes
+    /// what it matches instead. Can be specified multiple times to select multiple hooks/projects.
     #[arg(
         value_name = "HOOK|PROJECT",
         value_hint = ValueHint::Other,
@@ -550,6 +992,41 @@ pub(crate) struct ListArgs {
     /// The output format.
     #[arg(long, value_enum, default_value_t = ListOutputFormat::Text)]
     pub(crate) output_format: ListOutputFormat,
+    /// List the hooks provided by a hook repository instead of the hooks in the config.
+    ///
+    /// The repository can be a remote URL or a local path. It's cloned into the cache
+    /// (or the cached copy is reused), and the hooks are read from its `prek-hooks.toml` or
+    /// `.pre-commit-hooks.yaml`.
+    #[arg(long, value_name = "REPO", value_hint = ValueHint::Url)]
+    pub(crate) manifest: Option<String>,
+    /// The revision of the `--manifest` repository to list hooks from. Defaults to `HEAD`.
+    #[arg(long, value_name = "REV", requires = "manifest")]
+    pub(crate) rev: Option<String>,
+    /// Show the files each hook would run on, without running anything.
+    ///
+    /// Files are selected like `prek run` does: the staged files by default, or the ones chosen
+    /// by `--all-files` or `--from-ref`, filtered by the `files`, `exclude` and `types` of each
+    /// hook. Prints the number of files of each hook, and the files themselves with `--verbose`
+    /// or `--output-format json`.
+    #[arg(long, conflicts_with = "manifest")]
+    pub(crate) files: bool,
+    /// With `--files`, select from all files in the repo.
+    #[arg(short, long, requires = "files", conflicts_with_all = ["from_ref", "to_ref"])]
+    pub(crate) all_files: bool,
+    /// With `--files`, select from the files changed in a `<from_ref>...<to_ref>` diff.
+    #[arg(short = 's', long, alias = "source", requires = "files", value_hint = ValueHint::Other)]
+    pub(crate) from_ref: Option<String>,
+    /// The destination ref in a `from_ref...to_ref` diff expression.
+    /// Defaults to `HEAD` if `from_ref` is specified.
+    #[arg(
+        short = 'o',
+        long,
+        alias = "origin",
+        requires = "from_ref",
+        value_hint = ValueHint::Other,
+        default_value_if("from_ref", ArgPredicate::IsPresent, "HEAD")
+    )]
+    pub(crate) to_ref: Option<String>,
 }
 
 #[derive(Debug, Args)]
@@ -557,6 +1034,10 @@ pub(crate) struct ValidateConfigArgs {
     /// The path to the configuration file.
     #[arg(value_name = "CONFIG")]
     pub(crate) configs: Vec<PathBuf>,
+    /// Fail on unexpected keys, mutable revs, deprecated stage names and invalid file patterns,
+    /// reporting their line and column, instead of only warning about them.
+    #[arg(long)]
+    pub(crate) strict: bool,
 }
 
 #[derive(Debug, Args)]
@@ -579,6 +1060,7 @@ pub(crate) struct SampleConfigArgs {
 }
 
 #[derive(Debug, Args)]
+#[allow(clippy::struct_excessive_bools)]
 pub(crate) struct AutoUpdateArgs {
     /// Update to the bleeding edge of the default branch instead of the latest tagged version.
     #[arg(long)]
@@ -606,6 +1088,15 @@ pub(crate) struct AutoUpdateArgs {
         conflicts_with = "bleeding_edge"
     )]
     pub(crate) cooldown_days: u8,
+    /// Do not show the release notes of the updated repositories.
+    ///
+    /// By default, the messages of annotated tags are shown for each updated repository, or the
+    /// GitHub release notes when the repository is on GitHub and a token is available.
+    #[arg(long)]
+    pub(crate) no_release_notes: bool,
+    /// A GitHub token used to fetch the release notes of repositories hosted on GitHub.
+    #[arg(long, env = "GITHUB_TOKEN", hide_env_values = true)]
+    pub(crate) github_token: Option<String>,
 }
 
 #[derive(Debug, Args)]
@@ -620,7 +1111,10 @@ pub(crate) struct HookImplArgs {
     ///
     /// - `project-path:hook-id`: Run only the specified hook from the specified project
     ///
-    /// Can be specified multiple times to select multiple hooks/projects.
+    /// - `language:name`: Run all hooks of the specified language
+    ///
+    /// Hook IDs can be glob patterns like `lint-*`, and a selector prefixed with `!` excludes
+    /// what it matches instead. Can be specified multiple times to select multiple hooks/projects.
     #[arg(
         value_name = "HOOK|PROJECT",
         value_hint = ValueHint::Other,
@@ -665,11 +1159,92 @@ pub(crate) enum CacheCommand {
     /// Show the location of the prek cache.
     Dir,
     /// Remove unused cached repositories, hook environments, and other data.
-    GC,
+    ///
+    /// Runs that install hooks record the cached repositories and hook environments each local
+    /// repository uses. The ones no existing local repository uses are removed.
+    GC(GcArgs),
     /// Remove all prek cached data.
     Clean,
     /// Show the size of the prek cache.
     Size(SizeArgs),
+    /// Package cached repositories, hook environments and toolchains into an archive.
+    ///
+    /// The archive can be imported with `prek cache import` on another machine, for example to
+    /// bootstrap hook environments on machines without network access.
+    Export(ExportArgs),
+    /// Unpack an archive created by `prek cache export` into the cache.
+    ///
+    /// Paths pointing into the cache the archive was exported from, such as shebangs and
+    /// symlinks in hook environments, are rewritten to point into this cache.
+    /// Entries that already exist in the cache are kept.
+    Import(ImportArgs),
+    /// Show the log of the latest environment build of a hook.
+    ///
+    /// The output of the commands run while installing a hook environment, such as `pip`, `npm`,
+    /// `cargo` or `go`, is written to `$PREK_HOME/logs`.
+    Logs(LogsArgs),
+    /// List the images built for `docker` hooks, which are kept by the container runtime
+    /// rather than in the prek cache.
+    ///
+    /// Images are rebuilt only when the build context of the hook repository changes.
+    /// Images no installed hook environment uses are marked as unused.
+    Images(ImagesArgs),
+}
+
+/// A category of cached data that can be exported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum CacheCategory {
+    /// Cloned hook repositories.
+    Repos,
+    /// Hook environments.
+    HookEnvs,
+    /// Toolchains downloaded by prek, such as Python, Node.js or uv.
+    Toolchains,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct ExportArgs {
+    /// The archive to write, a `.tar` or `.tar.gz` file.
+    #[arg(value_hint = ValueHint::FilePath)]
+    pub(crate) output: PathBuf,
+    /// Only export the specified categories. Defaults to all of them.
+    #[arg(long = "only", value_enum, value_name = "CATEGORY")]
+    pub(crate) categories: Vec<CacheCategory>,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct GcArgs {
+    /// Show what would be removed and how much space it would reclaim, without removing anything.
+    #[arg(long)]
+    pub(crate) dry_run: bool,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct ImagesArgs {
+    /// Remove the images no installed hook environment uses.
+    #[arg(long)]
+    pub(crate) prune: bool,
+    /// With `--prune`, remove all images built by prek, including the ones in use.
+    #[arg(long, requires = "prune")]
+    pub(crate) all: bool,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct ImportArgs {
+    /// The archive to import, created by `prek cache export`.
+    #[arg(value_hint = ValueHint::FilePath)]
+    pub(crate) archive: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct LogsArgs {
+    /// The hook to show the build log of, `hook-id` or `project-path:hook-id`.
+    #[arg(
+        value_name = "HOOK",
+        value_hint = ValueHint::Other,
+        add = ArgValueCompleter::new(selector_completer)
+    )]
+    pub(crate) hook: String,
 }
 
 #[derive(Args, Debug)]
@@ -677,6 +1252,94 @@ pub struct SizeArgs {
     /// Display the cache size in human-readable format (e.g., `1.2 GiB` instead of raw bytes).
     #[arg(long = "human", short = 'H', alias = "human-readable")]
     pub(crate) human: bool,
+    /// Break the size down by category (repositories, hook environments, toolchains, ...) and by entry.
+    #[arg(long)]
+    pub(crate) breakdown: bool,
+    /// The output format.
+    #[arg(long, value_enum, default_value_t = ListOutputFormat::Text)]
+    pub(crate) output_format: ListOutputFormat,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct StatsArgs {
+    /// Sort hooks by their mean duration, to find the ones slowing down commits the most.
+    #[arg(long, conflicts_with = "flaky")]
+    pub(crate) slowest: bool,
+    /// Only show hooks whose outcome changed between runs, most frequently changing first.
+    #[arg(long)]
+    pub(crate) flaky: bool,
+    /// Show at most this many hooks, `0` to show all of them.
+    #[arg(long, default_value_t = 10)]
+    pub(crate) limit: usize,
+    /// Remove the recorded history.
+    #[arg(long, conflicts_with_all = ["slowest", "flaky"])]
+    pub(crate) clear: bool,
+    /// The output format.
+    #[arg(long, value_enum, default_value_t = ListOutputFormat::Text)]
+    pub(crate) output_format: ListOutputFormat,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct LastArgs {
+    /// Only list the files failed hooks failed on, relative to the workspace root.
+    #[arg(long)]
+    pub(crate) failures: bool,
+    /// The output format.
+    #[arg(long, value_enum, default_value_t = ListOutputFormat::Text)]
+    pub(crate) output_format: ListOutputFormat,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct AuditArgs {
+    /// Show at most this many commits, most recent first, `0` to show all of them.
+    #[arg(long, default_value_t = 20)]
+    pub(crate) limit: usize,
+    /// The output format.
+    #[arg(long, value_enum, default_value_t = ListOutputFormat::Text)]
+    pub(crate) output_format: ListOutputFormat,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct SkipNamespace {
+    #[command(subcommand)]
+    pub(crate) command: SkipCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum SkipCommand {
+    /// Skip hooks or projects in every run in the local repository.
+    Add(SkipAddArgs),
+    /// Stop skipping hooks or projects.
+    Remove(SkipRemoveArgs),
+    /// List the skip selectors of the local repository.
+    List,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct SkipAddArgs {
+    /// The hooks or projects to skip, with the selector syntax of `prek run --skip`.
+    #[arg(
+        required = true,
+        value_name = "HOOK|PROJECT",
+        value_hint = ValueHint::Other,
+        add = ArgValueCompleter::new(selector_completer)
+    )]
+    pub(crate) selectors: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct SkipRemoveArgs {
+    /// The skip selectors to remove, as given to `prek skip add`.
+    #[arg(
+        required_unless_present = "all",
+        value_name = "HOOK|PROJECT",
+        value_hint = ValueHint::Other,
+        add = ArgValueCompleter::new(selector_completer)
+    )]
+    pub(crate) selectors: Vec<String>,
+    /// Remove all skip selectors.
+    #[arg(long, conflicts_with = "selectors")]
+    pub(crate) all: bool,
 }
 
 #[derive(Debug, Args)]
@@ -694,13 +1357,38 @@ pub(crate) enum SelfCommand {
 #[derive(Debug, Args)]
 pub(crate) struct SelfUpdateArgs {
     /// Update to the specified version.
-    /// If not provided, prek will update to the latest version.
+    /// If not provided, prek will update to the latest version of the selected channel.
+    ///
+    /// If the version is the one prek was last updated from, its binary kept in
+    /// `$PREK_HOME/bin/previous` is restored without downloading anything.
     pub target_version: Option<String>,
 
+    /// The release channel to update from.
+    #[arg(long, value_enum, default_value_t, conflicts_with = "target_version")]
+    pub channel: UpdateChannel,
+
     /// A GitHub token for authentication.
     /// A token is not required but can be used to reduce the chance of encountering rate limits.
     #[arg(long, env = "GITHUB_TOKEN")]
     pub token: Option<String>,
+
+    /// Refuse to update unless the release archive's checksum and signature can be verified.
+    ///
+    /// Without this, an archive that has no checksum or signature is installed with a warning.
+    /// A checksum or signature that doesn't match is always an error.
+    #[arg(long)]
+    pub require_signature: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum UpdateChannel {
+    /// Stable releases.
+    #[default]
+    Stable,
+    /// Stable releases and release candidates, such as `0.3.0-rc.1`.
+    PreRelease,
+    /// All releases, including nightly builds, such as `0.3.0-nightly.20260101`.
+    Nightly,
 }
 
 #[derive(Debug, Args)]
@@ -724,10 +1412,29 @@ pub(crate) struct InitTemplateDirArgs {
     /// Specifies which git hook stage(s) you want to install the hook script for.
     /// Can be specified multiple times to install hooks for multiple stages.
     ///
-    /// If not specified, uses `default_install_hook_types` from the config file,
-    /// or defaults to `pre-commit` if that is also not set.
-    #[arg(short = 't', long = "hook-type", value_name = "HOOK_TYPE", value_enum)]
+    /// If not specified, uses `PREK_HOOK_TYPES` (comma-separated), then `default_install_hook_types`
+    /// from the config file, or defaults to `pre-commit` if neither is set.
+    #[arg(
+        short = 't',
+        long = "hook-type",
+        value_name = "HOOK_TYPE",
+        value_enum,
+        value_delimiter = ',',
+        env = EnvVars::PREK_HOOK_TYPES
+    )]
     pub(crate) hook_types: Vec<HookType>,
+
+    /// Download prek in the hook script when it's not installed.
+    ///
+    /// The hook script downloads a pinned prek release to `$PREK_HOME/bin` and verifies its
+    /// checksum, so that cloned repositories work on machines without prek installed.
+    /// Only supported on Linux and macOS.
+    #[arg(long)]
+    pub(crate) bootstrap: bool,
+
+    /// The prek version downloaded by `--bootstrap`, defaults to the running version.
+    #[arg(long, value_name = "VERSION", requires = "bootstrap")]
+    pub(crate) bootstrap_version: Option<String>,
 }
 
 #[cfg(unix)]
@@ -0,0 +1,23 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::cli::ExitStatus;
+use crate::config;
+use crate::fs::CWD;
+use crate::printer::Printer;
+use crate::workspace::Project;
+
+/// Print the config of the current project, with `extends` and YAML merge keys resolved.
+pub(crate) fn render(config: Option<&Path>, printer: Printer) -> Result<ExitStatus> {
+    let project = Project::discover(config, &CWD)?;
+    let (_, mut value) = config::load_config_value(project.config_file(), &mut Vec::new())?;
+    if let Some(value) = value.as_mapping_mut() {
+        value.remove("extends");
+    }
+
+    write!(printer.stdout(), "{}", serde_yaml::to_string(&value)?)?;
+
+    Ok(ExitStatus::Success)
+}
@@ -1,9 +1,13 @@
 use std::borrow::Cow;
-use std::sync::{Arc, Mutex, Weak};
+use std::fmt::Write as _;
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock, Mutex, Weak};
 use std::time::Duration;
 
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use owo_colors::OwoColorize;
+use prek_consts::env_vars::EnvVars;
 use rustc_hash::FxHashMap;
 use unicode_width::UnicodeWidthStr;
 
@@ -11,6 +15,59 @@ use crate::hook::Hook;
 use crate::printer::Printer;
 use crate::workspace;
 
+/// Whether stdout is a terminal that renders OSC-8 hyperlinks.
+///
+/// Can be forced on or off with `PREK_HYPERLINKS`.
+pub(crate) static HYPERLINKS: LazyLock<bool> = LazyLock::new(|| {
+    if let Some(force) = EnvVars::var_as_bool(EnvVars::PREK_HYPERLINKS) {
+        return force;
+    }
+    if !std::io::stdout().is_terminal() || EnvVars::is_set(EnvVars::CI) {
+        return false;
+    }
+    if anstream::Stdout::choice(&std::io::stdout()) == anstream::ColorChoice::Never {
+        return false;
+    }
+    supports_hyperlinks(|name| EnvVars::var(name).ok())
+});
+
+/// Detect terminals known to support OSC-8 hyperlinks from their environment variables.
+fn supports_hyperlinks(var: impl Fn(&str) -> Option<String>) -> bool {
+    if var(EnvVars::WT_SESSION).is_some() || var(EnvVars::KONSOLE_VERSION).is_some() {
+        return true;
+    }
+    // VTE based terminals (GNOME Terminal, Tilix, ...) support hyperlinks since 0.50.
+    if let Some(version) = var(EnvVars::VTE_VERSION)
+        && version.parse::<u32>().is_ok_and(|version| version >= 5000)
+    {
+        return true;
+    }
+    if let Some(program) = var(EnvVars::TERM_PROGRAM)
+        && matches!(
+            program.as_str(),
+            "iTerm.app" | "WezTerm" | "vscode" | "ghostty" | "Hyper"
+        )
+    {
+        return true;
+    }
+    var(EnvVars::TERM).is_some_and(|term| {
+        [
+            "xterm-kitty",
+            "xterm-ghostty",
+            "wezterm",
+            "alacritty",
+            "foot",
+        ]
+        .iter()
+        .any(|name| term.starts_with(name))
+    })
+}
+
+/// Render `text` as an OSC-8 hyperlink to `url`.
+pub(crate) fn hyperlink(text: &str, url: &str) -> String {
+    format!("\x1b]8;;{url}\x1b\\{text}\x1b]8;;\x1b\\")
+}
+
 /// Current progress reporter used to suspend rendering while printing normal output.
 static CURRENT_REPORTER: Mutex<Option<Weak<ProgressReporter>>> = Mutex::new(None);
 
@@ -172,14 +229,49 @@ impl HookInstallReporter {
         self.reporter.on_progress(id);
     }
 
+    /// Print the last lines of the build log of a hook that failed to install.
+    pub fn on_install_failed(&self, hook: &Hook, log: &str) {
+        const TAIL_LINES: usize = 20;
+
+        let lines = log.lines().collect::<Vec<_>>();
+        let tail = &lines[lines.len().saturating_sub(TAIL_LINES)..];
+        self.reporter.children.suspend(|| {
+            let mut stderr = self.reporter.printer.stderr();
+            let _ = writeln!(
+                stderr,
+                "{}",
+                format!("Last {} lines of the build log of `{hook}`:", tail.len()).bold()
+            );
+            for line in tail {
+                let _ = writeln!(stderr, "  {}", line.dimmed());
+            }
+        });
+    }
+
     pub fn on_complete(&self) {
         self.reporter.on_complete();
     }
 }
 
+/// The template of the line of a running hook: its name, dots filling up to `dots` columns,
+/// the number of files processed out of `len` (unless unknown) and the elapsed time.
+fn run_bar_template(dots: usize, len: usize) -> String {
+    let (files, files_width) = if len == 0 {
+        (String::new(), 0)
+    } else {
+        let digits = len.to_string().len();
+        (format!(" {{pos:>{digits}}}/{{len}}"), 2 + 2 * digits)
+    };
+    // Keep the counts within the line of the final status.
+    let bar = dots.saturating_sub(files_width + 4);
+    format!("{{msg}}{{bar:{bar}.green/dim}}{files} {{elapsed:>3.dim}}")
+}
+
 pub(crate) struct HookRunReporter {
     reporter: Arc<ProgressReporter>,
     dots: usize,
+    /// The project path and index of the hook each running bar belongs to, by bar ID.
+    running: Mutex<FxHashMap<usize, (PathBuf, usize)>>,
 }
 
 impl HookRunReporter {
@@ -187,7 +279,11 @@ impl HookRunReporter {
         let reporter = Arc::new(ProgressReporter::from(printer));
         set_current_reporter(Some(&reporter));
 
-        Self { reporter, dots }
+        Self {
+            reporter,
+            dots,
+            running: Mutex::default(),
+        }
     }
 
     pub fn on_run_start(&self, hook: &Hook, len: usize) -> usize {
@@ -199,21 +295,24 @@ impl HookRunReporter {
         let id = state.id();
 
         // len == 0 indicates an unknown length; use 1 to show an indeterminate bar.
-        let len = if len == 0 { 1 } else { len };
         let progress = self.reporter.children.insert_before(
             &self.reporter.root,
-            ProgressBar::with_draw_target(Some(len as u64), self.reporter.printer.target()),
+            ProgressBar::with_draw_target(Some(len.max(1) as u64), self.reporter.printer.target()),
         );
 
         let dots = self.dots.saturating_sub(hook.name.width());
         progress.enable_steady_tick(Duration::from_millis(200));
         progress.set_style(
-            ProgressStyle::with_template(&format!("{{msg}}{{bar:{dots}.green/dim}}"))
+            ProgressStyle::with_template(&run_bar_template(dots, len))
                 .unwrap()
                 .progress_chars(".."),
         );
         progress.set_message(hook.name.clone());
         state.bars.insert(id, progress);
+        self.running
+            .lock()
+            .unwrap()
+            .insert(id, (hook.project().path().to_path_buf(), hook.idx));
         id
     }
 
@@ -224,6 +323,7 @@ impl HookRunReporter {
     }
 
     pub fn on_run_complete(&self, id: usize) {
+        self.running.lock().unwrap().remove(&id);
         let progress = {
             let mut state = self.reporter.state.lock().unwrap();
             state.bars.remove(&id).unwrap()
@@ -235,6 +335,21 @@ impl HookRunReporter {
         progress.finish_and_clear();
     }
 
+    /// Clear the bars of a hook whose run was cancelled before it completed.
+    pub fn on_run_cancelled(&self, hook: &Hook) {
+        let ids = self
+            .running
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, (path, idx))| *idx == hook.idx && path == hook.project().path())
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        for id in ids {
+            self.on_run_complete(id);
+        }
+    }
+
     /// Temporarily suspend progress rendering while emitting normal output.
     ///
     /// This helps prevent the progress UI from being corrupted by concurrent writes.
@@ -278,3 +393,66 @@ impl AutoUpdateReporter {
         self.reporter.on_complete();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env<'a>(vars: &'a [(&str, &str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        |name| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| (*value).to_string())
+        }
+    }
+
+    #[test]
+    fn detect_hyperlinks() {
+        assert!(supports_hyperlinks(env(&[(EnvVars::WT_SESSION, "1")])));
+        assert!(supports_hyperlinks(env(&[(
+            EnvVars::TERM_PROGRAM,
+            "WezTerm"
+        )])));
+        assert!(supports_hyperlinks(env(&[(EnvVars::TERM, "xterm-kitty")])));
+        assert!(supports_hyperlinks(env(&[(EnvVars::VTE_VERSION, "7600")])));
+        assert!(!supports_hyperlinks(env(&[(EnvVars::VTE_VERSION, "4800")])));
+        assert!(!supports_hyperlinks(env(&[(
+            EnvVars::TERM,
+            "xterm-256color"
+        )])));
+        assert!(!supports_hyperlinks(env(&[])));
+    }
+
+    #[test]
+    fn run_bar() {
+        for (dots, len, template) in [
+            (60, 0, "{msg}{bar:56.green/dim} {elapsed:>3.dim}"),
+            (
+                60,
+                7,
+                "{msg}{bar:52.green/dim} {pos:>1}/{len} {elapsed:>3.dim}",
+            ),
+            (
+                60,
+                1200,
+                "{msg}{bar:46.green/dim} {pos:>4}/{len} {elapsed:>3.dim}",
+            ),
+            (
+                0,
+                7,
+                "{msg}{bar:0.green/dim} {pos:>1}/{len} {elapsed:>3.dim}",
+            ),
+        ] {
+            assert_eq!(run_bar_template(dots, len), template);
+            assert!(ProgressStyle::with_template(template).is_ok());
+        }
+    }
+
+    #[test]
+    fn render_hyperlink() {
+        assert_eq!(
+            hyperlink("a.py", "file:///src/a.py"),
+            "\x1b]8;;file:///src/a.py\x1b\\a.py\x1b]8;;\x1b\\"
+        );
+    }
+}
@@ -0,0 +1,202 @@
+use std::process::Stdio;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
+use tracing::{debug, trace};
+
+use crate::cli::run::summary::{HookSummary, RunSummary};
+use crate::process::Cmd;
+use crate::warn_user;
+
+/// An external reporter selected with `--reporter`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ExternalReporter {
+    /// Spawn the command and stream the run events to its stdin.
+    Exec(Vec<String>),
+}
+
+impl FromStr for ExternalReporter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some(command) = s.strip_prefix("exec:") else {
+            return Err("expected `exec:<command>`".to_string());
+        };
+        match shlex::split(command) {
+            Some(argv) if !argv.is_empty() => Ok(Self::Exec(argv)),
+            _ => Err(format!("invalid reporter command `{command}`")),
+        }
+    }
+}
+
+/// An event of a run, serialized as one line of JSON.
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub(crate) enum Event<'a> {
+    RunStarted {
+        version: &'static str,
+        /// Number of hooks selected to run.
+        hooks: usize,
+        /// Number of files collected for the run.
+        files: usize,
+    },
+    HookStarted {
+        id: &'a str,
+        name: &'a str,
+        project: String,
+        /// Number of files the hook runs on.
+        files: usize,
+    },
+    HookFinished {
+        #[serde(flatten)]
+        hook: &'a HookSummary,
+        files: usize,
+    },
+    RunFinished(&'a RunSummary),
+}
+
+/// A running external reporter, receiving newline-delimited JSON events on its stdin.
+pub(crate) struct EventStream {
+    command: String,
+    sender: mpsc::UnboundedSender<Vec<u8>>,
+    writer: tokio::task::JoinHandle<()>,
+    child: tokio::process::Child,
+}
+
+impl EventStream {
+    pub(crate) fn spawn(reporter: &ExternalReporter) -> Result<Self> {
+        let ExternalReporter::Exec(argv) = reporter;
+        let command =
+            shlex::try_join(argv.iter().map(String::as_str)).unwrap_or_else(|_| argv.join(" "));
+
+        let mut child = Cmd::new(&argv[0], "run reporter")
+            .args(&argv[1..])
+            .stdin(Stdio::piped())
+            .check(false)
+            .spawn()
+            .with_context(|| format!("Failed to spawn reporter `{command}`"))?;
+        let mut stdin = child.stdin.take().context("Failed to take stdin")?;
+
+        // Events are written in the background, so a slow reporter doesn't hold up the hooks.
+        let (sender, mut receiver) = mpsc::unbounded_channel::<Vec<u8>>();
+        let writer = tokio::spawn(async move {
+            while let Some(line) = receiver.recv().await {
+                // The reporter may exit without reading all events.
+                if let Err(err) = stdin.write_all(&line).await {
+                    debug!("Failed to write event to reporter: {err}");
+                    break;
+                }
+            }
+            let _ = stdin.shutdown().await;
+        });
+
+        Ok(Self {
+            command,
+            sender,
+            writer,
+            child,
+        })
+    }
+
+    pub(crate) fn send(&self, event: &Event) {
+        let mut line = match serde_json::to_vec(event) {
+            Ok(line) => line,
+            Err(err) => {
+                debug!("Failed to serialize event: {err}");
+                return;
+            }
+        };
+        trace!("Reporter event: {}", String::from_utf8_lossy(&line));
+        line.push(b'\n');
+        let _ = self.sender.send(line);
+    }
+
+    /// Close the reporter's stdin and wait for it to exit.
+    pub(crate) async fn finish(self) -> Result<()> {
+        let Self {
+            command,
+            sender,
+            writer,
+            mut child,
+        } = self;
+
+        drop(sender);
+        writer.await?;
+        let status = child
+            .wait()
+            .await
+            .with_context(|| format!("Failed to wait for reporter `{command}`"))?;
+        if !status.success() {
+            warn_user!("Reporter `{command}` exited with {status}");
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::run::summary::{SkipReason, SummaryStatus};
+
+    #[test]
+    fn parse_reporter() {
+        assert_eq!(
+            "exec:notify --channel 'ci builds'".parse::<ExternalReporter>(),
+            Ok(ExternalReporter::Exec(vec![
+                "notify".to_string(),
+                "--channel".to_string(),
+                "ci builds".to_string(),
+            ]))
+        );
+        assert!("exec:".parse::<ExternalReporter>().is_err());
+        assert!("notify".parse::<ExternalReporter>().is_err());
+    }
+
+    #[test]
+    fn serialize_events() -> Result<()> {
+        let hook = HookSummary {
+            id: "ruff".to_string(),
+            name: "ruff".to_string(),
+            project: ".".to_string(),
+            status: SummaryStatus::Failed,
+            skip_reason: None,
+            exit_code: 1,
+            duration: 0.5,
+        };
+        let skipped = HookSummary {
+            id: "mypy".to_string(),
+            name: "mypy".to_string(),
+            project: ".".to_string(),
+            status: SummaryStatus::Skipped,
+            skip_reason: Some(SkipReason::SkipEnv),
+            exit_code: 0,
+            duration: 0.0,
+        };
+        let started = Event::HookStarted {
+            id: &hook.id,
+            name: &hook.name,
+            project: hook.project.clone(),
+            files: 3,
+        };
+        let finished = Event::HookFinished {
+            hook: &hook,
+            files: 3,
+        };
+
+        insta::assert_snapshot!(serde_json::to_string(&started)?, @r#"
+        {"event":"hook-started","id":"ruff","name":"ruff","project":".","files":3}
+        "#);
+        insta::assert_snapshot!(serde_json::to_string(&finished)?, @r#"
+        {"event":"hook-finished","id":"ruff","name":"ruff","project":".","status":"failed","exit_code":1,"duration":0.5,"files":3}
+        "#);
+        insta::assert_snapshot!(serde_json::to_string(&Event::HookFinished { hook: &skipped, files: 0 })?, @r#"
+        {"event":"hook-finished","id":"mypy","name":"mypy","project":".","status":"skipped","skip_reason":"skip-env","exit_code":0,"duration":0.0,"files":0}
+        "#);
+
+        Ok(())
+    }
+}
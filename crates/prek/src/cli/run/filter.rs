@@ -11,7 +11,8 @@ use tracing::{debug, error, instrument};
 use crate::config::{FilePattern, Stage};
 use crate::git::GIT_ROOT;
 use crate::hook::Hook;
-use crate::identify::{TagSet, tags_from_path};
+use crate::identify::{DEFAULT_BINARY_DETECTION_BYTES, TagSet, tags_from_path_with};
+use crate::tags_cache::TagsCache;
 use crate::workspace::Project;
 use crate::{fs, git, warn_user};
 
@@ -81,15 +82,22 @@ impl<'a> FileTagFilter<'a> {
 pub(crate) struct FileFilter<'a> {
     filenames: Vec<&'a Path>,
     filename_prefix: &'a Path,
+    /// Files marked `skip-worktree` or `assume-unchanged` in the git index.
+    skip_worktree: Option<&'a FxHashSet<PathBuf>>,
+    /// Files larger than this many bytes are not passed to hooks.
+    max_file_size: Option<u64>,
+    binary_detection_bytes: usize,
+    tags_cache: Option<&'a TagsCache>,
 }
 
 impl<'a> FileFilter<'a> {
-    // Here, `filenames` are paths relative to the workspace root.
+    // Here, `filenames` and `skip_worktree` are paths relative to the workspace root.
     #[instrument(level = "trace", skip_all, fields(project = %project))]
     pub(crate) fn for_project<I>(
         filenames: I,
         project: &'a Project,
         mut consumed_files: Option<&mut FxHashSet<&'a Path>>,
+        skip_worktree: Option<&'a FxHashSet<PathBuf>>,
     ) -> Self
     where
         I: Iterator<Item = &'a PathBuf> + Send,
@@ -100,6 +108,7 @@ impl<'a> FileFilter<'a> {
         );
 
         let orphan = project.config().orphan.unwrap_or(false);
+        let exclude_skip_worktree = project.config().exclude_skip_worktree.unwrap_or(false);
 
         // The order of below filters matters.
         // If this is an orphan project, we must mark all files in its directory as consumed
@@ -122,14 +131,84 @@ impl<'a> FileFilter<'a> {
                 }
             })
             .filter(|filename| filter.filter(filename))
+            .filter(|filename| {
+                !exclude_skip_worktree
+                    || skip_worktree.is_none_or(|skip_worktree| !skip_worktree.contains(*filename))
+            })
             .collect::<Vec<_>>();
 
         Self {
             filenames,
             filename_prefix: project.relative_path(),
+            skip_worktree,
+            max_file_size: project.config().max_file_size,
+            binary_detection_bytes: project
+                .config()
+                .binary_detection_bytes
+                .unwrap_or(DEFAULT_BINARY_DETECTION_BYTES),
+            tags_cache: None,
+        }
+    }
+
+    /// Use a persistent cache to identify the tags of files.
+    #[must_use]
+    pub(crate) fn with_tags_cache(mut self, tags_cache: &'a TagsCache) -> Self {
+        self.tags_cache = Some(tags_cache);
+        self
+    }
+
+    /// Identify tags for a file, relative to the workspace root.
+    fn tags(&self, filename: &Path) -> Result<TagSet> {
+        match self.tags_cache {
+            Some(cache) => cache.tags(filename, self.binary_detection_bytes),
+            None => tags_from_path_with(filename, self.binary_detection_bytes),
         }
     }
 
+    /// Override the size limit of the project's configuration, like `--max-file-size`.
+    #[must_use]
+    pub(crate) fn with_max_file_size(mut self, max_file_size: Option<u64>) -> Self {
+        if max_file_size.is_some() {
+            self.max_file_size = max_file_size;
+        }
+        self
+    }
+
+    /// The size limit of the files passed to the hook, if any.
+    pub(crate) fn max_file_size(&self, hook: &Hook) -> Option<u64> {
+        hook.max_file_size
+            .or(self.max_file_size)
+            .filter(|&size| size > 0)
+    }
+
+    /// Remove the files larger than `max_file_size` bytes from the hook's `filenames`, and
+    /// return them.
+    pub(crate) fn take_oversized<'p>(
+        &self,
+        filenames: &mut Vec<&'p Path>,
+        max_file_size: u64,
+    ) -> Vec<&'p Path> {
+        let (oversized, kept) = filenames.drain(..).partition(|filename| {
+            std::fs::metadata(self.filename_prefix.join(filename))
+                .is_ok_and(|metadata| metadata.len() > max_file_size)
+        });
+        *filenames = kept;
+        oversized
+    }
+
+    /// Return the files among the hook's `filenames` that are marked `skip-worktree` or
+    /// `assume-unchanged`, relative to the workspace root.
+    pub(crate) fn skip_worktree_files(&self, filenames: &[&Path]) -> Vec<PathBuf> {
+        let Some(skip_worktree) = self.skip_worktree.filter(|files| !files.is_empty()) else {
+            return vec![];
+        };
+        filenames
+            .iter()
+            .map(|filename| self.filename_prefix.join(filename))
+            .filter(|filename| skip_worktree.contains(filename))
+            .collect()
+    }
+
     pub(crate) fn len(&self) -> usize {
         self.filenames.len()
     }
@@ -145,7 +224,7 @@ impl<'a> FileFilter<'a> {
         let filenames: Vec<_> = self
             .filenames
             .par_iter()
-            .filter(|filename| match tags_from_path(filename) {
+            .filter(|filename| match self.tags(filename) {
                 Ok(tags) => filter.filter(&tags),
                 Err(err) => {
                     error!(filename = ?filename.display(), error = %err, "Failed to get tags");
@@ -174,7 +253,7 @@ impl<'a> FileFilter<'a> {
 
         // Filter by hook `types`, `types_or` and `exclude_types`.
         let filter = FileTagFilter::for_hook(hook);
-        let filenames = filenames.filter(|filename| match tags_from_path(filename) {
+        let filenames = filenames.filter(|filename| match self.tags(filename) {
             Ok(tags) => filter.filter(&tags),
             Err(err) => {
                 error!(filename = ?filename.display(), error = %err, "Failed to get tags");
@@ -195,16 +274,29 @@ impl<'a> FileFilter<'a> {
 }
 
 #[derive(Default)]
+#[allow(clippy::struct_excessive_bools)]
 pub(crate) struct CollectOptions {
     pub(crate) hook_stage: Stage,
     pub(crate) from_ref: Option<String>,
     pub(crate) to_ref: Option<String>,
+    pub(crate) push_ranges: Vec<PushRange>,
     pub(crate) all_files: bool,
     pub(crate) files: Vec<String>,
     pub(crate) directories: Vec<String>,
+    pub(crate) include_untracked: bool,
+    pub(crate) include_ignored: bool,
+    pub(crate) recurse_submodules: bool,
     pub(crate) commit_msg_filename: Option<String>,
 }
 
+/// A range of commits being pushed, read from the stdin of the `pre-push` hook.
+#[derive(Debug, Clone)]
+pub(crate) struct PushRange {
+    /// The commit the remote already has, or `None` if the whole history of `to` is pushed.
+    pub(crate) from: Option<String>,
+    pub(crate) to: String,
+}
+
 impl CollectOptions {
     pub(crate) fn all_files() -> Self {
         Self {
@@ -222,12 +314,26 @@ pub(crate) async fn collect_files(root: &Path, opts: CollectOptions) -> Result<V
         hook_stage,
         from_ref,
         to_ref,
+        push_ranges,
         all_files,
         files,
         directories,
+        include_untracked,
+        include_ignored,
+        recurse_submodules,
         commit_msg_filename,
     } = opts;
 
+    // The files of submodules are only collected in the all-files and staged-files modes.
+    let submodule_files = (recurse_submodules
+        && hook_stage.operate_on_files()
+        && commit_msg_filename.is_none()
+        && push_ranges.is_empty()
+        && from_ref.is_none()
+        && files.is_empty()
+        && directories.is_empty())
+    .then_some(all_files);
+
     let git_root = GIT_ROOT.as_ref()?;
 
     // The workspace root relative to the git root.
@@ -239,22 +345,47 @@ pub(crate) async fn collect_files(root: &Path, opts: CollectOptions) -> Result<V
         )
     })?;
 
-    let filenames = collect_files_from_args(
+    let mut filenames = collect_files_from_args(
         git_root,
         root,
         hook_stage,
         from_ref,
         to_ref,
+        push_ranges,
         all_files,
         files,
         directories,
+        include_untracked,
+        include_ignored,
         commit_msg_filename,
     )
     .await?;
 
+    // Submodules show up as gitlink entries, which are directories of another repository
+    // rather than files of this one, so they are never passed to hooks.
+    let submodules = if let Some(all_files) = submodule_files {
+        let (submodules, files) = collect_submodule_files(git_root, all_files).await?;
+        filenames.extend(files);
+        submodules
+    } else {
+        git::list_submodules(git_root)
+            .unwrap_or_else(|err| {
+                debug!("Failed to list git submodules: {err}");
+                vec![]
+            })
+            .into_iter()
+            .filter_map(|submodule| Some(submodule.strip_prefix(git_root).ok()?.to_path_buf()))
+            .collect()
+    };
+    let submodules = submodules
+        .iter()
+        .map(PathBuf::as_path)
+        .collect::<FxHashSet<_>>();
+
     // Convert filenames to be relative to the workspace root.
     let mut filenames = filenames
         .into_iter()
+        .filter(|filename| !submodules.contains(filename.as_path()))
         .filter_map(|filename| {
             // Only keep files under the workspace root.
             filename
@@ -272,6 +403,52 @@ pub(crate) async fn collect_files(root: &Path, opts: CollectOptions) -> Result<V
     Ok(filenames)
 }
 
+/// Collect the files of the initialized submodules of the repository at `git_root`, and of their
+/// submodules: all files if `all_files`, otherwise the staged ones.
+/// Returns the paths of the submodules and the files, relative to `git_root`.
+async fn collect_submodule_files(
+    git_root: &Path,
+    all_files: bool,
+) -> Result<(Vec<PathBuf>, Vec<PathBuf>)> {
+    let mut submodules = Vec::new();
+    let mut files = Vec::new();
+
+    let mut repos = vec![PathBuf::new()];
+    while let Some(repo) = repos.pop() {
+        let repo_root = git_root.join(&repo);
+        for submodule_root in git::list_submodules(&repo_root)? {
+            let submodule = repo.join(
+                submodule_root
+                    .strip_prefix(&repo_root)
+                    .expect("Submodule should be under its repository"),
+            );
+            // Submodules that are not checked out have nothing to run hooks on.
+            if !submodule_root.join(".git").exists() {
+                debug!("Skipping uninitialized submodule `{}`", submodule.display());
+                submodules.push(submodule);
+                continue;
+            }
+
+            let submodule_files = if all_files {
+                git::ls_files(&submodule_root, Path::new(".")).await?
+            } else {
+                git::get_staged_files(&submodule_root).await?
+            };
+            debug!(
+                "Files in submodule `{}`: {}",
+                submodule.display(),
+                submodule_files.len()
+            );
+            files.extend(submodule_files.into_iter().map(|file| submodule.join(file)));
+
+            submodules.push(submodule.clone());
+            repos.push(submodule);
+        }
+    }
+
+    Ok((submodules, files))
+}
+
 fn adjust_relative_path(path: &str, new_cwd: &Path) -> Result<PathBuf, std::io::Error> {
     let absolute = std::path::absolute(path)?.clean();
     fs::relative_to(absolute, new_cwd)
@@ -286,9 +463,12 @@ async fn collect_files_from_args(
     hook_stage: Stage,
     from_ref: Option<String>,
     to_ref: Option<String>,
+    push_ranges: Vec<PushRange>,
     all_files: bool,
     files: Vec<String>,
     directories: Vec<String>,
+    include_untracked: bool,
+    include_ignored: bool,
     commit_msg_filename: Option<String>,
 ) -> Result<Vec<PathBuf>> {
     if !hook_stage.operate_on_files() {
@@ -301,6 +481,23 @@ async fn collect_files_from_args(
         return Ok(vec![path]);
     }
 
+    if !push_ranges.is_empty() {
+        let mut files = Vec::new();
+        for PushRange { from, to } in push_ranges {
+            let changed = match &from {
+                Some(from) => git::get_changed_files(from, &to, workspace_root).await?,
+                None => git::get_commit_files(&to, workspace_root).await?,
+            };
+            debug!(
+                "Files pushed between {} and {to}: {}",
+                from.as_deref().unwrap_or("the root commit"),
+                changed.len()
+            );
+            files.extend(changed);
+        }
+        return Ok(files.into_iter().unique().collect());
+    }
+
     if let (Some(from_ref), Some(to_ref)) = (from_ref, to_ref) {
         let files = git::get_changed_files(&from_ref, &to_ref, workspace_root).await?;
         debug!(
@@ -347,9 +544,46 @@ async fn collect_files_from_args(
             .map(|filename| adjust_relative_path(&filename, git_root).map(fs::normalize_path))
             .collect::<Result<FxHashSet<_>, _>>()?;
 
+        // Files outside the repository are dropped later, git refuses to list them.
+        let files = exists
+            .iter()
+            .filter(|file| !file.starts_with(".."))
+            .cloned()
+            .collect::<Vec<_>>();
+        if !files.is_empty() {
+            for (ignored, included, flag) in [
+                (false, include_untracked, "--include-untracked"),
+                (true, include_ignored, "--include-ignored"),
+            ] {
+                if included {
+                    continue;
+                }
+                let untracked = match git::ls_untracked_files(git_root, &files, ignored).await {
+                    Ok(untracked) => untracked,
+                    Err(err) => {
+                        debug!("Failed to list untracked files: {err}");
+                        continue;
+                    }
+                };
+                let skipped = untracked
+                    .into_iter()
+                    .map(fs::normalize_path)
+                    .filter(|file| exists.remove(file))
+                    .collect::<Vec<_>>();
+                warn_skipped_files(&skipped, ignored, flag);
+            }
+        }
+
         for dir in directories {
             let dir = adjust_relative_path(&dir, git_root)?;
-            let dir_files = git::ls_files(git_root, &dir).await?;
+            let mut dir_files = git::ls_files(git_root, &dir).await?;
+            let dirs = [dir];
+            if include_untracked {
+                dir_files.extend(git::ls_untracked_files(git_root, &dirs, false).await?);
+            }
+            if include_ignored {
+                dir_files.extend(git::ls_untracked_files(git_root, &dirs, true).await?);
+            }
             for file in dir_files {
                 let file = fs::normalize_path(file);
                 exists.insert(file);
@@ -378,6 +612,29 @@ async fn collect_files_from_args(
     Ok(files)
 }
 
+fn warn_skipped_files(skipped: &[PathBuf], ignored: bool, flag: &str) {
+    let kind = if ignored {
+        "ignored by git"
+    } else {
+        "untracked"
+    };
+    match skipped {
+        [] => {}
+        [file] => {
+            warn_user!(
+                "This file is {kind} and will be skipped, use `{flag}` to run hooks on it: `{}`",
+                file.display()
+            );
+        }
+        files => {
+            warn_user!(
+                "These files are {kind} and will be skipped, use `{flag}` to run hooks on them: `{}`",
+                files.iter().map(|file| file.display()).join(", ")
+            );
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
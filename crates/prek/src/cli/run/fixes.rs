@@ -0,0 +1,104 @@
+//! The files modified by each priority group of hooks, summarized after the run with
+//! `--show-fixes`.
+//!
+//! Files are compared by the hash of their content, so a file modified by several groups is
+//! attributed to each of them. The line counts come from `git diff` at the end of the run, so
+//! they cover the changes of all hooks to a file.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use itertools::Itertools;
+use owo_colors::OwoColorize;
+use rustc_hash::FxHashMap;
+use sha2::{Digest, Sha256};
+
+use crate::git::{self, GIT_ROOT};
+use crate::printer::Printer;
+
+/// The hash of the content of the files of a project with unstaged changes.
+pub(crate) struct UnstagedFiles {
+    root: PathBuf,
+    /// Files that no longer exist have no hash.
+    files: FxHashMap<PathBuf, Option<String>>,
+}
+
+impl UnstagedFiles {
+    pub(crate) async fn read(root: &Path) -> Result<Self> {
+        Ok(Self {
+            root: root.to_path_buf(),
+            files: Self::hash_files(root).await?,
+        })
+    }
+
+    async fn hash_files(root: &Path) -> Result<FxHashMap<PathBuf, Option<String>>> {
+        let git_root = GIT_ROOT.as_ref()?;
+        let mut files = FxHashMap::default();
+        for file in git::get_unstaged_files(root).await? {
+            let hash = match fs_err::tokio::read(git_root.join(&file)).await {
+                Ok(content) => Some(hex::encode(Sha256::digest(&content))),
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+                Err(err) => return Err(err.into()),
+            };
+            files.insert(file, hash);
+        }
+        Ok(files)
+    }
+
+    /// Read the files again, returning the files modified since they were last read.
+    pub(crate) async fn modified(&mut self) -> Result<Vec<PathBuf>> {
+        let files = Self::hash_files(&self.root).await?;
+        let modified = self
+            .files
+            .keys()
+            .chain(files.keys())
+            .unique()
+            .filter(|file| self.files.get(*file) != files.get(*file))
+            .cloned()
+            .sorted()
+            .collect();
+        self.files = files;
+        Ok(modified)
+    }
+}
+
+/// The files modified by a priority group of hooks.
+pub(crate) struct HookFixes {
+    /// The hooks that ran in the group, any of which may have modified the files.
+    pub(crate) hooks: Vec<String>,
+    pub(crate) files: Vec<PathBuf>,
+}
+
+/// Print the files modified by each hook, with the lines added and removed in them.
+pub(crate) async fn print_fixes(root: &Path, fixes: &[HookFixes], printer: Printer) -> Result<()> {
+    let stats = git::get_diff_numstat(root)
+        .await?
+        .into_iter()
+        .collect::<FxHashMap<_, _>>();
+
+    let mut output = String::new();
+    for fix in fixes {
+        writeln!(output, "  {}", fix.hooks.join(", ").bold())?;
+        for file in &fix.files {
+            match stats.get(file) {
+                Some(Some((added, removed))) => writeln!(
+                    output,
+                    "    {} {} {}",
+                    file.display(),
+                    format!("+{added}").green(),
+                    format!("-{removed}").red(),
+                )?,
+                Some(None) => writeln!(output, "    {} {}", file.display(), "(binary)".dimmed())?,
+                // Changed back by a later hook.
+                None => writeln!(output, "    {} {}", file.display(), "(no changes)".dimmed())?,
+            }
+        }
+    }
+
+    write!(
+        printer.stdout_important(),
+        "\nFiles modified by hooks:\n{output}"
+    )?;
+    Ok(())
+}
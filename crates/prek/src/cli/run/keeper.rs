@@ -10,7 +10,7 @@ use tracing::{debug, error, trace};
 use prek_consts::env_vars::EnvVars;
 
 use crate::cleanup::add_cleanup;
-use crate::fs::Simplified;
+use crate::fs::{LockedFile, Simplified};
 use crate::git::{self, GIT, git_cmd};
 use crate::store::Store;
 
@@ -218,6 +218,26 @@ impl Drop for WorkingTreeKeeper {
     }
 }
 
+/// Lock the work tree of the repository for the duration of a run.
+///
+/// Concurrent runs in the same work tree would have their hooks modify the same files, and one
+/// run could stash and restore unstaged changes over the changes made by the other.
+/// Runs nested in a hook of a run holding the lock, e.g. from a hook running `git commit`,
+/// don't lock again, as they would wait for the outer run forever.
+pub(crate) async fn lock_work_tree() -> Result<Option<LockedFile>> {
+    let git_dir = fs_err::canonicalize(git::get_git_dir().await?)?;
+    if EnvVars::var_os(EnvVars::PREK_INTERNAL__WORK_TREE_LOCK).is_some_and(|dir| dir == git_dir) {
+        return Ok(None);
+    }
+
+    let lock = LockedFile::acquire(git_dir.join("prek.lock"), "work tree").await?;
+    unsafe {
+        std::env::set_var(EnvVars::PREK_INTERNAL__WORK_TREE_LOCK, &git_dir);
+    }
+
+    Ok(Some(lock))
+}
+
 /// Clean Git intent-to-add files and working tree changes, and restore them when dropped.
 pub struct WorkTreeKeeper {
     intent_to_add: Option<IntentToAddKeeper>,
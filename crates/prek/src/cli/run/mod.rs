@@ -1,9 +1,15 @@
-pub(crate) use filter::{CollectOptions, FileFilter, collect_files};
-pub(crate) use run::{install_hooks, run};
-pub(crate) use selector::{SelectorSource, Selectors};
+pub(crate) use filter::{CollectOptions, FileFilter, PushRange, collect_files};
+pub(crate) use run::{find_installed_env, install_hooks, needs_env, plan_install, run};
+pub(crate) use selector::{SelectorSource, Selectors, normalize_local_skip};
+pub(crate) use stdin::run_stdin;
 
+pub(crate) mod events;
 mod filter;
+mod fixes;
 mod keeper;
+mod push_cache;
 #[allow(clippy::module_inception)]
 mod run;
 mod selector;
+mod stdin;
+pub(crate) mod summary;
@@ -0,0 +1,139 @@
+//! Results of the `pre-push` hooks that passed, so pushing the same commits again, e.g. after
+//! the remote rejected the push, doesn't run them again.
+//!
+//! A result is keyed on the hook, its environment and the config of its project, the pushed
+//! commit ranges and the tree of the index, since hooks run on the work tree rather than on the
+//! pushed commits. Only passing results are kept, in `.git/prek/push-results`.
+
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use anyhow::Result;
+use itertools::Itertools;
+use prek_consts::env_vars::EnvVars;
+use rustc_hash::FxHashSet;
+use sha2::{Digest, Sha256};
+use tracing::debug;
+
+use crate::cli::run::PushRange;
+use crate::git::{self, GIT_COMMON_DIR};
+use crate::hook::InstalledHook;
+
+/// The number of results kept, older ones are dropped.
+const MAX_RESULTS: usize = 1000;
+
+pub(crate) struct PushCache {
+    path: PathBuf,
+    /// The pushed ranges and the tree of the index, shared by the keys of all hooks.
+    push: String,
+    results: FxHashSet<String>,
+    passed: Mutex<Vec<String>>,
+}
+
+impl PushCache {
+    /// Load the cache for pushing `ranges`, unless disabled with `PREK_NO_PUSH_CACHE`.
+    pub(crate) async fn load(ranges: &[PushRange]) -> Result<Option<Self>> {
+        if ranges.is_empty() || EnvVars::is_set(EnvVars::PREK_NO_PUSH_CACHE) {
+            return Ok(None);
+        }
+
+        let tree = git::write_tree().await?;
+        let ranges = ranges
+            .iter()
+            .map(|range| {
+                format!(
+                    "{}..{}",
+                    range.from.as_deref().unwrap_or_default(),
+                    range.to
+                )
+            })
+            .sorted()
+            .join(" ");
+
+        let path = GIT_COMMON_DIR.as_ref()?.join("prek").join("push-results");
+        let results = match fs_err::read_to_string(&path) {
+            Ok(content) => content.lines().map(ToString::to_string).collect(),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => FxHashSet::default(),
+            Err(err) => return Err(err.into()),
+        };
+
+        Ok(Some(Self {
+            path,
+            push: format!("{ranges}\n{tree}"),
+            results,
+            passed: Mutex::default(),
+        }))
+    }
+
+    fn key(&self, hook: &InstalledHook) -> String {
+        let mut fingerprint = format!(
+            "{}\n{}\n{:?}\n{}\n{}\n{}\n",
+            env!("CARGO_PKG_VERSION"),
+            hook.project().config_file().display(),
+            hook.project().config(),
+            hook.idx,
+            hook.id,
+            self.push,
+        );
+        // A reinstalled environment, e.g. for a new toolchain, gets a new path.
+        if let Some(info) = hook.install_info() {
+            let _ = write!(
+                fingerprint,
+                "{}\n{}\n{}\n{}",
+                info.env_path.display(),
+                info.toolchain.display(),
+                info.language_version,
+                info.dependencies.iter().sorted().join(" "),
+            );
+        }
+        hex::encode(Sha256::digest(fingerprint.as_bytes()))
+    }
+
+    /// Whether the hook already passed for the same push.
+    pub(crate) fn passed_before(&self, hook: &InstalledHook) -> bool {
+        self.results.contains(&self.key(hook))
+    }
+
+    /// Remember that the hook passed.
+    pub(crate) fn record(&self, hook: &InstalledHook) {
+        self.passed.lock().unwrap().push(self.key(hook));
+    }
+
+    /// Write the results recorded during the run.
+    pub(crate) fn save(&self) {
+        let passed = std::mem::take(&mut *self.passed.lock().unwrap());
+        if passed.is_empty() {
+            return;
+        }
+        if let Err(err) = self.write(passed) {
+            debug!("Failed to save the results of the pushed commits: {err}");
+        }
+    }
+
+    fn write(&self, passed: Vec<String>) -> Result<()> {
+        // Keep the order of the file, so the oldest results are dropped first.
+        let mut results = match fs_err::read_to_string(&self.path) {
+            Ok(content) => content.lines().map(ToString::to_string).collect(),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => VecDeque::new(),
+            Err(err) => return Err(err.into()),
+        };
+        for key in passed {
+            if !results.contains(&key) {
+                results.push_back(key);
+            }
+        }
+        while results.len() > MAX_RESULTS {
+            results.pop_front();
+        }
+
+        if let Some(parent) = self.path.parent() {
+            fs_err::create_dir_all(parent)?;
+        }
+        let mut content = results.into_iter().join("\n");
+        content.push('\n');
+        fs_err::write(&self.path, content)?;
+        Ok(())
+    }
+}
@@ -1,11 +1,15 @@
+use std::borrow::Cow;
+use std::cell::Cell;
 use std::fmt::Write as _;
 use std::io::Write as _;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::sync::{Arc, LazyLock};
 
 use anyhow::{Context, Result};
-use futures::stream::{FuturesUnordered, StreamExt};
+use bstr::ByteSlice;
+use futures::stream::{FuturesOrdered, FuturesUnordered, StreamExt};
+use itertools::Itertools;
 use owo_colors::OwoColorize;
 use prek_consts::env_vars::EnvVars;
 use rand::SeedableRng;
@@ -15,48 +19,73 @@ use tokio::sync::{OnceCell, Semaphore};
 use tracing::{debug, trace, warn};
 use unicode_width::UnicodeWidthStr;
 
-use crate::cli::reporter::{HookInitReporter, HookInstallReporter, HookRunReporter};
-use crate::cli::run::keeper::WorkTreeKeeper;
-use crate::cli::run::{CollectOptions, FileFilter, Selectors, collect_files};
+use crate::ci::{self, CiProvider};
+use crate::cleanup::{GracefulShutdown, cancellable, is_cancelled, scope_token};
+use crate::cli::reporter::{
+    HYPERLINKS, HookInitReporter, HookInstallReporter, HookRunReporter, hyperlink,
+};
+use crate::cli::run::events::{Event, EventStream, ExternalReporter};
+use crate::cli::run::fixes::{HookFixes, UnstagedFiles, print_fixes};
+use crate::cli::run::keeper::{WorkTreeKeeper, lock_work_tree};
+use crate::cli::run::push_cache::PushCache;
+use crate::cli::run::summary::{HookSummary, RunSummary, SkipReason, SummaryStatus};
+use crate::cli::run::{CollectOptions, FileFilter, SelectorSource, Selectors, collect_files};
 use crate::cli::{ExitStatus, RunExtraArgs};
-use crate::config::{Language, Stage};
-use crate::fs::CWD;
+use crate::config::{FailFast, HookKind, Language, Stage};
+use crate::config_refs::ConfigRefs;
+use crate::fs::{CWD, Simplified};
 use crate::git::GIT_ROOT;
 use crate::hook::{Hook, InstallInfo, InstalledHook, Repo};
+use crate::last_run::{LastRun, LastRunHook};
 use crate::printer::Printer;
+use crate::process::capture_output;
 use crate::run::{CONCURRENCY, USE_COLOR};
+use crate::stats;
 use crate::store::Store;
+use crate::tags_cache::TagsCache;
 use crate::workspace::{Project, Workspace};
 use crate::{git, warn_user};
 
+/// Builtin hooks that rewrite line endings.
+const LINE_ENDING_FIXERS: &[&str] = &["mixed-line-ending", "end-of-file-fixer"];
+
 #[allow(clippy::too_many_arguments, clippy::fn_params_excessive_bools)]
 pub(crate) async fn run(
     store: &Store,
     config: Option<PathBuf>,
     includes: Vec<String>,
     skips: Vec<String>,
+    no_default_skips: bool,
     hook_stage: Option<Stage>,
+    hook_kind: Option<HookKind>,
     from_ref: Option<String>,
     to_ref: Option<String>,
     all_files: bool,
     files: Vec<String>,
     directories: Vec<String>,
+    include_untracked: bool,
+    include_ignored: bool,
+    recurse_submodules: bool,
     last_commit: bool,
+    changed_only: bool,
+    retry_failed: bool,
+    max_file_size: Option<u64>,
     show_diff_on_failure: bool,
-    fail_fast: bool,
+    diff_context: Option<u32>,
+    word_diff: bool,
+    show_fixes: bool,
+    fail_fast: Option<FailFast>,
+    modified_exit_code: Option<u8>,
+    autofix_stage: bool,
+    serial_projects: bool,
     dry_run: bool,
+    write_summary: Option<PathBuf>,
+    external_reporter: Option<ExternalReporter>,
     refresh: bool,
     extra_args: RunExtraArgs,
     verbose: bool,
     printer: Printer,
 ) -> Result<ExitStatus> {
-    // Convert `--last-commit` to `HEAD~1..HEAD`
-    let (from_ref, to_ref) = if last_commit {
-        (Some("HEAD~1".to_string()), Some("HEAD".to_string()))
-    } else {
-        (from_ref, to_ref)
-    };
-
     // Prevent recursive post-checkout hooks.
     if hook_stage == Some(Stage::PostCheckout)
         && EnvVars::is_set(EnvVars::PREK_INTERNAL__SKIP_POST_CHECKOUT)
@@ -67,6 +96,52 @@ pub(crate) async fn run(
     // Ensure we are in a git repository.
     LazyLock::force(&GIT_ROOT).as_ref()?;
 
+    // Convert `--last-commit` to `HEAD~1..HEAD`, and `--changed-only` to `<base>...HEAD`.
+    let (from_ref, to_ref) = if last_commit {
+        (Some("HEAD~1".to_string()), Some("HEAD".to_string()))
+    } else if changed_only {
+        let base = resolve_base_branch().await?;
+        (Some(base), Some("HEAD".to_string()))
+    } else {
+        (from_ref, to_ref)
+    };
+
+    let reproduce_command = reproduce_command(last_commit, from_ref.as_deref(), to_ref.as_deref());
+
+    let workspace_root = Workspace::find_root(config.as_deref(), &CWD)?;
+    // Hooks run from the workspace root, but the summary path is relative to where prek runs.
+    let write_summary = write_summary.map(|path| CWD.join(path));
+
+    // With `--retry-failed`, run the hooks that failed last time on the files they failed on.
+    let (retry, files, hook_stage) = if retry_failed {
+        let Some(last_run) = LastRun::load(&store.last_run_file())? else {
+            anyhow::bail!("No previous run to retry");
+        };
+        if last_run.workspace != workspace_root {
+            anyhow::bail!(
+                "The last run was in the workspace `{}`, not in `{}`",
+                last_run.workspace.display(),
+                workspace_root.display()
+            );
+        }
+        if last_run.failed_hooks().next().is_none() {
+            writeln!(printer.stdout(), "No hooks failed in the last run")?;
+            return Ok(ExitStatus::Success);
+        }
+        let files = last_run
+            .failed_files()
+            .into_iter()
+            .map(|file| workspace_root.join(file).to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        let retry = last_run
+            .failed_hooks()
+            .map(|hook| (hook.summary.project.clone(), hook.summary.id.clone()))
+            .collect::<FxHashSet<_>>();
+        (Some(retry), files, hook_stage.or(Some(last_run.stage)))
+    } else {
+        (None, files, hook_stage)
+    };
+
     let should_stash = !all_files && files.is_empty() && directories.is_empty();
 
     // Check if we have unresolved merge conflict files and fail fast.
@@ -74,27 +149,52 @@ pub(crate) async fn run(
         anyhow::bail!("You have unmerged paths. Resolve them before running prek");
     }
 
-    let workspace_root = Workspace::find_root(config.as_deref(), &CWD)?;
-    let selectors = Selectors::load(&includes, &skips, &workspace_root)?;
-    let mut workspace =
-        Workspace::discover(store, workspace_root, config, Some(&selectors), refresh)?;
+    let mut selectors = Selectors::load(&includes, &skips, &workspace_root)?;
+    let mut workspace = Workspace::discover(
+        store,
+        workspace_root,
+        config,
+        Some(&selectors),
+        refresh,
+        recurse_submodules,
+    )?;
+    if !no_default_skips {
+        selectors.load_default_skips(&workspace)?;
+    }
 
     if should_stash {
         workspace.check_configs_staged().await?;
     }
 
     let reporter = HookInitReporter::new(printer);
-    let lock = store.lock_async().await?;
+    let lock = store.lock_shared_async().await?;
 
     let hooks = workspace
         .init_hooks(store, Some(&reporter))
         .await
         .context("Failed to init hooks")?;
-    let selected_hooks: Vec<_> = hooks
-        .into_iter()
-        .filter(|h| selectors.matches_hook(h))
-        .map(Arc::new)
-        .collect();
+    // Hooks excluded by skip selectors are reported as skipped, hooks that are not included are not.
+    let mut selected_hooks = Vec::new();
+    let mut skipped_hooks = Vec::new();
+    for hook in hooks {
+        if retry
+            .as_ref()
+            .is_some_and(|retry| !retry.contains(&(hook.project().to_string(), hook.id.clone())))
+        {
+            continue;
+        }
+        if selectors.matches_hook(&hook) {
+            selected_hooks.push(Arc::new(hook));
+        } else if let Some(skip) = selectors.skipped_by(&hook) {
+            let reason = match skip.source() {
+                SelectorSource::EnvVar(_) => SkipReason::SkipEnv,
+                SelectorSource::LocalSkips => SkipReason::LocalSkip,
+                SelectorSource::DefaultSkips => SkipReason::DefaultSkip,
+                _ => SkipReason::Selector,
+            };
+            skipped_hooks.push((Arc::new(hook), reason));
+        }
+    }
 
     selectors.report_unused();
 
@@ -143,7 +243,41 @@ pub(crate) async fn run(
         (hooks, hook_stage)
     };
 
-    if filtered_hooks.is_empty() {
+    // Hooks skipped by selectors are only reported for the stage being run.
+    skipped_hooks.retain(|(h, _)| h.stages.contains(hook_stage));
+    skipped_hooks.extend(
+        selected_hooks
+            .iter()
+            .filter(|h| !h.stages.contains(hook_stage))
+            .map(|h| (h.clone(), SkipReason::Stage)),
+    );
+
+    // Hooks restricted to other platforms are reported as skipped instead of run.
+    let (filtered_hooks, unsupported_hooks): (Vec<_>, Vec<_>) = filtered_hooks
+        .into_iter()
+        .partition(|h| h.supports_current_platform());
+    skipped_hooks.extend(
+        unsupported_hooks
+            .into_iter()
+            .map(|h| (h, SkipReason::Platform)),
+    );
+
+    let filtered_hooks = if let Some(kind) = hook_kind {
+        skipped_hooks.retain(|(h, _)| h.kind == Some(kind));
+        filtered_hooks
+            .into_iter()
+            .filter(|h| h.kind == Some(kind))
+            .collect::<Vec<_>>()
+    } else {
+        filtered_hooks
+    };
+
+    // Nothing to run or report, stage-skipped hooks are only shown alongside other hooks.
+    if filtered_hooks.is_empty()
+        && skipped_hooks
+            .iter()
+            .all(|(_, reason)| *reason == SkipReason::Stage)
+    {
         debug!(
             stage = %hook_stage,
             "No hooks found for stage after filtering, exit early"
@@ -156,11 +290,24 @@ pub(crate) async fn run(
         filtered_hooks.iter().map(|h| &h.id).collect::<Vec<_>>()
     );
     let reporter = HookInstallReporter::new(printer);
-    let installed_hooks = install_hooks(filtered_hooks, store, &reporter).await?;
+    let installed_hooks = install_hooks(filtered_hooks, store, &reporter, false).await?;
 
     // Release the store lock.
     drop(lock);
 
+    // Wait for other runs in the same work tree, before stashing the unstaged changes.
+    let _work_tree_lock = lock_work_tree().await?;
+
+    // Fixes to files with unstaged changes can't be told apart from those changes.
+    let unstaged_files = if autofix_stage {
+        git::get_unstaged_files(workspace.root())
+            .await?
+            .into_iter()
+            .collect::<FxHashSet<_>>()
+    } else {
+        FxHashSet::default()
+    };
+
     // Clear any unstaged changes from the git working directory.
     let mut _guard = None;
     if should_stash {
@@ -171,17 +318,51 @@ pub(crate) async fn run(
         );
     }
 
+    // From now on, an interrupt stops the run gracefully: running hooks are terminated,
+    // a partial report is printed, and the work tree is restored when `_guard` is dropped.
+    let _shutdown = GracefulShutdown::enable();
+
     set_env_vars(from_ref.as_ref(), to_ref.as_ref(), &extra_args);
 
+    // Hooks that passed for the same pushed commits are not run again.
+    let push_cache = if dry_run {
+        None
+    } else {
+        PushCache::load(&extra_args.push_ranges)
+            .await
+            .unwrap_or_else(|err| {
+                debug!("Failed to load the results of pushed commits: {err}");
+                None
+            })
+    };
+    let installed_hooks = if let Some(push_cache) = &push_cache {
+        let (cached, hooks): (Vec<_>, Vec<_>) = installed_hooks
+            .into_iter()
+            .partition(|hook| push_cache.passed_before(hook));
+        skipped_hooks.extend(cached.into_iter().map(|hook| {
+            let hook = match hook {
+                InstalledHook::Installed { hook, .. } | InstalledHook::NoNeedInstall(hook) => hook,
+            };
+            (hook, SkipReason::Cached)
+        }));
+        hooks
+    } else {
+        installed_hooks
+    };
+
     let filenames = collect_files(
         workspace.root(),
         CollectOptions {
             hook_stage,
             from_ref,
             to_ref,
+            push_ranges: extra_args.push_ranges,
             all_files,
             files,
             directories,
+            include_untracked,
+            include_ignored,
+            recurse_submodules: workspace.recurse_submodules(),
             commit_msg_filename: extra_args.commit_msg_filename,
         },
     )
@@ -196,25 +377,221 @@ pub(crate) async fn run(
         )
     })?;
 
-    run_hooks(
+    let skipped_hooks = skipped_hooks
+        .into_iter()
+        .map(|(hook, reason)| (InstalledHook::NoNeedInstall(hook), reason))
+        .collect();
+
+    let new_last_run = || {
+        (!dry_run).then(|| {
+            LastRun::new(
+                workspace.root(),
+                hook_stage,
+                includes.clone(),
+                skips.clone(),
+            )
+        })
+    };
+
+    let outcome = run_hooks(
         &workspace,
         &installed_hooks,
+        skipped_hooks,
+        filenames.clone(),
+        max_file_size,
+        store,
+        show_diff_on_failure,
+        &reproduce_command,
+        diff_context,
+        word_diff,
+        show_fixes,
+        fail_fast,
+        serial_projects,
+        dry_run,
+        write_summary.as_deref(),
+        new_last_run(),
+        push_cache.as_ref(),
+        external_reporter.as_ref(),
+        verbose,
+        printer,
+    )
+    .await?;
+    if let Some(push_cache) = &push_cache {
+        push_cache.save();
+    }
+    if !autofix_stage || !outcome.only_modified() {
+        return Ok(outcome.exit_status(modified_exit_code));
+    }
+
+    if !stage_fixes(
+        GIT_ROOT.as_ref()?,
+        workspace.root(),
+        &unstaged_files,
+        printer,
+    )
+    .await?
+    {
+        return Ok(outcome.exit_status(modified_exit_code));
+    }
+
+    // Run the checks again on the staged fixes, fixers already had their say.
+    let hooks = installed_hooks
+        .into_iter()
+        .filter(|hook| hook.kind != Some(HookKind::Fixer))
+        .collect::<Vec<_>>();
+    if hooks.is_empty() {
+        return Ok(ExitStatus::Success);
+    }
+    writeln!(
+        printer.stdout(),
+        "\n{}",
+        "Running the hooks again on the fixed files:".bold()
+    )?;
+
+    let outcome = run_hooks(
+        &workspace,
+        &hooks,
+        Vec::new(),
         filenames,
+        max_file_size,
         store,
         show_diff_on_failure,
+        &reproduce_command,
+        diff_context,
+        word_diff,
+        show_fixes,
         fail_fast,
+        serial_projects,
         dry_run,
+        write_summary.as_deref(),
+        new_last_run(),
+        push_cache.as_ref(),
+        external_reporter.as_ref(),
         verbose,
         printer,
     )
-    .await
+    .await?;
+    if let Some(push_cache) = &push_cache {
+        push_cache.save();
+    }
+    Ok(outcome.exit_status(modified_exit_code))
+}
+
+/// Stage the changes hooks made to files, for `--autofix-stage`.
+///
+/// Files that had unstaged changes before the run are left alone, as the fixes can't be told
+/// apart from those changes. Returns whether all the fixes were staged.
+async fn stage_fixes(
+    git_root: &Path,
+    root: &Path,
+    unstaged_files: &FxHashSet<PathBuf>,
+    printer: Printer,
+) -> Result<bool> {
+    let (kept, fixed): (Vec<_>, Vec<_>) = git::get_unstaged_files(root)
+        .await?
+        .into_iter()
+        .partition(|file| unstaged_files.contains(file));
+
+    if !fixed.is_empty() {
+        git::add_files(git_root, &fixed).await?;
+        writeln!(
+            printer.stdout(),
+            "\nStaged the fixes of {} file{}",
+            fixed.len().cyan(),
+            if fixed.len() == 1 { "" } else { "s" },
+        )?;
+    }
+    if !kept.is_empty() {
+        warn_user!(
+            "Not staging the fixes of files with unstaged changes: `{}`",
+            kept.iter().map(|file| file.display()).join("`, `")
+        );
+    }
+
+    Ok(kept.is_empty())
+}
+
+/// The command to run the same hooks on the same files locally, suggested when hooks modify
+/// files in CI.
+fn reproduce_command(last_commit: bool, from_ref: Option<&str>, to_ref: Option<&str>) -> String {
+    match (from_ref, to_ref) {
+        _ if last_commit => "prek run --last-commit".to_string(),
+        (Some(from_ref), Some(to_ref)) => {
+            // Show the base branch of `--changed-only` as it is usually named.
+            let from_ref = from_ref.strip_prefix("refs/remotes/").unwrap_or(from_ref);
+            format!("prek run --from-ref {from_ref} --to-ref {to_ref}")
+        }
+        _ => "prek run --all-files".to_string(),
+    }
 }
 
 // `pre-commit` sets these environment variables for other git hooks.
+/// Commits fetched at a time when looking for the merge base in a shallow clone.
+const SHALLOW_FETCH_DEPTH: u32 = 50;
+/// Times to deepen a shallow clone before fetching the complete history.
+const SHALLOW_FETCH_ATTEMPTS: u32 = 4;
+
+/// Find the branch the current changes are going to be merged into for `--changed-only`,
+/// and make sure enough of its history is available to diff against it.
+///
+/// The branch is taken from the target branch of the pull request in GitHub Actions, GitLab CI
+/// or Bitbucket Pipelines, or else the upstream tracking branch of the current branch.
+async fn resolve_base_branch() -> Result<String> {
+    let base = if let Some(branch) = ci::base_branch() {
+        git::BaseBranch::origin(&branch)
+    } else if let Some(upstream) = git::get_upstream_branch().await? {
+        upstream
+    } else {
+        anyhow::bail!(
+            "Could not detect the base branch for `--changed-only`: none of {} is set and the current branch has no upstream. Use `--from-ref` to specify it",
+            ci::BASE_BRANCH_VARS
+                .iter()
+                .map(|name| format!("`{name}`"))
+                .join(", "),
+        );
+    };
+    debug!("Base branch for `--changed-only`: {}", base.local_ref);
+
+    if !git::is_shallow_repository().await? {
+        if !git::rev_exists(&base.local_ref).await? {
+            git::fetch_branch(&base, git::FetchDepth::Full).await?;
+        }
+        return Ok(base.local_ref);
+    }
+
+    // CI checkouts are usually shallow, deepen the history until the merge base is found.
+    git::fetch_branch(&base, git::FetchDepth::Depth(SHALLOW_FETCH_DEPTH)).await?;
+    for _ in 0..SHALLOW_FETCH_ATTEMPTS {
+        if git::merge_base(&base.local_ref, "HEAD").await?.is_some() {
+            return Ok(base.local_ref);
+        }
+        git::fetch_branch(&base, git::FetchDepth::Deepen(SHALLOW_FETCH_DEPTH)).await?;
+    }
+    if git::merge_base(&base.local_ref, "HEAD").await?.is_none() {
+        git::fetch_branch(&base, git::FetchDepth::Unshallow).await?;
+    }
+
+    Ok(base.local_ref)
+}
+
 fn set_env_vars(from_ref: Option<&String>, to_ref: Option<&String>, args: &RunExtraArgs) {
     unsafe {
         std::env::set_var("PRE_COMMIT", "1");
 
+        // The output of hooks is captured, so they can't tell that colors were asked for.
+        if matches!(
+            anstream::ColorChoice::global(),
+            anstream::ColorChoice::Always | anstream::ColorChoice::AlwaysAnsi
+        ) {
+            for key in [
+                EnvVars::FORCE_COLOR,
+                EnvVars::CLICOLOR_FORCE,
+                EnvVars::PY_COLORS,
+            ] {
+                std::env::set_var(key, "1");
+            }
+        }
+
         if let Some(source) = &args.prepare_commit_message_source {
             std::env::set_var("PRE_COMMIT_COMMIT_MSG_SOURCE", source);
         }
@@ -256,6 +633,15 @@ fn set_env_vars(from_ref: Option<&String>, to_ref: Option<&String>, args: &RunEx
         if let Some(command) = &args.rewrite_command {
             std::env::set_var("PRE_COMMIT_REWRITE_COMMAND", command);
         }
+        if args.is_workdir_updated {
+            std::env::set_var("PRE_COMMIT_WORKDIR_UPDATED", "1");
+        }
+        if args.is_skip_worktree_changed {
+            std::env::set_var("PRE_COMMIT_SKIP_WORKTREE_CHANGED", "1");
+        }
+        if let Some(state) = &args.reference_transaction_state {
+            std::env::set_var("PRE_COMMIT_REFERENCE_TRANSACTION_STATE", state);
+        }
     }
 }
 
@@ -302,14 +688,108 @@ impl LazyInstallInfo {
     }
 }
 
+/// The hooks whose environments are already installed, and the hooks whose environments need
+/// to be built.
+#[derive(Debug, Default)]
+pub(crate) struct InstallPlan {
+    pub(crate) up_to_date: Vec<Arc<Hook>>,
+    pub(crate) to_build: Vec<Arc<Hook>>,
+}
+
+/// Whether installing the hook creates an environment in the store.
+pub(crate) fn needs_env(hook: &Hook) -> bool {
+    if matches!(hook.repo(), Repo::Meta { .. } | Repo::Builtin { .. }) {
+        return false;
+    }
+    // `pygrep` hooks have no environment of their own, but record the Python they run with.
+    Language::supported(hook.language)
+        && (hook.language.supports_install_env() || hook.language == Language::Pygrep)
+}
+
+/// Find out which hooks already have a healthy environment with matching dependencies.
+///
+/// Hooks that don't need an environment, e.g. `system` or builtin hooks, are left out.
+/// With `force`, all the other hooks are planned to be built.
+pub(crate) async fn plan_install(hooks: &[Arc<Hook>], store: &Store, force: bool) -> InstallPlan {
+    let mut plan = InstallPlan::default();
+    let hooks = hooks.iter().filter(|hook| needs_env(hook));
+
+    if force {
+        plan.to_build.extend(hooks.cloned());
+        return plan;
+    }
+
+    let store_hooks = store
+        .installed_hooks()
+        .await
+        .into_iter()
+        .map(LazyInstallInfo::new)
+        .collect::<Vec<_>>();
+
+    for hook in hooks {
+        let mut up_to_date = false;
+        for env in &store_hooks {
+            if env.matches(hook) && env.ensure_healthy().await {
+                up_to_date = true;
+                break;
+            }
+        }
+        if up_to_date {
+            plan.up_to_date.push(hook.clone());
+        } else {
+            plan.to_build.push(hook.clone());
+        }
+    }
+
+    plan
+}
+
+/// Write the output of the commands run while installing the environment of `hook` to its build
+/// log, replacing the log of the previous build.
+///
+/// Returns the path of the log, or `None` if no command was run or the log couldn't be written.
+async fn write_build_log(store: &Store, hook: &Hook, log: &[u8]) -> Option<PathBuf> {
+    if log.is_empty() {
+        return None;
+    }
+    let path = store.env_log_file(&hook.env_key());
+    let write = async {
+        fs_err::tokio::create_dir_all(store.logs_dir()).await?;
+        fs_err::tokio::write(&path, log).await
+    };
+    match write.await {
+        Ok(()) => Some(path),
+        Err(err) => {
+            debug!("Failed to write the build log of `{hook}`: {err}");
+            None
+        }
+    }
+}
+
+/// Install the environments of the hooks, reusing matching environments from the store
+/// unless `force` is set.
 pub async fn install_hooks(
     hooks: Vec<Arc<Hook>>,
     store: &Store,
     reporter: &HookInstallReporter,
+    force: bool,
 ) -> Result<Vec<InstalledHook>> {
     let num_hooks = hooks.len();
     let mut result = Vec::with_capacity(hooks.len());
 
+    if let Some(env_dir) = store.env_dir() {
+        InstallInfo::check_env_dir(env_dir)?;
+        let envs = hooks
+            .iter()
+            .filter(|hook| !matches!(hook.repo(), Repo::Meta { .. } | Repo::Builtin { .. }))
+            .count();
+        if envs > 1 {
+            anyhow::bail!(
+                "`--env-dir` requires selecting a single hook, but {envs} selected hooks need an environment"
+            );
+        }
+    }
+
     let store_hooks = Rc::new(
         store
             .installed_hooks()
@@ -369,7 +849,7 @@ pub async fn install_hooks(
                         }
                     }
 
-                    if matched_info.is_none() {
+                    if matched_info.is_none() && !force {
                         for env in store_hooks.iter() {
                             if env.matches(&hook) {
                                 if env.ensure_healthy().await {
@@ -392,11 +872,53 @@ pub async fn install_hooks(
 
                     let _permit = semaphore.acquire().await.unwrap();
 
-                    let installed_hook = hook
-                        .language
-                        .install(hook.clone(), store, reporter)
-                        .await
-                        .with_context(|| format!("Failed to install hook `{hook}`"))?;
+                    // Another process may be installing the same environment, wait for it and
+                    // reuse its environment.
+                    let _env_lock = store.lock_env(hook.language, &hook.env_key()).await?;
+                    if !force && let Some(info) = find_installed_env(store, &hook).await {
+                        debug!(
+                            "Found environment installed by another process for hook `{}` at `{}`",
+                            &hook,
+                            info.env_path.display()
+                        );
+                        newly_installed.push(InstalledHook::Installed { hook, info });
+                        continue;
+                    }
+
+                    let (mut installed_hook, mut log) = capture_output(Box::pin(
+                        hook.language.install(hook.clone(), store, reporter),
+                    ))
+                    .await;
+                    // Repos are cloned without history, which some builds need, for example to
+                    // derive the package version from tags. Retry with the full history.
+                    if installed_hook.is_err()
+                        && let Repo::Remote { path, .. } = hook.repo()
+                        && store.unshallow_repo(path).await
+                    {
+                        debug!(
+                            "Retrying to install hook `{hook}` with the full history of its repo"
+                        );
+                        (installed_hook, log) = capture_output(Box::pin(hook.language.install(
+                            hook.clone(),
+                            store,
+                            reporter,
+                        )))
+                        .await;
+                    }
+                    let log_file = write_build_log(store, &hook, &log).await;
+                    let installed_hook = match (installed_hook, log_file) {
+                        (Ok(installed_hook), _) => installed_hook,
+                        (Err(err), Some(log_file)) => {
+                            reporter.on_install_failed(&hook, &String::from_utf8_lossy(&log));
+                            return Err(err.context(format!(
+                                "Failed to install hook `{hook}`, see the build log at `{}`",
+                                log_file.simplified_display()
+                            )));
+                        }
+                        (Err(err), None) => {
+                            return Err(err.context(format!("Failed to install hook `{hook}`")));
+                        }
+                    };
 
                     installed_hook
                         .mark_as_installed(store)
@@ -427,6 +949,10 @@ pub async fn install_hooks(
     }
     reporter.on_complete();
 
+    if let Err(err) = ConfigRefs::record(store, &result).await {
+        debug!("Failed to record the store entries used by the configs: {err}");
+    }
+
     debug_assert_eq!(
         num_hooks,
         result.len(),
@@ -436,6 +962,17 @@ pub async fn install_hooks(
     Ok(result)
 }
 
+/// Find a healthy environment for the hook in the store, including ones installed after the
+/// store was first read.
+pub(crate) async fn find_installed_env(store: &Store, hook: &Hook) -> Option<Arc<InstallInfo>> {
+    for info in store.installed_hooks().await {
+        if info.matches(hook) && info.check_health().await.is_ok() {
+            return Some(info);
+        }
+    }
+    None
+}
+
 /// Partition hooks into groups where hooks in the same group have same dependencies.
 /// Hooks in different groups can be installed in parallel.
 fn partition_hooks(hooks: &[Arc<Hook>]) -> Vec<Vec<Arc<Hook>>> {
@@ -459,8 +996,7 @@ fn partition_hooks(hooks: &[Arc<Hook>]) -> Vec<Vec<Arc<Hook>>> {
         current_group.push(index);
 
         for i in 0..hooks.len() {
-            if !visited[i] && hooks[index].env_key_dependencies() == hooks[i].env_key_dependencies()
-            {
+            if !visited[i] && hooks[index].env_key() == hooks[i].env_key() {
                 dfs(i, hooks, visited, current_group);
             }
         }
@@ -486,7 +1022,6 @@ fn partition_hooks(hooks: &[Arc<Hook>]) -> Vec<Vec<Arc<Hook>>> {
 }
 
 struct StatusPrinter {
-    printer: Printer,
     columns: usize,
 }
 
@@ -496,24 +1031,24 @@ impl StatusPrinter {
     const SKIPPED: &'static str = "Skipped";
     const DRY_RUN: &'static str = "Dry Run";
     const NO_FILES: &'static str = "(no files to check)";
+    const NOT_IN_STAGE: &'static str = "(not in this stage)";
+    const SKIP_SELECTOR: &'static str = "(skip selector)";
+    const SKIP_ENV: &'static str = "(skipped by SKIP)";
+    const LOCAL_SKIP: &'static str = "(skipped locally)";
+    const DEFAULT_SKIP: &'static str = "(skipped by default)";
+    const UNSUPPORTED_PLATFORM: &'static str = "(unsupported platform)";
     const UNIMPLEMENTED: &'static str = "(unimplemented yet)";
+    const CANCELLED: &'static str = "Cancelled";
+    const CACHED: &'static str = "(cached)";
 
-    fn for_hooks(hooks: &[InstalledHook], printer: Printer) -> Self {
-        let name_len = hooks
-            .iter()
-            .map(|hook| hook.name.width())
-            .max()
-            .unwrap_or(0);
+    fn for_hooks<'a>(hooks: impl Iterator<Item = &'a InstalledHook>) -> Self {
+        let name_len = hooks.map(|hook| hook.name.width()).max().unwrap_or(0);
         let columns = std::cmp::max(
             79,
-            // Hook name...(no files to check)Skipped
-            name_len + 3 + Self::NO_FILES.len() + Self::SKIPPED.len(),
+            // Hook name...(unsupported platform)Skipped
+            name_len + 3 + Self::UNSUPPORTED_PLATFORM.len() + Self::SKIPPED.len(),
         );
-        Self { printer, columns }
-    }
-
-    fn printer(&self) -> Printer {
-        self.printer
+        Self { columns }
     }
 
     fn bar_len(&self) -> usize {
@@ -522,21 +1057,37 @@ impl StatusPrinter {
 
     fn write(
         &self,
+        out: &mut ProjectOutput,
         hook_name: &str,
         prefix: &str,
         status: RunStatus,
     ) -> Result<(), std::fmt::Error> {
         let (suffix, status_line, status_width) = match status {
-            RunStatus::NoFiles => (
-                Self::NO_FILES,
-                Self::SKIPPED.black().on_cyan().to_string(),
-                Self::SKIPPED.width(),
-            ),
-            RunStatus::Unimplemented => (
+            RunStatus::Skipped(SkipReason::Unimplemented) => (
                 Self::UNIMPLEMENTED,
                 Self::SKIPPED.black().on_yellow().to_string(),
                 Self::SKIPPED.width(),
             ),
+            RunStatus::Skipped(SkipReason::Cached) => (
+                Self::CACHED,
+                Self::PASSED.on_green().to_string(),
+                Self::PASSED.width(),
+            ),
+            RunStatus::Skipped(reason) => (
+                match reason {
+                    SkipReason::NoFiles => Self::NO_FILES,
+                    SkipReason::Stage => Self::NOT_IN_STAGE,
+                    SkipReason::Selector => Self::SKIP_SELECTOR,
+                    SkipReason::SkipEnv => Self::SKIP_ENV,
+                    SkipReason::LocalSkip => Self::LOCAL_SKIP,
+                    SkipReason::DefaultSkip => Self::DEFAULT_SKIP,
+                    SkipReason::Platform => Self::UNSUPPORTED_PLATFORM,
+                    SkipReason::Unimplemented => Self::UNIMPLEMENTED,
+                    SkipReason::Cached => Self::CACHED,
+                },
+                Self::SKIPPED.black().on_cyan().to_string(),
+                Self::SKIPPED.width(),
+            ),
             RunStatus::DryRun => (
                 "",
                 Self::DRY_RUN.on_yellow().to_string(),
@@ -548,6 +1099,11 @@ impl StatusPrinter {
                 Self::PASSED.width(),
             ),
             RunStatus::Failed => ("", Self::FAILED.on_red().to_string(), Self::FAILED.width()),
+            RunStatus::Cancelled => (
+                "",
+                Self::CANCELLED.black().on_yellow().to_string(),
+                Self::CANCELLED.width(),
+            ),
         };
         let (prefix, prefix_width) = if prefix.is_empty() {
             (String::new(), 0)
@@ -560,11 +1116,59 @@ impl StatusPrinter {
             ".".repeat(dots.max(0)),
         );
         match status {
-            RunStatus::Failed => {
-                writeln!(self.printer.stdout_important(), "{line}")
+            RunStatus::Failed => writeln!(out.stdout_important(), "{line}"),
+            _ => writeln!(out.stdout(), "{line}"),
+        }
+    }
+}
+
+/// The output of a project, buffered so that projects running concurrently don't interleave.
+#[derive(Default)]
+struct ProjectOutput {
+    /// Chunks of output, and whether they are important (shown even with `--quiet`).
+    chunks: Vec<(String, bool)>,
+}
+
+impl ProjectOutput {
+    fn stdout(&mut self) -> ProjectOutputWriter<'_> {
+        ProjectOutputWriter {
+            output: self,
+            important: false,
+        }
+    }
+
+    fn stdout_important(&mut self) -> ProjectOutputWriter<'_> {
+        ProjectOutputWriter {
+            output: self,
+            important: true,
+        }
+    }
+
+    /// Write the buffered output to the printer.
+    fn flush(&mut self, printer: Printer) -> std::fmt::Result {
+        for (chunk, important) in self.chunks.drain(..) {
+            if important {
+                printer.stdout_important().write_str(&chunk)?;
+            } else {
+                printer.stdout().write_str(&chunk)?;
             }
-            _ => writeln!(self.printer.stdout(), "{line}"),
         }
+        Ok(())
+    }
+}
+
+struct ProjectOutputWriter<'a> {
+    output: &'a mut ProjectOutput,
+    important: bool,
+}
+
+impl std::fmt::Write for ProjectOutputWriter<'_> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        match self.output.chunks.last_mut() {
+            Some((chunk, important)) if *important == self.important => chunk.push_str(s),
+            _ => self.output.chunks.push((s.to_string(), self.important)),
+        }
+        Ok(())
     }
 }
 
@@ -573,20 +1177,54 @@ impl StatusPrinter {
 async fn run_hooks(
     workspace: &Workspace,
     hooks: &[InstalledHook],
+    skipped_hooks: Vec<(InstalledHook, SkipReason)>,
     filenames: Vec<PathBuf>,
+    max_file_size: Option<u64>,
     store: &Store,
     show_diff_on_failure: bool,
-    fail_fast: bool,
+    reproduce_command: &str,
+    diff_context: Option<u32>,
+    word_diff: bool,
+    show_fixes: bool,
+    fail_fast: Option<FailFast>,
+    serial_projects: bool,
     dry_run: bool,
+    write_summary: Option<&Path>,
+    mut last_run: Option<LastRun>,
+    push_cache: Option<&PushCache>,
+    external_reporter: Option<&ExternalReporter>,
     verbose: bool,
     printer: Printer,
-) -> Result<ExitStatus> {
-    debug_assert!(!hooks.is_empty(), "No hooks to run");
+) -> Result<RunOutcome> {
+    debug_assert!(
+        !hooks.is_empty() || !skipped_hooks.is_empty(),
+        "No hooks to run or report"
+    );
 
-    let status_printer = StatusPrinter::for_hooks(hooks, printer);
+    let start = std::time::Instant::now();
+    let mut hook_runs = Vec::new();
+    let num_files = filenames.len();
+
+    let events = external_reporter.map(EventStream::spawn).transpose()?;
+    if let Some(events) = &events {
+        events.send(&Event::RunStarted {
+            version: env!("CARGO_PKG_VERSION"),
+            hooks: hooks.len(),
+            files: filenames.len(),
+        });
+    }
+
+    // Hooks skipped for not being in the stage are only shown with `--verbose`.
+    let skipped_hooks = skipped_hooks
+        .into_iter()
+        .filter(|(_, reason)| verbose || *reason != SkipReason::Stage)
+        .collect::<Vec<_>>();
+    let status_printer =
+        StatusPrinter::for_hooks(hooks.iter().chain(skipped_hooks.iter().map(|(h, _)| h)));
     let reporter = HookRunReporter::new(printer, status_printer.bar_len());
 
     let mut success = true;
+    let mut checks_failed = false;
 
     // Group hooks by project to run them in order of their depth in the workspace.
     #[allow(clippy::mutable_key_type)]
@@ -597,17 +1235,47 @@ async fn run_hooks(
             .or_default()
             .push(hook.clone());
     }
+    // Skipped hooks are shown in their place among the project's hooks, keyed by their index.
+    #[allow(clippy::mutable_key_type)]
+    let mut project_to_skipped: FxHashMap<&Project, FxHashMap<usize, SkipReason>> =
+        FxHashMap::default();
+    for (hook, reason) in &skipped_hooks {
+        project_to_skipped
+            .entry(hook.project())
+            .or_default()
+            .insert(hook.idx, *reason);
+        project_to_hooks
+            .entry(hook.project())
+            .or_default()
+            .push(hook.clone());
+    }
 
     let projects_len = project_to_hooks.len();
-    let mut first = true;
     let mut file_modified = false;
     let mut has_unimplemented = false;
+    let mut hidden_eol_fixes = Vec::new();
+    let mut fixes = Vec::new();
 
     // Track files that have been consumed by orphan projects.
+    // Filters must be computed in workspace order, so that nested orphan projects claim
+    // their files before their parents.
     let mut consumed_files = FxHashSet::default();
-
-    'outer: for project in workspace.all_projects() {
-        let filter = FileFilter::for_project(filenames.iter(), project, Some(&mut consumed_files));
+    let skip_worktree = if filenames.is_empty() {
+        FxHashSet::default()
+    } else {
+        git::ls_skip_worktree_files(workspace.root()).await?
+    };
+    let tags_cache = TagsCache::load(store, workspace.root());
+    let mut runs = Vec::new();
+    for project in workspace.all_projects() {
+        let filter = FileFilter::for_project(
+            filenames.iter(),
+            project,
+            Some(&mut consumed_files),
+            Some(&skip_worktree),
+        )
+        .with_max_file_size(max_file_size)
+        .with_tags_cache(&tags_cache);
 
         let Some(mut hooks) = project_to_hooks.remove(project) else {
             continue;
@@ -621,69 +1289,95 @@ async fn run_hooks(
         // If two hooks have the same priority, preserve their original order from the config.
         hooks.sort_by(|a, b| a.priority.cmp(&b.priority).then(a.idx.cmp(&b.idx)));
 
-        if projects_len > 1 || !project.is_root() {
-            reporter.suspend(|| {
-                writeln!(
-                    status_printer.printer().stdout(),
-                    "{}{}",
-                    if first { "" } else { "\n" },
-                    format!("Running hooks for `{}`:", project.to_string().cyan()).bold()
-                )
-            })?;
-            first = false;
-        }
-        let mut prev_diff = git::get_diff(project.path()).await?;
-
-        let project_fail_fast = fail_fast || project.config().fail_fast.unwrap_or(false);
-
-        for group_range in PriorityGroupRanges::new(&hooks) {
-            let group_hooks = hooks[group_range].to_vec();
-            let mut group_results =
-                run_priority_group(group_hooks, &filter, store, dry_run, &reporter).await?;
-
-            // Print results in a stable order (same order as config within the project).
-            group_results.sort_unstable_by(|a, b| a.hook.idx.cmp(&b.hook.idx));
-
-            // Check if any files were modified by this group of hooks.
-            let all_skipped = group_results.iter().all(|r| r.status.is_skipped());
-            let group_modified_files = if !all_skipped {
-                let curr_diff = git::get_diff(project.path()).await?;
-                let group_modified_files = curr_diff != prev_diff;
-                prev_diff = curr_diff;
-                group_modified_files
-            } else {
-                false
-            };
+        let show_header = projects_len > 1 || !project.is_root();
+        runs.push(ProjectRun {
+            project,
+            hooks,
+            skipped: project_to_skipped.remove(project).unwrap_or_default(),
+            filter,
+            show_header,
+            first: runs.is_empty(),
+        });
+    }
 
-            if group_modified_files {
-                file_modified = true;
-            }
+    let ctx = ProjectContext {
+        store,
+        status_printer: &status_printer,
+        reporter: &reporter,
+        fail_fast,
+        dry_run,
+        verbose,
+        collect_summaries: write_summary.is_some()
+            || last_run.is_some()
+            || events.is_some()
+            || *HYPERLINKS,
+        events: events.as_ref(),
+        push_cache,
+        show_fixes,
+        printer,
+        stop: Cell::new(false),
+    };
 
-            reporter.suspend(|| {
-                render_priority_group(
-                    printer,
-                    &status_printer,
-                    &group_results,
-                    verbose,
-                    group_modified_files,
-                )
-            })?;
-
-            let hook_fail_fast = apply_group_outcome(
-                &group_results,
-                group_modified_files,
-                &mut success,
-                &mut has_unimplemented,
-            );
+    let mut outcomes = Vec::with_capacity(runs.len());
+    if serial_projects || runs.len() == 1 {
+        for run in runs {
+            if ctx.stop.get() || is_cancelled() {
+                break;
+            }
+            let mut output = ProjectOutput::default();
+            outcomes.push(run_project(&ctx, run, &mut output, true).await?);
+        }
+    } else {
+        // Projects run concurrently in waves: a project waits for the projects nested in it,
+        // whose modifications it would otherwise see mid-way.
+        // Output is buffered per project and printed as each project completes, in order.
+        let ctx = &ctx;
+        for wave in project_waves(runs) {
+            let mut tasks = wave
+                .into_iter()
+                .map(|run| async move {
+                    let mut output = ProjectOutput::default();
+                    let outcome = run_project(ctx, run, &mut output, false).await;
+                    (outcome, output)
+                })
+                .collect::<FuturesOrdered<_>>();
+
+            while let Some((outcome, mut output)) = tasks.next().await {
+                reporter.suspend(|| output.flush(printer))?;
+                outcomes.push(outcome?);
+            }
 
-            if !success && (project_fail_fast || hook_fail_fast) {
-                break 'outer;
+            if ctx.stop.get() || is_cancelled() {
+                break;
             }
         }
     }
 
+    for outcome in outcomes {
+        success &= outcome.success;
+        checks_failed |= outcome.checks_failed;
+        file_modified |= outcome.file_modified;
+        has_unimplemented |= outcome.has_unimplemented;
+        hidden_eol_fixes.extend(outcome.hidden_eol_fixes);
+        fixes.extend(outcome.fixes);
+        hook_runs.extend(outcome.hooks);
+    }
+
+    if let Err(err) = tags_cache.save() {
+        debug!("Failed to save tags cache: {err}");
+    }
+
     reporter.on_complete();
 
+    let cancelled = is_cancelled();
+    if cancelled {
+        writeln!(
+            printer.stderr(),
+            "{}: Interrupted, remaining hooks were not run",
+            "error".red().bold(),
+        )?;
+    }
+
     if has_unimplemented {
         warn_user!(
             "Some hooks were skipped because their languages are unimplemented.\nWe're working hard to support more languages. Check out current support status at {}.",
@@ -693,17 +1387,29 @@ async fn run_hooks(
         );
     }
 
-    if !success && show_diff_on_failure && file_modified {
-        if EnvVars::is_set(EnvVars::CI) {
+    if !hidden_eol_fixes.is_empty() && git::has_autocrlf().await.unwrap_or(false) {
+        let hooks = hidden_eol_fixes
+            .iter()
+            .unique()
+            .map(|id| format!("`{id}`"))
+            .join(", ");
+        warn_user!(
+            "{hooks} rewrote line endings that git converts back on checkout because `core.autocrlf` is `true`, so the files will be fixed again on every run\nhint: Set `core.autocrlf` to `input`, or set `eol` in `.gitattributes` and add `--respect-gitattributes` to the hook args"
+        );
+    }
+
+    if !success && !cancelled && show_diff_on_failure && file_modified {
+        if let Some(ci) = CiProvider::detect() {
             writeln!(
                 printer.stdout(),
                 "{}",
                 indoc::formatdoc! {
                     "\n{}: Some hooks made changes to the files.
-                    If you are seeing this message in CI, reproduce locally with: `{}`
+                    If you are seeing this message in {}, reproduce locally with: `{}`
                     To run prek as part of git workflow, use `{}` to set up git hooks.\n",
                     "Hint".yellow().bold(),
-                    "prek run --all-files".cyan(),
+                    ci.name(),
+                    reproduce_command.cyan(),
                     "prek install".cyan()
                 }
             )?;
@@ -716,12 +1422,23 @@ async fn run_hooks(
         } else {
             "--color=never"
         };
-        git::git_cmd("git diff")?
-            .arg("--no-pager")
+        let mut cmd = git::git_cmd("git diff")?;
+        cmd.arg("--no-pager")
             .arg("diff")
             .arg("--no-ext-diff")
-            .arg(color)
-            .arg("--")
+            .arg(color);
+        if let Some(context) = diff_context {
+            cmd.arg(format!("--unified={context}"));
+        }
+        if word_diff {
+            // Without color, mark the changed words with `[-...-]` and `{+...+}` instead.
+            cmd.arg(if *USE_COLOR {
+                "--word-diff=color"
+            } else {
+                "--word-diff=plain"
+            });
+        }
+        cmd.arg("--")
             .arg(workspace.root())
             .check(true)
             .spawn()?
@@ -729,11 +1446,424 @@ async fn run_hooks(
             .await?;
     }
 
-    if success {
-        Ok(ExitStatus::Success)
+    if show_fixes && !cancelled && !fixes.is_empty() {
+        print_fixes(workspace.root(), &fixes, printer).await?;
+    }
+
+    let hook_summaries = hook_runs
+        .iter()
+        .map(|hook| hook.summary.clone())
+        .collect::<Vec<_>>();
+    if stats::is_enabled() && !dry_run {
+        if let Err(err) = stats::record(&store.stats_file(), &hook_summaries) {
+            warn!("Failed to record hook run stats: {err}");
+        }
+    }
+
+    let status = if cancelled {
+        SummaryStatus::Cancelled
+    } else if success {
+        SummaryStatus::Passed
+    } else {
+        SummaryStatus::Failed
+    };
+    if let Some(last_run) = &mut last_run {
+        last_run.status = status;
+        last_run.duration = start.elapsed().as_secs_f64();
+        last_run.files = num_files;
+        last_run.hooks = hook_runs;
+        if let Err(err) = last_run.save(&store.last_run_file()) {
+            warn!("Failed to save the summary of the run: {err}");
+        }
+    }
+
+    if write_summary.is_some() || events.is_some() || *HYPERLINKS {
+        let summary = RunSummary::new(hook_summaries, status, start.elapsed());
+        if *HYPERLINKS {
+            writeln!(printer.stdout(), "\n{}", summary.to_footer())?;
+        }
+        if let Some(path) = write_summary {
+            summary.write(path)?;
+        }
+        if let Some(events) = events {
+            events.send(&Event::RunFinished(&summary));
+            events.finish().await?;
+        }
+    }
+
+    Ok(RunOutcome {
+        success,
+        checks_failed,
+        cancelled,
+    })
+}
+
+/// How a run of the hooks ended.
+struct RunOutcome {
+    success: bool,
+    /// Whether a hook failed for another reason than modifying files.
+    checks_failed: bool,
+    cancelled: bool,
+}
+
+impl RunOutcome {
+    /// Whether the only failures are hooks that modified files.
+    fn only_modified(&self) -> bool {
+        !self.success && !self.checks_failed && !self.cancelled
+    }
+
+    fn exit_status(&self, modified_exit_code: Option<u8>) -> ExitStatus {
+        if self.cancelled {
+            ExitStatus::Interrupted
+        } else if self.success {
+            ExitStatus::Success
+        } else if let Some(code) = modified_exit_code
+            && self.only_modified()
+        {
+            ExitStatus::External(code)
+        } else {
+            ExitStatus::Failure
+        }
+    }
+}
+
+struct ProjectRun<'a> {
+    project: &'a Project,
+    hooks: Vec<InstalledHook>,
+    /// Hooks that are not run, by their index.
+    skipped: FxHashMap<usize, SkipReason>,
+    filter: FileFilter<'a>,
+    show_header: bool,
+    first: bool,
+}
+
+/// State shared by the projects of a run.
+#[allow(clippy::struct_excessive_bools)]
+struct ProjectContext<'a> {
+    store: &'a Store,
+    status_printer: &'a StatusPrinter,
+    reporter: &'a HookRunReporter,
+    fail_fast: Option<FailFast>,
+    dry_run: bool,
+    verbose: bool,
+    collect_summaries: bool,
+    /// The external reporter to send hook events to.
+    events: Option<&'a EventStream>,
+    /// Where hooks that passed on `pre-push` are recorded.
+    push_cache: Option<&'a PushCache>,
+    /// Whether to track the files modified by each hook for `--show-fixes`.
+    show_fixes: bool,
+    printer: Printer,
+    /// Set when a failing hook stops the whole run.
+    stop: Cell<bool>,
+}
+
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Default)]
+struct ProjectOutcome {
+    success: bool,
+    /// Whether a hook failed for another reason than modifying files.
+    checks_failed: bool,
+    file_modified: bool,
+    has_unimplemented: bool,
+    /// Line ending fixers that rewrote files without changing the git diff.
+    hidden_eol_fixes: Vec<String>,
+    /// The files modified by each priority group, with `--show-fixes`.
+    fixes: Vec<HookFixes>,
+    hooks: Vec<LastRunHook>,
+}
+
+/// Split projects into waves of projects that can run concurrently.
+///
+/// A project runs in a later wave than every earlier project it overlaps with (one contains the
+/// other), so nested projects keep running in workspace order.
+fn project_waves(runs: Vec<ProjectRun<'_>>) -> Vec<Vec<ProjectRun<'_>>> {
+    let mut levels: Vec<usize> = Vec::with_capacity(runs.len());
+    for (i, run) in runs.iter().enumerate() {
+        let path = run.project.relative_path();
+        let level = runs[..i]
+            .iter()
+            .zip(&levels)
+            .filter(|(prev, _)| {
+                let prev = prev.project.relative_path();
+                prev.starts_with(path) || path.starts_with(prev)
+            })
+            .map(|(_, level)| level + 1)
+            .max()
+            .unwrap_or(0);
+        levels.push(level);
+    }
+
+    let mut waves: Vec<Vec<ProjectRun>> = Vec::new();
+    for (run, level) in runs.into_iter().zip(levels) {
+        if waves.len() <= level {
+            waves.resize_with(level + 1, Vec::new);
+        }
+        waves[level].push(run);
+    }
+    waves
+}
+
+/// Run the hooks of a project, writing its output to `output`.
+///
+/// If `stream` is true, the output is flushed to the printer after each priority group.
+async fn run_project(
+    ctx: &ProjectContext<'_>,
+    run: ProjectRun<'_>,
+    output: &mut ProjectOutput,
+    stream: bool,
+) -> Result<ProjectOutcome> {
+    let ProjectRun {
+        project,
+        hooks,
+        skipped,
+        filter,
+        show_header,
+        first,
+    } = run;
+
+    let mut outcome = ProjectOutcome {
+        success: true,
+        ..ProjectOutcome::default()
+    };
+
+    if show_header {
+        writeln!(
+            output.stdout(),
+            "{}{}",
+            if first { "" } else { "\n" },
+            format!("Running hooks for `{}`:", project.to_string().cyan()).bold()
+        )?;
+        if stream {
+            ctx.reporter.suspend(|| output.flush(ctx.printer))?;
+        }
+    }
+    let mut prev_diff = git::get_diff(project.path()).await?;
+    let mut unstaged_files = if ctx.show_fixes {
+        Some(UnstagedFiles::read(project.path()).await?)
     } else {
-        Ok(ExitStatus::Failure)
+        None
+    };
+
+    // The command line option takes precedence over the configuration of the project.
+    let project_fail_fast = ctx
+        .fail_fast
+        .or(project.config().fail_fast)
+        .unwrap_or_default();
+
+    let groups = PriorityGroupRanges::new(&hooks).collect::<Vec<_>>();
+    let overlap = hooks
+        .iter()
+        .map(|hook| GroupOverlap::of(hook, skipped.contains_key(&hook.idx), project_fail_fast))
+        .collect::<Vec<_>>();
+    // Each group has its own token, so `fail_fast: group` only cancels the hooks of its group.
+    let cancel = groups.iter().map(|_| scope_token()).collect::<Vec<_>>();
+    let mut pending = groups.iter().map(|_| Vec::new()).collect::<Vec<_>>();
+    let mut started = vec![false; hooks.len()];
+    let mut finished = vec![false; hooks.len()];
+    let mut running = FuturesUnordered::new();
+    // All hooks share the global batch slots (see `run::run_by_batch`), which bounds the number
+    // of running processes.
+    let mut start = |group: usize, pos: usize, running: &mut FuturesUnordered<_>| {
+        if std::mem::replace(&mut started[pos], true) {
+            return;
+        }
+        let hook = hooks[pos].clone();
+        let skip = skipped.get(&hook.idx).copied();
+        let future = cancellable(
+            cancel[group].clone(),
+            run_hook(
+                hook,
+                skip,
+                &filter,
+                ctx.store,
+                ctx.dry_run,
+                ctx.reporter,
+                ctx.events,
+            ),
+        );
+        running.push(async move { (group, pos, future.await) });
+    };
+
+    for (current, group_range) in groups.iter().enumerate() {
+        if ctx.stop.get() || is_cancelled() {
+            break;
+        }
+
+        debug!(
+            "Running priority group with priority {} with concurrency {}: {:?}",
+            hooks[group_range.start].priority,
+            *CONCURRENCY,
+            hooks[group_range.clone()]
+                .iter()
+                .map(|h| &h.id)
+                .collect::<Vec<_>>()
+        );
+        for pos in group_range.clone() {
+            start(current, pos, &mut running);
+        }
+
+        while group_range.clone().any(|pos| !finished[pos]) {
+            // Start the hooks of later groups that can't observe the difference: as long as the
+            // unfinished hooks of the groups before them leave the files alone and can't stop
+            // the project, they see the same files they would see after these groups.
+            let mut blocked = false;
+            for (group, range) in groups.iter().enumerate().skip(current) {
+                if blocked {
+                    break;
+                }
+                if group > current {
+                    for pos in range.clone() {
+                        if overlap[pos] == GroupOverlap::Always {
+                            start(group, pos, &mut running);
+                        }
+                    }
+                }
+                blocked = range.clone().any(|pos| match overlap[pos] {
+                    GroupOverlap::Never => true,
+                    GroupOverlap::WhenFinished => !finished[pos],
+                    GroupOverlap::Always => false,
+                });
+            }
+
+            let (group, pos, result) = running
+                .next()
+                .await
+                .expect("the current group has unfinished hooks");
+            let result = result?;
+            finished[pos] = true;
+            // The other hooks are asked to terminate, like on an interrupt, and report as cancelled.
+            if result.status == RunStatus::Failed
+                && project_fail_fast.max(result.hook.fail_fast) == FailFast::Group
+            {
+                cancel[group].cancel();
+            }
+            if result.status == RunStatus::Cancelled {
+                ctx.reporter.on_run_cancelled(&result.hook);
+            }
+            pending[group].push(result);
+        }
+        let mut group_results = std::mem::take(&mut pending[current]);
+
+        // Print results in a stable order (same order as config within the project).
+        group_results.sort_unstable_by(|a, b| a.hook.idx.cmp(&b.hook.idx));
+
+        // Check if any files were modified by this group of hooks.
+        let all_skipped = group_results.iter().all(|r| r.status.is_skipped());
+        let group_modified_files = if !all_skipped {
+            let curr_diff = git::get_diff(project.path()).await?;
+            let group_modified_files = curr_diff != prev_diff;
+            prev_diff = curr_diff;
+            group_modified_files
+        } else {
+            false
+        };
+
+        if group_modified_files {
+            outcome.file_modified = true;
+            if let Some(unstaged_files) = &mut unstaged_files {
+                let files = unstaged_files.modified().await?;
+                if !files.is_empty() {
+                    let hooks = group_results
+                        .iter()
+                        .filter(|result| !result.status.is_skipped())
+                        .map(|result| {
+                            if project.is_root() {
+                                result.hook.id.clone()
+                            } else {
+                                result.hook.full_id()
+                            }
+                        })
+                        .collect();
+                    outcome.fixes.push(HookFixes { hooks, files });
+                }
+            }
+        } else {
+            // With `core.autocrlf`, git doesn't see changes that only convert line endings.
+            outcome.hidden_eol_fixes.extend(
+                group_results
+                    .iter()
+                    .filter(|result| result.fixed_line_endings())
+                    .map(|result| result.hook.id.clone()),
+            );
+        }
+
+        if let Some(push_cache) = ctx.push_cache
+            && !group_modified_files
+        {
+            for result in &group_results {
+                if result.status == RunStatus::Success {
+                    push_cache.record(&result.hook);
+                }
+            }
+        }
+
+        render_priority_group(
+            output,
+            ctx.status_printer,
+            &group_results,
+            ctx.verbose,
+            group_modified_files,
+        )?;
+        if stream {
+            ctx.reporter.suspend(|| output.flush(ctx.printer))?;
+        }
+
+        let hook_fail_fast = apply_group_outcome(
+            &group_results,
+            group_modified_files,
+            &mut outcome.success,
+            &mut outcome.checks_failed,
+            &mut outcome.has_unimplemented,
+        );
+
+        if ctx.collect_summaries {
+            for result in &group_results {
+                let summary = result.to_summary(group_modified_files);
+                if let Some(events) = ctx.events {
+                    events.send(&Event::HookFinished {
+                        hook: &summary,
+                        files: result.files,
+                    });
+                }
+                let failed_files = if summary.status == SummaryStatus::Failed {
+                    result
+                        .failed_files(&filter)
+                        .into_iter()
+                        .map(|file| project.relative_path().join(file))
+                        .collect()
+                } else {
+                    Vec::new()
+                };
+                outcome.hooks.push(LastRunHook {
+                    summary,
+                    files: result.files,
+                    failed_files,
+                });
+            }
+        }
+
+        if !outcome.success {
+            match project_fail_fast.max(hook_fail_fast) {
+                FailFast::Workspace => {
+                    ctx.stop.set(true);
+                    break;
+                }
+                FailFast::Project => break,
+                FailFast::Group | FailFast::Off => {}
+            }
+        }
     }
+
+    // Hooks started ahead of a group that won't run anymore are discarded.
+    if !running.is_empty() {
+        for token in &cancel {
+            token.cancel();
+        }
+        while running.next().await.is_some() {}
+    }
+
+    Ok(outcome)
 }
 
 struct PriorityGroupRanges<'a> {
@@ -766,36 +1896,36 @@ impl Iterator for PriorityGroupRanges<'_> {
     }
 }
 
-async fn run_priority_group(
-    group_hooks: Vec<InstalledHook>,
-    filter: &FileFilter<'_>,
-    store: &Store,
-    dry_run: bool,
-    reporter: &HookRunReporter,
-) -> Result<Vec<RunResult>> {
-    debug!(
-        "Running priority group with priority {} with concurrency {}: {:?}",
-        group_hooks[0].priority,
-        *CONCURRENCY,
-        group_hooks.iter().map(|h| &h.id).collect::<Vec<_>>()
-    );
-
-    let mut results = futures::stream::iter(
-        group_hooks
-            .into_iter()
-            .map(|hook| run_hook(hook, filter, store, dry_run, reporter)),
-    )
-    .buffer_unordered(*CONCURRENCY);
+/// Whether the hooks of later priority groups may start while a hook is running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GroupOverlap {
+    /// The hook doesn't run, or only reads files.
+    Always,
+    /// The hook may modify files, later groups must wait until it has finished.
+    WhenFinished,
+    /// A failure of the hook skips the later groups, or it has ordering constraints.
+    Never,
+}
 
-    let mut group_results = Vec::new();
-    while let Some(result) = results.next().await {
-        group_results.push(result?);
+impl GroupOverlap {
+    fn of(hook: &Hook, skipped: bool, fail_fast: FailFast) -> Self {
+        if skipped {
+            Self::Always
+        } else if fail_fast.max(hook.fail_fast) > FailFast::Group
+            || !hook.after.is_empty()
+            || !hook.before.is_empty()
+        {
+            Self::Never
+        } else if hook.kind == Some(HookKind::Checker) {
+            Self::Always
+        } else {
+            Self::WhenFinished
+        }
     }
-    Ok(group_results)
 }
 
 fn render_priority_group(
-    printer: Printer,
+    output: &mut ProjectOutput,
     status_printer: &StatusPrinter,
     group_results: &[RunResult],
     verbose: bool,
@@ -813,6 +1943,7 @@ fn render_priority_group(
 
     if show_group_ui {
         status_printer.write(
+            output,
             "Files were modified by following hooks",
             "",
             RunStatus::Failed,
@@ -839,22 +1970,26 @@ fn render_priority_group(
             result.status
         };
 
-        status_printer.write(&result.hook.name, prefix, status)?;
+        status_printer.write(output, &result.hook.name, prefix, status)?;
 
-        if matches!(status, RunStatus::NoFiles | RunStatus::Unimplemented) {
+        if matches!(status, RunStatus::Skipped(_) | RunStatus::Cancelled) {
             continue;
         }
 
         let mut stdout = match status {
-            RunStatus::Failed => printer.stdout_important(),
-            _ => printer.stdout(),
+            RunStatus::Failed => output.stdout_important(),
+            _ => output.stdout(),
         };
 
         if verbose || result.hook.verbose || status == RunStatus::Failed {
+            let hook_id = match hook_url(&result.hook) {
+                Some(url) if *HYPERLINKS => hyperlink(&result.hook.id, url),
+                _ => result.hook.id.clone(),
+            };
             writeln!(
                 stdout,
                 "{group_prefix}{}",
-                format!("- hook id: {}", result.hook.id).dimmed()
+                format!("- hook id: {hook_id}").dimmed()
             )?;
             if verbose || result.hook.verbose {
                 writeln!(
@@ -863,11 +1998,38 @@ fn render_priority_group(
                     format!("- duration: {:.2?}s", result.duration.as_secs_f64()).dimmed()
                 )?;
             }
+            if result.attempts > 1 {
+                writeln!(
+                    stdout,
+                    "{group_prefix}{}",
+                    format!(
+                        "- attempts: {} of {}",
+                        result.attempts,
+                        result.hook.retries + 1
+                    )
+                    .dimmed()
+                )?;
+            }
             if result.exit_status != 0 {
+                let success_exit_codes = &result.hook.success_exit_codes;
+                let expected = if success_exit_codes.as_slice() != [0]
+                    && !success_exit_codes.contains(&result.exit_status)
+                {
+                    format!(" (expected {})", success_exit_codes.iter().join(", "))
+                } else {
+                    String::new()
+                };
                 writeln!(
                     stdout,
                     "{group_prefix}{}",
-                    format!("- exit code: {}", result.exit_status).dimmed()
+                    format!("- exit code: {}{expected}", result.exit_status).dimmed()
+                )?;
+            }
+            if result.failed_on_output() {
+                writeln!(
+                    stdout,
+                    "{group_prefix}{}",
+                    "- failed because the hook printed output (`fail_on_output`)".dimmed()
                 )?;
             }
             if single_hook_modified_files {
@@ -895,6 +2057,11 @@ fn render_priority_group(
                     }
                     let text = String::from_utf8_lossy(output);
                     for line in text.lines() {
+                        let line = if *HYPERLINKS && status == RunStatus::Failed {
+                            link_file_path(line, result.hook.project().path())
+                        } else {
+                            Cow::Borrowed(line)
+                        };
                         if line.is_empty() {
                             if show_group_ui {
                                 writeln!(stdout, "{}", "  │".dimmed())?;
@@ -917,13 +2084,50 @@ fn render_priority_group(
     Ok(())
 }
 
+/// The URL of the repository a hook comes from, if it's a web URL.
+fn hook_url(hook: &Hook) -> Option<&str> {
+    match hook.repo() {
+        Repo::Remote { url, .. } if url.starts_with("https://") || url.starts_with("http://") => {
+            Some(url.trim_end_matches(".git"))
+        }
+        _ => None,
+    }
+}
+
+/// Render a file path at the start of a line of hook output (like `src/main.py:1:5: ...`)
+/// as a `file://` hyperlink, so terminals can open it.
+fn link_file_path<'a>(line: &'a str, root: &Path) -> Cow<'a, str> {
+    let path = line.split(':').next().unwrap_or(line).trim_end();
+    if path.is_empty() {
+        return Cow::Borrowed(line);
+    }
+    let file = root.join(path);
+    if !file.is_file() {
+        return Cow::Borrowed(line);
+    }
+    let Ok(url) = url::Url::from_file_path(std::path::absolute(&file).unwrap_or(file)) else {
+        return Cow::Borrowed(line);
+    };
+    Cow::Owned(format!(
+        "{}{}",
+        hyperlink(path, url.as_str()),
+        &line[path.len()..]
+    ))
+}
+
 fn apply_group_outcome(
     group_results: &[RunResult],
     group_modified_files: bool,
     success: &mut bool,
+    checks_failed: &mut bool,
     has_unimplemented: &mut bool,
-) -> bool {
-    let mut hook_fail_fast = false;
+) -> FailFast {
+    let mut hook_fail_fast = FailFast::Off;
+    // Files are only attributed to a hook that ran alone in its group.
+    let ran = group_results
+        .iter()
+        .filter(|result| matches!(result.status, RunStatus::Success | RunStatus::Failed))
+        .count();
 
     for RunResult { hook, status, .. } in group_results {
         *has_unimplemented |= status.is_unimplemented();
@@ -934,9 +2138,20 @@ fn apply_group_outcome(
             status.as_bool()
         };
         *success &= ok;
+        // Fixers commonly exit with an error after modifying files, so their failures are
+        // attributed to the modifications: those of hooks marked `kind: fixer` in a group that
+        // modified files, and those of a hook that modified files on its own.
+        let modified_files = group_modified_files
+            && match hook.kind {
+                Some(HookKind::Fixer) => true,
+                Some(HookKind::Checker) => false,
+                None => ran == 1,
+            };
+        *checks_failed |= *status == RunStatus::Failed && !modified_files;
 
-        if !ok && hook.fail_fast {
-            hook_fail_fast = true;
+        // Hooks cancelled by a failure in the group don't widen the scope.
+        if !ok && *status != RunStatus::Cancelled {
+            hook_fail_fast = hook_fail_fast.max(hook.fail_fast);
         }
     }
 
@@ -956,24 +2171,21 @@ enum RunStatus {
     Success,
     Failed,
     DryRun,
-    NoFiles,
-    Unimplemented,
+    Skipped(SkipReason),
+    Cancelled,
 }
 
 impl RunStatus {
     fn as_bool(self) -> bool {
-        matches!(
-            self,
-            Self::Success | Self::NoFiles | Self::DryRun | Self::Unimplemented
-        )
+        matches!(self, Self::Success | Self::DryRun | Self::Skipped(_))
     }
 
     fn is_unimplemented(self) -> bool {
-        matches!(self, Self::Unimplemented)
+        matches!(self, Self::Skipped(SkipReason::Unimplemented))
     }
 
     fn is_skipped(self) -> bool {
-        matches!(self, Self::DryRun | Self::NoFiles | Self::Unimplemented)
+        matches!(self, Self::DryRun | Self::Skipped(_))
     }
 }
 
@@ -983,6 +2195,10 @@ struct RunResult {
     duration: std::time::Duration,
     exit_status: i32,
     output: Vec<u8>,
+    /// Number of files the hook ran on.
+    files: usize,
+    /// Number of times the hook was run, including retries.
+    attempts: u32,
 }
 
 impl RunResult {
@@ -993,17 +2209,82 @@ impl RunResult {
             duration: std::time::Duration::ZERO,
             exit_status: 0,
             output: Vec::new(),
+            files: 0,
+            attempts: 0,
+        }
+    }
+
+    /// Whether the hook exited successfully, but failed because of `fail_on_output`.
+    fn failed_on_output(&self) -> bool {
+        self.status == RunStatus::Failed
+            && self.hook.fail_on_output
+            && self.hook.success_exit_codes.contains(&self.exit_status)
+    }
+
+    /// Whether a line ending fixer failed because it rewrote files.
+    fn fixed_line_endings(&self) -> bool {
+        self.status == RunStatus::Failed
+            && LINE_ENDING_FIXERS.contains(&self.hook.id.as_str())
+            && self.output.contains_str("Fixing ")
+    }
+
+    /// The files the hook failed on: the ones it mentioned in its output, or else all the files
+    /// it ran on.
+    fn failed_files<'a>(&self, filter: &'a FileFilter<'_>) -> Vec<&'a Path> {
+        let files = filter.for_hook(&self.hook);
+        let mentioned = files
+            .iter()
+            .filter(|file| {
+                file.to_str()
+                    .is_some_and(|file| self.output.contains_str(file))
+            })
+            .copied()
+            .collect::<Vec<_>>();
+        if mentioned.is_empty() {
+            files
+        } else {
+            mentioned
+        }
+    }
+
+    fn to_summary(&self, modified_files: bool) -> HookSummary {
+        let status = match self.status {
+            RunStatus::Success if modified_files => SummaryStatus::Failed,
+            RunStatus::Success => SummaryStatus::Passed,
+            RunStatus::Failed => SummaryStatus::Failed,
+            RunStatus::DryRun => SummaryStatus::DryRun,
+            RunStatus::Skipped(_) => SummaryStatus::Skipped,
+            RunStatus::Cancelled => SummaryStatus::Cancelled,
+        };
+        let skip_reason = match self.status {
+            RunStatus::Skipped(reason) => Some(reason),
+            _ => None,
+        };
+        HookSummary {
+            id: self.hook.id.clone(),
+            name: self.hook.name.clone(),
+            project: self.hook.project().to_string(),
+            status,
+            skip_reason,
+            exit_code: self.exit_status,
+            duration: self.duration.as_secs_f64(),
         }
     }
 }
 
 async fn run_hook(
     hook: InstalledHook,
+    skip: Option<SkipReason>,
     filter: &FileFilter<'_>,
     store: &Store,
     dry_run: bool,
     reporter: &HookRunReporter,
+    events: Option<&EventStream>,
 ) -> Result<RunResult> {
+    if let Some(reason) = skip {
+        return Ok(RunResult::from_status(hook, RunStatus::Skipped(reason)));
+    }
+
     let mut filenames = filter.for_hook(&hook);
     trace!(
         "Files for hook `{}` after filtered: {}",
@@ -1011,14 +2292,54 @@ async fn run_hook(
         filenames.len()
     );
 
+    if let Some(max_file_size) = filter.max_file_size(&hook) {
+        let oversized = filter.take_oversized(&mut filenames, max_file_size);
+        if !oversized.is_empty() {
+            warn_user!(
+                "Skipped files larger than {max_file_size} bytes for hook `{}`: `{}`\nSet `max_file_size` on the hook to change the limit for it.",
+                hook.id,
+                oversized.iter().map(|file| file.display()).join("`, `")
+            );
+        }
+    }
+
+    if is_cancelled() {
+        return Ok(RunResult::from_status(hook, RunStatus::Cancelled));
+    }
     if filenames.is_empty() && !hook.always_run {
-        return Ok(RunResult::from_status(hook, RunStatus::NoFiles));
+        return Ok(RunResult::from_status(
+            hook,
+            RunStatus::Skipped(SkipReason::NoFiles),
+        ));
     }
     if !Language::supported(hook.language) {
-        return Ok(RunResult::from_status(hook, RunStatus::Unimplemented));
+        return Ok(RunResult::from_status(
+            hook,
+            RunStatus::Skipped(SkipReason::Unimplemented),
+        ));
+    }
+    let files = filenames.len();
+    if let Some(events) = events {
+        events.send(&Event::HookStarted {
+            id: &hook.id,
+            name: &hook.name,
+            project: hook.project().to_string(),
+            files,
+        });
     }
     let start = std::time::Instant::now();
 
+    if hook.kind == Some(HookKind::Fixer) && !dry_run {
+        let skip_worktree = filter.skip_worktree_files(&filenames);
+        if !skip_worktree.is_empty() {
+            warn_user!(
+                "Hook `{}` may modify files marked `skip-worktree` or `assume-unchanged`, git will not notice changes to them: `{}`\nSet `exclude_skip_worktree: true` in the config to skip these files.",
+                hook.id,
+                skip_worktree.iter().map(|file| file.display()).join(", ")
+            );
+        }
+    }
+
     let filenames = if hook.pass_filenames {
         shuffle(&mut filenames);
         filenames
@@ -1026,35 +2347,58 @@ async fn run_hook(
         vec![]
     };
 
-    let (exit_status, hook_output) = if dry_run {
-        let mut output = Vec::new();
-        if !filenames.is_empty() {
-            writeln!(
-                output,
-                "`{}` would be run on {} files:",
-                hook,
-                filenames.len()
-            )?;
+    // Known-flaky hooks can be retried before being reported as failed.
+    let mut attempts = 1;
+    let (exit_status, hook_output, run_status) = loop {
+        let (exit_status, hook_output) = if dry_run {
+            (0, dry_run_output(&hook, &filenames)?)
+        } else {
+            match hook.language.run(&hook, &filenames, store, reporter).await {
+                Ok(result) => result,
+                Err(_) if is_cancelled() => {
+                    return Ok(RunResult {
+                        duration: start.elapsed(),
+                        files,
+                        attempts,
+                        ..RunResult::from_status(hook, RunStatus::Cancelled)
+                    });
+                }
+                Err(err) => return Err(err.context(format!("Failed to run hook `{hook}`"))),
+            }
+        };
+
+        let run_status = if dry_run {
+            RunStatus::DryRun
+        } else if !hook.success_exit_codes.contains(&exit_status)
+            || (hook.fail_on_output && !hook_output.trim_ascii().is_empty())
+        {
+            RunStatus::Failed
+        } else {
+            RunStatus::Success
+        };
+
+        if run_status != RunStatus::Failed || attempts > hook.retries || is_cancelled() {
+            break (exit_status, hook_output, run_status);
         }
-        for filename in filenames {
-            writeln!(output, "- {}", filename.display())?;
+
+        debug!(
+            "Hook `{hook}` failed with exit code {exit_status}, retrying (attempt {} of {})",
+            attempts + 1,
+            hook.retries + 1
+        );
+        attempts += 1;
+        if !hook.retry_delay.is_zero() {
+            tokio::time::sleep(hook.retry_delay).await;
         }
-        (0, output)
-    } else {
-        hook.language
-            .run(&hook, &filenames, store, reporter)
-            .await
-            .with_context(|| format!("Failed to run hook `{hook}`"))?
     };
 
     let duration = start.elapsed();
 
-    let run_status = if dry_run {
-        RunStatus::DryRun
-    } else if exit_status == 0 {
-        RunStatus::Success
+    // Hooks may force colors regardless of `--color=never`.
+    let hook_output = if anstream::ColorChoice::global() == anstream::ColorChoice::Never {
+        anstream::adapter::strip_bytes(&hook_output).into_vec()
     } else {
-        RunStatus::Failed
+        hook_output
     };
 
     Ok(RunResult {
@@ -1063,5 +2407,68 @@ async fn run_hook(
         duration,
         exit_status,
         output: hook_output,
+        files,
+        attempts,
     })
 }
+
+/// Describe what running the hook would do: the command line, the environment it runs in
+/// and the file batches it would be run on.
+fn dry_run_output(hook: &InstalledHook, filenames: &[&Path]) -> Result<Vec<u8>> {
+    let entry = hook.entry.split()?;
+    let command = entry.iter().chain(&hook.args).map(String::as_str);
+    let command =
+        shlex::try_join(command.clone()).unwrap_or_else(|_| command.collect::<Vec<_>>().join(" "));
+
+    let mut output = Vec::new();
+    writeln!(output, "command: {command}")?;
+    if let Some(env_path) = hook.env_path() {
+        writeln!(output, "environment: {}", env_path.display())?;
+    }
+
+    // Variables prek sets for the stage, then the ones configured on the hook.
+    let mut env = std::env::vars_os()
+        .filter_map(|(key, value)| {
+            let key = key.into_string().ok()?;
+            (key.starts_with("PRE_COMMIT") && !hook.env.contains_key(&key))
+                .then(|| (key, value.to_string_lossy().into_owned()))
+        })
+        .collect::<Vec<_>>();
+    env.sort_unstable();
+    let mut hook_env = hook
+        .env
+        .iter()
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect::<Vec<_>>();
+    hook_env.sort_unstable();
+    env.extend(hook_env);
+    if !env.is_empty() {
+        writeln!(output, "env:")?;
+        for (key, value) in env {
+            writeln!(output, "  {key}={value}")?;
+        }
+    }
+
+    if filenames.is_empty() {
+        return Ok(output);
+    }
+    let batches = crate::run::batches(hook, &entry, filenames)?;
+    writeln!(
+        output,
+        "`{}` would be run on {} files in {} batch{}:",
+        hook,
+        filenames.len(),
+        batches.len(),
+        if batches.len() == 1 { "" } else { "es" },
+    )?;
+    for (i, batch) in batches.iter().enumerate() {
+        if batches.len() > 1 {
+            writeln!(output, "batch {} ({} files):", i + 1, batch.len())?;
+        }
+        for filename in *batch {
+            writeln!(output, "- {}", filename.display())?;
+        }
+    }
+
+    Ok(output)
+}
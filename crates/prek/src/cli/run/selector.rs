@@ -3,11 +3,20 @@ use std::fmt::Display;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
+use crate::config::Language;
+use crate::fs::Simplified;
+use crate::git::GIT_ROOT;
 use crate::hook::Hook;
+use crate::local_skips::LocalSkips;
+use crate::settings;
 use crate::warn_user;
+use crate::workspace::Workspace;
 
 use anyhow::anyhow;
+use clap::ValueEnum;
+use globset::{Glob, GlobMatcher};
 use itertools::Itertools;
+use owo_colors::OwoColorize;
 use path_clean::PathClean;
 use prek_consts::env_vars::EnvVars;
 use rustc_hash::FxHashSet;
@@ -28,6 +37,13 @@ pub(crate) enum Error {
         #[source]
         source: anyhow::Error,
     },
+
+    #[error("Invalid `default_skips` in `{config}`")]
+    DefaultSkips {
+        config: String,
+        #[source]
+        source: anyhow::Error,
+    },
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -35,27 +51,77 @@ pub(crate) enum SelectorSource {
     CliArg,
     CliFlag(&'static str),
     EnvVar(&'static str),
+    /// A key of the user settings.
+    Settings(&'static str),
+    /// The skips of the local repository, managed by `prek skip`.
+    LocalSkips,
+    /// The `default_skips` of a project config.
+    DefaultSkips,
+}
+
+/// A hook id, or a glob pattern of hook ids like `lint-*`.
+#[derive(Debug, Clone)]
+pub(crate) struct HookPattern {
+    pattern: String,
+    glob: Option<GlobMatcher>,
+}
+
+impl HookPattern {
+    fn new(pattern: &str) -> Result<Self, globset::Error> {
+        let glob = if pattern.contains(['*', '?', '[']) {
+            Some(Glob::new(pattern)?.compile_matcher())
+        } else {
+            None
+        };
+        Ok(Self {
+            pattern: pattern.to_string(),
+            glob,
+        })
+    }
+
+    fn matches(&self, hook_id: &str) -> bool {
+        match &self.glob {
+            Some(glob) => glob.is_match(hook_id),
+            None => self.pattern == hook_id,
+        }
+    }
+
+    fn matches_hook(&self, hook: &Hook) -> bool {
+        self.matches(&hook.id) || self.matches(&hook.alias)
+    }
+}
+
+impl Display for HookPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.pattern)
+    }
 }
 
 #[derive(Debug, Clone)]
 pub(crate) enum SelectorExpr {
-    HookId(String),
+    HookId(HookPattern),
     ProjectPrefix(PathBuf),
     ProjectHook {
         project_path: PathBuf,
-        hook_id: String,
+        hook_id: HookPattern,
     },
+    Language(Language),
 }
 
 #[derive(Debug, Clone)]
 pub(crate) struct Selector {
     source: SelectorSource,
     original: String,
+    /// Whether the selector excludes what it matches, written with a `!` prefix.
+    negated: bool,
     expr: SelectorExpr,
 }
 
 impl Display for Selector {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.negated {
+            write!(f, "!")?;
+        }
         match &self.expr {
             SelectorExpr::HookId(hook_id) => write!(f, "{hook_id}"),
             SelectorExpr::ProjectPrefix(project_path) => {
@@ -75,6 +141,7 @@ impl Display for Selector {
                     write!(f, "{}:{hook_id}", project_path.display())
                 }
             }
+            SelectorExpr::Language(language) => write!(f, "language:{language}"),
         }
     }
 }
@@ -82,17 +149,25 @@ impl Display for Selector {
 impl Selector {
     pub(crate) fn as_flag(&self) -> Cow<'_, str> {
         match &self.source {
-            SelectorSource::CliArg => Cow::Borrowed(&self.original),
+            SelectorSource::CliArg | SelectorSource::LocalSkips | SelectorSource::DefaultSkips => {
+                Cow::Borrowed(&self.original)
+            }
             SelectorSource::CliFlag(flag) => Cow::Owned(format!("{}={}", flag, self.original)),
-            SelectorSource::EnvVar(var) => Cow::Owned(format!("{}={}", var, self.original)),
+            SelectorSource::EnvVar(var) | SelectorSource::Settings(var) => {
+                Cow::Owned(format!("{}={}", var, self.original))
+            }
         }
     }
 
     pub(crate) fn as_normalized_flag(&self) -> String {
         match &self.source {
-            SelectorSource::CliArg => self.to_string(),
+            SelectorSource::CliArg | SelectorSource::LocalSkips | SelectorSource::DefaultSkips => {
+                self.to_string()
+            }
             SelectorSource::CliFlag(flag) => format!("{flag}={self}"),
-            SelectorSource::EnvVar(var) => format!("{var}={self}"),
+            SelectorSource::EnvVar(var) | SelectorSource::Settings(var) => {
+                format!("{var}={self}")
+            }
         }
     }
 
@@ -102,7 +177,9 @@ impl Selector {
 
     pub(crate) fn kind_str(&self) -> &'static str {
         match &self.expr {
-            SelectorExpr::HookId(_) | SelectorExpr::ProjectHook { .. } => "hooks",
+            SelectorExpr::HookId(_)
+            | SelectorExpr::ProjectHook { .. }
+            | SelectorExpr::Language(_) => "hooks",
             SelectorExpr::ProjectPrefix(_) => "projects",
         }
     }
@@ -113,7 +190,7 @@ impl Selector {
         match &self.expr {
             SelectorExpr::HookId(hook_id) => {
                 // For bare hook IDs, check if it matches the hook
-                &hook.id == hook_id || &hook.alias == hook_id
+                hook_id.matches_hook(hook)
             }
             SelectorExpr::ProjectPrefix(project_path) => {
                 // For project paths, check if the hook belongs to that project.
@@ -124,9 +201,9 @@ impl Selector {
                 hook_id,
             } => {
                 // For project:hook syntax, check both
-                (&hook.id == hook_id || &hook.alias == hook_id)
-                    && project_path == hook.project().relative_path()
+                hook_id.matches_hook(hook) && project_path == hook.project().relative_path()
             }
+            SelectorExpr::Language(language) => hook.language == *language,
         }
     }
 }
@@ -135,6 +212,8 @@ impl Selector {
 pub(crate) struct Selectors {
     includes: Vec<Selector>,
     skips: Vec<Selector>,
+    /// The `default_skips` of the project configs, with the relative path of their project.
+    default_skips: Vec<(PathBuf, Selector)>,
     usage: Arc<Mutex<SelectorUsage>>,
 }
 
@@ -181,10 +260,51 @@ impl Selectors {
         Ok(Self {
             includes,
             skips,
+            default_skips: Vec::new(),
             usage: Arc::default(),
         })
     }
 
+    /// Load the `default_skips` of the project configs, which skip hooks of their own project.
+    pub(crate) fn load_default_skips(&mut self, workspace: &Workspace) -> Result<(), Error> {
+        for project in workspace.all_projects() {
+            let Some(default_skips) = &project.config().default_skips else {
+                continue;
+            };
+            for skip in default_skips.iter().unique() {
+                let selector = parse_skip(
+                    skip,
+                    workspace.root(),
+                    SelectorSource::DefaultSkips,
+                    RootedFileSystem(project.path()),
+                )
+                .and_then(|selector| match selector.expr {
+                    SelectorExpr::HookId(_) | SelectorExpr::Language(_) => Ok(selector),
+                    _ => Err(Error::InvalidSelector {
+                        selector: skip.clone(),
+                        source: anyhow!("`default_skips` only select hooks of their own config"),
+                    }),
+                })
+                .map_err(|err| Error::DefaultSkips {
+                    config: project.config_file().user_display().to_string(),
+                    source: anyhow!(err),
+                })?;
+                self.default_skips
+                    .push((project.relative_path().to_path_buf(), selector));
+            }
+        }
+
+        trace!(
+            "Default skip selectors: `{}`",
+            self.default_skips
+                .iter()
+                .map(|(_, selector)| selector.to_string())
+                .join(", ")
+        );
+
+        Ok(())
+    }
+
     pub(crate) fn includes(&self) -> &[Selector] {
         &self.includes
     }
@@ -193,8 +313,13 @@ impl Selectors {
         &self.skips
     }
 
+    /// The include selectors without a `!` prefix.
+    fn positive_includes(&self) -> impl Iterator<Item = &Selector> {
+        self.includes.iter().filter(|include| !include.negated)
+    }
+
     pub(crate) fn has_project_selectors(&self) -> bool {
-        self.includes.iter().any(|include| {
+        self.positive_includes().any(|include| {
             matches!(
                 include.expr,
                 SelectorExpr::ProjectPrefix(_) | SelectorExpr::ProjectHook { .. }
@@ -203,8 +328,8 @@ impl Selectors {
     }
 
     pub(crate) fn includes_only_hook_targets(&self) -> bool {
-        !self.includes.is_empty()
-            && self.includes.iter().all(|s| {
+        self.positive_includes().next().is_some()
+            && self.positive_includes().all(|s| {
                 matches!(
                     s.expr,
                     SelectorExpr::HookId(_) | SelectorExpr::ProjectHook { .. }
@@ -212,6 +337,39 @@ impl Selectors {
             })
     }
 
+    /// Whether the include selectors select a hook, without tracking usage.
+    fn includes_hook(&self, hook: &Hook) -> bool {
+        if self
+            .includes
+            .iter()
+            .any(|include| include.negated && include.matches_hook(hook))
+        {
+            return false;
+        }
+        self.positive_includes().next().is_none()
+            || self
+                .positive_includes()
+                .any(|include| include.matches_hook(hook))
+    }
+
+    /// The `default_skips` selector that skips a hook, unless the hook is selected by id.
+    fn default_skipped_by(&self, hook: &Hook) -> Option<&Selector> {
+        if self.positive_includes().any(|include| {
+            matches!(
+                include.expr,
+                SelectorExpr::HookId(_) | SelectorExpr::ProjectHook { .. }
+            ) && include.matches_hook(hook)
+        }) {
+            return None;
+        }
+        self.default_skips
+            .iter()
+            .find(|(project, skip)| {
+                project == hook.project().relative_path() && skip.matches_hook(hook)
+            })
+            .map(|(_, skip)| skip)
+    }
+
     /// Check if a hook matches any of the selection criteria.
     pub(crate) fn matches_hook(&self, hook: &Hook) -> bool {
         let mut usage = self.usage.lock().unwrap();
@@ -224,22 +382,39 @@ impl Selectors {
                 skipped = true;
             }
         }
-        if skipped {
+        if skipped || self.default_skipped_by(hook).is_some() {
             return false;
         }
 
-        if self.includes.is_empty() {
-            return true; // No `includes` mean all hooks are included
-        }
-
+        // Includes with a `!` prefix exclude the hooks they match, the others select them.
         let mut included = false;
+        let mut excluded = false;
         for (idx, include) in self.includes.iter().enumerate() {
             if include.matches_hook(hook) {
                 usage.use_include(idx);
-                included = true;
+                if include.negated {
+                    excluded = true;
+                } else {
+                    included = true;
+                }
             }
         }
-        included
+        if excluded {
+            return false;
+        }
+        // No positive `includes` mean all hooks are included
+        included || self.positive_includes().next().is_none()
+    }
+
+    /// The skip selector that excludes a hook which the include selectors would select, if any.
+    pub(crate) fn skipped_by(&self, hook: &Hook) -> Option<&Selector> {
+        if !self.includes_hook(hook) {
+            return None;
+        }
+        self.skips
+            .iter()
+            .find(|skip| skip.matches_hook(hook))
+            .or_else(|| self.default_skipped_by(hook))
     }
 
     pub(crate) fn matches_hook_id(&self, hook_id: &str) -> bool {
@@ -249,7 +424,7 @@ impl Selectors {
         let mut skipped = false;
         for (idx, skip) in self.skips.iter().enumerate() {
             if let SelectorExpr::HookId(id) = &skip.expr {
-                if id == hook_id {
+                if id.matches(hook_id) {
                     usage.use_skip(idx);
                     skipped = true;
                 }
@@ -259,20 +434,25 @@ impl Selectors {
             return false;
         }
 
-        if self.includes.is_empty() {
-            return true; // No `includes` mean all hooks are included
-        }
-
         let mut included = false;
+        let mut excluded = false;
         for (idx, include) in self.includes.iter().enumerate() {
             if let SelectorExpr::HookId(id) = &include.expr {
-                if id == hook_id {
+                if id.matches(hook_id) {
                     usage.use_include(idx);
-                    included = true;
+                    if include.negated {
+                        excluded = true;
+                    } else {
+                        included = true;
+                    }
                 }
             }
         }
-        included
+        if excluded {
+            return false;
+        }
+        // No positive `includes` mean all hooks are included
+        included || self.positive_includes().next().is_none()
     }
 
     pub(crate) fn matches_path(&self, path: &Path) -> bool {
@@ -291,25 +471,29 @@ impl Selectors {
             return false;
         }
 
-        // If no project prefix selectors are present, all paths are included
-        if !self
-            .includes
-            .iter()
-            .any(|include| matches!(include.expr, SelectorExpr::ProjectPrefix(_)))
-        {
-            return true;
-        }
-
         let mut included = false;
+        let mut excluded = false;
         for (idx, include) in self.includes.iter().enumerate() {
             if let SelectorExpr::ProjectPrefix(project_path) = &include.expr {
                 if path.starts_with(project_path) {
                     usage.use_include(idx);
-                    included = true;
+                    if include.negated {
+                        excluded = true;
+                    } else {
+                        included = true;
+                    }
                 }
             }
         }
+        if excluded {
+            return false;
+        }
+
+        // If no project prefix selectors are present, all paths are included
         included
+            || !self
+                .positive_includes()
+                .any(|include| matches!(include.expr, SelectorExpr::ProjectPrefix(_)))
     }
 
     pub(crate) fn report_unused(&self) {
@@ -344,7 +528,15 @@ impl SelectorUsage {
                     .skips
                     .iter()
                     .enumerate()
-                    .filter(|(idx, _)| !self.used_skips.contains(idx)),
+                    .filter(|(idx, _)| !self.used_skips.contains(idx))
+                    // Skips from the user settings and the local repository apply to every
+                    // workspace, most won't match.
+                    .filter(|(_, selector)| {
+                        !matches!(
+                            selector.source,
+                            SelectorSource::Settings(_) | SelectorSource::LocalSkips
+                        )
+                    }),
             )
             .collect::<Vec<_>>();
 
@@ -398,7 +590,34 @@ fn parse_single_selector<FS: FileSystem>(
     source: SelectorSource,
     fs: FS,
 ) -> Result<Selector, Error> {
-    if input.chars().filter(|&c| c == ':').count() > 1 {
+    let (negated, selector) = match input.strip_prefix('!') {
+        Some(rest) => (true, rest),
+        None => (false, input),
+    };
+    let expr = parse_selector_expr(input, selector, workspace_root, fs)?;
+    Ok(Selector {
+        source,
+        original: input.to_string(),
+        negated,
+        expr,
+    })
+}
+
+fn hook_pattern(input: &str, pattern: &str) -> Result<HookPattern, Error> {
+    HookPattern::new(pattern).map_err(|e| Error::InvalidSelector {
+        selector: input.to_string(),
+        source: anyhow!(e),
+    })
+}
+
+/// Parse a selector without its `!` prefix, `input` being the whole selector for errors.
+fn parse_selector_expr<FS: FileSystem>(
+    input: &str,
+    selector: &str,
+    workspace_root: &Path,
+    fs: FS,
+) -> Result<SelectorExpr, Error> {
+    if selector.chars().filter(|&c| c == ':').count() > 1 {
         return Err(Error::InvalidSelector {
             selector: input.to_string(),
             source: anyhow!("only one ':' is allowed"),
@@ -406,34 +625,45 @@ fn parse_single_selector<FS: FileSystem>(
     }
 
     // Handle explicit hook ID with : prefix
-    if let Some(hook_id) = input.strip_prefix(':') {
+    if let Some(hook_id) = selector.strip_prefix(':') {
         if hook_id.is_empty() {
             return Err(Error::InvalidSelector {
                 selector: input.to_string(),
                 source: anyhow!("hook ID part is empty"),
             });
         }
-        return Ok(Selector {
-            source,
-            original: input.to_string(),
-            expr: SelectorExpr::HookId(hook_id.to_string()),
-        });
+        return Ok(SelectorExpr::HookId(hook_pattern(input, hook_id)?));
     }
 
-    // Handle `project:hook` syntax
-    if let Some((project_path, hook_id)) = input.split_once(':') {
-        if project_path.is_empty() {
+    // Handle `language:<language>` syntax
+    if let Some(language) = selector.strip_prefix("language:") {
+        let Some(language) = Language::value_variants()
+            .iter()
+            .find(|variant| variant.as_str() == language)
+        else {
             return Err(Error::InvalidSelector {
                 selector: input.to_string(),
-                source: anyhow!("project path part is empty"),
+                source: anyhow!(
+                    "unknown language `{language}`, expected one of: {}",
+                    Language::value_variants()
+                        .iter()
+                        .map(Language::as_str)
+                        .join(", ")
+                ),
             });
-        }
-        if hook_id.is_empty() {
+        };
+        return Ok(SelectorExpr::Language(*language));
+    }
+
+    // Handle `project:hook` syntax, `project:` selects all hooks of the project
+    if let Some((project_path, hook_id)) = selector.split_once(':') {
+        if project_path.is_empty() {
             return Err(Error::InvalidSelector {
                 selector: input.to_string(),
-                source: anyhow!("hook ID part is empty"),
+                source: anyhow!("project path part is empty"),
             });
         }
+        let hook_id = if hook_id.is_empty() { "*" } else { hook_id };
 
         let project_path = normalize_path(project_path, workspace_root, fs).map_err(|e| {
             Error::InvalidSelector {
@@ -442,43 +672,31 @@ fn parse_single_selector<FS: FileSystem>(
             }
         })?;
 
-        return Ok(Selector {
-            source,
-            original: input.to_string(),
-            expr: SelectorExpr::ProjectHook {
-                project_path,
-                hook_id: hook_id.to_string(),
-            },
+        return Ok(SelectorExpr::ProjectHook {
+            project_path,
+            hook_id: hook_pattern(input, hook_id)?,
         });
     }
 
     // Handle project paths
-    if input == "." || input.contains('/') {
+    if selector == "." || selector.contains('/') {
         let project_path =
-            normalize_path(input, workspace_root, fs).map_err(|e| Error::InvalidSelector {
+            normalize_path(selector, workspace_root, fs).map_err(|e| Error::InvalidSelector {
                 selector: input.to_string(),
                 source: anyhow!(e),
             })?;
 
-        return Ok(Selector {
-            source,
-            original: input.to_string(),
-            expr: SelectorExpr::ProjectPrefix(project_path),
-        });
+        return Ok(SelectorExpr::ProjectPrefix(project_path));
     }
 
     // Ambiguous case: treat as hook ID for backward compatibility
-    if input.is_empty() {
+    if selector.is_empty() {
         return Err(Error::InvalidSelector {
             selector: input.to_string(),
             source: anyhow!("cannot be empty"),
         });
     }
-    Ok(Selector {
-        source,
-        original: input.to_string(),
-        expr: SelectorExpr::HookId(input.to_string()),
-    })
+    Ok(SelectorExpr::HookId(hook_pattern(input, selector)?))
 }
 
 /// Trait to abstract filesystem operations for easier testing.
@@ -495,6 +713,16 @@ impl FileSystem for RealFileSystem {
     }
 }
 
+/// Resolves relative paths against a directory instead of the current directory.
+#[derive(Copy, Clone)]
+struct RootedFileSystem<'a>(&'a Path);
+
+impl FileSystem for RootedFileSystem<'_> {
+    fn absolute<P: AsRef<Path>>(&self, path: P) -> std::io::Result<PathBuf> {
+        Ok(self.0.join(path).clean())
+    }
+}
+
 /// Normalize a project path to the relative path from the workspace root.
 /// In workspace root:
 /// './project/' -> 'project'
@@ -554,17 +782,95 @@ pub(crate) fn load_skips<FS: FileSystem>(
             parse_comma_separated(s).collect(),
             SelectorSource::EnvVar(EnvVars::SKIP),
         )
+    } else if !settings::get().skip.is_empty() {
+        (
+            settings::get().skip.iter().map(String::as_str).collect(),
+            SelectorSource::Settings("skip"),
+        )
     } else {
-        return Ok(vec![]);
+        (vec![], SelectorSource::CliFlag("--skip"))
     };
 
-    skips
+    let mut selectors = skips
         .into_iter()
         .unique()
-        .map(|skip| parse_single_selector(skip, workspace_root, source, fs))
+        .map(|skip| parse_skip(skip, workspace_root, source, fs))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // The skips of the local repository apply in addition to the others.
+    selectors.extend(load_local_skips(workspace_root));
+
+    Ok(selectors)
+}
+
+fn parse_skip<FS: FileSystem>(
+    input: &str,
+    workspace_root: &Path,
+    source: SelectorSource,
+    fs: FS,
+) -> Result<Selector, Error> {
+    let selector = parse_single_selector(input, workspace_root, source, fs)?;
+    if selector.negated {
+        return Err(Error::InvalidSelector {
+            selector: input.to_string(),
+            source: anyhow!("skip selectors can't be negated"),
+        });
+    }
+    Ok(selector)
+}
+
+/// Load the skips of the local repository, with their project paths relative to the workspace.
+///
+/// Skips of projects outside the workspace don't apply, and invalid skips are ignored with a
+/// warning, so a hand-edited file doesn't break every run.
+fn load_local_skips(workspace_root: &Path) -> Vec<Selector> {
+    let (Ok(git_root), Ok(path)) = (GIT_ROOT.as_ref(), LocalSkips::path()) else {
+        return vec![];
+    };
+    let local_skips = match LocalSkips::load(&path) {
+        Ok(local_skips) => local_skips,
+        Err(err) => {
+            warn_user!("Failed to read `{}`: {err}", path.user_display());
+            return vec![];
+        }
+    };
+
+    let fs = RootedFileSystem(git_root);
+    local_skips
+        .selectors
+        .iter()
+        .filter_map(
+            |skip| match parse_skip(skip, workspace_root, SelectorSource::LocalSkips, fs) {
+                Ok(selector) => Some(selector),
+                Err(Error::InvalidSelector { source, .. })
+                    if source
+                        .downcast_ref::<Error>()
+                        .is_some_and(|err| matches!(err, Error::InvalidPath { .. })) =>
+                {
+                    trace!("Ignoring local skip `{skip}` outside of the workspace");
+                    None
+                }
+                Err(err) => {
+                    let reason = std::error::Error::source(&err)
+                        .map_or_else(|| err.to_string(), ToString::to_string);
+                    warn_user!(
+                        "Ignoring invalid skip `{skip}` in `{}`: {reason}",
+                        path.user_display()
+                    );
+                    None
+                }
+            },
+        )
         .collect()
 }
 
+/// Normalize a selector given to `prek skip`, resolving project paths against the current
+/// directory and making them relative to the root of the repository.
+pub(crate) fn normalize_local_skip(input: &str, git_root: &Path) -> Result<String, Error> {
+    let selector = parse_skip(input, git_root, SelectorSource::LocalSkips, RealFileSystem)?;
+    Ok(selector.to_string())
+}
+
 /// Parse comma-separated values, trimming whitespace and filtering empty strings
 fn parse_comma_separated(input: &str) -> impl Iterator<Item = &str> {
     input.split(',').map(str::trim).filter(|s| !s.is_empty())
@@ -613,11 +919,11 @@ mod tests {
 
         // Test explicit hook ID with colon prefix
         let selector = parse_single_selector(":black", fs.root(), SelectorSource::CliArg, &fs)?;
-        assert!(matches!(selector.expr, SelectorExpr::HookId(ref id) if id == "black"));
+        assert!(matches!(selector.expr, SelectorExpr::HookId(ref id) if id.to_string() == "black"));
 
         // Test bare hook ID (backward compatibility)
         let selector = parse_single_selector("black", fs.root(), SelectorSource::CliArg, &fs)?;
-        assert!(matches!(selector.expr, SelectorExpr::HookId(ref id) if id == "black"));
+        assert!(matches!(selector.expr, SelectorExpr::HookId(ref id) if id.to_string() == "black"));
 
         Ok(())
     }
@@ -656,11 +962,99 @@ mod tests {
                 hook_id,
             } => {
                 assert_eq!(project_path, PathBuf::from("src"));
-                assert_eq!(hook_id, "black");
+                assert_eq!(hook_id.to_string(), "black");
             }
             _ => panic!("Expected ProjectHook"),
         }
 
+        // Test all hooks of a project
+        let selector = parse_single_selector("src:", fs.root(), SelectorSource::CliArg, &fs)?;
+        match selector.expr {
+            SelectorExpr::ProjectHook {
+                project_path,
+                hook_id,
+            } => {
+                assert_eq!(project_path, PathBuf::from("src"));
+                assert!(hook_id.matches("black"));
+            }
+            _ => panic!("Expected ProjectHook"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_single_selector_glob() -> anyhow::Result<()> {
+        let fs = create_test_workspace()?;
+
+        let selector = parse_single_selector("lint-*", fs.root(), SelectorSource::CliArg, &fs)?;
+        let SelectorExpr::HookId(pattern) = selector.expr else {
+            panic!("Expected HookId");
+        };
+        assert!(pattern.matches("lint-js"));
+        assert!(pattern.matches("lint-"));
+        assert!(!pattern.matches("format-js"));
+
+        let selector =
+            parse_single_selector("src:check-[jt]*", fs.root(), SelectorSource::CliArg, &fs)?;
+        let SelectorExpr::ProjectHook { hook_id, .. } = selector.expr else {
+            panic!("Expected ProjectHook");
+        };
+        assert!(hook_id.matches("check-json"));
+        assert!(hook_id.matches("check-toml"));
+        assert!(!hook_id.matches("check-yaml"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_single_selector_negated() -> anyhow::Result<()> {
+        let fs = create_test_workspace()?;
+
+        let selector = parse_single_selector("!src/", fs.root(), SelectorSource::CliArg, &fs)?;
+        assert!(selector.negated);
+        assert!(
+            matches!(selector.expr, SelectorExpr::ProjectPrefix(ref path) if path == &PathBuf::from("src"))
+        );
+        assert_eq!(selector.to_string(), "!src/");
+
+        let selector = parse_single_selector("black", fs.root(), SelectorSource::CliArg, &fs)?;
+        assert!(!selector.negated);
+
+        let result = load_skips(&["!black".to_string()], fs.root(), &fs);
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_single_selector_language() -> anyhow::Result<()> {
+        let fs = create_test_workspace()?;
+
+        let selector =
+            parse_single_selector("language:python", fs.root(), SelectorSource::CliArg, &fs)?;
+        assert!(matches!(
+            selector.expr,
+            SelectorExpr::Language(Language::Python)
+        ));
+        assert_eq!(selector.to_string(), "language:python");
+
+        let selector = parse_single_selector(
+            "!language:docker_image",
+            fs.root(),
+            SelectorSource::CliArg,
+            &fs,
+        )?;
+        assert!(selector.negated);
+        assert!(matches!(
+            selector.expr,
+            SelectorExpr::Language(Language::DockerImage)
+        ));
+
+        let result =
+            parse_single_selector("language:cobol", fs.root(), SelectorSource::CliArg, &fs);
+        assert!(result.is_err());
+
         Ok(())
     }
 
@@ -672,8 +1066,12 @@ mod tests {
         let result = parse_single_selector(":", fs.root(), SelectorSource::CliArg, &fs);
         assert!(result.is_err());
 
-        // Test empty hook ID in project:hook
-        let result = parse_single_selector("src:", fs.root(), SelectorSource::CliArg, &fs);
+        // Test negation of nothing
+        let result = parse_single_selector("!", fs.root(), SelectorSource::CliArg, &fs);
+        assert!(result.is_err());
+
+        // Test invalid glob
+        let result = parse_single_selector("lint-[", fs.root(), SelectorSource::CliArg, &fs);
         assert!(result.is_err());
 
         // Test multiple colons
@@ -757,21 +1155,24 @@ mod tests {
         let selector = Selector {
             source: SelectorSource::CliArg,
             original: "black".to_string(),
-            expr: SelectorExpr::HookId("black".to_string()),
+            negated: false,
+            expr: SelectorExpr::HookId(HookPattern::new("black").unwrap()),
         };
         assert_eq!(selector.as_flag(), "black");
 
         let selector = Selector {
             source: SelectorSource::CliFlag("--skip"),
             original: "black".to_string(),
-            expr: SelectorExpr::HookId("black".to_string()),
+            negated: false,
+            expr: SelectorExpr::HookId(HookPattern::new("black").unwrap()),
         };
         assert_eq!(selector.as_flag(), "--skip=black");
 
         let selector = Selector {
             source: SelectorSource::EnvVar("SKIP"),
             original: "black".to_string(),
-            expr: SelectorExpr::HookId("black".to_string()),
+            negated: false,
+            expr: SelectorExpr::HookId(HookPattern::new("black").unwrap()),
         };
         assert_eq!(selector.as_flag(), "SKIP=black");
     }
@@ -0,0 +1,206 @@
+use std::fmt::Write as _;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use owo_colors::OwoColorize;
+use path_clean::PathClean;
+use tracing::debug;
+
+use crate::cli::ExitStatus;
+use crate::cli::reporter::{HookInitReporter, HookInstallReporter, HookRunReporter};
+use crate::cli::run::filter::{FileTagFilter, FilenameFilter};
+use crate::cli::run::{Selectors, install_hooks};
+use crate::config::{HookKind, Language, Stage};
+use crate::fs::{CWD, Simplified};
+use crate::hook::Hook;
+use crate::identify::{DEFAULT_BINARY_DETECTION_BYTES, tags_from_path_with};
+use crate::printer::Printer;
+use crate::store::Store;
+use crate::workspace::Workspace;
+
+/// Run hooks on content read from stdin, as if it were the content of `filename`.
+///
+/// The content is written to a temporary directory next to `filename`, under the same file
+/// name, so that tools find their configuration and detect the file type as for the real file.
+/// The resulting content is written to stdout and the output of the hooks to stderr.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn run_stdin(
+    store: &Store,
+    config: Option<PathBuf>,
+    includes: Vec<String>,
+    skips: Vec<String>,
+    no_default_skips: bool,
+    hook_stage: Option<Stage>,
+    hook_kind: Option<HookKind>,
+    filename: &Path,
+    refresh: bool,
+    verbose: bool,
+    printer: Printer,
+) -> Result<ExitStatus> {
+    let mut content = Vec::new();
+    std::io::stdin()
+        .read_to_end(&mut content)
+        .context("Failed to read stdin")?;
+
+    let workspace_root = Workspace::find_root(config.as_deref(), &CWD)?;
+    let mut selectors = Selectors::load(&includes, &skips, &workspace_root)?;
+    let mut workspace = Workspace::discover(
+        store,
+        workspace_root,
+        config,
+        Some(&selectors),
+        refresh,
+        false,
+    )?;
+    if !no_default_skips {
+        selectors.load_default_skips(&workspace)?;
+    }
+
+    let path = std::path::absolute(filename)?.clean();
+    let relative_path = path
+        .strip_prefix(workspace.root())
+        .with_context(|| {
+            format!(
+                "`{}` is not in the workspace `{}`",
+                filename.user_display(),
+                workspace.root().user_display()
+            )
+        })?
+        .to_path_buf();
+
+    let parent = path.parent().context("The file has no parent directory")?;
+    let temp_dir = tempfile::Builder::new()
+        .prefix(".prek-stdin-")
+        .tempdir_in(parent)
+        .with_context(|| {
+            format!(
+                "Failed to create a temporary directory in `{}`",
+                parent.user_display()
+            )
+        })?;
+    let temp_file = temp_dir
+        .path()
+        .join(path.file_name().context("The file has no file name")?);
+    fs_err::write(&temp_file, &content)?;
+
+    let reporter = HookInitReporter::new(printer);
+    let lock = store.lock_shared_async().await?;
+    let hooks = workspace
+        .init_hooks(store, Some(&reporter))
+        .await
+        .context("Failed to init hooks")?;
+    selectors.report_unused();
+
+    let hook_stage = hook_stage.unwrap_or(Stage::PreCommit);
+    let mut hooks = hooks
+        .into_iter()
+        .filter(|hook| {
+            selectors.matches_hook(hook)
+                && hook.stages.contains(hook_stage)
+                && hook_kind.is_none_or(|kind| hook.kind == Some(kind))
+                && hook.supports_current_platform()
+                && Language::supported(hook.language)
+        })
+        .filter(|hook| {
+            // The content only reaches hooks that are passed the file.
+            if !hook.pass_filenames {
+                debug!("Skipping hook `{hook}` which is not passed filenames");
+                return false;
+            }
+            hook_matches_file(&workspace, hook, &relative_path, &temp_file)
+        })
+        .map(Arc::new)
+        .collect::<Vec<_>>();
+    hooks.sort_by(|a, b| a.priority.cmp(&b.priority).then(a.idx.cmp(&b.idx)));
+
+    let reporter = HookInstallReporter::new(printer);
+    let hooks = install_hooks(hooks, store, &reporter, false).await?;
+    drop(lock);
+
+    std::env::set_current_dir(workspace.root()).with_context(|| {
+        format!(
+            "Failed to change directory to `{}`",
+            workspace.root().display()
+        )
+    })?;
+
+    let reporter = HookRunReporter::new(printer, 0);
+    let mut status = ExitStatus::Success;
+    for hook in hooks {
+        let before = fs_err::read(&temp_file)?;
+        let filename = temp_file
+            .strip_prefix(hook.work_dir())
+            .unwrap_or(&temp_file);
+
+        let (exit_code, output) = hook
+            .language
+            .run(&hook, &[filename], store, &reporter)
+            .await
+            .with_context(|| format!("Failed to run hook `{hook}`"))?;
+
+        let after =
+            fs_err::read(&temp_file).with_context(|| format!("Hook `{hook}` removed the file"))?;
+        let failed = !hook.success_exit_codes.contains(&exit_code)
+            || (hook.fail_on_output && !output.trim_ascii().is_empty());
+
+        // Fixers commonly exit with an error after modifying files, the fix is what we are after.
+        if failed && before == after {
+            status = ExitStatus::Failure;
+            writeln!(
+                printer.stderr(),
+                "{}: Hook `{}` failed with exit code {exit_code}",
+                "error".red().bold(),
+                hook.id.cyan()
+            )?;
+            std::io::stderr().write_all(&output)?;
+        } else if (verbose || hook.verbose) && !output.is_empty() {
+            std::io::stderr().write_all(&output)?;
+        }
+    }
+
+    std::io::stdout().write_all(&fs_err::read(&temp_file)?)?;
+
+    Ok(status)
+}
+
+/// Whether the hook runs on the file at `relative_path` in the workspace, with the content in
+/// `temp_file` used to detect the file type.
+fn hook_matches_file(
+    workspace: &Workspace,
+    hook: &Hook,
+    relative_path: &Path,
+    temp_file: &Path,
+) -> bool {
+    let project = hook.project();
+    let Ok(project_path) = relative_path.strip_prefix(project.relative_path()) else {
+        return false;
+    };
+
+    // Files in orphan projects are not seen by the projects containing them.
+    let depth = project.relative_path().components().count();
+    if workspace.all_projects().iter().any(|other| {
+        other.config().orphan.unwrap_or(false)
+            && other.relative_path().components().count() > depth
+            && relative_path.starts_with(other.relative_path())
+    }) {
+        return false;
+    }
+
+    let project_filter = FilenameFilter::new(
+        project.config().files.as_ref(),
+        project.config().exclude.as_ref(),
+    );
+    let hook_filter = FilenameFilter::new(hook.files.as_ref(), hook.exclude.as_ref());
+    if !project_filter.filter(relative_path) || !hook_filter.filter(project_path) {
+        return false;
+    }
+
+    let detection_bytes = project
+        .config()
+        .binary_detection_bytes
+        .unwrap_or(DEFAULT_BINARY_DETECTION_BYTES);
+    tags_from_path_with(temp_file, detection_bytes)
+        .is_ok_and(|tags| FileTagFilter::for_hook(hook).filter(&tags))
+}
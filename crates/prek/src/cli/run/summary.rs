@@ -0,0 +1,275 @@
+use std::fmt::Write as _;
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use owo_colors::OwoColorize;
+use serde::{Deserialize, Serialize};
+
+/// The outcome of a single hook, as recorded in the run summary.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum SummaryStatus {
+    Passed,
+    Failed,
+    Skipped,
+    DryRun,
+    Cancelled,
+}
+
+impl SummaryStatus {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Passed => "passed",
+            Self::Failed => "failed",
+            Self::Skipped => "skipped",
+            Self::DryRun => "dry-run",
+            Self::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// Why a hook was skipped, to tell deliberate skips apart from configuration mistakes.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum SkipReason {
+    /// No files matched the hook's filters.
+    NoFiles,
+    /// The hook is not configured for the stage being run.
+    Stage,
+    /// The hook was excluded by `--skip` or the `skip` setting.
+    Selector,
+    /// The hook was excluded by the `SKIP` or `PREK_SKIP` environment variable.
+    SkipEnv,
+    /// The hook was excluded by the skips of the local repository, managed by `prek skip`.
+    LocalSkip,
+    /// The hook was excluded by the `default_skips` of the config.
+    DefaultSkip,
+    /// The hook is restricted to other operating systems or architectures.
+    Platform,
+    /// The hook's language is not implemented yet.
+    Unimplemented,
+    /// The hook already passed for the same commits on a previous `pre-push` run.
+    Cached,
+}
+
+impl SkipReason {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::NoFiles => "no-files",
+            Self::Stage => "stage",
+            Self::Selector => "selector",
+            Self::SkipEnv => "skip-env",
+            Self::LocalSkip => "local-skip",
+            Self::DefaultSkip => "default-skip",
+            Self::Platform => "platform",
+            Self::Unimplemented => "unimplemented",
+            Self::Cached => "cached",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct HookSummary {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) project: String,
+    pub(crate) status: SummaryStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) skip_reason: Option<SkipReason>,
+    pub(crate) exit_code: i32,
+    /// Duration in seconds.
+    pub(crate) duration: f64,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub(crate) struct SummaryCounts {
+    pub(crate) passed: usize,
+    pub(crate) failed: usize,
+    pub(crate) skipped: usize,
+    pub(crate) dry_run: usize,
+    pub(crate) cancelled: usize,
+}
+
+/// A small, stable artifact describing the outcome of `prek run`, meant to be
+/// published by CI jobs as a check summary or badge source.
+#[derive(Debug, Serialize)]
+pub(crate) struct RunSummary {
+    pub(crate) version: &'static str,
+    pub(crate) status: SummaryStatus,
+    /// Total duration in seconds.
+    pub(crate) duration: f64,
+    pub(crate) counts: SummaryCounts,
+    pub(crate) hooks: Vec<HookSummary>,
+}
+
+impl RunSummary {
+    pub(crate) fn new(hooks: Vec<HookSummary>, status: SummaryStatus, duration: Duration) -> Self {
+        let mut counts = SummaryCounts::default();
+        for hook in &hooks {
+            match hook.status {
+                SummaryStatus::Passed => counts.passed += 1,
+                SummaryStatus::Failed => counts.failed += 1,
+                SummaryStatus::Skipped => counts.skipped += 1,
+                SummaryStatus::DryRun => counts.dry_run += 1,
+                SummaryStatus::Cancelled => counts.cancelled += 1,
+            }
+        }
+
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            status,
+            duration: duration.as_secs_f64(),
+            counts,
+            hooks,
+        }
+    }
+
+    /// Write the summary to `path`, as Markdown if the file has a `.md` extension and as JSON otherwise.
+    pub(crate) fn write(&self, path: &Path) -> Result<()> {
+        let is_markdown = path.extension().is_some_and(|ext| {
+            ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown")
+        });
+        let content = if is_markdown {
+            self.to_markdown()
+        } else {
+            serde_json::to_string_pretty(self)? + "\n"
+        };
+
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs_err::create_dir_all(parent)?;
+        }
+        fs_err::write(path, content)
+            .with_context(|| format!("Failed to write run summary to `{}`", path.display()))
+    }
+
+    /// A one-line footer for the terminal, like `5 passed · 2 failed · 1 skipped · 3.2s`.
+    pub(crate) fn to_footer(&self) -> String {
+        let mut parts = vec![
+            format!("{} passed", self.counts.passed).green().to_string(),
+            if self.counts.failed > 0 {
+                format!("{} failed", self.counts.failed).red().to_string()
+            } else {
+                format!("{} failed", self.counts.failed)
+            },
+            format!("{} skipped", self.counts.skipped + self.counts.dry_run),
+        ];
+        if self.counts.cancelled > 0 {
+            parts.push(
+                format!("{} cancelled", self.counts.cancelled)
+                    .yellow()
+                    .to_string(),
+            );
+        }
+        parts.push(format!("{:.1}s", self.duration).dimmed().to_string());
+        parts.join(&" · ".dimmed().to_string())
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "## prek run: {}\n\n{} passed, {} failed, {} skipped in {:.2}s (prek {})\n",
+            self.status.as_str(),
+            self.counts.passed,
+            self.counts.failed,
+            self.counts.skipped + self.counts.dry_run,
+            self.duration,
+            self.version,
+        );
+        out.push_str("| Hook | Project | Status | Exit code | Duration |\n");
+        out.push_str("| --- | --- | --- | --- | --- |\n");
+        for hook in &self.hooks {
+            let status = match hook.skip_reason {
+                Some(reason) => format!("{} ({})", hook.status.as_str(), reason.as_str()),
+                None => hook.status.as_str().to_string(),
+            };
+            let _ = writeln!(
+                out,
+                "| `{}` | `{}` | {} | {} | {:.2}s |",
+                hook.id, hook.project, status, hook.exit_code, hook.duration,
+            );
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary() -> RunSummary {
+        RunSummary::new(
+            vec![
+                HookSummary {
+                    id: "ruff".to_string(),
+                    name: "ruff".to_string(),
+                    project: ".".to_string(),
+                    status: SummaryStatus::Passed,
+                    skip_reason: None,
+                    exit_code: 0,
+                    duration: 0.5,
+                },
+                HookSummary {
+                    id: "typos".to_string(),
+                    name: "typos".to_string(),
+                    project: "docs".to_string(),
+                    status: SummaryStatus::Failed,
+                    skip_reason: None,
+                    exit_code: 2,
+                    duration: 1.25,
+                },
+                HookSummary {
+                    id: "mypy".to_string(),
+                    name: "mypy".to_string(),
+                    project: ".".to_string(),
+                    status: SummaryStatus::Skipped,
+                    skip_reason: Some(SkipReason::NoFiles),
+                    exit_code: 0,
+                    duration: 0.0,
+                },
+            ],
+            SummaryStatus::Failed,
+            Duration::from_millis(1750),
+        )
+    }
+
+    #[test]
+    fn summary_counts() {
+        let summary = summary();
+        assert_eq!(summary.status, SummaryStatus::Failed);
+        assert_eq!(summary.counts.passed, 1);
+        assert_eq!(summary.counts.failed, 1);
+        assert_eq!(summary.counts.skipped, 1);
+        assert_eq!(summary.counts.dry_run, 0);
+    }
+
+    #[test]
+    fn summary_footer() {
+        let footer = summary().to_footer();
+        assert_eq!(
+            anstream::adapter::strip_str(&footer).to_string(),
+            "1 passed · 1 failed · 1 skipped · 1.8s"
+        );
+    }
+
+    #[test]
+    fn summary_markdown() {
+        let markdown = summary()
+            .to_markdown()
+            .replace(env!("CARGO_PKG_VERSION"), "[VERSION]");
+        insta::assert_snapshot!(markdown, @r"
+        ## prek run: failed
+
+        1 passed, 1 failed, 1 skipped in 1.75s (prek [VERSION])
+
+        | Hook | Project | Status | Exit code | Duration |
+        | --- | --- | --- | --- | --- |
+        | `ruff` | `.` | passed | 0 | 0.50s |
+        | `typos` | `docs` | failed | 2 | 1.25s |
+        | `mypy` | `.` | skipped (no-files) | 0 | 0.00s |
+        ");
+    }
+}
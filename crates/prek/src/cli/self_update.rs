@@ -22,29 +22,41 @@
 
 use std::env;
 use std::fmt::Write;
+use std::path::{Path, PathBuf};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use axoupdater::{AxoUpdater, AxoupdateError, UpdateRequest};
+use minisign_verify::{PublicKey, Signature};
 use owo_colors::OwoColorize;
-use tracing::{debug, enabled};
+use semver::Version;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use tracing::debug;
 
-use crate::cli::ExitStatus;
+use crate::archive::{self, ArchiveExtension};
+use crate::cli::{ExitStatus, UpdateChannel};
+use crate::fs::Simplified;
+use crate::languages::REQWEST_CLIENT;
 use crate::printer::Printer;
+use crate::store::Store;
+use crate::{offline, retry, warn_user};
+
+/// The minisign public key prek releases are signed with.
+const RELEASE_PUBLIC_KEY: &str = "RWQ1BRhMPMOyzo7NjqQrpBzGD3zxpIrXEtQWCS3h3LVwshn/dQWUlb8M";
+
+/// The target triple prek was built for, which names the release archive of this platform.
+const TARGET_TRIPLE: &str = env!("PREK_TARGET_TRIPLE");
 
 /// Attempt to update the prek binary.
 pub(crate) async fn self_update(
+    store: &Store,
     version: Option<String>,
+    channel: UpdateChannel,
     token: Option<String>,
+    require_signature: bool,
     printer: Printer,
 ) -> Result<ExitStatus> {
     let mut updater = AxoUpdater::new_for("prek");
-    if enabled!(tracing::Level::DEBUG) {
-        unsafe { env::set_var("INSTALLER_PRINT_VERBOSE", "1") };
-        updater.enable_installer_output();
-    } else {
-        updater.disable_installer_output();
-    }
-
     if let Some(ref token) = token {
         updater.set_github_token(token);
     }
@@ -94,6 +106,19 @@ pub(crate) async fn self_update(
         return Ok(ExitStatus::Error);
     }
 
+    // The receipt records the last installed version, which is stale after a roll back.
+    let current = Version::parse(env!("CARGO_PKG_VERSION"))?;
+    updater.set_current_version(current.clone())?;
+
+    // Rolling back to a version prek was updated from doesn't need the network.
+    if let Some(version) = &version {
+        if let Some(status) = roll_back(store, version, printer)? {
+            return Ok(status);
+        }
+    }
+
+    offline::ensure_online(|| "Updating prek".to_string())?;
+
     writeln!(
         printer.stderr(),
         "{}",
@@ -104,78 +129,450 @@ pub(crate) async fn self_update(
         )
     )?;
 
-    let update_request = if let Some(version) = version {
-        UpdateRequest::SpecificTag(version)
-    } else {
-        UpdateRequest::Latest
+    let update_request = match (version, channel) {
+        (Some(version), _) => UpdateRequest::SpecificTag(version),
+        (None, UpdateChannel::Stable) => UpdateRequest::Latest,
+        (None, channel) => {
+            let releases = match list_releases(token.as_deref()).await {
+                Ok(releases) => releases,
+                Err(err) if is_rate_limited(&err) && token.is_none() => {
+                    return report_rate_limited(printer);
+                }
+                Err(err) => return Err(err.into()),
+            };
+            // A specific tag is updated to even if it's older, so only pick a newer release.
+            match latest_release(&releases, channel) {
+                Some((tag, version)) if version > current => UpdateRequest::SpecificTag(tag),
+                _ => return report_up_to_date(printer),
+            }
+        }
     };
 
     updater.configure_version_specifier(update_request);
 
-    // Run the updater. This involves a network request, since we need to determine the latest
-    // available version of prek.
-    match updater.run().await {
-        Ok(Some(result)) => {
-            let version_information = if let Some(old_version) = result.old_version {
-                format!(
-                    "from {} to {}",
-                    format!("v{old_version}").bold().white(),
-                    format!("v{}", result.new_version).bold().white(),
-                )
-            } else {
-                format!("to {}", format!("v{}", result.new_version).bold().white())
-            };
+    // The release archive is installed by prek itself, axoupdater only looks up the release.
+    let new_version = match updater.query_new_version().await {
+        Ok(Some(version)) => version.clone(),
+        Ok(None) => anyhow::bail!("No prek release found"),
+        Err(err) => return report_update_error(err, token.is_none(), printer),
+    };
+    match updater.is_update_needed().await {
+        Ok(true) => {}
+        Ok(false) => {
+            return report_up_to_date(printer);
+        }
+        Err(err) => return report_update_error(err, token.is_none(), printer),
+    }
 
-            writeln!(
-                printer.stderr(),
-                "{}",
-                format_args!(
-                    "{}{} Upgraded prek {}! {}",
-                    "success".green().bold(),
-                    ":".bold(),
-                    version_information,
-                    format!(
-                        "https://github.com/j178/prek/releases/tag/{}",
-                        result.new_version_tag
-                    )
-                    .cyan()
-                )
-            )?;
+    let download_dir = tempfile::tempdir()?;
+    let binary = download_binary(
+        &format!("v{new_version}"),
+        require_signature,
+        download_dir.path(),
+    )
+    .await?;
+
+    let kept = keep_current_binary(store)?;
+    self_replace::self_replace(&binary).with_context(|| {
+        format!(
+            "Failed to replace the prek binary with `{}`",
+            binary.simplified_display()
+        )
+    })?;
+    remove_kept_binaries(&kept)?;
+
+    writeln!(
+        printer.stderr(),
+        "{}",
+        format_args!(
+            "{}{} Upgraded prek from {} to {}! {}",
+            "success".green().bold(),
+            ":".bold(),
+            format!("v{current}").bold().white(),
+            format!("v{new_version}").bold().white(),
+            format!("https://github.com/j178/prek/releases/tag/v{new_version}").cyan()
+        )
+    )?;
+
+    Ok(ExitStatus::Success)
+}
+
+fn report_up_to_date(printer: Printer) -> Result<ExitStatus> {
+    writeln!(
+        printer.stderr(),
+        "{}",
+        format_args!(
+            "{}{} You're on the latest version of prek ({})",
+            "success".green().bold(),
+            ":".bold(),
+            format!("v{}", env!("CARGO_PKG_VERSION")).bold().white()
+        )
+    )?;
+    Ok(ExitStatus::Success)
+}
+
+fn report_rate_limited(printer: Printer) -> Result<ExitStatus> {
+    writeln!(
+        printer.stderr(),
+        "{}",
+        format_args!(
+            "{}{} GitHub API rate limit exceeded. Please provide a GitHub token via the {} option.",
+            "error".red().bold(),
+            ":".bold(),
+            "`--token`".green().bold()
+        )
+    )?;
+    Ok(ExitStatus::Error)
+}
+
+fn is_rate_limited(err: &reqwest::Error) -> bool {
+    err.status() == Some(http::StatusCode::FORBIDDEN)
+}
+
+fn report_update_error(
+    err: AxoupdateError,
+    missing_token: bool,
+    printer: Printer,
+) -> Result<ExitStatus> {
+    if let AxoupdateError::Reqwest(err) = err {
+        if is_rate_limited(&err) && missing_token {
+            report_rate_limited(printer)
+        } else {
+            Err(err.into())
         }
-        Ok(None) => {
-            writeln!(
-                printer.stderr(),
-                "{}",
-                format_args!(
-                    "{}{} You're on the latest version of prek ({})",
-                    "success".green().bold(),
-                    ":".bold(),
-                    format!("v{}", env!("CARGO_PKG_VERSION")).bold().white()
-                )
-            )?;
+    } else {
+        Err(err.into())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+}
+
+/// List the releases of prek, newest first.
+async fn list_releases(token: Option<&str>) -> reqwest::Result<Vec<GithubRelease>> {
+    let mut request = REQWEST_CLIENT
+        .get("https://api.github.com/repos/j178/prek/releases?per_page=100")
+        .header(http::header::ACCEPT, "application/vnd.github+json");
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    request.send().await?.error_for_status()?.json().await
+}
+
+/// The tag and version of the newest release in `channel`, by semver precedence.
+fn latest_release(releases: &[GithubRelease], channel: UpdateChannel) -> Option<(String, Version)> {
+    releases
+        .iter()
+        .filter_map(|release| {
+            let version = release
+                .tag_name
+                .strip_prefix('v')?
+                .parse::<Version>()
+                .ok()?;
+            Some((release.tag_name.clone(), version))
+        })
+        .filter(|(_, version)| channel.includes(version))
+        .max_by(|(_, a), (_, b)| a.cmp(b))
+}
+
+impl UpdateChannel {
+    fn includes(self, version: &Version) -> bool {
+        match self {
+            Self::Stable => version.pre.is_empty(),
+            Self::PreRelease => !version.pre.as_str().starts_with("nightly"),
+            Self::Nightly => true,
         }
-        Err(err) => {
-            return if let AxoupdateError::Reqwest(err) = err {
-                if err.status() == Some(http::StatusCode::FORBIDDEN) && token.is_none() {
-                    writeln!(
-                        printer.stderr(),
-                        "{}",
-                        format_args!(
-                            "{}{} GitHub API rate limit exceeded. Please provide a GitHub token via the {} option.",
-                            "error".red().bold(),
-                            ":".bold(),
-                            "`--token`".green().bold()
-                        )
-                    )?;
-                    Ok(ExitStatus::Error)
-                } else {
-                    Err(err.into())
-                }
-            } else {
-                Err(err.into())
-            };
+    }
+}
+
+/// The path the prek binary of `version` is kept at in the store.
+///
+/// Kept binaries have a directory of their own, `bin` also holds the binaries downloaded by
+/// hook scripts that bootstrap prek.
+fn kept_binary(store: &Store, version: &str) -> PathBuf {
+    store
+        .bin_dir()
+        .join("previous")
+        .join(format!("prek-{version}{}", env::consts::EXE_SUFFIX))
+}
+
+/// Keep the running binary in the store, so that the update can be rolled back.
+fn keep_current_binary(store: &Store) -> Result<PathBuf> {
+    let current_exe = env::current_exe()?;
+    let kept = kept_binary(store, env!("CARGO_PKG_VERSION"));
+    fs_err::create_dir_all(kept.parent().expect("kept binary has a parent"))?;
+    fs_err::copy(&current_exe, &kept).with_context(|| {
+        format!(
+            "Failed to keep the current prek binary at `{}`",
+            kept.simplified_display()
+        )
+    })?;
+    debug!("Kept the current prek binary at `{}`", kept.display());
+    Ok(kept)
+}
+
+/// Remove the kept binaries other than `kept`, only the binary replaced last is kept.
+fn remove_kept_binaries(kept: &Path) -> Result<()> {
+    let dir = kept.parent().expect("kept binary has a parent");
+    for entry in fs_err::read_dir(dir)? {
+        let path = entry?.path();
+        if path != kept {
+            debug!("Removing kept prek binary `{}`", path.display());
+            fs_err::remove_file(&path)?;
         }
     }
+    Ok(())
+}
 
-    Ok(ExitStatus::Success)
+/// Replace the running binary with the kept binary of `version`, if there is one.
+fn roll_back(store: &Store, version: &str, printer: Printer) -> Result<Option<ExitStatus>> {
+    let version = version.strip_prefix('v').unwrap_or(version);
+    let kept = kept_binary(store, version);
+    if version == env!("CARGO_PKG_VERSION") || !kept.is_file() {
+        return Ok(None);
+    }
+
+    let kept_current = keep_current_binary(store)?;
+    self_replace::self_replace(&kept).with_context(|| {
+        format!(
+            "Failed to replace the prek binary with `{}`",
+            kept.simplified_display()
+        )
+    })?;
+    remove_kept_binaries(&kept_current)?;
+
+    writeln!(
+        printer.stderr(),
+        "{}",
+        format_args!(
+            "{}{} Rolled back prek from {} to {} using `{}`",
+            "success".green().bold(),
+            ":".bold(),
+            format!("v{}", env!("CARGO_PKG_VERSION")).bold().white(),
+            format!("v{version}").bold().white(),
+            kept.simplified_display().cyan()
+        )
+    )?;
+    Ok(Some(ExitStatus::Success))
+}
+
+/// Download the archive of the release `tag` for this platform into `dir`, verify its checksum
+/// and signature, and unpack it. Returns the path of the unpacked prek binary.
+///
+/// Without `require_signature`, a missing checksum or signature is only warned about, but one
+/// that doesn't match is always an error.
+async fn download_binary(tag: &str, require_signature: bool, dir: &Path) -> Result<PathBuf> {
+    let name = if cfg!(windows) {
+        format!("prek-{TARGET_TRIPLE}.zip")
+    } else {
+        format!("prek-{TARGET_TRIPLE}.tar.gz")
+    };
+    let base_url = format!("https://github.com/j178/prek/releases/download/{tag}");
+
+    let archive = download(&format!("{base_url}/{name}"))
+        .await?
+        .with_context(|| format!("Release `{tag}` has no archive `{name}`"))?;
+
+    let checksum = download(&format!("{base_url}/{name}.sha256")).await?;
+    if let Some(checksum) = checksum {
+        verify_checksum(&name, &archive, &String::from_utf8_lossy(&checksum))?;
+        debug!("Verified checksum of `{name}`");
+    } else {
+        missing_verification(
+            &format!("release `{tag}` has no checksum for `{name}`"),
+            require_signature,
+        )?;
+    }
+
+    let signature = download(&format!("{base_url}/{name}.minisig")).await?;
+    if let Some(signature) = signature {
+        verify_signature(
+            &name,
+            &archive,
+            &String::from_utf8_lossy(&signature),
+            RELEASE_PUBLIC_KEY,
+        )?;
+        debug!("Verified signature of `{name}`");
+    } else {
+        missing_verification(
+            &format!("release `{tag}` has no signature for `{name}`"),
+            require_signature,
+        )?;
+    }
+
+    let ext = ArchiveExtension::from_path(&name)?;
+    archive::unpack(&archive[..], ext, dir).await?;
+
+    // Windows archives hold the binary at the top level, the others in a `prek-<target>` directory.
+    let binary_name = format!("prek{}", env::consts::EXE_SUFFIX);
+    [
+        dir.join(&binary_name),
+        dir.join(format!("prek-{TARGET_TRIPLE}")).join(&binary_name),
+    ]
+    .into_iter()
+    .find(|path| path.is_file())
+    .with_context(|| format!("Archive `{name}` has no `{binary_name}`"))
+}
+
+/// Download a release asset, or return `None` if it doesn't exist.
+async fn download(url: &str) -> Result<Option<Vec<u8>>> {
+    retry::with_retries(
+        &format!("Downloading `{url}`"),
+        retry::is_transient_http_error,
+        || async {
+            let response = REQWEST_CLIENT
+                .get(url)
+                .send()
+                .await
+                .with_context(|| format!("Failed to download file from {url}"))?;
+            if response.status() == http::StatusCode::NOT_FOUND {
+                return Ok(None);
+            }
+            let bytes = response
+                .error_for_status()
+                .with_context(|| format!("Failed to download file from {url}"))?
+                .bytes()
+                .await
+                .with_context(|| format!("Failed to download file from {url}"))?;
+            Ok(Some(bytes.to_vec()))
+        },
+    )
+    .await
+}
+
+fn missing_verification(reason: &str, require_signature: bool) -> Result<()> {
+    if require_signature {
+        anyhow::bail!("Refusing to update, {reason} and `--require-signature` is set");
+    }
+    warn_user!("Updating without verification, {reason}");
+    Ok(())
+}
+
+/// Check `content` against a `sha256sum`-style checksum file.
+fn verify_checksum(name: &str, content: &[u8], checksum_file: &str) -> Result<()> {
+    let expected = checksum_file
+        .split_whitespace()
+        .next()
+        .with_context(|| format!("Checksum file of `{name}` is empty"))?
+        .to_ascii_lowercase();
+    let actual = hex::encode(Sha256::digest(content));
+    if actual != expected {
+        anyhow::bail!("Checksum mismatch for `{name}`: expected `{expected}`, got `{actual}`");
+    }
+    Ok(())
+}
+
+/// Check `content` against a minisign signature made with `public_key`.
+fn verify_signature(name: &str, content: &[u8], signature: &str, public_key: &str) -> Result<()> {
+    let public_key =
+        PublicKey::from_base64(public_key).context("Failed to decode the release signing key")?;
+    let signature = Signature::decode(signature)
+        .with_context(|| format!("Failed to decode the signature of `{name}`"))?;
+    public_key
+        .verify(content, &signature, false)
+        .with_context(|| format!("Signature verification failed for `{name}`"))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIGNATURE: &str = "untrusted comment: signature from minisign secret key
+RUQ1BRhMPMOyzhouQbt0bVs80sZ3cPSl0pKJRSwOn6GzEV0ZQ5PN8nQwRrNW25tTC817F+jF/lPx50trkrVZhtwG5DOxkBUzHAw=
+trusted comment: timestamp:1760000000\tfile:prek-x86_64-unknown-linux-gnu.tar.gz\thashed
+9yXrAvtgXLXklDPiQTCIIqYmcIkNQDlfaiLJAHkJZ3mnCn96MRguvPf0KQPD/1mSWRBD2BqWUXvZYLvBSd0/BA==";
+
+    #[test]
+    fn channel_releases() {
+        let releases = [
+            "v0.3.0-nightly.20260102",
+            "v0.3.0-rc.2",
+            "v0.3.0-rc.10",
+            "v0.2.10",
+            "v0.2.9",
+            "nightly",
+        ]
+        .map(|tag| GithubRelease {
+            tag_name: tag.to_string(),
+        });
+        let latest = |channel| latest_release(&releases, channel).map(|(tag, _)| tag);
+
+        assert_eq!(latest(UpdateChannel::Stable).as_deref(), Some("v0.2.10"));
+        assert_eq!(
+            latest(UpdateChannel::PreRelease).as_deref(),
+            Some("v0.3.0-rc.10")
+        );
+        assert_eq!(
+            latest(UpdateChannel::Nightly).as_deref(),
+            Some("v0.3.0-rc.10")
+        );
+
+        let releases = [GithubRelease {
+            tag_name: "v0.3.1-nightly.20260105".to_string(),
+        }];
+        assert_eq!(latest_release(&releases, UpdateChannel::PreRelease), None);
+        assert_eq!(
+            latest_release(&releases, UpdateChannel::Nightly).map(|(tag, _)| tag),
+            Some("v0.3.1-nightly.20260105".to_string())
+        );
+    }
+
+    #[test]
+    fn checksum() {
+        let checksum = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08  prek-x86_64-unknown-linux-gnu.tar.gz\n";
+        assert!(verify_checksum("prek-x86_64-unknown-linux-gnu.tar.gz", b"test", checksum).is_ok());
+
+        let err =
+            verify_checksum("prek-x86_64-unknown-linux-gnu.tar.gz", b"Test", checksum).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("Checksum mismatch for `prek-x86_64-unknown-linux-gnu.tar.gz`")
+        );
+        assert!(verify_checksum("prek-x86_64-unknown-linux-gnu.tar.gz", b"test", "").is_err());
+    }
+
+    #[test]
+    fn only_last_binary_kept() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let kept = dir.path().join("prek-0.2.10");
+        for version in ["0.2.8", "0.2.9", "0.2.10"] {
+            fs_err::write(dir.path().join(format!("prek-{version}")), version)?;
+        }
+
+        remove_kept_binaries(&kept)?;
+
+        let remaining = fs_err::read_dir(dir.path())?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        assert_eq!(remaining, vec![kept]);
+        Ok(())
+    }
+
+    #[test]
+    fn signature() {
+        assert!(
+            verify_signature(
+                "prek-x86_64-unknown-linux-gnu.tar.gz",
+                b"test",
+                SIGNATURE,
+                RELEASE_PUBLIC_KEY
+            )
+            .is_ok()
+        );
+
+        let err = verify_signature(
+            "prek-x86_64-unknown-linux-gnu.tar.gz",
+            b"Test",
+            SIGNATURE,
+            RELEASE_PUBLIC_KEY,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Signature verification failed for `prek-x86_64-unknown-linux-gnu.tar.gz`"
+        );
+    }
 }
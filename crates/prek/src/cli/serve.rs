@@ -0,0 +1,361 @@
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use path_clean::PathClean;
+use rustc_hash::FxHashSet;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tokio::sync::mpsc;
+use tracing::{debug, trace, warn};
+
+use crate::cli::ExitStatus;
+use crate::cli::reporter::{HookInitReporter, HookInstallReporter, HookRunReporter};
+use crate::cli::run::{FileFilter, install_hooks};
+use crate::config::{Language, Stage};
+use crate::fs::CWD;
+use crate::hook::InstalledHook;
+use crate::printer::Printer;
+use crate::store::Store;
+use crate::version;
+use crate::workspace::Workspace;
+
+/// JSON-RPC error code for unknown methods.
+const METHOD_NOT_FOUND: i64 = -32601;
+/// JSON-RPC error code for invalid method parameters.
+const INVALID_PARAMS: i64 = -32602;
+/// JSON-RPC error code for requests that failed to run.
+const INTERNAL_ERROR: i64 = -32603;
+/// LSP diagnostic severity for errors.
+const SEVERITY_ERROR: u8 = 1;
+
+#[derive(Debug, Deserialize)]
+struct Message {
+    id: Option<Value>,
+    method: Option<String>,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckParams {
+    files: Vec<PathBuf>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DidSaveParams {
+    #[serde(rename = "textDocument")]
+    text_document: TextDocument,
+}
+
+#[derive(Debug, Deserialize)]
+struct TextDocument {
+    uri: url::Url,
+}
+
+/// The outcome of a hook on the checked files.
+#[derive(Debug, Serialize)]
+struct HookCheck {
+    id: String,
+    name: String,
+    project: String,
+    passed: bool,
+    exit_code: i32,
+    /// The files the hook ran on, relative to the workspace root.
+    files: Vec<PathBuf>,
+    output: String,
+}
+
+/// Serve hook checks to an editor over stdio.
+///
+/// Messages are JSON-RPC, framed with `Content-Length` headers like the Language Server Protocol.
+/// The hooks are initialized and installed once, then `prek/check` requests (and `textDocument/didSave`
+/// notifications with `--check-on-save`) run them on the given files.
+pub(crate) async fn serve(
+    store: &Store,
+    config: Option<PathBuf>,
+    check_on_save: bool,
+    refresh: bool,
+) -> Result<ExitStatus> {
+    // Stdout is reserved for the protocol.
+    let printer = Printer::Silent;
+
+    let workspace_root = Workspace::find_root(config.as_deref(), &CWD)?;
+    let mut workspace = Workspace::discover(store, workspace_root, config, None, refresh, false)?;
+
+    let lock = store.lock_shared_async().await?;
+    let hooks = workspace
+        .init_hooks(store, Some(&HookInitReporter::new(printer)))
+        .await
+        .context("Failed to init hooks")?
+        .into_iter()
+        .filter(|hook| hook.stages.contains(Stage::PreCommit) && hook.supports_current_platform())
+        .map(Arc::new)
+        .collect();
+    let hooks = install_hooks(hooks, store, &HookInstallReporter::new(printer), false).await?;
+    drop(lock);
+
+    std::env::set_current_dir(workspace.root()).with_context(|| {
+        format!(
+            "Failed to change directory to `{}`",
+            workspace.root().display()
+        )
+    })?;
+    debug!("Serving {} hooks", hooks.len());
+
+    let server = Server {
+        store,
+        workspace: &workspace,
+        hooks,
+        reporter: HookRunReporter::new(printer, 0),
+    };
+
+    let mut messages = spawn_reader();
+    while let Some(message) = messages.recv().await {
+        let message = match message {
+            Ok(message) => message,
+            Err(err) => {
+                warn!("Failed to read message: {err}");
+                break;
+            }
+        };
+        trace!("Received: {message:?}");
+        let Some(method) = message.method else {
+            // A response to a request we never sent.
+            continue;
+        };
+
+        let result = match method.as_str() {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    "textDocumentSync": { "save": check_on_save },
+                },
+                "serverInfo": { "name": "prek", "version": version::version().to_string() },
+            })),
+            "prek/check" => match serde_json::from_value::<CheckParams>(message.params) {
+                Ok(params) => server
+                    .check(&params.files)
+                    .await
+                    .map(|checks| json!({ "hooks": checks }))
+                    .map_err(|err| (INTERNAL_ERROR, format!("{err:#}"))),
+                Err(err) => Err((INVALID_PARAMS, err.to_string())),
+            },
+            "textDocument/didSave" if check_on_save => {
+                match serde_json::from_value::<DidSaveParams>(message.params) {
+                    Ok(params) => {
+                        if let Err(err) = server.publish_diagnostics(params.text_document.uri).await
+                        {
+                            warn!("Failed to check saved file: {err:#}");
+                        }
+                    }
+                    Err(err) => warn!("Invalid `didSave` notification: {err}"),
+                }
+                continue;
+            }
+            "shutdown" => Ok(Value::Null),
+            "exit" => break,
+            _ => Err((METHOD_NOT_FOUND, format!("Unknown method `{method}`"))),
+        };
+
+        // Notifications don't get a response.
+        let Some(id) = message.id else {
+            continue;
+        };
+        let response = match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": code, "message": message },
+            }),
+        };
+        send(&response)?;
+    }
+
+    Ok(ExitStatus::Success)
+}
+
+struct Server<'a> {
+    store: &'a Store,
+    workspace: &'a Workspace,
+    hooks: Vec<InstalledHook>,
+    reporter: HookRunReporter,
+}
+
+impl Server<'_> {
+    /// Run the hooks on the files, like `prek run --files`.
+    async fn check(&self, files: &[PathBuf]) -> Result<Vec<HookCheck>> {
+        // Files outside the workspace have no hooks.
+        let files = files
+            .iter()
+            .filter_map(|file| {
+                let file = std::path::absolute(file).ok()?.clean();
+                file.strip_prefix(self.workspace.root())
+                    .ok()
+                    .map(Path::to_path_buf)
+            })
+            .collect::<Vec<_>>();
+
+        let mut checks = Vec::new();
+        let mut consumed_files = FxHashSet::default();
+        for project in self.workspace.all_projects() {
+            let filter =
+                FileFilter::for_project(files.iter(), project, Some(&mut consumed_files), None);
+
+            for hook in self.hooks.iter().filter(|hook| hook.project() == project) {
+                let mut matched = filter.for_hook(hook);
+                if let Some(max_file_size) = filter.max_file_size(hook) {
+                    let oversized = filter.take_oversized(&mut matched, max_file_size);
+                    debug!(
+                        "Skipped {} files larger than {max_file_size} bytes",
+                        oversized.len()
+                    );
+                }
+                if matched.is_empty() || !Language::supported(hook.language) {
+                    continue;
+                }
+                let filenames = if hook.pass_filenames {
+                    matched.clone()
+                } else {
+                    vec![]
+                };
+
+                let (exit_code, output) = hook
+                    .language
+                    .run(hook, &filenames, self.store, &self.reporter)
+                    .await
+                    .with_context(|| format!("Failed to run hook `{hook}`"))?;
+                let passed = hook.success_exit_codes.contains(&exit_code)
+                    && (!hook.fail_on_output || output.trim_ascii().is_empty());
+
+                checks.push(HookCheck {
+                    id: hook.id.clone(),
+                    name: hook.name.clone(),
+                    project: project.to_string(),
+                    passed,
+                    exit_code,
+                    files: matched
+                        .into_iter()
+                        .map(|file| project.relative_path().join(file))
+                        .collect(),
+                    output: String::from_utf8_lossy(&output).into_owned(),
+                });
+            }
+        }
+
+        Ok(checks)
+    }
+
+    /// Check a saved file and publish a diagnostic for each failed hook.
+    async fn publish_diagnostics(&self, uri: url::Url) -> Result<()> {
+        let Ok(path) = uri.to_file_path() else {
+            debug!("Ignoring non-file URI `{uri}`");
+            return Ok(());
+        };
+
+        let diagnostics = self
+            .check(&[path])
+            .await?
+            .into_iter()
+            .filter(|check| !check.passed)
+            .map(|check| {
+                json!({
+                    "range": {
+                        "start": { "line": 0, "character": 0 },
+                        "end": { "line": 0, "character": 0 },
+                    },
+                    "severity": SEVERITY_ERROR,
+                    "source": "prek",
+                    "code": check.id,
+                    "message": format!("{} failed\n{}", check.name, check.output.trim_end()),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        send(&json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "diagnostics": diagnostics },
+        }))
+    }
+}
+
+/// Read messages from stdin on a blocking thread.
+fn spawn_reader() -> mpsc::UnboundedReceiver<Result<Message>> {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    tokio::task::spawn_blocking(move || {
+        let mut stdin = std::io::stdin().lock();
+        loop {
+            let message = match read_message(&mut stdin) {
+                Ok(Some(message)) => Ok(message),
+                // Stdin was closed.
+                Ok(None) => break,
+                Err(err) => Err(err),
+            };
+            let failed = message.is_err();
+            if sender.send(message).is_err() || failed {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+/// Read a message framed with a `Content-Length` header.
+fn read_message(reader: &mut impl BufRead) -> Result<Option<Message>> {
+    let mut content_length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.eq_ignore_ascii_case("Content-Length")
+        {
+            content_length = Some(value.trim().parse::<usize>()?);
+        }
+    }
+
+    let content_length = content_length.context("Missing `Content-Length` header")?;
+    let mut content = vec![0; content_length];
+    reader.read_exact(&mut content)?;
+    Ok(Some(serde_json::from_slice(&content)?))
+}
+
+fn send(message: &Value) -> Result<()> {
+    let content = serde_json::to_vec(message)?;
+    let mut stdout = std::io::stdout().lock();
+    write!(stdout, "Content-Length: {}\r\n\r\n", content.len())?;
+    stdout.write_all(&content)?;
+    stdout.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_framed_message() -> Result<()> {
+        let body = r#"{"jsonrpc":"2.0","id":1,"method":"prek/check","params":{"files":["a.py"]}}"#;
+        let input = format!(
+            "Content-Length: {}\r\nContent-Type: application/vscode-jsonrpc\r\n\r\n{body}",
+            body.len()
+        );
+        let mut reader = std::io::Cursor::new(input);
+
+        let message = read_message(&mut reader)?.expect("a message");
+        assert_eq!(message.id, Some(json!(1)));
+        assert_eq!(message.method.as_deref(), Some("prek/check"));
+        let params: CheckParams = serde_json::from_value(message.params)?;
+        assert_eq!(params.files, [PathBuf::from("a.py")]);
+
+        assert!(read_message(&mut reader)?.is_none());
+        Ok(())
+    }
+}
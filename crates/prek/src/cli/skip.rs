@@ -0,0 +1,101 @@
+use std::fmt::Write;
+
+use anyhow::Result;
+use owo_colors::OwoColorize;
+
+use crate::cli::ExitStatus;
+use crate::cli::run::normalize_local_skip;
+use crate::fs::Simplified;
+use crate::git::GIT_ROOT;
+use crate::local_skips::LocalSkips;
+use crate::printer::Printer;
+use crate::warn_user;
+
+/// Add skip selectors to the local repository.
+pub(crate) fn skip_add(selectors: &[String], printer: Printer) -> Result<ExitStatus> {
+    let git_root = GIT_ROOT.as_ref()?;
+    let path = LocalSkips::path()?;
+    let mut local_skips = LocalSkips::load(&path)?;
+
+    for selector in selectors {
+        let selector = normalize_local_skip(selector, git_root)?;
+        if local_skips.selectors.contains(&selector) {
+            writeln!(printer.stdout(), "`{}` is already skipped", selector.cyan())?;
+            continue;
+        }
+        writeln!(printer.stdout(), "Skipping `{}`", selector.cyan())?;
+        local_skips.selectors.push(selector);
+    }
+    local_skips.save(&path)?;
+
+    Ok(ExitStatus::Success)
+}
+
+/// Remove skip selectors from the local repository.
+pub(crate) fn skip_remove(selectors: &[String], all: bool, printer: Printer) -> Result<ExitStatus> {
+    let git_root = GIT_ROOT.as_ref()?;
+    let path = LocalSkips::path()?;
+    let mut local_skips = LocalSkips::load(&path)?;
+
+    if all {
+        for selector in &local_skips.selectors {
+            writeln!(printer.stdout(), "No longer skipping `{}`", selector.cyan())?;
+        }
+        local_skips.selectors.clear();
+        local_skips.save(&path)?;
+        return Ok(ExitStatus::Success);
+    }
+
+    let mut status = ExitStatus::Success;
+    for selector in selectors {
+        // Selectors that don't parse anymore can only be removed as written in the file.
+        let normalized = normalize_local_skip(selector, git_root).ok();
+        let before = local_skips.selectors.len();
+        local_skips
+            .selectors
+            .retain(|skip| skip != selector && Some(skip) != normalized.as_ref());
+        if local_skips.selectors.len() == before {
+            warn_user!(
+                "`{}` is not skipped, see `{}`",
+                selector,
+                "prek skip list".cyan()
+            );
+            status = ExitStatus::Failure;
+        } else {
+            writeln!(
+                printer.stdout(),
+                "No longer skipping `{}`",
+                normalized.as_deref().unwrap_or(selector).cyan()
+            )?;
+        }
+    }
+    local_skips.save(&path)?;
+
+    Ok(status)
+}
+
+/// List the skip selectors of the local repository.
+pub(crate) fn skip_list(printer: Printer) -> Result<ExitStatus> {
+    let path = LocalSkips::path()?;
+    let local_skips = LocalSkips::load(&path)?;
+
+    if local_skips.selectors.is_empty() {
+        writeln!(
+            printer.stderr(),
+            "No hooks are skipped in this repository, add skips with `{}`",
+            "prek skip add".cyan()
+        )?;
+        return Ok(ExitStatus::Success);
+    }
+
+    for selector in &local_skips.selectors {
+        writeln!(printer.stdout(), "{selector}")?;
+    }
+    writeln!(
+        printer.stderr(),
+        "{}",
+        format!("Stored in `{}`", path.user_display()).dimmed()
+    )?;
+
+    Ok(ExitStatus::Success)
+}
@@ -0,0 +1,177 @@
+use std::fmt::Write;
+
+use anyhow::Result;
+use owo_colors::OwoColorize;
+use rustc_hash::FxHashMap;
+use serde::Serialize;
+
+use crate::cli::{ExitStatus, ListOutputFormat};
+use crate::printer::Printer;
+use crate::stats::{self, Outcome};
+use crate::store::Store;
+
+#[derive(Debug, Serialize)]
+struct HookStats {
+    project: String,
+    hook: String,
+    runs: usize,
+    failed: usize,
+    /// Number of times the outcome changed from one run to the next.
+    flips: usize,
+    /// Mean duration in seconds.
+    mean: f64,
+    /// Max duration in seconds.
+    max: f64,
+    /// Total duration in seconds.
+    total: f64,
+}
+
+impl HookStats {
+    fn full_id(&self) -> String {
+        format!("{}:{}", self.project, self.hook)
+    }
+}
+
+/// Show the recorded hook durations and outcomes.
+pub(crate) fn stats(
+    store: &Store,
+    slowest: bool,
+    flaky: bool,
+    limit: usize,
+    clear: bool,
+    output_format: ListOutputFormat,
+    printer: Printer,
+) -> Result<ExitStatus> {
+    let path = store.stats_file();
+
+    if clear {
+        match fs_err::remove_file(&path) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err.into()),
+        }
+        writeln!(printer.stdout(), "Cleared hook run history")?;
+        return Ok(ExitStatus::Success);
+    }
+
+    let records = stats::load(&path)?;
+    if records.is_empty() && matches!(output_format, ListOutputFormat::Text) {
+        writeln!(
+            printer.stdout(),
+            "No hook runs recorded yet, set `{}` to record them",
+            "PREK_STATS=1".cyan()
+        )?;
+        return Ok(ExitStatus::Success);
+    }
+
+    // Records are appended in chronological order.
+    let mut hooks: FxHashMap<(String, String), (HookStats, Option<Outcome>)> = FxHashMap::default();
+    for record in records {
+        let (stats, last) = hooks
+            .entry((record.project.clone(), record.hook.clone()))
+            .or_insert_with(|| {
+                (
+                    HookStats {
+                        project: record.project,
+                        hook: record.hook,
+                        runs: 0,
+                        failed: 0,
+                        flips: 0,
+                        mean: 0.0,
+                        max: 0.0,
+                        total: 0.0,
+                    },
+                    None,
+                )
+            });
+        stats.runs += 1;
+        if record.outcome == Outcome::Failed {
+            stats.failed += 1;
+        }
+        if last.is_some_and(|last| last != record.outcome) {
+            stats.flips += 1;
+        }
+        *last = Some(record.outcome);
+        stats.total += record.duration;
+        stats.max = stats.max.max(record.duration);
+    }
+
+    let mut hooks = hooks
+        .into_values()
+        .map(|(mut stats, _)| {
+            #[allow(clippy::cast_precision_loss)]
+            let runs = stats.runs as f64;
+            stats.mean = stats.total / runs;
+            stats
+        })
+        .collect::<Vec<_>>();
+
+    if flaky {
+        hooks.retain(|stats| stats.flips > 0);
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let key = |stats: &HookStats| {
+        if flaky {
+            (stats.flips as f64, stats.failed as f64)
+        } else if slowest {
+            (stats.mean, 0.0)
+        } else {
+            (stats.total, 0.0)
+        }
+    };
+    // Largest first, ties broken by hook id to keep the order stable.
+    hooks.sort_by(|a, b| {
+        let (a1, a2) = key(a);
+        let (b1, b2) = key(b);
+        b1.total_cmp(&a1)
+            .then(b2.total_cmp(&a2))
+            .then_with(|| a.full_id().cmp(&b.full_id()))
+    });
+    if limit > 0 {
+        hooks.truncate(limit);
+    }
+
+    match output_format {
+        ListOutputFormat::Text => {
+            if hooks.is_empty() {
+                writeln!(printer.stdout(), "No flaky hooks found")?;
+                return Ok(ExitStatus::Success);
+            }
+
+            let width = hooks
+                .iter()
+                .map(|stats| stats.full_id().len())
+                .max()
+                .unwrap_or_default()
+                .max("Hook".len());
+            writeln!(
+                printer.stdout(),
+                "{}",
+                format!(
+                    "{:<width$}  {:>6}  {:>6}  {:>6}  {:>9}  {:>9}  {:>9}",
+                    "Hook", "Runs", "Failed", "Flips", "Mean", "Max", "Total"
+                )
+                .bold()
+            )?;
+            for stats in &hooks {
+                writeln!(
+                    printer.stdout(),
+                    "{:<width$}  {:>6}  {:>6}  {:>6}  {:>9}  {:>9}  {:>9}",
+                    stats.full_id(),
+                    stats.runs,
+                    stats.failed,
+                    stats.flips,
+                    format!("{:.2}s", stats.mean),
+                    format!("{:.2}s", stats.max),
+                    format!("{:.2}s", stats.total),
+                )?;
+            }
+        }
+        ListOutputFormat::Json => {
+            let json_output = serde_json::to_string_pretty(&hooks)?;
+            writeln!(printer.stdout(), "{json_output}")?;
+        }
+    }
+
+    Ok(ExitStatus::Success)
+}
@@ -12,6 +12,7 @@ use crate::cli::run::Selectors;
 use crate::config;
 use crate::git;
 use crate::git::GIT_ROOT;
+use crate::offline;
 use crate::printer::Printer;
 use crate::store::Store;
 use crate::warn_user;
@@ -89,6 +90,31 @@ async fn clone_and_commit(repo_path: &Path, head_rev: &str, tmp_dir: &Path) -> R
     Ok(shadow)
 }
 
+/// Resolve the `HEAD` revision of a local or remote repository.
+pub(crate) async fn head_rev(repo: &str) -> Result<String> {
+    let repo_path = Path::new(repo);
+    if repo_path.is_dir() {
+        return get_head_rev(repo_path).await;
+    }
+
+    // For remote repositories, use ls-remote
+    offline::ensure_online(|| format!("Resolving the HEAD revision of `{repo}`"))?;
+    let head_rev = git::git_cmd("get head rev")?
+        .arg("ls-remote")
+        .arg("--exit-code")
+        .arg(repo)
+        .arg("HEAD")
+        .output()
+        .await?
+        .stdout;
+    let head_rev = String::from_utf8_lossy(&head_rev)
+        .split_ascii_whitespace()
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("Failed to parse HEAD revision from git ls-remote output"))?
+        .to_string();
+    Ok(head_rev)
+}
+
 async fn prepare_repo_and_rev<'a>(
     repo: &'a str,
     rev: Option<&'a str>,
@@ -102,27 +128,7 @@ async fn prepare_repo_and_rev<'a>(
         return Ok((Cow::Borrowed(repo), rev.to_string()));
     }
 
-    // Get HEAD revision
-    let head_rev = if is_local {
-        get_head_rev(repo_path).await?
-    } else {
-        // For remote repositories, use ls-remote
-        let head_rev = git::git_cmd("get head rev")?
-            .arg("ls-remote")
-            .arg("--exit-code")
-            .arg(repo)
-            .arg("HEAD")
-            .output()
-            .await?
-            .stdout;
-        String::from_utf8_lossy(&head_rev)
-            .split_ascii_whitespace()
-            .next()
-            .ok_or_else(|| {
-                anyhow::anyhow!("Failed to parse HEAD revision from git ls-remote output")
-            })?
-            .to_string()
-    };
+    let head_rev = head_rev(repo).await?;
 
     // If repo is a local repo with uncommitted changes, create a shadow repo to commit the changes.
     if is_local && git::has_diff("HEAD", repo_path).await? {
@@ -147,6 +153,9 @@ pub(crate) async fn try_repo(
     if config.is_some() {
         warn_user!("`--config` option is ignored when using `try-repo`");
     }
+    if run_args.stdin {
+        anyhow::bail!("`--stdin` is not supported by `try-repo`");
+    }
 
     let store = Store::from_settings()?;
     let tmp_dir = TempDir::with_prefix_in("try-repo-", store.scratch_path())?;
@@ -165,7 +174,7 @@ pub(crate) async fn try_repo(
 
     let selectors = Selectors::load(&run_args.includes, &run_args.skips, GIT_ROOT.as_ref()?)?;
 
-    let manifest = config::read_manifest(&repo_clone_path.join(prek_consts::MANIFEST_FILE))?;
+    let manifest = config::read_manifest(&config::manifest_path(&repo_clone_path))?;
     let hooks_str = manifest
         .hooks
         .into_iter()
@@ -196,16 +205,32 @@ pub(crate) async fn try_repo(
         Some(config_file),
         vec![],
         vec![],
+        run_args.no_default_skips,
         run_args.hook_stage,
+        run_args.hook_kind(),
         run_args.from_ref,
         run_args.to_ref,
         run_args.all_files,
         run_args.files,
         run_args.directory,
+        run_args.include_untracked,
+        run_args.include_ignored,
+        run_args.recurse_submodules,
         run_args.last_commit,
+        run_args.changed_only,
+        run_args.retry_failed,
+        run_args.max_file_size,
         run_args.show_diff_on_failure,
+        run_args.diff_context,
+        run_args.word_diff,
+        run_args.show_fixes,
         run_args.fail_fast,
+        run_args.modified_exit_code,
+        run_args.autofix_stage,
+        run_args.serial_projects,
         run_args.dry_run,
+        run_args.write_summary,
+        run_args.external_reporter,
         refresh,
         run_args.extra,
         verbose,
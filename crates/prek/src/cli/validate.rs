@@ -1,17 +1,22 @@
 use std::error::Error;
 use std::fmt::Write;
 use std::iter;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use owo_colors::OwoColorize;
 
 use crate::cli::ExitStatus;
-use crate::config::{read_config, read_manifest};
+use crate::config::{Lint, lint_config, read_config, read_manifest};
+use crate::fs::Simplified;
 use crate::printer::Printer;
 use crate::warn_user;
 
-pub(crate) fn validate_configs(configs: Vec<PathBuf>, printer: Printer) -> Result<ExitStatus> {
+pub(crate) fn validate_configs(
+    configs: Vec<PathBuf>,
+    strict: bool,
+    printer: Printer,
+) -> Result<ExitStatus> {
     let mut status = ExitStatus::Success;
 
     if configs.is_empty() {
@@ -20,17 +25,33 @@ pub(crate) fn validate_configs(configs: Vec<PathBuf>, printer: Printer) -> Resul
     }
 
     for config in configs {
-        if let Err(err) = read_config(&config) {
-            writeln!(printer.stderr(), "{}: {}", "error".red().bold(), err)?;
-            for source in iter::successors(err.source(), |&err| err.source()) {
-                writeln!(
-                    printer.stderr(),
-                    "  {}: {}",
-                    "caused by".red().bold(),
-                    source
-                )?;
+        let lints = if strict {
+            lint_config(&config)
+        } else {
+            read_config(&config).map(|_| Vec::new())
+        };
+
+        match lints {
+            Ok(lints) => {
+                for lint in &lints {
+                    print_lint(&config, lint, printer)?;
+                }
+                if !lints.is_empty() {
+                    status = ExitStatus::Failure;
+                }
+            }
+            Err(err) => {
+                writeln!(printer.stderr(), "{}: {}", "error".red().bold(), err)?;
+                for source in iter::successors(err.source(), |&err| err.source()) {
+                    writeln!(
+                        printer.stderr(),
+                        "  {}: {}",
+                        "caused by".red().bold(),
+                        source
+                    )?;
+                }
+                status = ExitStatus::Failure;
             }
-            status = ExitStatus::Failure;
         }
     }
 
@@ -45,6 +66,26 @@ pub(crate) fn validate_configs(configs: Vec<PathBuf>, printer: Printer) -> Resul
     Ok(status)
 }
 
+fn print_lint(config: &Path, lint: &Lint, printer: Printer) -> Result<()> {
+    let source = match &lint.location {
+        Some(location) => format!(
+            "{}:{}:{}",
+            config.user_display(),
+            location.line(),
+            location.column()
+        ),
+        None => config.user_display().to_string(),
+    };
+    writeln!(
+        printer.stderr(),
+        "{}: {}: {}",
+        "error".red().bold(),
+        source.cyan(),
+        lint.message
+    )?;
+    Ok(())
+}
+
 pub(crate) fn validate_manifest(manifests: Vec<PathBuf>, printer: Printer) -> Result<ExitStatus> {
     let mut status = ExitStatus::Success;
 
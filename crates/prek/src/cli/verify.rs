@@ -0,0 +1,354 @@
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use anyhow::{Context, Result};
+use owo_colors::OwoColorize;
+use prek_consts::env_vars::EnvVars;
+use serde::Deserialize;
+use tempfile::TempDir;
+
+use crate::cli::ExitStatus;
+use crate::cli::run::summary::{HookSummary, SummaryStatus};
+use crate::config::Stage;
+use crate::git::{self, GIT_ROOT};
+use crate::printer::Printer;
+use crate::process::Cmd;
+use crate::store::Store;
+
+/// A commit of the verified range.
+struct Commit {
+    id: String,
+    subject: String,
+}
+
+/// The part of the `--write-summary` output of `prek run` needed to report failed hooks.
+#[derive(Deserialize)]
+struct RunSummary {
+    hooks: Vec<HookSummary>,
+}
+
+enum Outcome {
+    Passed,
+    /// The hooks that failed, as `hook-id` or `project-path:hook-id`.
+    Failed(Vec<String>),
+    /// `prek run` failed before running hooks, e.g. because the commit has no config.
+    Error(String),
+}
+
+/// Run the hooks on each commit of `from..to`, or once on the combined diff of the range,
+/// in a temporary worktree.
+///
+/// `prek run` runs as a separate process in the worktree, as the git and project discovery
+/// of a run is tied to the current directory.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn verify(
+    store: &Store,
+    config: Option<PathBuf>,
+    range: &str,
+    combined: bool,
+    includes: Vec<String>,
+    skips: Vec<String>,
+    hook_stage: Option<Stage>,
+    verbose: bool,
+    printer: Printer,
+) -> Result<ExitStatus> {
+    if config.is_some() {
+        crate::warn_user!("`--config` option is ignored when using `verify`");
+    }
+    LazyLock::force(&GIT_ROOT).as_ref()?;
+
+    let Some((from, to)) = range.split_once("..") else {
+        anyhow::bail!("Expected a revision range like `main..HEAD`, got `{range}`");
+    };
+    if to.starts_with('.') {
+        anyhow::bail!("Symmetric difference ranges like `{range}` are not supported, use `..`");
+    }
+    if from.is_empty() {
+        anyhow::bail!("Missing the start of the revision range `{range}`");
+    }
+    let to = if to.is_empty() { "HEAD" } else { to };
+    for rev in [from, to] {
+        if !git::rev_exists(rev).await? {
+            anyhow::bail!("Unknown revision `{rev}`");
+        }
+    }
+
+    let commits = list_commits(from, to).await?;
+    if commits.is_empty() {
+        writeln!(printer.stdout(), "No commits in `{from}..{to}`")?;
+        return Ok(ExitStatus::Success);
+    }
+
+    fs_err::create_dir_all(store.scratch_path())?;
+    let tmp_dir = TempDir::with_prefix_in("verify-", store.scratch_path())?;
+    let worktree = tmp_dir.path().join("worktree");
+    git::git_cmd("add worktree")?
+        .arg("worktree")
+        .arg("add")
+        .arg("--detach")
+        .arg("--quiet")
+        .arg(&worktree)
+        .arg(to)
+        .env(EnvVars::PREK_INTERNAL__SKIP_POST_CHECKOUT, "1")
+        .output()
+        .await
+        .context("Failed to create a temporary worktree")?;
+
+    let result = if combined {
+        let subject = format!("{} commits combined", commits.len());
+        run_in_worktree(
+            &worktree,
+            tmp_dir.path(),
+            Some(from),
+            to,
+            &includes,
+            &skips,
+            hook_stage,
+            verbose,
+            printer,
+        )
+        .await
+        .map(|outcome| {
+            vec![(
+                Commit {
+                    id: format!("{from}..{to}"),
+                    subject,
+                },
+                outcome,
+            )]
+        })
+    } else {
+        verify_commits(
+            commits,
+            &worktree,
+            tmp_dir.path(),
+            &includes,
+            &skips,
+            hook_stage,
+            verbose,
+            printer,
+        )
+        .await
+    };
+
+    // Remove the worktree even if a run failed, `git worktree prune` would only do it later.
+    git::git_cmd("remove worktree")?
+        .arg("worktree")
+        .arg("remove")
+        .arg("--force")
+        .arg(&worktree)
+        .check(false)
+        .output()
+        .await?;
+
+    report(&result?, combined, printer)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn verify_commits(
+    commits: Vec<Commit>,
+    worktree: &Path,
+    tmp_dir: &Path,
+    includes: &[String],
+    skips: &[String],
+    hook_stage: Option<Stage>,
+    verbose: bool,
+    printer: Printer,
+) -> Result<Vec<(Commit, Outcome)>> {
+    let mut results = Vec::with_capacity(commits.len());
+    for commit in commits {
+        let parent = git::get_parent_commit(&commit.id).await?;
+        let outcome = run_in_worktree(
+            worktree,
+            tmp_dir,
+            parent.as_deref(),
+            &commit.id,
+            includes,
+            skips,
+            hook_stage,
+            verbose,
+            printer,
+        )
+        .await?;
+        results.push((commit, outcome));
+    }
+    Ok(results)
+}
+
+/// Check out `to` in the worktree and run the hooks on the files changed since `from`, or on all
+/// files if `to` is a root commit.
+#[allow(clippy::too_many_arguments)]
+async fn run_in_worktree(
+    worktree: &Path,
+    tmp_dir: &Path,
+    from: Option<&str>,
+    to: &str,
+    includes: &[String],
+    skips: &[String],
+    hook_stage: Option<Stage>,
+    verbose: bool,
+    printer: Printer,
+) -> Result<Outcome> {
+    // Drop the changes of hooks that modified files in the previous run.
+    git::git_cmd("checkout commit")?
+        .current_dir(worktree)
+        .arg("checkout")
+        .arg("--force")
+        .arg("--detach")
+        .arg("--quiet")
+        .arg(to)
+        .env(EnvVars::PREK_INTERNAL__SKIP_POST_CHECKOUT, "1")
+        .remove_git_envs()
+        .output()
+        .await?;
+    git::git_cmd("clean worktree")?
+        .current_dir(worktree)
+        .arg("clean")
+        .arg("-d")
+        .arg("--force")
+        .arg("--quiet")
+        .remove_git_envs()
+        .output()
+        .await?;
+
+    let summary_file = tmp_dir.join("summary.json");
+    fs_err::remove_file(&summary_file).or_else(|err| {
+        if err.kind() == std::io::ErrorKind::NotFound {
+            Ok(())
+        } else {
+            Err(err)
+        }
+    })?;
+
+    let prek = std::env::current_exe()?;
+    let mut cmd = Cmd::new(&prek, "prek run");
+    cmd.current_dir(worktree)
+        .arg("run")
+        .args(includes)
+        .arg("--write-summary")
+        .arg(&summary_file)
+        .remove_git_envs()
+        .check(false);
+    match from {
+        Some(from) => cmd.arg("--from-ref").arg(from).arg("--to-ref").arg(to),
+        None => cmd.arg("--all-files"),
+    };
+    for skip in skips {
+        cmd.arg("--skip").arg(skip);
+    }
+    if let Some(hook_stage) = hook_stage {
+        cmd.arg("--hook-stage").arg(hook_stage.as_str());
+    }
+    let output = cmd.output().await?;
+
+    if verbose {
+        write!(
+            printer.stdout(),
+            "{}",
+            String::from_utf8_lossy(&output.stdout)
+        )?;
+        write!(
+            printer.stderr(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        )?;
+    }
+
+    if !summary_file.exists() {
+        return Ok(Outcome::Error(
+            String::from_utf8_lossy(output.stderr.trim_ascii()).to_string(),
+        ));
+    }
+    let summary: RunSummary = serde_json::from_str(&fs_err::read_to_string(&summary_file)?)
+        .context("Failed to read the run summary")?;
+    let failed = summary
+        .hooks
+        .into_iter()
+        .filter(|hook| hook.status == SummaryStatus::Failed)
+        .map(|hook| {
+            if hook.project == "." {
+                hook.id
+            } else {
+                format!("{}:{}", hook.project, hook.id)
+            }
+        })
+        .collect::<Vec<_>>();
+    if failed.is_empty() {
+        Ok(Outcome::Passed)
+    } else {
+        Ok(Outcome::Failed(failed))
+    }
+}
+
+/// The non-merge commits of `from..to`, oldest first.
+async fn list_commits(from: &str, to: &str) -> Result<Vec<Commit>> {
+    let output = git::git_cmd("list commits")?
+        .arg("log")
+        .arg("--reverse")
+        .arg("--no-merges")
+        .arg("--format=%H%x00%s")
+        .arg(format!("{from}..{to}"))
+        .arg("--")
+        .output()
+        .await?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (id, subject) = line.split_once('\0')?;
+            Some(Commit {
+                id: id.to_string(),
+                subject: subject.to_string(),
+            })
+        })
+        .collect())
+}
+
+fn report(results: &[(Commit, Outcome)], combined: bool, printer: Printer) -> Result<ExitStatus> {
+    for (commit, outcome) in results {
+        let status = match outcome {
+            Outcome::Passed => "passed".green().to_string(),
+            Outcome::Failed(hooks) => format!("{}: {}", "failed".red(), hooks.join(", ")),
+            Outcome::Error(_) => "error".red().to_string(),
+        };
+        let id = if combined {
+            commit.id.as_str()
+        } else {
+            commit.id.get(..10).unwrap_or(&commit.id)
+        };
+        writeln!(
+            printer.stdout(),
+            "{}  {}  {status}",
+            id.yellow(),
+            commit.subject
+        )?;
+    }
+
+    for (commit, outcome) in results {
+        if let Outcome::Error(error) = outcome {
+            writeln!(
+                printer.stderr(),
+                "{}: Failed to run hooks on `{}`:\n{error}",
+                "error".red().bold(),
+                commit.id.cyan()
+            )?;
+        }
+    }
+
+    let failed = results
+        .iter()
+        .filter(|(_, outcome)| !matches!(outcome, Outcome::Passed))
+        .count();
+    if failed == 0 {
+        return Ok(ExitStatus::Success);
+    }
+    if !combined {
+        writeln!(
+            printer.stderr(),
+            "{} of {} commits would have failed the hooks",
+            failed,
+            results.len()
+        )?;
+    }
+    Ok(ExitStatus::Failure)
+}
@@ -1,23 +1,29 @@
 #[cfg(feature = "schemars")]
 use std::borrow::Cow;
-use std::collections::BTreeMap;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
 use std::fmt::Display;
+use std::iter;
+use std::num::NonZeroUsize;
 use std::ops::RangeInclusive;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::LazyLock;
 
 use anyhow::Result;
 use fancy_regex::Regex;
 use globset::{Glob, GlobSet, GlobSetBuilder};
 use itertools::Itertools;
-use prek_consts::{ALT_CONFIG_FILE, CONFIG_FILE};
-use rustc_hash::FxHashMap;
+use owo_colors::OwoColorize;
+use prek_consts::{ALT_CONFIG_FILE, CONFIG_FILE, MANIFEST_FILE, TOML_MANIFEST_FILE};
+use rustc_hash::{FxHashMap, FxHashSet};
 use serde::{Deserialize, Deserializer, Serialize};
 use tracing::instrument;
 
 use crate::fs::Simplified;
 use crate::version;
 use crate::warn_user;
+use crate::warnings::{self, WarningLevel};
+use crate::yaml::PathSegment;
 use crate::{identify, yaml};
 
 pub(crate) static CONFIG_FILE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
@@ -72,6 +78,10 @@ enum FilePatternWireError {
 pub(crate) enum FilePattern {
     Regex(Regex),
     Glob(GlobPatterns),
+    /// Matches what both patterns match, the repo and hook level `files` of a hook.
+    All(Box<FilePattern>, Box<FilePattern>),
+    /// Matches what either pattern matches, the repo and hook level `exclude` of a hook.
+    Any(Box<FilePattern>, Box<FilePattern>),
 }
 
 #[cfg(feature = "schemars")]
@@ -126,6 +136,24 @@ impl FilePattern {
         match self {
             FilePattern::Regex(regex) => regex.is_match(str).unwrap_or(false),
             FilePattern::Glob(globs) => globs.is_match(str),
+            FilePattern::All(a, b) => a.is_match(str) && b.is_match(str),
+            FilePattern::Any(a, b) => a.is_match(str) || b.is_match(str),
+        }
+    }
+
+    /// Combine two optional include patterns, a file must match both.
+    pub(crate) fn all(a: Option<Self>, b: Option<Self>) -> Option<Self> {
+        match (a, b) {
+            (Some(a), Some(b)) => Some(Self::All(Box::new(a), Box::new(b))),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Combine two optional exclude patterns, a file is excluded if it matches either.
+    pub(crate) fn any(a: Option<Self>, b: Option<Self>) -> Option<Self> {
+        match (a, b) {
+            (Some(a), Some(b)) => Some(Self::Any(Box::new(a), Box::new(b))),
+            (a, b) => a.or(b),
         }
     }
 }
@@ -138,6 +166,8 @@ impl Display for FilePattern {
                 let patterns = globs.patterns.iter().join(", ");
                 write!(f, "glob: [{patterns}]")
             }
+            FilePattern::All(a, b) => write!(f, "{a} and {b}"),
+            FilePattern::Any(a, b) => write!(f, "{a} or {b}"),
         }
     }
 }
@@ -228,14 +258,17 @@ pub(crate) enum HookType {
     CommitMsg,
     PostCheckout,
     PostCommit,
+    PostIndexChange,
     PostMerge,
     PostRewrite,
+    PreAutoGc,
     #[default]
     PreCommit,
     PreMergeCommit,
     PrePush,
     PreRebase,
     PrepareCommitMsg,
+    ReferenceTransaction,
 }
 
 impl HookType {
@@ -244,13 +277,16 @@ impl HookType {
             Self::CommitMsg => "commit-msg",
             Self::PostCheckout => "post-checkout",
             Self::PostCommit => "post-commit",
+            Self::PostIndexChange => "post-index-change",
             Self::PostMerge => "post-merge",
             Self::PostRewrite => "post-rewrite",
+            Self::PreAutoGc => "pre-auto-gc",
             Self::PreCommit => "pre-commit",
             Self::PreMergeCommit => "pre-merge-commit",
             Self::PrePush => "pre-push",
             Self::PreRebase => "pre-rebase",
             Self::PrepareCommitMsg => "prepare-commit-msg",
+            Self::ReferenceTransaction => "reference-transaction",
         }
     }
 
@@ -261,14 +297,22 @@ impl HookType {
             Self::PostCheckout => 3..=3,
             Self::PreCommit => 0..=0,
             Self::PostCommit => 0..=0,
+            Self::PostIndexChange => 2..=2,
+            Self::PreAutoGc => 0..=0,
             Self::PreMergeCommit => 0..=0,
             Self::PostMerge => 1..=1,
             Self::PostRewrite => 1..=1,
             Self::PrePush => 2..=2,
             Self::PreRebase => 1..=2,
             Self::PrepareCommitMsg => 1..=3,
+            Self::ReferenceTransaction => 1..=1,
         }
     }
+
+    /// Whether git passes input to this hook on stdin, which is then re-provided to the hooks.
+    pub fn forwards_stdin(self) -> bool {
+        matches!(self, Self::PrePush | Self::ReferenceTransaction)
+    }
 }
 
 impl Display for HookType {
@@ -278,7 +322,18 @@ impl Display for HookType {
 }
 
 #[derive(
-    Debug, Clone, Copy, PartialEq, Eq, Default, Hash, Deserialize, Serialize, clap::ValueEnum,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Default,
+    Hash,
+    Deserialize,
+    Serialize,
+    clap::ValueEnum,
 )]
 #[serde(rename_all = "kebab-case")]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
@@ -287,8 +342,10 @@ pub(crate) enum Stage {
     CommitMsg,
     PostCheckout,
     PostCommit,
+    PostIndexChange,
     PostMerge,
     PostRewrite,
+    PreAutoGc,
     #[default]
     #[serde(alias = "commit")]
     PreCommit,
@@ -298,6 +355,7 @@ pub(crate) enum Stage {
     PrePush,
     PreRebase,
     PrepareCommitMsg,
+    ReferenceTransaction,
 }
 
 impl From<HookType> for Stage {
@@ -306,13 +364,16 @@ impl From<HookType> for Stage {
             HookType::CommitMsg => Self::CommitMsg,
             HookType::PostCheckout => Self::PostCheckout,
             HookType::PostCommit => Self::PostCommit,
+            HookType::PostIndexChange => Self::PostIndexChange,
             HookType::PostMerge => Self::PostMerge,
             HookType::PostRewrite => Self::PostRewrite,
+            HookType::PreAutoGc => Self::PreAutoGc,
             HookType::PreCommit => Self::PreCommit,
             HookType::PreMergeCommit => Self::PreMergeCommit,
             HookType::PrePush => Self::PrePush,
             HookType::PreRebase => Self::PreRebase,
             HookType::PrepareCommitMsg => Self::PrepareCommitMsg,
+            HookType::ReferenceTransaction => Self::ReferenceTransaction,
         }
     }
 }
@@ -324,13 +385,16 @@ impl Stage {
             Self::CommitMsg => "commit-msg",
             Self::PostCheckout => "post-checkout",
             Self::PostCommit => "post-commit",
+            Self::PostIndexChange => "post-index-change",
             Self::PostMerge => "post-merge",
             Self::PostRewrite => "post-rewrite",
+            Self::PreAutoGc => "pre-auto-gc",
             Self::PreCommit => "pre-commit",
             Self::PreMergeCommit => "pre-merge-commit",
             Self::PrePush => "pre-push",
             Self::PreRebase => "pre-rebase",
             Self::PrepareCommitMsg => "prepare-commit-msg",
+            Self::ReferenceTransaction => "reference-transaction",
         }
     }
 }
@@ -355,6 +419,66 @@ impl Stage {
     }
 }
 
+/// What a hook does to the files it runs on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub(crate) enum HookKind {
+    /// The hook modifies files to fix the problems it finds.
+    Fixer,
+    /// The hook only reports problems, without modifying files.
+    Checker,
+}
+
+/// An operating system a hook can be restricted to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub(crate) enum HookOs {
+    Linux,
+    Macos,
+    Windows,
+}
+
+impl HookOs {
+    /// The operating system prek is running on, if it is one hooks can be restricted to.
+    pub(crate) fn current() -> Option<Self> {
+        match std::env::consts::OS {
+            "linux" => Some(Self::Linux),
+            "macos" => Some(Self::Macos),
+            "windows" => Some(Self::Windows),
+            _ => None,
+        }
+    }
+}
+
+/// A CPU architecture a hook can be restricted to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub(crate) enum HookArch {
+    #[serde(rename = "x86")]
+    X86,
+    #[serde(rename = "x86_64")]
+    X86_64,
+    #[serde(rename = "arm")]
+    Arm,
+    #[serde(rename = "aarch64")]
+    Aarch64,
+}
+
+impl HookArch {
+    /// The architecture prek is running on, if it is one hooks can be restricted to.
+    pub(crate) fn current() -> Option<Self> {
+        match std::env::consts::ARCH {
+            "x86" => Some(Self::X86),
+            "x86_64" => Some(Self::X86_64),
+            "arm" => Some(Self::Arm),
+            "aarch64" => Some(Self::Aarch64),
+            _ => None,
+        }
+    }
+}
+
 /// Common hook options.
 #[derive(Debug, Clone, Default, Deserialize)]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
@@ -378,6 +502,10 @@ pub(crate) struct HookOptions {
     /// Default is `[]`.
     #[serde(deserialize_with = "deserialize_and_validate_tags", default)]
     pub exclude_types: Option<Vec<String>>,
+    /// Skip files larger than this many bytes instead of passing them to the hook.
+    /// Takes precedence over `max_file_size` of the configuration and `--max-file-size`,
+    /// `0` disables the limit.
+    pub max_file_size: Option<u64>,
     /// Not documented in the official docs.
     pub additional_dependencies: Option<Vec<String>>,
     /// Additional arguments to pass to the hook.
@@ -387,12 +515,40 @@ pub(crate) struct HookOptions {
     /// This hook will run even if there are no matching files.
     /// Default is false.
     pub always_run: Option<bool>,
-    /// If this hook fails, don't run any more hooks.
+    /// If this hook fails, stop running hooks: `true` or `workspace` stops the whole run,
+    /// `project` the remaining hooks of the project, `group` the running hooks of its priority group.
     /// Default is false.
-    pub fail_fast: Option<bool>,
+    pub fail_fast: Option<FailFast>,
     /// Append filenames that would be checked to the hook entry as arguments.
     /// Default is true.
     pub pass_filenames: Option<bool>,
+    /// Provide the refs being pushed, as git passes them to the `pre-push` hook, on stdin.
+    /// Default is false.
+    pub pass_push_refs: Option<bool>,
+    /// Exit codes that count as success, for tools that exit non-zero on warnings.
+    /// Default is `[0]`.
+    pub success_exit_codes: Option<Vec<i32>>,
+    /// Treat the hook as failed when it prints any output, even if it exits successfully.
+    /// Default is false.
+    pub fail_on_output: Option<bool>,
+    /// Run the hook again up to this many times when it fails, before reporting it as failed.
+    /// Default is 0.
+    pub retries: Option<u32>,
+    /// Seconds to wait before each retry of a failed hook.
+    /// Default is 0.
+    pub retry_delay: Option<f64>,
+    /// Operating systems the hook runs on (`linux`, `macos`, `windows`).
+    /// On other platforms the hook is skipped instead of run.
+    /// Default is all operating systems.
+    pub os: Option<Vec<HookOs>>,
+    /// CPU architectures the hook runs on (`x86`, `x86_64`, `arm`, `aarch64`).
+    /// On other platforms the hook is skipped instead of run.
+    /// Default is all architectures.
+    pub arch: Option<Vec<HookArch>>,
+    /// Whether the hook fixes files (`fixer`) or only checks them (`checker`).
+    /// Used by `prek run --only-fixers` and `--only-checkers`.
+    /// Default is unset, builtin hooks are already classified.
+    pub kind: Option<HookKind>,
     /// A description of the hook. For metadata only.
     pub description: Option<String>,
     /// Run the hook on a specific version of the language.
@@ -404,9 +560,17 @@ pub(crate) struct HookOptions {
     /// This hook will execute using a single process instead of in parallel.
     /// Default is false.
     pub require_serial: Option<bool>,
+    /// Pass at most this many files to each invocation of the hook, e.g. `1` for tools that
+    /// take a single file. The invocations still run in parallel unless `require_serial` is set.
+    /// Default is unlimited.
+    pub max_files_per_invocation: Option<NonZeroUsize>,
     /// Priority used by the scheduler to determine ordering and concurrency.
     /// Hooks with the same priority can run in parallel.
     pub priority: Option<u32>,
+    /// Ids of hooks in the same configuration file that must finish before this hook starts.
+    pub after: Option<Vec<String>>,
+    /// Ids of hooks in the same configuration file that must not start before this hook finishes.
+    pub before: Option<Vec<String>>,
     /// Select which git hook(s) to run for.
     /// Default all stages are selected.
     /// See <https://pre-commit.com/#confining-hooks-to-run-at-certain-stages>.
@@ -417,6 +581,12 @@ pub(crate) struct HookOptions {
     /// The minimum version of prek required to run this hook.
     #[serde(deserialize_with = "deserialize_and_validate_minimum_version", default)]
     pub minimum_prek_version: Option<String>,
+    /// Pin the image of a `docker_image` hook to a content digest, e.g. `sha256:...`.
+    /// The local image is verified against the digest before the hook runs.
+    pub image_digest: Option<String>,
+    /// Extra build flags passed to `go install` when installing a `golang` hook,
+    /// e.g. `["-tags=netgo", "-trimpath"]`.
+    pub goflags: Option<Vec<String>>,
     #[serde(skip_serializing)]
     #[serde(flatten)]
     pub _unused_keys: BTreeMap<String, serde_json::Value>,
@@ -441,19 +611,33 @@ impl HookOptions {
             types,
             types_or,
             exclude_types,
+            max_file_size,
             additional_dependencies,
             args,
             always_run,
             fail_fast,
             pass_filenames,
+            pass_push_refs,
+            success_exit_codes,
+            fail_on_output,
+            retries,
+            retry_delay,
+            os,
+            arch,
+            kind,
             description,
             language_version,
             log_file,
             require_serial,
+            max_files_per_invocation,
             priority,
+            after,
+            before,
             stages,
             verbose,
             minimum_prek_version,
+            image_digest,
+            goflags,
         );
 
         // Merge environment variables.
@@ -611,11 +795,171 @@ impl From<BuiltinHook> for ManifestHook {
     }
 }
 
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum UpdatePolicyError {
+    #[error("invalid update policy `{0}`, expected `pin-major`, `pin-minor` or a version range")]
+    Invalid(String),
+}
+
+/// Constraint on the revisions `prek auto-update` may move a remote repo to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(try_from = "String")]
+pub(crate) enum UpdatePolicy {
+    /// Stay on the major version of the current revision.
+    PinMajor,
+    /// Stay on the major and minor version of the current revision.
+    PinMinor,
+    /// Only versions matching the range, like `<2.0` or `~=1.4`.
+    Range(semver::VersionReq),
+}
+
+impl UpdatePolicy {
+    /// Parse a PEP 440 style compatible release clause (`~=1.4`) into a semver range.
+    fn parse_compatible_release(version: &str) -> Option<semver::VersionReq> {
+        let parts = version
+            .split('.')
+            .map(|part| part.parse::<u64>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        let upper = match parts.as_slice() {
+            [major, _] => format!("<{}.0", major + 1),
+            [major, minor, _] => format!("<{major}.{}", minor + 1),
+            _ => return None,
+        };
+        semver::VersionReq::parse(&format!(">={version}, {upper}")).ok()
+    }
+}
+
+impl std::str::FromStr for UpdatePolicy {
+    type Err = UpdatePolicyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        match s {
+            "pin-major" => return Ok(Self::PinMajor),
+            "pin-minor" => return Ok(Self::PinMinor),
+            _ => {}
+        }
+        let req = if let Some(version) = s.strip_prefix("~=") {
+            Self::parse_compatible_release(version.trim())
+        } else {
+            semver::VersionReq::parse(s).ok()
+        };
+        req.map(Self::Range)
+            .ok_or_else(|| UpdatePolicyError::Invalid(s.to_string()))
+    }
+}
+
+impl TryFrom<String> for UpdatePolicy {
+    type Error = UpdatePolicyError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl Display for UpdatePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PinMajor => f.write_str("pin-major"),
+            Self::PinMinor => f.write_str("pin-minor"),
+            Self::Range(req) => write!(f, "{req}"),
+        }
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for UpdatePolicy {
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Borrowed("UpdatePolicy")
+    }
+
+    fn json_schema(_gen: &mut schemars::generate::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+            "description": "Constraint on the revisions `prek auto-update` may update the repo to: `pin-major`, `pin-minor`, or a version range like `<2.0` or `~=1.4`.",
+        })
+    }
+}
+
+/// How much of the run a failing hook stops, from the narrowest to the widest scope.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize, clap::ValueEnum,
+)]
+#[serde(try_from = "FailFastWire")]
+pub(crate) enum FailFast {
+    /// A failure doesn't stop other hooks.
+    #[default]
+    #[value(skip)]
+    Off,
+    /// Cancel the hooks of the same priority group that are still running.
+    Group,
+    /// Skip the remaining hooks of the same project.
+    Project,
+    /// Stop the whole run.
+    Workspace,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum FailFastError {
+    #[error("invalid fail_fast `{0}`, expected a boolean, `group`, `project` or `workspace`")]
+    Invalid(String),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FailFastWire {
+    Enabled(bool),
+    Scope(String),
+}
+
+impl TryFrom<FailFastWire> for FailFast {
+    type Error = FailFastError;
+
+    fn try_from(value: FailFastWire) -> Result<Self, Self::Error> {
+        match value {
+            FailFastWire::Enabled(true) => Ok(Self::Workspace),
+            FailFastWire::Enabled(false) => Ok(Self::Off),
+            FailFastWire::Scope(scope) => match scope.as_str() {
+                "group" => Ok(Self::Group),
+                "project" => Ok(Self::Project),
+                "workspace" => Ok(Self::Workspace),
+                _ => Err(FailFastError::Invalid(scope)),
+            },
+        }
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for FailFast {
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Borrowed("FailFast")
+    }
+
+    fn json_schema(_gen: &mut schemars::generate::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "Stop running hooks after a failure: `true` (same as `workspace`) stops the whole run, `project` skips the remaining hooks of the project, `group` cancels the running hooks of the same priority group.",
+            "oneOf": [
+                { "type": "boolean" },
+                { "type": "string", "enum": ["group", "project", "workspace"] },
+            ],
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub(crate) struct RemoteRepo {
     pub repo: String,
     pub rev: String,
+    /// Constraint on the revisions `prek auto-update` may update the repo to.
+    #[serde(skip_serializing)]
+    pub update_policy: Option<UpdatePolicy>,
+    /// File include pattern for all hooks of the repo, in addition to their own `files`.
+    #[serde(skip_serializing)]
+    pub files: Option<FilePattern>,
+    /// File exclude pattern for all hooks of the repo, in addition to their own `exclude`.
+    #[serde(skip_serializing)]
+    pub exclude: Option<FilePattern>,
     #[serde(skip_serializing)]
     pub hooks: Vec<RemoteHook>,
     #[serde(skip_serializing)]
@@ -628,6 +972,9 @@ impl RemoteRepo {
         Self {
             repo,
             rev,
+            update_policy: None,
+            files: None,
+            exclude: None,
             hooks,
             _unused_keys: BTreeMap::new(),
         }
@@ -660,6 +1007,10 @@ impl Display for RemoteRepo {
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub(crate) struct LocalRepo {
     pub repo: String,
+    /// File include pattern for all hooks of the repo, in addition to their own `files`.
+    pub files: Option<FilePattern>,
+    /// File exclude pattern for all hooks of the repo, in addition to their own `exclude`.
+    pub exclude: Option<FilePattern>,
     pub hooks: Vec<LocalHook>,
     #[serde(skip_serializing)]
     #[serde(flatten)]
@@ -781,6 +1132,24 @@ impl TryFrom<serde_json::Value> for Repo {
     }
 }
 
+/// The config files a config extends.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub(crate) enum Extends {
+    Path(String),
+    Paths(Vec<String>),
+}
+
+impl Extends {
+    pub(crate) fn paths(&self) -> &[String] {
+        match self {
+            Self::Path(path) => std::slice::from_ref(path),
+            Self::Paths(paths) => paths,
+        }
+    }
+}
+
 // TODO: warn sensible regex
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -795,13 +1164,32 @@ pub(crate) struct Config {
     /// A configuration-wide default for the stages property of hooks.
     /// Default to all stages.
     pub default_stages: Option<Vec<Stage>>,
+    /// Hooks skipped by default, like with `SKIP`, e.g. hooks meant for CI or manual runs.
+    /// Accepts hook ids, glob patterns like `lint-*` and `language:<name>` selectors of the hooks
+    /// of this config. Hooks selected by id on the command line still run, and
+    /// `prek run --no-default-skips` runs all of them.
+    pub default_skips: Option<Vec<String>>,
     /// Global file include pattern.
     pub files: Option<FilePattern>,
     /// Global file exclude pattern.
     pub exclude: Option<FilePattern>,
-    /// Set to true to have prek stop running hooks after the first failure.
+    /// Set to true to skip files marked `skip-worktree` or `assume-unchanged` in the git index,
+    /// such as files outside a sparse checkout. Default is false.
+    pub exclude_skip_worktree: Option<bool>,
+    /// Set to true to discover the configs of initialized git submodules as projects of the
+    /// workspace, and to run hooks on the files of the submodules.
+    /// Only read from the config at the workspace root. Default is false.
+    pub recurse_submodules: Option<bool>,
+    /// Skip files larger than this many bytes instead of passing them to hooks.
+    /// The `--max-file-size` command line option takes precedence. Default is no limit.
+    pub max_file_size: Option<u64>,
+    /// The number of bytes read from the start of a file to classify it as `text` or `binary`.
+    /// Default is 1024.
+    pub binary_detection_bytes: Option<usize>,
+    /// Set to true to have prek stop running hooks after the first failure, or to `group` or
+    /// `project` to only stop the hooks of the same priority group or project.
     /// Default is false.
-    pub fail_fast: Option<bool>,
+    pub fail_fast: Option<FailFast>,
     /// The minimum version of prek required to run this configuration.
     #[serde(deserialize_with = "deserialize_and_validate_minimum_version", default)]
     pub minimum_prek_version: Option<String>,
@@ -811,50 +1199,372 @@ pub(crate) struct Config {
     /// When false (default), files in subprojects are processed by both the subproject and
     /// any parent projects that contain them.
     pub orphan: Option<bool>,
+    /// How to treat prek's own warnings: `ignore`, `warn` (default) or `error`.
+    /// The `--warnings` command line option takes precedence.
+    pub warnings: Option<WarningLevel>,
+    /// Other config files this config extends, relative to this file. Their repos, hooks and
+    /// top-level keys are merged under the ones of this config.
+    pub extends: Option<Extends>,
 
     #[serde(skip_serializing)]
     #[serde(flatten)]
     _unused_keys: BTreeMap<String, serde_json::Value>,
 }
 
+impl Config {
+    /// Iterate over the ids and options of all hooks in the configuration.
+    pub(crate) fn hook_options(&self) -> impl Iterator<Item = (&str, &HookOptions)> {
+        self.repos.iter().flat_map(|repo| {
+            let hooks: Box<dyn Iterator<Item = (&str, &HookOptions)>> = match repo {
+                Repo::Remote(remote) => {
+                    Box::new(remote.hooks.iter().map(|h| (h.id.as_str(), &h.options)))
+                }
+                Repo::Local(local) => {
+                    Box::new(local.hooks.iter().map(|h| (h.id.as_str(), &h.options)))
+                }
+                Repo::Meta(meta) => {
+                    Box::new(meta.hooks.iter().map(|h| (h.0.id.as_str(), &h.0.options)))
+                }
+                Repo::Builtin(builtin) => Box::new(
+                    builtin
+                        .hooks
+                        .iter()
+                        .map(|h| (h.0.id.as_str(), &h.0.options)),
+                ),
+            };
+            hooks
+        })
+    }
+
+    /// Whether any hook declares `after` or `before` ordering constraints.
+    pub(crate) fn uses_hook_ordering(&self) -> bool {
+        self.hook_options()
+            .any(|(_, options)| options.after.is_some() || options.before.is_some())
+    }
+}
+
+/// The scheduling inputs of a hook, see [`resolve_hook_order`].
+pub(crate) struct HookOrdering<'a> {
+    pub(crate) id: &'a str,
+    /// The priority of the hook, its position in the config if not set.
+    pub(crate) priority: u32,
+    pub(crate) after: &'a [String],
+    pub(crate) before: &'a [String],
+}
+
+/// Resolve `after` / `before` constraints into the effective priority of each hook.
+///
+/// Hooks run in ascending priority order, and hooks sharing a priority run concurrently.
+/// Hooks keep the order of their priority, except that a hook is moved after the hooks it must
+/// run after. Hooks only run concurrently if they share a priority, constraints never put
+/// hooks into the same group.
+///
+/// Returns the ids of the hooks forming a cycle if the constraints can't be satisfied.
+pub(crate) fn resolve_hook_order(hooks: &[HookOrdering]) -> Result<Vec<u32>, Vec<String>> {
+    let n = hooks.len();
+    let mut successors = vec![Vec::new(); n];
+    let mut in_degree = vec![0usize; n];
+    let mut add_edge = |from: usize, to: usize| {
+        successors[from].push(to);
+        in_degree[to] += 1;
+    };
+
+    for (i, hook) in hooks.iter().enumerate() {
+        for (j, other) in hooks.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+            if hook.after.iter().any(|id| id == other.id) {
+                add_edge(j, i);
+            }
+            if hook.before.iter().any(|id| id == other.id) {
+                add_edge(i, j);
+            }
+        }
+    }
+
+    // Kahn's algorithm, taking the ready hook with the lowest priority first, so hooks keep
+    // their order unless a constraint moves them.
+    let mut priorities = vec![0u32; n];
+    let mut earliest = vec![0u32; n];
+    let mut ready = (0..n)
+        .filter(|&i| in_degree[i] == 0)
+        .map(|i| Reverse((hooks[i].priority, i)))
+        .collect::<BinaryHeap<_>>();
+    let mut last: Option<(u32, u32)> = None;
+    let mut resolved = 0;
+    while let Some(Reverse((priority, i))) = ready.pop() {
+        resolved += 1;
+        let wanted = priority.max(earliest[i]);
+        priorities[i] = match last {
+            None => wanted,
+            Some((last, _)) if wanted > last => wanted,
+            // Hooks sharing a priority still run together.
+            Some((last, last_priority)) if last_priority == priority => last,
+            Some((last, _)) => last.saturating_add(1),
+        };
+        last = Some((priorities[i], priority));
+
+        for &j in &successors[i] {
+            earliest[j] = earliest[j].max(priorities[i].saturating_add(1));
+            in_degree[j] -= 1;
+            if in_degree[j] == 0 {
+                ready.push(Reverse((hooks[j].priority, j)));
+            }
+        }
+    }
+
+    if resolved == n {
+        return Ok(priorities);
+    }
+
+    // Walk the unresolved hooks until one repeats, which yields a cycle.
+    let mut path: Vec<usize> = Vec::new();
+    let mut current = (0..n)
+        .find(|&i| in_degree[i] > 0)
+        .expect("a hook is in a cycle");
+    loop {
+        if let Some(start) = path.iter().position(|&i| i == current) {
+            let mut cycle = path[start..]
+                .iter()
+                .map(|&i| hooks[i].id.to_string())
+                .collect::<Vec<_>>();
+            cycle.push(hooks[current].id.to_string());
+            return Err(cycle);
+        }
+        path.push(current);
+        current = successors[current]
+            .iter()
+            .copied()
+            .find(|&j| in_degree[j] > 0)
+            .expect("an unresolved hook has an unresolved successor");
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub(crate) enum Error {
     #[error("Config file not found: {0}")]
     NotFound(String),
 
+    #[error("Hook ordering constraints in `{0}` form a cycle: {1}")]
+    OrderingCycle(String, String),
+
     #[error(transparent)]
     Io(#[from] std::io::Error),
 
     #[error("Failed to parse `{0}`")]
-    Yaml(String, #[source] serde_yaml::Error),
+    Yaml(String, #[source] YamlError),
+
+    #[error("Failed to parse `{0}`")]
+    Toml(String, #[source] Box<toml::de::Error>),
 
     #[error("Failed to merge keys in `{0}`")]
     YamlMerge(String, #[source] yaml::MergeKeyError),
+
+    #[error("Invalid `extends` in `{0}`, expected a path or a list of paths")]
+    InvalidExtends(String),
+
+    #[error("Cyclic `extends`: `{0}` extends `{1}`, which already extends it")]
+    ExtendsCycle(String, String),
+
+    #[error("Failed to load `{1}`, extended by `{0}`")]
+    Extends(String, String, #[source] Box<Error>),
+}
+
+/// A YAML error, with a code frame pointing at its location in the source when it's known.
+#[derive(Debug)]
+pub(crate) struct YamlError {
+    error: serde_yaml::Error,
+    frame: Option<String>,
+}
+
+impl YamlError {
+    /// Wrap an error from deserializing `content`.
+    fn new(error: serde_yaml::Error, path: &Path, content: &str) -> Self {
+        let frame = error
+            .location()
+            .map(|location| code_frame(path, content, &location));
+        Self { error, frame }
+    }
+
+    /// Wrap an error from deserializing the document after resolving merge keys.
+    ///
+    /// Values don't carry their location, so the source is deserialized again to find where the
+    /// same error occurs. Errors only caused by merged keys are left without location.
+    fn from_value<T: serde::de::DeserializeOwned>(
+        error: serde_yaml::Error,
+        path: &Path,
+        content: &str,
+    ) -> Self {
+        let message = error.to_string();
+        let frame = serde_yaml::from_str::<T>(content)
+            .err()
+            .filter(|located| located.to_string().contains(&message))
+            .and_then(|located| located.location())
+            .map(|location| code_frame(path, content, &location));
+        Self { error, frame }
+    }
+}
+
+impl Display for YamlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.error)?;
+        if let Some(frame) = &self.frame {
+            write!(f, "\n{frame}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for YamlError {}
+
+/// Render the source line of `location` with a caret under its column, like:
+///
+/// ```text
+///     --> .pre-commit-config.yaml:2:5
+///      |
+///    2 |   - repo: https://github.com/pre-commit/pre-commit-hooks
+///      |     ^
+/// ```
+fn code_frame(path: &Path, content: &str, location: &serde_yaml::Location) -> String {
+    let lines = content.lines().collect::<Vec<_>>();
+    let (line_number, column) = match lines.get(location.line().saturating_sub(1)) {
+        Some(_) => (location.line(), location.column()),
+        // Errors at the end of the input point past the last line, show the end of it instead.
+        None => (
+            lines.len().max(1),
+            lines.last().map_or(0, |line| line.chars().count()) + 1,
+        ),
+    };
+    let line = lines.get(line_number - 1).copied().unwrap_or_default();
+    let gutter = " ".repeat(line_number.to_string().len());
+    // The column counts characters, pad with the same whitespace to keep tabs aligned.
+    let padding = line
+        .chars()
+        .take(column.saturating_sub(1))
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect::<String>();
+
+    let source = format!("    {line_number} | {line}");
+    format!(
+        "    {gutter}--> {}:{line_number}:{column}\n    {gutter} |\n{}\n    {gutter} | {padding}^",
+        path.user_display(),
+        source.trim_end(),
+    )
 }
 
 /// Keys that prek does not use.
 const EXPECTED_UNUSED: &[&str] = &["minimum_pre_commit_version", "ci"];
 
-fn push_unused_paths<'a, I>(acc: &mut Vec<String>, prefix: &str, keys: I)
+/// Keys of the top-level config, of repos and of hooks, to suggest for misspelled keys.
+const CONFIG_KEYS: &[&str] = &[
+    "binary_detection_bytes",
+    "default_install_hook_types",
+    "default_language_version",
+    "default_skips",
+    "default_stages",
+    "exclude",
+    "exclude_skip_worktree",
+    "extends",
+    "fail_fast",
+    "files",
+    "max_file_size",
+    "minimum_prek_version",
+    "orphan",
+    "recurse_submodules",
+    "repos",
+    "warnings",
+];
+const REPO_KEYS: &[&str] = &["exclude", "files", "hooks", "repo", "rev", "update_policy"];
+const HOOK_KEYS: &[&str] = &[
+    "additional_dependencies",
+    "after",
+    "alias",
+    "always_run",
+    "arch",
+    "args",
+    "before",
+    "description",
+    "entry",
+    "env",
+    "exclude",
+    "exclude_types",
+    "fail_fast",
+    "fail_on_output",
+    "files",
+    "goflags",
+    "id",
+    "image_digest",
+    "kind",
+    "language",
+    "language_version",
+    "log_file",
+    "max_file_size",
+    "max_files_per_invocation",
+    "minimum_prek_version",
+    "name",
+    "os",
+    "pass_filenames",
+    "pass_push_refs",
+    "priority",
+    "require_serial",
+    "retries",
+    "retry_delay",
+    "stages",
+    "success_exit_codes",
+    "types",
+    "types_or",
+    "verbose",
+];
+
+/// Suggest the known key closest to the unexpected key at `path`, if it looks like a typo of it.
+fn suggest_key(path: &[PathSegment]) -> Option<&'static str> {
+    let known = match path.len() {
+        1 => CONFIG_KEYS,
+        3 => REPO_KEYS,
+        5 => HOOK_KEYS,
+        _ => return None,
+    };
+    let Some(PathSegment::Key(key)) = path.last() else {
+        return None;
+    };
+    let key = key.to_ascii_lowercase().replace('-', "_");
+    // Allow about one edit for every three characters, so short keys don't match anything.
+    let max_distance = (key.chars().count() / 3).max(1);
+    known
+        .iter()
+        .map(|candidate| (levenshtein::levenshtein(&key, candidate), *candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Describe the unexpected key at `path`, with the key it's likely a typo of.
+fn display_unused_path(path: &[PathSegment]) -> String {
+    let key = format!("`{}`", yaml::display_path(path).yellow());
+    match suggest_key(path) {
+        Some(suggestion) => format!("{key} (did you mean `{}`?)", suggestion.green()),
+        None => key,
+    }
+}
+
+fn push_unused_paths<'a, I>(acc: &mut Vec<Vec<PathSegment>>, prefix: &[PathSegment], keys: I)
 where
     I: Iterator<Item = &'a str>,
 {
     for key in keys {
-        let path = if prefix.is_empty() {
-            key.to_string()
-        } else {
-            format!("{prefix}.{key}")
-        };
+        let mut path = prefix.to_vec();
+        path.push(PathSegment::Key(key.to_string()));
         acc.push(path);
     }
 }
 
-fn collect_unused_paths(config: &Config) -> Vec<String> {
+fn collect_unused_paths(config: &Config) -> Vec<Vec<PathSegment>> {
     let mut paths = Vec::new();
 
     push_unused_paths(
         &mut paths,
-        "",
+        &[],
         config._unused_keys.keys().filter_map(|key| {
             let key = key.as_str();
             (!EXPECTED_UNUSED.contains(&key)).then_some(key)
@@ -862,7 +1572,10 @@ fn collect_unused_paths(config: &Config) -> Vec<String> {
     );
 
     for (repo_idx, repo) in config.repos.iter().enumerate() {
-        let repo_prefix = format!("repos[{repo_idx}]");
+        let repo_prefix = [
+            PathSegment::Key("repos".to_string()),
+            PathSegment::Index(repo_idx),
+        ];
         let (repo_unused_keys, hooks_options): (_, Box<dyn Iterator<Item = &HookOptions>>) =
             match repo {
                 Repo::Remote(remote) => (
@@ -889,7 +1602,14 @@ fn collect_unused_paths(config: &Config) -> Vec<String> {
             repo_unused_keys.keys().map(String::as_str),
         );
         for (hook_idx, options) in hooks_options.enumerate() {
-            let hook_prefix = format!("{repo_prefix}.hooks[{hook_idx}]");
+            let hook_prefix = [
+                &repo_prefix[..],
+                &[
+                    PathSegment::Key("hooks".to_string()),
+                    PathSegment::Index(hook_idx),
+                ],
+            ]
+            .concat();
             push_unused_paths(
                 &mut paths,
                 &hook_prefix,
@@ -901,7 +1621,7 @@ fn collect_unused_paths(config: &Config) -> Vec<String> {
     paths
 }
 
-fn warn_unused_paths(path: &Path, entries: &[String]) {
+fn warn_unused_paths(path: &Path, entries: &[Vec<PathSegment>]) {
     if entries.is_empty() {
         return;
     }
@@ -909,7 +1629,7 @@ fn warn_unused_paths(path: &Path, entries: &[String]) {
     if entries.len() < 4 {
         let inline = entries
             .iter()
-            .map(|entry| format!("`{}`", entry.yellow()))
+            .map(|entry| display_unused_path(entry))
             .join(", ");
         warn_user!(
             "Ignored unexpected keys in `{}`: {inline}",
@@ -918,7 +1638,7 @@ fn warn_unused_paths(path: &Path, entries: &[String]) {
     } else {
         let list = entries
             .iter()
-            .map(|entry| format!("  - `{}`", entry.yellow()))
+            .map(|entry| format!("  - {}", display_unused_path(entry)))
             .join("\n");
         warn_user!(
             "Ignored unexpected keys in `{}`:\n{list}",
@@ -929,6 +1649,26 @@ fn warn_unused_paths(path: &Path, entries: &[String]) {
 
 /// Read the configuration file from the given path.
 pub(crate) fn load_config(path: &Path) -> Result<Config, Error> {
+    let (content, config) = load_config_value(path, &mut Vec::new())?;
+
+    let config: Config = serde_yaml::from_value(config).map_err(|e| {
+        Error::Yaml(
+            path.user_display().to_string(),
+            YamlError::from_value::<Config>(e, path, &content),
+        )
+    })?;
+
+    Ok(config)
+}
+
+/// Read the configuration document from the given path, with merge keys and `extends` resolved.
+///
+/// Returns the source of the file along with the document. `stack` holds the configs currently
+/// being extended, to detect cycles.
+pub(crate) fn load_config_value(
+    path: &Path,
+    stack: &mut Vec<PathBuf>,
+) -> Result<(String, serde_yaml::Value), Error> {
     let content = match fs_err::read_to_string(path) {
         Ok(content) => content,
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
@@ -937,16 +1677,200 @@ pub(crate) fn load_config(path: &Path) -> Result<Config, Error> {
         Err(e) => return Err(e.into()),
     };
 
-    let config: serde_yaml::Value = serde_yaml::from_str(&content)
-        .map_err(|e| Error::Yaml(path.user_display().to_string(), e))?;
+    let config: serde_yaml::Value = serde_yaml::from_str(&content).map_err(|e| {
+        Error::Yaml(
+            path.user_display().to_string(),
+            YamlError::new(e, path, &content),
+        )
+    })?;
 
     let config = yaml::merge_keys(config)
         .map_err(|e| Error::YamlMerge(path.user_display().to_string(), e))?;
 
-    let config: Config = serde_yaml::from_value(config)
-        .map_err(|e| Error::Yaml(path.user_display().to_string(), e))?;
+    let serde_yaml::Value::Mapping(config) = config else {
+        return Ok((content, config));
+    };
+    let Some(extends) = config.get("extends") else {
+        return Ok((content, serde_yaml::Value::Mapping(config)));
+    };
+    let extends = serde_yaml::from_value::<Extends>(extends.clone())
+        .map_err(|_| Error::InvalidExtends(path.user_display().to_string()))?;
+
+    let canonical = fs_err::canonicalize(path)?;
+    stack.push(canonical);
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let mut base = serde_yaml::Mapping::new();
+    for extended in extends.paths() {
+        let extended = dir.join(extended);
+        if fs_err::canonicalize(&extended).is_ok_and(|extended| stack.contains(&extended)) {
+            return Err(Error::ExtendsCycle(
+                path.user_display().to_string(),
+                extended.user_display().to_string(),
+            ));
+        }
+        let (_, extended_config) = load_config_value(&extended, stack).map_err(|e| {
+            Error::Extends(
+                path.user_display().to_string(),
+                extended.user_display().to_string(),
+                Box::new(e),
+            )
+        })?;
+        let serde_yaml::Value::Mapping(mut extended_config) = extended_config else {
+            continue;
+        };
+        // Only the `extends` of the config being loaded is kept.
+        extended_config.remove("extends");
+        base = merge_extended_config(base, extended_config);
+    }
+    stack.pop();
 
-    Ok(config)
+    Ok((
+        content,
+        serde_yaml::Value::Mapping(merge_extended_config(base, config)),
+    ))
+}
+
+/// Read the `repo` and `rev` of the remote repos declared in the config file itself, in order,
+/// without the repos of the configs it extends.
+pub(crate) fn declared_remote_repos(path: &Path) -> Result<Vec<(String, String)>, Error> {
+    let content = fs_err::read_to_string(path)?;
+    let config: serde_yaml::Value = serde_yaml::from_str(&content).map_err(|e| {
+        Error::Yaml(
+            path.user_display().to_string(),
+            YamlError::new(e, path, &content),
+        )
+    })?;
+    let config = yaml::merge_keys(config)
+        .map_err(|e| Error::YamlMerge(path.user_display().to_string(), e))?;
+
+    Ok(config
+        .get("repos")
+        .and_then(serde_yaml::Value::as_sequence)
+        .into_iter()
+        .flatten()
+        .filter_map(repo_key)
+        .filter_map(|(repo, rev)| Some((repo, rev?)))
+        .collect())
+}
+
+/// Merge a config document into the one it extends.
+///
+/// Repos with the same `repo` and `rev` are merged, with their hooks merged by `id`: the keys of
+/// a hook or repo override the ones of the extended config. `default_language_version` is merged
+/// by language, and other top-level keys override the ones of the extended config.
+fn merge_extended_config(
+    mut base: serde_yaml::Mapping,
+    config: serde_yaml::Mapping,
+) -> serde_yaml::Mapping {
+    for (key, value) in config {
+        let Some(slot) = base.get_mut(&key) else {
+            base.insert(key, value);
+            continue;
+        };
+        *slot = match (key.as_str(), std::mem::take(slot), value) {
+            (
+                Some("repos"),
+                serde_yaml::Value::Sequence(base_repos),
+                serde_yaml::Value::Sequence(repos),
+            ) => serde_yaml::Value::Sequence(merge_by_key(base_repos, repos, repo_key, merge_repo)),
+            (
+                Some("default_language_version"),
+                serde_yaml::Value::Mapping(mut base_versions),
+                serde_yaml::Value::Mapping(versions),
+            ) => {
+                base_versions.extend(versions);
+                serde_yaml::Value::Mapping(base_versions)
+            }
+            (_, _, value) => value,
+        };
+    }
+    base
+}
+
+/// Merge a repo into the repo of the extended config with the same `repo` and `rev`.
+fn merge_repo(base: serde_yaml::Value, repo: serde_yaml::Value) -> serde_yaml::Value {
+    merge_mapping(base, repo, |key, base_hooks, hooks| {
+        match (base_hooks, hooks) {
+            (serde_yaml::Value::Sequence(base_hooks), serde_yaml::Value::Sequence(hooks))
+                if key == "hooks" =>
+            {
+                serde_yaml::Value::Sequence(merge_by_key(
+                    base_hooks,
+                    hooks,
+                    hook_key,
+                    |base, hook| merge_mapping(base, hook, |_, _, hook| hook),
+                ))
+            }
+            (_, value) => value,
+        }
+    })
+}
+
+/// The key repos are merged by, their `repo` and `rev`.
+fn repo_key(repo: &serde_yaml::Value) -> Option<(String, Option<String>)> {
+    let repo = repo.as_mapping()?;
+    Some((
+        repo.get("repo")?.as_str()?.to_string(),
+        repo.get("rev")
+            .and_then(serde_yaml::Value::as_str)
+            .map(ToString::to_string),
+    ))
+}
+
+/// The key hooks are merged by, their `id`.
+fn hook_key(hook: &serde_yaml::Value) -> Option<String> {
+    Some(hook.as_mapping()?.get("id")?.as_str()?.to_string())
+}
+
+/// Merge the items of `items` into the items of `base` with the same key, and append the others.
+fn merge_by_key<K: PartialEq>(
+    mut base: serde_yaml::Sequence,
+    items: serde_yaml::Sequence,
+    key: impl Fn(&serde_yaml::Value) -> Option<K>,
+    merge: impl Fn(serde_yaml::Value, serde_yaml::Value) -> serde_yaml::Value,
+) -> serde_yaml::Sequence {
+    for item in items {
+        let existing = key(&item).and_then(|item_key| {
+            base.iter()
+                .position(|base| key(base).is_some_and(|base_key| base_key == item_key))
+        });
+        match existing {
+            Some(idx) => {
+                let merged = merge(std::mem::take(&mut base[idx]), item);
+                base[idx] = merged;
+            }
+            None => base.push(item),
+        }
+    }
+    base
+}
+
+/// Merge the keys of `value` into the mapping `base`, with `merge` combining the values of keys
+/// present in both.
+fn merge_mapping(
+    base: serde_yaml::Value,
+    value: serde_yaml::Value,
+    merge: impl Fn(&str, serde_yaml::Value, serde_yaml::Value) -> serde_yaml::Value,
+) -> serde_yaml::Value {
+    let (serde_yaml::Value::Mapping(mut base), serde_yaml::Value::Mapping(value)) = (base, value)
+    else {
+        unreachable!("repos and hooks are only merged by key when they are mappings");
+    };
+    for (key, value) in value {
+        match base.get_mut(&key) {
+            Some(slot) => {
+                *slot = merge(
+                    key.as_str().unwrap_or_default(),
+                    std::mem::take(slot),
+                    value,
+                );
+            }
+            None => {
+                base.insert(key, value);
+            }
+        }
+    }
+    serde_yaml::Value::Mapping(base)
 }
 
 /// Read the configuration file from the given path, and warn about certain issues.
@@ -954,19 +1878,24 @@ pub(crate) fn load_config(path: &Path) -> Result<Config, Error> {
 pub(crate) fn read_config(path: &Path) -> Result<Config, Error> {
     let config = load_config(path)?;
 
+    if let Some(level) = config.warnings {
+        warnings::set_config_level(level);
+    }
+
     let unused_paths = collect_unused_paths(&config);
     warn_unused_paths(path, &unused_paths);
 
+    if config.uses_hook_ordering() {
+        check_hook_ordering(path, &config)?;
+    }
+
     // Check for mutable revs and warn the user.
     let repos_has_mutable_rev = config
         .repos
         .iter()
         .filter_map(|repo| {
             if let Repo::Remote(repo) = repo {
-                let rev = &repo.rev;
-                // A rev is considered mutable if it doesn't contain a '.' (like a version)
-                // and is not a hexadecimal string (like a commit SHA).
-                if !rev.contains('.') && !looks_like_sha(rev) {
+                if is_mutable_rev(&repo.rev) {
                     return Some(repo);
                 }
             }
@@ -996,16 +1925,251 @@ pub(crate) fn read_config(path: &Path) -> Result<Config, Error> {
     Ok(config)
 }
 
+/// A problem `prek validate-config --strict` reports, which otherwise is only a warning.
+#[derive(Debug)]
+pub(crate) struct Lint {
+    /// The line and column of the offending node, if it can be found in the source.
+    pub(crate) location: Option<serde_yaml::Location>,
+    pub(crate) message: String,
+}
+
+/// Stage names accepted for compatibility with old `pre-commit` versions.
+const DEPRECATED_STAGES: &[(&str, &str)] = &[
+    ("commit", "pre-commit"),
+    ("merge-commit", "pre-merge-commit"),
+    ("push", "pre-push"),
+];
+
+/// Check the configuration file strictly: unknown keys, mutable revs, deprecated stage names
+/// and invalid file patterns are reported instead of warned about.
+pub(crate) fn lint_config(path: &Path) -> Result<Vec<Lint>, Error> {
+    let content = match fs_err::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(Error::NotFound(path.user_display().to_string()));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let value: serde_yaml::Value = serde_yaml::from_str(&content).map_err(|e| {
+        Error::Yaml(
+            path.user_display().to_string(),
+            YamlError::new(e, path, &content),
+        )
+    })?;
+
+    let mut issues = Vec::new();
+
+    // File patterns are checked on the document, so they are reported with their location
+    // even though the config can't be loaded with them.
+    for (pattern_path, pattern) in file_patterns(&value) {
+        if let Err(err) = serde_yaml::from_value::<FilePattern>(pattern.clone()) {
+            issues.push((pattern_path, format!("Invalid file pattern: {err}")));
+        }
+    }
+    if !issues.is_empty() {
+        return Ok(locate_lints(&content, issues));
+    }
+
+    for (stage_path, stage) in stage_names(&value) {
+        if let Some((_, name)) = DEPRECATED_STAGES.iter().find(|(alias, _)| *alias == stage) {
+            issues.push((
+                stage_path,
+                format!("Stage `{stage}` is deprecated, use `{name}` instead"),
+            ));
+        }
+    }
+
+    let config = load_config(path)?;
+    if config.uses_hook_ordering() {
+        check_hook_ordering(path, &config)?;
+    }
+
+    for unused in collect_unused_paths(&config) {
+        let key = yaml::display_path(&unused);
+        let message = match suggest_key(&unused) {
+            Some(suggestion) => format!("Unexpected key `{key}`, did you mean `{suggestion}`?"),
+            None => format!("Unexpected key `{key}`"),
+        };
+        issues.push((unused, message));
+    }
+
+    for (idx, repo) in config.repos.iter().enumerate() {
+        if let Repo::Remote(repo) = repo
+            && is_mutable_rev(&repo.rev)
+        {
+            issues.push((
+                vec![
+                    PathSegment::Key("repos".to_string()),
+                    PathSegment::Index(idx),
+                    PathSegment::Key("rev".to_string()),
+                ],
+                format!(
+                    "Repo `{}` has a mutable `rev: {}` (moving tag / branch)",
+                    repo.repo, repo.rev
+                ),
+            ));
+        }
+    }
+
+    Ok(locate_lints(&content, issues))
+}
+
+fn locate_lints(content: &str, issues: Vec<(Vec<PathSegment>, String)>) -> Vec<Lint> {
+    issues
+        .into_iter()
+        .map(|(path, message)| Lint {
+            location: yaml::locate(content, &path),
+            message,
+        })
+        .sorted_by_key(|lint| lint.location.as_ref().map(|loc| (loc.line(), loc.column())))
+        .collect()
+}
+
+/// The hooks of all repos in a config document, with their paths.
+fn hook_nodes(
+    value: &serde_yaml::Value,
+) -> impl Iterator<Item = (Vec<PathSegment>, &serde_yaml::Value)> {
+    let repos = value.get("repos").and_then(serde_yaml::Value::as_sequence);
+    repos
+        .into_iter()
+        .flatten()
+        .enumerate()
+        .flat_map(|(repo_idx, repo)| {
+            let hooks = repo.get("hooks").and_then(serde_yaml::Value::as_sequence);
+            hooks
+                .into_iter()
+                .flatten()
+                .enumerate()
+                .map(move |(hook_idx, hook)| {
+                    let path = vec![
+                        PathSegment::Key("repos".to_string()),
+                        PathSegment::Index(repo_idx),
+                        PathSegment::Key("hooks".to_string()),
+                        PathSegment::Index(hook_idx),
+                    ];
+                    (path, hook)
+                })
+        })
+}
+
+/// The `files` and `exclude` patterns of a config document, with their paths.
+fn file_patterns(
+    value: &serde_yaml::Value,
+) -> impl Iterator<Item = (Vec<PathSegment>, &serde_yaml::Value)> {
+    iter::once((Vec::new(), value))
+        .chain(hook_nodes(value))
+        .flat_map(|(path, node)| {
+            ["files", "exclude"].into_iter().filter_map(move |key| {
+                let pattern = node.get(key)?;
+                let mut path = path.clone();
+                path.push(PathSegment::Key(key.to_string()));
+                Some((path, pattern))
+            })
+        })
+}
+
+/// The stage names in `default_stages` and the `stages` of hooks, with their paths.
+fn stage_names(value: &serde_yaml::Value) -> impl Iterator<Item = (Vec<PathSegment>, &str)> {
+    iter::once((Vec::new(), "default_stages", value))
+        .chain(hook_nodes(value).map(|(path, hook)| (path, "stages", hook)))
+        .flat_map(|(path, key, node)| {
+            let stages = node.get(key).and_then(serde_yaml::Value::as_sequence);
+            stages
+                .into_iter()
+                .flatten()
+                .enumerate()
+                .filter_map(move |(idx, stage)| {
+                    let mut path = path.clone();
+                    path.extend([PathSegment::Key(key.to_string()), PathSegment::Index(idx)]);
+                    Some((path, stage.as_str()?))
+                })
+        })
+}
+
+/// Check that `after` / `before` reference known hooks and don't form a cycle.
+fn check_hook_ordering(path: &Path, config: &Config) -> Result<(), Error> {
+    let ids = config
+        .hook_options()
+        .map(|(id, _)| id)
+        .collect::<FxHashSet<_>>();
+    for (id, options) in config.hook_options() {
+        for other in options.after.iter().chain(&options.before).flatten() {
+            if !ids.contains(other.as_str()) {
+                warn_user!(
+                    "Hook `{}` in `{}` is ordered relative to unknown hook `{}`",
+                    id.cyan(),
+                    path.user_display().cyan(),
+                    other.yellow(),
+                );
+            }
+        }
+    }
+
+    let ordering = config
+        .hook_options()
+        .enumerate()
+        .map(|(idx, (id, options))| HookOrdering {
+            id,
+            priority: options
+                .priority
+                .unwrap_or_else(|| u32::try_from(idx).expect("idx too large")),
+            after: options.after.as_deref().unwrap_or_default(),
+            before: options.before.as_deref().unwrap_or_default(),
+        })
+        .collect::<Vec<_>>();
+    resolve_hook_order(&ordering).map_err(|cycle| {
+        Error::OrderingCycle(path.user_display().to_string(), cycle.join(" -> "))
+    })?;
+
+    Ok(())
+}
+
 // TODO: disallow `priority` in manifest
 
-/// Read the manifest file from the given path.
+/// The hooks of a `prek-hooks.toml` manifest, which are listed under `[[hooks]]` tables.
+#[derive(Deserialize)]
+struct TomlManifest {
+    hooks: Vec<ManifestHook>,
+}
+
+/// Return the manifest of the hook repository at `repo_path`.
+///
+/// `prek-hooks.toml` is preferred over `.pre-commit-hooks.yaml` when the repository has both.
+pub(crate) fn manifest_path(repo_path: &Path) -> PathBuf {
+    let toml = repo_path.join(TOML_MANIFEST_FILE);
+    if toml.is_file() {
+        toml
+    } else {
+        repo_path.join(MANIFEST_FILE)
+    }
+}
+
+/// Read the manifest file from the given path, as TOML if it has a `.toml` extension.
 pub(crate) fn read_manifest(path: &Path) -> Result<Manifest, Error> {
     let content = fs_err::read_to_string(path)?;
-    let manifest = serde_yaml::from_str(&content)
-        .map_err(|e| Error::Yaml(path.user_display().to_string(), e))?;
+    if path.extension().is_some_and(|ext| ext == "toml") {
+        let manifest: TomlManifest = toml::from_str(&content)
+            .map_err(|e| Error::Toml(path.user_display().to_string(), Box::new(e)))?;
+        return Ok(Manifest {
+            hooks: manifest.hooks,
+        });
+    }
+
+    let manifest = serde_yaml::from_str(&content).map_err(|e| {
+        Error::Yaml(
+            path.user_display().to_string(),
+            YamlError::new(e, path, &content),
+        )
+    })?;
     Ok(manifest)
 }
 
+/// A rev is considered mutable if it doesn't contain a '.' (like a version)
+/// and is not a hexadecimal string (like a commit SHA).
+fn is_mutable_rev(rev: &str) -> bool {
+    !rev.contains('.') && !looks_like_sha(rev)
+}
+
 /// Check if a string looks like a git SHA
 fn looks_like_sha(s: &str) -> bool {
     !s.is_empty() && s.as_bytes().iter().all(u8::is_ascii_hexdigit)
@@ -1173,6 +2337,8 @@ mod tests {
                     Local(
                         LocalRepo {
                             repo: "local",
+                            files: None,
+                            exclude: None,
                             hooks: [
                                 ManifestHook {
                                     id: "cargo-fmt",
@@ -1186,20 +2352,34 @@ mod tests {
                                         types: None,
                                         types_or: None,
                                         exclude_types: None,
+                                        max_file_size: None,
                                         additional_dependencies: None,
                                         args: None,
                                         env: None,
                                         always_run: None,
                                         fail_fast: None,
                                         pass_filenames: None,
+                                        pass_push_refs: None,
+                                        success_exit_codes: None,
+                                        fail_on_output: None,
+                                        retries: None,
+                                        retry_delay: None,
+                                        os: None,
+                                        arch: None,
+                                        kind: None,
                                         description: None,
                                         language_version: None,
                                         log_file: None,
                                         require_serial: None,
+                                        max_files_per_invocation: None,
                                         priority: None,
+                                        after: None,
+                                        before: None,
                                         stages: None,
                                         verbose: None,
                                         minimum_prek_version: None,
+                                        image_digest: None,
+                                        goflags: None,
                                         _unused_keys: {},
                                     },
                                 },
@@ -1211,11 +2391,18 @@ mod tests {
                 default_install_hook_types: None,
                 default_language_version: None,
                 default_stages: None,
+                default_skips: None,
                 files: None,
                 exclude: None,
+                exclude_skip_worktree: None,
+                recurse_submodules: None,
+                max_file_size: None,
+                binary_detection_bytes: None,
                 fail_fast: None,
                 minimum_prek_version: None,
                 orphan: None,
+                warnings: None,
+                extends: None,
                 _unused_keys: {},
             },
         )
@@ -1251,6 +2438,9 @@ mod tests {
                         RemoteRepo {
                             repo: "https://github.com/crate-ci/typos",
                             rev: "v1.0.0",
+                            update_policy: None,
+                            files: None,
+                            exclude: None,
                             hooks: [
                                 RemoteHook {
                                     id: "typos",
@@ -1264,20 +2454,34 @@ mod tests {
                                         types: None,
                                         types_or: None,
                                         exclude_types: None,
+                                        max_file_size: None,
                                         additional_dependencies: None,
                                         args: None,
                                         env: None,
                                         always_run: None,
                                         fail_fast: None,
                                         pass_filenames: None,
+                                        pass_push_refs: None,
+                                        success_exit_codes: None,
+                                        fail_on_output: None,
+                                        retries: None,
+                                        retry_delay: None,
+                                        os: None,
+                                        arch: None,
+                                        kind: None,
                                         description: None,
                                         language_version: None,
                                         log_file: None,
                                         require_serial: None,
+                                        max_files_per_invocation: None,
                                         priority: None,
+                                        after: None,
+                                        before: None,
                                         stages: None,
                                         verbose: None,
                                         minimum_prek_version: None,
+                                        image_digest: None,
+                                        goflags: None,
                                         _unused_keys: {},
                                     },
                                 },
@@ -1289,11 +2493,18 @@ mod tests {
                 default_install_hook_types: None,
                 default_language_version: None,
                 default_stages: None,
+                default_skips: None,
                 files: None,
                 exclude: None,
+                exclude_skip_worktree: None,
+                recurse_submodules: None,
+                max_file_size: None,
+                binary_detection_bytes: None,
                 fail_fast: None,
                 minimum_prek_version: None,
                 orphan: None,
+                warnings: None,
+                extends: None,
                 _unused_keys: {},
             },
         )
@@ -1354,6 +2565,8 @@ mod tests {
                     Local(
                         LocalRepo {
                             repo: "local",
+                            files: None,
+                            exclude: None,
                             hooks: [
                                 ManifestHook {
                                     id: "cargo-fmt",
@@ -1367,20 +2580,34 @@ mod tests {
                                         types: None,
                                         types_or: None,
                                         exclude_types: None,
+                                        max_file_size: None,
                                         additional_dependencies: None,
                                         args: None,
                                         env: None,
                                         always_run: None,
                                         fail_fast: None,
                                         pass_filenames: None,
+                                        pass_push_refs: None,
+                                        success_exit_codes: None,
+                                        fail_on_output: None,
+                                        retries: None,
+                                        retry_delay: None,
+                                        os: None,
+                                        arch: None,
+                                        kind: None,
                                         description: None,
                                         language_version: None,
                                         log_file: None,
                                         require_serial: None,
+                                        max_files_per_invocation: None,
                                         priority: None,
+                                        after: None,
+                                        before: None,
                                         stages: None,
                                         verbose: None,
                                         minimum_prek_version: None,
+                                        image_digest: None,
+                                        goflags: None,
                                         _unused_keys: {},
                                     },
                                 },
@@ -1392,11 +2619,18 @@ mod tests {
                 default_install_hook_types: None,
                 default_language_version: None,
                 default_stages: None,
+                default_skips: None,
                 files: None,
                 exclude: None,
+                exclude_skip_worktree: None,
+                recurse_submodules: None,
+                max_file_size: None,
+                binary_detection_bytes: None,
                 fail_fast: None,
                 minimum_prek_version: None,
                 orphan: None,
+                warnings: None,
+                extends: None,
                 _unused_keys: {},
             },
         )
@@ -1484,20 +2718,36 @@ mod tests {
                                             types: None,
                                             types_or: None,
                                             exclude_types: None,
+                                            max_file_size: None,
                                             additional_dependencies: None,
                                             args: None,
                                             env: None,
                                             always_run: None,
                                             fail_fast: None,
                                             pass_filenames: None,
+                                            pass_push_refs: None,
+                                            success_exit_codes: None,
+                                            fail_on_output: None,
+                                            retries: None,
+                                            retry_delay: None,
+                                            os: None,
+                                            arch: None,
+                                            kind: Some(
+                                                Checker,
+                                            ),
                                             description: None,
                                             language_version: None,
                                             log_file: None,
                                             require_serial: None,
+                                            max_files_per_invocation: None,
                                             priority: None,
+                                            after: None,
+                                            before: None,
                                             stages: None,
                                             verbose: None,
                                             minimum_prek_version: None,
+                                            image_digest: None,
+                                            goflags: None,
                                             _unused_keys: {},
                                         },
                                     },
@@ -1519,20 +2769,36 @@ mod tests {
                                             types: None,
                                             types_or: None,
                                             exclude_types: None,
+                                            max_file_size: None,
                                             additional_dependencies: None,
                                             args: None,
                                             env: None,
                                             always_run: None,
                                             fail_fast: None,
                                             pass_filenames: None,
+                                            pass_push_refs: None,
+                                            success_exit_codes: None,
+                                            fail_on_output: None,
+                                            retries: None,
+                                            retry_delay: None,
+                                            os: None,
+                                            arch: None,
+                                            kind: Some(
+                                                Checker,
+                                            ),
                                             description: None,
                                             language_version: None,
                                             log_file: None,
                                             require_serial: None,
+                                            max_files_per_invocation: None,
                                             priority: None,
+                                            after: None,
+                                            before: None,
                                             stages: None,
                                             verbose: None,
                                             minimum_prek_version: None,
+                                            image_digest: None,
+                                            goflags: None,
                                             _unused_keys: {},
                                         },
                                     },
@@ -1550,22 +2816,38 @@ mod tests {
                                             types: None,
                                             types_or: None,
                                             exclude_types: None,
+                                            max_file_size: None,
                                             additional_dependencies: None,
                                             args: None,
                                             env: None,
                                             always_run: None,
                                             fail_fast: None,
                                             pass_filenames: None,
+                                            pass_push_refs: None,
+                                            success_exit_codes: None,
+                                            fail_on_output: None,
+                                            retries: None,
+                                            retry_delay: None,
+                                            os: None,
+                                            arch: None,
+                                            kind: Some(
+                                                Checker,
+                                            ),
                                             description: None,
                                             language_version: None,
                                             log_file: None,
                                             require_serial: None,
+                                            max_files_per_invocation: None,
                                             priority: None,
+                                            after: None,
+                                            before: None,
                                             stages: None,
                                             verbose: Some(
                                                 true,
                                             ),
                                             minimum_prek_version: None,
+                                            image_digest: None,
+                                            goflags: None,
                                             _unused_keys: {},
                                         },
                                     },
@@ -1578,11 +2860,18 @@ mod tests {
                 default_install_hook_types: None,
                 default_language_version: None,
                 default_stages: None,
+                default_skips: None,
                 files: None,
                 exclude: None,
+                exclude_skip_worktree: None,
+                recurse_submodules: None,
+                max_file_size: None,
+                binary_detection_bytes: None,
                 fail_fast: None,
                 minimum_prek_version: None,
                 orphan: None,
+                warnings: None,
+                extends: None,
                 _unused_keys: {},
             },
         )
@@ -1619,6 +2908,8 @@ mod tests {
                     Local(
                         LocalRepo {
                             repo: "local",
+                            files: None,
+                            exclude: None,
                             hooks: [
                                 ManifestHook {
                                     id: "hook-1",
@@ -1632,22 +2923,36 @@ mod tests {
                                         types: None,
                                         types_or: None,
                                         exclude_types: None,
+                                        max_file_size: None,
                                         additional_dependencies: None,
                                         args: None,
                                         env: None,
                                         always_run: None,
                                         fail_fast: None,
                                         pass_filenames: None,
+                                        pass_push_refs: None,
+                                        success_exit_codes: None,
+                                        fail_on_output: None,
+                                        retries: None,
+                                        retry_delay: None,
+                                        os: None,
+                                        arch: None,
+                                        kind: None,
                                         description: None,
                                         language_version: Some(
                                             "default",
                                         ),
                                         log_file: None,
                                         require_serial: None,
+                                        max_files_per_invocation: None,
                                         priority: None,
+                                        after: None,
+                                        before: None,
                                         stages: None,
                                         verbose: None,
                                         minimum_prek_version: None,
+                                        image_digest: None,
+                                        goflags: None,
                                         _unused_keys: {},
                                     },
                                 },
@@ -1663,22 +2968,36 @@ mod tests {
                                         types: None,
                                         types_or: None,
                                         exclude_types: None,
+                                        max_file_size: None,
                                         additional_dependencies: None,
                                         args: None,
                                         env: None,
                                         always_run: None,
                                         fail_fast: None,
                                         pass_filenames: None,
+                                        pass_push_refs: None,
+                                        success_exit_codes: None,
+                                        fail_on_output: None,
+                                        retries: None,
+                                        retry_delay: None,
+                                        os: None,
+                                        arch: None,
+                                        kind: None,
                                         description: None,
                                         language_version: Some(
                                             "system",
                                         ),
                                         log_file: None,
                                         require_serial: None,
+                                        max_files_per_invocation: None,
                                         priority: None,
+                                        after: None,
+                                        before: None,
                                         stages: None,
                                         verbose: None,
                                         minimum_prek_version: None,
+                                        image_digest: None,
+                                        goflags: None,
                                         _unused_keys: {},
                                     },
                                 },
@@ -1694,22 +3013,36 @@ mod tests {
                                         types: None,
                                         types_or: None,
                                         exclude_types: None,
+                                        max_file_size: None,
                                         additional_dependencies: None,
                                         args: None,
                                         env: None,
                                         always_run: None,
                                         fail_fast: None,
                                         pass_filenames: None,
+                                        pass_push_refs: None,
+                                        success_exit_codes: None,
+                                        fail_on_output: None,
+                                        retries: None,
+                                        retry_delay: None,
+                                        os: None,
+                                        arch: None,
+                                        kind: None,
                                         description: None,
                                         language_version: Some(
                                             "3.8",
                                         ),
                                         log_file: None,
                                         require_serial: None,
+                                        max_files_per_invocation: None,
                                         priority: None,
+                                        after: None,
+                                        before: None,
                                         stages: None,
                                         verbose: None,
                                         minimum_prek_version: None,
+                                        image_digest: None,
+                                        goflags: None,
                                         _unused_keys: {},
                                     },
                                 },
@@ -1721,11 +3054,18 @@ mod tests {
                 default_install_hook_types: None,
                 default_language_version: None,
                 default_stages: None,
+                default_skips: None,
                 files: None,
                 exclude: None,
+                exclude_skip_worktree: None,
+                recurse_submodules: None,
+                max_file_size: None,
+                binary_detection_bytes: None,
                 fail_fast: None,
                 minimum_prek_version: None,
                 orphan: None,
+                warnings: None,
+                extends: None,
                 _unused_keys: {},
             },
         )
@@ -1935,6 +3275,8 @@ mod tests {
                 Local(
                     LocalRepo {
                         repo: "local",
+                        files: None,
+                        exclude: None,
                         hooks: [
                             ManifestHook {
                                 id: "mypy-local",
@@ -1953,20 +3295,34 @@ mod tests {
                                         ],
                                     ),
                                     exclude_types: None,
+                                    max_file_size: None,
                                     additional_dependencies: None,
                                     args: None,
                                     env: None,
                                     always_run: None,
                                     fail_fast: None,
                                     pass_filenames: None,
+                                    pass_push_refs: None,
+                                    success_exit_codes: None,
+                                    fail_on_output: None,
+                                    retries: None,
+                                    retry_delay: None,
+                                    os: None,
+                                    arch: None,
+                                    kind: None,
                                     description: None,
                                     language_version: None,
                                     log_file: None,
                                     require_serial: None,
+                                    max_files_per_invocation: None,
                                     priority: None,
+                                    after: None,
+                                    before: None,
                                     stages: None,
                                     verbose: None,
                                     minimum_prek_version: None,
+                                    image_digest: None,
+                                    goflags: None,
                                     _unused_keys: {},
                                 },
                             },
@@ -1987,20 +3343,34 @@ mod tests {
                                         ],
                                     ),
                                     exclude_types: None,
+                                    max_file_size: None,
                                     additional_dependencies: None,
                                     args: None,
                                     env: None,
                                     always_run: None,
                                     fail_fast: None,
                                     pass_filenames: None,
+                                    pass_push_refs: None,
+                                    success_exit_codes: None,
+                                    fail_on_output: None,
+                                    retries: None,
+                                    retry_delay: None,
+                                    os: None,
+                                    arch: None,
+                                    kind: None,
                                     description: None,
                                     language_version: None,
                                     log_file: None,
                                     require_serial: None,
+                                    max_files_per_invocation: None,
                                     priority: None,
+                                    after: None,
+                                    before: None,
                                     stages: None,
                                     verbose: None,
                                     minimum_prek_version: None,
+                                    image_digest: None,
+                                    goflags: None,
                                     _unused_keys: {},
                                 },
                             },
@@ -2012,11 +3382,18 @@ mod tests {
             default_install_hook_types: None,
             default_language_version: None,
             default_stages: None,
+            default_skips: None,
             files: None,
             exclude: None,
+            exclude_skip_worktree: None,
+            recurse_submodules: None,
+            max_file_size: None,
+            binary_detection_bytes: None,
             fail_fast: None,
             minimum_prek_version: None,
             orphan: None,
+            warnings: None,
+            extends: None,
             _unused_keys: {},
         }
         "#);
@@ -2055,6 +3432,8 @@ mod tests {
                 Local(
                     LocalRepo {
                         repo: "local",
+                        files: None,
+                        exclude: None,
                         hooks: [
                             ManifestHook {
                                 id: "test-yaml",
@@ -2068,6 +3447,7 @@ mod tests {
                                     types: None,
                                     types_or: None,
                                     exclude_types: None,
+                                    max_file_size: None,
                                     additional_dependencies: None,
                                     args: None,
                                     env: None,
@@ -2076,13 +3456,24 @@ mod tests {
                                     pass_filenames: Some(
                                         false,
                                     ),
+                                    pass_push_refs: None,
+                                    success_exit_codes: None,
+                                    fail_on_output: None,
+                                    retries: None,
+                                    retry_delay: None,
+                                    os: None,
+                                    arch: None,
+                                    kind: None,
                                     description: None,
                                     language_version: None,
                                     log_file: None,
                                     require_serial: Some(
                                         true,
                                     ),
+                                    max_files_per_invocation: None,
                                     priority: None,
+                                    after: None,
+                                    before: None,
                                     stages: Some(
                                         [
                                             PreCommit,
@@ -2090,6 +3481,8 @@ mod tests {
                                     ),
                                     verbose: None,
                                     minimum_prek_version: None,
+                                    image_digest: None,
+                                    goflags: None,
                                     _unused_keys: {},
                                 },
                             },
@@ -2101,11 +3494,18 @@ mod tests {
             default_install_hook_types: None,
             default_language_version: None,
             default_stages: None,
+            default_skips: None,
             files: None,
             exclude: None,
+            exclude_skip_worktree: None,
+            recurse_submodules: None,
+            max_file_size: None,
+            binary_detection_bytes: None,
             fail_fast: None,
             minimum_prek_version: None,
             orphan: None,
+            warnings: None,
+            extends: None,
             _unused_keys: {
                 "local": Object {
                     "language": String("system"),
@@ -2151,6 +3551,99 @@ mod tests {
         let result = serde_yaml::from_str::<Config>(yaml);
         assert!(result.is_ok());
     }
+
+    fn ordering<'a>(
+        id: &'a str,
+        priority: u32,
+        after: &'a [String],
+        before: &'a [String],
+    ) -> HookOrdering<'a> {
+        HookOrdering {
+            id,
+            priority,
+            after,
+            before,
+        }
+    }
+
+    #[test]
+    fn resolve_hook_order_levels() {
+        let format = ["fmt".to_string()];
+        let lint = ["lint".to_string()];
+
+        // Constraints that are already met keep the hooks in order.
+        let hooks = [
+            ordering("fmt", 0, &[], &[]),
+            ordering("typos", 1, &[], &[]),
+            ordering("lint", 2, &format, &[]),
+        ];
+        assert_eq!(resolve_hook_order(&hooks).unwrap(), vec![0, 1, 2]);
+
+        // Hooks are moved after the hooks they run after, without running concurrently.
+        let hooks = [
+            ordering("lint", 0, &format, &[]),
+            ordering("fmt", 1, &[], &[]),
+            ordering("typos", 2, &[], &[]),
+            ordering("test", 3, &lint, &[]),
+            ordering("gen", 4, &[], &format),
+        ];
+        assert_eq!(resolve_hook_order(&hooks).unwrap(), vec![6, 5, 2, 7, 4]);
+
+        // Explicit priorities still apply, and hooks sharing one still run concurrently.
+        let hooks = [
+            ordering("fmt", 5, &[], &[]),
+            ordering("lint", 1, &format, &[]),
+            ordering("late", 10, &[], &[]),
+            ordering("also-late", 10, &[], &[]),
+        ];
+        assert_eq!(resolve_hook_order(&hooks).unwrap(), vec![5, 6, 10, 10]);
+
+        // Hooks can't be moved past the highest priority, which doesn't overflow.
+        let hooks = [
+            ordering("fmt", u32::MAX, &[], &[]),
+            ordering("lint", 1, &format, &[]),
+        ];
+        assert_eq!(
+            resolve_hook_order(&hooks).unwrap(),
+            vec![u32::MAX, u32::MAX]
+        );
+    }
+
+    #[test]
+    fn resolve_hook_order_cycle() {
+        let a = ["a".to_string()];
+        let b = ["b".to_string()];
+        let hooks = [
+            ordering("free", 0, &[], &[]),
+            ordering("a", 0, &b, &[]),
+            ordering("b", 0, &a, &[]),
+        ];
+        assert_eq!(
+            resolve_hook_order(&hooks).unwrap_err(),
+            vec!["a".to_string(), "b".to_string(), "a".to_string()]
+        );
+
+        let hooks = [ordering("a", 0, &[], &a)];
+        assert!(resolve_hook_order(&hooks).is_ok());
+    }
+
+    #[test]
+    fn parse_update_policy() {
+        assert_eq!(
+            "pin-major".parse::<UpdatePolicy>().unwrap(),
+            UpdatePolicy::PinMajor
+        );
+        assert_eq!(
+            "~=1.4".parse::<UpdatePolicy>().unwrap(),
+            UpdatePolicy::Range(semver::VersionReq::parse(">=1.4, <2.0").unwrap())
+        );
+        assert_eq!(
+            "~=1.4.2".parse::<UpdatePolicy>().unwrap(),
+            UpdatePolicy::Range(semver::VersionReq::parse(">=1.4.2, <1.5").unwrap())
+        );
+        assert!("~=1".parse::<UpdatePolicy>().is_err());
+        assert!("latest".parse::<UpdatePolicy>().is_err());
+    }
 }
 
 #[cfg(unix)]
@@ -2183,6 +3676,32 @@ mod _gen {
         serde_json::to_string_pretty(&schema).unwrap() + "\n"
     }
 
+    /// The keys suggested for misspelled keys are the ones of the schema.
+    #[test]
+    fn suggested_keys() {
+        let schema: serde_json::Value = serde_json::from_str(&generate()).unwrap();
+        let keys = |schema: &serde_json::Value| {
+            schema["properties"]
+                .as_object()
+                .into_iter()
+                .flat_map(|properties| properties.keys().cloned())
+                .collect::<std::collections::BTreeSet<_>>()
+        };
+        let definitions = &schema["definitions"];
+        let repo_keys = ["RemoteRepo", "LocalRepo", "MetaRepo", "BuiltinRepo"]
+            .iter()
+            .flat_map(|name| keys(&definitions[name]))
+            .collect::<std::collections::BTreeSet<_>>();
+
+        let expected = |keys: &[&str]| keys.iter().map(ToString::to_string).collect();
+        assert_eq!(keys(&schema), expected(super::CONFIG_KEYS));
+        assert_eq!(repo_keys, expected(super::REPO_KEYS));
+        assert_eq!(
+            keys(&definitions["ManifestHook"]),
+            expected(super::HOOK_KEYS)
+        );
+    }
+
     #[test]
     fn generate_json_schema() -> anyhow::Result<()> {
         let mode = if EnvVars::is_set(EnvVars::PREK_GENERATE) {
@@ -0,0 +1,154 @@
+//! Store entries used by each config file, by the local repository the config file is in.
+//!
+//! Every run that installs hooks records in `$PREK_HOME/config-refs.json` the repository clones
+//! and hook environments the hooks of each config file use. `prek cache gc` keeps the entries
+//! referenced by repositories that still exist, even by config files missing from the current
+//! checkout, which may exist on other branches. References of deleted repositories are dropped.
+//!
+//! Entries created before the references were first recorded may be used by repositories that
+//! haven't run since, so they are never removed for being unreferenced.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::git::GIT_ROOT;
+use crate::hook::{InstalledHook, Repo};
+use crate::store::Store;
+
+/// Store entries used by the hooks of a config file, by hook id.
+///
+/// Entries are paths relative to the store, such as `repos/<digest>` or `hooks/<name>`.
+type HookRefs = BTreeMap<String, BTreeSet<String>>;
+
+/// When this process started, so the entries it creates are newer than the references it
+/// records first.
+///
+/// Truncated to seconds, as some file systems store modification times in whole seconds.
+pub(crate) static STARTED: LazyLock<SystemTime> = LazyLock::new(|| {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    UNIX_EPOCH + Duration::from_secs(secs)
+});
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct ConfigRefs {
+    /// When the references started being recorded, only entries created since are tracked.
+    #[serde(default)]
+    pub(crate) since: Option<SystemTime>,
+    /// The config files of each local repository, relative to the repository, and the store
+    /// entries their hooks use.
+    pub(crate) repos: BTreeMap<PathBuf, BTreeMap<PathBuf, HookRefs>>,
+}
+
+impl ConfigRefs {
+    /// Read the references, if any run recorded them.
+    pub(crate) fn load(path: &Path) -> Result<Option<Self>> {
+        let content = match fs_err::read(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        Ok(Some(serde_json::from_slice(&content)?))
+    }
+
+    pub(crate) fn save(&self, path: &Path) -> Result<()> {
+        let parent = path.parent().unwrap_or(Path::new("."));
+        // Write to a temporary file first, so `prek cache gc` never reads a partial record.
+        let mut file = tempfile::NamedTempFile::new_in(parent)?;
+        serde_json::to_writer_pretty(&mut file, self)?;
+        file.persist(path)?;
+        Ok(())
+    }
+
+    /// Record the store entries used by the installed hooks.
+    ///
+    /// The entries of hooks that didn't run are kept, so a run of a single hook doesn't drop
+    /// the references of the other hooks of its config.
+    pub(crate) async fn record(store: &Store, hooks: &[InstalledHook]) -> Result<()> {
+        let mut used: BTreeMap<(PathBuf, PathBuf), HookRefs> = BTreeMap::new();
+        for hook in hooks {
+            let entries = used
+                .entry(repo_and_config(hook.project().config_file()))
+                .or_default()
+                .entry(hook.id.clone())
+                .or_default();
+            if let Repo::Remote { path, .. } = hook.repo() {
+                entries.extend(store.entry_name(path));
+            }
+            if let Some(env_path) = hook.env_path() {
+                entries.extend(store.entry_name(env_path));
+            }
+        }
+        if used.is_empty() {
+            return Ok(());
+        }
+
+        let _lock = store.lock_config_refs().await?;
+        let path = store.config_refs_file();
+        let mut refs = Self::load(&path)?.unwrap_or_default();
+        refs.since.get_or_insert(*STARTED);
+        for ((repo, config), hooks) in used {
+            refs.repos
+                .entry(repo)
+                .or_default()
+                .entry(config)
+                .or_default()
+                .extend(hooks);
+        }
+        refs.save(&path)
+    }
+
+    /// Forget the repositories that don't exist anymore, and return them.
+    pub(crate) fn prune(&mut self) -> Vec<PathBuf> {
+        let removed = self
+            .repos
+            .keys()
+            .filter(|repo| !repo.is_dir())
+            .cloned()
+            .collect::<Vec<_>>();
+        for repo in &removed {
+            self.repos.remove(repo);
+        }
+        removed
+    }
+
+    /// The store entries referenced by the config files of `repo`.
+    pub(crate) fn repo_entries<'a>(&'a self, repo: &Path) -> impl Iterator<Item = &'a str> {
+        self.repos
+            .get(repo)
+            .into_iter()
+            .flat_map(BTreeMap::values)
+            .flat_map(BTreeMap::values)
+            .flatten()
+            .map(String::as_str)
+    }
+
+    /// All store entries referenced by any repository.
+    pub(crate) fn entries(&self) -> BTreeSet<&str> {
+        self.repos
+            .keys()
+            .flat_map(|repo| self.repo_entries(repo))
+            .collect()
+    }
+}
+
+/// The local repository a config file is in, and the path of the config file relative to it.
+///
+/// Config files outside of the current git repository, passed with `--config`, are recorded
+/// under their directory.
+fn repo_and_config(config_file: &Path) -> (PathBuf, PathBuf) {
+    if let Ok(root) = GIT_ROOT.as_ref()
+        && let Ok(relative) = config_file.strip_prefix(root)
+    {
+        return (root.clone(), relative.to_path_buf());
+    }
+    let parent = config_file.parent().unwrap_or(Path::new(""));
+    let name = config_file.file_name().unwrap_or_default();
+    (parent.to_path_buf(), PathBuf::from(name))
+}
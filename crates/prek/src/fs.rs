@@ -46,14 +46,24 @@ fn record_lock_warning(message: String) {
 pub struct LockedFile(fs_err::File);
 
 impl LockedFile {
-    /// Inner implementation for [`LockedFile::acquire_blocking`] and [`LockedFile::acquire`].
-    fn lock_file_blocking(file: fs_err::File, resource: &str) -> Result<Self, std::io::Error> {
+    /// Inner implementation for [`LockedFile::acquire`] and [`LockedFile::acquire_shared`].
+    fn lock_file_blocking(
+        file: fs_err::File,
+        resource: &str,
+        shared: bool,
+    ) -> Result<Self, std::io::Error> {
         trace!(
             resource,
+            shared,
             path = %file.path().display(),
             "Checking lock",
         );
-        match file.try_lock() {
+        let result = if shared {
+            file.file().try_lock_shared()
+        } else {
+            file.try_lock()
+        };
+        match result {
             Ok(()) => {
                 debug!(resource, "Acquired lock");
                 Ok(Self(file))
@@ -68,7 +78,12 @@ impl LockedFile {
                     path = %file.path().display(),
                     "Waiting to acquire lock",
                 );
-                file.lock().map_err(|err| {
+                let result = if shared {
+                    file.file().lock_shared()
+                } else {
+                    file.lock()
+                };
+                result.map_err(|err| {
                     // Not a fs_err method, we need to build our own path context
                     std::io::Error::other(format!(
                         "Could not acquire lock for `{resource}` at `{}`: {}",
@@ -87,12 +102,28 @@ impl LockedFile {
         path: impl AsRef<Path>,
         resource: impl Display,
     ) -> Result<Self, std::io::Error> {
-        let path = path.as_ref().to_path_buf();
+        Self::acquire_inner(path.as_ref(), resource.to_string(), false).await
+    }
+
+    /// Acquire a cross-process lock for a resource that other processes may hold at the same
+    /// time with [`LockedFile::acquire_shared`], but not with [`LockedFile::acquire`].
+    pub async fn acquire_shared(
+        path: impl AsRef<Path>,
+        resource: impl Display,
+    ) -> Result<Self, std::io::Error> {
+        Self::acquire_inner(path.as_ref(), resource.to_string(), true).await
+    }
+
+    async fn acquire_inner(
+        path: &Path,
+        resource: String,
+        shared: bool,
+    ) -> Result<Self, std::io::Error> {
+        let path = path.to_path_buf();
         let file = fs_err::File::create(&path)?;
 
-        let resource = resource.to_string();
         let mut task =
-            tokio::task::spawn_blocking(move || Self::lock_file_blocking(file, &resource));
+            tokio::task::spawn_blocking(move || Self::lock_file_blocking(file, &resource, shared));
 
         tokio::select! {
             result = &mut task => result?,
@@ -167,6 +198,51 @@ pub(crate) fn normalize_path(path: PathBuf) -> PathBuf {
     }
 }
 
+/// Convert an absolute path to its extended-length form (`\\?\C:\...` or `\\?\UNC\...`) on Windows,
+/// lifting the `MAX_PATH` limit for everything below it. On other platforms, it's a no-op.
+///
+/// On Windows, relative paths are made absolute against the current working directory first.
+pub(crate) fn long_path(path: impl Into<PathBuf>) -> PathBuf {
+    let path = path.into();
+
+    #[cfg(windows)]
+    {
+        use std::path::{Component, Prefix};
+
+        let Ok(path) = std::path::absolute(&path) else {
+            return path;
+        };
+        let mut components = path.components();
+        let Some(Component::Prefix(prefix)) = components.next() else {
+            return path;
+        };
+        // Extended-length paths are not normalized by Windows, `absolute` already did that.
+        let long = match prefix.kind() {
+            Prefix::Disk(_) => {
+                let mut long = std::ffi::OsString::from(r"\\?\");
+                long.push(path.as_os_str());
+                Some(long)
+            }
+            Prefix::UNC(server, share) => {
+                let mut long = std::ffi::OsString::from(r"\\?\UNC\");
+                long.push(server);
+                long.push("\\");
+                long.push(share);
+                long.push(components.as_path().as_os_str());
+                Some(long)
+            }
+            // Already extended-length, or a device path.
+            _ => None,
+        };
+        long.map_or(path, PathBuf::from)
+    }
+
+    #[cfg(not(windows))]
+    {
+        path
+    }
+}
+
 /// Compute a path describing `path` relative to `base`.
 ///
 /// `lib/python/site-packages/foo/__init__.py` and `lib/python/site-packages` -> `foo/__init__.py`
@@ -369,6 +445,62 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg(windows)]
+    fn long_path() {
+        use std::path::Path;
+
+        assert_eq!(
+            super::long_path(r"C:\Users\prek\..\cache"),
+            Path::new(r"\\?\C:\Users\cache")
+        );
+        assert_eq!(
+            super::long_path(r"\\server\share\prek"),
+            Path::new(r"\\?\UNC\server\share\prek")
+        );
+        assert_eq!(
+            super::long_path(r"\\?\C:\cache"),
+            Path::new(r"\\?\C:\cache")
+        );
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn long_path() {
+        assert_eq!(
+            super::long_path("relative/cache"),
+            std::path::Path::new("relative/cache")
+        );
+    }
+
+    #[tokio::test]
+    async fn shared_lock() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let lock_path = tmp.path().join(".lock");
+
+        // Shared locks can be held at the same time.
+        let shared1 = super::LockedFile::acquire_shared(&lock_path, "test-lock")
+            .await
+            .expect("acquire shared1");
+        let shared2 = super::LockedFile::acquire_shared(&lock_path, "test-lock")
+            .await
+            .expect("acquire shared2");
+
+        // An exclusive lock waits for all shared locks to be released.
+        let lock_path2 = lock_path.clone();
+        let task =
+            tokio::spawn(async move { super::LockedFile::acquire(lock_path2, "test-lock").await });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!task.is_finished());
+
+        drop(shared1);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!task.is_finished());
+
+        drop(shared2);
+        task.await.expect("join task").expect("acquire exclusive");
+    }
+
     #[tokio::test]
     async fn lock_warning_emitted_after_timeout() {
         let _warnings = WarningGuard::new();
@@ -8,12 +8,13 @@ use std::sync::LazyLock;
 use anyhow::Result;
 use path_clean::PathClean;
 use prek_consts::env_vars::EnvVars;
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tracing::{debug, instrument, warn};
 
 use crate::process;
 use crate::process::{Cmd, StatusError};
+use crate::retry;
 
 #[derive(Debug, thiserror::Error)]
 pub(crate) enum Error {
@@ -39,6 +40,13 @@ pub(crate) static GIT_ROOT: LazyLock<Result<PathBuf, Error>> = LazyLock::new(||
     })
 });
 
+/// The git directory shared by all worktrees of the repository.
+pub(crate) static GIT_COMMON_DIR: LazyLock<Result<PathBuf, Error>> = LazyLock::new(|| {
+    get_common_dir().inspect(|dir| {
+        debug!("Git common dir: {}", dir.display());
+    })
+});
+
 /// Remove some `GIT_` environment variables exposed by `git`.
 ///
 /// For some commands, like `git commit -a` or `git commit -p`, git creates a `.git/index.lock` file
@@ -71,6 +79,8 @@ pub(crate) static GIT_ENV_TO_REMOVE: LazyLock<Vec<(String, String)>> = LazyLock:
 pub(crate) fn git_cmd(summary: &str) -> Result<Cmd, Error> {
     let mut cmd = Cmd::new(GIT.as_ref().map_err(|&e| Error::GitNotFound(e))?, summary);
     cmd.arg("-c").arg("core.useBuiltinFSMonitor=false");
+    // Git commands are short-lived and needed to leave the repository in a consistent state.
+    cmd.cancellable(false);
 
     Ok(cmd)
 }
@@ -132,6 +142,36 @@ pub(crate) async fn get_changed_files(
     Ok(zsplit(&output.stdout)?)
 }
 
+/// Get the files in the tree of a commit, like `get_changed_files` from before any commit.
+pub(crate) async fn get_commit_files(commit: &str, root: &Path) -> Result<Vec<PathBuf>, Error> {
+    // The empty tree hash depends on the object format of the repository.
+    let output = git_cmd("get empty tree")?
+        .arg("hash-object")
+        .arg("-t")
+        .arg("tree")
+        .arg("--stdin")
+        .stdin(Stdio::null())
+        .check(true)
+        .output()
+        .await?;
+    let empty_tree = str::from_utf8(&output.stdout)?.trim_ascii();
+
+    let output = git_cmd("get commit files")?
+        .arg("diff")
+        .arg("--name-only")
+        .arg("--diff-filter=ACMRT")
+        .arg("--no-ext-diff") // Disable external diff drivers
+        .arg("-z") // Use NUL as line terminator
+        .arg(empty_tree)
+        .arg(commit)
+        .arg("--")
+        .arg(root)
+        .check(true)
+        .output()
+        .await?;
+    Ok(zsplit(&output.stdout)?)
+}
+
 #[instrument(level = "trace")]
 pub(crate) async fn ls_files(cwd: &Path, path: &Path) -> Result<Vec<PathBuf>, Error> {
     let output = git_cmd("git ls-files")?
@@ -147,6 +187,54 @@ pub(crate) async fn ls_files(cwd: &Path, path: &Path) -> Result<Vec<PathBuf>, Er
     Ok(zsplit(&output.stdout)?)
 }
 
+/// List the untracked files under `paths`: only the ignored ones if `ignored`, otherwise
+/// only the ones that are not ignored.
+pub(crate) async fn ls_untracked_files(
+    cwd: &Path,
+    paths: &[PathBuf],
+    ignored: bool,
+) -> Result<Vec<PathBuf>, Error> {
+    let mut cmd = git_cmd("git ls-files")?;
+    cmd.current_dir(cwd)
+        .arg("--literal-pathspecs")
+        .arg("ls-files")
+        .arg("-z")
+        .arg("--others")
+        .arg("--exclude-standard");
+    if ignored {
+        cmd.arg("--ignored");
+    }
+    let output = cmd.arg("--").args(paths).check(true).output().await?;
+
+    Ok(zsplit(&output.stdout)?)
+}
+
+/// List the files under `cwd` marked `skip-worktree` or `assume-unchanged` in the index,
+/// relative to `cwd`.
+#[instrument(level = "trace")]
+pub(crate) async fn ls_skip_worktree_files(cwd: &Path) -> Result<FxHashSet<PathBuf>, Error> {
+    let output = git_cmd("git ls-files")?
+        .current_dir(cwd)
+        .arg("ls-files")
+        .arg("-z")
+        .arg("-t")
+        .arg("-v")
+        .check(true)
+        .output()
+        .await?;
+
+    // Each entry is a status tag followed by a space and the path. `S` marks skip-worktree
+    // entries, and lowercase tags mark assume-unchanged entries.
+    Ok(zsplit(&output.stdout)?
+        .into_iter()
+        .filter_map(|entry| {
+            let entry = entry.to_str()?;
+            let (tag, path) = entry.split_once(' ')?;
+            (tag == "S" || tag.chars().all(|c| c.is_ascii_lowercase())).then(|| PathBuf::from(path))
+        })
+        .collect())
+}
+
 pub(crate) async fn get_git_dir() -> Result<PathBuf, Error> {
     let output = git_cmd("get git dir")?
         .arg("rev-parse")
@@ -190,6 +278,68 @@ pub(crate) async fn get_staged_files(root: &Path) -> Result<Vec<PathBuf>, Error>
     Ok(zsplit(&output.stdout)?)
 }
 
+/// Get the files under `root` with changes in the work tree that are not staged, relative to the
+/// root of the repository.
+pub(crate) async fn get_unstaged_files(root: &Path) -> Result<Vec<PathBuf>, Error> {
+    let output = git_cmd("get unstaged files")?
+        .arg("diff")
+        .arg("--name-only")
+        .arg("--no-ext-diff") // Disable external diff drivers
+        .arg("--ignore-submodules")
+        .arg("-z") // Use NUL as line terminator
+        .arg("--")
+        .arg(root)
+        .check(true)
+        .output()
+        .await?;
+    Ok(zsplit(&output.stdout)?)
+}
+
+/// Get the lines added and removed by the unstaged changes of the files under `root`, by their
+/// path relative to the root of the repository. Binary files have no line counts.
+pub(crate) async fn get_diff_numstat(
+    root: &Path,
+) -> Result<Vec<(PathBuf, Option<(u64, u64)>)>, Error> {
+    let output = git_cmd("git diff")?
+        .arg("diff")
+        .arg("--numstat")
+        .arg("--no-ext-diff") // Disable external diff drivers
+        .arg("--no-textconv")
+        .arg("--ignore-submodules")
+        .arg("-z") // Use NUL as line terminator
+        .arg("--")
+        .arg(root)
+        .check(true)
+        .output()
+        .await?;
+
+    let mut stats = Vec::new();
+    for record in output.stdout.split(|&b| b == b'\0') {
+        // Each record is `<added>\t<removed>\t<path>`, with `-` for the counts of binary files.
+        let mut parts = str::from_utf8(record)?.splitn(3, '\t');
+        let (Some(added), Some(removed), Some(path)) = (parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+        let lines = added.parse().ok().zip(removed.parse().ok());
+        stats.push((PathBuf::from(path), lines));
+    }
+    Ok(stats)
+}
+
+/// Stage the work tree changes of files, given relative to the root of the repository.
+pub(crate) async fn add_files(root: &Path, files: &[PathBuf]) -> Result<(), Error> {
+    git_cmd("git add")?
+        .current_dir(root)
+        .arg("add")
+        .arg("--")
+        .args(files)
+        .check(true)
+        .output()
+        .await?;
+    Ok(())
+}
+
 pub(crate) async fn files_not_staged(files: &[&Path]) -> Result<Vec<PathBuf>> {
     let output = git_cmd("git diff")?
         .arg("diff")
@@ -334,18 +484,46 @@ pub(crate) fn get_root() -> Result<PathBuf, Error> {
     ))
 }
 
-pub(crate) async fn init_repo(url: &str, path: &Path) -> Result<(), Error> {
-    let url = if Path::new(url).is_dir() {
-        // If the URL is a local path, convert it to an absolute path
-        Cow::Owned(
+/// Get the absolute path of the git directory shared by all worktrees.
+#[instrument(level = "trace")]
+fn get_common_dir() -> Result<PathBuf, Error> {
+    let git = GIT.as_ref().map_err(|&e| Error::GitNotFound(e))?;
+    let output = std::process::Command::new(git)
+        .arg("rev-parse")
+        .arg("--git-common-dir")
+        .output()?;
+    if !output.status.success() {
+        return Err(Error::Command(process::Error::Status {
+            summary: "get git common dir".to_string(),
+            error: StatusError {
+                status: output.status,
+                output: Some(output),
+            },
+        }));
+    }
+
+    // The path is relative to the current directory, unless it is outside the working tree.
+    Ok(std::path::absolute(
+        String::from_utf8_lossy(&output.stdout).trim_ascii(),
+    )?)
+}
+
+/// The URL of a remote, with local paths made absolute.
+fn remote_url(url: &str) -> Result<Cow<'_, str>, Error> {
+    if Path::new(url).is_dir() {
+        Ok(Cow::Owned(
             std::path::absolute(url)?
                 .clean()
                 .to_string_lossy()
                 .to_string(),
-        )
+        ))
     } else {
-        Cow::Borrowed(url)
-    };
+        Ok(Cow::Borrowed(url))
+    }
+}
+
+pub(crate) async fn init_repo(url: &str, path: &Path) -> Result<(), Error> {
+    let url = remote_url(url)?;
 
     git_cmd("init git repo")?
         // Unset `extensions.objectFormat` if set, just follow what hash the remote uses.
@@ -370,83 +548,398 @@ pub(crate) async fn init_repo(url: &str, path: &Path) -> Result<(), Error> {
         .output()
         .await?;
 
+    // Let git check out files beyond `MAX_PATH`, the store path is already in extended-length form.
+    #[cfg(windows)]
+    git_cmd("enable git long paths")?
+        .current_dir(path)
+        .arg("config")
+        .arg("core.longpaths")
+        .arg("true")
+        .remove_git_envs()
+        .check(true)
+        .output()
+        .await?;
+
     Ok(())
 }
 
+/// Run a git command that talks to a remote, retrying it when it fails because of the network.
+async fn output_with_retries(
+    operation: &str,
+    mut cmd: impl FnMut() -> Result<Cmd, Error>,
+) -> Result<(), Error> {
+    retry::with_retries(operation, is_network_error, || {
+        let cmd = cmd();
+        async move {
+            cmd?.output().await?;
+            Ok(())
+        }
+    })
+    .await
+}
+
+/// Whether a git command failed because of a (likely transient) network error.
+pub(crate) fn is_network_error(err: &Error) -> bool {
+    const PATTERNS: &[&str] = &[
+        "Could not resolve host",
+        "Connection refused",
+        "Connection reset",
+        "Connection timed out",
+        "Operation timed out",
+        "Failed to connect",
+        "early EOF",
+        "RPC failed",
+        "remote end hung up unexpectedly",
+        "TLS connection was non-properly terminated",
+        "The requested URL returned error: 5",
+        "The requested URL returned error: 429",
+    ];
+
+    let Error::Command(process::Error::Status {
+        error: StatusError {
+            output: Some(output),
+            ..
+        },
+        ..
+    }) = err
+    else {
+        return false;
+    };
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    PATTERNS.iter().any(|pattern| stderr.contains(pattern))
+}
+
+/// Fetch only the commit of `rev` without any file contents, which are fetched on checkout for
+/// just that commit. Servers that don't support filters send the contents right away.
 async fn shallow_clone(rev: &str, path: &Path) -> Result<(), Error> {
-    git_cmd("git shallow clone")?
+    output_with_retries("`git fetch`", || {
+        let mut cmd = git_cmd("git shallow clone")?;
+        cmd.current_dir(path)
+            .arg("-c")
+            .arg("protocol.version=2")
+            .arg("fetch")
+            .arg("origin")
+            .arg(rev)
+            .arg("--depth=1")
+            .arg("--filter=blob:none")
+            // Disable interactive prompts in the terminal, as they'll be erased by the progress bar
+            // animation and the process will "hang".
+            .env(EnvVars::GIT_TERMINAL_PROMPT, "0")
+            .remove_git_envs()
+            .check(true);
+        Ok(cmd)
+    })
+    .await?;
+
+    // Checking out fetches the file contents from the remote.
+    output_with_retries("`git checkout`", || {
+        let mut cmd = git_cmd("git checkout")?;
+        cmd.current_dir(path)
+            .arg("-c")
+            .arg("protocol.version=2")
+            .arg("checkout")
+            .arg("FETCH_HEAD")
+            .env(EnvVars::GIT_TERMINAL_PROMPT, "0")
+            .remove_git_envs()
+            .env(EnvVars::PREK_INTERNAL__SKIP_POST_CHECKOUT, "1")
+            .check(true);
+        Ok(cmd)
+    })
+    .await?;
+
+    output_with_retries("`git submodule update`", || {
+        let mut cmd = git_cmd("update git submodules")?;
+        cmd.current_dir(path)
+            .arg("-c")
+            .arg("protocol.version=2")
+            .arg("submodule")
+            .arg("update")
+            .arg("--init")
+            .arg("--recursive")
+            .arg("--depth=1")
+            .env(EnvVars::GIT_TERMINAL_PROMPT, "0")
+            .remove_git_envs()
+            .check(true);
+        Ok(cmd)
+    })
+    .await?;
+
+    Ok(())
+}
+
+async fn full_clone(rev: &str, path: &Path) -> Result<(), Error> {
+    output_with_retries("`git fetch`", || {
+        let mut cmd = git_cmd("git full clone")?;
+        cmd.current_dir(path)
+            .arg("fetch")
+            .arg("origin")
+            .arg("--tags")
+            .env(EnvVars::GIT_TERMINAL_PROMPT, "0")
+            .remove_git_envs()
+            .check(true);
+        Ok(cmd)
+    })
+    .await?;
+
+    git_cmd("git checkout")?
         .current_dir(path)
-        .arg("-c")
-        .arg("protocol.version=2")
-        .arg("fetch")
-        .arg("origin")
+        .arg("checkout")
         .arg(rev)
-        .arg("--depth=1")
-        // Disable interactive prompts in the terminal, as they'll be erased by the progress bar
-        // animation and the process will "hang".
-        .env(EnvVars::GIT_TERMINAL_PROMPT, "0")
+        .env(EnvVars::PREK_INTERNAL__SKIP_POST_CHECKOUT, "1")
         .remove_git_envs()
         .check(true)
         .output()
         .await?;
 
-    git_cmd("git checkout")?
-        .current_dir(path)
-        .arg("checkout")
-        .arg("FETCH_HEAD")
+    output_with_retries("`git submodule update`", || {
+        let mut cmd = git_cmd("update git submodules")?;
+        cmd.current_dir(path)
+            .arg("submodule")
+            .arg("update")
+            .arg("--init")
+            .arg("--recursive")
+            .env(EnvVars::GIT_TERMINAL_PROMPT, "0")
+            .remove_git_envs()
+            .check(true);
+        Ok(cmd)
+    })
+    .await?;
+
+    Ok(())
+}
+
+pub(crate) async fn clone_repo(url: &str, rev: &str, path: &Path) -> Result<(), Error> {
+    init_repo(url, path).await?;
+
+    match shallow_clone(rev, path).await {
+        Ok(()) => Ok(()),
+        // A full clone won't fare better if the remote can't be reached.
+        Err(err) if is_network_error(&err) => Err(err),
+        Err(err) => {
+            warn!(?err, "Failed to shallow clone, falling back to full clone");
+            full_clone(rev, path).await
+        }
+    }
+}
+
+/// Fetch `rev` of `url` into `objects_repo`, a bare repo holding the objects of every revision
+/// of `url` cloned so far, and return the commit `rev` points to.
+///
+/// Only the commit is fetched, without its history. Objects it shares with revisions fetched
+/// before are not sent again.
+pub(crate) async fn fetch_shared_objects(
+    url: &str,
+    rev: &str,
+    objects_repo: &Path,
+) -> Result<String, Error> {
+    init_objects_repo(url, objects_repo).await?;
+
+    // A commit fetched for another clone is already there.
+    let output = git_cmd("git rev-parse")?
+        .current_dir(objects_repo)
+        .arg("rev-parse")
+        .arg("--verify")
+        .arg("--quiet")
+        .arg(format!("{rev}^{{commit}}"))
         .remove_git_envs()
-        .env(EnvVars::PREK_INTERNAL__SKIP_POST_CHECKOUT, "1")
-        .check(true)
+        .check(false)
         .output()
         .await?;
+    let existing = str::from_utf8(&output.stdout)?.trim_ascii();
+    if output.status.success() && existing.eq_ignore_ascii_case(rev) {
+        return Ok(existing.to_string());
+    }
 
-    git_cmd("update git submodules")?
-        .current_dir(path)
-        .arg("-c")
-        .arg("protocol.version=2")
-        .arg("submodule")
-        .arg("update")
-        .arg("--init")
-        .arg("--recursive")
-        .arg("--depth=1")
-        .env(EnvVars::GIT_TERMINAL_PROMPT, "0")
+    output_with_retries("`git fetch`", || {
+        let mut cmd = git_cmd("git fetch")?;
+        cmd.current_dir(objects_repo)
+            .arg("-c")
+            .arg("protocol.version=2")
+            .arg("fetch")
+            .arg("--no-tags")
+            .arg("--depth=1")
+            .arg("origin")
+            .arg(rev)
+            .env(EnvVars::GIT_TERMINAL_PROMPT, "0")
+            .remove_git_envs()
+            .check(true);
+        Ok(cmd)
+    })
+    .await?;
+
+    let output = git_cmd("git rev-parse")?
+        .current_dir(objects_repo)
+        .arg("rev-parse")
+        .arg("FETCH_HEAD^{commit}")
         .remove_git_envs()
         .check(true)
         .output()
         .await?;
+    let commit = str::from_utf8(&output.stdout)?.trim_ascii().to_string();
+    keep_shared_commit(objects_repo, &commit).await?;
 
+    Ok(commit)
+}
+
+/// Create the bare repo shared by all revisions of `url`, if it doesn't exist yet.
+async fn init_objects_repo(url: &str, objects_repo: &Path) -> Result<(), Error> {
+    if !objects_repo.join("HEAD").is_file() {
+        git_cmd("init git repo")?
+            .arg("-c")
+            .arg("init.defaultObjectFormat=")
+            .arg("init")
+            .arg("--bare")
+            .arg("--template=")
+            .arg(objects_repo)
+            .remove_git_envs()
+            .check(true)
+            .output()
+            .await?;
+    }
+    // Set every time, in case an interrupted init didn't get to it. Clones using the objects
+    // aren't visible to git here, so it must never prune any of them. Fetched objects are kept
+    // packed, so that clones moving their objects here can tell which they have.
+    for (key, value) in [
+        ("remote.origin.url", &*remote_url(url)?),
+        ("gc.auto", "0"),
+        ("fetch.unpackLimit", "1"),
+    ] {
+        git_cmd("configure git repo")?
+            .current_dir(objects_repo)
+            .arg("config")
+            .arg(key)
+            .arg(value)
+            .remove_git_envs()
+            .check(true)
+            .output()
+            .await?;
+    }
     Ok(())
 }
 
-async fn full_clone(rev: &str, path: &Path) -> Result<(), Error> {
-    git_cmd("git full clone")?
-        .current_dir(path)
-        .arg("fetch")
-        .arg("origin")
-        .arg("--tags")
-        .env(EnvVars::GIT_TERMINAL_PROMPT, "0")
+/// Reference `commit` in `objects_repo`, so that its objects are never pruned.
+async fn keep_shared_commit(objects_repo: &Path, commit: &str) -> Result<(), Error> {
+    git_cmd("git update-ref")?
+        .current_dir(objects_repo)
+        .arg("update-ref")
+        .arg(format!("refs/prek/{commit}"))
+        .arg(commit)
         .remove_git_envs()
         .check(true)
         .output()
         .await?;
+    Ok(())
+}
+
+/// Check out `commit` of `url` into a new repo at `path`, reading its objects from
+/// `objects_dir`, as fetched by [`fetch_shared_objects`], instead of fetching them again.
+///
+/// `objects_dir` is used as a git alternate, a relative path is relative to `.git/objects`.
+pub(crate) async fn checkout_shared_objects(
+    url: &str,
+    commit: &str,
+    objects_dir: &Path,
+    path: &Path,
+) -> Result<(), Error> {
+    init_repo(url, path).await?;
+
+    let git_dir = path.join(".git");
+    fs_err::tokio::create_dir_all(git_dir.join("objects").join("info")).await?;
+    fs_err::tokio::write(
+        git_dir.join("objects").join("info").join("alternates"),
+        format!("{}\n", objects_dir.to_string_lossy().replace('\\', "/")),
+    )
+    .await?;
+    // Without the history, the commit is the boundary of a shallow clone, from which
+    // `unshallow_repo` can fetch the history when it's needed.
+    fs_err::tokio::write(git_dir.join("shallow"), format!("{commit}\n")).await?;
 
     git_cmd("git checkout")?
         .current_dir(path)
         .arg("checkout")
-        .arg(rev)
+        .arg("--detach")
+        .arg(commit)
         .env(EnvVars::PREK_INTERNAL__SKIP_POST_CHECKOUT, "1")
         .remove_git_envs()
         .check(true)
         .output()
         .await?;
 
-    git_cmd("update git submodules")?
+    output_with_retries("`git submodule update`", || {
+        let mut cmd = git_cmd("update git submodules")?;
+        cmd.current_dir(path)
+            .arg("-c")
+            .arg("protocol.version=2")
+            .arg("submodule")
+            .arg("update")
+            .arg("--init")
+            .arg("--recursive")
+            .arg("--depth=1")
+            .env(EnvVars::GIT_TERMINAL_PROMPT, "0")
+            .remove_git_envs()
+            .check(true);
+        Ok(cmd)
+    })
+    .await?;
+
+    Ok(())
+}
+
+/// Move the objects of the checked out commit of a repo cloned by [`clone_repo`] into
+/// `objects_repo`, and read them from there through `objects_dir`, as a git alternate.
+pub(crate) async fn share_objects(
+    url: &str,
+    path: &Path,
+    objects_repo: &Path,
+    objects_dir: &Path,
+) -> Result<(), Error> {
+    let output = git_cmd("git rev-parse")?
+        .current_dir(path)
+        .arg("rev-parse")
+        .arg("HEAD")
+        .remove_git_envs()
+        .check(true)
+        .output()
+        .await?;
+    let commit = str::from_utf8(&output.stdout)?.trim_ascii().to_string();
+
+    init_objects_repo(url, objects_repo).await?;
+    git_cmd("git fetch")?
+        .current_dir(objects_repo)
+        .arg("fetch")
+        .arg("--no-tags")
+        .arg("--depth=1")
+        .arg(path)
+        .arg("HEAD")
+        .remove_git_envs()
+        .check(true)
+        .output()
+        .await?;
+    keep_shared_commit(objects_repo, &commit).await?;
+
+    fs_err::tokio::write(
+        path.join(".git")
+            .join("objects")
+            .join("info")
+            .join("alternates"),
+        format!("{}\n", objects_dir.to_string_lossy().replace('\\', "/")),
+    )
+    .await?;
+    // Drop the objects the alternate has.
+    git_cmd("git repack")?
+        .current_dir(path)
+        .arg("repack")
+        .arg("-a")
+        .arg("-d")
+        .arg("-l")
+        .arg("-q")
+        .remove_git_envs()
+        .check(true)
+        .output()
+        .await?;
+    git_cmd("git prune-packed")?
         .current_dir(path)
-        .arg("submodule")
-        .arg("update")
-        .arg("--init")
-        .arg("--recursive")
-        .env(EnvVars::GIT_TERMINAL_PROMPT, "0")
+        .arg("prune-packed")
         .remove_git_envs()
         .check(true)
         .output()
@@ -455,15 +948,39 @@ async fn full_clone(rev: &str, path: &Path) -> Result<(), Error> {
     Ok(())
 }
 
-pub(crate) async fn clone_repo(url: &str, rev: &str, path: &Path) -> Result<(), Error> {
-    init_repo(url, path).await?;
-
-    if let Err(err) = shallow_clone(rev, path).await {
-        warn!(?err, "Failed to shallow clone, falling back to full clone");
-        full_clone(rev, path).await
-    } else {
-        Ok(())
+/// Fetch the full history and the tags of a repo cloned by [`clone_repo`], if it's shallow.
+///
+/// Returns whether the repo was shallow.
+pub(crate) async fn unshallow_repo(path: &Path) -> Result<bool, Error> {
+    let output = git_cmd("check shallow repository")?
+        .current_dir(path)
+        .arg("rev-parse")
+        .arg("--is-shallow-repository")
+        .remove_git_envs()
+        .check(true)
+        .output()
+        .await?;
+    if str::from_utf8(&output.stdout)?.trim_ascii() != "true" {
+        return Ok(false);
     }
+
+    output_with_retries("`git fetch`", || {
+        let mut cmd = git_cmd("git unshallow")?;
+        cmd.current_dir(path)
+            .arg("-c")
+            .arg("protocol.version=2")
+            .arg("fetch")
+            .arg("origin")
+            .arg("--unshallow")
+            .arg("--tags")
+            .env(EnvVars::GIT_TERMINAL_PROMPT, "0")
+            .remove_git_envs()
+            .check(true);
+        Ok(cmd)
+    })
+    .await?;
+
+    Ok(true)
 }
 
 pub(crate) async fn has_hooks_path_set() -> Result<bool> {
@@ -481,14 +998,57 @@ pub(crate) async fn has_hooks_path_set() -> Result<bool> {
     }
 }
 
+/// Whether git converts line endings to CRLF on checkout, with `core.autocrlf` set to `true`.
+pub(crate) async fn has_autocrlf() -> Result<bool> {
+    let output = git_cmd("get git autocrlf")?
+        .arg("config")
+        .arg("--type=bool")
+        .arg("--get")
+        .arg("core.autocrlf")
+        .check(false)
+        .output()
+        .await?;
+    // `input` is not a boolean, so git fails and it's treated as not set.
+    Ok(output.status.success() && output.stdout.trim_ascii() == b"true")
+}
+
+/// A line ending set with the `eol` attribute in `.gitattributes`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Eol {
+    Lf,
+    Crlf,
+}
+
 pub(crate) async fn get_lfs_files(paths: &[&Path]) -> Result<FxHashSet<PathBuf>, Error> {
+    Ok(check_attr("filter", paths)
+        .await?
+        .into_iter()
+        .filter_map(|(file, value)| (value == "lfs").then_some(file))
+        .collect())
+}
+
+/// Get the line endings of the files that set the `eol` attribute.
+pub(crate) async fn get_eol_attrs(paths: &[&Path]) -> Result<FxHashMap<PathBuf, Eol>, Error> {
+    Ok(check_attr("eol", paths)
+        .await?
+        .into_iter()
+        .filter_map(|(file, value)| match value.as_str() {
+            "lf" => Some((file, Eol::Lf)),
+            "crlf" => Some((file, Eol::Crlf)),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Get the value of an attribute for each path, with `git check-attr`.
+async fn check_attr(attr: &str, paths: &[&Path]) -> Result<Vec<(PathBuf, String)>, Error> {
     if paths.is_empty() {
-        return Ok(FxHashSet::default());
+        return Ok(Vec::new());
     }
 
     let mut child = git_cmd("git check-attr")?
         .arg("check-attr")
-        .arg("filter")
+        .arg(attr)
         .arg("-z")
         .arg("--stdin")
         .stdin(Stdio::piped())
@@ -527,19 +1087,17 @@ pub(crate) async fn get_lfs_files(paths: &[&Path]) -> Result<FxHashSet<PathBuf>,
         }));
     }
 
-    let mut lfs_files = FxHashSet::default();
+    let mut attrs = Vec::new();
     let read_result = String::from_utf8_lossy(&read_result);
     let mut it = read_result.split_terminator('\0');
     loop {
         let (Some(file), Some(_attr), Some(value)) = (it.next(), it.next(), it.next()) else {
             break;
         };
-        if value == "lfs" {
-            lfs_files.insert(PathBuf::from(file));
-        }
+        attrs.push((PathBuf::from(file), value.to_string()));
     }
 
-    Ok(lfs_files)
+    Ok(attrs)
 }
 
 /// Check if a git revision exists
@@ -610,6 +1168,140 @@ pub(crate) async fn get_parent_commit(commit: &str) -> Result<Option<String>, Er
     }
 }
 
+/// The branch the current changes are going to be merged into.
+#[derive(Debug)]
+pub(crate) struct BaseBranch {
+    /// The remote the branch is fetched from, `None` if it is a local branch.
+    pub(crate) remote: Option<String>,
+    /// The ref of the branch on the remote, e.g. `refs/heads/main`.
+    pub(crate) remote_ref: String,
+    /// The local ref tracking the branch, e.g. `refs/remotes/origin/main`.
+    pub(crate) local_ref: String,
+}
+
+impl BaseBranch {
+    /// A branch of the `origin` remote, as named by CI providers.
+    pub(crate) fn origin(branch: &str) -> Self {
+        Self {
+            remote: Some("origin".to_string()),
+            remote_ref: format!("refs/heads/{branch}"),
+            local_ref: format!("refs/remotes/origin/{branch}"),
+        }
+    }
+}
+
+/// Get the upstream tracking branch of the current branch, if any.
+pub(crate) async fn get_upstream_branch() -> Result<Option<BaseBranch>, Error> {
+    let output = git_cmd("get current branch")?
+        .arg("symbolic-ref")
+        .arg("--quiet")
+        .arg("HEAD")
+        .check(false)
+        .output()
+        .await?;
+    // Detached `HEAD` has no upstream.
+    if !output.status.success() {
+        return Ok(None);
+    }
+    let branch = str::from_utf8(&output.stdout)?.trim_ascii();
+
+    let output = git_cmd("get upstream branch")?
+        .arg("for-each-ref")
+        .arg("--format=%(upstream)%00%(upstream:remotename)%00%(upstream:remoteref)")
+        .arg(branch)
+        .check(true)
+        .output()
+        .await?;
+    let output = str::from_utf8(&output.stdout)?.trim_ascii();
+    let mut parts = output.split('\0');
+    let (Some(local_ref), Some(remote), Some(remote_ref)) =
+        (parts.next(), parts.next(), parts.next())
+    else {
+        return Ok(None);
+    };
+    if local_ref.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(BaseBranch {
+        // Branches tracking a local branch have `.` as remote.
+        remote: (!remote.is_empty() && remote != ".").then(|| remote.to_string()),
+        remote_ref: remote_ref.to_string(),
+        local_ref: local_ref.to_string(),
+    }))
+}
+
+/// Check if the repository is a shallow clone.
+pub(crate) async fn is_shallow_repository() -> Result<bool, Error> {
+    let output = git_cmd("check shallow repository")?
+        .arg("rev-parse")
+        .arg("--is-shallow-repository")
+        .check(true)
+        .output()
+        .await?;
+    Ok(str::from_utf8(&output.stdout)?.trim_ascii() == "true")
+}
+
+/// Get the best common ancestor of two commits, if they have one.
+pub(crate) async fn merge_base(a: &str, b: &str) -> Result<Option<String>, Error> {
+    let output = git_cmd("get merge base")?
+        .arg("merge-base")
+        .arg(a)
+        .arg(b)
+        .check(false)
+        .output()
+        .await?;
+    if output.status.success() {
+        Ok(Some(
+            str::from_utf8(&output.stdout)?.trim_ascii().to_string(),
+        ))
+    } else {
+        Ok(None)
+    }
+}
+
+/// How much history to fetch when fetching a branch.
+#[derive(Debug, Copy, Clone)]
+pub(crate) enum FetchDepth {
+    Full,
+    /// Fetch at most this many commits from the tip of the branch.
+    Depth(u32),
+    /// Fetch this many more commits from the current shallow boundary.
+    Deepen(u32),
+    /// Fetch the complete history of a shallow repository.
+    Unshallow,
+}
+
+/// Fetch a branch from a remote into its tracking ref.
+pub(crate) async fn fetch_branch(branch: &BaseBranch, depth: FetchDepth) -> Result<(), Error> {
+    let Some(remote) = &branch.remote else {
+        return Ok(());
+    };
+
+    output_with_retries("`git fetch`", || {
+        let mut cmd = git_cmd("git fetch")?;
+        cmd.arg("fetch").arg("--no-tags");
+        match depth {
+            FetchDepth::Full => {}
+            FetchDepth::Depth(depth) => {
+                cmd.arg(format!("--depth={depth}"));
+            }
+            FetchDepth::Deepen(depth) => {
+                cmd.arg(format!("--deepen={depth}"));
+            }
+            FetchDepth::Unshallow => {
+                cmd.arg("--unshallow");
+            }
+        }
+        cmd.arg(remote)
+            .arg(format!("+{}:{}", branch.remote_ref, branch.local_ref))
+            .env(EnvVars::GIT_TERMINAL_PROMPT, "0")
+            .check(true);
+        Ok(cmd)
+    })
+    .await
+}
+
 /// Return a list of absolute paths of all git submodules in the repository.
 #[instrument(level = "trace")]
 pub(crate) fn list_submodules(git_root: &Path) -> Result<Vec<PathBuf>, Error> {
@@ -1,13 +1,16 @@
 use std::borrow::Cow;
 use std::ffi::OsStr;
 use std::fmt::{Display, Formatter};
+use std::num::NonZeroUsize;
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 
 use anyhow::{Context, Result};
 use clap::ValueEnum;
-use prek_consts::MANIFEST_FILE;
+use itertools::Itertools;
+use lazy_regex::regex;
 use rustc_hash::{FxBuildHasher, FxHashMap, FxHashSet};
 use serde::{Deserialize, Serialize};
 use tempfile::TempDir;
@@ -15,12 +18,16 @@ use thiserror::Error;
 use tracing::trace;
 
 use crate::config::{
-    self, BuiltinHook, Config, FilePattern, HookOptions, Language, LocalHook, ManifestHook,
-    MetaHook, RemoteHook, Stage, read_manifest,
+    self, BuiltinHook, Config, FailFast, FilePattern, HookArch, HookKind, HookOptions, HookOs,
+    Language, LocalHook, ManifestHook, MetaHook, RemoteHook, Stage, manifest_path, read_manifest,
 };
 use crate::languages::version::LanguageRequest;
-use crate::languages::{extract_metadata_from_entry, resolve_command};
-use crate::store::Store;
+use crate::languages::{
+    cargo_path_dependencies_key, editable_metadata_key, extract_metadata_from_entry,
+    resolve_command,
+};
+use crate::run::STAGED_FILES_FILE;
+use crate::store::{Store, env_digest};
 use crate::workspace::Project;
 
 #[derive(Error, Debug)]
@@ -44,6 +51,9 @@ pub(crate) enum Error {
 
     #[error("Failed to create directory for hook environment")]
     TmpDir(#[from] std::io::Error),
+
+    #[error("`{}` is not empty and doesn't contain a hook environment", .0.display())]
+    EnvDirNotEmpty(PathBuf),
 }
 
 #[derive(Debug, Clone)]
@@ -69,7 +79,7 @@ pub(crate) enum Repo {
 impl Repo {
     /// Load the remote repo manifest from the path.
     pub(crate) fn remote(url: String, rev: String, path: PathBuf) -> Result<Self, Error> {
-        let manifest = read_manifest(&path.join(MANIFEST_FILE)).map_err(|e| Error::Manifest {
+        let manifest = read_manifest(&manifest_path(&path)).map_err(|e| Error::Manifest {
             repo: url.clone(),
             error: e,
         })?;
@@ -173,6 +183,18 @@ impl HookBuilder {
         self
     }
 
+    /// Restrict the hook to the files selected by the `files` and `exclude` of its repo.
+    pub(crate) fn repo_filters(
+        &mut self,
+        files: Option<&FilePattern>,
+        exclude: Option<&FilePattern>,
+    ) -> &mut Self {
+        let options = &mut self.config.options;
+        options.files = FilePattern::all(files.cloned(), options.files.take());
+        options.exclude = FilePattern::any(exclude.cloned(), options.exclude.take());
+        self
+    }
+
     /// Combine the hook configuration with the project level configuration.
     pub(crate) fn combine(&mut self, config: &Config) {
         let options = &mut self.config.options;
@@ -200,11 +222,17 @@ impl HookBuilder {
         options.types_or.get_or_insert_default();
         options.exclude_types.get_or_insert_default();
         options.always_run.get_or_insert(false);
-        options.fail_fast.get_or_insert(false);
+        options.fail_fast.get_or_insert(FailFast::Off);
         options.pass_filenames.get_or_insert(true);
+        options.pass_push_refs.get_or_insert(false);
+        options.success_exit_codes.get_or_insert(vec![0]);
+        options.fail_on_output.get_or_insert(false);
+        options.retries.get_or_insert(0);
+        options.retry_delay.get_or_insert(0.0);
         options.require_serial.get_or_insert(false);
         options.verbose.get_or_insert(false);
         options.additional_dependencies.get_or_insert_default();
+        options.goflags.get_or_insert_default();
     }
 
     /// Check the hook configuration.
@@ -213,6 +241,9 @@ impl HookBuilder {
         let HookOptions {
             language_version,
             additional_dependencies,
+            image_digest,
+            goflags,
+            args,
             ..
         } = &self.config.options;
 
@@ -257,6 +288,55 @@ impl HookBuilder {
             }
         }
 
+        if let Some(image_digest) = image_digest {
+            if language != Language::DockerImage {
+                return Err(Error::Hook {
+                    hook: self.config.id.clone(),
+                    error: anyhow::anyhow!(
+                        "Hook specified `image_digest: {image_digest}` but the language `{language}` does not run a prebuilt image",
+                    ),
+                });
+            }
+
+            if !regex!(r"^[a-z0-9]+(?:[.+_-][a-z0-9]+)*:[a-fA-F0-9]{32,}$").is_match(image_digest) {
+                return Err(Error::Hook {
+                    hook: self.config.id.clone(),
+                    error: anyhow::anyhow!(
+                        "Invalid `image_digest: {image_digest}`, expected a digest like `sha256:<hex>`",
+                    ),
+                });
+            }
+        }
+
+        if let Some(goflags) = goflags
+            && language != Language::Golang
+        {
+            return Err(Error::Hook {
+                hook: self.config.id.clone(),
+                error: anyhow::anyhow!(
+                    "Hook specified `goflags: {}` but the language `{language}` is not `golang`",
+                    goflags.join(" "),
+                ),
+            });
+        }
+
+        // Docker hooks can't see the file on the host, `pygrep` reads the files from stdin.
+        if matches!(
+            language,
+            Language::Docker | Language::DockerImage | Language::Pygrep
+        ) && args
+            .iter()
+            .flatten()
+            .any(|arg| arg.contains(STAGED_FILES_FILE))
+        {
+            return Err(Error::Hook {
+                hook: self.config.id.clone(),
+                error: anyhow::anyhow!(
+                    "Hook specified `{STAGED_FILES_FILE}` in `args` but the language `{language}` does not support it",
+                ),
+            });
+        }
+
         Ok(())
     }
 
@@ -293,13 +373,26 @@ impl HookBuilder {
             None => Stages::All,
         };
 
+        let retry_delay = options.retry_delay.expect("retry_delay not set");
+        let retry_delay = Duration::try_from_secs_f64(retry_delay).map_err(|_| Error::Hook {
+            hook: self.config.id.clone(),
+            error: anyhow::anyhow!(
+                "Invalid `retry_delay: {retry_delay}`, expected a non-negative number of seconds",
+            ),
+        })?;
+
         let priority = options
             .priority
-            .unwrap_or(u32::try_from(self.idx).expect("idx too large"));
+            .unwrap_or_else(|| u32::try_from(self.idx).expect("idx too large"));
 
         let mut hook = Hook {
             entry,
             stages,
+            language_version: if language_version.is_empty() {
+                "default".to_string()
+            } else {
+                language_version
+            },
             language_request,
             additional_dependencies,
             dependencies: OnceLock::new(),
@@ -315,17 +408,33 @@ impl HookBuilder {
             types: options.types.expect("types not set"),
             types_or: options.types_or.expect("types_or not set"),
             exclude_types: options.exclude_types.expect("exclude_types not set"),
+            max_file_size: options.max_file_size,
             args: options.args.expect("args not set"),
             env: options.env.expect("env not set"),
             always_run: options.always_run.expect("always_run not set"),
             fail_fast: options.fail_fast.expect("fail_fast not set"),
             pass_filenames: options.pass_filenames.expect("pass_filenames not set"),
+            pass_push_refs: options.pass_push_refs.expect("pass_push_refs not set"),
+            success_exit_codes: options
+                .success_exit_codes
+                .expect("success_exit_codes not set"),
+            fail_on_output: options.fail_on_output.expect("fail_on_output not set"),
+            retries: options.retries.expect("retries not set"),
+            retry_delay,
+            os: options.os.unwrap_or_default(),
+            arch: options.arch.unwrap_or_default(),
+            kind: options.kind,
             description: options.description,
             log_file: options.log_file,
             require_serial: options.require_serial.expect("require_serial not set"),
+            max_files_per_invocation: options.max_files_per_invocation,
             verbose: options.verbose.expect("verbose not set"),
             minimum_prek_version: options.minimum_prek_version,
+            image_digest: options.image_digest,
+            goflags: options.goflags.expect("goflags not set"),
             priority,
+            after: options.after.unwrap_or_default(),
+            before: options.before.unwrap_or_default(),
         };
 
         if let Err(err) = extract_metadata_from_entry(&mut hook).await {
@@ -361,11 +470,8 @@ impl Display for Stages {
         match self {
             Stages::All => write!(f, "all"),
             Stages::Some(stages) => {
-                let stages_str = stages
-                    .iter()
-                    .map(Stage::as_str)
-                    .collect::<Vec<_>>()
-                    .join(", ");
+                // In declaration order, the set has no order of its own.
+                let stages_str = stages.iter().sorted().map(Stage::as_str).join(", ");
                 write!(f, "{stages_str}")
             }
         }
@@ -431,20 +537,39 @@ pub(crate) struct Hook {
     pub types: Vec<String>,
     pub types_or: Vec<String>,
     pub exclude_types: Vec<String>,
+    pub max_file_size: Option<u64>,
     pub additional_dependencies: FxHashSet<String>,
     pub args: Vec<String>,
     pub env: FxHashMap<String, String>,
     pub always_run: bool,
-    pub fail_fast: bool,
+    pub fail_fast: FailFast,
     pub pass_filenames: bool,
+    pub pass_push_refs: bool,
+    pub success_exit_codes: Vec<i32>,
+    pub fail_on_output: bool,
+    pub retries: u32,
+    pub retry_delay: Duration,
+    /// Operating systems the hook is restricted to, empty for all.
+    pub os: Vec<HookOs>,
+    /// Architectures the hook is restricted to, empty for all.
+    pub arch: Vec<HookArch>,
+    pub kind: Option<HookKind>,
     pub description: Option<String>,
+    /// The `language_version` of the hook as configured, `default` if not set.
+    pub language_version: String,
     pub language_request: LanguageRequest,
     pub log_file: Option<String>,
     pub require_serial: bool,
+    /// The maximum number of files passed to each invocation, unlimited if unset.
+    pub max_files_per_invocation: Option<NonZeroUsize>,
     pub stages: Stages,
     pub verbose: bool,
     pub minimum_prek_version: Option<String>,
+    pub image_digest: Option<String>,
+    pub goflags: Vec<String>,
     pub priority: u32,
+    pub after: Vec<String>,
+    pub before: Vec<String>,
 }
 
 impl Display for Hook {
@@ -489,26 +614,72 @@ impl Hook {
         matches!(&*self.repo, Repo::Remote { .. })
     }
 
+    /// Whether the hook can run on the current operating system and architecture.
+    pub(crate) fn supports_current_platform(&self) -> bool {
+        let os = self.os.is_empty() || HookOs::current().is_some_and(|os| self.os.contains(&os));
+        let arch = self.arch.is_empty()
+            || HookArch::current().is_some_and(|arch| self.arch.contains(&arch));
+        os && arch
+    }
+
+    /// The input git passed to the hook script, if it should be provided on the stdin of this hook.
+    pub(crate) fn stdin_data(&self) -> Option<Arc<[u8]>> {
+        let (stage, data) = crate::run::hook_stdin()?;
+        // Only hooks that ask for the push refs get them, the rest keep a closed stdin.
+        (stage != Stage::PrePush || self.pass_push_refs).then_some(data)
+    }
+
     /// Dependencies used to identify whether an existing hook environment can be reused.
     ///
     /// For remote hooks, the repo URL is included to avoid reusing an environment created
-    /// from a different remote repository.
+    /// from a different remote repository. For local Python hooks with editable requirements,
+    /// a digest of the projects' metadata is included so the environment is rebuilt when
+    /// their dependencies change. Likewise for local Rust hooks installing crates from paths,
+    /// with a digest of the crates' sources. Build flags of Go hooks are included as well, as
+    /// they change the installed binaries.
     pub(crate) fn env_key_dependencies(&self) -> &FxHashSet<String> {
+        let goflags_key =
+            (!self.goflags.is_empty()).then(|| format!("goflags:{}", self.goflags.join(" ")));
         if !self.is_remote() {
-            return &self.additional_dependencies;
+            let key_fn = match self.language {
+                Language::Python => editable_metadata_key,
+                Language::Rust => cargo_path_dependencies_key,
+                _ if goflags_key.is_some() => |_: &FxHashSet<String>, _: &Path| None,
+                _ => return &self.additional_dependencies,
+            };
+            return self.dependencies.get_or_init(|| {
+                let mut deps = self.additional_dependencies.clone();
+                if let Some(key) = key_fn(&self.additional_dependencies, self.work_dir()) {
+                    deps.insert(key);
+                }
+                deps.extend(goflags_key);
+                deps
+            });
         }
         self.dependencies.get_or_init(|| {
             // For remote hooks, itself is an implicit dependency of the hook.
             let mut deps = FxHashSet::with_capacity_and_hasher(
-                self.additional_dependencies.len() + 1,
+                self.additional_dependencies.len() + 2,
                 FxBuildHasher,
             );
             deps.extend(self.additional_dependencies.clone());
             deps.insert(self.repo.to_string());
+            deps.extend(goflags_key);
             deps
         })
     }
 
+    /// The key of the environment of the hook, see [`env_digest`].
+    ///
+    /// Hooks with the same key share an environment, whichever project they are configured in.
+    pub(crate) fn env_key(&self) -> String {
+        env_digest(
+            self.language,
+            &self.language_version,
+            self.env_key_dependencies(),
+        )
+    }
+
     /// Dependencies to pass to language dependency installers.
     ///
     /// For remote hooks, this includes the local path to the cloned repository so that
@@ -551,7 +722,8 @@ impl Display for InstalledHook {
     }
 }
 
-const HOOK_MARKER: &str = ".prek-hook.json";
+/// Written to a hook environment once it is completely installed.
+pub(crate) const HOOK_MARKER: &str = ".prek-hook.json";
 
 impl InstalledHook {
     /// Get the path to the environment where the hook is installed.
@@ -592,6 +764,9 @@ pub(crate) struct InstallInfo {
     pub(crate) language: Language,
     pub(crate) language_version: semver::Version,
     pub(crate) dependencies: FxHashSet<String>,
+    /// The key of the hooks the environment was built for, see [`Hook::env_key`].
+    #[serde(default)]
+    pub(crate) env_key: String,
     pub(crate) env_path: PathBuf,
     pub(crate) toolchain: PathBuf,
     extra: FxHashMap<String, String>,
@@ -605,6 +780,7 @@ impl Clone for InstallInfo {
             language: self.language,
             language_version: self.language_version.clone(),
             dependencies: self.dependencies.clone(),
+            env_key: self.env_key.clone(),
             env_path: self.env_path.clone(),
             toolchain: self.toolchain.clone(),
             extra: self.extra.clone(),
@@ -619,14 +795,26 @@ impl InstallInfo {
         dependencies: FxHashSet<String>,
         hooks_dir: &Path,
     ) -> Result<Self, Error> {
+        let env_key = env_digest(language, "default", &dependencies);
+        Self::with_key(language, dependencies, env_key, hooks_dir)
+    }
+
+    fn with_key(
+        language: Language,
+        dependencies: FxHashSet<String>,
+        env_key: String,
+        hooks_dir: &Path,
+    ) -> Result<Self, Error> {
+        // Keep the directory name short, to leave room for deep `node_modules` and the like.
         let env_path = tempfile::Builder::new()
-            .prefix(&format!("{}-", language.as_str()))
-            .rand_bytes(20)
+            .prefix(&format!("{}-", &env_key[..8]))
+            .rand_bytes(6)
             .tempdir_in(hooks_dir)?;
 
         Ok(Self {
             language,
             dependencies,
+            env_key,
             env_path: env_path.path().to_path_buf(),
             language_version: semver::Version::new(0, 0, 0),
             toolchain: PathBuf::new(),
@@ -635,6 +823,44 @@ impl InstallInfo {
         })
     }
 
+    /// A new environment for `hook` in the store, or in the directory pinned with `--env-dir`.
+    pub(crate) fn for_hook(hook: &Hook, store: &Store) -> Result<Self, Error> {
+        let language = hook.language;
+        let dependencies = hook.env_key_dependencies().clone();
+        let env_key = hook.env_key();
+        let Some(env_dir) = store.env_dir() else {
+            return Self::with_key(language, dependencies, env_key, &store.hooks_dir());
+        };
+
+        Self::check_env_dir(env_dir)?;
+        if env_dir.exists() {
+            fs_err::remove_dir_all(env_dir)?;
+        }
+        fs_err::create_dir_all(env_dir)?;
+
+        Ok(Self {
+            language,
+            dependencies,
+            env_key,
+            env_path: env_dir.to_path_buf(),
+            language_version: semver::Version::new(0, 0, 0),
+            toolchain: PathBuf::new(),
+            extra: FxHashMap::default(),
+            temp_dir: None,
+        })
+    }
+
+    /// Check that a pinned environment directory can be replaced: it is empty or holds an
+    /// environment built there before, never unrelated files.
+    pub(crate) fn check_env_dir(env_dir: &Path) -> Result<(), Error> {
+        if !env_dir.join(HOOK_MARKER).exists()
+            && fs_err::read_dir(env_dir).is_ok_and(|mut entries| entries.next().is_some())
+        {
+            return Err(Error::EnvDirNotEmpty(env_dir.to_path_buf()));
+        }
+        Ok(())
+    }
+
     pub(crate) fn persist_env_path(&mut self) {
         if let Some(temp_dir) = self.temp_dir.take() {
             self.env_path = temp_dir.keep();
@@ -672,6 +898,10 @@ impl InstallInfo {
     }
 
     pub(crate) fn matches(&self, hook: &Hook) -> bool {
+        // Built for the same key, and so for the same `language_version` request.
+        if !self.env_key.is_empty() && self.env_key == hook.env_key() {
+            return true;
+        }
         self.language == hook.language
             && &self.dependencies == hook.env_key_dependencies()
             && hook.language_request.satisfied_by(self)
@@ -0,0 +1,142 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use anyhow::Result;
+use lazy_regex::regex;
+
+use crate::hook::Hook;
+use crate::hooks::pre_commit_hooks::python_tokenizer::{TokenKind, tokenize};
+use crate::hooks::run_concurrent_file_checks;
+use crate::run::CONCURRENCY;
+
+/// A suppression comment that doesn't name the codes it suppresses.
+#[derive(Debug, Copy, Clone)]
+enum Blanket {
+    /// `# noqa` without codes, e.g. instead of `# noqa: F401`.
+    Noqa,
+    /// `# type: ignore` without codes, e.g. instead of `# type: ignore[attr-defined]`.
+    TypeIgnore,
+}
+
+impl Blanket {
+    /// The byte offset of each blanket suppression in a comment.
+    fn find(self, comment: &str) -> Vec<usize> {
+        match self {
+            Self::Noqa => regex!(r"(?i)#\s*noqa\b(?<codes>:\s*[a-z]+[0-9]+)?")
+                .captures_iter(comment)
+                .filter(|captures| captures.name("codes").is_none())
+                .map(|captures| captures.get(0).unwrap().start())
+                .collect(),
+            Self::TypeIgnore => regex!(r"#\s*type:\s*ignore(?<codes>\[|\w)?")
+                .captures_iter(comment)
+                .filter(|captures| captures.name("codes").is_none())
+                .map(|captures| captures.get(0).unwrap().start())
+                .collect(),
+        }
+    }
+
+    fn message(self) -> &'static str {
+        match self {
+            Self::Noqa => "use specific codes with `# noqa`, e.g. `# noqa: F401`",
+            Self::TypeIgnore => {
+                "use specific error codes with `# type: ignore`, e.g. `# type: ignore[attr-defined]`"
+            }
+        }
+    }
+}
+
+pub(crate) async fn check_blanket_noqa(hook: &Hook, filenames: &[&Path]) -> Result<(i32, Vec<u8>)> {
+    run_concurrent_file_checks(filenames.iter().copied(), *CONCURRENCY, |filename| {
+        check_file(hook.project().relative_path(), filename, Blanket::Noqa)
+    })
+    .await
+}
+
+pub(crate) async fn check_blanket_type_ignore(
+    hook: &Hook,
+    filenames: &[&Path],
+) -> Result<(i32, Vec<u8>)> {
+    run_concurrent_file_checks(filenames.iter().copied(), *CONCURRENCY, |filename| {
+        check_file(
+            hook.project().relative_path(),
+            filename,
+            Blanket::TypeIgnore,
+        )
+    })
+    .await
+}
+
+async fn check_file(file_base: &Path, filename: &Path, blanket: Blanket) -> Result<(i32, Vec<u8>)> {
+    let content = fs_err::tokio::read(file_base.join(filename)).await?;
+    let source = String::from_utf8_lossy(&content);
+
+    let mut output = String::new();
+    match find_blanket_comments(&source, blanket) {
+        Ok(locations) => {
+            for (line, col) in &locations {
+                writeln!(
+                    output,
+                    "{}:{line}:{col}: {}",
+                    filename.display(),
+                    blanket.message()
+                )?;
+            }
+            Ok((i32::from(!locations.is_empty()), output.into_bytes()))
+        }
+        Err(err) => {
+            writeln!(output, "{}: Failed to tokenize: {err}", filename.display())?;
+            Ok((1, output.into_bytes()))
+        }
+    }
+}
+
+/// Find the blanket suppressions in the comments of Python source, by line and byte offset in
+/// the line. Strings that look like comments are not reported.
+fn find_blanket_comments(source: &str, blanket: Blanket) -> Result<Vec<(usize, usize)>> {
+    Ok(tokenize(source)?
+        .into_iter()
+        .filter(|token| token.kind == TokenKind::Comment)
+        .flat_map(|token| {
+            blanket
+                .find(token.text)
+                .into_iter()
+                .map(move |offset| (token.line, token.col + offset))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_noqa() {
+        let source = indoc::indoc! {r##"
+            import os  # noqa
+            import sys  # noqa: F401
+            import re  # NOQA:E501,F401
+            x = 1  #noqa
+            y = "# noqa"
+            z = 2  # comment # noqa
+        "##};
+        assert_eq!(
+            find_blanket_comments(source, Blanket::Noqa).unwrap(),
+            [(1, 11), (4, 7), (6, 17)]
+        );
+    }
+
+    #[test]
+    fn test_type_ignore() {
+        let source = indoc::indoc! {r##"
+            a = f()  # type: ignore
+            b = f()  # type: ignore[attr-defined]
+            c = f()  # type:ignore  # noqa
+            d = "# type: ignore"
+            e = f()  # type: ignored
+        "##};
+        assert_eq!(
+            find_blanket_comments(source, Blanket::TypeIgnore).unwrap(),
+            [(1, 9), (3, 9)]
+        );
+    }
+}
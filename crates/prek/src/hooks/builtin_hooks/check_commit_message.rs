@@ -0,0 +1,192 @@
+use std::path::Path;
+
+use anyhow::Result;
+use clap::Parser;
+use lazy_regex::regex;
+
+use crate::hook::Hook;
+
+const DEFAULT_TYPES: &[&str] = &[
+    "build", "chore", "ci", "docs", "feat", "fix", "perf", "refactor", "revert", "style", "test",
+];
+
+#[derive(Parser)]
+#[command(disable_help_subcommand = true)]
+#[command(disable_version_flag = true)]
+#[command(disable_help_flag = true)]
+struct Args {
+    /// Allowed commit types.
+    #[arg(long = "types", value_delimiter = ',', default_values = DEFAULT_TYPES)]
+    types: Vec<String>,
+    /// Allowed scopes. Any scope is allowed if empty.
+    #[arg(long = "scopes", value_delimiter = ',')]
+    scopes: Vec<String>,
+    /// Require a scope in the header.
+    #[arg(long)]
+    require_scope: bool,
+    /// Maximum length of the header line, `0` to disable the check.
+    #[arg(long, default_value_t = 100)]
+    max_header_length: usize,
+}
+
+pub(crate) async fn check_commit_message(
+    hook: &Hook,
+    filenames: &[&Path],
+) -> Result<(i32, Vec<u8>)> {
+    let args = Args::try_parse_from(hook.entry.resolve(None)?.iter().chain(&hook.args))?;
+
+    let mut code = 0;
+    let mut output = Vec::new();
+    for filename in filenames {
+        let content =
+            fs_err::tokio::read_to_string(hook.project().relative_path().join(filename)).await?;
+        if let Err(err) = args.check(&content) {
+            code = 1;
+            output.extend(format!("{}: {err}\n", filename.display()).into_bytes());
+        }
+    }
+
+    Ok((code, output))
+}
+
+impl Args {
+    fn check(&self, message: &str) -> Result<(), String> {
+        let lines = message_lines(message);
+        let Some(header) = lines.first() else {
+            // Git aborts the commit on an empty message by itself.
+            return Ok(());
+        };
+
+        if is_exempt(header) {
+            return Ok(());
+        }
+
+        if self.max_header_length > 0 && header.chars().count() > self.max_header_length {
+            return Err(format!(
+                "commit message header is longer than {} characters",
+                self.max_header_length
+            ));
+        }
+
+        let Some(captures) =
+            regex!(r"^(?<type>[^\s(!:]+)(?:\((?<scope>[^()]*)\))?(?<breaking>!)?: (?<subject>.*)$")
+                .captures(header)
+        else {
+            return Err(format!(
+                "commit message header `{header}` does not match `<type>(<scope>): <subject>`"
+            ));
+        };
+
+        let kind = &captures["type"];
+        if !self.types.iter().any(|t| t == kind) {
+            return Err(format!(
+                "commit type `{kind}` is not one of: {}",
+                self.types.join(", ")
+            ));
+        }
+
+        match captures.name("scope").map(|m| m.as_str().trim()) {
+            Some("") => return Err("commit scope is empty".to_string()),
+            Some(scope) if !self.scopes.is_empty() && !self.scopes.iter().any(|s| s == scope) => {
+                return Err(format!(
+                    "commit scope `{scope}` is not one of: {}",
+                    self.scopes.join(", ")
+                ));
+            }
+            None if self.require_scope => {
+                return Err("commit scope is required".to_string());
+            }
+            _ => {}
+        }
+
+        if captures["subject"].trim().is_empty() {
+            return Err("commit subject is empty".to_string());
+        }
+
+        if lines.get(1).is_some_and(|line| !line.trim().is_empty()) {
+            return Err(
+                "commit message body must be separated from the header by a blank line".to_string(),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// The lines of the message as git would record it: comments and everything below the
+/// scissors line (`git commit --verbose`) removed, leading blank lines skipped.
+fn message_lines(message: &str) -> Vec<&str> {
+    message
+        .lines()
+        .take_while(|line| {
+            !line.starts_with("# ------------------------ >8 ------------------------")
+        })
+        .filter(|line| !line.starts_with('#'))
+        .skip_while(|line| line.trim().is_empty())
+        .collect()
+}
+
+/// Messages generated by git itself, or meant to be squashed later.
+fn is_exempt(header: &str) -> bool {
+    ["Merge ", "Revert ", "fixup! ", "squash! ", "amend! "]
+        .iter()
+        .any(|prefix| header.starts_with(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(extra: &[&str]) -> Args {
+        Args::try_parse_from(["check-commit-message"].iter().chain(extra)).unwrap()
+    }
+
+    #[test]
+    fn valid_messages() {
+        let args = args(&[]);
+        assert!(args.check("feat: add a thing\n").is_ok());
+        assert!(
+            args.check("fix(parser)!: handle empty input\n\nDetails.\n")
+                .is_ok()
+        );
+        assert!(
+            args.check("# comment\n\ndocs: update readme\n# another\n")
+                .is_ok()
+        );
+        assert!(args.check("Merge branch 'main' into feature\n").is_ok());
+        assert!(args.check("fixup! feat: add a thing\n").is_ok());
+        assert!(args.check("\n# only comments\n").is_ok());
+        assert!(
+            args.check(
+                "chore: bump\n# ------------------------ >8 ------------------------\ndiff\n"
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn invalid_messages() {
+        let args = args(&[]);
+        assert!(args.check("add a thing\n").is_err());
+        assert!(args.check("feature: add a thing\n").is_err());
+        assert!(args.check("feat(): add a thing\n").is_err());
+        assert!(args.check("feat: \n").is_err());
+        assert!(args.check("feat: add a thing\nbody\n").is_err());
+        assert!(args.check(&format!("feat: {}\n", "a".repeat(100))).is_err());
+    }
+
+    #[test]
+    fn configured_types_and_scopes() {
+        let args = args(&[
+            "--types",
+            "feat,fix",
+            "--scopes=cli",
+            "--scopes=docs",
+            "--require-scope",
+        ]);
+        assert!(args.check("feat(cli): add a flag\n").is_ok());
+        assert!(args.check("chore(cli): bump\n").is_err());
+        assert!(args.check("fix(core): handle it\n").is_err());
+        assert!(args.check("fix: handle it\n").is_err());
+    }
+}
@@ -0,0 +1,228 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use rustc_hash::FxHashMap;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tracing::debug;
+use url::Url;
+
+use crate::hook::Hook;
+use crate::hooks::builtin_hooks::json_schema::{Validator, document_key, external_refs};
+use crate::hooks::run_concurrent_file_checks;
+use crate::languages::REQWEST_CLIENT;
+use crate::run::CONCURRENCY;
+use crate::store::{CacheBucket, Store};
+use crate::{offline, retry};
+
+/// Remote schemas are downloaded again once their cached copy is older than this.
+const CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Parser)]
+#[command(disable_help_subcommand = true)]
+#[command(disable_version_flag = true)]
+#[command(disable_help_flag = true)]
+struct Args {
+    /// The schema to validate against, a path relative to the project or an `http(s)` URL.
+    #[arg(long)]
+    schemafile: String,
+    /// Download remote schemas again instead of using their cached copy.
+    #[arg(long)]
+    no_cache: bool,
+}
+
+pub(crate) async fn check_jsonschema(
+    store: &Store,
+    hook: &Hook,
+    filenames: &[&Path],
+) -> Result<(i32, Vec<u8>)> {
+    let args = Args::try_parse_from(hook.entry.resolve(None)?.iter().chain(&hook.args))?;
+    let file_base = hook.project().relative_path();
+
+    let loader = SchemaLoader {
+        cache_dir: store.cache_path(CacheBucket::Prek).join("schemas"),
+        no_cache: args.no_cache,
+    };
+    let validator = loader.load(&args.schemafile, file_base).await?;
+
+    run_concurrent_file_checks(filenames.iter().copied(), *CONCURRENCY, |filename| {
+        check_file(&validator, file_base, filename)
+    })
+    .await
+}
+
+async fn check_file(
+    validator: &Validator,
+    file_base: &Path,
+    filename: &Path,
+) -> Result<(i32, Vec<u8>)> {
+    let content = fs_err::tokio::read_to_string(file_base.join(filename)).await?;
+    let instance = match parse_instance(filename, &content) {
+        Ok(instance) => instance,
+        Err(err) => {
+            let message = format!("{}: Failed to parse ({err})\n", filename.display());
+            return Ok((1, message.into_bytes()));
+        }
+    };
+
+    let errors = validator.validate(&instance);
+    let mut output = Vec::new();
+    for error in &errors {
+        output.extend(
+            format!(
+                "{}::{}: {}\n",
+                filename.display(),
+                error.path,
+                error.message
+            )
+            .into_bytes(),
+        );
+    }
+    Ok((i32::from(!errors.is_empty()), output))
+}
+
+/// Parse a JSON, JSON5, TOML or YAML file by its extension, as YAML if it has another one.
+fn parse_instance(filename: &Path, content: &str) -> Result<Value> {
+    let extension = filename
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    let value = match extension.as_deref() {
+        Some("json") => serde_json::from_str(content.trim_start_matches('\u{feff}'))?,
+        Some("json5") => json5::from_str(content)?,
+        Some("toml") => toml_to_json(toml::from_str(content)?),
+        _ => serde_yaml::from_str(content)?,
+    };
+    Ok(value)
+}
+
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(string) => Value::String(string),
+        toml::Value::Integer(integer) => Value::from(integer),
+        toml::Value::Float(float) => Value::from(float),
+        toml::Value::Boolean(boolean) => Value::Bool(boolean),
+        toml::Value::Datetime(datetime) => Value::String(datetime.to_string()),
+        toml::Value::Array(array) => Value::Array(array.into_iter().map(toml_to_json).collect()),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(key, value)| (key, toml_to_json(value)))
+                .collect(),
+        ),
+    }
+}
+
+struct SchemaLoader {
+    cache_dir: PathBuf,
+    no_cache: bool,
+}
+
+impl SchemaLoader {
+    /// Load the schema at `location` and every document it references.
+    async fn load(&self, location: &str, file_base: &Path) -> Result<Validator> {
+        let root = match Url::parse(location) {
+            Ok(url) if matches!(url.scheme(), "http" | "https" | "file") => url,
+            _ => {
+                let path = std::path::absolute(file_base.join(location))?;
+                Url::from_file_path(&path)
+                    .map_err(|()| anyhow::anyhow!("Invalid schema path `{location}`"))?
+            }
+        };
+
+        let mut documents = FxHashMap::default();
+        let mut pending = vec![root.clone()];
+        while let Some(uri) = pending.pop() {
+            let key = document_key(&uri);
+            if documents.contains_key(&key) {
+                continue;
+            }
+            let document = self
+                .load_document(&uri)
+                .await
+                .with_context(|| format!("Failed to load schema `{uri}`"))?;
+            pending.extend(external_refs(&document, &uri));
+            documents.insert(key, document);
+        }
+
+        Ok(Validator::new(root, documents))
+    }
+
+    async fn load_document(&self, uri: &Url) -> Result<Value> {
+        let content = match uri.scheme() {
+            "file" => {
+                let path = uri
+                    .to_file_path()
+                    .map_err(|()| anyhow::anyhow!("Invalid schema path `{uri}`"))?;
+                fs_err::tokio::read_to_string(path).await?
+            }
+            _ => self.fetch(uri).await?,
+        };
+        // YAML is a superset of JSON, for schemas written in YAML.
+        match serde_json::from_str(&content) {
+            Ok(document) => Ok(document),
+            Err(_) => Ok(serde_yaml::from_str(&content)?),
+        }
+    }
+
+    /// Download a remote schema, or read it from the cache if it was downloaded recently.
+    async fn fetch(&self, url: &Url) -> Result<String> {
+        let digest = hex::encode(Sha256::digest(url.as_str().as_bytes()));
+        let cached = self.cache_dir.join(format!("{}.json", &digest[..16]));
+
+        let age = fs_err::metadata(&cached)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .map(|modified| modified.elapsed().unwrap_or_default());
+        if let Some(age) = age
+            && ((age < CACHE_TTL && !self.no_cache) || offline::is_offline())
+        {
+            debug!(%url, "Using cached schema");
+            return Ok(fs_err::tokio::read_to_string(&cached).await?);
+        }
+
+        offline::ensure_online(|| format!("Downloading schema `{url}`"))?;
+        let content = retry::with_retries(
+            &format!("Download of `{url}`"),
+            retry::is_transient_http_error,
+            async || {
+                let response = REQWEST_CLIENT
+                    .get(url.as_str())
+                    .send()
+                    .await
+                    .and_then(reqwest::Response::error_for_status)
+                    .with_context(|| format!("Failed to download schema from {url}"))?;
+                response
+                    .text()
+                    .await
+                    .with_context(|| format!("Failed to download schema from {url}"))
+            },
+        )
+        .await?;
+
+        fs_err::tokio::create_dir_all(&self.cache_dir).await?;
+        fs_err::tokio::write(&cached, &content).await?;
+        Ok(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_instances() -> Result<()> {
+        let toml = parse_instance(Path::new("a.toml"), "date = 2024-01-01\n[tool]\nx = 1\n")?;
+        assert_eq!(
+            toml,
+            serde_json::json!({"date": "2024-01-01", "tool": {"x": 1}})
+        );
+        let yaml = parse_instance(Path::new(".github/workflows/ci.yml"), "on: push\n")?;
+        assert_eq!(yaml, serde_json::json!({"on": "push"}));
+        let json = parse_instance(Path::new("renovate.json"), "\u{feff}{\"a\": [1]}")?;
+        assert_eq!(json, serde_json::json!({"a": [1]}));
+        Ok(())
+    }
+}
@@ -0,0 +1,865 @@
+//! A validator for the commonly used keywords of JSON Schema, drafts 4 to 2020-12.
+//!
+//! `format` is an annotation only, and `unevaluatedProperties`, `unevaluatedItems` and
+//! `$dynamicRef` are not supported.
+
+use std::fmt::Write;
+use std::sync::Mutex;
+
+use fancy_regex::Regex;
+use itertools::Itertools;
+use rustc_hash::FxHashMap;
+use serde_json::{Map, Value};
+use url::Url;
+
+/// Stop following `$ref` after this depth, e.g. for a schema that references itself.
+const MAX_DEPTH: usize = 256;
+
+#[derive(Debug)]
+pub(crate) struct ValidationError {
+    /// The location of the invalid value, like `$.jobs.build.steps[0]`.
+    pub(crate) path: String,
+    pub(crate) message: String,
+}
+
+pub(crate) struct Validator {
+    root: Url,
+    /// Schema documents and the subschemas with an `$id`, by URI without fragment.
+    documents: FxHashMap<String, Value>,
+    /// Whether `$ref` overrides its sibling keywords, as in drafts 4 to 7.
+    legacy_ref: bool,
+    regexes: Mutex<FxHashMap<String, Option<Regex>>>,
+}
+
+impl Validator {
+    /// Create a validator for the schema at `root`, with `documents` holding the schema and
+    /// every document it references.
+    pub(crate) fn new(root: Url, documents: FxHashMap<String, Value>) -> Self {
+        let mut indexed = FxHashMap::default();
+        for (uri, document) in &documents {
+            if let Ok(base) = Url::parse(uri) {
+                index_ids(document, &base, &mut indexed);
+            }
+        }
+        let mut documents = documents;
+        for (uri, schema) in indexed {
+            documents.entry(uri).or_insert(schema);
+        }
+
+        let legacy_ref = documents
+            .get(&document_key(&root))
+            .and_then(|schema| schema.get("$schema"))
+            .and_then(Value::as_str)
+            .is_some_and(|dialect| {
+                ["draft-03", "draft-04", "draft-06", "draft-07"]
+                    .iter()
+                    .any(|draft| dialect.contains(draft))
+            });
+
+        Self {
+            root,
+            documents,
+            legacy_ref,
+            regexes: Mutex::new(FxHashMap::default()),
+        }
+    }
+
+    /// Validate `instance` against the root schema.
+    pub(crate) fn validate(&self, instance: &Value) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        if let Some(schema) = self.documents.get(&document_key(&self.root)) {
+            let mut path = Vec::new();
+            self.validate_schema(schema, &self.root, instance, &mut path, &mut errors, 0);
+        }
+        errors
+    }
+
+    fn is_valid(&self, schema: &Value, base: &Url, instance: &Value, depth: usize) -> bool {
+        let mut errors = Vec::new();
+        self.validate_schema(schema, base, instance, &mut Vec::new(), &mut errors, depth);
+        errors.is_empty()
+    }
+
+    fn validate_schema(
+        &self,
+        schema: &Value,
+        base: &Url,
+        instance: &Value,
+        path: &mut Vec<PathSegment>,
+        errors: &mut Vec<ValidationError>,
+        depth: usize,
+    ) {
+        let schema = match schema {
+            Value::Bool(true) => return,
+            Value::Bool(false) => {
+                errors.push(error(path, format!("{} is not allowed", repr(instance))));
+                return;
+            }
+            Value::Object(schema) => schema,
+            _ => return,
+        };
+        if depth > MAX_DEPTH {
+            errors.push(error(
+                path,
+                "Schema references are nested too deeply".to_string(),
+            ));
+            return;
+        }
+
+        let base = match schema.get("$id").or_else(|| schema.get("id")) {
+            Some(Value::String(id)) if !id.starts_with('#') => {
+                base.join(id).unwrap_or_else(|_| base.clone())
+            }
+            _ => base.clone(),
+        };
+
+        if let Some(Value::String(reference)) = schema.get("$ref") {
+            match self.resolve(&base, reference) {
+                Some((target, target_base)) => {
+                    self.validate_schema(target, &target_base, instance, path, errors, depth + 1);
+                }
+                None => errors.push(error(
+                    path,
+                    format!("Unresolvable schema reference `{reference}`"),
+                )),
+            }
+            if self.legacy_ref {
+                return;
+            }
+        }
+
+        self.validate_keywords(schema, &base, instance, path, errors, depth);
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn validate_keywords(
+        &self,
+        schema: &Map<String, Value>,
+        base: &Url,
+        instance: &Value,
+        path: &mut Vec<PathSegment>,
+        errors: &mut Vec<ValidationError>,
+        depth: usize,
+    ) {
+        if let Some(types) = schema.get("type") {
+            let matches = match types {
+                Value::String(kind) => is_type(instance, kind),
+                Value::Array(kinds) => kinds
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .any(|kind| is_type(instance, kind)),
+                _ => true,
+            };
+            if !matches {
+                let expected = match types {
+                    Value::Array(kinds) => repr(&Value::Array(kinds.clone())),
+                    kind => repr(kind),
+                };
+                errors.push(error(
+                    path,
+                    format!("{} is not of type {expected}", repr(instance)),
+                ));
+            }
+        }
+
+        if let Some(Value::Array(values)) = schema.get("enum")
+            && !values.iter().any(|value| json_eq(value, instance))
+        {
+            errors.push(error(
+                path,
+                format!(
+                    "{} is not one of {}",
+                    repr(instance),
+                    repr(&Value::Array(values.clone()))
+                ),
+            ));
+        }
+        if let Some(value) = schema.get("const")
+            && !json_eq(value, instance)
+        {
+            errors.push(error(path, format!("{} was expected", repr(value))));
+        }
+
+        if let Some(Value::Array(subschemas)) = schema.get("allOf") {
+            for subschema in subschemas {
+                self.validate_schema(subschema, base, instance, path, errors, depth + 1);
+            }
+        }
+        if let Some(Value::Array(subschemas)) = schema.get("anyOf")
+            && !subschemas
+                .iter()
+                .any(|subschema| self.is_valid(subschema, base, instance, depth + 1))
+        {
+            errors.push(error(
+                path,
+                format!(
+                    "{} is not valid under any of the given schemas",
+                    repr(instance)
+                ),
+            ));
+        }
+        if let Some(Value::Array(subschemas)) = schema.get("oneOf") {
+            let valid = subschemas
+                .iter()
+                .filter(|subschema| self.is_valid(subschema, base, instance, depth + 1))
+                .count();
+            if valid == 0 {
+                errors.push(error(
+                    path,
+                    format!(
+                        "{} is not valid under any of the given schemas",
+                        repr(instance)
+                    ),
+                ));
+            } else if valid > 1 {
+                errors.push(error(
+                    path,
+                    format!("{} is valid under each of {valid} schemas", repr(instance)),
+                ));
+            }
+        }
+        if let Some(subschema) = schema.get("not")
+            && self.is_valid(subschema, base, instance, depth + 1)
+        {
+            errors.push(error(
+                path,
+                format!(
+                    "{} should not be valid under {}",
+                    repr(instance),
+                    repr(subschema)
+                ),
+            ));
+        }
+        if let Some(condition) = schema.get("if") {
+            let branch = if self.is_valid(condition, base, instance, depth + 1) {
+                schema.get("then")
+            } else {
+                schema.get("else")
+            };
+            if let Some(branch) = branch {
+                self.validate_schema(branch, base, instance, path, errors, depth + 1);
+            }
+        }
+
+        match instance {
+            Value::Object(object) => {
+                self.validate_object(schema, base, object, path, errors, depth);
+            }
+            Value::Array(array) => self.validate_array(schema, base, array, path, errors, depth),
+            Value::String(string) => self.validate_string(schema, string, path, errors),
+            Value::Number(_) => validate_number(schema, instance, path, errors),
+            _ => {}
+        }
+    }
+
+    fn validate_object(
+        &self,
+        schema: &Map<String, Value>,
+        base: &Url,
+        object: &Map<String, Value>,
+        path: &mut Vec<PathSegment>,
+        errors: &mut Vec<ValidationError>,
+        depth: usize,
+    ) {
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for key in required.iter().filter_map(Value::as_str) {
+                if !object.contains_key(key) {
+                    errors.push(error(path, format!("'{key}' is a required property")));
+                }
+            }
+        }
+        if let Some(min) = schema.get("minProperties").and_then(Value::as_u64)
+            && (object.len() as u64) < min
+        {
+            errors.push(error(
+                path,
+                format!("{} does not have enough properties", repr_object(object)),
+            ));
+        }
+        if let Some(max) = schema.get("maxProperties").and_then(Value::as_u64)
+            && (object.len() as u64) > max
+        {
+            errors.push(error(
+                path,
+                format!("{} has too many properties", repr_object(object)),
+            ));
+        }
+
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let pattern_properties = schema.get("patternProperties").and_then(Value::as_object);
+        let additional = schema.get("additionalProperties");
+        let mut unexpected = Vec::new();
+        for (key, value) in object {
+            let mut matched = false;
+            if let Some(subschema) = properties.and_then(|properties| properties.get(key)) {
+                matched = true;
+                path.push(PathSegment::Key(key.clone()));
+                self.validate_schema(subschema, base, value, path, errors, depth + 1);
+                path.pop();
+            }
+            for (pattern, subschema) in pattern_properties.into_iter().flatten() {
+                if self.is_match(pattern, key) {
+                    matched = true;
+                    path.push(PathSegment::Key(key.clone()));
+                    self.validate_schema(subschema, base, value, path, errors, depth + 1);
+                    path.pop();
+                }
+            }
+            if matched {
+                continue;
+            }
+            match additional {
+                Some(Value::Bool(false)) => unexpected.push(key.as_str()),
+                Some(subschema @ Value::Object(_)) => {
+                    path.push(PathSegment::Key(key.clone()));
+                    self.validate_schema(subschema, base, value, path, errors, depth + 1);
+                    path.pop();
+                }
+                _ => {}
+            }
+        }
+        if !unexpected.is_empty() {
+            let list = unexpected.iter().map(|key| format!("'{key}'")).join(", ");
+            let verb = if unexpected.len() == 1 { "was" } else { "were" };
+            errors.push(error(
+                path,
+                format!("Additional properties are not allowed ({list} {verb} unexpected)"),
+            ));
+        }
+
+        if let Some(names) = schema.get("propertyNames") {
+            for key in object.keys() {
+                if !self.is_valid(names, base, &Value::String(key.clone()), depth + 1) {
+                    errors.push(error(path, format!("'{key}' is not a valid property name")));
+                }
+            }
+        }
+
+        // `dependencies` was split into `dependentRequired` and `dependentSchemas` in 2019-09.
+        for keyword in ["dependencies", "dependentRequired", "dependentSchemas"] {
+            let Some(Value::Object(dependencies)) = schema.get(keyword) else {
+                continue;
+            };
+            for (key, dependency) in dependencies {
+                if !object.contains_key(key) {
+                    continue;
+                }
+                match dependency {
+                    Value::Array(required) => {
+                        for dependent in required.iter().filter_map(Value::as_str) {
+                            if !object.contains_key(dependent) {
+                                errors.push(error(
+                                    path,
+                                    format!("'{dependent}' is a dependency of '{key}'"),
+                                ));
+                            }
+                        }
+                    }
+                    subschema => {
+                        let instance = Value::Object(object.clone());
+                        self.validate_schema(subschema, base, &instance, path, errors, depth + 1);
+                    }
+                }
+            }
+        }
+    }
+
+    fn validate_array(
+        &self,
+        schema: &Map<String, Value>,
+        base: &Url,
+        array: &[Value],
+        path: &mut Vec<PathSegment>,
+        errors: &mut Vec<ValidationError>,
+        depth: usize,
+    ) {
+        if let Some(min) = schema.get("minItems").and_then(Value::as_u64)
+            && (array.len() as u64) < min
+        {
+            errors.push(error(path, format!("{} is too short", repr_array(array))));
+        }
+        if let Some(max) = schema.get("maxItems").and_then(Value::as_u64)
+            && (array.len() as u64) > max
+        {
+            errors.push(error(path, format!("{} is too long", repr_array(array))));
+        }
+        if schema.get("uniqueItems") == Some(&Value::Bool(true)) {
+            let duplicate = array
+                .iter()
+                .enumerate()
+                .any(|(idx, item)| array[idx + 1..].iter().any(|other| json_eq(item, other)));
+            if duplicate {
+                errors.push(error(
+                    path,
+                    format!("{} has non-unique elements", repr_array(array)),
+                ));
+            }
+        }
+
+        // Before 2020-12, an array of schemas in `items` validates the items by position.
+        let (prefix, rest) = match (schema.get("prefixItems"), schema.get("items")) {
+            (Some(Value::Array(prefix)), items) => (prefix.as_slice(), items),
+            (_, Some(Value::Array(prefix))) => (prefix.as_slice(), schema.get("additionalItems")),
+            (_, items) => (&[][..], items),
+        };
+        for (idx, item) in array.iter().enumerate() {
+            let subschema = match prefix.get(idx) {
+                Some(subschema) => subschema,
+                None => match rest {
+                    Some(subschema) => subschema,
+                    None => break,
+                },
+            };
+            path.push(PathSegment::Index(idx));
+            self.validate_schema(subschema, base, item, path, errors, depth + 1);
+            path.pop();
+        }
+
+        if let Some(contains) = schema.get("contains") {
+            let count = array
+                .iter()
+                .filter(|item| self.is_valid(contains, base, item, depth + 1))
+                .count() as u64;
+            let min = schema
+                .get("minContains")
+                .and_then(Value::as_u64)
+                .unwrap_or(1);
+            let max = schema.get("maxContains").and_then(Value::as_u64);
+            if count < min {
+                errors.push(error(
+                    path,
+                    format!(
+                        "{} does not contain enough items matching {}",
+                        repr_array(array),
+                        repr(contains)
+                    ),
+                ));
+            } else if max.is_some_and(|max| count > max) {
+                errors.push(error(
+                    path,
+                    format!(
+                        "{} contains too many items matching {}",
+                        repr_array(array),
+                        repr(contains)
+                    ),
+                ));
+            }
+        }
+    }
+
+    fn validate_string(
+        &self,
+        schema: &Map<String, Value>,
+        string: &str,
+        path: &[PathSegment],
+        errors: &mut Vec<ValidationError>,
+    ) {
+        let length = string.chars().count() as u64;
+        if let Some(min) = schema.get("minLength").and_then(Value::as_u64)
+            && length < min
+        {
+            errors.push(error(path, format!("'{string}' is too short")));
+        }
+        if let Some(max) = schema.get("maxLength").and_then(Value::as_u64)
+            && length > max
+        {
+            errors.push(error(path, format!("'{string}' is too long")));
+        }
+        if let Some(Value::String(pattern)) = schema.get("pattern")
+            && !self.is_match(pattern, string)
+        {
+            errors.push(error(
+                path,
+                format!("'{string}' does not match '{pattern}'"),
+            ));
+        }
+    }
+
+    /// Whether `value` matches the ECMA-262 regex `pattern`. Invalid patterns match everything.
+    fn is_match(&self, pattern: &str, value: &str) -> bool {
+        let regex = {
+            let mut regexes = self.regexes.lock().expect("regex cache lock poisoned");
+            regexes
+                .entry(pattern.to_string())
+                .or_insert_with(|| Regex::new(pattern).ok())
+                .clone()
+        };
+        regex.is_none_or(|regex| regex.is_match(value).unwrap_or(true))
+    }
+
+    /// Resolve `reference` against `base`, returning the target schema and its base URI.
+    fn resolve(&self, base: &Url, reference: &str) -> Option<(&Value, Url)> {
+        let target = base.join(reference).ok()?;
+        let document = self.documents.get(&document_key(&target))?;
+        let mut document_base = target.clone();
+        document_base.set_fragment(None);
+
+        let fragment = target.fragment().unwrap_or_default();
+        let fragment = percent_decode(fragment);
+        if fragment.is_empty() {
+            return Some((document, document_base));
+        }
+        if let Some(pointer) = fragment.strip_prefix('/') {
+            let mut value = document;
+            for token in pointer.split('/') {
+                let token = token.replace("~1", "/").replace("~0", "~");
+                value = match value {
+                    Value::Object(object) => object.get(&token)?,
+                    Value::Array(array) => array.get(token.parse::<usize>().ok()?)?,
+                    _ => return None,
+                };
+            }
+            return Some((value, document_base));
+        }
+        find_anchor(document, &fragment).map(|schema| (schema, document_base))
+    }
+}
+
+#[derive(Debug, Clone)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+fn error(path: &[PathSegment], message: String) -> ValidationError {
+    let mut rendered = "$".to_string();
+    for segment in path {
+        match segment {
+            PathSegment::Key(key)
+                if !key.is_empty()
+                    && key
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') =>
+            {
+                let _ = write!(rendered, ".{key}");
+            }
+            PathSegment::Key(key) => {
+                let _ = write!(rendered, "[{}]", Value::String(key.clone()));
+            }
+            PathSegment::Index(idx) => {
+                let _ = write!(rendered, "[{idx}]");
+            }
+        }
+    }
+    ValidationError {
+        path: rendered,
+        message,
+    }
+}
+
+fn validate_number(
+    schema: &Map<String, Value>,
+    instance: &Value,
+    path: &[PathSegment],
+    errors: &mut Vec<ValidationError>,
+) {
+    let Some(number) = instance.as_f64() else {
+        return;
+    };
+    let bound = |keyword: &str| schema.get(keyword).and_then(Value::as_f64);
+    // In draft 4, `exclusiveMinimum` and `exclusiveMaximum` are booleans modifying the bounds.
+    let exclusive = |keyword: &str| schema.get(keyword) == Some(&Value::Bool(true));
+
+    if let Some(minimum) = bound("minimum") {
+        if exclusive("exclusiveMinimum") && number <= minimum {
+            errors.push(error(
+                path,
+                format!(
+                    "{} is less than or equal to the minimum of {minimum}",
+                    repr(instance)
+                ),
+            ));
+        } else if number < minimum {
+            errors.push(error(
+                path,
+                format!("{} is less than the minimum of {minimum}", repr(instance)),
+            ));
+        }
+    }
+    if let Some(maximum) = bound("maximum") {
+        if exclusive("exclusiveMaximum") && number >= maximum {
+            errors.push(error(
+                path,
+                format!(
+                    "{} is greater than or equal to the maximum of {maximum}",
+                    repr(instance)
+                ),
+            ));
+        } else if number > maximum {
+            errors.push(error(
+                path,
+                format!(
+                    "{} is greater than the maximum of {maximum}",
+                    repr(instance)
+                ),
+            ));
+        }
+    }
+    if let Some(minimum) = bound("exclusiveMinimum")
+        && number <= minimum
+    {
+        errors.push(error(
+            path,
+            format!(
+                "{} is less than or equal to the minimum of {minimum}",
+                repr(instance)
+            ),
+        ));
+    }
+    if let Some(maximum) = bound("exclusiveMaximum")
+        && number >= maximum
+    {
+        errors.push(error(
+            path,
+            format!(
+                "{} is greater than or equal to the maximum of {maximum}",
+                repr(instance)
+            ),
+        ));
+    }
+    if let Some(divisor) = bound("multipleOf")
+        && divisor > 0.0
+    {
+        let quotient = number / divisor;
+        if (quotient - quotient.round()).abs() > 1e-9 {
+            errors.push(error(
+                path,
+                format!("{} is not a multiple of {divisor}", repr(instance)),
+            ));
+        }
+    }
+}
+
+fn is_type(instance: &Value, kind: &str) -> bool {
+    match kind {
+        "null" => instance.is_null(),
+        "boolean" => instance.is_boolean(),
+        "object" => instance.is_object(),
+        "array" => instance.is_array(),
+        "string" => instance.is_string(),
+        "number" => instance.is_number(),
+        "integer" => match instance {
+            Value::Number(number) => {
+                number.is_i64()
+                    || number.is_u64()
+                    || number.as_f64().is_some_and(|float| float.fract() == 0.0)
+            }
+            _ => false,
+        },
+        _ => true,
+    }
+}
+
+/// JSON equality, where numbers are equal if their values are, like `1` and `1.0`.
+fn json_eq(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => match (a.as_i64(), b.as_i64()) {
+            (Some(a), Some(b)) => a == b,
+            _ => a.as_f64() == b.as_f64(),
+        },
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| json_eq(a, b))
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(key, a)| b.get(key).is_some_and(|b| json_eq(a, b)))
+        }
+        (a, b) => a == b,
+    }
+}
+
+/// Render a value for error messages, strings in single quotes, and long values truncated.
+fn repr(value: &Value) -> String {
+    let rendered = match value {
+        Value::String(string) => format!("'{string}'"),
+        value => value.to_string(),
+    };
+    if rendered.chars().count() > 80 {
+        format!("{}...", rendered.chars().take(77).collect::<String>())
+    } else {
+        rendered
+    }
+}
+
+fn repr_object(object: &Map<String, Value>) -> String {
+    repr(&Value::Object(object.clone()))
+}
+
+fn repr_array(array: &[Value]) -> String {
+    repr(&Value::Array(array.to_vec()))
+}
+
+/// The key of the document `uri` belongs to, the URI without its fragment.
+pub(crate) fn document_key(uri: &Url) -> String {
+    let mut uri = uri.clone();
+    uri.set_fragment(None);
+    uri.to_string()
+}
+
+/// The documents referenced by `schema` with `$ref`, other than itself, resolved against `base`.
+pub(crate) fn external_refs(schema: &Value, base: &Url) -> Vec<Url> {
+    let mut refs = Vec::new();
+    collect_refs(schema, base, &mut refs);
+    refs.retain(|uri| document_key(uri) != document_key(base));
+    refs
+}
+
+fn collect_refs(value: &Value, base: &Url, refs: &mut Vec<Url>) {
+    match value {
+        Value::Object(object) => {
+            let base = match object.get("$id").or_else(|| object.get("id")) {
+                Some(Value::String(id)) if !id.starts_with('#') => {
+                    base.join(id).unwrap_or_else(|_| base.clone())
+                }
+                _ => base.clone(),
+            };
+            if let Some(Value::String(reference)) = object.get("$ref")
+                && !reference.starts_with('#')
+                && let Ok(mut target) = base.join(reference)
+            {
+                target.set_fragment(None);
+                refs.push(target);
+            }
+            for value in object.values() {
+                collect_refs(value, &base, refs);
+            }
+        }
+        Value::Array(array) => {
+            for value in array {
+                collect_refs(value, base, refs);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Index the subschemas with an `$id` by their URI, so references to them resolve.
+fn index_ids(value: &Value, base: &Url, indexed: &mut FxHashMap<String, Value>) {
+    match value {
+        Value::Object(object) => {
+            let base = match object.get("$id") {
+                Some(Value::String(id)) if !id.starts_with('#') => match base.join(id) {
+                    Ok(uri) => {
+                        indexed.insert(document_key(&uri), value.clone());
+                        uri
+                    }
+                    Err(_) => base.clone(),
+                },
+                _ => base.clone(),
+            };
+            for value in object.values() {
+                index_ids(value, &base, indexed);
+            }
+        }
+        Value::Array(array) => {
+            for value in array {
+                index_ids(value, base, indexed);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Find the subschema with `$anchor: <anchor>`, or `$id: #<anchor>` before 2019-09.
+fn find_anchor<'a>(value: &'a Value, anchor: &str) -> Option<&'a Value> {
+    match value {
+        Value::Object(object) => {
+            let matches = object.get("$anchor").and_then(Value::as_str) == Some(anchor)
+                || object
+                    .get("$id")
+                    .or_else(|| object.get("id"))
+                    .and_then(Value::as_str)
+                    .and_then(|id| id.strip_prefix('#'))
+                    == Some(anchor);
+            if matches {
+                return Some(value);
+            }
+            object.values().find_map(|value| find_anchor(value, anchor))
+        }
+        Value::Array(array) => array.iter().find_map(|value| find_anchor(value, anchor)),
+        _ => None,
+    }
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if bytes[idx] == b'%'
+            && let Some(byte) = value
+                .get(idx + 1..idx + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            decoded.push(byte);
+            idx += 3;
+            continue;
+        }
+        decoded.push(bytes[idx]);
+        idx += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate(schema: Value, instance: &Value) -> Vec<String> {
+        let root = Url::parse("file:///schema.json").unwrap();
+        let documents = FxHashMap::from_iter([(document_key(&root), schema)]);
+        Validator::new(root, documents)
+            .validate(instance)
+            .into_iter()
+            .map(|error| format!("{}: {}", error.path, error.message))
+            .collect()
+    }
+
+    #[test]
+    fn object_keywords() {
+        let schema = serde_json::json!({
+            "type": "object",
+            "required": ["name"],
+            "properties": {
+                "name": {"type": "string", "minLength": 1},
+                "tags": {"type": "array", "items": {"enum": ["a", "b"]}, "uniqueItems": true},
+            },
+            "additionalProperties": false,
+        });
+        insta::assert_debug_snapshot!(validate(schema.clone(), &serde_json::json!({"name": "x", "tags": ["a"]})), @"[]");
+        insta::assert_debug_snapshot!(validate(schema, &serde_json::json!({"tags": ["a", "c", "a"], "extra": 1})), @r#"
+        [
+            "$: 'name' is a required property",
+            "$.tags: [\"a\",\"c\",\"a\"] has non-unique elements",
+            "$.tags[1]: 'c' is not one of [\"a\",\"b\"]",
+            "$: Additional properties are not allowed ('extra' was unexpected)",
+        ]
+        "#);
+    }
+
+    #[test]
+    fn references_and_combinators() {
+        let schema = serde_json::json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "definitions": {
+                "port": {"type": "integer", "minimum": 1, "maximum": 65535},
+                "node": {"type": "object", "properties": {"children": {"type": "array", "items": {"$ref": "#/definitions/node"}}}},
+            },
+            "properties": {
+                "port": {"$ref": "#/definitions/port"},
+                "host": {"oneOf": [{"type": "string", "pattern": "^[a-z.]+$"}, {"type": "null"}]},
+                "tree": {"$ref": "#/definitions/node"},
+                "mode": {"if": {"const": "tls"}, "then": true, "else": {"not": {"const": "tls"}}},
+            },
+        });
+        insta::assert_debug_snapshot!(validate(schema.clone(), &serde_json::json!({"port": 443.0, "host": null, "tree": {"children": [{"children": []}]}})), @"[]");
+        insta::assert_debug_snapshot!(validate(schema, &serde_json::json!({"port": 0, "host": "Example.com", "tree": {"children": [{"children": 1}]}})), @r#"
+        [
+            "$.host: 'Example.com' is not valid under any of the given schemas",
+            "$.port: 0 is less than the minimum of 1",
+            "$.tree.children[0].children: 1 is not of type 'array'",
+        ]
+        "#);
+    }
+}
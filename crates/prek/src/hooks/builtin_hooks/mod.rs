@@ -2,30 +2,43 @@ use anyhow::Result;
 use std::path::Path;
 use std::str::FromStr;
 
-use crate::config::{BuiltinHook, HookOptions, Language, ManifestHook, Stage};
+use crate::config::{BuiltinHook, HookKind, HookOptions, Language, ManifestHook, Stage};
 use crate::hook::Hook;
 use crate::hooks::pre_commit_hooks;
 use crate::store::Store;
 
+mod check_blanket_comments;
+mod check_commit_message;
 mod check_json5;
+mod check_jsonschema;
+mod json_schema;
 
 #[derive(Debug, Copy, Clone)]
 pub(crate) enum BuiltinHooks {
     CheckAddedLargeFiles,
+    CheckBuiltinLiterals,
+    CheckByteOrderMarker,
     CheckCaseConflict,
+    CheckCommitMessage,
+    CheckDocstringFirst,
     CheckExecutablesHaveShebangs,
+    CheckIllegalWindowsNames,
     CheckJson,
     CheckJson5,
+    CheckJsonschema,
     CheckMergeConflict,
     CheckSymlinks,
     CheckToml,
     CheckXml,
     CheckYaml,
+    DebugStatements,
     DetectPrivateKey,
     EndOfFileFixer,
     FixByteOrderMarker,
     MixedLineEnding,
     NoCommitToBranch,
+    PythonCheckBlanketNoqa,
+    PythonCheckBlanketTypeIgnore,
     TrailingWhitespace,
 }
 
@@ -35,20 +48,29 @@ impl FromStr for BuiltinHooks {
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         match s {
             "check-added-large-files" => Ok(Self::CheckAddedLargeFiles),
+            "check-builtin-literals" => Ok(Self::CheckBuiltinLiterals),
+            "check-byte-order-marker" => Ok(Self::CheckByteOrderMarker),
             "check-case-conflict" => Ok(Self::CheckCaseConflict),
+            "check-commit-message" => Ok(Self::CheckCommitMessage),
+            "check-docstring-first" => Ok(Self::CheckDocstringFirst),
             "check-executables-have-shebangs" => Ok(Self::CheckExecutablesHaveShebangs),
+            "check-illegal-windows-names" => Ok(Self::CheckIllegalWindowsNames),
             "check-json" => Ok(Self::CheckJson),
             "check-json5" => Ok(Self::CheckJson5),
+            "check-jsonschema" => Ok(Self::CheckJsonschema),
             "check-merge-conflict" => Ok(Self::CheckMergeConflict),
             "check-symlinks" => Ok(Self::CheckSymlinks),
             "check-toml" => Ok(Self::CheckToml),
             "check-xml" => Ok(Self::CheckXml),
             "check-yaml" => Ok(Self::CheckYaml),
+            "debug-statements" => Ok(Self::DebugStatements),
             "detect-private-key" => Ok(Self::DetectPrivateKey),
             "end-of-file-fixer" => Ok(Self::EndOfFileFixer),
             "fix-byte-order-marker" => Ok(Self::FixByteOrderMarker),
             "mixed-line-ending" => Ok(Self::MixedLineEnding),
             "no-commit-to-branch" => Ok(Self::NoCommitToBranch),
+            "python-check-blanket-noqa" => Ok(Self::PythonCheckBlanketNoqa),
+            "python-check-blanket-type-ignore" => Ok(Self::PythonCheckBlanketTypeIgnore),
             "trailing-whitespace" => Ok(Self::TrailingWhitespace),
             _ => Err(()),
         }
@@ -58,7 +80,7 @@ impl FromStr for BuiltinHooks {
 impl BuiltinHooks {
     pub(crate) async fn run(
         self,
-        _store: &Store,
+        store: &Store,
         hook: &Hook,
         filenames: &[&Path],
     ) -> Result<(i32, Vec<u8>)> {
@@ -66,12 +88,30 @@ impl BuiltinHooks {
             Self::CheckAddedLargeFiles => {
                 pre_commit_hooks::check_added_large_files(hook, filenames).await
             }
+            Self::CheckBuiltinLiterals => {
+                pre_commit_hooks::check_builtin_literals(hook, filenames).await
+            }
+            Self::CheckByteOrderMarker => {
+                pre_commit_hooks::check_byte_order_marker(hook, filenames).await
+            }
             Self::CheckCaseConflict => pre_commit_hooks::check_case_conflict(hook, filenames).await,
+            Self::CheckCommitMessage => {
+                check_commit_message::check_commit_message(hook, filenames).await
+            }
+            Self::CheckDocstringFirst => {
+                pre_commit_hooks::check_docstring_first(hook, filenames).await
+            }
             Self::CheckExecutablesHaveShebangs => {
                 pre_commit_hooks::check_executables_have_shebangs(hook, filenames).await
             }
+            Self::CheckIllegalWindowsNames => {
+                pre_commit_hooks::check_illegal_windows_names(hook, filenames).await
+            }
             Self::CheckJson => pre_commit_hooks::check_json(hook, filenames).await,
             Self::CheckJson5 => check_json5::check_json5(hook, filenames).await,
+            Self::CheckJsonschema => {
+                check_jsonschema::check_jsonschema(store, hook, filenames).await
+            }
             Self::CheckMergeConflict => {
                 pre_commit_hooks::check_merge_conflict(hook, filenames).await
             }
@@ -79,6 +119,7 @@ impl BuiltinHooks {
             Self::CheckToml => pre_commit_hooks::check_toml(hook, filenames).await,
             Self::CheckXml => pre_commit_hooks::check_xml(hook, filenames).await,
             Self::CheckYaml => pre_commit_hooks::check_yaml(hook, filenames).await,
+            Self::DebugStatements => pre_commit_hooks::debug_statements(hook, filenames).await,
             Self::DetectPrivateKey => pre_commit_hooks::detect_private_key(hook, filenames).await,
             Self::EndOfFileFixer => pre_commit_hooks::fix_end_of_file(hook, filenames).await,
             Self::FixByteOrderMarker => {
@@ -86,6 +127,12 @@ impl BuiltinHooks {
             }
             Self::MixedLineEnding => pre_commit_hooks::mixed_line_ending(hook, filenames).await,
             Self::NoCommitToBranch => pre_commit_hooks::no_commit_to_branch(hook).await,
+            Self::PythonCheckBlanketNoqa => {
+                check_blanket_comments::check_blanket_noqa(hook, filenames).await
+            }
+            Self::PythonCheckBlanketTypeIgnore => {
+                check_blanket_comments::check_blanket_type_ignore(hook, filenames).await
+            }
             Self::TrailingWhitespace => {
                 pre_commit_hooks::fix_trailing_whitespace(hook, filenames).await
             }
@@ -105,6 +152,36 @@ impl BuiltinHook {
                 options: HookOptions {
                     description: Some("prevents giant files from being committed.".to_string()),
                     stages: Some(vec![Stage::PreCommit, Stage::PrePush, Stage::Manual]),
+                    kind: Some(HookKind::Checker),
+                    ..Default::default()
+                },
+            },
+            BuiltinHooks::CheckBuiltinLiterals => ManifestHook {
+                id: "check-builtin-literals".to_string(),
+                name: "check builtin type constructor use".to_string(),
+                language: Language::Python,
+                entry: "check-builtin-literals".to_string(),
+                options: HookOptions {
+                    description: Some(
+                        "requires literal syntax when initializing empty or zero python builtin types."
+                            .to_string(),
+                    ),
+                    types: Some(vec!["python".to_string()]),
+                    kind: Some(HookKind::Checker),
+                    ..Default::default()
+                },
+            },
+            BuiltinHooks::CheckByteOrderMarker => ManifestHook {
+                id: "check-byte-order-marker".to_string(),
+                name: "check for byte-order marker".to_string(),
+                language: Language::Python,
+                entry: "check-byte-order-marker".to_string(),
+                options: HookOptions {
+                    description: Some(
+                        "forbids files which have a utf-8 byte-order marker.".to_string(),
+                    ),
+                    types: Some(vec!["text".to_string()]),
+                    kind: Some(HookKind::Checker),
                     ..Default::default()
                 },
             },
@@ -118,6 +195,36 @@ impl BuiltinHook {
                         "checks for files that would conflict in case-insensitive filesystems"
                             .to_string(),
                     ),
+                    kind: Some(HookKind::Checker),
+                    ..Default::default()
+                },
+            },
+            BuiltinHooks::CheckCommitMessage => ManifestHook {
+                id: "check-commit-message".to_string(),
+                name: "check commit message".to_string(),
+                language: Language::Python,
+                entry: "check-commit-message".to_string(),
+                options: HookOptions {
+                    description: Some(
+                        "checks that the commit message follows the conventional commits format."
+                            .to_string(),
+                    ),
+                    stages: Some(vec![Stage::CommitMsg]),
+                    kind: Some(HookKind::Checker),
+                    ..Default::default()
+                },
+            },
+            BuiltinHooks::CheckDocstringFirst => ManifestHook {
+                id: "check-docstring-first".to_string(),
+                name: "check docstring is first".to_string(),
+                language: Language::Python,
+                entry: "check-docstring-first".to_string(),
+                options: HookOptions {
+                    description: Some(
+                        "checks a common error of defining a docstring after code.".to_string(),
+                    ),
+                    types: Some(vec!["python".to_string()]),
+                    kind: Some(HookKind::Checker),
                     ..Default::default()
                 },
             },
@@ -132,6 +239,20 @@ impl BuiltinHook {
                     ),
                     types: Some(vec!["text".to_string(), "executable".to_string()]),
                     stages: Some(vec![Stage::PreCommit, Stage::PrePush, Stage::Manual]),
+                    kind: Some(HookKind::Checker),
+                    ..Default::default()
+                },
+            },
+            BuiltinHooks::CheckIllegalWindowsNames => ManifestHook {
+                id: "check-illegal-windows-names".to_string(),
+                name: "check illegal windows names".to_string(),
+                language: Language::Python,
+                entry: "check-illegal-windows-names".to_string(),
+                options: HookOptions {
+                    description: Some(
+                        "checks for filenames which cannot be created on windows.".to_string(),
+                    ),
+                    kind: Some(HookKind::Checker),
                     ..Default::default()
                 },
             },
@@ -143,6 +264,7 @@ impl BuiltinHook {
                 options: HookOptions {
                     description: Some("checks json files for parseable syntax.".to_string()),
                     types: Some(vec!["json".to_string()]),
+                    kind: Some(HookKind::Checker),
                     ..Default::default()
                 },
             },
@@ -154,6 +276,26 @@ impl BuiltinHook {
                 options: HookOptions {
                     description: Some("checks json5 files for parseable syntax.".to_string()),
                     types: Some(vec!["json5".to_string()]),
+                    kind: Some(HookKind::Checker),
+                    ..Default::default()
+                },
+            },
+            BuiltinHooks::CheckJsonschema => ManifestHook {
+                id: "check-jsonschema".to_string(),
+                name: "check jsonschema".to_string(),
+                language: Language::Python,
+                entry: "check-jsonschema".to_string(),
+                options: HookOptions {
+                    description: Some(
+                        "validates json, yaml and toml files against a json schema.".to_string(),
+                    ),
+                    types_or: Some(vec![
+                        "json".to_string(),
+                        "json5".to_string(),
+                        "toml".to_string(),
+                        "yaml".to_string(),
+                    ]),
+                    kind: Some(HookKind::Checker),
                     ..Default::default()
                 },
             },
@@ -167,6 +309,7 @@ impl BuiltinHook {
                         "checks for files that contain merge conflict strings.".to_string(),
                     ),
                     types: Some(vec!["text".to_string()]),
+                    kind: Some(HookKind::Checker),
                     ..Default::default()
                 },
             },
@@ -180,6 +323,7 @@ impl BuiltinHook {
                         "checks for symlinks which do not point to anything.".to_string(),
                     ),
                     types: Some(vec!["symlink".to_string()]),
+                    kind: Some(HookKind::Checker),
                     ..Default::default()
                 },
             },
@@ -191,6 +335,7 @@ impl BuiltinHook {
                 options: HookOptions {
                     description: Some("checks toml files for parseable syntax.".to_string()),
                     types: Some(vec!["toml".to_string()]),
+                    kind: Some(HookKind::Checker),
                     ..Default::default()
                 },
             },
@@ -202,6 +347,7 @@ impl BuiltinHook {
                 options: HookOptions {
                     description: Some("checks xml files for parseable syntax.".to_string()),
                     types: Some(vec!["xml".to_string()]),
+                    kind: Some(HookKind::Checker),
                     ..Default::default()
                 },
             },
@@ -213,6 +359,22 @@ impl BuiltinHook {
                 options: HookOptions {
                     description: Some("checks yaml files for parseable syntax.".to_string()),
                     types: Some(vec!["yaml".to_string()]),
+                    kind: Some(HookKind::Checker),
+                    ..Default::default()
+                },
+            },
+            BuiltinHooks::DebugStatements => ManifestHook {
+                id: "debug-statements".to_string(),
+                name: "debug statements (python)".to_string(),
+                language: Language::Python,
+                entry: "debug-statement-hook".to_string(),
+                options: HookOptions {
+                    description: Some(
+                        "checks for debugger imports and py37+ `breakpoint()` calls in python source."
+                            .to_string(),
+                    ),
+                    types: Some(vec!["python".to_string()]),
+                    kind: Some(HookKind::Checker),
                     ..Default::default()
                 },
             },
@@ -224,6 +386,7 @@ impl BuiltinHook {
                 options: HookOptions {
                     description: Some("detects the presence of private keys.".to_string()),
                     types: Some(vec!["text".to_string()]),
+                    kind: Some(HookKind::Checker),
                     ..Default::default()
                 },
             },
@@ -239,6 +402,7 @@ impl BuiltinHook {
                     ),
                     types: Some(vec!["text".to_string()]),
                     stages: Some(vec![Stage::PreCommit, Stage::PrePush, Stage::Manual]),
+                    kind: Some(HookKind::Fixer),
                     ..Default::default()
                 },
             },
@@ -250,6 +414,7 @@ impl BuiltinHook {
                 options: HookOptions {
                     description: Some("removes utf-8 byte order marker.".to_string()),
                     types: Some(vec!["text".to_string()]),
+                    kind: Some(HookKind::Fixer),
                     ..Default::default()
                 },
             },
@@ -261,6 +426,7 @@ impl BuiltinHook {
                 options: HookOptions {
                     description: Some("replaces or checks mixed line ending.".to_string()),
                     types: Some(vec!["text".to_string()]),
+                    kind: Some(HookKind::Fixer),
                     ..Default::default()
                 },
             },
@@ -272,6 +438,37 @@ impl BuiltinHook {
                 options: HookOptions {
                     pass_filenames: Some(false),
                     always_run: Some(true),
+                    kind: Some(HookKind::Checker),
+                    ..Default::default()
+                },
+            },
+            BuiltinHooks::PythonCheckBlanketNoqa => ManifestHook {
+                id: "python-check-blanket-noqa".to_string(),
+                name: "check blanket noqa".to_string(),
+                language: Language::Python,
+                entry: "python-check-blanket-noqa".to_string(),
+                options: HookOptions {
+                    description: Some(
+                        "Enforce that `noqa` annotations always occur with specific codes."
+                            .to_string(),
+                    ),
+                    types: Some(vec!["python".to_string()]),
+                    kind: Some(HookKind::Checker),
+                    ..Default::default()
+                },
+            },
+            BuiltinHooks::PythonCheckBlanketTypeIgnore => ManifestHook {
+                id: "python-check-blanket-type-ignore".to_string(),
+                name: "check blanket type ignore".to_string(),
+                language: Language::Python,
+                entry: "python-check-blanket-type-ignore".to_string(),
+                options: HookOptions {
+                    description: Some(
+                        "Enforce that `# type: ignore` annotations always occur with specific codes."
+                            .to_string(),
+                    ),
+                    types: Some(vec!["python".to_string()]),
+                    kind: Some(HookKind::Checker),
                     ..Default::default()
                 },
             },
@@ -284,6 +481,7 @@ impl BuiltinHook {
                     description: Some("trims trailing whitespace.".to_string()),
                     types: Some(vec!["text".to_string()]),
                     stages: Some(vec![Stage::PreCommit, Stage::PrePush, Stage::Manual]),
+                    kind: Some(HookKind::Fixer),
                     ..Default::default()
                 },
             },
@@ -1,13 +1,15 @@
+use std::collections::BTreeSet;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use anyhow::{Context, Result};
 use itertools::Itertools;
+use prek_consts::{ALT_CONFIG_FILE, CONFIG_FILE};
 
 use crate::cli::run::{CollectOptions, FileFilter, collect_files};
 use crate::config::{
-    self, CONFIG_FILE_REGEX, FilePattern, HookOptions, Language, ManifestHook, MetaHook,
+    self, CONFIG_FILE_REGEX, FilePattern, HookKind, HookOptions, Language, ManifestHook, MetaHook,
 };
 use crate::hook::Hook;
 use crate::store::Store;
@@ -68,6 +70,7 @@ impl MetaHook {
                 entry: String::new(),
                 options: HookOptions {
                     files: Some(FilePattern::from(config_file_regex)),
+                    kind: Some(HookKind::Checker),
                     ..Default::default()
                 },
             },
@@ -78,6 +81,7 @@ impl MetaHook {
                 entry: String::new(),
                 options: HookOptions {
                     files: Some(FilePattern::from(config_file_regex)),
+                    kind: Some(HookKind::Checker),
                     ..Default::default()
                 },
             },
@@ -88,6 +92,7 @@ impl MetaHook {
                 entry: String::new(),
                 options: HookOptions {
                     verbose: Some(true),
+                    kind: Some(HookKind::Checker),
                     ..Default::default()
                 },
             },
@@ -97,31 +102,88 @@ impl MetaHook {
     }
 }
 
+/// Collect all files of the hook's project, relative to the workspace root.
+async fn project_files(hook: &Hook) -> Result<Vec<PathBuf>> {
+    let relative_path = hook.project().relative_path();
+    let input = collect_files(hook.work_dir(), CollectOptions::all_files()).await?;
+    Ok(input.into_iter().map(|f| relative_path.join(f)).collect())
+}
+
+/// Load the projects whose configuration a meta hook checks.
+///
+/// These are the configurations passed to the hook, and in workspace mode the configurations of
+/// the projects nested in the hook's project, unless they run the same meta hook themselves.
+fn projects_to_check(hook: &Hook, filenames: &[&Path], input: &[PathBuf]) -> Result<Vec<Project>> {
+    let relative_path = hook.project().relative_path();
+    let mut config_files = filenames
+        .iter()
+        .map(|filename| relative_path.join(filename))
+        .collect::<BTreeSet<_>>();
+
+    let is_config_file = |path: &Path| {
+        path.file_name()
+            .is_some_and(|name| name == CONFIG_FILE || name == ALT_CONFIG_FILE)
+    };
+    // With `--config <path>`, there are no nested projects.
+    if is_config_file(hook.project().config_file()) {
+        config_files.extend(
+            input
+                .iter()
+                .filter(|file| is_config_file(file))
+                .filter(|file| file.parent() != Some(relative_path))
+                .cloned(),
+        );
+    }
+
+    let mut projects = Vec::with_capacity(config_files.len());
+    for config_file in config_files {
+        let project_path = config_file
+            .parent()
+            .expect("config file must have a parent")
+            .to_path_buf();
+        let nested = project_path != relative_path;
+        let mut project = Project::from_config_file(config_file.into(), None)?;
+        project.with_relative_path(project_path);
+
+        let runs_hook = project.config().repos.iter().any(|repo| {
+            matches!(repo, config::Repo::Meta(meta) if meta.hooks.iter().any(|h| h.0.id == hook.id))
+        });
+        if nested && runs_hook {
+            continue;
+        }
+        projects.push(project);
+    }
+
+    Ok(projects)
+}
+
+/// The prefix of the findings for a project: its path, unless it's the project of the hook.
+fn finding_prefix(hook: &Hook, project: &Project) -> String {
+    if project.relative_path() == hook.project().relative_path() {
+        String::new()
+    } else {
+        format!("{project}: ")
+    }
+}
+
 /// Ensures that the configured hooks apply to at least one file in the repository.
 pub(crate) async fn check_hooks_apply(
     store: &Store,
     hook: &Hook,
     filenames: &[&Path],
 ) -> Result<(i32, Vec<u8>)> {
-    let relative_path = hook.project().relative_path();
-    // Collect all files in the project
-    let input = collect_files(hook.work_dir(), CollectOptions::all_files()).await?;
-    // Prepend the project relative path to each input file
-    let input: Vec<_> = input.into_iter().map(|f| relative_path.join(f)).collect();
+    let input = project_files(hook).await?;
 
     let mut code = 0;
     let mut output = Vec::new();
 
-    for filename in filenames {
-        let path = relative_path.join(filename);
-        let mut project = Project::from_config_file(path.into(), None)?;
-        project.with_relative_path(relative_path.to_path_buf());
-
+    for mut project in projects_to_check(hook, filenames, &input)? {
         let project_hooks = project
             .init_hooks(store, None)
             .await
             .context("Failed to init hooks")?;
-        let filter = FileFilter::for_project(input.iter(), &project, None);
+        let filter = FileFilter::for_project(input.iter(), &project, None, None);
+        let prefix = finding_prefix(hook, &project);
 
         for project_hook in project_hooks {
             if project_hook.always_run || matches!(project_hook.language, Language::Fail) {
@@ -134,7 +196,7 @@ pub(crate) async fn check_hooks_apply(
                 code = 1;
                 writeln!(
                     &mut output,
-                    "{} does not apply to this repository",
+                    "{prefix}{} does not apply to this repository",
                     project_hook.id
                 )?;
             }
@@ -178,20 +240,19 @@ pub(crate) async fn check_useless_excludes(
     hook: &Hook,
     filenames: &[&Path],
 ) -> Result<(i32, Vec<u8>)> {
-    let relative_path = hook.project().relative_path();
-    let input = collect_files(hook.work_dir(), CollectOptions::all_files()).await?;
-    let input: Vec<_> = input.into_iter().map(|f| relative_path.join(f)).collect();
+    let input = project_files(hook).await?;
 
     let mut code = 0;
     let mut output = Vec::new();
 
-    for filename in filenames {
-        let path = relative_path.join(filename);
-        let mut project = Project::from_config_file(path.into(), None)?;
-        project.with_relative_path(relative_path.to_path_buf());
-
+    for project in projects_to_check(hook, filenames, &input)? {
+        let prefix = finding_prefix(hook, &project);
         let config = project.config();
-        if !excludes_any(&input, None, config.exclude.as_ref()) {
+        let project_input = input
+            .iter()
+            .filter(|file| file.starts_with(project.relative_path()))
+            .collect::<Vec<_>>();
+        if !excludes_any(&project_input, None, config.exclude.as_ref()) {
             code = 1;
             let display = config
                 .exclude
@@ -200,11 +261,11 @@ pub(crate) async fn check_useless_excludes(
                 .unwrap_or_default();
             writeln!(
                 &mut output,
-                "The global exclude pattern `{display}` does not match any files"
+                "{prefix}The global exclude pattern `{display}` does not match any files"
             )?;
         }
 
-        let filter = FileFilter::for_project(input.iter(), &project, None);
+        let filter = FileFilter::for_project(input.iter(), &project, None, None);
 
         for repo in &config.repos {
             let hooks_iter: Box<dyn Iterator<Item = (&String, &HookOptions)>> = match repo {
@@ -217,11 +278,16 @@ pub(crate) async fn check_useless_excludes(
             };
 
             for (hook_id, opts) in hooks_iter {
-                let filtered_files = filter.by_type(
-                    opts.types.as_deref().unwrap_or(&[]),
-                    opts.types_or.as_deref().unwrap_or(&[]),
-                    opts.exclude_types.as_deref().unwrap_or(&[]),
-                );
+                // Hook patterns match paths relative to the project, like when running hooks.
+                let filtered_files = filter
+                    .by_type(
+                        opts.types.as_deref().unwrap_or(&[]),
+                        opts.types_or.as_deref().unwrap_or(&[]),
+                        opts.exclude_types.as_deref().unwrap_or(&[]),
+                    )
+                    .into_iter()
+                    .filter_map(|file| file.strip_prefix(project.relative_path()).ok())
+                    .collect::<Vec<_>>();
 
                 if !excludes_any(&filtered_files, opts.files.as_ref(), opts.exclude.as_ref()) {
                     code = 1;
@@ -232,7 +298,7 @@ pub(crate) async fn check_useless_excludes(
                         .unwrap_or_default();
                     writeln!(
                         &mut output,
-                        "The exclude pattern `{display}` for `{hook_id}` does not match any files"
+                        "{prefix}The exclude pattern `{display}` for `{hook_id}` does not match any files"
                     )?;
                 }
             }
@@ -257,7 +323,6 @@ pub fn identity(_hook: &Hook, filenames: &[&Path]) -> (i32, Vec<u8>) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use prek_consts::{ALT_CONFIG_FILE, CONFIG_FILE};
 
     fn regex_pattern(pattern: &str) -> FilePattern {
         let regex = fancy_regex::Regex::new(pattern).unwrap();
@@ -0,0 +1,257 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use anyhow::Result;
+use clap::Parser;
+
+use crate::hook::Hook;
+use crate::hooks::pre_commit_hooks::python_tokenizer::{Token, TokenKind, tokenize};
+use crate::hooks::run_concurrent_file_checks;
+use crate::run::CONCURRENCY;
+
+/// Builtin types and the literal to use instead of calling them without arguments.
+const BUILTIN_TYPES: &[(&str, &str)] = &[
+    ("complex", "0j"),
+    ("dict", "{}"),
+    ("float", "0.0"),
+    ("int", "0"),
+    ("list", "[]"),
+    ("str", "''"),
+    ("tuple", "()"),
+];
+
+#[derive(Parser)]
+#[command(disable_help_subcommand = true)]
+#[command(disable_version_flag = true)]
+#[command(disable_help_flag = true)]
+struct Args {
+    /// Builtin types to allow calls of.
+    #[arg(long, value_delimiter = ',')]
+    ignore: Vec<String>,
+    /// Also report `dict()` calls with keyword arguments.
+    #[arg(long = "no-allow-dict-kwargs", action = clap::ArgAction::SetFalse)]
+    allow_dict_kwargs: bool,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct Call {
+    line: usize,
+    col: usize,
+    name: &'static str,
+    literal: &'static str,
+}
+
+pub(crate) async fn check_builtin_literals(
+    hook: &Hook,
+    filenames: &[&Path],
+) -> Result<(i32, Vec<u8>)> {
+    let args = Args::try_parse_from(hook.entry.resolve(None)?.iter().chain(&hook.args))?;
+
+    run_concurrent_file_checks(filenames.iter().copied(), *CONCURRENCY, |filename| {
+        check_file(hook.project().relative_path(), filename, &args)
+    })
+    .await
+}
+
+async fn check_file(file_base: &Path, filename: &Path, args: &Args) -> Result<(i32, Vec<u8>)> {
+    let content = fs_err::tokio::read(file_base.join(filename)).await?;
+    let source = String::from_utf8_lossy(&content);
+
+    let mut output = String::new();
+    match find_builtin_calls(&source, args) {
+        Ok(calls) => {
+            for call in &calls {
+                writeln!(
+                    output,
+                    "{}:{}:{}: replace {}() with {}",
+                    filename.display(),
+                    call.line,
+                    call.col,
+                    call.name,
+                    call.literal
+                )?;
+            }
+            Ok((i32::from(!calls.is_empty()), output.into_bytes()))
+        }
+        Err(err) => {
+            writeln!(
+                output,
+                "{} - Could not parse ast\n\n\tSyntaxError: {err}\n",
+                filename.display()
+            )?;
+            Ok((1, output.into_bytes()))
+        }
+    }
+}
+
+/// Find calls of builtin types that could be literals, like `dict()` or `list()`.
+///
+/// Like upstream, the arguments of a call with arguments to a builtin type are not checked.
+fn find_builtin_calls(source: &str, args: &Args) -> Result<Vec<Call>> {
+    let tokens = tokenize(source)?
+        .into_iter()
+        .filter(|token| !matches!(token.kind, TokenKind::Comment | TokenKind::Nl))
+        .collect::<Vec<_>>();
+
+    let mut calls = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = &tokens[i];
+        let builtin = BUILTIN_TYPES.iter().find(|(name, _)| {
+            token.is_name(name) && !args.ignore.iter().any(|ignored| ignored == name)
+        });
+        let Some(&(name, literal)) = builtin else {
+            i += 1;
+            continue;
+        };
+        if !tokens.get(i + 1).is_some_and(|token| token.is_op("("))
+            || i.checked_sub(1).is_some_and(|prev| {
+                let prev = &tokens[prev];
+                prev.is_op(".") || prev.is_name("def") || prev.is_name("class")
+            })
+        {
+            i += 1;
+            continue;
+        }
+
+        let end = closing_paren(&tokens, i + 1);
+        let (positional, keywords) = count_arguments(&tokens[i + 2..end]);
+        let call = Call {
+            line: token.line,
+            col: token.col,
+            name,
+            literal,
+        };
+        if positional == 0 && keywords == 0 {
+            calls.push(call);
+        } else if name == "dict" && positional == 0 && !args.allow_dict_kwargs {
+            calls.push(call);
+            i += 2;
+            continue;
+        }
+        i = end + 1;
+    }
+
+    Ok(calls)
+}
+
+/// The index of the parenthesis closing the one at `open`.
+fn closing_paren(tokens: &[Token<'_>], open: usize) -> usize {
+    let mut depth = 0usize;
+    for (i, token) in tokens.iter().enumerate().skip(open) {
+        if token.kind != TokenKind::Op {
+            continue;
+        }
+        match token.text {
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" => {
+                depth -= 1;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ => {}
+        }
+    }
+    // The tokenizer rejects unbalanced brackets.
+    tokens.len()
+}
+
+/// Count the positional and keyword arguments of a call, given the tokens between its parentheses.
+///
+/// `**mapping` counts as a keyword argument and `*iterable` as a positional one, like in `ast`.
+fn count_arguments(tokens: &[Token<'_>]) -> (usize, usize) {
+    let mut positional = 0;
+    let mut keywords = 0;
+    let mut depth = 0usize;
+    let mut start = 0;
+    for i in 0..=tokens.len() {
+        let at_end = i == tokens.len();
+        if !at_end && tokens[i].kind == TokenKind::Op {
+            match tokens[i].text {
+                "(" | "[" | "{" => depth += 1,
+                ")" | "]" | "}" => depth -= 1,
+                _ => {}
+            }
+        }
+        if !at_end && (depth > 0 || !tokens[i].is_op(",")) {
+            continue;
+        }
+
+        let argument = &tokens[start..i];
+        start = i + 1;
+        match argument {
+            // A trailing comma.
+            [] => {}
+            [first, second, third, ..]
+                if first.kind == TokenKind::Name && second.is_op("=") && !third.is_op("=") =>
+            {
+                keywords += 1;
+            }
+            [first, second, ..] if first.is_op("*") && second.is_op("*") => keywords += 1,
+            _ => positional += 1,
+        }
+    }
+    (positional, keywords)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find(source: &str, args: &[&str]) -> Vec<(usize, usize, &'static str)> {
+        let args = Args::try_parse_from(["check-builtin-literals"].iter().chain(args)).unwrap();
+        find_builtin_calls(source, &args)
+            .unwrap()
+            .into_iter()
+            .map(|call| (call.line, call.col, call.name))
+            .collect()
+    }
+
+    #[test]
+    fn test_empty_calls() {
+        assert_eq!(
+            find("x = dict()\ny = [list(), tuple( )]\nz = str(1)\n", &[]),
+            [(1, 4, "dict"), (2, 5, "list"), (2, 13, "tuple")]
+        );
+        assert_eq!(
+            find("a = int()\nb = float()\nc = complex()\n", &[]),
+            [(1, 4, "int"), (2, 4, "float"), (3, 4, "complex")]
+        );
+    }
+
+    #[test]
+    fn test_not_builtin_calls() {
+        assert!(find("obj.dict()\ndef list(): pass\nclass str: pass\n", &[]).is_empty());
+        assert!(find("f(dict=1)\nx = dict\n'dict()'\n# list()\n", &[]).is_empty());
+        // Upstream doesn't look into the arguments of builtin type calls with arguments.
+        assert!(find("list(tuple())\n", &[]).is_empty());
+        assert_eq!(find("f(tuple())\n", &[]), [(1, 2, "tuple")]);
+    }
+
+    #[test]
+    fn test_dict_kwargs() {
+        assert!(find("dict(a=1)\ndict(**kwargs)\n", &[]).is_empty());
+        assert_eq!(
+            find(
+                "dict(a=1)\ndict(**kwargs)\ndict(x, a=1)\ndict(a=list())\n",
+                &["--no-allow-dict-kwargs"]
+            ),
+            [
+                (1, 0, "dict"),
+                (2, 0, "dict"),
+                (4, 0, "dict"),
+                (4, 7, "list")
+            ]
+        );
+        assert!(find("dict(a == b)\n", &["--no-allow-dict-kwargs"]).is_empty());
+    }
+
+    #[test]
+    fn test_ignore() {
+        assert_eq!(
+            find("dict()\nlist()\ntuple()\n", &["--ignore=dict,list"]),
+            [(3, 0, "tuple")]
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use std::path::Path;
+
+use anyhow::Result;
+use tokio::io::AsyncReadExt;
+
+use crate::hook::Hook;
+use crate::hooks::run_concurrent_file_checks;
+use crate::run::CONCURRENCY;
+
+const UTF8_BOM: &[u8] = b"\xef\xbb\xbf";
+
+pub(crate) async fn check_byte_order_marker(
+    hook: &Hook,
+    filenames: &[&Path],
+) -> Result<(i32, Vec<u8>)> {
+    run_concurrent_file_checks(filenames.iter().copied(), *CONCURRENCY, |filename| {
+        check_file(hook.project().relative_path(), filename)
+    })
+    .await
+}
+
+async fn check_file(file_base: &Path, filename: &Path) -> Result<(i32, Vec<u8>)> {
+    let mut file = fs_err::tokio::File::open(file_base.join(filename)).await?;
+    let mut buffer = Vec::with_capacity(UTF8_BOM.len());
+    (&mut file)
+        .take(UTF8_BOM.len() as u64)
+        .read_to_end(&mut buffer)
+        .await?;
+
+    if buffer != UTF8_BOM {
+        return Ok((0, Vec::new()));
+    }
+
+    Ok((
+        1,
+        format!("{}: Has a byte-order marker\n", filename.display()).into_bytes(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_byte_order_marker() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let with_bom = dir.path().join("with_bom.txt");
+        let without_bom = dir.path().join("without_bom.txt");
+        let short = dir.path().join("short.txt");
+        fs_err::tokio::write(&with_bom, b"\xef\xbb\xbfHello").await?;
+        fs_err::tokio::write(&without_bom, b"Hello").await?;
+        fs_err::tokio::write(&short, b"\xef\xbb").await?;
+
+        let (code, output) = check_file(Path::new(""), &with_bom).await?;
+        assert_eq!(code, 1);
+        assert!(String::from_utf8_lossy(&output).ends_with(": Has a byte-order marker\n"));
+        // The file is left unchanged.
+        assert_eq!(fs_err::tokio::read(&with_bom).await?, b"\xef\xbb\xbfHello");
+
+        assert_eq!(
+            check_file(Path::new(""), &without_bom).await?,
+            (0, Vec::new())
+        );
+        assert_eq!(check_file(Path::new(""), &short).await?, (0, Vec::new()));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::hook::Hook;
+use crate::hooks::pre_commit_hooks::python_tokenizer::{TokenKind, tokenize};
+use crate::hooks::run_concurrent_file_checks;
+use crate::run::CONCURRENCY;
+
+pub(crate) async fn check_docstring_first(
+    hook: &Hook,
+    filenames: &[&Path],
+) -> Result<(i32, Vec<u8>)> {
+    run_concurrent_file_checks(filenames.iter().copied(), *CONCURRENCY, |filename| {
+        check_file(hook.project().relative_path(), filename)
+    })
+    .await
+}
+
+async fn check_file(file_base: &Path, filename: &Path) -> Result<(i32, Vec<u8>)> {
+    let content = fs_err::tokio::read(file_base.join(filename)).await?;
+    let source = String::from_utf8_lossy(&content);
+
+    match check_source(&source) {
+        Some(message) => Ok((
+            1,
+            format!("{}:{message}\n", filename.display()).into_bytes(),
+        )),
+        None => Ok((0, Vec::new())),
+    }
+}
+
+/// Check that a module docstring, if any, comes before any code.
+///
+/// A docstring is a string token at the start of a line, like in `pre-commit-hooks`.
+fn check_source(source: &str) -> Option<String> {
+    let tokens = match tokenize(source) {
+        Ok(tokens) => tokens,
+        Err(err) => return Some(format!("{}: Failed to tokenize: {}", err.line, err.message)),
+    };
+
+    let mut docstring_line = None;
+    let mut code_line = None;
+    for token in tokens {
+        match token.kind {
+            TokenKind::String if token.col == 0 => {
+                if let Some(docstring_line) = docstring_line {
+                    return Some(format!(
+                        "{}: Multiple module docstrings (first docstring on line {docstring_line}).",
+                        token.line
+                    ));
+                }
+                if let Some(code_line) = code_line {
+                    return Some(format!(
+                        "{}: Module docstring appears after code (code seen on line {code_line}).",
+                        token.line
+                    ));
+                }
+                docstring_line = Some(token.line);
+            }
+            TokenKind::Comment | TokenKind::Newline | TokenKind::Nl => {}
+            _ => {
+                code_line.get_or_insert(token.line);
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_source() {
+        assert_eq!(check_source(""), None);
+        assert_eq!(
+            check_source("# comment\n\"\"\"docstring\"\"\"\nimport os\n"),
+            None
+        );
+        assert_eq!(check_source("import os\n"), None);
+        assert_eq!(
+            check_source("'''foo'''\n\n'''bar'''\n"),
+            Some("3: Multiple module docstrings (first docstring on line 1).".to_string())
+        );
+        assert_eq!(
+            check_source("import os\n\"\"\"docstring\n\"\"\"\n"),
+            Some("2: Module docstring appears after code (code seen on line 1).".to_string())
+        );
+        // Strings that aren't at the start of a line are not docstrings.
+        assert_eq!(check_source("def f():\n    '''docstring'''\n"), None);
+        assert_eq!(check_source("x = '''\n'''\n"), None);
+    }
+}
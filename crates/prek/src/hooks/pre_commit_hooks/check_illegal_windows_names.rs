@@ -0,0 +1,145 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::hook::Hook;
+
+/// Device names reserved on Windows, with or without an extension.
+const RESERVED_NAMES: &[&str] = &["CON", "PRN", "AUX", "NUL"];
+/// Device names reserved on Windows when followed by a digit, including superscript digits.
+const RESERVED_PREFIXES: &[&str] = &["COM", "LPT"];
+
+#[allow(clippy::unused_async)]
+pub(crate) async fn check_illegal_windows_names(
+    _hook: &Hook,
+    filenames: &[&Path],
+) -> Result<(i32, Vec<u8>)> {
+    let mut code = 0;
+    let mut output = String::new();
+
+    for filename in filenames {
+        let path = filename.to_string_lossy();
+        if let Some(reason) = path.split('/').find_map(check_component) {
+            code = 1;
+            writeln!(
+                output,
+                "{}: Illegal Windows filename ({reason})",
+                filename.display()
+            )?;
+        }
+    }
+
+    Ok((code, output.into_bytes()))
+}
+
+/// Return why a path component can't be used on Windows, if it can't.
+fn check_component(component: &str) -> Option<&'static str> {
+    if let Some(c) = component.chars().find(|c| {
+        matches!(c, '<' | '>' | ':' | '"' | '\\' | '|' | '?' | '*') || c.is_ascii_control()
+    }) {
+        return Some(if c.is_ascii_control() {
+            "contains a control character"
+        } else {
+            "contains a character not allowed on Windows"
+        });
+    }
+
+    if component.ends_with('.') || component.ends_with(char::is_whitespace) {
+        return Some("ends with a dot or a space");
+    }
+
+    // `CON`, `con.txt` and `con.tar.gz` are all reserved.
+    let stem = component.split('.').next().unwrap_or(component);
+    if is_reserved(stem) {
+        return Some("reserved device name");
+    }
+
+    None
+}
+
+fn is_reserved(stem: &str) -> bool {
+    if RESERVED_NAMES
+        .iter()
+        .any(|name| stem.eq_ignore_ascii_case(name))
+    {
+        return true;
+    }
+    RESERVED_PREFIXES.iter().any(|prefix| {
+        let Some(head) = stem.get(..prefix.len()) else {
+            return false;
+        };
+        let mut rest = stem[prefix.len()..].chars();
+        head.eq_ignore_ascii_case(prefix)
+            && matches!(
+                (rest.next(), rest.next()),
+                (Some('0'..='9' | '¹' | '²' | '³'), None)
+            )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legal_names() {
+        for name in [
+            "file.txt",
+            ".gitignore",
+            "console.log",
+            "com10",
+            "lpt",
+            "auxiliary.rs",
+            "a.b.c",
+            "ünïcödé",
+        ] {
+            assert_eq!(check_component(name), None, "{name}");
+        }
+    }
+
+    #[test]
+    fn reserved_names() {
+        for name in [
+            "CON",
+            "con",
+            "Aux.txt",
+            "nul.tar.gz",
+            "COM1",
+            "lpt9.log",
+            "com¹",
+        ] {
+            assert_eq!(
+                check_component(name),
+                Some("reserved device name"),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn illegal_characters() {
+        for name in ["a<b", "a>b", "a:b", "a\"b", "a\\b", "a|b", "a?b", "a*b"] {
+            assert_eq!(
+                check_component(name),
+                Some("contains a character not allowed on Windows"),
+                "{name}"
+            );
+        }
+        assert_eq!(
+            check_component("a\tb"),
+            Some("contains a control character")
+        );
+    }
+
+    #[test]
+    fn trailing_dot_or_space() {
+        for name in ["file.", "file ", "dir.."] {
+            assert_eq!(
+                check_component(name),
+                Some("ends with a dot or a space"),
+                "{name}"
+            );
+        }
+    }
+}
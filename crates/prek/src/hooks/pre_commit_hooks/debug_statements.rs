@@ -0,0 +1,238 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::hook::Hook;
+use crate::hooks::pre_commit_hooks::python_tokenizer::{Token, TokenKind, tokenize};
+use crate::hooks::run_concurrent_file_checks;
+use crate::run::CONCURRENCY;
+
+const DEBUG_STATEMENTS: &[&str] = &[
+    "bpdb",
+    "ipdb",
+    "pdb",
+    "pdbr",
+    "pudb",
+    "pydevd_pycharm",
+    "q",
+    "rdb",
+    "rpdb",
+    "wdb",
+];
+
+#[derive(Debug, Eq, PartialEq)]
+struct Debug {
+    line: usize,
+    col: usize,
+    name: String,
+    reason: &'static str,
+}
+
+pub(crate) async fn debug_statements(hook: &Hook, filenames: &[&Path]) -> Result<(i32, Vec<u8>)> {
+    run_concurrent_file_checks(filenames.iter().copied(), *CONCURRENCY, |filename| {
+        check_file(hook.project().relative_path(), filename)
+    })
+    .await
+}
+
+async fn check_file(file_base: &Path, filename: &Path) -> Result<(i32, Vec<u8>)> {
+    let content = fs_err::tokio::read(file_base.join(filename)).await?;
+    let source = String::from_utf8_lossy(&content);
+
+    let mut output = String::new();
+    match find_debug_statements(&source) {
+        Ok(debugs) => {
+            for debug in &debugs {
+                writeln!(
+                    output,
+                    "{}:{}:{}: {} {}",
+                    filename.display(),
+                    debug.line,
+                    debug.col,
+                    debug.name,
+                    debug.reason
+                )?;
+            }
+            Ok((i32::from(!debugs.is_empty()), output.into_bytes()))
+        }
+        Err(err) => {
+            writeln!(
+                output,
+                "{} - Could not parse ast\n\n\tSyntaxError: {err}\n",
+                filename.display()
+            )?;
+            Ok((1, output.into_bytes()))
+        }
+    }
+}
+
+/// Find imports of debugger modules and `breakpoint()` calls.
+fn find_debug_statements(source: &str) -> Result<Vec<Debug>> {
+    let tokens = tokenize(source)?
+        .into_iter()
+        .filter(|token| !matches!(token.kind, TokenKind::Comment | TokenKind::Nl))
+        .collect::<Vec<_>>();
+
+    let mut debugs = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = &tokens[i];
+        if token.is_name("from") {
+            // `from .module import name`: the module is checked without the leading dots.
+            let mut j = i + 1;
+            while tokens.get(j).is_some_and(|token| token.is_op(".")) {
+                j += 1;
+            }
+            let (module, end) = if tokens.get(j).is_some_and(|token| token.is_name("import")) {
+                (None, j)
+            } else {
+                dotted_name(&tokens, j)
+            };
+            if tokens.get(end).is_some_and(|token| token.is_name("import")) {
+                if let Some(module) = module
+                    && DEBUG_STATEMENTS.contains(&module.as_str())
+                {
+                    debugs.push(Debug {
+                        line: token.line,
+                        col: token.col,
+                        name: module,
+                        reason: "imported",
+                    });
+                }
+                i = end + 1;
+                continue;
+            }
+        } else if token.is_name("import") {
+            // `import a.b as c, d`
+            let mut j = i + 1;
+            loop {
+                let (name, end) = dotted_name(&tokens, j);
+                let Some(name) = name else {
+                    break;
+                };
+                if DEBUG_STATEMENTS.contains(&name.as_str()) {
+                    debugs.push(Debug {
+                        line: token.line,
+                        col: token.col,
+                        name,
+                        reason: "imported",
+                    });
+                }
+                j = end;
+                if tokens.get(j).is_some_and(|token| token.is_name("as")) {
+                    j += 2;
+                }
+                if !tokens.get(j).is_some_and(|token| token.is_op(",")) {
+                    break;
+                }
+                j += 1;
+            }
+            i = j;
+            continue;
+        } else if token.is_name("breakpoint")
+            && tokens.get(i + 1).is_some_and(|token| token.is_op("("))
+            && !i.checked_sub(1).is_some_and(|prev| {
+                let prev = &tokens[prev];
+                prev.is_op(".") || prev.is_name("def") || prev.is_name("class")
+            })
+        {
+            debugs.push(Debug {
+                line: token.line,
+                col: token.col,
+                name: "breakpoint".to_string(),
+                reason: "called",
+            });
+        }
+        i += 1;
+    }
+
+    Ok(debugs)
+}
+
+/// Parse a dotted name like `a.b.c` starting at `start`, returning it and the index after it.
+fn dotted_name(tokens: &[Token<'_>], start: usize) -> (Option<String>, usize) {
+    let mut name = String::new();
+    let mut i = start;
+    while let Some(token) = tokens.get(i)
+        && token.kind == TokenKind::Name
+    {
+        name.push_str(token.text);
+        i += 1;
+        if tokens.get(i).is_some_and(|token| token.is_op("."))
+            && tokens
+                .get(i + 1)
+                .is_some_and(|token| token.kind == TokenKind::Name)
+        {
+            name.push('.');
+            i += 1;
+        } else {
+            break;
+        }
+    }
+
+    if name.is_empty() {
+        (None, start)
+    } else {
+        (Some(name), i)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find(source: &str) -> Vec<(usize, usize, String, &'static str)> {
+        find_debug_statements(source)
+            .unwrap()
+            .into_iter()
+            .map(|debug| (debug.line, debug.col, debug.name, debug.reason))
+            .collect()
+    }
+
+    #[test]
+    fn test_imports() {
+        assert_eq!(
+            find("import os\nimport pdb\nimport sys, ipdb as debugger\n"),
+            [
+                (2, 0, "pdb".to_string(), "imported"),
+                (3, 0, "ipdb".to_string(), "imported"),
+            ]
+        );
+        assert_eq!(
+            find("def f():\n    from pudb import set_trace; set_trace()\n"),
+            [(2, 4, "pudb".to_string(), "imported")]
+        );
+        assert_eq!(
+            find("from .q import x\nfrom . import pdb\nimport pdb.foo\n"),
+            [(1, 0, "q".to_string(), "imported")]
+        );
+    }
+
+    #[test]
+    fn test_breakpoint() {
+        assert_eq!(
+            find("x = 1\nif x: breakpoint()\n"),
+            [(2, 6, "breakpoint".to_string(), "called")]
+        );
+        assert!(
+            find("def breakpoint():\n    pass\nobj.breakpoint()\nf(breakpoint=1)\n").is_empty()
+        );
+    }
+
+    #[test]
+    fn test_ignores_strings_and_comments() {
+        assert!(find("# import pdb\n'''\nimport pdb\nbreakpoint()\n'''\n").is_empty());
+        assert!(find("raise ValueError from pdb\n").is_empty());
+    }
+
+    #[test]
+    fn test_syntax_error() {
+        assert_eq!(
+            find_debug_statements("import pdb\nx = '\n")
+                .unwrap_err()
+                .to_string(),
+            "unterminated string literal (line 2)"
+        );
+    }
+}
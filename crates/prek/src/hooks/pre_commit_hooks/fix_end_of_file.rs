@@ -1,20 +1,50 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
+use clap::Parser;
+use rustc_hash::FxHashMap;
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, AsyncWriteExt, SeekFrom};
 
+use crate::git::{Eol, get_eol_attrs};
 use crate::hook::Hook;
 use crate::hooks::run_concurrent_file_checks;
 use crate::run::CONCURRENCY;
 
+#[derive(Parser)]
+#[command(disable_help_subcommand = true)]
+#[command(disable_version_flag = true)]
+#[command(disable_help_flag = true)]
+struct Args {
+    /// Add the line ending from the `eol` attribute in `.gitattributes` for files that set it.
+    #[clap(long)]
+    respect_gitattributes: bool,
+}
+
 pub(crate) async fn fix_end_of_file(hook: &Hook, filenames: &[&Path]) -> Result<(i32, Vec<u8>)> {
+    let args = Args::try_parse_from(hook.entry.resolve(None)?.iter().chain(&hook.args))?;
+
+    let file_base = hook.project().relative_path();
+    let eol_attrs = if args.respect_gitattributes {
+        let paths = filenames
+            .iter()
+            .map(|filename| file_base.join(filename))
+            .collect::<Vec<_>>();
+        get_eol_attrs(&paths.iter().map(PathBuf::as_path).collect::<Vec<_>>()).await?
+    } else {
+        FxHashMap::default()
+    };
+
     run_concurrent_file_checks(filenames.iter().copied(), *CONCURRENCY, |filename| {
-        fix_file(hook.project().relative_path(), filename)
+        let eol = eol_attrs.get(&file_base.join(filename)).copied();
+        fix_file(file_base, filename, eol)
     })
     .await
 }
 
-async fn fix_file(file_base: &Path, filename: &Path) -> Result<(i32, Vec<u8>)> {
+/// Make sure the file ends with exactly one line ending.
+///
+/// A missing line ending is added as `eol`, or `\n` if the file doesn't set one.
+async fn fix_file(file_base: &Path, filename: &Path, eol: Option<Eol>) -> Result<(i32, Vec<u8>)> {
     let file_path = file_base.join(filename);
     let mut file = fs_err::tokio::OpenOptions::new()
         .read(true)
@@ -39,7 +69,11 @@ async fn fix_file(file_base: &Path, filename: &Path) -> Result<(i32, Vec<u8>)> {
         (Some(pos), None) => {
             // File has some content, but no line ending at the end.
             file.seek(SeekFrom::Start(pos + 1)).await?;
-            file.write_all(b"\n").await?;
+            let ending: &[u8] = match eol {
+                Some(Eol::Crlf) => b"\r\n",
+                Some(Eol::Lf) | None => b"\n",
+            };
+            file.write_all(ending).await?;
             file.flush().await?;
             file.shutdown().await?;
             Ok((1, format!("Fixing {}\n", filename.display()).into_bytes()))
@@ -146,7 +180,7 @@ mod tests {
 
         let content = b"line1\nline2\nline3";
         let file_path = create_test_file(&dir, "unix_no_eof.txt", content).await?;
-        let (code, output) = fix_file(Path::new(""), &file_path).await?;
+        let (code, output) = fix_file(Path::new(""), &file_path, None).await?;
         assert_eq!(code, 1, "Should fix the file");
         assert!(output.as_bytes().contains_str("Fixing"));
         let new_content = fs_err::tokio::read(&file_path).await?;
@@ -154,7 +188,7 @@ mod tests {
 
         let content = b"line1\r\nline2\nline3\r\nline4";
         let file_path = create_test_file(&dir, "mixed.txt", content).await?;
-        let (code, output) = fix_file(Path::new(""), &file_path).await?;
+        let (code, output) = fix_file(Path::new(""), &file_path, None).await?;
         assert_eq!(code, 1, "Should fix the file");
         assert!(output.as_bytes().contains_str("Fixing"));
         let new_content = fs_err::tokio::read(&file_path).await?;
@@ -162,7 +196,7 @@ mod tests {
 
         let content = b"line1\r\nline2\r\nline3";
         let file_path = create_test_file(&dir, "windows_no_eof.txt", content).await?;
-        let (code, output) = fix_file(Path::new(""), &file_path).await?;
+        let (code, output) = fix_file(Path::new(""), &file_path, None).await?;
         assert_eq!(code, 1, "Should fix the file");
         assert!(output.as_bytes().contains_str("Fixing"));
         let new_content = fs_err::tokio::read(&file_path).await?;
@@ -178,7 +212,7 @@ mod tests {
         let content = b"line1\r\nline2\r\nline3\r\n";
         let file_path = create_test_file(&dir, "windows_with_eof.txt", content).await?;
 
-        let (code, output) = fix_file(Path::new(""), &file_path).await?;
+        let (code, output) = fix_file(Path::new(""), &file_path, None).await?;
 
         assert_eq!(code, 0, "Should not change the file");
         assert!(output.is_empty());
@@ -196,7 +230,7 @@ mod tests {
         let content = b"line1\nline2\nline3\n";
         let file_path = create_test_file(&dir, "unix_with_eof.txt", content).await?;
 
-        let (code, output) = fix_file(Path::new(""), &file_path).await?;
+        let (code, output) = fix_file(Path::new(""), &file_path, None).await?;
 
         assert_eq!(code, 0, "Should not change the file");
         assert!(output.is_empty());
@@ -214,7 +248,7 @@ mod tests {
         let content = b"";
         let file_path = create_test_file(&dir, "empty.txt", content).await?;
 
-        let (code, output) = fix_file(Path::new(""), &file_path).await?;
+        let (code, output) = fix_file(Path::new(""), &file_path, None).await?;
 
         assert_eq!(code, 0, "Should not change empty file");
         assert!(output.is_empty());
@@ -232,7 +266,7 @@ mod tests {
         let content = b"line1\nline2\n\n\n\n";
         let file_path = create_test_file(&dir, "excess_newlines.txt", content).await?;
 
-        let (code, output) = fix_file(Path::new(""), &file_path).await?;
+        let (code, output) = fix_file(Path::new(""), &file_path, None).await?;
 
         assert_eq!(code, 1, "Should fix the file");
         assert!(output.as_bytes().contains_str("Fixing"));
@@ -250,7 +284,7 @@ mod tests {
         let content = b"line1\r\nline2\r\n\r\n\r\n";
         let file_path = create_test_file(&dir, "excess_crlf.txt", content).await?;
 
-        let (code, output) = fix_file(Path::new(""), &file_path).await?;
+        let (code, output) = fix_file(Path::new(""), &file_path, None).await?;
 
         assert_eq!(code, 1, "Should fix the file");
         assert!(output.as_bytes().contains_str("Fixing"));
@@ -268,7 +302,7 @@ mod tests {
         let content = b"\n\n\n\n";
         let file_path = create_test_file(&dir, "only_newlines.txt", content).await?;
 
-        let (code, output) = fix_file(Path::new(""), &file_path).await?;
+        let (code, output) = fix_file(Path::new(""), &file_path, None).await?;
 
         assert_eq!(code, 1, "Should fix the file");
         assert!(output.as_bytes().contains_str("Fixing"));
@@ -278,4 +312,19 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_no_line_ending_eol_attr() -> Result<()> {
+        let dir = tempdir()?;
+
+        let content = b"line1\r\nline2";
+        let file_path = create_test_file(&dir, "crlf_no_eof.txt", content).await?;
+        let (code, output) = fix_file(Path::new(""), &file_path, Some(Eol::Crlf)).await?;
+        assert_eq!(code, 1, "Should fix the file");
+        assert!(output.as_bytes().contains_str("Fixing"));
+        let new_content = fs_err::tokio::read(&file_path).await?;
+        assert_eq!(new_content, b"line1\r\nline2\r\n");
+
+        Ok(())
+    }
 }
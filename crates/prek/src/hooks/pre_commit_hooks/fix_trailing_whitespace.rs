@@ -40,10 +40,17 @@ struct Args {
     // so, we use Chars to achieve it.
     #[arg(long)]
     chars: Option<Chars>,
+    // Deprecated upstream, accepted for compatibility.
+    #[arg(long, hide = true)]
+    no_markdown_linebreak_ext: bool,
 }
 
 impl Args {
     fn markdown_exts(&self) -> Result<Vec<String>> {
+        if self.markdown_linebreak_ext.iter().any(String::is_empty) {
+            anyhow::bail!("`--markdown-linebreak-ext` requires a non-empty argument");
+        }
+
         let markdown_exts = self
             .markdown_linebreak_ext
             .iter()
@@ -78,28 +85,32 @@ pub(crate) async fn fix_trailing_whitespace(
 
     let force_markdown = args.force_markdown();
     let markdown_exts = args.markdown_exts()?;
-    let chars = if let Some(chars) = args.chars {
-        chars.deref().to_owned()
-    } else {
-        Vec::new()
-    };
+    let chars = args.chars.as_deref().map(Vec::as_slice);
+
+    let (code, output) =
+        run_concurrent_file_checks(filenames.iter().copied(), *CONCURRENCY, |filename| {
+            fix_file(
+                hook.project().relative_path(),
+                filename,
+                chars,
+                force_markdown,
+                &markdown_exts,
+            )
+        })
+        .await?;
 
-    run_concurrent_file_checks(filenames.iter().copied(), *CONCURRENCY, |filename| {
-        fix_file(
-            hook.project().relative_path(),
-            filename,
-            &chars,
-            force_markdown,
-            &markdown_exts,
-        )
-    })
-    .await
+    if args.no_markdown_linebreak_ext {
+        let mut notice = b"--no-markdown-linebreak-ext now does nothing!\n".to_vec();
+        notice.extend(output);
+        return Ok((code, notice));
+    }
+    Ok((code, output))
 }
 
 async fn fix_file(
     file_base: &Path,
     filename: &Path,
-    chars: &[char],
+    chars: Option<&[char]>,
     force_markdown: bool,
     markdown_exts: &[String],
 ) -> Result<(i32, Vec<u8>)> {
@@ -125,11 +136,10 @@ async fn fix_file(
             trimmed = &trimmed[..trimmed.len() - MARKDOWN_LINE_BREAK.len()];
         }
 
-        if chars.is_empty() {
-            trimmed = trimmed.trim_ascii_end();
-        } else {
-            trimmed = trimmed.trim_end_with(|c| chars.contains(&c));
-        }
+        trimmed = match chars {
+            Some(chars) => trimmed.trim_end_with(|c| chars.contains(&c)),
+            None => trim_whitespace_end(trimmed),
+        };
 
         output.extend_from_slice(trimmed);
         if markdown_end {
@@ -149,18 +159,27 @@ async fn fix_file(
     }
 }
 
+/// Lines are split on `\n` only, a lone `\r` is trailing whitespace like upstream.
 fn detect_line_ending(line: &[u8]) -> &[u8] {
     if line.ends_with(b"\r\n") {
         b"\r\n"
     } else if line.ends_with(b"\n") {
         b"\n"
-    } else if line.ends_with(b"\r") {
-        b"\r"
     } else {
         b""
     }
 }
 
+/// Trim the whitespace Python's `bytes.rstrip()` strips, which includes `\x0b` unlike
+/// `u8::is_ascii_whitespace`.
+fn trim_whitespace_end(line: &[u8]) -> &[u8] {
+    let end = line
+        .iter()
+        .rposition(|&b| !matches!(b, b' ' | b'\t' | b'\n' | b'\r' | b'\x0b' | b'\x0c'))
+        .map_or(0, |i| i + 1);
+    &line[..end]
+}
+
 fn needs_markdown_break(is_markdown: bool, trimmed: &[u8]) -> bool {
     is_markdown
         && !trimmed.chars().all(|b| b.is_ascii_whitespace())
@@ -189,7 +208,8 @@ mod tests {
         let chars = vec![' ', '\t'];
         let md_exts = vec![".md".to_string()];
 
-        let (code, msg) = fix_file(Path::new(""), &file_path, &chars, false, &md_exts).await?;
+        let (code, msg) =
+            fix_file(Path::new(""), &file_path, Some(&chars), false, &md_exts).await?;
 
         // modified
         assert_eq!(code, 1);
@@ -217,7 +237,8 @@ mod tests {
         let chars = vec![' ', '\t'];
         let md_exts = vec![".md".to_string()];
 
-        let (code, _msg) = fix_file(Path::new(""), &file_path, &chars, false, &md_exts).await?;
+        let (code, _msg) =
+            fix_file(Path::new(""), &file_path, Some(&chars), false, &md_exts).await?;
 
         // second line changed 3 -> 2 spaces, so modified
         assert_eq!(code, 1);
@@ -243,7 +264,8 @@ mod tests {
         let chars = vec![' ', '\t'];
         let md_exts: Vec<String> = vec![]; // irrelevant because force_markdown = true
 
-        let (code, _msg) = fix_file(Path::new(""), &file_path, &chars, true, &md_exts).await?;
+        let (code, _msg) =
+            fix_file(Path::new(""), &file_path, Some(&chars), true, &md_exts).await?;
 
         // modified because one line had 3 spaces -> reduced to 2
         assert_eq!(code, 1);
@@ -263,7 +285,7 @@ mod tests {
         let md_exts = vec![".md".to_string()];
 
         // file already trimmed -> no changes
-        let (code, msg) = fix_file(Path::new(""), &path, &chars, false, &md_exts).await?;
+        let (code, msg) = fix_file(Path::new(""), &path, Some(&chars), false, &md_exts).await?;
         assert_eq!(code, 0);
         assert!(msg.is_empty());
 
@@ -280,7 +302,7 @@ mod tests {
         let chars = vec![' ', '\t'];
         let md_exts = vec![];
 
-        let (code, msg) = fix_file(Path::new(""), &path, &chars, false, &md_exts).await?;
+        let (code, msg) = fix_file(Path::new(""), &path, Some(&chars), false, &md_exts).await?;
         assert_eq!(code, 0);
         assert!(msg.is_empty());
         let content = fs_err::tokio::read_to_string(&path).await?;
@@ -297,7 +319,7 @@ mod tests {
         let chars = vec![' ', '\t'];
         let md_exts = vec![".md".to_string()];
 
-        let (code, _msg) = fix_file(Path::new(""), &path, &chars, false, &md_exts).await?;
+        let (code, _msg) = fix_file(Path::new(""), &path, Some(&chars), false, &md_exts).await?;
         // trimming whitespace-only lines will change them to empty lines -> modified true
         assert_eq!(code, 1);
 
@@ -309,23 +331,61 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_chars_empty_uses_trim_ascii_end() -> Result<()> {
+    async fn test_default_chars_trim_whitespace() -> Result<()> {
         let dir = TempDir::new()?;
-        // trailing ascii spaces should be removed by trim_ascii_end when chars is empty
-        let path = create_test_file(&dir, "ascii.txt", b"foo   \nbar \t\n").await?;
-        let chars = vec![]; // will hit trim_ascii_end()
+        // without `--chars`, all ASCII whitespace is trimmed, including `\x0b` and a lone `\r`
+        let path = create_test_file(&dir, "ascii.txt", b"foo   \nbar \t\x0b\nbaz \r").await?;
         let md_exts = vec![];
 
-        let (code, _msg) = fix_file(Path::new(""), &path, &chars, false, &md_exts).await?;
+        let (code, _msg) = fix_file(Path::new(""), &path, None, false, &md_exts).await?;
         assert_eq!(code, 1);
 
         let content = fs_err::tokio::read_to_string(&path).await?;
-        let expected = "foo\nbar\n";
+        let expected = "foo\nbar\nbaz";
         assert_eq!(content, expected);
 
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_empty_chars_trims_nothing() -> Result<()> {
+        let dir = TempDir::new()?;
+        // `--chars=` strips nothing, like `bytes.rstrip(b"")`
+        let path = create_test_file(&dir, "empty_chars.txt", b"foo   \n").await?;
+        let md_exts = vec![];
+
+        let (code, _msg) = fix_file(Path::new(""), &path, Some(&[]), false, &md_exts).await?;
+        assert_eq!(code, 0);
+
+        let content = fs_err::tokio::read_to_string(&path).await?;
+        assert_eq!(content, "foo   \n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_args_parity() {
+        let parse = |args: &[&str]| {
+            Args::try_parse_from(["trailing-whitespace"].iter().chain(args)).unwrap()
+        };
+
+        let args = parse(&["--markdown-linebreak-ext=md,.MARKDOWN", "--chars= \t"]);
+        assert_eq!(args.markdown_exts().unwrap(), vec![".md", ".markdown"]);
+        assert_eq!(args.chars.as_deref(), Some(&vec![' ', '\t']));
+
+        assert!(
+            parse(&["--markdown-linebreak-ext="])
+                .markdown_exts()
+                .is_err()
+        );
+        assert!(
+            parse(&["--markdown-linebreak-ext=foo/md"])
+                .markdown_exts()
+                .is_err()
+        );
+        assert!(parse(&["--no-markdown-linebreak-ext"]).no_markdown_linebreak_ext);
+    }
+
     #[tokio::test]
     async fn test_crlf_lines_handling() -> Result<()> {
         let dir = TempDir::new()?;
@@ -334,7 +394,7 @@ mod tests {
         let chars = vec![' ', '\t'];
         let md_exts = vec![".txt".to_string()]; // treat as markdown for this test
 
-        let (code, _msg) = fix_file(Path::new(""), &path, &chars, false, &md_exts).await?;
+        let (code, _msg) = fix_file(Path::new(""), &path, Some(&chars), false, &md_exts).await?;
         assert_eq!(code, 1);
 
         // read file and check logical lines presence (line endings may be normalized by lines())
@@ -353,7 +413,7 @@ mod tests {
         let chars = vec![' ', '\t'];
         let md_exts = vec![];
 
-        let (code, _msg) = fix_file(Path::new(""), &path, &chars, false, &md_exts).await?;
+        let (code, _msg) = fix_file(Path::new(""), &path, Some(&chars), false, &md_exts).await?;
         assert_eq!(code, 1);
 
         let content = fs_err::tokio::read_to_string(&path).await?;
@@ -371,7 +431,7 @@ mod tests {
         let chars = vec!['。', '　'];
         let md_exts = vec![];
 
-        let (code, _msg) = fix_file(Path::new(""), &path, &chars, false, &md_exts).await?;
+        let (code, _msg) = fix_file(Path::new(""), &path, Some(&chars), false, &md_exts).await?;
         assert_eq!(code, 1);
 
         let content = fs_err::tokio::read_to_string(&path).await?;
@@ -388,7 +448,7 @@ mod tests {
         let chars = vec![' ', '\t'];
         let md_exts = vec![".md".to_string()];
 
-        let (code, _msg) = fix_file(Path::new(""), &path, &chars, false, &md_exts).await?;
+        let (code, _msg) = fix_file(Path::new(""), &path, Some(&chars), false, &md_exts).await?;
         assert_eq!(code, 1);
 
         let content = fs_err::tokio::read_to_string(&path).await?;
@@ -405,7 +465,7 @@ mod tests {
         let chars = vec![' ', '\t'];
         let md_exts = vec![];
 
-        let (code, _msg) = fix_file(Path::new(""), &path, &chars, false, &md_exts).await?;
+        let (code, _msg) = fix_file(Path::new(""), &path, Some(&chars), false, &md_exts).await?;
         assert_eq!(code, 1);
 
         let content = fs_err::tokio::read_to_string(&path).await?;
@@ -423,7 +483,7 @@ mod tests {
         let chars = vec![' ', '\t'];
         let md_exts = vec![];
 
-        let (code, msg) = fix_file(Path::new(""), &path, &chars, false, &md_exts).await?;
+        let (code, msg) = fix_file(Path::new(""), &path, Some(&chars), false, &md_exts).await?;
         assert_eq!(code, 0);
         assert!(msg.is_empty());
 
@@ -441,7 +501,7 @@ mod tests {
         let chars = vec![' ', '\t'];
         let md_exts = vec!["*".to_string()];
 
-        let (code, _msg) = fix_file(Path::new(""), &path, &chars, true, &md_exts).await?;
+        let (code, _msg) = fix_file(Path::new(""), &path, Some(&chars), true, &md_exts).await?;
         assert_eq!(code, 1);
 
         let expected = "foo  \nbar\nbaz  \n\n\n";
@@ -458,7 +518,7 @@ mod tests {
         let chars = vec![' '];
         let md_exts = vec!["*".to_string()];
 
-        let (code, _msg) = fix_file(Path::new(""), &path, &chars, true, &md_exts).await?;
+        let (code, _msg) = fix_file(Path::new(""), &path, Some(&chars), true, &md_exts).await?;
         assert_eq!(code, 1);
 
         let expected = "\ta \t  \n";
@@ -475,7 +535,7 @@ mod tests {
         let chars = vec!['x'];
         let md_exts = vec![];
 
-        let (code, _msg) = fix_file(Path::new(""), &path, &chars, true, &md_exts).await?;
+        let (code, _msg) = fix_file(Path::new(""), &path, Some(&chars), true, &md_exts).await?;
         assert_eq!(code, 0);
 
         let expected = "a\nb\r\r\r\n";
@@ -492,7 +552,7 @@ mod tests {
         let chars = vec!['x'];
         let md_exts = vec!["md".to_string()];
 
-        let (code, _msg) = fix_file(Path::new(""), &path, &chars, true, &md_exts).await?;
+        let (code, _msg) = fix_file(Path::new(""), &path, Some(&chars), true, &md_exts).await?;
         assert_eq!(code, 1);
 
         let expected = "a  \n";
@@ -511,7 +571,7 @@ mod tests {
         let chars = vec![' ', '\t'];
         let md_exts: Vec<String> = vec![];
 
-        let (code, _msg) = fix_file(Path::new(""), &path, &chars, false, &md_exts).await?;
+        let (code, _msg) = fix_file(Path::new(""), &path, Some(&chars), false, &md_exts).await?;
         assert_eq!(code, 0);
 
         let new_content = fs_err::tokio::read(&path).await?;
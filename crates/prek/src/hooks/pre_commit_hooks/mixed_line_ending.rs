@@ -1,10 +1,11 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use bstr::ByteSlice;
 use clap::{Parser, ValueEnum};
 use rustc_hash::FxHashMap;
 
+use crate::git::{Eol, get_eol_attrs};
 use crate::hook::Hook;
 use crate::hooks::run_concurrent_file_checks;
 use crate::run::CONCURRENCY;
@@ -23,6 +24,9 @@ struct Args {
     /// or a specified line ending.
     #[clap(long, short, value_enum, default_value_t = FixMode::Auto)]
     fix: FixMode,
+    /// Convert files that set the `eol` attribute in `.gitattributes` to that line ending.
+    #[clap(long)]
+    respect_gitattributes: bool,
 }
 
 #[derive(Copy, Clone, Debug, Default, ValueEnum)]
@@ -44,8 +48,24 @@ enum FixMode {
 pub(crate) async fn mixed_line_ending(hook: &Hook, filenames: &[&Path]) -> Result<(i32, Vec<u8>)> {
     let args = Args::try_parse_from(hook.entry.resolve(None)?.iter().chain(&hook.args))?;
 
+    let file_base = hook.project().relative_path();
+    let eol_attrs = if args.respect_gitattributes && !matches!(args.fix, FixMode::No) {
+        let paths = filenames
+            .iter()
+            .map(|filename| file_base.join(filename))
+            .collect::<Vec<_>>();
+        get_eol_attrs(&paths.iter().map(PathBuf::as_path).collect::<Vec<_>>()).await?
+    } else {
+        FxHashMap::default()
+    };
+
     run_concurrent_file_checks(filenames.iter().copied(), *CONCURRENCY, |filename| {
-        fix_file(hook.project().relative_path(), filename, args.fix)
+        let fix_mode = match eol_attrs.get(&file_base.join(filename)) {
+            Some(Eol::Lf) => FixMode::LF,
+            Some(Eol::Crlf) => FixMode::CRLF,
+            None => args.fix,
+        };
+        fix_file(file_base, filename, fix_mode)
     })
     .await
 }
@@ -7,30 +7,41 @@ use tracing::debug;
 use crate::hook::Hook;
 
 mod check_added_large_files;
+mod check_builtin_literals;
+mod check_byte_order_marker;
 mod check_case_conflict;
+mod check_docstring_first;
 mod check_executables_have_shebangs;
+mod check_illegal_windows_names;
 pub(crate) mod check_json;
 mod check_merge_conflict;
 mod check_symlinks;
 mod check_toml;
 mod check_xml;
 mod check_yaml;
+mod debug_statements;
 mod detect_private_key;
 mod fix_byte_order_marker;
 mod fix_end_of_file;
 mod fix_trailing_whitespace;
 mod mixed_line_ending;
 mod no_commit_to_branch;
+pub(crate) mod python_tokenizer;
 
 pub(crate) use check_added_large_files::check_added_large_files;
+pub(crate) use check_builtin_literals::check_builtin_literals;
+pub(crate) use check_byte_order_marker::check_byte_order_marker;
 pub(crate) use check_case_conflict::check_case_conflict;
+pub(crate) use check_docstring_first::check_docstring_first;
 pub(crate) use check_executables_have_shebangs::check_executables_have_shebangs;
+pub(crate) use check_illegal_windows_names::check_illegal_windows_names;
 pub(crate) use check_json::check_json;
 pub(crate) use check_merge_conflict::check_merge_conflict;
 pub(crate) use check_symlinks::check_symlinks;
 pub(crate) use check_toml::check_toml;
 pub(crate) use check_xml::check_xml;
 pub(crate) use check_yaml::check_yaml;
+pub(crate) use debug_statements::debug_statements;
 pub(crate) use detect_private_key::detect_private_key;
 pub(crate) use fix_byte_order_marker::fix_byte_order_marker;
 pub(crate) use fix_end_of_file::fix_end_of_file;
@@ -41,8 +52,12 @@ pub(crate) use no_commit_to_branch::no_commit_to_branch;
 /// Hooks from `https://github.com/pre-commit/pre-commit-hooks`.
 pub(crate) enum PreCommitHooks {
     CheckAddedLargeFiles,
+    CheckBuiltinLiterals,
+    CheckByteOrderMarker,
     CheckCaseConflict,
+    CheckDocstringFirst,
     CheckExecutablesHaveShebangs,
+    CheckIllegalWindowsNames,
     EndOfFileFixer,
     FixByteOrderMarker,
     CheckJson,
@@ -52,6 +67,7 @@ pub(crate) enum PreCommitHooks {
     CheckXml,
     CheckYaml,
     MixedLineEnding,
+    DebugStatements,
     DetectPrivateKey,
     NoCommitToBranch,
     TrailingWhitespace,
@@ -63,8 +79,12 @@ impl FromStr for PreCommitHooks {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "check-added-large-files" => Ok(Self::CheckAddedLargeFiles),
+            "check-builtin-literals" => Ok(Self::CheckBuiltinLiterals),
+            "check-byte-order-marker" => Ok(Self::CheckByteOrderMarker),
             "check-case-conflict" => Ok(Self::CheckCaseConflict),
+            "check-docstring-first" => Ok(Self::CheckDocstringFirst),
             "check-executables-have-shebangs" => Ok(Self::CheckExecutablesHaveShebangs),
+            "check-illegal-windows-names" => Ok(Self::CheckIllegalWindowsNames),
             "end-of-file-fixer" => Ok(Self::EndOfFileFixer),
             "fix-byte-order-marker" => Ok(Self::FixByteOrderMarker),
             "check-json" => Ok(Self::CheckJson),
@@ -74,6 +94,7 @@ impl FromStr for PreCommitHooks {
             "check-xml" => Ok(Self::CheckXml),
             "check-yaml" => Ok(Self::CheckYaml),
             "mixed-line-ending" => Ok(Self::MixedLineEnding),
+            "debug-statements" => Ok(Self::DebugStatements),
             "detect-private-key" => Ok(Self::DetectPrivateKey),
             "no-commit-to-branch" => Ok(Self::NoCommitToBranch),
             "trailing-whitespace" => Ok(Self::TrailingWhitespace),
@@ -95,10 +116,14 @@ impl PreCommitHooks {
         debug!("Running hook `{}` in fast path", hook.id);
         match self {
             Self::CheckAddedLargeFiles => check_added_large_files(hook, filenames).await,
+            Self::CheckBuiltinLiterals => check_builtin_literals(hook, filenames).await,
+            Self::CheckByteOrderMarker => check_byte_order_marker(hook, filenames).await,
             Self::CheckCaseConflict => check_case_conflict(hook, filenames).await,
+            Self::CheckDocstringFirst => check_docstring_first(hook, filenames).await,
             Self::CheckExecutablesHaveShebangs => {
                 check_executables_have_shebangs(hook, filenames).await
             }
+            Self::CheckIllegalWindowsNames => check_illegal_windows_names(hook, filenames).await,
             Self::EndOfFileFixer => fix_end_of_file(hook, filenames).await,
             Self::FixByteOrderMarker => fix_byte_order_marker(hook, filenames).await,
             Self::CheckJson => check_json(hook, filenames).await,
@@ -108,6 +133,7 @@ impl PreCommitHooks {
             Self::CheckYaml => check_yaml(hook, filenames).await,
             Self::CheckXml => check_xml(hook, filenames).await,
             Self::MixedLineEnding => mixed_line_ending(hook, filenames).await,
+            Self::DebugStatements => debug_statements(hook, filenames).await,
             Self::DetectPrivateKey => detect_private_key(hook, filenames).await,
             Self::NoCommitToBranch => no_commit_to_branch(hook).await,
             Self::TrailingWhitespace => fix_trailing_whitespace(hook, filenames).await,
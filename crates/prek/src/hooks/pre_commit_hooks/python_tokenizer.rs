@@ -0,0 +1,464 @@
+//! A minimal Python tokenizer, enough for hooks that look at the token stream of Python source.
+//!
+//! It follows the token types of Python's `tokenize` module, but doesn't track indentation
+//! (no `INDENT`/`DEDENT` tokens) and keeps f-strings as a single string token.
+
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum TokenKind {
+    Name,
+    Number,
+    String,
+    Op,
+    Comment,
+    /// The end of a logical line.
+    Newline,
+    /// A line break that doesn't end a logical line (blank lines, comments, inside brackets).
+    Nl,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Token<'a> {
+    pub(crate) kind: TokenKind,
+    pub(crate) text: &'a str,
+    /// 1-based line number of the start of the token.
+    pub(crate) line: usize,
+    /// Byte offset of the start of the token in its line.
+    pub(crate) col: usize,
+}
+
+impl Token<'_> {
+    pub(crate) fn is_name(&self, name: &str) -> bool {
+        self.kind == TokenKind::Name && self.text == name
+    }
+
+    pub(crate) fn is_op(&self, op: &str) -> bool {
+        self.kind == TokenKind::Op && self.text == op
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct TokenizeError {
+    pub(crate) line: usize,
+    pub(crate) message: String,
+}
+
+impl TokenizeError {
+    fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for TokenizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (line {})", self.message, self.line)
+    }
+}
+
+impl std::error::Error for TokenizeError {}
+
+/// String prefixes, lowercased. `t` strings are template strings from Python 3.14.
+const STRING_PREFIXES: &[&str] = &["r", "u", "b", "f", "t", "br", "rb", "fr", "rf", "tr", "rt"];
+
+pub(crate) fn tokenize(source: &str) -> Result<Vec<Token<'_>>, TokenizeError> {
+    Tokenizer::new(source).run()
+}
+
+struct Tokenizer<'a> {
+    source: &'a str,
+    pos: usize,
+    line: usize,
+    line_start: usize,
+    /// Open brackets, with the line they were opened on.
+    brackets: Vec<(char, usize)>,
+    /// Whether the current logical line has any code.
+    line_has_code: bool,
+    tokens: Vec<Token<'a>>,
+}
+
+impl<'a> Tokenizer<'a> {
+    fn new(source: &'a str) -> Self {
+        let pos = if source.starts_with('\u{feff}') {
+            '\u{feff}'.len_utf8()
+        } else {
+            0
+        };
+        Self {
+            source,
+            pos,
+            line: 1,
+            line_start: pos,
+            brackets: Vec::new(),
+            line_has_code: false,
+            tokens: Vec::new(),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.source[self.pos..].chars().next()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.source[self.pos..].chars().nth(offset)
+    }
+
+    fn push(&mut self, kind: TokenKind, start: usize, line: usize, line_start: usize) {
+        if !matches!(
+            kind,
+            TokenKind::Comment | TokenKind::Newline | TokenKind::Nl
+        ) {
+            self.line_has_code = true;
+        }
+        self.tokens.push(Token {
+            kind,
+            text: &self.source[start..self.pos],
+            line,
+            col: start - line_start,
+        });
+    }
+
+    /// Consume a line break at the current position, if any.
+    fn newline(&mut self) -> bool {
+        let len = if self.source[self.pos..].starts_with("\r\n") {
+            2
+        } else if matches!(self.peek(), Some('\n' | '\r')) {
+            1
+        } else {
+            return false;
+        };
+        self.pos += len;
+        self.line += 1;
+        self.line_start = self.pos;
+        true
+    }
+
+    fn run(mut self) -> Result<Vec<Token<'a>>, TokenizeError> {
+        while let Some(c) = self.peek() {
+            let (start, line, line_start) = (self.pos, self.line, self.line_start);
+            match c {
+                ' ' | '\t' | '\x0c' => self.pos += 1,
+                '\n' | '\r' => {
+                    self.newline();
+                    let kind = if self.brackets.is_empty() && self.line_has_code {
+                        TokenKind::Newline
+                    } else {
+                        TokenKind::Nl
+                    };
+                    self.tokens.push(Token {
+                        kind,
+                        text: &self.source[start..self.pos],
+                        line,
+                        col: start - line_start,
+                    });
+                    if kind == TokenKind::Newline {
+                        self.line_has_code = false;
+                    }
+                }
+                '\\' => {
+                    self.pos += 1;
+                    if !self.newline() {
+                        return Err(TokenizeError::new(
+                            line,
+                            "unexpected character after line continuation",
+                        ));
+                    }
+                }
+                '#' => {
+                    let end = self.source[self.pos..]
+                        .find(['\n', '\r'])
+                        .map_or(self.source.len(), |i| self.pos + i);
+                    self.pos = end;
+                    self.push(TokenKind::Comment, start, line, line_start);
+                }
+                '\'' | '"' => {
+                    self.string(false)?;
+                    self.push(TokenKind::String, start, line, line_start);
+                }
+                c if c.is_ascii_digit()
+                    || (c == '.' && self.peek_at(1).is_some_and(|c| c.is_ascii_digit())) =>
+                {
+                    self.number();
+                    self.push(TokenKind::Number, start, line, line_start);
+                }
+                c if c == '_' || c.is_alphabetic() => {
+                    while self.peek().is_some_and(|c| c == '_' || c.is_alphanumeric()) {
+                        self.pos += self.peek().map_or(1, char::len_utf8);
+                    }
+                    let name = &self.source[start..self.pos];
+                    if matches!(self.peek(), Some('\'' | '"'))
+                        && STRING_PREFIXES.contains(&name.to_ascii_lowercase().as_str())
+                    {
+                        let formatted = name.contains(['f', 'F', 't', 'T']);
+                        self.string(formatted)?;
+                        self.push(TokenKind::String, start, line, line_start);
+                    } else {
+                        self.push(TokenKind::Name, start, line, line_start);
+                    }
+                }
+                '(' | '[' | '{' => {
+                    self.pos += 1;
+                    self.brackets.push((c, line));
+                    self.push(TokenKind::Op, start, line, line_start);
+                }
+                ')' | ']' | '}' => {
+                    let open = match c {
+                        ')' => '(',
+                        ']' => '[',
+                        _ => '{',
+                    };
+                    match self.brackets.pop() {
+                        Some((bracket, _)) if bracket == open => {}
+                        Some((bracket, _)) => {
+                            return Err(TokenizeError::new(
+                                line,
+                                format!(
+                                    "closing parenthesis '{c}' does not match opening parenthesis '{bracket}'"
+                                ),
+                            ));
+                        }
+                        None => return Err(TokenizeError::new(line, format!("unmatched '{c}'"))),
+                    }
+                    self.pos += 1;
+                    self.push(TokenKind::Op, start, line, line_start);
+                }
+                c if "+-*/%@&|^~<>=!.,:;".contains(c) => {
+                    self.pos += 1;
+                    self.push(TokenKind::Op, start, line, line_start);
+                }
+                c => {
+                    return Err(TokenizeError::new(line, format!("invalid character '{c}'")));
+                }
+            }
+        }
+
+        if let Some((bracket, line)) = self.brackets.first() {
+            return Err(TokenizeError::new(
+                *line,
+                format!("'{bracket}' was never closed"),
+            ));
+        }
+        Ok(self.tokens)
+    }
+
+    fn number(&mut self) {
+        let mut prev = '\0';
+        while let Some(c) = self.peek() {
+            let exponent_sign = matches!(c, '+' | '-') && matches!(prev, 'e' | 'E');
+            if !(c.is_ascii_alphanumeric() || c == '_' || c == '.' || exponent_sign) {
+                break;
+            }
+            // `0xE+1` is an addition, not an exponent.
+            if exponent_sign && self.is_hex_literal() {
+                break;
+            }
+            prev = c;
+            self.pos += 1;
+        }
+    }
+
+    /// Whether the number being scanned is a hex literal.
+    fn is_hex_literal(&self) -> bool {
+        let start = self.source[..self.pos]
+            .rfind(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.'))
+            .map_or(0, |i| i + 1);
+        let number = &self.source[start..self.pos];
+        number.starts_with("0x") || number.starts_with("0X")
+    }
+
+    /// Scan a string literal starting at the opening quote.
+    fn string(&mut self, formatted: bool) -> Result<(), TokenizeError> {
+        let line = self.line;
+        let quote = self.peek().unwrap();
+        let triple = self.peek_at(1) == Some(quote) && self.peek_at(2) == Some(quote);
+        self.pos += if triple { 3 } else { 1 };
+
+        loop {
+            let Some(c) = self.peek() else {
+                let message = if triple {
+                    "unterminated triple-quoted string literal"
+                } else {
+                    "unterminated string literal"
+                };
+                return Err(TokenizeError::new(line, message));
+            };
+            match c {
+                '\\' => {
+                    self.pos += 1;
+                    if !self.newline() {
+                        self.pos += self.peek().map_or(0, char::len_utf8);
+                    }
+                }
+                '\n' | '\r' => {
+                    if !triple {
+                        return Err(TokenizeError::new(line, "unterminated string literal"));
+                    }
+                    self.newline();
+                }
+                '{' if formatted => {
+                    self.pos += 1;
+                    if self.peek() == Some('{') {
+                        self.pos += 1;
+                    } else {
+                        self.replacement_field(line)?;
+                    }
+                }
+                c if c == quote => {
+                    if !triple {
+                        self.pos += 1;
+                        return Ok(());
+                    }
+                    if self.peek_at(1) == Some(quote) && self.peek_at(2) == Some(quote) {
+                        self.pos += 3;
+                        return Ok(());
+                    }
+                    self.pos += 1;
+                }
+                c => self.pos += c.len_utf8(),
+            }
+        }
+    }
+
+    /// Skip the expression of an f-string replacement field, up to and including its closing `}`.
+    fn replacement_field(&mut self, line: usize) -> Result<(), TokenizeError> {
+        let mut depth = 0usize;
+        loop {
+            let Some(c) = self.peek() else {
+                return Err(TokenizeError::new(line, "f-string: expecting '}'"));
+            };
+            match c {
+                '{' | '(' | '[' => {
+                    depth += 1;
+                    self.pos += 1;
+                }
+                ')' | ']' => {
+                    depth = depth.saturating_sub(1);
+                    self.pos += 1;
+                }
+                '}' => {
+                    self.pos += 1;
+                    if depth == 0 {
+                        return Ok(());
+                    }
+                    depth -= 1;
+                }
+                '\'' | '"' => {
+                    // Nested strings may have a prefix, which was already skipped as part of the expression.
+                    let prefix_start = self.source[..self.pos]
+                        .rfind(|c: char| !c.is_ascii_alphabetic())
+                        .map_or(0, |i| i + 1);
+                    let formatted =
+                        self.source[prefix_start..self.pos].contains(['f', 'F', 't', 'T']);
+                    self.string(formatted)?;
+                }
+                '\n' | '\r' => {
+                    self.newline();
+                }
+                c => self.pos += c.len_utf8(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(source: &str) -> Vec<(TokenKind, &str)> {
+        tokenize(source)
+            .unwrap()
+            .into_iter()
+            .map(|token| (token.kind, token.text))
+            .collect()
+    }
+
+    #[test]
+    fn simple_statements() {
+        assert_eq!(
+            kinds("import pdb  # debug\nx = 1.5e-3\n"),
+            [
+                (TokenKind::Name, "import"),
+                (TokenKind::Name, "pdb"),
+                (TokenKind::Comment, "# debug"),
+                (TokenKind::Newline, "\n"),
+                (TokenKind::Name, "x"),
+                (TokenKind::Op, "="),
+                (TokenKind::Number, "1.5e-3"),
+                (TokenKind::Newline, "\n"),
+            ]
+        );
+    }
+
+    #[test]
+    fn newlines_in_brackets_and_blank_lines() {
+        assert_eq!(
+            kinds("\n# comment\nf(\n  a,\n)\n"),
+            [
+                (TokenKind::Nl, "\n"),
+                (TokenKind::Comment, "# comment"),
+                (TokenKind::Nl, "\n"),
+                (TokenKind::Name, "f"),
+                (TokenKind::Op, "("),
+                (TokenKind::Nl, "\n"),
+                (TokenKind::Name, "a"),
+                (TokenKind::Op, ","),
+                (TokenKind::Nl, "\n"),
+                (TokenKind::Op, ")"),
+                (TokenKind::Newline, "\n"),
+            ]
+        );
+    }
+
+    #[test]
+    fn strings() {
+        let source = "'''doc\n\"quoted\"\n'''\nrb'\\''\nf\"{x[\"a\"]!r:>{width}} {{\"\n";
+        let tokens = tokenize(source).unwrap();
+        let strings = tokens
+            .iter()
+            .filter(|token| token.kind == TokenKind::String)
+            .map(|token| (token.text, token.line, token.col))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            strings,
+            [
+                ("'''doc\n\"quoted\"\n'''", 1, 0),
+                ("rb'\\''", 4, 0),
+                ("f\"{x[\"a\"]!r:>{width}} {{\"", 5, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn line_continuation() {
+        let tokens = tokenize("x = 1 + \\\n    2\ny").unwrap();
+        let y = tokens.last().unwrap();
+        assert_eq!((y.text, y.line, y.col), ("y", 3, 0));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            tokenize("x = 'abc\n").unwrap_err().to_string(),
+            "unterminated string literal (line 1)"
+        );
+        assert_eq!(
+            tokenize("x = \"\"\"abc\n").unwrap_err().to_string(),
+            "unterminated triple-quoted string literal (line 1)"
+        );
+        assert_eq!(
+            tokenize("f(\n  a,\n").unwrap_err().to_string(),
+            "'(' was never closed (line 1)"
+        );
+        assert_eq!(
+            tokenize("x)").unwrap_err().to_string(),
+            "unmatched ')' (line 1)"
+        );
+        assert_eq!(
+            tokenize("x = $").unwrap_err().to_string(),
+            "invalid character '$' (line 1)"
+        );
+    }
+}
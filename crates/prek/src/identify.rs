@@ -65,6 +65,14 @@ impl TagSet {
         self.0.is_empty()
     }
 
+    /// Build a tag set from tag names, returning `None` if any of them is not a known tag.
+    pub(crate) fn from_names<'a>(names: impl IntoIterator<Item = &'a str>) -> Option<Self> {
+        names
+            .into_iter()
+            .map(|name| all_tags().get(name).copied())
+            .collect()
+    }
+
     fn with_added(mut self, extra: &[&'static str]) -> Self {
         self.extend_from_iter(extra.iter().copied());
         self
@@ -648,8 +656,17 @@ fn is_encoding_tag(tag: &str) -> bool {
     matches!(tag, tags::TEXT | tags::BINARY)
 }
 
+/// The number of bytes read from the start of a file to classify it as text or binary.
+pub(crate) const DEFAULT_BINARY_DETECTION_BYTES: usize = 1024;
+
 /// Identify tags for a file at the given path.
 pub(crate) fn tags_from_path(path: &Path) -> Result<TagSet> {
+    tags_from_path_with(path, DEFAULT_BINARY_DETECTION_BYTES)
+}
+
+/// Identify tags for a file at the given path, reading `detection_bytes` bytes to classify it
+/// as text or binary.
+pub(crate) fn tags_from_path_with(path: &Path, detection_bytes: usize) -> Result<TagSet> {
     let metadata = std::fs::symlink_metadata(path)?;
     if metadata.is_dir() {
         return Ok(TagSet::from([tags::DIRECTORY]));
@@ -704,7 +721,7 @@ pub(crate) fn tags_from_path(path: &Path) -> Result<TagSet> {
     }
 
     if !tags.iter().any(is_encoding_tag) {
-        if is_text_file(path) {
+        if is_text_file(path, detection_bytes) {
             tags.insert(tags::TEXT);
         } else {
             tags.insert(tags::BINARY);
@@ -903,24 +920,25 @@ fn is_text_char(b: u8) -> bool {
     (IS_TEXT_CHAR[idx / 32] & (1 << (idx % 32))) != 0
 }
 
-/// Return whether the first KB of contents seems to be binary.
+/// Return whether the first `detection_bytes` of contents seem to be text.
 ///
 /// This is roughly based on libmagic's binary/text detection:
 /// <https://github.com/file/file/blob/df74b09b9027676088c797528edcaae5a9ce9ad0/src/encoding.c#L203-L228>
-fn is_text_file(path: &Path) -> bool {
-    let mut buffer = [0; 1024];
-    let Ok(mut file) = fs_err::File::open(path) else {
+fn is_text_file(path: &Path, detection_bytes: usize) -> bool {
+    let Ok(file) = fs_err::File::open(path) else {
         return false;
     };
 
-    let Ok(bytes_read) = file.read(&mut buffer) else {
+    let mut buffer = Vec::with_capacity(detection_bytes);
+    if file
+        .take(detection_bytes as u64)
+        .read_to_end(&mut buffer)
+        .is_err()
+    {
         return false;
-    };
-    if bytes_read == 0 {
-        return true;
     }
 
-    buffer[..bytes_read].iter().all(|&b| is_text_char(b))
+    buffer.iter().all(|&b| is_text_char(b))
 }
 
 pub fn all_tags() -> &'static FxHashSet<&'static str> {
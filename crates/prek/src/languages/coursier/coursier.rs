@@ -0,0 +1,212 @@
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use prek_consts::env_vars::EnvVars;
+use prek_consts::prepend_paths;
+use tracing::debug;
+
+use crate::cli::reporter::{HookInstallReporter, HookRunReporter};
+use crate::hook::{Hook, InstallInfo, InstalledHook};
+use crate::languages::LanguageImpl;
+use crate::languages::coursier::JdkRequest;
+use crate::languages::coursier::installer::JdkInstaller;
+use crate::languages::version::LanguageRequest;
+use crate::offline;
+use crate::process::Cmd;
+use crate::run::{Batch, run_by_batch};
+use crate::store::{Store, ToolBucket};
+
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Coursier;
+
+impl LanguageImpl for Coursier {
+    async fn install(
+        &self,
+        hook: Arc<Hook>,
+        store: &Store,
+        reporter: &HookInstallReporter,
+    ) -> Result<InstalledHook> {
+        let progress = reporter.on_install_start(&hook);
+
+        // Like `pre-commit`, install the apps of the `.pre-commit-channel` directory of the hook
+        // repository, and `additional_dependencies`.
+        let channel = hook
+            .repo_path()
+            .map(|repo| repo.join(".pre-commit-channel"))
+            .filter(|channel| channel.is_dir());
+        if channel.is_none() && hook.additional_dependencies.is_empty() {
+            anyhow::bail!(
+                "Expected a `.pre-commit-channel` directory or `additional_dependencies`"
+            );
+        }
+
+        // 1. Install the JDK the launchers run on
+        let installer = JdkInstaller::new(store.tools_path(ToolBucket::Jdk));
+        let (request, allows_download) = match &hook.language_request {
+            LanguageRequest::Any { system_only } => (&JdkRequest::Any, !system_only),
+            LanguageRequest::Coursier(request) => (request, true),
+            _ => unreachable!(),
+        };
+        let jdk = installer
+            .install(store, request, allows_download)
+            .await
+            .context("Failed to install JDK")?;
+
+        let mut info = InstallInfo::for_hook(&hook, store)?;
+        info.with_toolchain(jdk.java())
+            .with_language_version(jdk.version().clone());
+
+        debug!(%hook, target = %info.env_path.display(), %jdk, "Installing coursier environment");
+
+        // 2. Install the apps into the environment
+        let cs = find_coursier()?;
+        fs_err::tokio::create_dir_all(&info.env_path).await?;
+
+        offline::ensure_online(|| format!("Installing coursier apps for hook `{hook}`"))?;
+
+        let new_path =
+            prepend_paths(&[&java_bin_dir(&info.toolchain)]).context("Failed to join PATH")?;
+        let cs_install = || {
+            let mut cmd = Cmd::new(&cs, "cs install");
+            cmd.arg("install")
+                .arg("--dir")
+                .arg(&info.env_path)
+                .env(EnvVars::PATH, &new_path)
+                .env(EnvVars::JAVA_HOME, jdk.home())
+                .env(EnvVars::COURSIER_CACHE, cache_dir(&info.env_path))
+                .check(true);
+            cmd
+        };
+
+        if let Some(channel) = &channel {
+            for entry in fs_err::read_dir(channel)? {
+                let entry = entry?;
+                let Some(app) = entry.path().file_stem().map(ToOwned::to_owned) else {
+                    continue;
+                };
+                cs_install()
+                    .arg("--default-channels=false")
+                    .arg("--channel")
+                    .arg(channel)
+                    .arg(app)
+                    .output()
+                    .await
+                    .context("Failed to install coursier app from `.pre-commit-channel`")?;
+            }
+        }
+        if !hook.additional_dependencies.is_empty() {
+            cs_install()
+                .args(&hook.additional_dependencies)
+                .output()
+                .await
+                .context("Failed to install coursier apps")?;
+        }
+
+        info.persist_env_path();
+
+        reporter.on_install_complete(progress);
+
+        Ok(InstalledHook::Installed {
+            hook,
+            info: Arc::new(info),
+        })
+    }
+
+    async fn check_health(&self, info: &InstallInfo) -> Result<()> {
+        if !info.toolchain.exists() {
+            anyhow::bail!(
+                "JDK not found at `{}`",
+                java_home(&info.toolchain).display()
+            );
+        }
+        Ok(())
+    }
+
+    async fn run(
+        &self,
+        hook: &InstalledHook,
+        filenames: &[&Path],
+        _store: &Store,
+        reporter: &HookRunReporter,
+    ) -> Result<(i32, Vec<u8>)> {
+        let progress = reporter.on_run_start(hook, filenames.len());
+
+        let env_dir = hook.env_path().expect("Coursier must have env path");
+        let info = hook
+            .install_info()
+            .expect("Coursier hook must be installed");
+
+        // The launchers installed by `cs install` run on the `java` of `JAVA_HOME`.
+        let new_path = prepend_paths(&[env_dir, &java_bin_dir(&info.toolchain)])
+            .context("Failed to join PATH")?;
+        let java_home = java_home(&info.toolchain);
+        let cache_dir = cache_dir(env_dir);
+        let entry = hook.entry.resolve(Some(&new_path))?;
+
+        let run = async |batch: &Batch<'_>| {
+            let mut output = Cmd::new(&entry[0], "run coursier command")
+                .current_dir(hook.work_dir())
+                .args(&entry[1..])
+                .env(EnvVars::PATH, &new_path)
+                .env(EnvVars::JAVA_HOME, &java_home)
+                .env(EnvVars::COURSIER_CACHE, &cache_dir)
+                .envs(&hook.env)
+                .args(batch.args())
+                .check(false)
+                .stdin(Stdio::null())
+                .stdin_data(hook.stdin_data())
+                .process_tree(true)
+                .pty_output()
+                .await?;
+
+            reporter.on_run_progress(progress, batch.len() as u64);
+
+            output.stdout.extend(output.stderr);
+            let code = output.status.code().unwrap_or(1);
+            anyhow::Ok((code, output.stdout))
+        };
+
+        let results = run_by_batch(hook, filenames, &entry, run).await?;
+
+        reporter.on_run_complete(progress);
+
+        let mut combined_status = 0;
+        let mut combined_output = Vec::new();
+
+        for (code, output) in results {
+            combined_status |= code;
+            combined_output.extend(output);
+        }
+
+        Ok((combined_status, combined_output))
+    }
+}
+
+/// Find the coursier CLI, installed as `cs` or `coursier`.
+fn find_coursier() -> Result<PathBuf> {
+    which::which("cs")
+        .or_else(|_| which::which("coursier"))
+        .context("Neither `cs` nor `coursier` was found on PATH")
+}
+
+/// The `bin` directory of the JDK a `java` executable belongs to.
+fn java_bin_dir(java: &Path) -> PathBuf {
+    java.parent()
+        .expect("java should be in a bin directory")
+        .to_path_buf()
+}
+
+/// The `JAVA_HOME` of the JDK a `java` executable belongs to.
+fn java_home(java: &Path) -> PathBuf {
+    java.parent()
+        .and_then(Path::parent)
+        .expect("java should be in a JDK home")
+        .to_path_buf()
+}
+
+/// The coursier cache of the environment, like `pre-commit` keeps it.
+fn cache_dir(env_path: &Path) -> PathBuf {
+    env_path.join(".cs-cache")
+}
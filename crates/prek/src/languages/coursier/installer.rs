@@ -0,0 +1,347 @@
+use std::env::consts::EXE_EXTENSION;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use itertools::Itertools;
+use prek_consts::env_vars::EnvVars;
+use target_lexicon::{Architecture, HOST, OperatingSystem};
+use tracing::{debug, trace, warn};
+
+use crate::fs::LockedFile;
+use crate::languages::coursier::JdkRequest;
+use crate::languages::coursier::version::parse_java_version;
+use crate::languages::{REQWEST_CLIENT, download_and_extract};
+use crate::offline;
+use crate::process::Cmd;
+use crate::store::Store;
+
+/// The Adoptium API serving Eclipse Temurin builds.
+const ADOPTIUM_API: &str = "https://api.adoptium.net/v3";
+
+pub(crate) struct JdkResult {
+    home: PathBuf,
+    version: semver::Version,
+}
+
+impl Display for JdkResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}@{}", self.home.display(), self.version)?;
+        Ok(())
+    }
+}
+
+impl JdkResult {
+    fn from_home(home: PathBuf) -> Self {
+        Self {
+            home,
+            version: semver::Version::new(0, 0, 0),
+        }
+    }
+
+    /// A JDK extracted from a Temurin archive, which nests the JDK home in `Contents/Home` on macOS.
+    fn from_dir(dir: &Path) -> Self {
+        let macos_home = dir.join("Contents").join("Home");
+        if macos_home.is_dir() {
+            Self::from_home(macos_home)
+        } else {
+            Self::from_home(dir.to_path_buf())
+        }
+    }
+
+    /// The `JAVA_HOME` of the JDK.
+    pub(crate) fn home(&self) -> &Path {
+        &self.home
+    }
+
+    pub(crate) fn java(&self) -> PathBuf {
+        java_bin(&self.home)
+    }
+
+    pub(crate) fn version(&self) -> &semver::Version {
+        &self.version
+    }
+
+    fn with_version(mut self, version: semver::Version) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Read the version from the `release` file of the JDK, or ask `java` if it has none.
+    async fn fill_version(mut self) -> Result<Self> {
+        let release = fs_err::tokio::read_to_string(self.home.join("release"))
+            .await
+            .ok()
+            .and_then(|release| {
+                release
+                    .lines()
+                    .find_map(|line| parse_java_version(line.strip_prefix("JAVA_VERSION=")?))
+            });
+        if let Some(version) = release {
+            self.version = version;
+            return Ok(self);
+        }
+
+        let output = Cmd::new(self.java(), "java -version")
+            .arg("-version")
+            .check(true)
+            .output()
+            .await?;
+        // e.g. `openjdk version "21.0.4" 2024-07-16`, printed to stderr
+        let output = String::from_utf8_lossy(&output.stderr);
+        self.version = output
+            .lines()
+            .next()
+            .and_then(|line| line.split('"').nth(1))
+            .and_then(parse_java_version)
+            .with_context(|| format!("Failed to parse Java version from output: {output}"))?;
+        Ok(self)
+    }
+}
+
+/// The `java` executable of a JDK home.
+pub(crate) fn java_bin(home: &Path) -> PathBuf {
+    home.join("bin").join("java").with_extension(EXE_EXTENSION)
+}
+
+#[derive(serde::Deserialize)]
+struct AvailableReleases {
+    most_recent_lts: u64,
+}
+
+#[derive(serde::Deserialize)]
+struct Release {
+    binaries: Vec<Binary>,
+    version_data: VersionData,
+}
+
+#[derive(serde::Deserialize)]
+struct Binary {
+    package: Package,
+}
+
+#[derive(serde::Deserialize)]
+struct Package {
+    name: String,
+    link: String,
+}
+
+#[derive(serde::Deserialize)]
+struct VersionData {
+    major: u64,
+    minor: u64,
+    security: u64,
+}
+
+impl VersionData {
+    fn version(&self) -> semver::Version {
+        semver::Version::new(self.major, self.minor, self.security)
+    }
+}
+
+pub(crate) struct JdkInstaller {
+    root: PathBuf,
+}
+
+impl JdkInstaller {
+    pub(crate) fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    pub(crate) async fn install(
+        &self,
+        store: &Store,
+        request: &JdkRequest,
+        allows_download: bool,
+    ) -> Result<JdkResult> {
+        fs_err::tokio::create_dir_all(&self.root).await?;
+
+        let _lock = LockedFile::acquire(self.root.join(".lock"), "jdk").await?;
+
+        if let Ok(jdk) = self.find_installed(request) {
+            trace!(%jdk, "Found installed JDK");
+            return Ok(jdk);
+        }
+
+        if let Some(jdk) = Self::find_system_jdk(request).await? {
+            trace!(%jdk, "Using system JDK");
+            return Ok(jdk);
+        }
+
+        if !allows_download {
+            anyhow::bail!("No suitable system JDK found and downloads are disabled");
+        }
+        offline::ensure_cached(|| "A JDK matching the request".to_string())?;
+
+        let release = Self::resolve_release(request)
+            .await
+            .context("Failed to resolve JDK version")?;
+        trace!(version = %release.version_data.version(), "Installing JDK");
+
+        self.download(store, &release).await
+    }
+
+    fn find_installed(&self, request: &JdkRequest) -> Result<JdkResult> {
+        let mut installed = fs_err::read_dir(&self.root)
+            .ok()
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| match entry {
+                Ok(entry) => Some(entry),
+                Err(e) => {
+                    warn!(?e, "Failed to read entry");
+                    None
+                }
+            })
+            .filter(|entry| entry.file_type().is_ok_and(|f| f.is_dir()))
+            .filter_map(|entry| {
+                let dir_name = entry.file_name();
+                let version = semver::Version::parse(&dir_name.to_string_lossy()).ok()?;
+                Some((version, entry.path()))
+            })
+            .sorted_unstable_by(|(a, _), (b, _)| a.cmp(b))
+            .rev();
+
+        installed
+            .find_map(|(version, path)| {
+                let jdk = JdkResult::from_dir(&path);
+                if request.matches(&version, Some(&jdk.java())) {
+                    trace!(%version, "Found matching installed JDK");
+                    Some(jdk.with_version(version))
+                } else {
+                    trace!(%version, "Installed JDK does not match request");
+                    None
+                }
+            })
+            .context("No installed JDK version matches the request")
+    }
+
+    /// Find a JDK from `JAVA_HOME`, the requested path, or a `java` on `PATH`.
+    async fn find_system_jdk(request: &JdkRequest) -> Result<Option<JdkResult>> {
+        let mut homes = Vec::new();
+        if let JdkRequest::Path(path) = request {
+            // Either the JDK home or its `java` executable.
+            if path.is_file() {
+                homes.extend(path.parent().and_then(Path::parent).map(Path::to_path_buf));
+            } else {
+                homes.push(path.clone());
+            }
+        }
+        if let Some(java_home) = EnvVars::var_os(EnvVars::JAVA_HOME) {
+            homes.push(PathBuf::from(java_home));
+        }
+        match which::which_all("java") {
+            Ok(paths) => homes.extend(paths.filter_map(|java| {
+                // Resolve symlinks like `/usr/bin/java` to the JDK they belong to.
+                let java = fs_err::canonicalize(java).ok()?;
+                Some(java.parent()?.parent()?.to_path_buf())
+            })),
+            Err(e) => debug!("No java executables found in PATH: {}", e),
+        }
+
+        for home in homes.into_iter().unique() {
+            if !java_bin(&home).is_file() {
+                trace!(home = %home.display(), "No java executable in JDK home");
+                continue;
+            }
+            match JdkResult::from_home(home).fill_version().await {
+                Ok(jdk) => {
+                    if request.matches(&jdk.version, Some(&jdk.java())) {
+                        trace!(%jdk, "Found matching system JDK");
+                        return Ok(Some(jdk));
+                    }
+                    trace!(%jdk, "System JDK does not match requested version");
+                }
+                Err(e) => {
+                    warn!(?e, "Failed to get version for system JDK");
+                }
+            }
+        }
+
+        debug!(?request, "No system JDK matches the requested version");
+        Ok(None)
+    }
+
+    /// Find the latest Temurin release for this platform that matches the request.
+    async fn resolve_release(request: &JdkRequest) -> Result<Release> {
+        let feature = if let Some(feature) = request.feature_release() {
+            feature
+        } else {
+            let url = format!("{ADOPTIUM_API}/info/available_releases");
+            REQWEST_CLIENT
+                .get(&url)
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status)
+                .with_context(|| format!("Failed to list JDK releases from {url}"))?
+                .json::<AvailableReleases>()
+                .await?
+                .most_recent_lts
+        };
+
+        let (os, arch) = platform()?;
+        let url = format!(
+            "{ADOPTIUM_API}/assets/feature_releases/{feature}/ga?os={os}&architecture={arch}&image_type=jdk&jvm_impl=hotspot&vendor=eclipse&page_size=50&sort_order=DESC"
+        );
+        let releases: Vec<Release> = REQWEST_CLIENT
+            .get(&url)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .with_context(|| format!("Failed to list JDK releases from {url}"))?
+            .json()
+            .await?;
+
+        releases
+            .into_iter()
+            .filter(|release| !release.binaries.is_empty())
+            .find(|release| request.matches(&release.version_data.version(), None))
+            .with_context(|| format!("No Temurin JDK release matches `{request}`"))
+    }
+
+    async fn download(&self, store: &Store, release: &Release) -> Result<JdkResult> {
+        let version = release.version_data.version();
+        let package = &release.binaries[0].package;
+        let target = self.root.join(version.to_string());
+
+        download_and_extract(&package.link, &package.name, store, async |extracted| {
+            if target.exists() {
+                debug!(target = %target.display(), "Removing existing JDK");
+                fs_err::tokio::remove_dir_all(&target).await?;
+            }
+
+            debug!(?extracted, target = %target.display(), "Moving JDK to target");
+            fs_err::tokio::rename(extracted, &target).await?;
+
+            anyhow::Ok(())
+        })
+        .await
+        .context("Failed to download and extract JDK")?;
+
+        Ok(JdkResult::from_dir(&target).with_version(version))
+    }
+}
+
+/// The operating system and architecture of this platform, as named by the Adoptium API.
+fn platform() -> Result<(&'static str, &'static str)> {
+    let arch = match HOST.architecture {
+        Architecture::X86_32(_) => "x32",
+        Architecture::X86_64 => "x64",
+        Architecture::Aarch64(_) => "aarch64",
+        Architecture::Arm(_) => "arm",
+        Architecture::S390x => "s390x",
+        Architecture::Powerpc64le => "ppc64le",
+        Architecture::Riscv64(_) => "riscv64",
+        _ => return Err(anyhow::anyhow!("Unsupported architecture")),
+    };
+    let os = match HOST.operating_system {
+        OperatingSystem::Darwin(_) => "mac",
+        OperatingSystem::Linux if cfg!(target_env = "musl") => "alpine-linux",
+        OperatingSystem::Linux => "linux",
+        OperatingSystem::Windows => "windows",
+        OperatingSystem::Aix => "aix",
+        OperatingSystem::Solaris => "solaris",
+        _ => return Err(anyhow::anyhow!("Unsupported OS")),
+    };
+    Ok((os, arch))
+}
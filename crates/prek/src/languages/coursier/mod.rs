@@ -0,0 +1,7 @@
+#[allow(clippy::module_inception)]
+mod coursier;
+mod installer;
+mod version;
+
+pub(crate) use coursier::Coursier;
+pub(crate) use version::JdkRequest;
@@ -0,0 +1,221 @@
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::hook::InstallInfo;
+use crate::languages::version::{Error, try_into_u64_slice};
+
+/// JDK version request parsed from the `language_version` of a coursier hook.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) enum JdkRequest {
+    /// Any available JDK (prefer `JAVA_HOME`, then `PATH`)
+    Any,
+
+    /// Feature release, e.g. `21`
+    Major(u64),
+
+    /// Exact feature.interim.update version, e.g. `21.0.4`
+    Exact(u64, u64, u64),
+
+    /// Explicit path to a JDK home directory or a `java` executable
+    Path(PathBuf),
+
+    /// Semver range (e.g., ">=17, <22")
+    Range(semver::VersionReq, String),
+}
+
+impl FromStr for JdkRequest {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Ok(Self::Any);
+        }
+
+        // Strip "jdk" or "java" prefix if present, e.g. `jdk21` or `java-17`
+        if let Some(version_part) = s.strip_prefix("jdk").or_else(|| s.strip_prefix("java")) {
+            let version_part = version_part.strip_prefix('-').unwrap_or(version_part);
+            if version_part.is_empty() {
+                return Ok(Self::Any);
+            }
+
+            return Self::parse_version_numbers(version_part, s);
+        }
+
+        if let Ok(req) = Self::parse_version_numbers(s, s) {
+            return Ok(req);
+        }
+
+        if let Ok(req) = semver::VersionReq::parse(s) {
+            return Ok(Self::Range(req, s.to_string()));
+        }
+
+        let path = PathBuf::from(s);
+        if path.exists() {
+            return Ok(Self::Path(path));
+        }
+
+        Err(Error::InvalidVersion(s.to_string()))
+    }
+}
+
+impl Display for JdkRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JdkRequest::Any => write!(f, "any"),
+            JdkRequest::Major(major) => write!(f, "{major}"),
+            JdkRequest::Exact(major, minor, patch) => write!(f, "{major}.{minor}.{patch}"),
+            JdkRequest::Path(path) => write!(f, "{}", path.display()),
+            JdkRequest::Range(_, range_str) => write!(f, "{range_str}"),
+        }
+    }
+}
+
+impl JdkRequest {
+    pub(crate) fn is_any(&self) -> bool {
+        matches!(self, Self::Any)
+    }
+
+    fn parse_version_numbers(
+        version_str: &str,
+        original_request: &str,
+    ) -> Result<JdkRequest, Error> {
+        let parts = try_into_u64_slice(version_str)
+            .map_err(|_| Error::InvalidVersion(original_request.to_string()))?;
+
+        match parts.as_slice() {
+            [major] => Ok(JdkRequest::Major(*major)),
+            [major, minor, patch] => Ok(JdkRequest::Exact(*major, *minor, *patch)),
+            _ => Err(Error::InvalidVersion(original_request.to_string())),
+        }
+    }
+
+    /// The feature release to download for this request, `None` for the latest LTS release.
+    pub(crate) fn feature_release(&self) -> Option<u64> {
+        match self {
+            JdkRequest::Major(major) | JdkRequest::Exact(major, _, _) => Some(*major),
+            JdkRequest::Any | JdkRequest::Path(_) | JdkRequest::Range(..) => None,
+        }
+    }
+
+    pub(crate) fn matches(&self, version: &semver::Version, java: Option<&Path>) -> bool {
+        match self {
+            JdkRequest::Any => true,
+            JdkRequest::Major(major) => version.major == *major,
+            JdkRequest::Exact(major, minor, patch) => {
+                version.major == *major && version.minor == *minor && version.patch == *patch
+            }
+            JdkRequest::Path(path) => {
+                java.is_some_and(|java| java == path || java.starts_with(path))
+            }
+            JdkRequest::Range(req, _) => req.matches(version),
+        }
+    }
+
+    pub(crate) fn satisfied_by(&self, install_info: &InstallInfo) -> bool {
+        self.matches(
+            &install_info.language_version,
+            Some(&install_info.toolchain),
+        )
+    }
+}
+
+/// Parse a Java version string, such as `21.0.4`, `17` or the legacy `1.8.0_392`.
+pub(crate) fn parse_java_version(version: &str) -> Option<semver::Version> {
+    let version = version.trim().trim_matches('"');
+    // Drop the build number, e.g. `21.0.4+7`.
+    let version = version.split(['+', '-']).next()?;
+    if let Some(legacy) = version.strip_prefix("1.") {
+        // `1.8.0_392` is Java 8 update 392.
+        let (major, update) = legacy.split_once(".0_").unwrap_or((legacy, "0"));
+        let major = major.split('.').next()?.parse().ok()?;
+        return Some(semver::Version::new(major, 0, update.parse().ok()?));
+    }
+
+    let parts = try_into_u64_slice(version).ok()?;
+    match parts.as_slice() {
+        [major] => Some(semver::Version::new(*major, 0, 0)),
+        [major, minor] => Some(semver::Version::new(*major, *minor, 0)),
+        // `11.0.20.1` has a fourth component for emergency patches.
+        [major, minor, patch, ..] => Some(semver::Version::new(*major, *minor, *patch)),
+        [] => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_jdk_request() {
+        assert_eq!(JdkRequest::from_str("").unwrap(), JdkRequest::Any);
+        assert_eq!(JdkRequest::from_str("jdk").unwrap(), JdkRequest::Any);
+        assert_eq!(JdkRequest::from_str("21").unwrap(), JdkRequest::Major(21));
+        assert_eq!(
+            JdkRequest::from_str("jdk21").unwrap(),
+            JdkRequest::Major(21)
+        );
+        assert_eq!(
+            JdkRequest::from_str("java-17").unwrap(),
+            JdkRequest::Major(17)
+        );
+        assert_eq!(
+            JdkRequest::from_str("21.0.4").unwrap(),
+            JdkRequest::Exact(21, 0, 4)
+        );
+        assert!(matches!(
+            JdkRequest::from_str(">=17, <22").unwrap(),
+            JdkRequest::Range(_, _)
+        ));
+        assert!(JdkRequest::from_str("jdk-abc").is_err());
+    }
+
+    #[test]
+    fn test_jdk_request_matches() {
+        let version = semver::Version::new(21, 0, 4);
+        assert!(JdkRequest::Any.matches(&version, None));
+        assert!(JdkRequest::Major(21).matches(&version, None));
+        assert!(!JdkRequest::Major(17).matches(&version, None));
+        assert!(JdkRequest::Exact(21, 0, 4).matches(&version, None));
+        assert!(!JdkRequest::Exact(21, 0, 3).matches(&version, None));
+        assert!(
+            JdkRequest::from_str(">=17")
+                .unwrap()
+                .matches(&version, None)
+        );
+
+        let home = PathBuf::from("/opt/jdk-21");
+        let java = home.join("bin").join("java");
+        assert!(JdkRequest::Path(home.clone()).matches(&version, Some(&java)));
+        assert!(!JdkRequest::Path(home).matches(&version, None));
+    }
+
+    #[test]
+    fn test_parse_java_version() {
+        assert_eq!(
+            parse_java_version("21.0.4"),
+            Some(semver::Version::new(21, 0, 4))
+        );
+        assert_eq!(
+            parse_java_version("\"21.0.4+7\""),
+            Some(semver::Version::new(21, 0, 4))
+        );
+        assert_eq!(
+            parse_java_version("17"),
+            Some(semver::Version::new(17, 0, 0))
+        );
+        assert_eq!(
+            parse_java_version("11.0.20.1"),
+            Some(semver::Version::new(11, 0, 20))
+        );
+        assert_eq!(
+            parse_java_version("1.8.0_392"),
+            Some(semver::Version::new(8, 0, 392))
+        );
+        assert_eq!(
+            parse_java_version("22-ea"),
+            Some(semver::Version::new(22, 0, 0))
+        );
+        assert_eq!(parse_java_version("abc"), None);
+    }
+}
@@ -16,8 +16,9 @@ use tracing::{trace, warn};
 use crate::cli::reporter::{HookInstallReporter, HookRunReporter};
 use crate::hook::{Hook, InstallInfo, InstalledHook};
 use crate::languages::LanguageImpl;
+use crate::offline;
 use crate::process::Cmd;
-use crate::run::{USE_COLOR, run_by_batch};
+use crate::run::{Batch, USE_COLOR, run_by_batch};
 use crate::store::Store;
 
 #[derive(Debug, Copy, Clone)]
@@ -317,19 +318,59 @@ impl ContainerRuntimeInfo {
 static CONTAINER_RUNTIME: LazyLock<ContainerRuntimeInfo> =
     LazyLock::new(ContainerRuntimeInfo::detect_runtime);
 
+/// The install info key of the image built for a hook environment.
+const IMAGE_KEY: &str = "image";
+
+/// An image built by prek for a `docker` hook.
+pub(crate) struct BuiltImage {
+    /// `repository:tag`, or `<none>:<none>` for images whose tag moved to a newer build.
+    pub(crate) name: String,
+    pub(crate) id: String,
+    pub(crate) size: String,
+    pub(crate) created: String,
+}
+
 impl Docker {
-    fn docker_tag(info: &InstallInfo) -> String {
+    fn docker_tag(info: &InstallInfo, context_digest: &str) -> String {
         let mut hasher = DefaultHasher::new();
 
         info.language.hash(&mut hasher);
         info.language_version.hash(&mut hasher);
         let deps = info.dependencies.iter().collect::<BTreeSet<&String>>();
         deps.hash(&mut hasher);
+        context_digest.hash(&mut hasher);
 
         let digest = hex::encode(hasher.finish().to_le_bytes());
         format!("prek-{digest}")
     }
 
+    /// A digest of the build context: the Dockerfile and every other file of the repo that the
+    /// build can copy into the image, except the `.git` directory.
+    fn context_digest(src: &Path) -> Result<String> {
+        use sha2::{Digest, Sha256};
+
+        let mut hasher = Sha256::new();
+        let entries = walkdir::WalkDir::new(src)
+            .min_depth(1)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|entry| entry.depth() != 1 || entry.file_name() != ".git");
+        for entry in entries {
+            let entry = entry?;
+            let relative = entry.path().strip_prefix(src)?;
+            hasher.update(relative.to_string_lossy().as_bytes());
+            hasher.update([0]);
+            if entry.file_type().is_file() {
+                std::io::copy(&mut fs_err::File::open(entry.path())?, &mut hasher)?;
+            } else if entry.file_type().is_symlink() {
+                hasher.update(fs::read_link(entry.path())?.to_string_lossy().as_bytes());
+            }
+            hasher.update([0]);
+        }
+        Ok(hex::encode(hasher.finalize()))
+    }
+
+    /// Build the image of the hook, unless an image of the same build context already exists.
     async fn build_docker_image(
         hook: &Hook,
         install_info: &InstallInfo,
@@ -339,7 +380,16 @@ impl Docker {
             anyhow::bail!("Language `docker` cannot work with `local` repository");
         };
 
-        let tag = Self::docker_tag(install_info);
+        let context_digest = {
+            let src = src.to_path_buf();
+            tokio::task::spawn_blocking(move || Self::context_digest(&src)).await??
+        };
+        let tag = Self::docker_tag(install_info, &context_digest);
+        if Self::image_exists(&tag).await {
+            trace!(%tag, "Reusing docker image of the same build context");
+            return Ok(tag);
+        }
+
         let mut cmd = Cmd::new(CONTAINER_RUNTIME.cmd(), "build docker image");
         let cmd = cmd
             .arg("build")
@@ -352,10 +402,12 @@ impl Docker {
             .arg("--label")
             .arg(format!("prek.hook.id={}", hook.id))
             .arg("--label")
+            .arg(format!("prek.context-digest={context_digest}"))
+            .arg("--label")
             .arg("prek.managed=true");
 
-        // Always attempt to pull all referenced images.
-        if pull {
+        // Always attempt to pull all referenced images, unless offline.
+        if pull && !offline::is_offline() {
             cmd.arg("--pull");
         }
 
@@ -368,6 +420,95 @@ impl Docker {
         Ok(tag)
     }
 
+    /// The image built for an installed hook environment, if it is a `docker` one.
+    pub(crate) fn built_image(info: &InstallInfo) -> Option<&str> {
+        info.get_extra(IMAGE_KEY).map(String::as_str)
+    }
+
+    /// List the images built by prek for `docker` hooks.
+    pub(crate) async fn list_built_images() -> Result<Vec<BuiltImage>> {
+        let output = Cmd::new(CONTAINER_RUNTIME.cmd(), "list docker images")
+            .arg("image")
+            .arg("ls")
+            .arg("--filter")
+            .arg("label=prek.managed=true")
+            .arg("--format")
+            .arg("{{.Repository}}:{{.Tag}}\t{{.ID}}\t{{.Size}}\t{{.CreatedSince}}")
+            .check(true)
+            .output()
+            .await?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split('\t');
+                Some(BuiltImage {
+                    name: fields.next()?.to_string(),
+                    id: fields.next()?.to_string(),
+                    size: fields.next()?.to_string(),
+                    created: fields.next()?.to_string(),
+                })
+            })
+            .collect())
+    }
+
+    /// Remove images by id.
+    pub(crate) async fn remove_images(ids: &[&str]) -> Result<()> {
+        Cmd::new(CONTAINER_RUNTIME.cmd(), "remove docker images")
+            .arg("image")
+            .arg("rm")
+            .args(ids)
+            .check(true)
+            .output()
+            .await?;
+        Ok(())
+    }
+
+    /// Check whether the image is already present in the local image store.
+    pub(crate) async fn image_exists(image: &str) -> bool {
+        Cmd::new(CONTAINER_RUNTIME.cmd(), "inspect docker image")
+            .arg("image")
+            .arg("inspect")
+            .arg(image)
+            .check(false)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await
+            .is_ok_and(|status| status.success())
+    }
+
+    /// Pull the image from its registry.
+    pub(crate) async fn pull_image(image: &str) -> Result<()> {
+        Cmd::new(CONTAINER_RUNTIME.cmd(), "pull docker image")
+            .arg("pull")
+            .arg(image)
+            .check(true)
+            .output()
+            .await?;
+        Ok(())
+    }
+
+    /// Get the repository digests (`name@sha256:...`) of a local image.
+    pub(crate) async fn image_repo_digests(image: &str) -> Result<Vec<String>> {
+        let output = Cmd::new(CONTAINER_RUNTIME.cmd(), "inspect docker image")
+            .arg("image")
+            .arg("inspect")
+            .arg("--format")
+            .arg("{{json .RepoDigests}}")
+            .arg(image)
+            .check(true)
+            .output()
+            .await?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stdout = stdout.trim();
+        if stdout.is_empty() || stdout == "null" {
+            return Ok(vec![]);
+        }
+        serde_json::from_str(stdout).context("Failed to parse docker image inspect output")
+    }
+
     pub(crate) fn docker_run_cmd(work_dir: &Path) -> Cmd {
         let mut command = Cmd::new(CONTAINER_RUNTIME.cmd(), "run container");
         command.arg("run").arg("--rm");
@@ -434,15 +575,12 @@ impl LanguageImpl for Docker {
     ) -> Result<InstalledHook> {
         let progress = reporter.on_install_start(&hook);
 
-        let mut info = InstallInfo::new(
-            hook.language,
-            hook.env_key_dependencies().clone(),
-            &store.hooks_dir(),
-        )?;
+        let mut info = InstallInfo::for_hook(&hook, store)?;
 
-        Docker::build_docker_image(&hook, &info, true)
+        let tag = Docker::build_docker_image(&hook, &info, true)
             .await
             .context("Failed to build docker image")?;
+        info.with_extra(IMAGE_KEY, &tag);
 
         info.persist_env_path();
 
@@ -483,9 +621,13 @@ impl LanguageImpl for Docker {
         .context("Failed to build docker image")?;
         let entry = hook.entry.split()?;
 
-        let run = async |batch: &[&Path]| {
+        let run = async |batch: &Batch<'_>| {
             // docker run [OPTIONS] IMAGE [COMMAND] [ARG...]
             let mut cmd = Docker::docker_run_cmd(hook.work_dir());
+            let stdin_data = hook.stdin_data();
+            if stdin_data.is_some() {
+                cmd.arg("--interactive");
+            }
             let mut output = cmd
                 .current_dir(hook.work_dir())
                 .args(&env_args)
@@ -493,10 +635,11 @@ impl LanguageImpl for Docker {
                 .arg(&entry[0])
                 .arg(&docker_tag)
                 .args(&entry[1..])
-                .args(&hook.args)
-                .args(batch)
+                .args(batch.args())
                 .check(false)
                 .stdin(Stdio::null())
+                .stdin_data(stdin_data)
+                .process_tree(true)
                 .output()
                 .await?;
 
@@ -542,6 +685,30 @@ mod tests {
 403 401 0:45 /docker/containers/6d81fc3a1c26e24a27803e263d534be37c821e390521961a77f782c46fd85bc0/resolv.conf /etc/resolv.conf rw,nosuid,nodev,relatime - tmpfs tmpfs rw,size=65536k,mode=755
 ";
 
+    #[test]
+    fn context_digest() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(dir.path().join("Dockerfile"), "FROM alpine\n")?;
+        fs::create_dir_all(dir.path().join(".git"))?;
+        fs::write(
+            dir.path().join(".git").join("HEAD"),
+            "ref: refs/heads/main\n",
+        )?;
+        let digest = Docker::context_digest(dir.path())?;
+
+        // The git directory is not part of the build context.
+        fs::write(
+            dir.path().join(".git").join("HEAD"),
+            "ref: refs/heads/other\n",
+        )?;
+        assert_eq!(Docker::context_digest(dir.path())?, digest);
+
+        fs::write(dir.path().join("Dockerfile"), "FROM debian\n")?;
+        assert_ne!(Docker::context_digest(dir.path())?, digest);
+
+        Ok(())
+    }
+
     #[test]
     fn test_container_id_from_cgroup_v1() -> anyhow::Result<()> {
         for (sample, expected) in [
@@ -2,13 +2,15 @@ use std::path::Path;
 use std::process::Stdio;
 use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use tracing::debug;
 
 use crate::cli::reporter::{HookInstallReporter, HookRunReporter};
 use crate::hook::{Hook, InstallInfo, InstalledHook};
 use crate::languages::LanguageImpl;
 use crate::languages::docker::Docker;
-use crate::run::run_by_batch;
+use crate::offline;
+use crate::run::{Batch, run_by_batch};
 use crate::store::Store;
 
 #[derive(Debug, Copy, Clone)]
@@ -19,8 +21,28 @@ impl LanguageImpl for DockerImage {
         &self,
         hook: Arc<Hook>,
         _store: &Store,
-        _reporter: &HookInstallReporter,
+        reporter: &HookInstallReporter,
     ) -> Result<InstalledHook> {
+        // Pre-pull the image so the first run doesn't have to wait for it, `docker run` pulls
+        // it otherwise.
+        let entry = hook.entry.split()?;
+        let Some(image) = image_from_entry(&entry) else {
+            debug!(
+                "Failed to find image reference in entry `{}`, not pre-pulling it",
+                hook.entry.raw()
+            );
+            return Ok(InstalledHook::NoNeedInstall(hook));
+        };
+
+        if !Docker::image_exists(image).await {
+            offline::ensure_cached(|| format!("Docker image `{image}`"))?;
+            let progress = reporter.on_install_start(&hook);
+            Docker::pull_image(image)
+                .await
+                .with_context(|| format!("Failed to pull image `{image}`"))?;
+            reporter.on_install_complete(progress);
+        }
+
         Ok(InstalledHook::NoNeedInstall(hook))
     }
 
@@ -45,16 +67,31 @@ impl LanguageImpl for DockerImage {
             .collect();
 
         let entry = hook.entry.split()?;
-        let run = async |batch: &[&Path]| {
+        if let Some(digest) = &hook.image_digest {
+            let image = image_from_entry(&entry).with_context(|| {
+                format!(
+                    "Failed to find image reference in entry `{}`",
+                    hook.entry.raw()
+                )
+            })?;
+            verify_image_digest(image, digest).await?;
+        }
+
+        let run = async |batch: &Batch<'_>| {
             let mut cmd = Docker::docker_run_cmd(hook.work_dir());
+            let stdin_data = hook.stdin_data();
+            if stdin_data.is_some() {
+                cmd.arg("--interactive");
+            }
             let mut output = cmd
                 .current_dir(hook.work_dir())
                 .args(&env_args)
                 .args(&entry[..])
-                .args(&hook.args)
-                .args(batch)
+                .args(batch.args())
                 .check(false)
                 .stdin(Stdio::null())
+                .stdin_data(stdin_data)
+                .process_tree(true)
                 .output()
                 .await?;
 
@@ -81,3 +118,239 @@ impl LanguageImpl for DockerImage {
         Ok((combined_status, combined_output))
     }
 }
+
+/// Long `docker run` options that take a value, as `--option value` or `--option=value`.
+const VALUE_OPTIONS: &[&str] = &[
+    "--add-host",
+    "--annotation",
+    "--attach",
+    "--blkio-weight",
+    "--blkio-weight-device",
+    "--cap-add",
+    "--cap-drop",
+    "--cgroup-parent",
+    "--cgroupns",
+    "--cidfile",
+    "--cpu-period",
+    "--cpu-quota",
+    "--cpu-rt-period",
+    "--cpu-rt-runtime",
+    "--cpu-shares",
+    "--cpus",
+    "--cpuset-cpus",
+    "--cpuset-mems",
+    "--detach-keys",
+    "--device",
+    "--device-cgroup-rule",
+    "--device-read-bps",
+    "--device-read-iops",
+    "--device-write-bps",
+    "--device-write-iops",
+    "--dns",
+    "--dns-option",
+    "--dns-search",
+    "--domainname",
+    "--entrypoint",
+    "--env",
+    "--env-file",
+    "--expose",
+    "--gpus",
+    "--group-add",
+    "--health-cmd",
+    "--health-interval",
+    "--health-retries",
+    "--health-start-interval",
+    "--health-start-period",
+    "--health-timeout",
+    "--hostname",
+    "--ip",
+    "--ip6",
+    "--ipc",
+    "--isolation",
+    "--kernel-memory",
+    "--label",
+    "--label-file",
+    "--link",
+    "--link-local-ip",
+    "--log-driver",
+    "--log-opt",
+    "--mac-address",
+    "--memory",
+    "--memory-reservation",
+    "--memory-swap",
+    "--memory-swappiness",
+    "--mount",
+    "--name",
+    "--net",
+    "--net-alias",
+    "--network",
+    "--network-alias",
+    "--oom-score-adj",
+    "--pid",
+    "--pids-limit",
+    "--platform",
+    "--publish",
+    "--pull",
+    "--restart",
+    "--runtime",
+    "--security-opt",
+    "--shm-size",
+    "--stop-signal",
+    "--stop-timeout",
+    "--storage-opt",
+    "--sysctl",
+    "--tmpfs",
+    "--ulimit",
+    "--user",
+    "--userns",
+    "--uts",
+    "--volume",
+    "--volume-driver",
+    "--volumes-from",
+    "--workdir",
+];
+
+/// Long `docker run` options that don't take a value.
+const FLAG_OPTIONS: &[&str] = &[
+    "--detach",
+    "--disable-content-trust",
+    "--init",
+    "--interactive",
+    "--no-healthcheck",
+    "--oom-kill-disable",
+    "--privileged",
+    "--publish-all",
+    "--quiet",
+    "--read-only",
+    "--rm",
+    "--sig-proxy",
+    "--tty",
+    "--use-api-socket",
+];
+
+/// Short `docker run` options that take a value, as `-e value` or `-evalue`.
+const VALUE_SHORT_OPTIONS: &[char] = &['a', 'c', 'e', 'h', 'l', 'm', 'p', 'u', 'v', 'w'];
+
+/// Short `docker run` options that don't take a value.
+const FLAG_SHORT_OPTIONS: &[char] = &['d', 'i', 't', 'P', 'q'];
+
+/// Find the image reference in a `docker_image` entry, skipping leading `docker run` options
+/// and their values.
+///
+/// Returns `None` if there is no image, or if an unknown option makes it ambiguous whether the
+/// next argument is its value or the image.
+fn image_from_entry(entry: &[String]) -> Option<&str> {
+    let mut iter = entry.iter();
+    while let Some(arg) = iter.next() {
+        if let Some(option) = arg.strip_prefix("--") {
+            if option.contains('=') {
+                continue;
+            }
+            if VALUE_OPTIONS.contains(&arg.as_str()) {
+                iter.next();
+            } else if !FLAG_OPTIONS.contains(&arg.as_str()) {
+                return None;
+            }
+        } else if let Some(options) = arg.strip_prefix('-') {
+            // Flags can be combined, such as `-it`, the first option taking a value takes the
+            // rest of the argument, or the next argument.
+            for (i, option) in options.char_indices() {
+                if VALUE_SHORT_OPTIONS.contains(&option) {
+                    if i + option.len_utf8() == options.len() {
+                        iter.next();
+                    }
+                    break;
+                } else if !FLAG_SHORT_OPTIONS.contains(&option) {
+                    return None;
+                }
+            }
+        } else {
+            return Some(arg);
+        }
+    }
+    None
+}
+
+/// Check that the local image matches the pinned `image_digest`.
+async fn verify_image_digest(image: &str, digest: &str) -> Result<()> {
+    let repo_digests = Docker::image_repo_digests(image)
+        .await
+        .with_context(|| format!("Failed to inspect image `{image}`"))?;
+
+    if repo_digests.iter().any(|repo_digest| {
+        repo_digest
+            .rsplit_once('@')
+            .is_some_and(|(_, d)| d == digest)
+    }) {
+        return Ok(());
+    }
+
+    let found = if repo_digests.is_empty() {
+        "none".to_string()
+    } else {
+        repo_digests.join(", ")
+    };
+    anyhow::bail!("Image `{image}` does not match the pinned digest `{digest}` (found: {found})");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::image_from_entry;
+
+    fn split(entry: &str) -> Vec<String> {
+        shlex::split(entry).unwrap()
+    }
+
+    #[test]
+    fn image_from_entry_plain() {
+        let entry = split("docker.io/zricethezav/gitleaks:v8.21.2 git --pre-commit");
+        assert_eq!(
+            image_from_entry(&entry),
+            Some("docker.io/zricethezav/gitleaks:v8.21.2")
+        );
+    }
+
+    #[test]
+    fn image_from_entry_with_entrypoint() {
+        let entry = split("--entrypoint /bin/sh alpine:3.20 -c 'echo hi'");
+        assert_eq!(image_from_entry(&entry), Some("alpine:3.20"));
+
+        let entry = split("--entrypoint=/bin/sh alpine:3.20 -c 'echo hi'");
+        assert_eq!(image_from_entry(&entry), Some("alpine:3.20"));
+    }
+
+    #[test]
+    fn image_from_entry_with_options() {
+        let entry = split("-e FOO=1 --volume /tmp:/tmp -u 1000 --rm alpine:3.20 echo hi");
+        assert_eq!(image_from_entry(&entry), Some("alpine:3.20"));
+
+        let entry = split("--env=FOO=1 -w/src --network host alpine:3.20");
+        assert_eq!(image_from_entry(&entry), Some("alpine:3.20"));
+
+        // Combined short flags, the last one taking the next argument as its value.
+        let entry = split("-itv /tmp:/tmp alpine:3.20");
+        assert_eq!(image_from_entry(&entry), Some("alpine:3.20"));
+
+        let entry = split("-ituroot alpine:3.20");
+        assert_eq!(image_from_entry(&entry), Some("alpine:3.20"));
+    }
+
+    #[test]
+    fn image_from_entry_unknown_option() {
+        let entry = split("--some-new-option value alpine:3.20");
+        assert_eq!(image_from_entry(&entry), None);
+
+        let entry = split("-X alpine:3.20");
+        assert_eq!(image_from_entry(&entry), None);
+
+        // Unambiguous with the value attached.
+        let entry = split("--some-new-option=value alpine:3.20");
+        assert_eq!(image_from_entry(&entry), Some("alpine:3.20"));
+    }
+
+    #[test]
+    fn image_from_entry_missing() {
+        let entry = split("--entrypoint /bin/sh");
+        assert_eq!(image_from_entry(&entry), None);
+    }
+}
@@ -1,3 +1,4 @@
+use std::ffi::OsString;
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
@@ -14,7 +15,7 @@ use crate::languages::golang::GoRequest;
 use crate::languages::golang::installer::GoInstaller;
 use crate::languages::version::LanguageRequest;
 use crate::process::Cmd;
-use crate::run::run_by_batch;
+use crate::run::{Batch, run_by_batch};
 use crate::store::{CacheBucket, Store, ToolBucket};
 
 #[derive(Debug, Copy, Clone)]
@@ -43,11 +44,7 @@ impl LanguageImpl for Golang {
             .await
             .context("Failed to install go")?;
 
-        let mut info = InstallInfo::new(
-            hook.language,
-            hook.env_key_dependencies().clone(),
-            &store.hooks_dir(),
-        )?;
+        let mut info = InstallInfo::for_hook(&hook, store)?;
         info.with_toolchain(go.bin().to_path_buf())
             .with_language_version(go.version().deref().clone());
 
@@ -66,23 +63,25 @@ impl LanguageImpl for Golang {
             .expect("Go root should exist");
         let go_cache = store.cache_path(CacheBucket::Go);
 
+        let goflags = goflags();
         let go_install_cmd = || {
-            if go.is_from_system() {
-                let mut cmd = go.cmd("go install");
-                cmd.arg("install")
-                    .env(EnvVars::GOTOOLCHAIN, "local")
-                    .env(EnvVars::GOBIN, bin_dir(&info.env_path));
-                cmd
-            } else {
-                let mut cmd = go.cmd("go install");
-                cmd.arg("install")
-                    .env(EnvVars::GOTOOLCHAIN, "local")
-                    .env(EnvVars::GOROOT, go_root)
-                    .env(EnvVars::GOBIN, bin_dir(&info.env_path))
-                    .env(EnvVars::GOFLAGS, "-modcacherw")
+            let mut cmd = go.cmd("go install");
+            cmd.arg("install")
+                .env(EnvVars::GOTOOLCHAIN, "local")
+                .env(EnvVars::GOBIN, bin_dir(&info.env_path))
+                .envs(private_module_envs());
+            if !go.is_from_system() {
+                cmd.env(EnvVars::GOROOT, go_root)
+                    .env(EnvVars::GOFLAGS, &goflags)
                     .env(EnvVars::GOPATH, &go_cache);
-                cmd
             }
+            if hook.is_remote() {
+                // A `GOWORK` pointing at the user's workspace doesn't include the hook repository,
+                // only honor a `go.work` of the repository itself.
+                cmd.env(EnvVars::GOWORK, "auto");
+            }
+            cmd.args(&hook.goflags);
+            cmd
         };
 
         // GOPATH used to store downloaded source code (in $GOPATH/pkg/mod)
@@ -95,12 +94,16 @@ impl LanguageImpl for Golang {
                 .output()
                 .await?;
         }
+        // Local hooks resolve path dependencies and `go.work` from the project directory.
+        let work_dir = hook.repo_path().unwrap_or(hook.work_dir());
         for dep in &hook.additional_dependencies {
-            let mut cmd = go_install_cmd();
-            if let Some(repo) = hook.repo_path() {
-                cmd.current_dir(repo);
-            }
-            cmd.arg(dep).remove_git_envs().check(true).output().await?;
+            go_install_cmd()
+                .arg(dep)
+                .current_dir(work_dir)
+                .remove_git_envs()
+                .check(true)
+                .output()
+                .await?;
         }
 
         info.persist_env_path();
@@ -143,21 +146,23 @@ impl LanguageImpl for Golang {
         };
         let new_path = prepend_paths(&[&go_bin, go_root_bin]).context("Failed to join PATH")?;
 
+        let goflags = goflags();
         let entry = hook.entry.resolve(Some(&new_path))?;
-        let run = async |batch: &[&Path]| {
+        let run = async |batch: &Batch<'_>| {
             let mut output = Cmd::new(&entry[0], "go hook")
                 .current_dir(hook.work_dir())
                 .args(&entry[1..])
                 .env(EnvVars::PATH, &new_path)
                 .env(EnvVars::GOTOOLCHAIN, "local")
                 .env(EnvVars::GOBIN, &go_bin)
-                .env(EnvVars::GOFLAGS, "-modcacherw")
+                .env(EnvVars::GOFLAGS, &goflags)
                 .envs(go_envs.iter().copied())
                 .envs(&hook.env)
-                .args(&hook.args)
-                .args(batch)
+                .args(batch.args())
                 .check(false)
                 .stdin(Stdio::null())
+                .stdin_data(hook.stdin_data())
+                .process_tree(true)
                 .pty_output()
                 .await?;
 
@@ -187,3 +192,26 @@ impl LanguageImpl for Golang {
 pub(crate) fn bin_dir(env_path: &Path) -> PathBuf {
     env_path.join("bin")
 }
+
+/// `GOFLAGS` for prek-managed Go, keeping the flags the user already set.
+fn goflags() -> String {
+    match EnvVars::var(EnvVars::GOFLAGS) {
+        Ok(flags) if !flags.trim().is_empty() => format!("-modcacherw {}", flags.trim()),
+        _ => "-modcacherw".to_string(),
+    }
+}
+
+/// Settings for fetching private modules: which modules bypass the proxy and checksum database,
+/// and where credentials come from (`GOAUTH`, `.netrc` or the file in `NETRC`).
+fn private_module_envs() -> impl Iterator<Item = (&'static str, OsString)> {
+    [
+        EnvVars::GOPRIVATE,
+        EnvVars::GONOPROXY,
+        EnvVars::GONOSUMDB,
+        EnvVars::GOINSECURE,
+        EnvVars::GOAUTH,
+        EnvVars::NETRC,
+    ]
+    .into_iter()
+    .filter_map(|key| EnvVars::var_os(key).map(|value| (key, value)))
+}
@@ -12,11 +12,13 @@ use tracing::{debug, trace, warn};
 
 use crate::fs::LockedFile;
 use crate::git;
-use crate::languages::download_and_extract;
 use crate::languages::golang::GoRequest;
 use crate::languages::golang::golang::bin_dir;
 use crate::languages::golang::version::GoVersion;
+use crate::languages::{REQWEST_CLIENT, download_and_extract};
+use crate::offline;
 use crate::process::Cmd;
+use crate::settings;
 use crate::store::Store;
 
 pub(crate) struct GoResult {
@@ -129,6 +131,7 @@ impl GoInstaller {
         if !allows_download {
             anyhow::bail!("No suitable system Go version found and downloads are disabled");
         }
+        offline::ensure_cached(|| "A Go version matching the request".to_string())?;
 
         let resolved_version = self
             .resolve_version(request)
@@ -174,6 +177,22 @@ impl GoInstaller {
     }
 
     async fn resolve_version(&self, req: &GoRequest) -> Result<GoVersion> {
+        let versions =
+            if settings::get().mirrors.go.is_some() || EnvVars::is_set(EnvVars::PREK_GO_MIRROR) {
+                Self::list_mirror_versions().await?
+            } else {
+                Self::list_remote_versions().await?
+            };
+
+        let version = versions
+            .into_iter()
+            .find(|version| req.matches(version, None))
+            .context("Version not found on remote")?;
+        Ok(version)
+    }
+
+    /// List all Go versions from the tags of the Go repository.
+    async fn list_remote_versions() -> Result<Vec<GoVersion>> {
         let output = git::git_cmd("list go tags")?
             .arg("ls-remote")
             .arg("--tags")
@@ -182,7 +201,7 @@ impl GoInstaller {
             .await?
             .stdout;
         let output_str = String::from_utf8(output)?;
-        let versions: Vec<GoVersion> = output_str
+        Ok(output_str
             .lines()
             .filter_map(|line| {
                 let tag = line.split('\t').nth(1)?;
@@ -190,13 +209,30 @@ impl GoInstaller {
                 GoVersion::from_str(tag).ok()
             })
             .sorted_unstable_by(|a, b| b.cmp(a))
-            .collect();
+            .collect())
+    }
 
-        let version = versions
-            .into_iter()
-            .find(|version| req.matches(version, None))
-            .context("Version not found on remote")?;
-        Ok(version)
+    /// List all Go versions from the JSON index of a `go.dev/dl` mirror.
+    async fn list_mirror_versions() -> Result<Vec<GoVersion>> {
+        #[derive(serde::Deserialize)]
+        struct Release {
+            version: String,
+        }
+
+        let url = format!("{}/?mode=json&include=all", settings::get().mirrors.go());
+        let releases: Vec<Release> = REQWEST_CLIENT
+            .get(&url)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .with_context(|| format!("Failed to list go versions from {url}"))?
+            .json()
+            .await?;
+        Ok(releases
+            .iter()
+            .filter_map(|release| GoVersion::from_str(release.version.strip_prefix("go")?).ok())
+            .sorted_unstable_by(|a, b| b.cmp(a))
+            .collect())
     }
 
     async fn download(&self, store: &Store, version: &GoVersion) -> Result<GoResult> {
@@ -224,7 +260,7 @@ impl GoInstaller {
 
         let ext = if cfg!(windows) { "zip" } else { "tar.gz" };
         let filename = format!("go{version}.{os}-{arch}.{ext}");
-        let url = format!("https://go.dev/dl/{filename}");
+        let url = format!("{}/{filename}", settings::get().mirrors.go());
         let target = self.root.join(version.to_string());
 
         download_and_extract(&url, &filename, store, async |extracted| {
@@ -0,0 +1,303 @@
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use prek_consts::env_vars::EnvVars;
+use prek_consts::prepend_paths;
+use semver::Version;
+use tracing::debug;
+
+use crate::cli::reporter::{HookInstallReporter, HookRunReporter};
+use crate::hook::{Hook, InstallInfo, InstalledHook};
+use crate::languages::LanguageImpl;
+use crate::offline;
+use crate::process::Cmd;
+use crate::run::{Batch, run_by_batch};
+use crate::store::Store;
+
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Haskell;
+
+/// The build tool used to install Haskell packages.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Builder {
+    Cabal,
+    Stack,
+}
+
+impl Builder {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "cabal" => Some(Self::Cabal),
+            "stack" => Some(Self::Stack),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Cabal => "cabal",
+            Self::Stack => "stack",
+        }
+    }
+}
+
+impl Display for Builder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+struct GhcInfo {
+    builder: Builder,
+    version: Version,
+    /// The `ghc` executable for cabal, or the `stack` executable which manages its own GHC.
+    executable: PathBuf,
+}
+
+/// Find a Haskell build tool, preferring `cabal` and falling back to `stack`.
+async fn query_ghc_info() -> Result<GhcInfo> {
+    if which::which("cabal").is_ok() {
+        let executable = which::which("ghc").context("`cabal` requires `ghc` on PATH")?;
+        let stdout = Cmd::new(&executable, "get ghc version")
+            .arg("--numeric-version")
+            .check(true)
+            .output()
+            .await?
+            .stdout;
+        return Ok(GhcInfo {
+            builder: Builder::Cabal,
+            version: parse_ghc_version(&stdout)?,
+            executable,
+        });
+    }
+
+    if let Ok(executable) = which::which("stack") {
+        let stdout = Cmd::new(&executable, "get stack ghc version")
+            .arg("ghc")
+            .arg("--")
+            .arg("--numeric-version")
+            .check(true)
+            .output()
+            .await?
+            .stdout;
+        return Ok(GhcInfo {
+            builder: Builder::Stack,
+            version: parse_ghc_version(&stdout)?,
+            executable,
+        });
+    }
+
+    anyhow::bail!("Neither `cabal` nor `stack` was found on PATH")
+}
+
+fn parse_ghc_version(stdout: &[u8]) -> Result<Version> {
+    // 9.6.6
+    let version = String::from_utf8_lossy(stdout);
+    let version = version.trim();
+    Version::parse(version).with_context(|| format!("Failed to parse GHC version `{version}`"))
+}
+
+impl LanguageImpl for Haskell {
+    async fn install(
+        &self,
+        hook: Arc<Hook>,
+        store: &Store,
+        reporter: &HookInstallReporter,
+    ) -> Result<InstalledHook> {
+        let progress = reporter.on_install_start(&hook);
+
+        // Like `pre-commit`, install the packages in the hook repository and `additional_dependencies`.
+        let has_cabal_file = hook.repo_path().is_some_and(Self::has_cabal_file);
+        if !has_cabal_file && hook.additional_dependencies.is_empty() {
+            anyhow::bail!("Expected `.cabal` files or `additional_dependencies`");
+        }
+
+        let mut info = InstallInfo::for_hook(&hook, store)?;
+
+        debug!(%hook, target = %info.env_path.display(), "Installing Haskell environment");
+
+        let ghc_info = query_ghc_info().await.context("Failed to query GHC info")?;
+
+        let bin_dir = info.env_path.join("bin");
+        fs_err::tokio::create_dir_all(&bin_dir).await?;
+
+        offline::ensure_online(|| format!("Installing Haskell packages for hook `{hook}`"))?;
+
+        let work_dir = hook.repo_path().unwrap_or(&info.env_path);
+        let mut targets = Vec::new();
+        if has_cabal_file {
+            targets.push(".".to_string());
+        }
+        targets.extend(hook.additional_dependencies.iter().cloned());
+
+        match ghc_info.builder {
+            Builder::Cabal => Self::cabal_install(work_dir, &bin_dir, &targets).await?,
+            Builder::Stack => {
+                Self::stack_install(&ghc_info.executable, work_dir, &bin_dir, &targets).await?;
+            }
+        }
+
+        info.with_toolchain(ghc_info.executable)
+            .with_language_version(ghc_info.version)
+            .with_extra("builder", ghc_info.builder.as_str());
+
+        info.persist_env_path();
+
+        reporter.on_install_complete(progress);
+
+        Ok(InstalledHook::Installed {
+            hook,
+            info: Arc::new(info),
+        })
+    }
+
+    async fn check_health(&self, info: &InstallInfo) -> Result<()> {
+        if !info.toolchain.exists() {
+            anyhow::bail!("GHC toolchain not found at `{}`", info.toolchain.display());
+        }
+
+        let builder = info
+            .get_extra("builder")
+            .and_then(|builder| Builder::parse(builder))
+            .context("Missing Haskell builder in install info")?;
+        let current = query_ghc_info()
+            .await
+            .context("Failed to query current GHC info")?;
+
+        if current.builder != builder {
+            anyhow::bail!(
+                "Haskell builder mismatch: expected `{builder}`, found `{}`",
+                current.builder
+            );
+        }
+        if current.version != info.language_version {
+            anyhow::bail!(
+                "GHC version mismatch: expected `{}`, found `{}`",
+                info.language_version,
+                current.version
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn run(
+        &self,
+        hook: &InstalledHook,
+        filenames: &[&Path],
+        _store: &Store,
+        reporter: &HookRunReporter,
+    ) -> Result<(i32, Vec<u8>)> {
+        let progress = reporter.on_run_start(hook, filenames.len());
+
+        let env_dir = hook.env_path().expect("Haskell must have env path");
+        let new_path = prepend_paths(&[&env_dir.join("bin")]).context("Failed to join PATH")?;
+        let entry = hook.entry.resolve(Some(&new_path))?;
+
+        let run = async |batch: &Batch<'_>| {
+            let mut output = Cmd::new(&entry[0], "run haskell command")
+                .current_dir(hook.work_dir())
+                .args(&entry[1..])
+                .env(EnvVars::PATH, &new_path)
+                .envs(&hook.env)
+                .args(batch.args())
+                .check(false)
+                .stdin(Stdio::null())
+                .stdin_data(hook.stdin_data())
+                .process_tree(true)
+                .pty_output()
+                .await?;
+
+            reporter.on_run_progress(progress, batch.len() as u64);
+
+            output.stdout.extend(output.stderr);
+            let code = output.status.code().unwrap_or(1);
+            anyhow::Ok((code, output.stdout))
+        };
+
+        let results = run_by_batch(hook, filenames, &entry, run).await?;
+
+        reporter.on_run_complete(progress);
+
+        let mut combined_status = 0;
+        let mut combined_output = Vec::new();
+
+        for (code, output) in results {
+            combined_status |= code;
+            combined_output.extend(output);
+        }
+
+        Ok((combined_status, combined_output))
+    }
+}
+
+impl Haskell {
+    async fn cabal_install(work_dir: &Path, bin_dir: &Path, targets: &[String]) -> Result<()> {
+        Cmd::new("cabal", "cabal update")
+            .current_dir(work_dir)
+            .arg("update")
+            .check(true)
+            .output()
+            .await
+            .context("Failed to update cabal package index")?;
+
+        Cmd::new("cabal", "cabal install")
+            .current_dir(work_dir)
+            .arg("install")
+            .arg("--install-method")
+            .arg("copy")
+            .arg("--installdir")
+            .arg(bin_dir)
+            .args(targets)
+            .check(true)
+            .output()
+            .await
+            .context("Failed to install Haskell packages with cabal")?;
+        Ok(())
+    }
+
+    async fn stack_install(
+        stack: &Path,
+        work_dir: &Path,
+        bin_dir: &Path,
+        targets: &[String],
+    ) -> Result<()> {
+        // `stack` builds the project in the working directory when no target is given,
+        // and only the given targets otherwise.
+        let (project, packages): (Vec<_>, Vec<_>) =
+            targets.iter().partition(|target| *target == ".");
+        let mut runs = Vec::new();
+        if !project.is_empty() {
+            runs.push(Vec::new());
+        }
+        if !packages.is_empty() {
+            runs.push(packages);
+        }
+
+        for targets in runs {
+            Cmd::new(stack, "stack install")
+                .current_dir(work_dir)
+                .arg("install")
+                .arg("--local-bin-path")
+                .arg(bin_dir)
+                .args(targets)
+                .check(true)
+                .output()
+                .await
+                .context("Failed to install Haskell packages with stack")?;
+        }
+        Ok(())
+    }
+
+    fn has_cabal_file(root_path: &Path) -> bool {
+        let Ok(entries) = std::fs::read_dir(root_path) else {
+            return false;
+        };
+        entries
+            .flatten()
+            .any(|entry| entry.path().extension().is_some_and(|ext| ext == "cabal"))
+    }
+}
@@ -12,7 +12,7 @@ use crate::cli::reporter::{HookInstallReporter, HookRunReporter};
 use crate::hook::{Hook, InstallInfo, InstalledHook};
 use crate::languages::LanguageImpl;
 use crate::process::Cmd;
-use crate::run::run_by_batch;
+use crate::run::{Batch, run_by_batch};
 use crate::store::Store;
 
 #[derive(Debug, Copy, Clone)]
@@ -63,11 +63,7 @@ impl LanguageImpl for Lua {
     ) -> Result<InstalledHook> {
         let progress = reporter.on_install_start(&hook);
 
-        let mut info = InstallInfo::new(
-            hook.language,
-            hook.env_key_dependencies().clone(),
-            &store.hooks_dir(),
-        )?;
+        let mut info = InstallInfo::for_hook(&hook, store)?;
 
         debug!(%hook, target = %info.env_path.display(), "Installing Lua environment");
 
@@ -145,7 +141,7 @@ impl LanguageImpl for Lua {
         let lua_path = Lua::get_lua_path(env_dir, &version);
         let lua_cpath = Lua::get_lua_cpath(env_dir, &version);
 
-        let run = async |batch: &[&Path]| {
+        let run = async |batch: &Batch<'_>| {
             let mut output = Cmd::new(&entry[0], "run lua command")
                 .current_dir(hook.work_dir())
                 .args(&entry[1..])
@@ -153,10 +149,11 @@ impl LanguageImpl for Lua {
                 .env(EnvVars::LUA_PATH, &lua_path)
                 .env(EnvVars::LUA_CPATH, &lua_cpath)
                 .envs(&hook.env)
-                .args(&hook.args)
-                .args(batch)
+                .args(batch.args())
                 .check(false)
                 .stdin(Stdio::null())
+                .stdin_data(hook.stdin_data())
+                .process_tree(true)
                 .pty_output()
                 .await?;
 
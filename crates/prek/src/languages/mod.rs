@@ -1,11 +1,14 @@
 use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock};
 
 use anyhow::{Context, Result};
 use futures::TryStreamExt;
+use owo_colors::OwoColorize;
 use prek_consts::env_vars::EnvVars;
+use rustc_hash::FxHashMap;
+use sha2::{Digest, Sha256};
 use tokio_util::compat::FuturesAsyncReadCompatExt;
 use tracing::{debug, error, instrument, trace};
 
@@ -16,30 +19,40 @@ use crate::fs::{CWD, Simplified};
 use crate::hook::{Hook, InstallInfo, InstalledHook, Repo};
 use crate::identify::parse_shebang;
 use crate::store::Store;
-use crate::{archive, hooks, warn_user_once};
+use crate::{archive, hooks, offline, retry, settings, warn_user_once};
 
+mod coursier;
 mod docker;
 mod docker_image;
 mod fail;
 mod golang;
+mod haskell;
 mod lua;
 mod node;
 mod pygrep;
 mod python;
+mod r;
 mod ruby;
 mod rust;
 mod script;
 mod system;
 pub mod version;
 
+pub(crate) use docker::{BuiltImage, Docker};
+pub(crate) use python::editable_metadata_key;
+pub(crate) use rust::cargo_path_dependencies_key;
+
+static COURSIER: coursier::Coursier = coursier::Coursier;
 static DOCKER: docker::Docker = docker::Docker;
 static DOCKER_IMAGE: docker_image::DockerImage = docker_image::DockerImage;
 static FAIL: fail::Fail = fail::Fail;
 static GOLANG: golang::Golang = golang::Golang;
+static HASKELL: haskell::Haskell = haskell::Haskell;
 static LUA: lua::Lua = lua::Lua;
 static NODE: node::Node = node::Node;
 static PYGREP: pygrep::Pygrep = pygrep::Pygrep;
 static PYTHON: python::Python = python::Python;
+static R: r::R = r::R;
 static RUBY: ruby::Ruby = ruby::Ruby;
 static RUST: rust::Rust = rust::Rust;
 static SCRIPT: script::Script = script::Script;
@@ -98,7 +111,7 @@ impl LanguageImpl for Unimplemented {
 
 // `pre-commit` language support:
 // conda: only system version, support env, support additional deps
-// coursier: only system version, support env, support additional deps
+// coursier: only system version, support env, support additional deps (prek installs a JDK)
 // dart: only system version, support env, support additional deps
 // docker_image: only system version, no env, no additional deps
 // docker: only system version, support env, no additional deps
@@ -122,14 +135,17 @@ impl Language {
     pub fn supported(lang: Language) -> bool {
         matches!(
             lang,
-            Self::Golang
+            Self::Coursier
+                | Self::Golang
                 | Self::Docker
                 | Self::DockerImage
                 | Self::Fail
+                | Self::Haskell
                 | Self::Lua
                 | Self::Node
                 | Self::Pygrep
                 | Self::Python
+                | Self::R
                 | Self::Ruby
                 | Self::Rust
                 | Self::Script
@@ -150,7 +166,13 @@ impl Language {
     pub fn supports_language_version(self) -> bool {
         matches!(
             self,
-            Self::Python | Self::Node | Self::Golang | Self::Ruby | Self::Rust
+            Self::Python
+                | Self::Node
+                | Self::Coursier
+                | Self::Golang
+                | Self::R
+                | Self::Ruby
+                | Self::Rust
         )
     }
 
@@ -179,14 +201,17 @@ impl Language {
         reporter: &HookInstallReporter,
     ) -> Result<InstalledHook> {
         match self {
+            Self::Coursier => COURSIER.install(hook, store, reporter).await,
             Self::Docker => DOCKER.install(hook, store, reporter).await,
             Self::DockerImage => DOCKER_IMAGE.install(hook, store, reporter).await,
             Self::Fail => FAIL.install(hook, store, reporter).await,
             Self::Golang => GOLANG.install(hook, store, reporter).await,
+            Self::Haskell => HASKELL.install(hook, store, reporter).await,
             Self::Lua => LUA.install(hook, store, reporter).await,
             Self::Node => NODE.install(hook, store, reporter).await,
             Self::Pygrep => PYGREP.install(hook, store, reporter).await,
             Self::Python => PYTHON.install(hook, store, reporter).await,
+            Self::R => R.install(hook, store, reporter).await,
             Self::Ruby => RUBY.install(hook, store, reporter).await,
             Self::Rust => RUST.install(hook, store, reporter).await,
             Self::Script => SCRIPT.install(hook, store, reporter).await,
@@ -197,14 +222,17 @@ impl Language {
 
     pub async fn check_health(&self, info: &InstallInfo) -> Result<()> {
         match self {
+            Self::Coursier => COURSIER.check_health(info).await,
             Self::Docker => DOCKER.check_health(info).await,
             Self::DockerImage => DOCKER_IMAGE.check_health(info).await,
             Self::Fail => FAIL.check_health(info).await,
             Self::Golang => GOLANG.check_health(info).await,
+            Self::Haskell => HASKELL.check_health(info).await,
             Self::Lua => LUA.check_health(info).await,
             Self::Node => NODE.check_health(info).await,
             Self::Pygrep => PYGREP.check_health(info).await,
             Self::Python => PYTHON.check_health(info).await,
+            Self::R => R.check_health(info).await,
             Self::Ruby => RUBY.check_health(info).await,
             Self::Rust => RUST.check_health(info).await,
             Self::Script => SCRIPT.check_health(info).await,
@@ -244,14 +272,17 @@ impl Language {
         }
 
         match self {
+            Self::Coursier => COURSIER.run(hook, filenames, store, reporter).await,
             Self::Docker => DOCKER.run(hook, filenames, store, reporter).await,
             Self::DockerImage => DOCKER_IMAGE.run(hook, filenames, store, reporter).await,
             Self::Fail => FAIL.run(hook, filenames, store, reporter).await,
             Self::Golang => GOLANG.run(hook, filenames, store, reporter).await,
+            Self::Haskell => HASKELL.run(hook, filenames, store, reporter).await,
             Self::Lua => LUA.run(hook, filenames, store, reporter).await,
             Self::Node => NODE.run(hook, filenames, store, reporter).await,
             Self::Pygrep => PYGREP.run(hook, filenames, store, reporter).await,
             Self::Python => PYTHON.run(hook, filenames, store, reporter).await,
+            Self::R => R.run(hook, filenames, store, reporter).await,
             Self::Ruby => RUBY.run(hook, filenames, store, reporter).await,
             Self::Rust => RUST.run(hook, filenames, store, reporter).await,
             Self::Script => SCRIPT.run(hook, filenames, store, reporter).await,
@@ -308,31 +339,54 @@ async fn download_and_extract(
     store: &Store,
     callback: impl AsyncFn(&Path) -> Result<()>,
 ) -> Result<()> {
-    let response = REQWEST_CLIENT
-        .get(url)
-        .send()
-        .await
-        .with_context(|| format!("Failed to download file from {url}"))?;
-    if !response.status().is_success() {
-        anyhow::bail!(
-            "Failed to download file from {}: {}",
-            url,
-            response.status()
-        );
-    }
-
-    let tarball = response
-        .bytes_stream()
-        .map_err(std::io::Error::other)
-        .into_async_read()
-        .compat();
+    offline::ensure_online(|| format!("Downloading `{url}`"))?;
 
+    let ext = ArchiveExtension::from_path(filename)?;
     let scratch_dir = store.scratch_path();
-    let temp_dir = tempfile::tempdir_in(&scratch_dir)?;
-    debug!(url = %url, temp_dir = ?temp_dir.path(), "Downloading");
 
-    let ext = ArchiveExtension::from_path(filename)?;
-    archive::unpack(tarball, ext, temp_dir.path()).await?;
+    let checksum = expected_checksum(filename)?;
+
+    // The archive is streamed into the extractor, so a connection dropping mid-way
+    // restarts the download from scratch.
+    let temp_dir = retry::with_retries(
+        &format!("Download of `{url}`"),
+        retry::is_transient_http_error,
+        async || {
+            let response = REQWEST_CLIENT
+                .get(url)
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status)
+                .with_context(|| format!("Failed to download file from {url}"))?;
+
+            let temp_dir = tempfile::tempdir_in(&scratch_dir)?;
+            debug!(url = %url, temp_dir = ?temp_dir.path(), "Downloading");
+
+            if let Some(expected) = checksum {
+                // Verify the whole archive before extracting anything from it.
+                let bytes = response
+                    .bytes()
+                    .await
+                    .with_context(|| format!("Failed to download file from {url}"))?;
+                let actual = hex::encode(Sha256::digest(&bytes));
+                if actual != expected {
+                    anyhow::bail!(
+                        "Checksum mismatch for `{filename}`: expected `{expected}`, got `{actual}`"
+                    );
+                }
+                archive::unpack(&bytes[..], ext, temp_dir.path()).await?;
+            } else {
+                let tarball = response
+                    .bytes_stream()
+                    .map_err(std::io::Error::other)
+                    .into_async_read()
+                    .compat();
+                archive::unpack(tarball, ext, temp_dir.path()).await?;
+            }
+            anyhow::Ok(temp_dir)
+        },
+    )
+    .await?;
 
     let extracted = match archive::strip_component(temp_dir.path()) {
         Ok(top_level) => top_level,
@@ -347,6 +401,45 @@ async fn download_and_extract(
     Ok(())
 }
 
+/// Expected SHA-256 digests of toolchain archives by file name, from the manifest configured
+/// with `PREK_TOOLCHAIN_CHECKSUMS` or `mirrors.checksums`.
+static CHECKSUMS: LazyLock<Result<Option<FxHashMap<String, String>>, String>> =
+    LazyLock::new(|| {
+        let Some(path) = settings::get().mirrors.checksums() else {
+            return Ok(None);
+        };
+        let content = fs_err::read_to_string(&path).map_err(|err| err.to_string())?;
+        Ok(Some(parse_checksums(&content)))
+    });
+
+/// Parse a manifest in the format of `sha256sum` (and Node.js' `SHASUMS256.txt`).
+fn parse_checksums(content: &str) -> FxHashMap<String, String> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (digest, path) = line.trim().split_once(char::is_whitespace)?;
+            // `*` marks binary mode, and only the file name is matched.
+            let path = path.trim_start().trim_start_matches('*');
+            let filename = path.rsplit('/').next()?;
+            Some((filename.to_string(), digest.to_ascii_lowercase()))
+        })
+        .collect()
+}
+
+/// The checksum an archive must match, if a checksum manifest is configured.
+fn expected_checksum(filename: &str) -> Result<Option<&'static str>> {
+    match &*CHECKSUMS {
+        Ok(None) => Ok(None),
+        Ok(Some(checksums)) => checksums
+            .get(filename)
+            .map(|digest| Some(digest.as_str()))
+            .with_context(|| {
+                format!("`{filename}` is not listed in the toolchain checksum manifest")
+            }),
+        Err(err) => anyhow::bail!("Failed to read the toolchain checksum manifest: {err}"),
+    }
+}
+
 pub(crate) static REQWEST_CLIENT: std::sync::LazyLock<reqwest::Client> =
     std::sync::LazyLock::new(|| {
         let native_tls = use_native_tls();
@@ -363,6 +456,15 @@ fn create_reqwest_client(native_tls: bool) -> reqwest::Client {
     } else {
         builder.tls_built_in_webpki_certs(true)
     };
+    let builder = match ca_bundle_certificates() {
+        Ok(certs) => certs
+            .into_iter()
+            .fold(builder, reqwest::ClientBuilder::add_root_certificate),
+        Err(e) => {
+            warn_user_once!("Ignoring invalid `proxy.ca-bundle`: {e}");
+            builder
+        }
+    };
     builder.build().unwrap_or_else(|e| {
         error!(
             "Unable to create reqwest client, falling back to default {:?}",
@@ -372,6 +474,15 @@ fn create_reqwest_client(native_tls: bool) -> reqwest::Client {
     })
 }
 
+/// Certificates from the `ca-bundle` of the user settings.
+fn ca_bundle_certificates() -> Result<Vec<reqwest::Certificate>> {
+    let Some(path) = &settings::get().proxy.ca_bundle else {
+        return Ok(Vec::new());
+    };
+    let pem = fs_err::read(path)?;
+    Ok(reqwest::Certificate::from_pem_bundle(&pem)?)
+}
+
 fn use_native_tls() -> bool {
     if let Some(val) = EnvVars::var_as_bool(EnvVars::PREK_NATIVE_TLS) {
         return val;
@@ -398,4 +509,17 @@ mod tests {
         let resp = client.get("https://github.com").send().await;
         assert!(resp.is_ok(), "Failed to send request with native TLS");
     }
+
+    #[test]
+    fn parse_checksums() {
+        let checksums = super::parse_checksums(indoc::indoc! {"
+            ABC123  node-v22.0.0-linux-x64.tar.xz
+            def456 *dl/go1.22.0.linux-amd64.tar.gz
+
+            invalid
+        "});
+        assert_eq!(checksums.len(), 2);
+        assert_eq!(checksums["node-v22.0.0-linux-x64.tar.xz"], "abc123");
+        assert_eq!(checksums["go1.22.0.linux-amd64.tar.gz"], "def456");
+    }
 }
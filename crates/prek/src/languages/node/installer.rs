@@ -15,7 +15,9 @@ use crate::fs::LockedFile;
 use crate::languages::node::NodeRequest;
 use crate::languages::node::version::NodeVersion;
 use crate::languages::{REQWEST_CLIENT, download_and_extract};
+use crate::offline;
 use crate::process::Cmd;
+use crate::settings;
 use crate::store::Store;
 
 #[derive(Debug)]
@@ -88,6 +90,15 @@ impl NodeResult {
         &self.npm
     }
 
+    /// The corepack executable shipped next to npm, if any (Node.js 25 dropped it).
+    pub(crate) fn corepack(&self) -> Option<PathBuf> {
+        let corepack = self
+            .npm
+            .with_file_name("corepack")
+            .with_extension(if cfg!(windows) { "cmd" } else { "" });
+        corepack.is_file().then_some(corepack)
+    }
+
     pub(crate) fn version(&self) -> &NodeVersion {
         &self.version
     }
@@ -127,6 +138,7 @@ impl NodeInstaller {
         if !allows_download {
             anyhow::bail!("No suitable system Node version found and downloads are disabled");
         }
+        offline::ensure_cached(|| "A Node.js version matching the request".to_string())?;
 
         let resolved_version = self.resolve_version(request).await?;
         trace!(version = %resolved_version, "Downloading node");
@@ -182,12 +194,11 @@ impl NodeInstaller {
 
     /// List all versions of Node.js available on the Node.js website.
     async fn list_remote_versions(&self) -> Result<Vec<NodeVersion>> {
-        let url = "https://nodejs.org/dist/index.json";
-        let versions: Vec<NodeVersion> = REQWEST_CLIENT.get(url).send().await?.json().await?;
+        let url = format!("{}/index.json", settings::get().mirrors.node());
+        let versions: Vec<NodeVersion> = REQWEST_CLIENT.get(&url).send().await?.json().await?;
         Ok(versions)
     }
 
-    // TODO: support mirror?
     /// Install a specific version of Node.js.
     async fn download(&self, store: &Store, version: &NodeVersion) -> Result<NodeResult> {
         let mut arch = match HOST.architecture {
@@ -214,7 +225,11 @@ impl NodeInstaller {
         let ext = if cfg!(windows) { "zip" } else { "tar.xz" };
 
         let filename = format!("node-v{}-{os}-{arch}.{ext}", version.version());
-        let url = format!("https://nodejs.org/dist/v{}/{filename}", version.version());
+        let url = format!(
+            "{}/v{}/{filename}",
+            settings::get().mirrors.node(),
+            version.version()
+        );
         let target = self.root.join(version.to_string());
 
         download_and_extract(&url, &filename, store, async |extracted| {
@@ -1,6 +1,7 @@
 mod installer;
 #[allow(clippy::module_inception)]
 mod node;
+mod package_manager;
 mod version;
 
 pub(crate) use node::Node;
@@ -14,11 +14,13 @@ use crate::hook::{Hook, InstallInfo};
 use crate::languages::LanguageImpl;
 use crate::languages::node::NodeRequest;
 use crate::languages::node::installer::{NodeInstaller, NodeResult, bin_dir, lib_dir};
+use crate::languages::node::package_manager::{PackageManager, copy_sources};
 use crate::languages::node::version::EXTRA_KEY_LTS;
 use crate::languages::version::LanguageRequest;
 use crate::process::Cmd;
-use crate::run::run_by_batch;
+use crate::run::{Batch, run_by_batch};
 use crate::store::{Store, ToolBucket};
+use crate::warn_user;
 
 #[derive(Debug, Copy, Clone)]
 pub(crate) struct Node;
@@ -53,11 +55,7 @@ impl LanguageImpl for Node {
             .await
             .context("Failed to install node")?;
 
-        let mut info = InstallInfo::new(
-            hook.language,
-            hook.env_key_dependencies().clone(),
-            &store.hooks_dir(),
-        )?;
+        let mut info = InstallInfo::for_hook(&hook, store)?;
 
         let lts = serde_json::to_string(&node.version().lts).context("Failed to serialize LTS")?;
         info.with_toolchain(node.node().to_path_buf());
@@ -80,7 +78,64 @@ impl LanguageImpl for Node {
         .await?;
 
         // 3. Install dependencies
-        let deps = hook.install_dependencies();
+        // `npm` is a script uses `/usr/bin/env node`, we need add `bin_dir` to PATH
+        // so that `npm` can find `node`.
+        let new_path = prepend_paths(&[&bin_dir]).context("Failed to join PATH")?;
+        let npm_install = |deps: &[&String], install_links: bool| {
+            let mut cmd = Cmd::new(node.npm(), "npm install");
+            cmd.arg("install")
+                .arg("-g")
+                .arg("--no-progress")
+                .arg("--no-save")
+                .arg("--no-fund")
+                .arg("--no-audit")
+                .arg(if install_links {
+                    "--install-links"
+                } else {
+                    "--no-install-links"
+                })
+                .args(deps)
+                .env(EnvVars::PATH, &new_path)
+                .env(EnvVars::NPM_CONFIG_PREFIX, &info.env_path)
+                .env_remove(EnvVars::NPM_CONFIG_USERCONFIG)
+                .env(EnvVars::NODE_PATH, &lib_dir)
+                .check(true);
+            cmd
+        };
+
+        let install_dependencies = hook.install_dependencies();
+        let mut deps = install_dependencies.iter().collect::<Vec<_>>();
+
+        // Hook repositories with a pnpm or yarn lockfile are installed with that package manager,
+        // to get the dependency tree the hook was tested with.
+        if let Some(repo) = hook.repo_path()
+            && let Some(package_manager) = PackageManager::detect(repo)
+        {
+            if node.corepack().is_some() {
+                let src_dir = info.env_path.join("src");
+                copy_sources(repo, &src_dir)?;
+                package_manager
+                    .install(
+                        &node,
+                        &src_dir,
+                        &store.tools_path(ToolBucket::Corepack),
+                        &new_path,
+                    )
+                    .await?;
+
+                // Link the package into the environment, keeping its installed `node_modules`.
+                npm_install(&[&src_dir.to_string_lossy().to_string()], false)
+                    .output()
+                    .await?;
+                deps.retain(|dep| Path::new(dep) != repo);
+            } else {
+                warn_user!(
+                    "Hook `{}` ships a {package_manager} lockfile, but corepack is not available, falling back to npm",
+                    hook.id
+                );
+            }
+        }
+
         if deps.is_empty() {
             debug!("No dependencies to install");
         } else {
@@ -92,27 +147,7 @@ impl LanguageImpl for Node {
             //
             // NOTE: If you want to install the content of a directory like a package from the registry
             // instead of creating a link, you would need to use the --install-links option.
-
-            // `npm` is a script uses `/usr/bin/env node`, we need add `bin_dir` to PATH
-            // so that `npm` can find `node`.
-            let new_path = prepend_paths(&[&bin_dir]).context("Failed to join PATH")?;
-
-            Cmd::new(node.npm(), "npm install")
-                .arg("install")
-                .arg("-g")
-                .arg("--no-progress")
-                .arg("--no-save")
-                .arg("--no-fund")
-                .arg("--no-audit")
-                .arg("--install-links")
-                .args(&*deps)
-                .env(EnvVars::PATH, new_path)
-                .env(EnvVars::NPM_CONFIG_PREFIX, &info.env_path)
-                .env_remove(EnvVars::NPM_CONFIG_USERCONFIG)
-                .env(EnvVars::NODE_PATH, &lib_dir)
-                .check(true)
-                .output()
-                .await?;
+            npm_install(&deps, true).output().await?;
         }
 
         info.persist_env_path();
@@ -155,7 +190,7 @@ impl LanguageImpl for Node {
         let new_path = prepend_paths(&[&bin_dir(env_dir)]).context("Failed to join PATH")?;
 
         let entry = hook.entry.resolve(Some(&new_path))?;
-        let run = async |batch: &[&Path]| {
+        let run = async |batch: &Batch<'_>| {
             let mut output = Cmd::new(&entry[0], "node hook")
                 .current_dir(hook.work_dir())
                 .args(&entry[1..])
@@ -164,10 +199,11 @@ impl LanguageImpl for Node {
                 .env_remove(EnvVars::NPM_CONFIG_USERCONFIG)
                 .env(EnvVars::NODE_PATH, lib_dir(env_dir))
                 .envs(&hook.env)
-                .args(&hook.args)
-                .args(batch)
+                .args(batch.args())
                 .check(false)
                 .stdin(Stdio::null())
+                .stdin_data(hook.stdin_data())
+                .process_tree(true)
                 .pty_output()
                 .await?;
 
@@ -0,0 +1,147 @@
+use std::ffi::OsStr;
+use std::fmt::Display;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use prek_consts::env_vars::EnvVars;
+use tracing::{debug, warn};
+
+use crate::languages::node::installer::NodeResult;
+use crate::process::Cmd;
+
+/// Package managers used instead of npm for hook repositories that ship their lockfile.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum PackageManager {
+    Pnpm,
+    Yarn,
+    /// Yarn 2+, which is configured by `.yarnrc.yml` and uses a different lockfile format.
+    YarnBerry,
+}
+
+impl Display for PackageManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl PackageManager {
+    /// Detect the package manager of a hook repository from its lockfile.
+    pub(crate) fn detect(repo: &Path) -> Option<Self> {
+        if repo.join("pnpm-lock.yaml").is_file() {
+            Some(Self::Pnpm)
+        } else if repo.join("yarn.lock").is_file() {
+            if repo.join(".yarnrc.yml").is_file() {
+                Some(Self::YarnBerry)
+            } else {
+                Some(Self::Yarn)
+            }
+        } else {
+            None
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Pnpm => "pnpm",
+            Self::Yarn | Self::YarnBerry => "yarn",
+        }
+    }
+
+    /// Arguments to install the dependencies exactly as locked.
+    ///
+    /// Development dependencies are kept, as a `prepare` script building the package needs them.
+    fn install_args(self) -> &'static [&'static str] {
+        match self {
+            Self::Pnpm | Self::Yarn => &["install", "--frozen-lockfile"],
+            Self::YarnBerry => &["install", "--immutable"],
+        }
+    }
+
+    /// Install the dependencies of the package in `dir` from its lockfile.
+    ///
+    /// The package manager is run with corepack, which downloads the version pinned in the
+    /// `packageManager` field of `package.json` to `corepack_home`.
+    pub(crate) async fn install(
+        self,
+        node: &NodeResult,
+        dir: &Path,
+        corepack_home: &Path,
+        path: &OsStr,
+    ) -> Result<()> {
+        let corepack = node
+            .corepack()
+            .with_context(|| format!("corepack is required to install with {self}"))?;
+
+        debug!(package_manager = %self, dir = %dir.display(), "Installing from lockfile");
+        Cmd::new(corepack, format!("{self} install"))
+            .arg(self.name())
+            .args(self.install_args())
+            .current_dir(dir)
+            .env(EnvVars::PATH, path)
+            .env(EnvVars::COREPACK_HOME, corepack_home)
+            .env(EnvVars::COREPACK_ENABLE_DOWNLOAD_PROMPT, "0")
+            .env(EnvVars::COREPACK_ENABLE_STRICT, "0")
+            // Yarn 2+ defaults to Plug'n'Play, which doesn't leave a `node_modules` to link.
+            .env(EnvVars::YARN_NODE_LINKER, "node-modules")
+            .env(EnvVars::YARN_ENABLE_GLOBAL_CACHE, "true")
+            .remove_git_envs()
+            .check(true)
+            .output()
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Copy the sources of a hook repository, so its dependencies can be installed without
+/// touching the shared clone.
+pub(crate) fn copy_sources(repo: &Path, target: &Path) -> Result<()> {
+    for entry in walkdir::WalkDir::new(repo)
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0
+                || !matches!(entry.file_name().to_str(), Some(".git" | "node_modules"))
+        })
+    {
+        let entry = entry?;
+        let dest = target.join(entry.path().strip_prefix(repo)?);
+        if entry.file_type().is_dir() {
+            fs_err::create_dir_all(&dest)?;
+        } else if let Err(err) = fs_err::copy(entry.path(), &dest) {
+            // Dangling symlinks and the like are not needed for the install.
+            warn!("Failed to copy `{}`: {err}", entry.path().display());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        assert_eq!(PackageManager::detect(dir.path()), None);
+
+        fs_err::write(dir.path().join("yarn.lock"), "")?;
+        assert_eq!(
+            PackageManager::detect(dir.path()),
+            Some(PackageManager::Yarn)
+        );
+
+        fs_err::write(dir.path().join(".yarnrc.yml"), "")?;
+        assert_eq!(
+            PackageManager::detect(dir.path()),
+            Some(PackageManager::YarnBerry)
+        );
+
+        fs_err::write(dir.path().join("pnpm-lock.yaml"), "")?;
+        assert_eq!(
+            PackageManager::detect(dir.path()),
+            Some(PackageManager::Pnpm)
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,186 @@
+use std::path::{Path, PathBuf};
+
+use rustc_hash::FxHashSet;
+use sha2::{Digest, Sha256};
+
+/// Files describing the dependencies of a Python project, an editable install
+/// only needs to be rebuilt when one of them changes.
+const METADATA_FILES: &[&str] = &["pyproject.toml", "setup.py", "setup.cfg"];
+
+/// Prefix of the synthetic dependency recording the editable projects' metadata digest.
+const METADATA_KEY_PREFIX: &str = "editable-metadata:";
+
+/// `additional_dependencies` split into editable installs and regular requirements.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct Requirements<'a> {
+    /// Paths of the projects to install in editable mode.
+    pub(crate) editables: Vec<&'a str>,
+    /// Everything else, passed to the installer unchanged.
+    pub(crate) requirements: Vec<&'a str>,
+}
+
+impl<'a> Requirements<'a> {
+    /// Split `additional_dependencies` into editable and regular requirements.
+    ///
+    /// `additional_dependencies` is an unordered set, so `["-e", "."]` can't be read as a flag
+    /// followed by its value. Instead, a bare `-e`/`--editable` marks every local path
+    /// requirement as editable. `-e .` and `--editable=.` are accepted as single entries too.
+    pub(crate) fn parse(deps: &'a FxHashSet<String>) -> Self {
+        let mut parsed = Self::default();
+        let mut bare_flag = false;
+
+        for dep in deps {
+            let dep = dep.trim();
+            if matches!(dep, "-e" | "--editable") {
+                bare_flag = true;
+            } else if let Some(path) = strip_editable_flag(dep) {
+                parsed.editables.push(path);
+            } else {
+                parsed.requirements.push(dep);
+            }
+        }
+
+        if bare_flag {
+            let (paths, requirements): (Vec<_>, Vec<_>) = parsed
+                .requirements
+                .into_iter()
+                .partition(|dep| is_local_path(dep));
+            parsed.editables.extend(paths);
+            parsed.requirements = requirements;
+        }
+
+        // Keep the installer invocation deterministic.
+        parsed.editables.sort_unstable();
+        parsed.requirements.sort_unstable();
+        parsed
+    }
+}
+
+fn strip_editable_flag(dep: &str) -> Option<&str> {
+    let rest = dep
+        .strip_prefix("--editable")
+        .or_else(|| dep.strip_prefix("-e"))?;
+    let path = rest
+        .strip_prefix('=')
+        .unwrap_or(rest)
+        .trim_start_matches([' ', '\t']);
+    // Require a separator so that e.g. `-egg` isn't mistaken for a flag.
+    (path.len() < rest.len() && !path.is_empty()).then_some(path)
+}
+
+fn is_local_path(dep: &str) -> bool {
+    dep.starts_with('.')
+        || dep.starts_with('/')
+        || dep.starts_with('~')
+        || Path::new(dep).is_absolute()
+}
+
+/// Resolve an editable requirement path against the hook's working directory.
+///
+/// Extras (e.g. `.[dev]`) stay attached to the resolved path.
+pub(crate) fn resolve_editable(path: &str, work_dir: &Path) -> PathBuf {
+    work_dir.join(path)
+}
+
+/// Environment key component for hooks with editable requirements.
+///
+/// Editable installs pick up source changes without reinstalling, but changes to the project
+/// metadata (dependencies, entry points) require a new environment. Returns `None` if there are
+/// no editable requirements.
+pub(crate) fn metadata_key(deps: &FxHashSet<String>, work_dir: &Path) -> Option<String> {
+    let parsed = Requirements::parse(deps);
+    if parsed.editables.is_empty() {
+        return None;
+    }
+
+    let mut hasher = Sha256::new();
+    for editable in &parsed.editables {
+        let project = project_dir(&resolve_editable(editable, work_dir));
+        for file in METADATA_FILES {
+            // Missing files hash differently from empty ones.
+            hasher.update(file.as_bytes());
+            match fs_err::read(project.join(file)) {
+                Ok(content) => {
+                    hasher.update([1]);
+                    hasher.update(&content);
+                }
+                Err(_) => hasher.update([0]),
+            }
+        }
+    }
+
+    Some(format!(
+        "{METADATA_KEY_PREFIX}{}",
+        hex::encode(hasher.finalize())
+    ))
+}
+
+/// Strip a trailing extras specifier (`path[extra1,extra2]`) from an editable path.
+fn project_dir(path: &Path) -> PathBuf {
+    let raw = path.to_string_lossy();
+    match raw
+        .strip_suffix(']')
+        .and_then(|s| s.rfind('[').map(|idx| &s[..idx]))
+    {
+        Some(dir) => PathBuf::from(dir),
+        None => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deps(items: &[&str]) -> FxHashSet<String> {
+        items.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn parse_requirements() {
+        let set = deps(&["-e", ".", "requests"]);
+        let parsed = Requirements::parse(&set);
+        assert_eq!(parsed.editables, vec!["."]);
+        assert_eq!(parsed.requirements, vec!["requests"]);
+
+        let set = deps(&["-e ./pkg[dev]", "--editable=../other", "./wheel.whl"]);
+        let parsed = Requirements::parse(&set);
+        assert_eq!(parsed.editables, vec!["../other", "./pkg[dev]"]);
+        assert_eq!(parsed.requirements, vec!["./wheel.whl"]);
+
+        let set = deps(&["-egg", "."]);
+        let parsed = Requirements::parse(&set);
+        assert!(parsed.editables.is_empty());
+        assert_eq!(parsed.requirements, vec!["-egg", "."]);
+    }
+
+    #[test]
+    fn metadata_key_tracks_metadata_files() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let set = deps(&["-e", "."]);
+
+        assert_eq!(metadata_key(&deps(&["requests"]), dir.path()), None);
+
+        let empty = metadata_key(&set, dir.path());
+        fs_err::write(dir.path().join("pyproject.toml"), "[project]\nname = 'a'\n")?;
+        let first = metadata_key(&set, dir.path());
+        assert_ne!(empty, first);
+
+        // Source changes don't affect the key.
+        fs_err::write(dir.path().join("module.py"), "print('hi')\n")?;
+        assert_eq!(first, metadata_key(&set, dir.path()));
+
+        fs_err::write(dir.path().join("pyproject.toml"), "[project]\nname = 'b'\n")?;
+        assert_ne!(first, metadata_key(&set, dir.path()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn project_dir_strips_extras() {
+        assert_eq!(
+            project_dir(Path::new("/a/b[dev,test]")),
+            PathBuf::from("/a/b")
+        );
+        assert_eq!(project_dir(Path::new("/a/b")), PathBuf::from("/a/b"));
+    }
+}
@@ -1,9 +1,11 @@
+mod editable;
 mod pep723;
 #[allow(clippy::module_inception)]
 mod python;
 mod uv;
 mod version;
 
+pub(crate) use editable::metadata_key as editable_metadata_key;
 pub(crate) use pep723::extract_pep723_metadata;
 pub(crate) use python::Python;
 pub(crate) use python::{python_exec, query_python_info_cached};
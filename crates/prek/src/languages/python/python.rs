@@ -17,11 +17,13 @@ use crate::hook::InstalledHook;
 use crate::hook::{Hook, InstallInfo};
 use crate::languages::LanguageImpl;
 use crate::languages::python::PythonRequest;
+use crate::languages::python::editable::{Requirements, resolve_editable};
 use crate::languages::python::uv::Uv;
 use crate::languages::version::LanguageRequest;
+use crate::offline;
 use crate::process;
 use crate::process::Cmd;
-use crate::run::run_by_batch;
+use crate::run::{Batch, run_by_batch};
 use crate::store::{Store, ToolBucket};
 
 #[derive(Debug, Copy, Clone)]
@@ -118,11 +120,7 @@ impl LanguageImpl for Python {
             .await
             .context("Failed to install uv")?;
 
-        let mut info = InstallInfo::new(
-            hook.language,
-            hook.env_key_dependencies().clone(),
-            &store.hooks_dir(),
-        )?;
+        let mut info = InstallInfo::for_hook(&hook, store)?;
 
         debug!(%hook, target = %info.env_path.display(), "Installing environment");
 
@@ -151,27 +149,32 @@ impl LanguageImpl for Python {
             cmd
         };
 
+        let requirements = Requirements::parse(&hook.additional_dependencies);
+        let add_requirements = |cmd: &mut Cmd| {
+            cmd.args(&requirements.requirements);
+            for editable in &requirements.editables {
+                cmd.arg("--editable")
+                    .arg(resolve_editable(editable, hook.work_dir()));
+            }
+        };
+
         if let Some(repo_path) = hook.repo_path() {
             trace!(
                 "Installing dependencies from repo path: {}",
                 repo_path.display()
             );
-            pip_install()
-                .arg("--directory")
-                .arg(repo_path)
-                .arg(".")
-                .args(&hook.additional_dependencies)
-                .output()
-                .await?;
+            let mut cmd = pip_install();
+            cmd.arg("--directory").arg(repo_path).arg(".");
+            add_requirements(&mut cmd);
+            cmd.output().await?;
         } else if !hook.additional_dependencies.is_empty() {
             trace!(
                 "Installing additional dependencies: {:?}",
                 hook.additional_dependencies
             );
-            pip_install()
-                .args(&hook.additional_dependencies)
-                .output()
-                .await?;
+            let mut cmd = pip_install();
+            add_requirements(&mut cmd);
+            cmd.output().await?;
         } else {
             debug!("No dependencies to install");
         }
@@ -232,7 +235,7 @@ impl LanguageImpl for Python {
         let new_path = prepend_paths(&[&bin_dir(env_dir)]).context("Failed to join PATH")?;
         let entry = hook.entry.resolve(Some(&new_path))?;
 
-        let run = async |batch: &[&Path]| {
+        let run = async |batch: &Batch<'_>| {
             let mut output = Cmd::new(&entry[0], "python hook")
                 .current_dir(hook.work_dir())
                 .args(&entry[1..])
@@ -240,10 +243,11 @@ impl LanguageImpl for Python {
                 .env(EnvVars::PATH, &new_path)
                 .env_remove(EnvVars::PYTHONHOME)
                 .envs(&hook.env)
-                .args(&hook.args)
-                .args(batch)
+                .args(batch.args())
                 .check(false)
                 .stdin(Stdio::null())
+                .stdin_data(hook.stdin_data())
+                .process_tree(true)
                 .pty_output()
                 .await?;
 
@@ -296,7 +300,7 @@ impl Python {
         python_request: &LanguageRequest,
     ) -> Result<()> {
         // Try creating venv without downloads first
-        match Self::create_venv_command(uv, store, info, python_request, false, false)
+        match Self::create_venv_command(uv, store, info, python_request, false)
             .check(true)
             .output()
             .await
@@ -316,12 +320,15 @@ impl Python {
                             "No suitable system Python version found and downloads are disabled"
                         );
                     }
+                    offline::ensure_cached(|| {
+                        "A Python interpreter matching the request".to_string()
+                    })?;
 
                     debug!(
                         "Retrying venv creation with managed Python downloads: `{}`",
                         info.env_path.display()
                     );
-                    Self::create_venv_command(uv, store, info, python_request, true, true)
+                    Self::create_venv_command(uv, store, info, python_request, true)
                         .check(true)
                         .output()
                         .await?;
@@ -342,7 +349,6 @@ impl Python {
         store: &Store,
         info: &InstallInfo,
         python_request: &LanguageRequest,
-        set_install_dir: bool,
         allow_downloads: bool,
     ) -> Cmd {
         let mut cmd = uv.cmd("create venv", store);
@@ -353,17 +359,17 @@ impl Python {
             .arg("--no-project")
             // Explicitly set project to root to avoid uv searching for project-level configs
             .args(["--project", "/"])
+            // Managed Python builds are downloaded to and discovered from the store,
+            // so they are shared between hooks and independent of the user's uv setup.
+            .env(
+                EnvVars::UV_PYTHON_INSTALL_DIR,
+                store.tools_path(ToolBucket::Python),
+            )
             .env_remove(EnvVars::UV_PYTHON)
             // `--managed_python` conflicts with `--python-preference`, ignore any user setting
             .env_remove(EnvVars::UV_MANAGED_PYTHON)
             .env_remove(EnvVars::UV_NO_MANAGED_PYTHON);
 
-        if set_install_dir {
-            cmd.env(
-                EnvVars::UV_PYTHON_INSTALL_DIR,
-                store.tools_path(ToolBucket::Python),
-            );
-        }
         if allow_downloads {
             cmd.arg("--allow-python-downloads");
         } else {
@@ -392,6 +398,7 @@ impl Python {
 
         let stderr = String::from_utf8_lossy(&output.stderr);
         stderr.contains("A managed Python download is available")
+            || stderr.contains("No interpreter found")
     }
 }
 
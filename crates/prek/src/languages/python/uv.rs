@@ -15,6 +15,7 @@ use prek_consts::env_vars::EnvVars;
 
 use crate::fs::LockedFile;
 use crate::languages::{REQWEST_CLIENT, download_and_extract};
+use crate::offline;
 use crate::process::Cmd;
 use crate::store::{CacheBucket, Store};
 use crate::version;
@@ -379,6 +380,9 @@ impl Uv {
     pub(crate) fn cmd(&self, summary: &str, store: &Store) -> Cmd {
         let mut cmd = Cmd::new(&self.path, summary);
         cmd.env(EnvVars::UV_CACHE_DIR, store.cache_path(CacheBucket::Uv));
+        if let Some(mirror) = crate::settings::get().mirrors.python() {
+            cmd.env(EnvVars::UV_PYTHON_INSTALL_MIRROR, mirror);
+        }
         cmd
     }
 
@@ -489,6 +493,8 @@ impl Uv {
             return Ok(Self::new(uv_path));
         }
 
+        offline::ensure_cached(|| "uv".to_string())?;
+
         let source = if let Some(uv_source) = uv_source_from_env() {
             uv_source
         } else {
@@ -501,7 +507,9 @@ impl Uv {
 }
 
 fn uv_source_from_env() -> Option<InstallSource> {
-    let var = EnvVars::var(EnvVars::PREK_UV_SOURCE).ok()?;
+    let var = EnvVars::var(EnvVars::PREK_UV_SOURCE)
+        .ok()
+        .or_else(|| crate::settings::get().mirrors.uv.clone())?;
     match var.as_str() {
         "github" => Some(InstallSource::GitHub),
         "pypi" => Some(InstallSource::PyPi(PyPiMirror::Pypi)),
@@ -0,0 +1,13 @@
+#![warn(dead_code)]
+#![warn(clippy::missing_errors_doc)]
+#![warn(clippy::missing_panics_doc)]
+#![warn(clippy::must_use_candidate)]
+#![warn(clippy::module_name_repetitions)]
+#![warn(clippy::too_many_arguments)]
+
+#[allow(clippy::module_inception)]
+mod r;
+mod version;
+
+pub(crate) use r::R;
+pub(crate) use version::RRequest;
@@ -0,0 +1,326 @@
+use std::env::consts::EXE_SUFFIX;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use prek_consts::env_vars::EnvVars;
+use prek_consts::prepend_paths;
+use tracing::{debug, trace};
+
+use crate::cli::reporter::{HookInstallReporter, HookRunReporter};
+use crate::hook::{Hook, InstallInfo, InstalledHook};
+use crate::languages::LanguageImpl;
+use crate::languages::r::RRequest;
+use crate::languages::version::LanguageRequest;
+use crate::offline;
+use crate::process::Cmd;
+use crate::run::{Batch, run_by_batch};
+use crate::store::Store;
+
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct R;
+
+/// Flags passed to `Rscript` when running hooks, so that site and user profiles
+/// (which commonly activate a project-local renv library) don't shadow the hook library.
+const RSCRIPT_RUN_FLAGS: &[&str] = &[
+    "--no-save",
+    "--no-restore",
+    "--no-site-file",
+    "--no-init-file",
+    "--no-environ",
+];
+
+/// Installs the hook repository and `additional_dependencies` into the hook library.
+///
+/// Arguments: `<library> <repo path or empty> [dependencies...]`.
+const INSTALL_SCRIPT: &str = r#"
+args <- commandArgs(trailingOnly = TRUE)
+lib <- normalizePath(args[[1]], mustWork = TRUE)
+repo <- args[[2]]
+deps <- args[-c(1, 2)]
+.libPaths(c(lib, .libPaths()))
+
+repos <- getOption("repos")
+if (is.null(repos) || identical(unname(repos["CRAN"]), "@CRAN@")) {
+  repos <- c(CRAN = "https://cloud.r-project.org")
+}
+options(repos = repos)
+
+ensure_renv <- function() {
+  if (!requireNamespace("renv", lib.loc = lib, quietly = TRUE)) {
+    install.packages("renv", lib = lib)
+  }
+}
+
+if (nzchar(repo) && file.exists(file.path(repo, "renv.lock"))) {
+  ensure_renv()
+  renv::restore(
+    project = repo,
+    lockfile = file.path(repo, "renv.lock"),
+    library = lib,
+    prompt = FALSE
+  )
+}
+if (nzchar(repo) && file.exists(file.path(repo, "DESCRIPTION"))) {
+  ensure_renv()
+  renv::install(normalizePath(repo), library = lib, prompt = FALSE)
+}
+
+if (length(deps) > 0) {
+  # Plain package names come from CRAN, anything else (`pkg@1.0`, `user/repo`) goes through renv.
+  plain <- grepl("^[A-Za-z][A-Za-z0-9.]*$", deps)
+  if (any(plain)) {
+    install.packages(deps[plain], lib = lib)
+    missing <- deps[plain][!vapply(deps[plain], function(pkg) {
+      requireNamespace(pkg, lib.loc = lib, quietly = TRUE)
+    }, logical(1))]
+    if (length(missing) > 0) {
+      stop("Failed to install: ", paste(missing, collapse = ", "))
+    }
+  }
+  if (any(!plain)) {
+    ensure_renv()
+    renv::install(deps[!plain], library = lib, prompt = FALSE)
+  }
+}
+"#;
+
+struct RInfo {
+    version: semver::Version,
+    executable: PathBuf,
+}
+
+/// Query the version of the given `Rscript` executable.
+async fn query_r_version(rscript: &Path) -> Result<semver::Version> {
+    let stdout = Cmd::new(rscript, "get R version")
+        .arg("--vanilla")
+        .arg("-e")
+        .arg("cat(format(getRversion()))")
+        .check(true)
+        .output()
+        .await?
+        .stdout;
+
+    // 4.4.1
+    let version = String::from_utf8_lossy(&stdout);
+    let version = version.trim();
+    semver::Version::parse(version)
+        .with_context(|| format!("Failed to parse R version `{version}`"))
+}
+
+/// Find an `Rscript` executable satisfying the request.
+///
+/// Candidates are, in order: the requested path, `$R_HOME/bin/Rscript`, and `Rscript` on `PATH`.
+async fn find_r(request: &RRequest) -> Result<RInfo> {
+    let mut candidates = Vec::new();
+
+    if let RRequest::Path(path) = request {
+        if path.is_dir() {
+            candidates.push(path.join("bin").join(format!("Rscript{EXE_SUFFIX}")));
+        } else {
+            candidates.push(path.clone());
+        }
+    } else {
+        if let Some(r_home) = EnvVars::var_os(EnvVars::R_HOME) {
+            candidates.push(
+                PathBuf::from(r_home)
+                    .join("bin")
+                    .join(format!("Rscript{EXE_SUFFIX}")),
+            );
+        }
+        if let Ok(paths) = which::which_all("Rscript") {
+            candidates.extend(paths);
+        }
+    }
+
+    for candidate in candidates {
+        if !candidate.is_file() {
+            continue;
+        }
+        let version = match query_r_version(&candidate).await {
+            Ok(version) => version,
+            Err(err) => {
+                trace!(path = %candidate.display(), "Failed to query R version: {err}");
+                continue;
+            }
+        };
+        if request.matches(&version, Some(&candidate)) {
+            return Ok(RInfo {
+                version,
+                executable: candidate,
+            });
+        }
+        trace!(path = %candidate.display(), %version, "R version does not satisfy the request");
+    }
+
+    anyhow::bail!("No suitable R installation found for request: {request}")
+}
+
+fn r_library(env_path: &Path) -> PathBuf {
+    env_path.join("library")
+}
+
+impl LanguageImpl for R {
+    async fn install(
+        &self,
+        hook: Arc<Hook>,
+        store: &Store,
+        reporter: &HookInstallReporter,
+    ) -> Result<InstalledHook> {
+        let progress = reporter.on_install_start(&hook);
+
+        let request = match &hook.language_request {
+            LanguageRequest::Any { .. } => &RRequest::Any,
+            LanguageRequest::R(req) => req,
+            _ => unreachable!(),
+        };
+        let r_info = find_r(request).await.context("Failed to find R")?;
+
+        let mut info = InstallInfo::for_hook(&hook, store)?;
+
+        debug!(%hook, target = %info.env_path.display(), "Installing R environment");
+
+        let library = r_library(&info.env_path);
+        fs_err::tokio::create_dir_all(&library).await?;
+
+        let repo_path = hook
+            .repo_path()
+            .filter(|path| path.join("renv.lock").is_file() || path.join("DESCRIPTION").is_file());
+        if repo_path.is_some() || !hook.additional_dependencies.is_empty() {
+            offline::ensure_online(|| format!("Installing R packages for hook `{hook}`"))?;
+
+            let script = info.env_path.join("install.R");
+            fs_err::tokio::write(&script, INSTALL_SCRIPT).await?;
+
+            Cmd::new(&r_info.executable, "install R packages")
+                .arg("--vanilla")
+                .arg(&script)
+                .arg(&library)
+                .arg(repo_path.unwrap_or(Path::new("")))
+                .args(&hook.additional_dependencies)
+                .env(EnvVars::R_LIBS_USER, &library)
+                .check(true)
+                .output()
+                .await
+                .context("Failed to install R packages")?;
+        }
+
+        info.with_toolchain(r_info.executable)
+            .with_language_version(r_info.version);
+
+        info.persist_env_path();
+
+        reporter.on_install_complete(progress);
+
+        Ok(InstalledHook::Installed {
+            hook,
+            info: Arc::new(info),
+        })
+    }
+
+    async fn check_health(&self, info: &InstallInfo) -> Result<()> {
+        if !info.toolchain.exists() {
+            anyhow::bail!("Rscript not found at `{}`", info.toolchain.display());
+        }
+
+        let version = query_r_version(&info.toolchain)
+            .await
+            .context("Failed to query current R version")?;
+        if version != info.language_version {
+            anyhow::bail!(
+                "R version mismatch: expected `{}`, found `{version}`",
+                info.language_version
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn run(
+        &self,
+        hook: &InstalledHook,
+        filenames: &[&Path],
+        _store: &Store,
+        reporter: &HookRunReporter,
+    ) -> Result<(i32, Vec<u8>)> {
+        let progress = reporter.on_run_start(hook, filenames.len());
+
+        let env_dir = hook.env_path().expect("R must have env path");
+        let info = hook.install_info().expect("R must have install info");
+        let library = r_library(env_dir);
+
+        let mut paths = Vec::new();
+        if let Some(bin_dir) = info.toolchain.parent() {
+            paths.push(bin_dir);
+        }
+        let new_path = prepend_paths(&paths).context("Failed to join PATH")?;
+
+        let entry = Self::resolve_entry(hook, &info.toolchain, &new_path)?;
+
+        let run = async |batch: &Batch<'_>| {
+            let mut output = Cmd::new(&entry[0], "run R command")
+                .current_dir(hook.work_dir())
+                .args(&entry[1..])
+                .env(EnvVars::PATH, &new_path)
+                .env(EnvVars::R_LIBS, &library)
+                .env(EnvVars::R_LIBS_USER, &library)
+                .envs(&hook.env)
+                .args(batch.args())
+                .check(false)
+                .stdin(Stdio::null())
+                .stdin_data(hook.stdin_data())
+                .process_tree(true)
+                .pty_output()
+                .await?;
+
+            reporter.on_run_progress(progress, batch.len() as u64);
+
+            output.stdout.extend(output.stderr);
+            let code = output.status.code().unwrap_or(1);
+            anyhow::Ok((code, output.stdout))
+        };
+
+        let results = run_by_batch(hook, filenames, &entry, run).await?;
+
+        reporter.on_run_complete(progress);
+
+        let mut combined_status = 0;
+        let mut combined_output = Vec::new();
+
+        for (code, output) in results {
+            combined_status |= code;
+            combined_output.extend(output);
+        }
+
+        Ok((combined_status, combined_output))
+    }
+}
+
+impl R {
+    /// Build the command line for a hook entry.
+    ///
+    /// Entries starting with `Rscript` run with the discovered `Rscript` in an isolated session.
+    /// Like `pre-commit`, a script path following `Rscript` is resolved against the hook repository.
+    fn resolve_entry(hook: &InstalledHook, rscript: &Path, path: &OsStr) -> Result<Vec<String>> {
+        let mut entry = hook.entry.split()?;
+
+        let is_rscript = Path::new(&entry[0])
+            .file_stem()
+            .is_some_and(|stem| stem == "Rscript");
+        if !is_rscript {
+            return Ok(hook.entry.resolve(Some(path))?);
+        }
+
+        entry[0] = rscript.to_string_lossy().to_string();
+        if let (Some(script), Some(repo_path)) = (entry.get(1), hook.repo_path()) {
+            if !script.starts_with('-') && repo_path.join(script).is_file() {
+                entry[1] = repo_path.join(script).to_string_lossy().to_string();
+            }
+        }
+        entry.splice(1..1, RSCRIPT_RUN_FLAGS.iter().map(ToString::to_string));
+
+        Ok(entry)
+    }
+}
@@ -0,0 +1,184 @@
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::hook::InstallInfo;
+use crate::languages::version::{Error, try_into_u64_slice};
+
+/// R version request parsed from `language_version` field
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) enum RRequest {
+    /// Any available R (prefer `R_HOME`, then `PATH`)
+    Any,
+
+    /// Exact major.minor.patch version
+    Exact(u64, u64, u64),
+
+    /// Major.minor (any patch)
+    MajorMinor(u64, u64),
+
+    /// Major version (any minor.patch)
+    Major(u64),
+
+    /// Explicit path to an `Rscript` executable or an R home directory
+    Path(PathBuf),
+
+    /// Semver range (e.g., ">=4.2, <5.0")
+    Range(semver::VersionReq, String),
+}
+
+impl FromStr for RRequest {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Ok(Self::Any);
+        }
+
+        // Strip "R-" prefix if present, e.g. `R-4.4.1` as named by rig and the Windows installer
+        if let Some(version_part) = s.strip_prefix("R").or_else(|| s.strip_prefix("r")) {
+            let version_part = version_part.strip_prefix('-').unwrap_or(version_part);
+            if version_part.is_empty() {
+                return Ok(Self::Any);
+            }
+
+            // Only allow version numbers after "R" prefix
+            return Self::parse_version_numbers(version_part, s);
+        }
+
+        if let Ok(req) = Self::parse_version_numbers(s, s) {
+            return Ok(req);
+        }
+
+        if let Ok(req) = semver::VersionReq::parse(s) {
+            return Ok(Self::Range(req, s.to_string()));
+        }
+
+        let path = PathBuf::from(s);
+        if path.exists() {
+            return Ok(Self::Path(path));
+        }
+
+        Err(Error::InvalidVersion(s.to_string()))
+    }
+}
+
+impl Display for RRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RRequest::Any => write!(f, "any"),
+            RRequest::Exact(major, minor, patch) => write!(f, "{major}.{minor}.{patch}"),
+            RRequest::MajorMinor(major, minor) => write!(f, "{major}.{minor}"),
+            RRequest::Major(major) => write!(f, "{major}"),
+            RRequest::Path(path) => write!(f, "{}", path.display()),
+            RRequest::Range(_, range_str) => write!(f, "{range_str}"),
+        }
+    }
+}
+
+impl RRequest {
+    /// Check if this request accepts any R version
+    pub(crate) fn is_any(&self) -> bool {
+        matches!(self, Self::Any)
+    }
+
+    /// Parse version numbers into appropriate `RRequest` variants
+    fn parse_version_numbers(version_str: &str, original_request: &str) -> Result<RRequest, Error> {
+        let parts = try_into_u64_slice(version_str)
+            .map_err(|_| Error::InvalidVersion(original_request.to_string()))?;
+
+        match parts.as_slice() {
+            [major] => Ok(RRequest::Major(*major)),
+            [major, minor] => Ok(RRequest::MajorMinor(*major, *minor)),
+            [major, minor, patch] => Ok(RRequest::Exact(*major, *minor, *patch)),
+            _ => Err(Error::InvalidVersion(original_request.to_string())),
+        }
+    }
+
+    /// Check if this request matches an R version found during discovery
+    pub(crate) fn matches(&self, version: &semver::Version, toolchain: Option<&Path>) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Exact(maj, min, patch) => {
+                version.major == *maj && version.minor == *min && version.patch == *patch
+            }
+            Self::MajorMinor(maj, min) => version.major == *maj && version.minor == *min,
+            Self::Major(maj) => version.major == *maj,
+            Self::Path(path) => toolchain.is_some_and(|t| t == path || t.starts_with(path)),
+            Self::Range(req, _) => req.matches(version),
+        }
+    }
+
+    /// Check if this request is satisfied by the given R installation
+    pub(crate) fn satisfied_by(&self, install_info: &InstallInfo) -> bool {
+        self.matches(
+            &install_info.language_version,
+            Some(&install_info.toolchain),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Language;
+    use rustc_hash::FxHashSet;
+
+    #[test]
+    fn test_parse_r_request() {
+        assert_eq!(RRequest::from_str("").unwrap(), RRequest::Any);
+        assert_eq!(RRequest::from_str("R").unwrap(), RRequest::Any);
+
+        assert_eq!(
+            RRequest::from_str("4.4.1").unwrap(),
+            RRequest::Exact(4, 4, 1)
+        );
+        assert_eq!(
+            RRequest::from_str("R-4.4.1").unwrap(),
+            RRequest::Exact(4, 4, 1)
+        );
+        assert_eq!(
+            RRequest::from_str("4.4").unwrap(),
+            RRequest::MajorMinor(4, 4)
+        );
+        assert_eq!(RRequest::from_str("r-4").unwrap(), RRequest::Major(4));
+
+        assert!(matches!(
+            RRequest::from_str(">=4.2, <5.0").unwrap(),
+            RRequest::Range(_, _)
+        ));
+        assert!(RRequest::from_str("R>=4.2").is_err());
+        assert!(RRequest::from_str("/nonexistent/Rscript").is_err());
+    }
+
+    #[test]
+    fn test_version_matching() -> anyhow::Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let mut install_info =
+            InstallInfo::new(Language::R, FxHashSet::default(), temp_dir.path())?;
+        install_info
+            .with_language_version(semver::Version::new(4, 4, 1))
+            .with_toolchain(PathBuf::from("/opt/R/4.4.1/bin/Rscript"));
+
+        assert!(RRequest::Any.satisfied_by(&install_info));
+        assert!(RRequest::Exact(4, 4, 1).satisfied_by(&install_info));
+        assert!(RRequest::MajorMinor(4, 4).satisfied_by(&install_info));
+        assert!(RRequest::Major(4).satisfied_by(&install_info));
+        assert!(!RRequest::Exact(4, 4, 2).satisfied_by(&install_info));
+        assert!(!RRequest::Major(3).satisfied_by(&install_info));
+
+        // Both the executable and the R home directory match
+        assert!(
+            RRequest::Path(PathBuf::from("/opt/R/4.4.1/bin/Rscript")).satisfied_by(&install_info)
+        );
+        assert!(RRequest::Path(PathBuf::from("/opt/R/4.4.1")).satisfied_by(&install_info));
+        assert!(!RRequest::Path(PathBuf::from("/opt/R/4.3.0")).satisfied_by(&install_info));
+
+        let req = semver::VersionReq::parse(">=4.2, <5.0")?;
+        assert!(RRequest::Range(req, ">=4.2, <5.0".to_string()).satisfied_by(&install_info));
+        let req = semver::VersionReq::parse(">=4.5")?;
+        assert!(!RRequest::Range(req, ">=4.5".to_string()).satisfied_by(&install_info));
+
+        Ok(())
+    }
+}
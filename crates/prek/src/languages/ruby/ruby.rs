@@ -15,7 +15,7 @@ use crate::languages::ruby::gem::{build_gemspecs, install_gems};
 use crate::languages::ruby::installer::RubyInstaller;
 use crate::languages::version::LanguageRequest;
 use crate::process::Cmd;
-use crate::run::run_by_batch;
+use crate::run::{Batch, run_by_batch};
 use crate::store::Store;
 
 #[derive(Debug, Copy, Clone)]
@@ -45,11 +45,7 @@ impl LanguageImpl for Ruby {
             .context("Failed to install Ruby")?;
 
         // 2. Create InstallInfo
-        let mut info = InstallInfo::new(
-            hook.language,
-            hook.env_key_dependencies().clone(),
-            &store.hooks_dir(),
-        )?;
+        let mut info = InstallInfo::for_hook(&hook, store)?;
 
         info.with_toolchain(ruby.ruby_bin().to_path_buf())
             .with_language_version(ruby.version().clone());
@@ -164,7 +160,7 @@ impl LanguageImpl for Ruby {
         let entry = hook.entry.resolve(Some(&new_path))?;
 
         // Execute in batches
-        let run = async |batch: &[&Path]| {
+        let run = async |batch: &Batch<'_>| {
             let mut output = Cmd::new(&entry[0], "ruby hook")
                 .current_dir(hook.work_dir())
                 .env(EnvVars::PATH, &new_path)
@@ -174,10 +170,11 @@ impl LanguageImpl for Ruby {
                 .env_remove(EnvVars::BUNDLE_GEMFILE)
                 .envs(&hook.env)
                 .args(&entry[1..])
-                .args(&hook.args)
-                .args(batch)
+                .args(batch.args())
                 .check(false)
                 .stdin(Stdio::null())
+                .stdin_data(hook.stdin_data())
+                .process_tree(true)
                 .pty_output()
                 .await?;
 
@@ -0,0 +1,207 @@
+use std::path::{Path, PathBuf};
+
+use rustc_hash::FxHashSet;
+use sha2::{Digest, Sha256};
+
+/// Prefix of the environment key component for path crates, see [`path_dependencies_key`].
+const PATH_KEY_PREFIX: &str = "cargo-path-sources:";
+
+/// Whether an install spec refers to a crate on disk rather than on crates.io or in git.
+fn is_path_dependency(dep: &str) -> bool {
+    dep.starts_with("./")
+        || dep.starts_with("../")
+        || dep == "."
+        || dep == ".."
+        || Path::new(dep).is_absolute()
+}
+
+/// Convert an install spec from `additional_dependencies` into `cargo install` arguments.
+///
+/// Supported formats:
+/// - `name`, `name:version` and `name@version` for crates.io packages,
+/// - `https://host/repo` and `https://host/repo:tag` for git repositories,
+/// - `name --git URL --rev SHA` (or any other `cargo install` arguments) for full control,
+/// - `./path/to/crate` for crates on disk, relative to the hook's working directory.
+pub(crate) fn cargo_install_args(dep: &str, work_dir: &Path) -> Vec<String> {
+    if is_path_dependency(dep) {
+        return vec![
+            "--path".to_string(),
+            work_dir.join(dep).to_string_lossy().to_string(),
+        ];
+    }
+    if dep.contains(char::is_whitespace) {
+        if let Some(args) = shlex::split(dep) {
+            return args;
+        }
+    }
+    if !dep.contains("://") && dep.contains('@') {
+        // `cargo install` understands `name@version` itself.
+        return vec![dep.to_string()];
+    }
+
+    let is_url = dep.starts_with("http://") || dep.starts_with("https://");
+    let (package, version) = if is_url && dep.matches(':').count() == 1 {
+        (dep, "") // We have a url without version
+    } else {
+        dep.rsplit_once(':').unwrap_or((dep, ""))
+    };
+
+    let mut args = Vec::new();
+    if is_url {
+        args.extend(["--git".to_string(), package.to_string()]);
+        if !version.is_empty() {
+            args.extend(["--tag".to_string(), version.to_string()]);
+        }
+    } else {
+        args.push(package.to_string());
+        if !version.is_empty() {
+            args.extend(["--version".to_string(), version.to_string()]);
+        }
+    }
+    args
+}
+
+/// Environment key component for hooks installing crates from local paths.
+///
+/// Unlike registry and git specs, a path does not pin the source it is built from, so a digest
+/// of each crate's location and sources is included. Returns `None` if there are no path specs.
+pub(crate) fn path_dependencies_key(deps: &FxHashSet<String>, work_dir: &Path) -> Option<String> {
+    let mut paths = deps
+        .iter()
+        .map(|dep| dep.strip_prefix("cli:").unwrap_or(dep))
+        .filter(|dep| is_path_dependency(dep))
+        .map(|dep| work_dir.join(dep))
+        .collect::<Vec<_>>();
+    if paths.is_empty() {
+        return None;
+    }
+    paths.sort_unstable();
+
+    let mut hasher = Sha256::new();
+    for path in &paths {
+        hasher.update(path.to_string_lossy().as_bytes());
+        for file in source_files(path) {
+            hasher.update(
+                file.strip_prefix(path)
+                    .unwrap_or(&file)
+                    .to_string_lossy()
+                    .as_bytes(),
+            );
+            match fs_err::read(&file) {
+                Ok(content) => {
+                    hasher.update([1]);
+                    hasher.update(&content);
+                }
+                Err(_) => hasher.update([0]),
+            }
+        }
+    }
+
+    Some(format!(
+        "{PATH_KEY_PREFIX}{}",
+        hex::encode(hasher.finalize())
+    ))
+}
+
+/// The files of a crate that affect the binaries built from it, skipping build outputs and
+/// hidden directories.
+fn source_files(path: &Path) -> Vec<PathBuf> {
+    let mut files = walkdir::WalkDir::new(path)
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0
+                || !(entry.file_name() == "target"
+                    || entry.file_name().to_string_lossy().starts_with('.'))
+        })
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .map(walkdir::DirEntry::into_path)
+        .collect::<Vec<_>>();
+    files.sort_unstable();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cargo_install_args() {
+        let work_dir = Path::new("/work");
+        assert_eq!(cargo_install_args("typos-cli", work_dir), ["typos-cli"]);
+        assert_eq!(
+            cargo_install_args("typos-cli:1.0", work_dir),
+            ["typos-cli", "--version", "1.0"]
+        );
+        assert_eq!(
+            cargo_install_args("typos-cli@1.0", work_dir),
+            ["typos-cli@1.0"]
+        );
+        assert_eq!(
+            cargo_install_args("https://github.com/fish-shell/fish-shell", work_dir),
+            ["--git", "https://github.com/fish-shell/fish-shell"]
+        );
+        assert_eq!(
+            cargo_install_args("https://github.com/fish-shell/fish-shell:4.0", work_dir),
+            [
+                "--git",
+                "https://github.com/fish-shell/fish-shell",
+                "--tag",
+                "4.0"
+            ]
+        );
+        assert_eq!(
+            cargo_install_args(
+                "ripgrep --git https://github.com/BurntSushi/ripgrep --rev abc123",
+                work_dir
+            ),
+            [
+                "ripgrep",
+                "--git",
+                "https://github.com/BurntSushi/ripgrep",
+                "--rev",
+                "abc123"
+            ]
+        );
+        assert_eq!(
+            cargo_install_args("./tools/lint", work_dir),
+            [
+                "--path",
+                &*Path::new("/work").join("./tools/lint").to_string_lossy()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_path_dependencies_key() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let krate = dir.path().join("tool");
+        fs_err::create_dir_all(krate.join("src"))?;
+        fs_err::create_dir_all(krate.join("target"))?;
+        fs_err::write(krate.join("Cargo.toml"), "[package]\nname = \"tool\"\n")?;
+        fs_err::write(krate.join("src/main.rs"), "fn main() {}")?;
+
+        let deps = ["cli:./tool".to_string()].into_iter().collect();
+        assert_eq!(
+            path_dependencies_key(&["serde@1".to_string()].into_iter().collect(), dir.path()),
+            None
+        );
+
+        let key = path_dependencies_key(&deps, dir.path()).expect("path dependency");
+        assert!(key.starts_with(PATH_KEY_PREFIX));
+
+        // Build outputs don't change the key, sources do.
+        fs_err::write(krate.join("target/out"), "binary")?;
+        assert_eq!(
+            path_dependencies_key(&deps, dir.path()).as_ref(),
+            Some(&key)
+        );
+        fs_err::write(krate.join("src/main.rs"), "fn main() { println!(); }")?;
+        assert_ne!(
+            path_dependencies_key(&deps, dir.path()).as_ref(),
+            Some(&key)
+        );
+
+        Ok(())
+    }
+}
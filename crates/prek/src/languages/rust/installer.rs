@@ -11,6 +11,7 @@ use crate::fs::LockedFile;
 use crate::languages::rust::RustRequest;
 use crate::languages::rust::rustup::{Rustup, ToolchainInfo};
 use crate::languages::rust::version::{Channel, RustVersion};
+use crate::offline;
 use crate::process::Cmd;
 
 pub(crate) struct RustResult {
@@ -108,6 +109,7 @@ impl RustInstaller {
         if !allows_download {
             anyhow::bail!("No suitable system Rust version found and downloads are disabled");
         }
+        offline::ensure_cached(|| format!("Rust toolchain `{request}`"))?;
 
         // Install new toolchain
         let toolchain = self.resolve_version(request).await?;
@@ -1,8 +1,10 @@
+mod deps;
 mod installer;
 #[allow(clippy::module_inception)]
 mod rust;
 mod rustup;
 mod version;
 
+pub(crate) use deps::path_dependencies_key as cargo_path_dependencies_key;
 pub(crate) use rust::Rust;
 pub(crate) use version::RustRequest;
@@ -15,12 +15,13 @@ use crate::cli::reporter::{HookInstallReporter, HookRunReporter};
 use crate::hook::{Hook, InstallInfo, InstalledHook};
 use crate::languages::LanguageImpl;
 use crate::languages::rust::RustRequest;
+use crate::languages::rust::deps::cargo_install_args;
 use crate::languages::rust::installer::RustInstaller;
 use crate::languages::rust::rustup::Rustup;
 use crate::languages::rust::version::EXTRA_KEY_CHANNEL;
 use crate::languages::version::LanguageRequest;
 use crate::process::Cmd;
-use crate::run::run_by_batch;
+use crate::run::{Batch, run_by_batch};
 use crate::store::{CacheBucket, Store, ToolBucket};
 
 fn format_cargo_dependency(dep: &str) -> String {
@@ -32,29 +33,6 @@ fn format_cargo_dependency(dep: &str) -> String {
     }
 }
 
-fn format_cargo_cli_dependency(dep: &str) -> Vec<&str> {
-    let is_url = dep.starts_with("http://") || dep.starts_with("https://");
-    let (package, version) = if is_url && dep.matches(':').count() == 1 {
-        (dep, "") // We have a url without version
-    } else {
-        dep.rsplit_once(':').unwrap_or((dep, ""))
-    };
-
-    let mut args = Vec::new();
-    if is_url {
-        args.extend(["--git", package]);
-        if !version.is_empty() {
-            args.extend(["--tag", version]);
-        }
-    } else {
-        args.push(package);
-        if !version.is_empty() {
-            args.extend(["--version", version]);
-        }
-    }
-    args
-}
-
 /// Find the package directory that produces the given binary.
 /// Returns (`package_dir`, `package_name`, `is_workspace`).
 async fn find_package_dir(
@@ -176,6 +154,7 @@ async fn install_local_project(
     cargo: &Path,
     cargo_home: &Path,
     new_path: &OsStr,
+    rustc_wrapper: Option<&Path>,
 ) -> anyhow::Result<()> {
     // Get the binary name from the hook entry
     let entry_parts = hook.entry.split()?;
@@ -216,8 +195,11 @@ async fn install_local_project(
 
     if lib_deps.is_empty() {
         // For packages without lib deps, use `cargo install` directly
-        Cmd::new(cargo, "install local")
-            .args(["install", "--bins", "--root"])
+        let mut cmd = Cmd::new(cargo, "install local");
+        if let Some(wrapper) = rustc_wrapper {
+            cmd.env(EnvVars::RUSTC_WRAPPER, wrapper);
+        }
+        cmd.args(["install", "--bins", "--root"])
             .arg(&info.env_path)
             .args(["--path", "."])
             .current_dir(&package_dir)
@@ -291,6 +273,9 @@ async fn install_local_project(
         if is_workspace && !package_name.is_empty() {
             cmd.args(["--package", &package_name]);
         }
+        if let Some(wrapper) = rustc_wrapper {
+            cmd.env(EnvVars::RUSTC_WRAPPER, wrapper);
+        }
 
         cmd.current_dir(&package_dir)
             .env(EnvVars::PATH, new_path)
@@ -311,6 +296,17 @@ async fn install_local_project(
     Ok(())
 }
 
+/// Use `sccache` to cache compilation across environments if it is installed, unless a
+/// `RUSTC_WRAPPER` is already configured (set it to an empty string to disable the wrapper).
+fn rustc_wrapper() -> Option<PathBuf> {
+    if EnvVars::var_os(EnvVars::RUSTC_WRAPPER).is_some() {
+        return None;
+    }
+    let sccache = which::which("sccache").ok()?;
+    debug!("Using `{}` as rustc wrapper", sccache.display());
+    Some(sccache)
+}
+
 #[derive(Debug, Copy, Clone)]
 pub(crate) struct Rust;
 
@@ -344,11 +340,7 @@ impl LanguageImpl for Rust {
         // Add toolchain bin to PATH, for cargo to use correct rustc
         let new_path = prepend_paths(&[&rustc_bin]).context("Failed to join PATH")?;
 
-        let mut info = InstallInfo::new(
-            hook.language,
-            hook.env_key_dependencies().clone(),
-            &store.hooks_dir(),
-        )?;
+        let mut info = InstallInfo::for_hook(&hook, store)?;
         info.with_toolchain(rust.toolchain().to_path_buf())
             .with_language_version(rust.version().deref().clone());
 
@@ -368,15 +360,19 @@ impl LanguageImpl for Rust {
         fs_err::tokio::create_dir_all(bin_dir(&info.env_path)).await?;
 
         // 3. Install dependencies
-        // Split dependencies by cli: prefix
+        // Split dependencies by cli: prefix. Without a project to add library dependencies to,
+        // all dependencies are installed with `cargo install`.
         let (cli_deps, lib_deps): (Vec<_>, Vec<_>) =
             hook.additional_dependencies.iter().partition_map(|dep| {
                 if let Some(stripped) = dep.strip_prefix("cli:") {
                     Either::Left(stripped)
+                } else if hook.repo_path().is_none() {
+                    Either::Left(dep.as_str())
                 } else {
                     Either::Right(dep)
                 }
             });
+        let rustc_wrapper = rustc_wrapper();
 
         // Install library dependencies and local project
         if let Some(repo) = hook.repo_path() {
@@ -388,6 +384,7 @@ impl LanguageImpl for Rust {
                 &cargo,
                 &cargo_home,
                 &new_path,
+                rustc_wrapper.as_deref(),
             )
             .await?;
         }
@@ -397,7 +394,10 @@ impl LanguageImpl for Rust {
             let mut cmd = Cmd::new(&cargo, "install cli dep");
             cmd.args(["install", "--bins", "--root"])
                 .arg(&info.env_path)
-                .args(format_cargo_cli_dependency(cli_dep));
+                .args(cargo_install_args(cli_dep, hook.work_dir()));
+            if let Some(wrapper) = &rustc_wrapper {
+                cmd.env(EnvVars::RUSTC_WRAPPER, wrapper);
+            }
             cmd.env(EnvVars::PATH, &new_path)
                 .env(EnvVars::CARGO_HOME, &cargo_home)
                 .remove_git_envs()
@@ -439,7 +439,7 @@ impl LanguageImpl for Rust {
         let new_path = prepend_paths(&[&rust_bin, &rustc_bin]).context("Failed to join PATH")?;
 
         let entry = hook.entry.resolve(Some(&new_path))?;
-        let run = async |batch: &[&Path]| {
+        let run = async |batch: &Batch<'_>| {
             let mut output = Cmd::new(&entry[0], "rust hook")
                 .current_dir(hook.work_dir())
                 .args(&entry[1..])
@@ -447,10 +447,11 @@ impl LanguageImpl for Rust {
                 .env(EnvVars::CARGO_HOME, &cargo_home)
                 .env(EnvVars::RUSTUP_AUTO_INSTALL, "0")
                 .envs(&hook.env)
-                .args(&hook.args)
-                .args(batch)
+                .args(batch.args())
                 .check(false)
                 .stdin(Stdio::null())
+                .stdin_data(hook.stdin_data())
+                .process_tree(true)
                 .pty_output()
                 .await?;
 
@@ -783,26 +784,4 @@ edition = "2021"
         assert_eq!(format_cargo_dependency("serde:1.0"), "serde@1.0");
         assert_eq!(format_cargo_dependency("tokio:1.0.0"), "tokio@1.0.0");
     }
-
-    #[test]
-    fn test_format_cargo_cli_dependency() {
-        assert_eq!(format_cargo_cli_dependency("typos-cli"), ["typos-cli"]);
-        assert_eq!(
-            format_cargo_cli_dependency("typos-cli:1.0"),
-            ["typos-cli", "--version", "1.0"]
-        );
-        assert_eq!(
-            format_cargo_cli_dependency("https://github.com/fish-shell/fish-shell"),
-            ["--git", "https://github.com/fish-shell/fish-shell"]
-        );
-        assert_eq!(
-            format_cargo_cli_dependency("https://github.com/fish-shell/fish-shell:4.0"),
-            [
-                "--git",
-                "https://github.com/fish-shell/fish-shell",
-                "--tag",
-                "4.0"
-            ]
-        );
-    }
 }
@@ -12,7 +12,9 @@ use tracing::{debug, trace};
 use crate::fs::LockedFile;
 use crate::languages::REQWEST_CLIENT;
 use crate::languages::rust::version::RustVersion;
+use crate::offline;
 use crate::process::Cmd;
+use crate::retry;
 use crate::store::Store;
 
 #[derive(Clone)]
@@ -71,6 +73,7 @@ impl Rustup {
             });
         }
 
+        offline::ensure_cached(|| "rustup".to_string())?;
         Self::download(store, rustup_home)
             .await
             .context("Failed to install rustup")
@@ -91,20 +94,20 @@ impl Rustup {
         debug!(url = %url, temp_dir = ?temp_dir.path(), "Downloading");
 
         let tmp_target = temp_dir.path().join(filename);
-        let response = REQWEST_CLIENT
-            .get(&url)
-            .send()
-            .await
-            .with_context(|| format!("Failed to download file from {url}"))?;
-        if !response.status().is_success() {
-            anyhow::bail!(
-                "Failed to download file from {}: {}",
-                url,
-                response.status()
-            );
-        }
-
-        let bytes = response.bytes().await?;
+        let bytes = retry::with_retries(
+            &format!("Download of `{url}`"),
+            retry::is_transient_http_error,
+            async || {
+                let response = REQWEST_CLIENT
+                    .get(&url)
+                    .send()
+                    .await
+                    .and_then(reqwest::Response::error_for_status)
+                    .with_context(|| format!("Failed to download file from {url}"))?;
+                anyhow::Ok(response.bytes().await?)
+            },
+        )
+        .await?;
         fs_err::tokio::write(&tmp_target, bytes).await?;
 
         make_executable(&tmp_target)?;
@@ -9,7 +9,7 @@ use crate::hook::InstalledHook;
 use crate::hook::{Hook, InstallInfo};
 use crate::languages::{LanguageImpl, resolve_command};
 use crate::process::Cmd;
-use crate::run::run_by_batch;
+use crate::run::{Batch, run_by_batch};
 use crate::store::Store;
 
 #[derive(Debug, Copy, Clone)]
@@ -49,15 +49,16 @@ impl LanguageImpl for Script {
         split[0] = cmd.to_string_lossy().to_string();
         let entry = resolve_command(split, None);
 
-        let run = async |batch: &[&Path]| {
+        let run = async |batch: &Batch<'_>| {
             let mut output = Cmd::new(&entry[0], "run script command")
                 .current_dir(hook.work_dir())
                 .envs(&hook.env)
                 .args(&entry[1..])
-                .args(&hook.args)
-                .args(batch)
+                .args(batch.args())
                 .check(false)
                 .stdin(Stdio::null())
+                .stdin_data(hook.stdin_data())
+                .process_tree(true)
                 .pty_output()
                 .await?;
 
@@ -2,9 +2,11 @@ use std::str::FromStr;
 
 use crate::config::Language;
 use crate::hook::InstallInfo;
+use crate::languages::coursier::JdkRequest;
 use crate::languages::golang::GoRequest;
 use crate::languages::node::NodeRequest;
 use crate::languages::python::PythonRequest;
+use crate::languages::r::RRequest;
 use crate::languages::ruby::RubyRequest;
 use crate::languages::rust::RustRequest;
 
@@ -17,10 +19,12 @@ pub(crate) enum Error {
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub(crate) enum LanguageRequest {
     Any { system_only: bool },
+    Coursier(JdkRequest),
     Golang(GoRequest),
     Ruby(RubyRequest),
     Node(NodeRequest),
     Python(PythonRequest),
+    R(RRequest),
     Rust(RustRequest),
     // TODO: all other languages default to semver for now.
     Semver(SemverRequest),
@@ -30,9 +34,11 @@ impl LanguageRequest {
     pub(crate) fn is_any(&self) -> bool {
         match self {
             LanguageRequest::Any { .. } => true,
+            LanguageRequest::Coursier(req) => req.is_any(),
             LanguageRequest::Golang(req) => req.is_any(),
             LanguageRequest::Node(req) => req.is_any(),
             LanguageRequest::Python(req) => req.is_any(),
+            LanguageRequest::R(req) => req.is_any(),
             LanguageRequest::Ruby(req) => req.is_any(),
             LanguageRequest::Rust(req) => req.is_any(),
             LanguageRequest::Semver(_) => false,
@@ -61,6 +67,7 @@ impl LanguageRequest {
         // - Python version passed down to `virtualenv`, e.g. `python`, `python3`, `python3.8`
         // - Node.js version passed down to `nodeenv`
         // - Rust version passed down to `rustup`
+        // - JDK version for coursier, a prek extension
 
         if request == "default" || request.is_empty() {
             return Ok(LanguageRequest::Any { system_only: false });
@@ -70,9 +77,11 @@ impl LanguageRequest {
         }
 
         Ok(match lang {
+            Language::Coursier => Self::Coursier(request.parse()?),
             Language::Golang => Self::Golang(request.parse()?),
             Language::Node => Self::Node(request.parse()?),
             Language::Python => Self::Python(request.parse()?),
+            Language::R => Self::R(request.parse()?),
             Language::Ruby => Self::Ruby(request.parse()?),
             Language::Rust => Self::Rust(request.parse()?),
             _ => Self::Semver(request.parse()?),
@@ -82,9 +91,11 @@ impl LanguageRequest {
     pub(crate) fn satisfied_by(&self, install_info: &InstallInfo) -> bool {
         match self {
             LanguageRequest::Any { .. } => true,
+            LanguageRequest::Coursier(req) => req.satisfied_by(install_info),
             LanguageRequest::Golang(req) => req.satisfied_by(install_info),
             LanguageRequest::Node(req) => req.satisfied_by(install_info),
             LanguageRequest::Python(req) => req.satisfied_by(install_info),
+            LanguageRequest::R(req) => req.satisfied_by(install_info),
             LanguageRequest::Ruby(req) => req.satisfied_by(install_info),
             LanguageRequest::Rust(req) => req.satisfied_by(install_info),
             LanguageRequest::Semver(req) => req.satisfied_by(install_info),
@@ -0,0 +1,169 @@
+//! Summary of the most recent `prek run`.
+//!
+//! Every run that executes hooks overwrites `$PREK_HOME/last-run.json` with the selectors it was
+//! invoked with, the outcome of each hook and the files failed hooks failed on. `prek last`
+//! prints it back, and `prek run --retry-failed` runs the failed hooks again on those files.
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::cli::run::summary::{HookSummary, SummaryStatus};
+use crate::config::Stage;
+
+/// The outcome of a hook in the last run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct LastRunHook {
+    #[serde(flatten)]
+    pub(crate) summary: HookSummary,
+    /// Number of files the hook ran on.
+    pub(crate) files: usize,
+    /// Files the hook failed on, relative to the workspace root: the ones it mentioned in its
+    /// output, or else all the files it ran on.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) failed_files: Vec<PathBuf>,
+}
+
+impl LastRunHook {
+    pub(crate) fn failed(&self) -> bool {
+        self.summary.status == SummaryStatus::Failed
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct LastRun {
+    /// The prek version that ran the hooks.
+    pub(crate) version: String,
+    /// Unix timestamp of the end of the run, in seconds.
+    pub(crate) time: u64,
+    pub(crate) workspace: PathBuf,
+    pub(crate) stage: Stage,
+    pub(crate) includes: Vec<String>,
+    pub(crate) skips: Vec<String>,
+    pub(crate) status: SummaryStatus,
+    /// Total duration in seconds.
+    pub(crate) duration: f64,
+    /// Number of files collected for the run.
+    pub(crate) files: usize,
+    pub(crate) hooks: Vec<LastRunHook>,
+}
+
+impl LastRun {
+    /// Start the record of a run, the outcome is filled in when the hooks finished.
+    pub(crate) fn new(
+        workspace: &Path,
+        stage: Stage,
+        includes: Vec<String>,
+        skips: Vec<String>,
+    ) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            time: 0,
+            workspace: workspace.to_path_buf(),
+            stage,
+            includes,
+            skips,
+            status: SummaryStatus::Passed,
+            duration: 0.0,
+            files: 0,
+            hooks: Vec::new(),
+        }
+    }
+
+    /// The hooks that failed.
+    pub(crate) fn failed_hooks(&self) -> impl Iterator<Item = &LastRunHook> {
+        self.hooks.iter().filter(|hook| hook.failed())
+    }
+
+    /// The files any hook failed on, sorted and deduplicated.
+    pub(crate) fn failed_files(&self) -> Vec<&Path> {
+        let mut files = self
+            .failed_hooks()
+            .flat_map(|hook| hook.failed_files.iter().map(PathBuf::as_path))
+            .collect::<Vec<_>>();
+        files.sort_unstable();
+        files.dedup();
+        files
+    }
+
+    /// Write the record, replacing the previous one.
+    pub(crate) fn save(&mut self, path: &Path) -> Result<()> {
+        self.time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+
+        let parent = path.parent().unwrap_or(Path::new("."));
+        // Write to a temporary file first, so `prek last` never reads a partial record.
+        let mut file = tempfile::NamedTempFile::new_in(parent)?;
+        serde_json::to_writer_pretty(&mut file, self)?;
+        file.persist(path)?;
+        Ok(())
+    }
+
+    /// Read the record of the last run, if there is one.
+    pub(crate) fn load(path: &Path) -> Result<Option<Self>> {
+        let content = match fs_err::read(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        Ok(Some(serde_json::from_slice(&content)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hook(id: &str, status: SummaryStatus, failed_files: &[&str]) -> LastRunHook {
+        LastRunHook {
+            summary: HookSummary {
+                id: id.to_string(),
+                name: id.to_string(),
+                project: ".".to_string(),
+                status,
+                skip_reason: None,
+                exit_code: i32::from(status == SummaryStatus::Failed),
+                duration: 0.5,
+            },
+            files: failed_files.len(),
+            failed_files: failed_files.iter().map(PathBuf::from).collect(),
+        }
+    }
+
+    #[test]
+    fn last_run_round_trip() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("last-run.json");
+        assert!(LastRun::load(&path)?.is_none());
+
+        let mut last_run = LastRun::new(dir.path(), Stage::PreCommit, vec![], vec![]);
+        last_run.status = SummaryStatus::Failed;
+        last_run.hooks = vec![
+            hook("ruff", SummaryStatus::Failed, &["b.py", "a.py"]),
+            hook("typos", SummaryStatus::Passed, &[]),
+            hook("mypy", SummaryStatus::Failed, &["a.py"]),
+        ];
+        last_run.save(&path)?;
+
+        let loaded = LastRun::load(&path)?.expect("last run should be saved");
+        assert!(loaded.time > 0);
+        assert_eq!(loaded.stage, Stage::PreCommit);
+        assert_eq!(
+            loaded
+                .failed_hooks()
+                .map(|hook| hook.summary.id.as_str())
+                .collect::<Vec<_>>(),
+            ["ruff", "mypy"]
+        );
+        assert_eq!(
+            loaded.failed_files(),
+            [Path::new("a.py"), Path::new("b.py")]
+        );
+
+        Ok(())
+    }
+}
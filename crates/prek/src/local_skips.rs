@@ -0,0 +1,56 @@
+//! Skip selectors kept in the local repository, managed by `prek skip`.
+//!
+//! The selectors are stored in `.git/prek/skips`, one per line, so they apply to every run in the
+//! repository and its worktrees without being committed. Project paths in the selectors are
+//! relative to the root of the repository.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use crate::git::GIT_COMMON_DIR;
+
+#[derive(Debug, Default)]
+pub(crate) struct LocalSkips {
+    pub(crate) selectors: Vec<String>,
+}
+
+impl LocalSkips {
+    /// The file the selectors of the current repository are stored in.
+    pub(crate) fn path() -> Result<PathBuf> {
+        Ok(GIT_COMMON_DIR.as_ref()?.join("prek").join("skips"))
+    }
+
+    /// Read the selectors, ignoring empty lines and `#` comments.
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        let content = match fs_err::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err.into()),
+        };
+        let selectors = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(ToString::to_string)
+            .collect();
+        Ok(Self { selectors })
+    }
+
+    /// Write the selectors, removing the file when there are none left.
+    pub(crate) fn save(&self, path: &Path) -> Result<()> {
+        if self.selectors.is_empty() {
+            match fs_err::remove_file(path) {
+                Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+                _ => return Ok(()),
+            }
+        }
+
+        let parent = path.parent().unwrap_or(Path::new("."));
+        fs_err::create_dir_all(parent)?;
+        let mut content = self.selectors.join("\n");
+        content.push('\n');
+        fs_err::write(path, content)?;
+        Ok(())
+    }
+}
@@ -18,30 +18,44 @@ use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::{EnvFilter, Layer};
 
-use crate::cleanup::cleanup;
-use crate::cli::{CacheCommand, CacheNamespace, Cli, Command, ExitStatus};
+use crate::cleanup::handle_interrupt;
+use crate::cli::run::CollectOptions;
+use crate::cli::{
+    CacheCommand, CacheNamespace, Cli, Command, ExitStatus, SkipCommand, SkipNamespace,
+};
 #[cfg(feature = "self-update")]
 use crate::cli::{SelfCommand, SelfNamespace, SelfUpdateArgs};
+use crate::fs::Simplified;
 use crate::printer::Printer;
 use crate::run::USE_COLOR;
 use crate::store::Store;
 
 mod archive;
+mod audit;
+mod ci;
 mod cleanup;
 mod cli;
 mod config;
+mod config_refs;
 mod fs;
 mod git;
 mod hook;
 mod hooks;
 mod identify;
 mod languages;
+mod last_run;
+mod local_skips;
+mod offline;
 mod printer;
 mod process;
 #[cfg(all(unix, feature = "profiler"))]
 mod profiler;
+mod retry;
 mod run;
+mod settings;
+mod stats;
 mod store;
+mod tags_cache;
 mod version;
 mod warnings;
 mod workspace;
@@ -138,13 +152,17 @@ fn setup_logging(level: Level, log_file: LogFile, store: &Store) -> Result<()> {
     Ok(())
 }
 
-async fn run(cli: Cli) -> Result<ExitStatus> {
+async fn run(mut cli: Cli) -> Result<ExitStatus> {
     // Enabled ANSI colors on Windows.
     let _ = anstyle_query::windows::enable_ansi_colors();
 
-    ColorChoice::write_global(cli.globals.color.into());
-
     let store = Store::from_settings()?;
+    let settings = settings::init(&store.settings_file())?;
+
+    let color = cli.globals.color.or(settings.color).unwrap_or_default();
+    cli.globals.color = Some(color);
+    ColorChoice::write_global(color.into());
+
     let log_file = LogFile::from_args(cli.globals.log_file.clone(), cli.globals.no_log_file);
     setup_logging(
         match cli.globals.verbose {
@@ -175,6 +193,18 @@ async fn run(cli: Cli) -> Result<ExitStatus> {
     } else {
         warnings::enable();
     }
+    if let Some(level) = cli.globals.warnings {
+        warnings::set_level(level);
+    } else if let Some(level) = settings.warnings {
+        warnings::set_default_level(level);
+    }
+    retry::set_retries(cli.globals.retries);
+    if !cli.globals.offline && !EnvVars::is_set(EnvVars::PREK_OFFLINE) {
+        cli.globals.offline = settings.offline.unwrap_or(false);
+    }
+    if cli.globals.offline {
+        offline::set_offline();
+    }
 
     debug!("prek: {}", version::version());
 
@@ -215,14 +245,40 @@ async fn run(cli: Cli) -> Result<ExitStatus> {
         };
     }
     show_settings!(cli.globals, false);
+    show_settings!(settings, false);
 
     let command = cli
         .command
         .unwrap_or_else(|| Command::Run(Box::new(cli.run_args)));
+    let env_dir = match &command {
+        Command::Run(args) => args.env_dir.as_deref(),
+        Command::TryRepo(args) => args.run_args.env_dir.as_deref(),
+        Command::Exec(args) => args.env_dir.as_deref(),
+        _ => None,
+    };
+    let store = match env_dir {
+        Some(env_dir) => store.with_env_dir(std::path::absolute(env_dir)?),
+        None => store,
+    };
     match command {
         Command::Install(args) => {
             show_settings!(args);
 
+            if let Some(directory) = args.recursive {
+                if cli.globals.config.is_some() {
+                    anyhow::bail!("Cannot use `--config` with `--recursive`");
+                }
+                return cli::install_recursive(
+                    &directory,
+                    args.hook_types,
+                    args.install_hooks,
+                    args.overwrite,
+                    cli.globals.refresh,
+                    printer,
+                )
+                .await;
+            }
+
             cli::install(
                 &store,
                 cli.globals.config,
@@ -235,6 +291,7 @@ async fn run(cli: Cli) -> Result<ExitStatus> {
                 cli.globals.refresh,
                 printer,
                 None,
+                None,
             )
             .await
         }
@@ -244,6 +301,8 @@ async fn run(cli: Cli) -> Result<ExitStatus> {
                 cli.globals.config,
                 args.includes,
                 args.skips,
+                args.force,
+                args.dry_run,
                 cli.globals.refresh,
                 printer,
             )
@@ -257,21 +316,54 @@ async fn run(cli: Cli) -> Result<ExitStatus> {
         Command::Run(args) => {
             show_settings!(args);
 
+            let hook_kind = args.hook_kind();
+            if let Some(filename) = args.stdin_filename {
+                return cli::run_stdin(
+                    &store,
+                    cli.globals.config,
+                    args.includes,
+                    args.skips,
+                    args.no_default_skips,
+                    args.hook_stage,
+                    hook_kind,
+                    &filename,
+                    cli.globals.refresh,
+                    cli.globals.verbose > 0,
+                    printer,
+                )
+                .await;
+            }
             cli::run(
                 &store,
                 cli.globals.config,
                 args.includes,
                 args.skips,
+                args.no_default_skips,
                 args.hook_stage,
+                hook_kind,
                 args.from_ref,
                 args.to_ref,
                 args.all_files,
                 args.files,
                 args.directory,
+                args.include_untracked,
+                args.include_ignored,
+                args.recurse_submodules,
                 args.last_commit,
+                args.changed_only,
+                args.retry_failed,
+                args.max_file_size,
                 args.show_diff_on_failure,
+                args.diff_context,
+                args.word_diff,
+                args.show_fixes,
                 args.fail_fast,
+                args.modified_exit_code,
+                args.autofix_stage,
+                args.serial_projects,
                 args.dry_run,
+                args.write_summary,
+                args.external_reporter,
                 cli.globals.refresh,
                 args.extra,
                 cli.globals.verbose > 0,
@@ -279,16 +371,91 @@ async fn run(cli: Cli) -> Result<ExitStatus> {
             )
             .await
         }
+        Command::Exec(args) => {
+            show_settings!(args);
+
+            cli::exec(&store, cli.globals.config, args.hook, args.files, printer).await
+        }
+        Command::Bisect(args) => {
+            show_settings!(args);
+
+            let opts = CollectOptions {
+                hook_stage: args.hook_stage.unwrap_or_default(),
+                all_files: args.files.is_empty() && args.from_ref.is_none(),
+                from_ref: args.from_ref,
+                to_ref: args.to_ref,
+                files: args.files,
+                ..CollectOptions::default()
+            };
+            cli::bisect(
+                &store,
+                cli.globals.config,
+                args.hook,
+                opts,
+                cli.globals.refresh,
+                printer,
+            )
+            .await
+        }
+        Command::Verify(args) => {
+            show_settings!(args);
+
+            cli::verify(
+                &store,
+                cli.globals.config,
+                &args.range,
+                args.combined,
+                args.includes,
+                args.skips,
+                args.hook_stage,
+                cli.globals.verbose > 0,
+                printer,
+            )
+            .await
+        }
+        Command::Serve(args) => {
+            show_settings!(args);
+
+            cli::serve(
+                &store,
+                cli.globals.config,
+                args.check_on_save,
+                cli.globals.refresh,
+            )
+            .await
+        }
+        Command::Info(args) => {
+            show_settings!(args);
+
+            cli::info(
+                &store,
+                cli.globals.config,
+                args.hook,
+                cli.globals.refresh,
+                printer,
+            )
+            .await
+        }
         Command::List(args) => {
             show_settings!(args);
 
+            let files = args.files.then(|| CollectOptions {
+                hook_stage: args.hook_stage.unwrap_or_default(),
+                from_ref: args.from_ref,
+                to_ref: args.to_ref,
+                all_files: args.all_files,
+                ..CollectOptions::default()
+            });
             cli::list(
                 &store,
                 cli.globals.config,
+                args.manifest,
+                args.rev,
                 args.includes,
                 args.skips,
                 args.hook_stage,
                 args.language,
+                files,
                 args.output_format,
                 cli.globals.refresh,
                 cli.globals.verbose > 0,
@@ -318,21 +485,78 @@ async fn run(cli: Cli) -> Result<ExitStatus> {
         }) => match cache_command {
             CacheCommand::Clean => cli::cache_clean(&store, printer),
             CacheCommand::Dir => {
-                writeln!(printer.stdout(), "{}", store.path().display().cyan())?;
+                writeln!(
+                    printer.stdout(),
+                    "{}",
+                    store.path().simplified_display().cyan()
+                )?;
                 Ok(ExitStatus::Success)
             }
-            CacheCommand::GC => {
-                writeln!(printer.stderr(), "Command not implemented yet")?;
-                Ok(ExitStatus::Failure)
+            CacheCommand::GC(cli::GcArgs { dry_run }) => {
+                cli::cache_gc(&store, dry_run, printer).await
+            }
+            CacheCommand::Size(cli::SizeArgs {
+                human,
+                breakdown,
+                output_format,
+            }) => cli::cache_size(&store, human, breakdown, output_format, printer).await,
+            CacheCommand::Export(cli::ExportArgs { output, categories }) => {
+                cli::cache_export(&store, &output, categories, printer).await
+            }
+            CacheCommand::Import(cli::ImportArgs { archive }) => {
+                cli::cache_import(&store, &archive, printer).await
+            }
+            CacheCommand::Images(cli::ImagesArgs { prune, all }) => {
+                cli::cache_images(&store, prune, all, printer).await
+            }
+            CacheCommand::Logs(cli::LogsArgs { hook }) => {
+                cli::cache_logs(
+                    &store,
+                    cli.globals.config,
+                    hook,
+                    cli.globals.refresh,
+                    printer,
+                )
+                .await
             }
-            CacheCommand::Size(cli::SizeArgs { human }) => cli::cache_size(&store, human, printer),
         },
         Command::Clean => cli::cache_clean(&store, printer),
+        Command::Stats(args) => {
+            show_settings!(args);
+
+            cli::stats(
+                &store,
+                args.slowest,
+                args.flaky,
+                args.limit,
+                args.clear,
+                args.output_format,
+                printer,
+            )
+        }
+        Command::Last(args) => {
+            show_settings!(args);
+
+            cli::last(&store, args.failures, args.output_format, printer)
+        }
+        Command::Skip(SkipNamespace {
+            command: skip_command,
+        }) => match skip_command {
+            SkipCommand::Add(args) => cli::skip_add(&args.selectors, printer),
+            SkipCommand::Remove(args) => cli::skip_remove(&args.selectors, args.all, printer),
+            SkipCommand::List => cli::skip_list(printer),
+        },
+        Command::Audit(args) => {
+            show_settings!(args);
+
+            cli::audit(args.limit, args.output_format, printer).await
+        }
         Command::ValidateConfig(args) => {
             show_settings!(args);
 
-            cli::validate_configs(args.configs, printer)
+            cli::validate_configs(args.configs, args.strict, printer)
         }
+        Command::Render => cli::render(cli.globals.config.as_deref(), printer),
         Command::ValidateManifest(args) => {
             show_settings!(args);
 
@@ -349,6 +573,8 @@ async fn run(cli: Cli) -> Result<ExitStatus> {
                 args.jobs,
                 args.dry_run,
                 args.cooldown_days,
+                !args.no_release_notes,
+                args.github_token,
                 printer,
             )
             .await
@@ -372,9 +598,21 @@ async fn run(cli: Cli) -> Result<ExitStatus> {
             command:
                 SelfCommand::Update(SelfUpdateArgs {
                     target_version,
+                    channel,
                     token,
+                    require_signature,
                 }),
-        }) => cli::self_update(target_version, token, printer).await,
+        }) => {
+            cli::self_update(
+                &store,
+                target_version,
+                channel,
+                token,
+                require_signature,
+                printer,
+            )
+            .await
+        }
         #[cfg(not(feature = "self-update"))]
         Command::Self_(_) => {
             anyhow::bail!(
@@ -403,6 +641,10 @@ async fn run(cli: Cli) -> Result<ExitStatus> {
                 cli.globals.config,
                 args.hook_types,
                 args.no_allow_missing_config,
+                args.bootstrap.then(|| {
+                    args.bootstrap_version
+                        .unwrap_or_else(|| env!("CARGO_PKG_VERSION").to_string())
+                }),
                 cli.globals.refresh,
                 printer,
             )
@@ -416,19 +658,12 @@ async fn run(cli: Cli) -> Result<ExitStatus> {
 }
 
 fn main() -> ExitCode {
+    // Store entries created from now on are newer than the references this run records.
+    std::sync::LazyLock::force(&config_refs::STARTED);
+
     CompleteEnv::with_factory(Cli::command).complete();
 
-    ctrlc::set_handler(move || {
-        cleanup();
-
-        #[allow(clippy::exit, clippy::cast_possible_wrap)]
-        std::process::exit(if cfg!(windows) {
-            0xC000_013A_u32 as i32
-        } else {
-            130
-        });
-    })
-    .expect("Error setting Ctrl-C handler");
+    ctrlc::set_handler(handle_interrupt).expect("Error setting Ctrl-C handler");
 
     let cli = match Cli::try_parse() {
         Ok(cli) => cli,
@@ -450,6 +685,10 @@ fn main() -> ExitCode {
     profiler::finish_profiling(_profiler_guard);
 
     match result {
+        Ok(ExitStatus::Success) if warnings::has_errors() => {
+            eprintln!("{}: Warnings were treated as errors", "error".red().bold());
+            ExitStatus::Failure.into()
+        }
         Ok(code) => code.into(),
         Err(err) => {
             let mut causes = err.chain();
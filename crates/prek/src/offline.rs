@@ -0,0 +1,53 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use thiserror::Error;
+
+use prek_consts::env_vars::EnvVars;
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Error)]
+pub(crate) enum Error {
+    #[error("{0} is not cached, and network access is disabled by `--offline`")]
+    NotCached(String),
+    #[error("{0} requires network access, which is disabled by `--offline`")]
+    NetworkRequired(String),
+}
+
+/// Forbid all network access.
+///
+/// Also configures the package managers prek (and hooks) invoke to only use their local caches.
+pub(crate) fn set_offline() {
+    OFFLINE.store(true, Ordering::SeqCst);
+
+    for (key, value) in [
+        (EnvVars::UV_OFFLINE, "1"),
+        (EnvVars::PIP_NO_INDEX, "1"),
+        (EnvVars::NPM_CONFIG_OFFLINE, "true"),
+        (EnvVars::GOPROXY, "off"),
+        (EnvVars::CARGO_NET_OFFLINE, "true"),
+    ] {
+        unsafe { std::env::set_var(key, value) };
+    }
+}
+
+/// Whether network access is forbidden.
+pub(crate) fn is_offline() -> bool {
+    OFFLINE.load(Ordering::SeqCst)
+}
+
+/// Fail with a "not cached" error if offline, for a resource that must be fetched from the network.
+pub(crate) fn ensure_cached(what: impl FnOnce() -> String) -> Result<(), Error> {
+    if is_offline() {
+        return Err(Error::NotCached(what()));
+    }
+    Ok(())
+}
+
+/// Fail if offline, for an operation that cannot work without network access.
+pub(crate) fn ensure_online(what: impl FnOnce() -> String) -> Result<(), Error> {
+    if is_offline() {
+        return Err(Error::NetworkRequired(what()));
+    }
+    Ok(())
+}
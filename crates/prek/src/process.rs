@@ -25,18 +25,32 @@
 // DEALINGS IN THE SOFTWARE.
 
 /// Adapt [axoprocess] to use [`tokio::process::Process`] instead of [`std::process::Command`].
+use std::cell::RefCell;
 use std::ffi::OsStr;
 use std::fmt::Display;
+use std::io::Write;
 use std::path::Path;
 use std::process::Output;
 use std::process::{CommandArgs, CommandEnvs, ExitStatus, Stdio};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::Duration;
 
 use owo_colors::OwoColorize;
+use rustc_hash::FxHashSet;
 use thiserror::Error;
+use tokio::io::AsyncWriteExt;
 use tracing::trace;
 
+use crate::cleanup::cancellation_token;
 use crate::git::GIT;
 
+/// How long a child gets to exit after being asked to terminate, before it's killed.
+const TERMINATE_GRACE_PERIOD: Duration = Duration::from_secs(3);
+
+/// How long to wait for more output of a pty after its child exited.
+#[cfg(not(windows))]
+const PTY_DRAIN_TIMEOUT: Duration = Duration::from_millis(100);
+
 /// An error from executing a Command
 #[derive(Debug, Error)]
 pub enum Error {
@@ -51,6 +65,8 @@ pub enum Error {
     },
     #[error("Command `{summary}` exited with an error:\n{error}")]
     Status { summary: String, error: StatusError },
+    #[error("Command `{summary}` was cancelled")]
+    Cancelled { summary: String },
     #[cfg(not(windows))]
     #[error("Failed to open pty")]
     Pty(#[from] prek_pty::Error),
@@ -106,6 +122,9 @@ pub struct Cmd {
     pub inner: tokio::process::Command,
     summary: String,
     check_status: bool,
+    cancellable: bool,
+    process_tree: bool,
+    stdin_data: Option<Arc<[u8]>>,
 }
 
 /// Constructors
@@ -117,6 +136,9 @@ impl Cmd {
             summary: summary.into(),
             inner,
             check_status: true,
+            cancellable: true,
+            process_tree: false,
+            stdin_data: None,
         }
     }
 }
@@ -147,6 +169,30 @@ impl Cmd {
         self.check_status = checked;
         self
     }
+
+    /// Provide `data` on the stdin of the command, for [`Cmd::output`] and [`Cmd::pty_output`].
+    pub fn stdin_data(&mut self, data: Option<Arc<[u8]>>) -> &mut Self {
+        self.stdin_data = data;
+        self
+    }
+
+    /// Set whether the command should be terminated when a graceful shutdown is requested.
+    ///
+    /// Defaults to `true`.
+    pub fn cancellable(&mut self, cancellable: bool) -> &mut Self {
+        self.cancellable = cancellable;
+        self
+    }
+
+    /// Set whether the command runs as the root of its own process tree, a process group on Unix
+    /// and a job object on Windows, so that terminating it also terminates the processes it
+    /// spawned. The tree is killed if the command is dropped before it exits.
+    ///
+    /// Defaults to `false`. Only applies to cancellable commands.
+    pub fn process_tree(&mut self, process_tree: bool) -> &mut Self {
+        self.process_tree = process_tree;
+        self
+    }
 }
 
 /// Execution APIs
@@ -170,12 +216,70 @@ impl Cmd {
 
     /// Equivalent to [`std::process::Command::output`][],
     /// but logged, with the error wrapped, and status checked (by default)
+    ///
+    /// If a graceful shutdown is requested while a cancellable command is running, the child is asked
+    /// to terminate, killed if it's still alive after a grace period, and [`Error::Cancelled`]
+    /// is returned.
     pub async fn output(&mut self) -> Result<Output, Error> {
-        self.log_command();
-        let output = self.inner.output().await.map_err(|cause| Error::Exec {
-            summary: self.summary.clone(),
-            cause,
-        })?;
+        if !self.cancellable && self.stdin_data.is_none() {
+            self.log_command();
+            let output = self.inner.output().await.map_err(|cause| Error::Exec {
+                summary: self.summary.clone(),
+                cause,
+            })?;
+            self.record(output.status, &output.stdout, &output.stderr);
+            self.maybe_check_output(&output)?;
+            return Ok(output);
+        }
+
+        // Same as `tokio::process::Command::output`, but keep a handle to the child.
+        self.inner.stdout(Stdio::piped());
+        self.inner.stderr(Stdio::piped());
+        self.inner.kill_on_drop(true);
+        if self.stdin_data.is_some() {
+            self.inner.stdin(Stdio::piped());
+        }
+        if self.process_tree {
+            #[cfg(unix)]
+            self.inner.process_group(0);
+            #[cfg(windows)]
+            self.inner
+                .creation_flags(windows_sys::Win32::System::Threading::CREATE_NEW_PROCESS_GROUP);
+        }
+        let mut child = self.spawn()?;
+        let tree = self
+            .process_tree
+            .then(|| ProcessTree::new(&child))
+            .flatten();
+        self.write_stdin(&mut child);
+        let pid = child.id();
+
+        let wait = child.wait_with_output();
+        tokio::pin!(wait);
+
+        let output = tokio::select! {
+            biased;
+            output = &mut wait => output.map_err(|cause| Error::Exec {
+                summary: self.summary.clone(),
+                cause,
+            })?,
+            () = cancellation_token().cancelled_owned() => {
+                match &tree {
+                    Some(tree) => tree.terminate(),
+                    None => terminate(pid),
+                }
+                // The child is killed when `wait` is dropped, and the rest of its tree when
+                // `tree` is dropped.
+                let _ = tokio::time::timeout(TERMINATE_GRACE_PERIOD, &mut wait).await;
+                return Err(Error::Cancelled {
+                    summary: self.summary.clone(),
+                });
+            }
+        };
+        if let Some(tree) = tree {
+            tree.finish();
+        }
+        self.record(output.status, &output.stdout, &output.stderr);
         self.maybe_check_output(&output)?;
         Ok(output)
     }
@@ -198,7 +302,11 @@ impl Cmd {
         let (mut pty, pts) = prek_pty::open()?;
         let (_, stdout, stderr) = pts.setup_subprocess()?;
 
-        self.inner.stdin(Stdio::null());
+        self.inner.stdin(if self.stdin_data.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        });
         self.inner.stdout(stdout);
         self.inner.stderr(stderr);
 
@@ -214,10 +322,21 @@ impl Cmd {
         // avoids capability negotiation that can deadlock under a fake PTY.
         self.inner.env(EnvVars::TERM, "dumb");
 
+        // The child becomes a session leader, so it's already the leader of its process group.
         let session_leader = pts.session_leader();
         unsafe { self.inner.pre_exec(session_leader) };
+        self.inner.kill_on_drop(true);
 
         let mut child = self.spawn()?;
+        // Close our ends of the terminal, so reading the pty ends when the child exits.
+        drop(pts);
+        self.inner.stdout(Stdio::null());
+        self.inner.stderr(Stdio::null());
+        let tree = self
+            .process_tree
+            .then(|| ProcessTree::new(&child))
+            .flatten();
+        self.write_stdin(&mut child);
 
         let mut stdout = Vec::new();
         let mut buffer = [0u8; 4096];
@@ -233,6 +352,10 @@ impl Cmd {
                         Ok(n) => {
                             stdout.extend_from_slice(&buffer[..n]);
                         }
+                        // Linux reports `EIO` instead of EOF once the terminal is closed.
+                        Err(e) if e.raw_os_error() == Some(libc::EIO) => {
+                            break child.wait().await?;
+                        }
                         Err(e) => {
                             // PTY error, try to get child status
                             if let Ok(Some(status)) = child.try_wait() {
@@ -242,25 +365,34 @@ impl Cmd {
                         }
                     }
                 }
+                () = cancellation_token().cancelled_owned() => {
+                    match &tree {
+                        Some(tree) => tree.terminate(),
+                        None => terminate(child.id()),
+                    }
+                    let _ = tokio::time::timeout(TERMINATE_GRACE_PERIOD, child.wait()).await;
+                    return Err(Error::Cancelled {
+                        summary: self.summary.clone(),
+                    });
+                }
                 status = child.wait() => {
-                    let status = status?;
-                    // On linux, after child exited, the pty `AsyncFd.poll_read_ready` will hang immediately.
-                    // Don't know why, so commenting this out for now.
-
-                    // Child finished, do one final read to get any remaining output
-                    // loop {
-                    //     match pty.read(&mut buffer).await {
-                    //         Ok(0) => break, // EOF
-                    //         Ok(n) => stdout.extend_from_slice(&buffer[..n]),
-                    //         Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
-                    //         Err(_) => break, // Other errors, stop reading
-                    //     }
-                    // }
-                    break status;
+                    break status?;
                 }
             }
         };
 
+        // The child may exit before its output is read. The pty reports EOF (`EIO` on Linux)
+        // once no process holds the terminal open, wait a little for the rest in case a
+        // background process it spawned still does.
+        while let Ok(Ok(n)) = tokio::time::timeout(PTY_DRAIN_TIMEOUT, pty.read(&mut buffer)).await
+            && n > 0
+        {
+            stdout.extend_from_slice(&buffer[..n]);
+        }
+
+        if let Some(tree) = tree {
+            tree.finish();
+        }
         child.stdin.take();
         child.stdout.take();
         child.stderr.take();
@@ -275,6 +407,17 @@ impl Cmd {
         Ok(output)
     }
 
+    /// Write the [`Cmd::stdin_data`] to the stdin of the child in the background.
+    fn write_stdin(&self, child: &mut tokio::process::Child) {
+        let (Some(data), Some(mut stdin)) = (self.stdin_data.clone(), child.stdin.take()) else {
+            return;
+        };
+        tokio::spawn(async move {
+            // The command may exit without reading all of its input.
+            let _ = stdin.write_all(&data).await;
+        });
+    }
+
     /// Equivalent to [`std::process::Command::status`][]
     /// but logged, with the error wrapped, and status checked (by default)
     pub async fn status(&mut self) -> Result<ExitStatus, Error> {
@@ -283,6 +426,7 @@ impl Cmd {
             summary: self.summary.clone(),
             cause,
         })?;
+        self.record(status, &[], &[]);
         self.maybe_check_status(status)?;
         Ok(status)
     }
@@ -446,6 +590,209 @@ impl Cmd {
     pub fn log_command(&self) {
         trace!("Executing `{self}`");
     }
+
+    /// Append the command and its output to the log collected by [`capture_output`], if any.
+    fn record(&self, status: ExitStatus, stdout: &[u8], stderr: &[u8]) {
+        let _ = CAPTURED_OUTPUT.try_with(|log| {
+            let mut log = log.borrow_mut();
+            let _ = write!(log, "$");
+            if let Some(cwd) = self.get_current_dir() {
+                let _ = write!(log, " cd {} &&", cwd.to_string_lossy());
+            }
+            let _ = write!(log, " {}", self.get_program().to_string_lossy());
+            for arg in self.get_args() {
+                let _ = write!(log, " {}", arg.to_string_lossy());
+            }
+            let _ = writeln!(log);
+            for output in [stdout, stderr] {
+                if output.is_empty() {
+                    continue;
+                }
+                log.extend_from_slice(output);
+                if !output.ends_with(b"\n") {
+                    log.push(b'\n');
+                }
+            }
+            let _ = writeln!(log, "[{status}]");
+        });
+    }
+}
+
+tokio::task_local! {
+    /// The commands run by the current task and their output, see [`capture_output`].
+    static CAPTURED_OUTPUT: RefCell<Vec<u8>>;
+}
+
+/// Run `future`, collecting the commands it runs through [`Cmd::output`] and [`Cmd::status`]
+/// together with their output.
+///
+/// Returns the output of `future` and the collected log. Commands run in other tasks spawned
+/// by `future` are not collected.
+pub async fn capture_output<F: Future>(future: F) -> (F::Output, Vec<u8>) {
+    CAPTURED_OUTPUT
+        .scope(RefCell::default(), async move {
+            let output = future.await;
+            let log = CAPTURED_OUTPUT.with(RefCell::take);
+            (output, log)
+        })
+        .await
+}
+
+/// Ask a child process to terminate.
+///
+/// On Unix this sends `SIGTERM`, on other platforms the child is killed when its handle is dropped.
+#[allow(clippy::cast_possible_wrap)]
+fn terminate(pid: Option<u32>) {
+    #[cfg(unix)]
+    if let Some(pid) = pid {
+        unsafe {
+            libc::kill(pid as libc::pid_t, libc::SIGTERM);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = pid;
+}
+
+/// The process trees of commands that are still running.
+static RUNNING_TREES: LazyLock<Mutex<FxHashSet<RawTree>>> = LazyLock::new(Mutex::default);
+
+/// Kill the process trees of all commands that are still running.
+///
+/// Used when prek exits without unwinding, where the trees would otherwise outlive it.
+pub fn kill_process_trees() {
+    for tree in RUNNING_TREES.lock().unwrap().drain() {
+        tree.kill();
+    }
+}
+
+/// The processes spawned by a command started with [`Cmd::process_tree`].
+///
+/// Dropping it before [`ProcessTree::finish`] kills every process still in the tree.
+struct ProcessTree {
+    raw: RawTree,
+    finished: bool,
+}
+
+impl ProcessTree {
+    fn new(child: &tokio::process::Child) -> Option<Self> {
+        let raw = RawTree::new(child)?;
+        RUNNING_TREES.lock().unwrap().insert(raw);
+        Some(Self {
+            raw,
+            finished: false,
+        })
+    }
+
+    /// Ask every process of the tree to terminate.
+    fn terminate(&self) {
+        self.raw.terminate();
+    }
+
+    /// The command exited, leave the processes it left running alone.
+    fn finish(mut self) {
+        self.finished = true;
+    }
+}
+
+impl Drop for ProcessTree {
+    fn drop(&mut self) {
+        if !RUNNING_TREES.lock().unwrap().remove(&self.raw) {
+            // Already killed by `kill_process_trees`.
+            return;
+        }
+        if !self.finished {
+            self.raw.kill();
+        }
+        self.raw.close();
+    }
+}
+
+/// A process group, identified by the pid of its leader.
+#[cfg(unix)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct RawTree(libc::pid_t);
+
+#[cfg(unix)]
+impl RawTree {
+    fn new(child: &tokio::process::Child) -> Option<Self> {
+        let pid = libc::pid_t::try_from(child.id()?).ok()?;
+        Some(Self(pid))
+    }
+
+    fn terminate(self) {
+        unsafe {
+            libc::killpg(self.0, libc::SIGTERM);
+        }
+    }
+
+    fn kill(self) {
+        unsafe {
+            libc::killpg(self.0, libc::SIGKILL);
+        }
+    }
+
+    #[allow(clippy::unused_self)]
+    fn close(self) {}
+}
+
+/// A job object the root process was assigned to, with the root process id.
+///
+/// Processes spawned by the root process before it was assigned to the job are not part of it.
+#[cfg(windows)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct RawTree {
+    pid: u32,
+    job: usize,
+}
+
+#[cfg(windows)]
+impl RawTree {
+    fn new(child: &tokio::process::Child) -> Option<Self> {
+        use windows_sys::Win32::Foundation::CloseHandle;
+        use windows_sys::Win32::System::JobObjects::{AssignProcessToJobObject, CreateJobObjectW};
+
+        let pid = child.id()?;
+        let handle = child.raw_handle()?;
+        unsafe {
+            let job = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+            if job.is_null() {
+                return None;
+            }
+            if AssignProcessToJobObject(job, handle) == 0 {
+                CloseHandle(job);
+                return None;
+            }
+            Some(Self {
+                pid,
+                job: job as usize,
+            })
+        }
+    }
+
+    fn terminate(self) {
+        use windows_sys::Win32::System::Console::{CTRL_BREAK_EVENT, GenerateConsoleCtrlEvent};
+
+        // The root process was started in a new process group, which only receives Ctrl-Break.
+        unsafe {
+            GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, self.pid);
+        }
+    }
+
+    fn kill(self) {
+        use windows_sys::Win32::System::JobObjects::TerminateJobObject;
+
+        unsafe {
+            TerminateJobObject(self.job as _, 1);
+        }
+    }
+
+    fn close(self) {
+        use windows_sys::Win32::Foundation::CloseHandle;
+
+        unsafe {
+            CloseHandle(self.job as _);
+        }
+    }
 }
 
 /// Returns the number of arguments to skip.
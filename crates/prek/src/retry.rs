@@ -0,0 +1,186 @@
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+use anstream::eprintln;
+use owo_colors::OwoColorize;
+use rand::Rng;
+use tracing::debug;
+
+/// The default number of retries for network operations.
+pub(crate) const DEFAULT_RETRIES: u32 = 3;
+
+/// Delay before the first retry, doubled for each further attempt.
+const BASE_DELAY: Duration = Duration::from_millis(500);
+/// Upper bound of the delay between two attempts.
+const MAX_DELAY: Duration = Duration::from_secs(10);
+
+static RETRIES: AtomicU32 = AtomicU32::new(DEFAULT_RETRIES);
+
+/// Set the number of times network operations are retried after a transient failure.
+pub(crate) fn set_retries(retries: u32) {
+    RETRIES.store(retries, Ordering::SeqCst);
+}
+
+/// The delay before the given retry (starting from 1): exponential backoff with jitter.
+fn backoff(retry: u32) -> Duration {
+    let delay = BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+        .min(MAX_DELAY);
+    // "Equal jitter": wait at least half of the delay, so that concurrent retries spread out
+    // without retrying immediately.
+    delay / 2 + delay.mul_f64(rand::rng().random_range(0.0..0.5))
+}
+
+/// Run a network operation, retrying it with exponential backoff while it fails with an error
+/// for which `is_transient` returns true.
+pub(crate) async fn with_retries<T, E, F, Fut>(
+    operation: &str,
+    is_transient: impl Fn(&E) -> bool,
+    f: F,
+) -> Result<T, E>
+where
+    E: Display,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    retry(RETRIES.load(Ordering::SeqCst), operation, is_transient, f).await
+}
+
+/// Run a network operation, retrying it up to `retries` times.
+async fn retry<T, E, F, Fut>(
+    retries: u32,
+    operation: &str,
+    is_transient: impl Fn(&E) -> bool,
+    mut f: F,
+) -> Result<T, E>
+where
+    E: Display,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let attempts = retries + 1;
+    let mut retry = 0;
+    loop {
+        match f().await {
+            Ok(value) => return Ok(value),
+            Err(err) if retry < retries && is_transient(&err) => {
+                retry += 1;
+                let delay = backoff(retry);
+                debug!("{operation} failed: {err}");
+                // Progress rather than a warning: a retry that succeeds is not a problem, and
+                // must not fail the run with `--warnings error`.
+                eprintln!(
+                    "{}",
+                    format!(
+                        "{operation} failed, retrying in {:.1}s (attempt {} of {attempts})",
+                        delay.as_secs_f32(),
+                        retry + 1,
+                    )
+                    .yellow()
+                );
+                tokio::time::sleep(delay).await;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Whether a failed HTTP request or download is worth retrying.
+pub(crate) fn is_transient_http_error(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        if let Some(err) = cause.downcast_ref::<reqwest::Error>() {
+            return is_transient_reqwest_error(err);
+        }
+        if let Some(err) = cause.downcast_ref::<std::io::Error>() {
+            // Errors of the response body stream are wrapped in `io::Error`.
+            if let Some(err) = err.get_ref().and_then(|err| err.downcast_ref()) {
+                return is_transient_reqwest_error(err);
+            }
+            return matches!(
+                err.kind(),
+                std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::TimedOut
+                    | std::io::ErrorKind::UnexpectedEof
+            );
+        }
+        false
+    })
+}
+
+fn is_transient_reqwest_error(err: &reqwest::Error) -> bool {
+    if let Some(status) = err.status() {
+        return status.is_server_error()
+            || status == reqwest::StatusCode::REQUEST_TIMEOUT
+            || status == reqwest::StatusCode::TOO_MANY_REQUESTS;
+    }
+    err.is_connect() || err.is_timeout() || err.is_request() || err.is_body()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    #[test]
+    fn backoff_is_bounded() {
+        for retry in 1..10 {
+            let max = BASE_DELAY
+                .saturating_mul(2u32.pow(retry - 1))
+                .min(MAX_DELAY);
+            let delay = backoff(retry);
+            assert!(delay >= max / 2 && delay <= max, "retry {retry}: {delay:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_only_transient_errors() {
+        let calls = Cell::new(0);
+        let result: Result<(), &str> = retry(
+            0,
+            "test",
+            |_| true,
+            || async {
+                calls.set(calls.get() + 1);
+                Err("error")
+            },
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 1);
+
+        let calls = Cell::new(0);
+        let result: Result<(), &str> = retry(
+            1,
+            "test",
+            |_| false,
+            || async {
+                calls.set(calls.get() + 1);
+                Err("permanent")
+            },
+        )
+        .await;
+        assert_eq!(result, Err("permanent"));
+        assert_eq!(calls.get(), 1);
+
+        let calls = Cell::new(0);
+        let result = retry(
+            1,
+            "test",
+            |_| true,
+            || async {
+                calls.set(calls.get() + 1);
+                if calls.get() < 2 {
+                    Err("flaky")
+                } else {
+                    Ok(42)
+                }
+            },
+        )
+        .await;
+        assert_eq!(result, Ok(42));
+        assert_eq!(calls.get(), 2);
+    }
+}
@@ -1,13 +1,17 @@
 use std::cmp::max;
-use std::ffi::OsStr;
+use std::ffi::{OsStr, OsString};
+use std::io::Write;
 use std::path::Path;
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock, OnceLock};
 
 use anstream::ColorChoice;
 use futures::{StreamExt, TryStreamExt};
 use prek_consts::env_vars::EnvVars;
+use tokio::sync::Semaphore;
 use tracing::trace;
 
+use crate::cleanup::is_cancelled;
+use crate::config::Stage;
 use crate::hook::Hook;
 
 pub(crate) static USE_COLOR: LazyLock<bool> =
@@ -21,6 +25,8 @@ pub(crate) static USE_COLOR: LazyLock<bool> =
 pub(crate) static CONCURRENCY: LazyLock<usize> = LazyLock::new(|| {
     if EnvVars::is_set(EnvVars::PREK_NO_CONCURRENCY) {
         1
+    } else if let Some(jobs) = crate::settings::get().jobs {
+        jobs.get()
     } else {
         std::thread::available_parallelism()
             .map(std::num::NonZero::get)
@@ -28,6 +34,26 @@ pub(crate) static CONCURRENCY: LazyLock<usize> = LazyLock::new(|| {
     }
 });
 
+/// Process-wide pool of slots that every hook batch must hold while it runs.
+///
+/// All hooks of a priority group are started together and their batches queue up on this
+/// pool, so batches from different hooks are interleaved: when one slow hook is still
+/// working through its last batches, the free slots pick up batches from the other hooks
+/// instead of sitting idle. The semaphore is fair, so slots are handed out in request order.
+static BATCH_SLOTS: LazyLock<Semaphore> = LazyLock::new(|| Semaphore::new(*CONCURRENCY));
+
+/// What git passed on the stdin of the hook script of a stage, re-provided on the stdin of the hooks.
+static HOOK_STDIN: OnceLock<(Stage, Arc<[u8]>)> = OnceLock::new();
+
+pub(crate) fn set_hook_stdin(stage: Stage, data: Vec<u8>) {
+    let _ = HOOK_STDIN.set((stage, data.into()));
+}
+
+/// See [`Hook::stdin_data`].
+pub(crate) fn hook_stdin() -> Option<(Stage, Arc<[u8]>)> {
+    HOOK_STDIN.get().cloned()
+}
+
 fn target_concurrency(serial: bool) -> usize {
     if serial { 1 } else { *CONCURRENCY }
 }
@@ -93,7 +119,10 @@ impl<'a> Partitions<'a> {
         filenames: &'a [&'a Path],
         concurrency: usize,
     ) -> anyhow::Result<Self> {
-        let max_per_batch = max(4, filenames.len().div_ceil(concurrency));
+        let mut max_per_batch = max(4, filenames.len().div_ceil(concurrency));
+        if let Some(max_files) = hook.max_files_per_invocation {
+            max_per_batch = max_per_batch.min(max_files.get());
+        }
         let mut max_cli_length = platform_max_cli_length();
 
         let cmd = Path::new(&entry[0]);
@@ -208,6 +237,77 @@ impl<'a> Iterator for Partitions<'a> {
     }
 }
 
+/// Split the files into the batches [`run_by_batch`] would run the hook on.
+pub(crate) fn batches<'a>(
+    hook: &'a Hook,
+    entry: &'a [String],
+    filenames: &'a [&'a Path],
+) -> anyhow::Result<Vec<&'a [&'a Path]>> {
+    let concurrency = target_concurrency(hook.require_serial);
+    Ok(Partitions::split(hook, entry, filenames, concurrency)?.collect())
+}
+
+/// Replaced in hook `args` by the path to a file listing the files of the batch, NUL-delimited.
+///
+/// The files are then not passed as arguments, for tools that only read a file of files.
+pub(crate) const STAGED_FILES_FILE: &str = "{staged_files_file}";
+
+/// A batch of files to run a hook on.
+pub(crate) struct Batch<'a> {
+    files: &'a [&'a Path],
+    args: Vec<OsString>,
+    /// The file listing `files`, removed when the batch is dropped.
+    _files_file: Option<tempfile::TempPath>,
+}
+
+impl<'a> Batch<'a> {
+    fn new(hook: &Hook, files: &'a [&'a Path]) -> anyhow::Result<Self> {
+        if !hook.args.iter().any(|arg| arg.contains(STAGED_FILES_FILE)) {
+            let args = hook
+                .args
+                .iter()
+                .map(OsString::from)
+                .chain(files.iter().map(|file| file.as_os_str().to_os_string()))
+                .collect();
+            return Ok(Self {
+                files,
+                args,
+                _files_file: None,
+            });
+        }
+
+        let mut files_file = tempfile::Builder::new().prefix("prek-files-").tempfile()?;
+        for file in files {
+            files_file.write_all(file.to_string_lossy().as_bytes())?;
+            files_file.write_all(b"\0")?;
+        }
+        files_file.flush()?;
+        let path = files_file.into_temp_path();
+
+        let path_str = path.to_string_lossy();
+        let args = hook
+            .args
+            .iter()
+            .map(|arg| OsString::from(arg.replace(STAGED_FILES_FILE, &path_str)))
+            .collect();
+        Ok(Self {
+            files,
+            args,
+            _files_file: Some(path),
+        })
+    }
+
+    /// The number of files in the batch.
+    pub(crate) fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// The hook `args`, followed by the files unless they are passed in a file.
+    pub(crate) fn args(&self) -> &[OsString] {
+        &self.args
+    }
+}
+
 pub(crate) async fn run_by_batch<T, F>(
     hook: &Hook,
     filenames: &[&Path],
@@ -215,7 +315,7 @@ pub(crate) async fn run_by_batch<T, F>(
     run: F,
 ) -> anyhow::Result<Vec<T>>
 where
-    F: for<'a> AsyncFn(&'a [&'a Path]) -> anyhow::Result<T>,
+    F: for<'a> AsyncFn(&'a Batch<'a>) -> anyhow::Result<T>,
     T: Send + 'static,
 {
     let concurrency = target_concurrency(hook.require_serial);
@@ -229,14 +329,30 @@ where
         hook.id,
     );
 
-    #[allow(clippy::redundant_closure)]
-    let results: Vec<_> = futures::stream::iter(partitions)
-        .map(|batch| run(batch))
-        .buffered(concurrency)
+    let run = &run;
+    let mut results: Vec<_> = futures::stream::iter(partitions.enumerate())
+        .map(|(index, batch)| async move {
+            let _slot = BATCH_SLOTS
+                .acquire()
+                .await
+                .expect("batch slots semaphore is never closed");
+            // Don't start new batches once a shutdown is requested.
+            if is_cancelled() {
+                anyhow::bail!("Hook `{}` was cancelled", hook.id);
+            }
+            let batch = Batch::new(hook, batch)?;
+            Ok((index, run(&batch).await?))
+        })
+        // A batch starts as soon as any batch of the hook finishes, not only the oldest one,
+        // so a slow file doesn't hold back the rest. `require_serial` hooks keep at most one
+        // batch in flight.
+        .buffer_unordered(concurrency)
         .try_collect()
         .await?;
 
-    Ok(results)
+    // Report the results in the order of the batches, whichever finished first.
+    results.sort_unstable_by_key(|(index, _)| *index);
+    Ok(results.into_iter().map(|(_, result)| result).collect())
 }
 
 #[cfg(test)]
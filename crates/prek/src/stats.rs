@@ -0,0 +1,104 @@
+//! Local, opt-in history of hook runs.
+//!
+//! When `PREK_STATS` is enabled, every `prek run` appends one record per executed hook to
+//! `$PREK_HOME/stats.jsonl`. Nothing is ever sent over the network; `prek stats` reads the
+//! history back to find slow or flaky hooks.
+
+use std::io::Write;
+use std::path::Path;
+use std::sync::LazyLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use prek_consts::env_vars::EnvVars;
+
+use crate::cli::run::summary::{HookSummary, SummaryStatus};
+
+static ENABLED: LazyLock<bool> =
+    LazyLock::new(|| EnvVars::var_as_bool(EnvVars::PREK_STATS).unwrap_or(false));
+
+/// Whether recording hook runs is enabled.
+pub(crate) fn is_enabled() -> bool {
+    *ENABLED
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum Outcome {
+    Passed,
+    Failed,
+}
+
+/// A single execution of a hook.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Record {
+    /// Unix timestamp of the end of the run, in seconds.
+    pub(crate) time: u64,
+    pub(crate) project: String,
+    pub(crate) hook: String,
+    pub(crate) outcome: Outcome,
+    /// Duration in seconds.
+    pub(crate) duration: f64,
+}
+
+/// Append the hooks that actually ran to the history file.
+pub(crate) fn record(path: &Path, hooks: &[HookSummary]) -> Result<()> {
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+
+    let mut content = Vec::new();
+    for hook in hooks {
+        let outcome = match hook.status {
+            SummaryStatus::Passed => Outcome::Passed,
+            SummaryStatus::Failed => Outcome::Failed,
+            SummaryStatus::Skipped | SummaryStatus::DryRun | SummaryStatus::Cancelled => continue,
+        };
+        let record = Record {
+            time,
+            project: hook.project.clone(),
+            hook: hook.id.clone(),
+            outcome,
+            duration: hook.duration,
+        };
+        serde_json::to_writer(&mut content, &record)?;
+        content.push(b'\n');
+    }
+    if content.is_empty() {
+        return Ok(());
+    }
+
+    // A single `write` of a whole run keeps concurrent runs from interleaving records.
+    let mut file = fs_err::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    file.write_all(&content)?;
+
+    Ok(())
+}
+
+/// Read the history file, skipping records that can't be parsed.
+pub(crate) fn load(path: &Path) -> Result<Vec<Record>> {
+    let content = match fs_err::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+
+    Ok(content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(record) => Some(record),
+            Err(err) => {
+                debug!(%err, "Skipping invalid stats record");
+                None
+            }
+        })
+        .collect())
+}
@@ -6,18 +6,24 @@ use std::sync::Arc;
 use anyhow::Result;
 use etcetera::BaseStrategy;
 use futures::StreamExt;
+use itertools::Itertools;
+use rustc_hash::FxHashSet;
 use thiserror::Error;
 use tracing::{debug, warn};
 
 use prek_consts::env_vars::EnvVars;
 
-use crate::config::RemoteRepo;
+use crate::config::{Language, RemoteRepo};
 use crate::fs::LockedFile;
-use crate::git::clone_repo;
+use crate::git;
 use crate::hook::InstallInfo;
+use crate::offline;
 use crate::run::CONCURRENCY;
 use crate::workspace::HookInitReporter;
 
+/// Written to a cloned repository once it is completely checked out.
+pub(crate) const REPO_MARKER: &str = ".prek-repo.json";
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("Home directory not found")]
@@ -28,37 +34,116 @@ pub enum Error {
     Git(#[from] crate::git::Error),
     #[error(transparent)]
     Serde(#[from] serde_json::Error),
+    #[error(transparent)]
+    Offline(#[from] crate::offline::Error),
 }
 
 /// A store for managing repos.
+///
+/// Everything is written to `path`, except package caches that can be recreated at any time,
+/// which may live in a separate `cache` directory. A read-only store, such as a cache baked into
+/// a CI image, can be layered below it: repos and hook environments found there are used as is.
 #[derive(Debug)]
 pub struct Store {
     path: PathBuf,
+    cache: PathBuf,
+    read_only: Option<PathBuf>,
+    /// The directory the environment of the single hook to install is pinned to.
+    env_dir: Option<PathBuf>,
 }
 
 impl Store {
     pub(crate) fn from_path(path: impl Into<PathBuf>) -> Self {
-        Self { path: path.into() }
+        // Hook environments (e.g. `node_modules`) easily exceed `MAX_PATH` on Windows.
+        let path = crate::fs::long_path(path);
+        Self {
+            cache: path.join("cache"),
+            path,
+            read_only: None,
+            env_dir: None,
+        }
+    }
+
+    /// Install the environment of the hook into `path` instead of the store, and reuse it from
+    /// there, so that it can be inspected.
+    pub(crate) fn with_env_dir(mut self, path: impl Into<PathBuf>) -> Self {
+        self.env_dir = Some(path.into());
+        self
+    }
+
+    /// The directory the hook environment is pinned to, see [`Store::with_env_dir`].
+    pub(crate) fn env_dir(&self) -> Option<&Path> {
+        self.env_dir.as_deref()
+    }
+
+    /// Keep package caches in `path` rather than in the store.
+    pub(crate) fn with_cache_dir(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cache = crate::fs::long_path(path);
+        self
+    }
+
+    /// Layer a read-only store below this one.
+    pub(crate) fn with_read_only(mut self, path: impl Into<PathBuf>) -> Self {
+        self.read_only = Some(crate::fs::long_path(path));
+        self
     }
 
     /// Create a store from environment variables or default paths.
+    ///
+    /// `PREK_HOME` keeps everything in one directory. Otherwise toolchains, repos and hook
+    /// environments go to the data directory and package caches to the cache directory, so that
+    /// backups and container layers can treat them differently.
     pub(crate) fn from_settings() -> Result<Self, Error> {
-        let path = if let Some(path) = EnvVars::var_os(EnvVars::PREK_HOME) {
-            Some(path.into())
+        let base = etcetera::choose_base_strategy().ok();
+        let (path, cache) = if let Some(path) = EnvVars::var_os(EnvVars::PREK_HOME) {
+            (PathBuf::from(path), None)
         } else {
-            etcetera::choose_base_strategy()
-                .map(|path| path.cache_dir().join("prek"))
-                .ok()
+            let default_cache = base.as_ref().map(|base| base.cache_dir().join("prek"));
+            let data = EnvVars::var_os(EnvVars::PREK_DATA_DIR)
+                .map(PathBuf::from)
+                .or_else(|| Self::default_data_dir(base.as_ref()?, default_cache.as_ref()?));
+            match data {
+                Some(data) => (data, default_cache),
+                // Stores created before data and caches were split keep both in one directory.
+                None => match default_cache {
+                    Some(path) => (path, None),
+                    None => return Err(Error::HomeNotFound),
+                },
+            }
         };
-
-        let Some(path) = path else {
-            return Err(Error::HomeNotFound);
+        let cache = EnvVars::var_os(EnvVars::PREK_CACHE_DIR)
+            .map(PathBuf::from)
+            .or(cache);
+        // With an overlay, `PREK_HOME` is only read from and everything new goes to the overlay.
+        let mut store = if let Some(overlay) = EnvVars::var_os(EnvVars::PREK_HOME_OVERLAY) {
+            debug!(
+                "Using `{}` as a read-only store",
+                Path::new(&path).display()
+            );
+            Store::from_path(overlay).with_read_only(path)
+        } else {
+            Store::from_path(path)
         };
-        let store = Store::from_path(path).init()?;
+        if let Some(cache) = cache {
+            store = store.with_cache_dir(cache);
+        }
+        let store = store.init()?;
 
         Ok(store)
     }
 
+    /// The platform data directory for the store, unless a store created before data and caches
+    /// were split exists in the cache directory.
+    ///
+    /// On Windows, the data directory is the roaming `%APPDATA%`, which is no place for
+    /// toolchains, so the store stays in `%LOCALAPPDATA%` with the caches.
+    fn default_data_dir(base: &impl BaseStrategy, default_cache: &Path) -> Option<PathBuf> {
+        if cfg!(windows) || default_cache.join("README").is_file() {
+            return None;
+        }
+        Some(base.data_dir().join("prek"))
+    }
+
     pub(crate) fn path(&self) -> &Path {
         self.path.as_ref()
     }
@@ -88,8 +173,23 @@ impl Store {
         reporter: Option<&dyn HookInitReporter>,
     ) -> Result<PathBuf, Error> {
         // Check if the repo is already cloned.
-        let target = self.repo_path(repo);
-        if target.join(".prek-repo.json").try_exists()? {
+        if let Some(read_only) = &self.read_only {
+            let target = Self::repo_path(read_only, repo);
+            if target.join(REPO_MARKER).try_exists()? {
+                return Ok(target);
+            }
+        }
+        let target = Self::repo_path(&self.path, repo);
+        if target.join(REPO_MARKER).try_exists()? {
+            return Ok(target);
+        }
+
+        offline::ensure_cached(|| format!("Repository `{repo}`"))?;
+
+        // Another process may be cloning the same repo, wait for it and check again.
+        let name = target.file_name().unwrap_or_default().to_string_lossy();
+        let _lock = self.lock_entry(&format!("repo-{name}"), repo).await?;
+        if target.join(REPO_MARKER).try_exists()? {
             return Ok(target);
         }
 
@@ -104,14 +204,14 @@ impl Store {
             %repo,
             "Cloning repo",
         );
-        clone_repo(&repo.repo, &repo.rev, temp.path()).await?;
+        self.clone_shared(repo, temp.path()).await?;
 
         // TODO: add windows retry
         fs_err::tokio::remove_dir_all(&target).await.ok();
         fs_err::tokio::rename(temp, &target).await?;
 
         let content = serde_json::to_string_pretty(&repo)?;
-        fs_err::tokio::write(target.join(".prek-repo.json"), content).await?;
+        fs_err::tokio::write(target.join(REPO_MARKER), content).await?;
 
         if let Some((reporter, progress)) = progress {
             reporter.on_clone_complete(progress);
@@ -120,11 +220,122 @@ impl Store {
         Ok(target)
     }
 
-    /// Returns installed hooks in the store.
-    pub(crate) async fn installed_hooks(&self) -> Vec<Arc<InstallInfo>> {
-        let Ok(dirs) = fs_err::read_dir(self.hooks_dir()) else {
-            return vec![];
+    /// Check out `repo` at `path`, with its objects in the object store shared by all revisions
+    /// of the repo, so that revisions don't each keep a copy of the files they have in common.
+    ///
+    /// Falls back to a clone with objects of its own if the revision can't be fetched alone.
+    async fn clone_shared(&self, repo: &RemoteRepo, path: &Path) -> Result<(), Error> {
+        let (objects_repo, objects_dir) = self.repo_objects(&repo.repo);
+        let commit = {
+            let name = objects_repo
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy();
+            let _lock = self
+                .lock_entry(&format!("repo-objects-{name}"), &repo.repo)
+                .await?;
+            git::fetch_shared_objects(&repo.repo, &repo.rev, &objects_repo).await
+        };
+
+        match commit {
+            Ok(commit) => {
+                git::checkout_shared_objects(&repo.repo, &commit, &objects_dir, path).await?;
+            }
+            // A clone won't fare better if the remote can't be reached.
+            Err(err) if git::is_network_error(&err) => return Err(err.into()),
+            Err(err) => {
+                warn!(
+                    ?err,
+                    "Failed to fetch into the shared object store, falling back to clone"
+                );
+                git::clone_repo(&repo.repo, &repo.rev, path).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Move the objects of a repo cloned before revisions shared an object store, or as a
+    /// fallback, into the object store shared by all revisions of `url`.
+    pub(crate) async fn share_repo_objects(&self, path: &Path, url: &str) -> Result<(), Error> {
+        let (objects_repo, objects_dir) = self.repo_objects(url);
+        let name = objects_repo
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy();
+        let _lock = self
+            .lock_entry(&format!("repo-objects-{name}"), url)
+            .await?;
+        git::share_objects(url, path, &objects_repo, &objects_dir).await?;
+        Ok(())
+    }
+
+    /// The bare repo holding the objects of all revisions of `url` cloned into the store, and
+    /// its objects directory relative to the `.git/objects` directory of a cloned repo.
+    fn repo_objects(&self, url: &str) -> (PathBuf, PathBuf) {
+        let mut hasher = DefaultHasher::new();
+        url.hash(&mut hasher);
+        let digest = to_hex(hasher.finish());
+        let objects_dir = ["..", "..", "..", "..", "repo-objects", &digest, "objects"]
+            .iter()
+            .collect();
+        (self.repo_objects_dir().join(digest), objects_dir)
+    }
+
+    /// Fetch the full history of a repo cloned into the store, for hooks whose installation
+    /// needs it, for example to derive the package version from tags.
+    ///
+    /// Returns whether the repo was shallow and now has its full history.
+    pub(crate) async fn unshallow_repo(&self, path: &Path) -> bool {
+        // Repos in the read-only store can't be changed.
+        if !path.starts_with(&self.path) {
+            return false;
+        }
+        if offline::is_offline() {
+            return false;
+        }
+
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let _lock = match self
+            .lock_entry(&format!("repo-{name}"), path.display())
+            .await
+        {
+            Ok(lock) => lock,
+            Err(err) => {
+                debug!("Failed to lock `{}`: {err}", path.display());
+                return false;
+            }
         };
+        match git::unshallow_repo(path).await {
+            Ok(unshallowed) => unshallowed,
+            Err(err) => {
+                debug!(
+                    "Failed to fetch the full history of `{}`: {err}",
+                    path.display()
+                );
+                false
+            }
+        }
+    }
+
+    /// Returns installed hooks in the store, including the read-only store.
+    ///
+    /// With a pinned environment directory, only the environment there is returned.
+    pub(crate) async fn installed_hooks(&self) -> Vec<Arc<InstallInfo>> {
+        if let Some(env_dir) = &self.env_dir {
+            return InstallInfo::from_env_path(env_dir)
+                .await
+                .ok()
+                .map(Arc::new)
+                .into_iter()
+                .collect();
+        }
+
+        let dirs = self
+            .read_only
+            .iter()
+            .chain(std::iter::once(&self.path))
+            .filter_map(|store| fs_err::read_dir(store.join("hooks")).ok())
+            .flatten();
 
         let mut tasks = futures::stream::iter(dirs)
             .map(async |entry| {
@@ -156,22 +367,63 @@ impl Store {
         hooks
     }
 
+    /// Lock the whole store, for operations that remove or replace data other processes may use.
     pub(crate) async fn lock_async(&self) -> Result<LockedFile, std::io::Error> {
         LockedFile::acquire(self.path.join(".lock"), "store").await
     }
 
-    /// Returns the path to the cloned repo.
-    fn repo_path(&self, repo: &RemoteRepo) -> PathBuf {
+    /// Lock the store against [`Store::lock_async`], for operations that only add data to it.
+    ///
+    /// Processes holding the shared lock coordinate with [`Store::lock_entry`] instead.
+    pub(crate) async fn lock_shared_async(&self) -> Result<LockedFile, std::io::Error> {
+        LockedFile::acquire_shared(self.path.join(".lock"), "store").await
+    }
+
+    /// Lock a single entry of the store, such as a repo clone or a hook environment.
+    async fn lock_entry(
+        &self,
+        name: &str,
+        resource: impl std::fmt::Display,
+    ) -> Result<LockedFile, std::io::Error> {
+        let dir = self.locks_dir();
+        fs_err::tokio::create_dir_all(&dir).await?;
+        LockedFile::acquire(dir.join(format!("{name}.lock")), resource).await
+    }
+
+    /// Lock the record of the store entries used by each config file, see [`crate::config_refs`].
+    pub(crate) async fn lock_config_refs(&self) -> Result<LockedFile, std::io::Error> {
+        self.lock_entry("config-refs", "config references").await
+    }
+
+    /// Lock the environment with the given key, see [`env_digest`], so concurrent processes
+    /// don't install the same environment at the same time.
+    pub(crate) async fn lock_env(
+        &self,
+        language: Language,
+        env_key: &str,
+    ) -> Result<LockedFile, std::io::Error> {
+        let name = format!("env-{}-{env_key}", language.as_str());
+        self.lock_entry(&name, format!("{} environment", language.as_str()))
+            .await
+    }
+
+    /// Returns the path to the cloned repo in the given store directory.
+    fn repo_path(store: &Path, repo: &RemoteRepo) -> PathBuf {
         let mut hasher = DefaultHasher::new();
         repo.hash(&mut hasher);
         let digest = to_hex(hasher.finish());
-        self.repos_dir().join(digest)
+        store.join("repos").join(digest)
     }
 
     pub(crate) fn repos_dir(&self) -> PathBuf {
         self.path.join("repos")
     }
 
+    /// Object stores shared by the clones of all revisions of a repo.
+    pub(crate) fn repo_objects_dir(&self) -> PathBuf {
+        self.path.join("repo-objects")
+    }
+
     pub(crate) fn hooks_dir(&self) -> PathBuf {
         self.path.join("hooks")
     }
@@ -180,13 +432,38 @@ impl Store {
         self.path.join("patches")
     }
 
+    /// Lock files of single repos and hook environments.
+    pub(crate) fn locks_dir(&self) -> PathBuf {
+        self.path.join("locks")
+    }
+
+    pub(crate) fn tools_dir(&self) -> PathBuf {
+        self.path.join("tools")
+    }
+
     /// The path to the tool directory in the store.
     pub(crate) fn tools_path(&self, tool: ToolBucket) -> PathBuf {
-        self.path.join("tools").join(tool.as_str())
+        self.tools_dir().join(tool.as_str())
+    }
+
+    /// prek binaries, downloaded by hook scripts that bootstrap prek, or kept by
+    /// `prek self update` in `previous` to roll back to.
+    pub(crate) fn bin_dir(&self) -> PathBuf {
+        self.path.join("bin")
+    }
+
+    /// Package caches, such as downloads of uv, cargo and go, that can be recreated at any time.
+    pub(crate) fn cache_dir(&self) -> PathBuf {
+        self.cache.clone()
+    }
+
+    /// Whether package caches are kept outside of the store.
+    pub(crate) fn has_separate_cache_dir(&self) -> bool {
+        !self.cache.starts_with(&self.path)
     }
 
     pub(crate) fn cache_path(&self, tool: CacheBucket) -> PathBuf {
-        self.path.join("cache").join(tool.as_str())
+        self.cache_dir().join(tool.as_str())
     }
 
     /// Scratch path for temporary files.
@@ -197,6 +474,55 @@ impl Store {
     pub(crate) fn log_file(&self) -> PathBuf {
         self.path.join("prek.log")
     }
+
+    /// Build logs of hook environments.
+    pub(crate) fn logs_dir(&self) -> PathBuf {
+        self.path.join("logs")
+    }
+
+    /// The log of the latest build of the environment with the given key, see [`env_digest`].
+    pub(crate) fn env_log_file(&self, env_key: &str) -> PathBuf {
+        self.logs_dir().join(format!("{env_key}.log"))
+    }
+
+    /// The history of hook runs, see [`crate::stats`].
+    pub(crate) fn stats_file(&self) -> PathBuf {
+        self.path.join("stats.jsonl")
+    }
+
+    /// The store entries used by each config file, see [`crate::config_refs`].
+    pub(crate) fn config_refs_file(&self) -> PathBuf {
+        self.path.join("config-refs.json")
+    }
+
+    /// The name of an entry of the store, such as `repos/<digest>`, relative to the store.
+    ///
+    /// Returns `None` for paths outside of the store, such as entries of the read-only store.
+    pub(crate) fn entry_name(&self, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(&self.path).ok()?;
+        Some(
+            relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .join("/"),
+        )
+    }
+
+    /// The summary of the last run, see [`crate::last_run`].
+    pub(crate) fn last_run_file(&self) -> PathBuf {
+        self.path.join("last-run.json")
+    }
+
+    /// User-level settings, see [`crate::settings`].
+    ///
+    /// Falls back to the settings of the read-only store.
+    pub(crate) fn settings_file(&self) -> PathBuf {
+        let path = self.path.join("config.toml");
+        match &self.read_only {
+            Some(read_only) if !path.exists() => read_only.join("config.toml"),
+            _ => path,
+        }
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -204,7 +530,9 @@ pub(crate) enum ToolBucket {
     Uv,
     Python,
     Node,
+    Corepack,
     Go,
+    Jdk,
     Ruby,
     Rustup,
 }
@@ -213,7 +541,9 @@ impl ToolBucket {
     pub(crate) fn as_str(&self) -> &str {
         match self {
             ToolBucket::Go => "go",
+            ToolBucket::Jdk => "jdk",
             ToolBucket::Node => "node",
+            ToolBucket::Corepack => "corepack",
             ToolBucket::Python => "python",
             ToolBucket::Ruby => "ruby",
             ToolBucket::Rustup => "rustup",
@@ -243,7 +573,62 @@ impl CacheBucket {
     }
 }
 
+/// The key of a hook environment, a digest of its language, `language_version` and dependencies.
+///
+/// Neither the project nor the repo of a hook are part of the key, other than through the
+/// dependencies, so hooks with the same key share an environment across the workspace.
+pub(crate) fn env_digest(
+    language: Language,
+    language_version: &str,
+    dependencies: &FxHashSet<String>,
+) -> String {
+    let mut hasher = DefaultHasher::new();
+    language.as_str().hash(&mut hasher);
+    language_version.hash(&mut hasher);
+    dependencies
+        .iter()
+        .sorted()
+        .for_each(|dep| dep.hash(&mut hasher));
+    to_hex(hasher.finish())
+}
+
 /// Convert a u64 to a hex string.
 fn to_hex(num: u64) -> String {
     hex::encode(num.to_le_bytes())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_digest_includes_language_version() {
+        let deps = |deps: &[&str]| deps.iter().map(ToString::to_string).collect();
+        let digest = env_digest(
+            Language::Python,
+            "3.12",
+            &deps(&["flake8==7.1.1", "pep8-naming"]),
+        );
+
+        assert_eq!(
+            digest,
+            env_digest(
+                Language::Python,
+                "3.12",
+                &deps(&["pep8-naming", "flake8==7.1.1"])
+            )
+        );
+        assert_ne!(
+            digest,
+            env_digest(
+                Language::Python,
+                "3.13",
+                &deps(&["flake8==7.1.1", "pep8-naming"])
+            )
+        );
+        assert_ne!(
+            digest,
+            env_digest(Language::Python, "3.12", &deps(&["flake8==7.1.1"]))
+        );
+    }
+}
@@ -0,0 +1,248 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use anyhow::Result;
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::identify::{TagSet, tags_from_path_with};
+use crate::store::{CacheBucket, Store};
+
+/// Files modified this recently are not cached, as a later write within the resolution of the
+/// file system timestamps would go unnoticed.
+const RACY_WINDOW: Duration = Duration::from_secs(2);
+
+/// Classification of a file, valid as long as its metadata is unchanged.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedTags {
+    size: u64,
+    modified: SystemTime,
+    executable: bool,
+    /// The number of bytes read to classify the file as text or binary.
+    detection_bytes: usize,
+    tags: Vec<String>,
+}
+
+impl CachedTags {
+    fn matches(&self, metadata: &std::fs::Metadata, detection_bytes: usize) -> bool {
+        self.size == metadata.len()
+            && metadata
+                .modified()
+                .is_ok_and(|modified| modified == self.modified)
+            && self.executable == is_executable(metadata)
+            && self.detection_bytes == detection_bytes
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TagsCacheFile {
+    /// Cache version for compatibility
+    version: u32,
+    /// Cached tags by path, relative to the workspace root
+    entries: FxHashMap<PathBuf, CachedTags>,
+}
+
+/// Cache of file type tags persisted between runs, keyed by path and invalidated when the size,
+/// modification time or executable bit of the file changes.
+pub(crate) struct TagsCache {
+    path: PathBuf,
+    workspace_root: PathBuf,
+    entries: FxHashMap<PathBuf, CachedTags>,
+    /// Entries computed during this run.
+    updated: Mutex<FxHashMap<PathBuf, CachedTags>>,
+}
+
+impl TagsCache {
+    const CURRENT_VERSION: u32 = 1;
+
+    /// Get cache file path for a workspace
+    fn cache_path(store: &Store, workspace_root: &Path) -> PathBuf {
+        let mut hasher = DefaultHasher::new();
+        workspace_root.hash(&mut hasher);
+        let digest = hex::encode(hasher.finish().to_le_bytes());
+
+        store
+            .cache_path(CacheBucket::Prek)
+            .join("tags")
+            .join(digest)
+    }
+
+    /// Load the cache of the workspace, starting empty if it is missing or invalid.
+    pub(crate) fn load(store: &Store, workspace_root: &Path) -> Self {
+        let path = Self::cache_path(store, workspace_root);
+
+        let entries = match std::fs::read(&path) {
+            Ok(content) => match serde_json::from_slice::<TagsCacheFile>(&content) {
+                Ok(cache) if cache.version == Self::CURRENT_VERSION => cache.entries,
+                Ok(_) => {
+                    let _ = std::fs::remove_file(&path);
+                    FxHashMap::default()
+                }
+                Err(e) => {
+                    debug!("Failed to deserialize tags cache: {}", e);
+                    let _ = std::fs::remove_file(&path);
+                    FxHashMap::default()
+                }
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => FxHashMap::default(),
+            Err(e) => {
+                debug!("Failed to read tags cache: {}", e);
+                FxHashMap::default()
+            }
+        };
+
+        Self {
+            path,
+            workspace_root: workspace_root.to_path_buf(),
+            entries,
+            updated: Mutex::new(FxHashMap::default()),
+        }
+    }
+
+    /// Identify tags for a file, relative to the current directory, reusing the cached tags if
+    /// the file is unchanged since they were computed.
+    pub(crate) fn tags(&self, filename: &Path, detection_bytes: usize) -> Result<TagSet> {
+        let metadata = std::fs::symlink_metadata(filename)?;
+        if !metadata.is_file() {
+            return tags_from_path_with(filename, detection_bytes);
+        }
+
+        if let Some(cached) = self.entries.get(filename)
+            && cached.matches(&metadata, detection_bytes)
+            && let Some(tags) = TagSet::from_names(cached.tags.iter().map(String::as_str))
+        {
+            return Ok(tags);
+        }
+
+        let tags = tags_from_path_with(filename, detection_bytes)?;
+
+        let Ok(modified) = metadata.modified() else {
+            return Ok(tags);
+        };
+        let racy = SystemTime::now()
+            .duration_since(modified)
+            .is_ok_and(|elapsed| elapsed < RACY_WINDOW)
+            || modified > SystemTime::now();
+        if !racy {
+            self.updated.lock().unwrap().insert(
+                filename.to_path_buf(),
+                CachedTags {
+                    size: metadata.len(),
+                    modified,
+                    executable: is_executable(&metadata),
+                    detection_bytes,
+                    tags: tags.iter().map(ToString::to_string).collect(),
+                },
+            );
+        }
+
+        Ok(tags)
+    }
+
+    /// Write the cache back, dropping entries of files that no longer exist.
+    pub(crate) fn save(self) -> Result<()> {
+        let updated = self.updated.into_inner().unwrap();
+        if updated.is_empty() {
+            return Ok(());
+        }
+
+        let mut entries = self.entries;
+        entries.extend(updated);
+        entries.retain(|path, _| self.workspace_root.join(path).is_file());
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let cache = TagsCacheFile {
+            version: Self::CURRENT_VERSION,
+            entries,
+        };
+        // Write to a temporary file first, concurrent runs may save the cache at the same time.
+        let mut file =
+            tempfile::NamedTempFile::new_in(self.path.parent().unwrap_or(Path::new(".")))?;
+        serde_json::to_writer(&mut file, &cache)?;
+        file.persist(&self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(metadata: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_metadata: &std::fs::Metadata) -> bool {
+    // Determined from the shebang, which is covered by the modification time.
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_old(path: &Path, content: &str) -> Result<()> {
+        std::fs::write(path, content)?;
+        let file = std::fs::File::options().write(true).open(path)?;
+        file.set_modified(SystemTime::now() - Duration::from_secs(60))?;
+        Ok(())
+    }
+
+    #[test]
+    fn cached_tags_invalidated_on_change() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let file = dir.path().join("notes");
+        write_old(&file, "hello\n")?;
+
+        let cache = TagsCache {
+            path: dir.path().join("cache"),
+            workspace_root: dir.path().to_path_buf(),
+            entries: FxHashMap::default(),
+            updated: Mutex::new(FxHashMap::default()),
+        };
+        let tags = cache.tags(&file, 1024)?;
+        assert!(tags.contains("text"));
+        cache.save()?;
+
+        let content = std::fs::read(dir.path().join("cache"))?;
+        let stored: TagsCacheFile = serde_json::from_slice(&content)?;
+        let cached = &stored.entries[&file];
+
+        let entries = FxHashMap::from_iter([(
+            file.clone(),
+            CachedTags {
+                tags: vec!["file".to_string(), "binary".to_string()],
+                ..cached.clone()
+            },
+        )]);
+        let cache = TagsCache {
+            path: dir.path().join("cache"),
+            workspace_root: dir.path().to_path_buf(),
+            entries: entries.clone(),
+            updated: Mutex::new(FxHashMap::default()),
+        };
+        // Unchanged file: the cached entry is used.
+        assert!(cache.tags(&file, 1024)?.contains("binary"));
+        // Different detection size: the entry does not apply.
+        assert!(!cache.tags(&file, 512)?.contains("binary"));
+
+        // Changed file: the entry is invalidated.
+        write_old(&file, "hello world\n")?;
+        let cache = TagsCache {
+            path: dir.path().join("cache"),
+            workspace_root: dir.path().to_path_buf(),
+            entries,
+            updated: Mutex::new(FxHashMap::default()),
+        };
+        let tags = cache.tags(&file, 1024)?;
+        assert!(tags.contains("text"));
+        assert!(!tags.contains("binary"));
+
+        Ok(())
+    }
+}
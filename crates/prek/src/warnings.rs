@@ -21,40 +21,130 @@
 // SOFTWARE.
 
 use std::collections::HashSet;
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 use std::sync::{LazyLock, Mutex};
 
+use anstream::eprintln;
+use owo_colors::OwoColorize;
+use serde::Deserialize;
+
 // macro hygiene: The user might not have direct dependencies on those crates
 #[doc(hidden)]
 pub use anstream;
 #[doc(hidden)]
 pub use owo_colors;
 
+/// How prek's own warnings are treated.
+#[derive(
+    Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize, clap::ValueEnum,
+)]
+#[serde(rename_all = "kebab-case")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[repr(u8)]
+pub(crate) enum WarningLevel {
+    /// Don't show warnings.
+    Ignore,
+    /// Show warnings.
+    #[default]
+    Warn,
+    /// Show warnings as errors and exit with a non-zero status.
+    Error,
+}
+
+impl WarningLevel {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Ignore,
+            1 => Self::Warn,
+            _ => Self::Error,
+        }
+    }
+}
+
 /// Whether user-facing warnings are enabled.
 pub static ENABLED: AtomicBool = AtomicBool::new(false);
 
+static LEVEL: AtomicU8 = AtomicU8::new(WarningLevel::Warn as u8);
+/// Whether the level was set explicitly on the command line, which takes precedence over configs.
+static LEVEL_FROM_CLI: AtomicBool = AtomicBool::new(false);
+static LEVEL_FROM_CONFIG: AtomicBool = AtomicBool::new(false);
+/// Whether a warning has been reported as an error.
+static ERRORED: AtomicBool = AtomicBool::new(false);
+
 /// Enable user-facing warnings.
 pub fn enable() {
-    ENABLED.store(true, std::sync::atomic::Ordering::SeqCst);
+    ENABLED.store(true, Ordering::SeqCst);
 }
 
 /// Disable user-facing warnings.
 pub fn disable() {
-    ENABLED.store(false, std::sync::atomic::Ordering::SeqCst);
+    ENABLED.store(false, Ordering::SeqCst);
+}
+
+/// Set the warning level from the command line.
+pub(crate) fn set_level(level: WarningLevel) {
+    LEVEL.store(level as u8, Ordering::SeqCst);
+    LEVEL_FROM_CLI.store(true, Ordering::SeqCst);
+}
+
+/// Set the warning level from the user settings, the config files take precedence.
+pub(crate) fn set_default_level(level: WarningLevel) {
+    LEVEL.store(level as u8, Ordering::SeqCst);
+}
+
+/// Set the warning level from a config file, unless it was set on the command line.
+///
+/// When multiple configs set a level, the strictest one wins.
+pub(crate) fn set_config_level(level: WarningLevel) {
+    if LEVEL_FROM_CLI.load(Ordering::SeqCst) {
+        return;
+    }
+    if LEVEL_FROM_CONFIG.swap(true, Ordering::SeqCst) {
+        LEVEL.fetch_max(level as u8, Ordering::SeqCst);
+    } else {
+        LEVEL.store(level as u8, Ordering::SeqCst);
+    }
+}
+
+pub(crate) fn level() -> WarningLevel {
+    WarningLevel::from_u8(LEVEL.load(Ordering::SeqCst))
+}
+
+/// Whether any warning was reported as an error.
+pub(crate) fn has_errors() -> bool {
+    ERRORED.load(Ordering::SeqCst)
+}
+
+/// Report a warning according to the current [`WarningLevel`].
+///
+/// This is the sink behind [`warn_user!`] and [`warn_user_once!`].
+#[doc(hidden)]
+pub fn emit(message: &str) {
+    match level() {
+        WarningLevel::Ignore => {}
+        WarningLevel::Warn => {
+            if ENABLED.load(Ordering::SeqCst) {
+                eprintln!(
+                    "{}{} {}",
+                    "warning".yellow().bold(),
+                    ":".bold(),
+                    message.bold()
+                );
+            }
+        }
+        WarningLevel::Error => {
+            ERRORED.store(true, Ordering::SeqCst);
+            eprintln!("{}{} {}", "error".red().bold(), ":".bold(), message.bold());
+        }
+    }
 }
 
 /// Warn a user, if warnings are enabled.
 #[macro_export]
 macro_rules! warn_user {
     ($($arg:tt)*) => {
-        use $crate::warnings::anstream::eprintln;
-        use $crate::warnings::owo_colors::OwoColorize;
-
-        if $crate::warnings::ENABLED.load(std::sync::atomic::Ordering::SeqCst) {
-            let message = format!("{}", format_args!($($arg)*));
-            let formatted = message.bold();
-            eprintln!("{}{} {formatted}", "warning".yellow().bold(), ":".bold());
-        }
+        let message = format!("{}", format_args!($($arg)*));
+        $crate::warnings::emit(&message);
     };
 }
 
@@ -65,15 +155,10 @@ pub static WARNINGS: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(Mutex::def
 #[macro_export]
 macro_rules! warn_user_once {
     ($($arg:tt)*) => {
-        use $crate::warnings::anstream::eprintln;
-        use $crate::warnings::owo_colors::OwoColorize;
-
-        if $crate::warnings::ENABLED.load(std::sync::atomic::Ordering::SeqCst) {
-            if let Ok(mut states) = $crate::warnings::WARNINGS.lock() {
-                let message = format!("{}", format_args!($($arg)*));
-                if states.insert(message.clone()) {
-                    eprintln!("{}{} {}", "warning".yellow().bold(), ":".bold(), message.bold());
-                }
+        if let Ok(mut states) = $crate::warnings::WARNINGS.lock() {
+            let message = format!("{}", format_args!($($arg)*));
+            if states.insert(message.clone()) {
+                $crate::warnings::emit(&message);
             }
         }
     };
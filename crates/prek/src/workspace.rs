@@ -325,6 +325,8 @@ impl Project {
                         let mut builder =
                             HookBuilder::new(self.clone(), repo, hook.clone(), hooks.len());
                         builder.update(hook_config);
+                        builder
+                            .repo_filters(repo_config.files.as_ref(), repo_config.exclude.as_ref());
                         builder.combine(&self.config);
 
                         let hook = builder.build().await?;
@@ -336,6 +338,8 @@ impl Project {
                         let repo = Arc::clone(repo);
                         let mut builder =
                             HookBuilder::new(self.clone(), repo, hook_config.clone(), hooks.len());
+                        builder
+                            .repo_filters(repo_config.files.as_ref(), repo_config.exclude.as_ref());
                         builder.combine(&self.config);
 
                         let hook = builder.build().await?;
@@ -369,6 +373,27 @@ impl Project {
             }
         }
 
+        if self.config.uses_hook_ordering() {
+            let ordering = hooks
+                .iter()
+                .map(|hook| config::HookOrdering {
+                    id: &hook.id,
+                    priority: hook.priority,
+                    after: &hook.after,
+                    before: &hook.before,
+                })
+                .collect::<Vec<_>>();
+            let priorities = config::resolve_hook_order(&ordering).map_err(|cycle| {
+                config::Error::OrderingCycle(
+                    self.config_path.user_display().to_string(),
+                    cycle.join(" -> "),
+                )
+            })?;
+            for (hook, priority) in hooks.iter_mut().zip(priorities) {
+                hook.priority = priority;
+            }
+        }
+
         Ok(hooks)
     }
 }
@@ -395,6 +420,9 @@ struct WorkspaceCache {
     created_at: SystemTime,
     /// Configuration files with their metadata
     config_files: Vec<CachedConfigFile>,
+    /// Whether the projects in git submodules were discovered
+    #[serde(default)]
+    recurse_submodules: bool,
 }
 
 impl WorkspaceCache {
@@ -403,7 +431,7 @@ impl WorkspaceCache {
     const MAX_CACHE_AGE: u64 = 60 * 60;
 
     /// Create a new cache from workspace discovery results
-    fn new(workspace_root: PathBuf, projects: &[Project]) -> Self {
+    fn new(workspace_root: PathBuf, projects: &[Project], recurse_submodules: bool) -> Self {
         let mut config_files = Vec::new();
 
         for project in projects {
@@ -421,6 +449,7 @@ impl WorkspaceCache {
             created_at: SystemTime::now(),
             workspace_root,
             config_files,
+            recurse_submodules,
         }
     }
 
@@ -487,7 +516,12 @@ impl WorkspaceCache {
     }
 
     /// Load cache from file
-    fn load(store: &Store, workspace_root: &Path, refresh: bool) -> Option<Self> {
+    fn load(
+        store: &Store,
+        workspace_root: &Path,
+        refresh: bool,
+        recurse_submodules: bool,
+    ) -> Option<Self> {
         if refresh {
             return None;
         }
@@ -496,7 +530,10 @@ impl WorkspaceCache {
         match std::fs::read_to_string(&cache_path) {
             Ok(content) => match serde_json::from_str::<Self>(&content) {
                 Ok(cache) => {
-                    if cache.version == Self::CURRENT_VERSION && cache.is_valid() {
+                    if cache.version == Self::CURRENT_VERSION
+                        && cache.recurse_submodules == recurse_submodules
+                        && cache.is_valid()
+                    {
                         Some(cache)
                     } else {
                         // Invalid cache, remove it
@@ -537,6 +574,7 @@ pub(crate) struct Workspace {
     root: PathBuf,
     projects: Vec<Arc<Project>>,
     all_projects: Vec<Project>,
+    recurse_submodules: bool,
 }
 
 impl Workspace {
@@ -563,6 +601,9 @@ impl Workspace {
     }
 
     /// Discover the workspace from the given workspace root.
+    ///
+    /// With `recurse_submodules`, or `recurse_submodules: true` in the config at the root, the
+    /// projects in initialized git submodules are discovered too.
     #[instrument(level = "trace", skip(store, selectors))]
     pub(crate) fn discover(
         store: &Store,
@@ -570,63 +611,77 @@ impl Workspace {
         config: Option<PathBuf>,
         selectors: Option<&Selectors>,
         refresh: bool,
+        recurse_submodules: bool,
     ) -> Result<Self, Error> {
         if let Some(config) = config {
             let project = Project::from_config_file(config.into(), Some(root.clone()))?;
+            let recurse_submodules =
+                recurse_submodules || project.config().recurse_submodules == Some(true);
             let arc_project = Arc::new(project.clone());
             return Ok(Self {
                 root,
                 projects: vec![arc_project],
                 all_projects: vec![project],
+                recurse_submodules,
             });
         }
 
-        // Try to load from cache first
-        let projects = if let Some(cache) = WorkspaceCache::load(store, &root, refresh) {
-            debug!("Loaded workspace from cache");
-            let projects: Result<Vec<_>, _> = cache
-                .config_files
+        // The root config is loaded again once discovered, so it's read without warnings here.
+        let recurse_submodules = recurse_submodules
+            || [CONFIG_FILE, ALT_CONFIG_FILE]
                 .into_iter()
-                .map(
-                    |config_file| match Project::from_config_file(config_file.path.into(), None) {
-                        Ok(mut project) => {
-                            let relative_path = project
-                                .config_file()
-                                .parent()
-                                .and_then(|p| p.strip_prefix(&root).ok())
-                                .expect("Entry path should be relative to the root")
-                                .to_path_buf();
-                            project.with_relative_path(relative_path);
-                            Ok(project)
-                        }
-                        Err(e) => {
-                            debug!("Failed to load cached project config: {}", e);
-                            Err(e)
+                .map(|file| root.join(file))
+                .find(|path| path.is_file())
+                .and_then(|path| config::load_config(&path).ok())
+                .is_some_and(|config| config.recurse_submodules == Some(true));
+
+        // Try to load from cache first
+        let projects =
+            if let Some(cache) = WorkspaceCache::load(store, &root, refresh, recurse_submodules) {
+                debug!("Loaded workspace from cache");
+                let projects: Result<Vec<_>, _> = cache
+                    .config_files
+                    .into_iter()
+                    .map(|config_file| {
+                        match Project::from_config_file(config_file.path.into(), None) {
+                            Ok(mut project) => {
+                                let relative_path = project
+                                    .config_file()
+                                    .parent()
+                                    .and_then(|p| p.strip_prefix(&root).ok())
+                                    .expect("Entry path should be relative to the root")
+                                    .to_path_buf();
+                                project.with_relative_path(relative_path);
+                                Ok(project)
+                            }
+                            Err(e) => {
+                                debug!("Failed to load cached project config: {}", e);
+                                Err(e)
+                            }
                         }
-                    },
-                )
-                .collect();
+                    })
+                    .collect();
 
-            match projects {
-                Ok(projects) if !projects.is_empty() => Some(projects),
-                _ => {
-                    debug!("Cache invalid or empty, performing fresh discovery");
-                    None
+                match projects {
+                    Ok(projects) if !projects.is_empty() => Some(projects),
+                    _ => {
+                        debug!("Cache invalid or empty, performing fresh discovery");
+                        None
+                    }
                 }
-            }
-        } else {
-            None
-        };
+            } else {
+                None
+            };
 
         let mut all_projects = if let Some(projects) = projects {
             projects
         } else {
             // Cache miss or invalid, perform fresh discovery
             debug!("Performing fresh workspace discovery");
-            let projects = Self::discover_fresh(&root, selectors)?;
+            let projects = Self::discover_fresh(&root, selectors, recurse_submodules)?;
 
             // Save to cache
-            let cache = WorkspaceCache::new(root.clone(), &projects);
+            let cache = WorkspaceCache::new(root.clone(), &projects, recurse_submodules);
             if let Err(e) = cache.save(store) {
                 debug!("Failed to save workspace cache: {}", e);
             }
@@ -662,18 +717,27 @@ impl Workspace {
             root,
             projects,
             all_projects,
+            recurse_submodules,
         })
     }
 
     /// Perform fresh workspace discovery without cache
-    fn discover_fresh(root: &Path, selectors: Option<&Selectors>) -> Result<Vec<Project>, Error> {
+    fn discover_fresh(
+        root: &Path,
+        selectors: Option<&Selectors>,
+        recurse_submodules: bool,
+    ) -> Result<Vec<Project>, Error> {
         let projects = Mutex::new(Ok(Vec::new()));
 
         let git_root = GIT_ROOT.as_ref().map_err(|e| Error::Git(e.into()))?;
-        let submodules = git::list_submodules(git_root).unwrap_or_else(|e| {
-            error!("Failed to list git submodules: {e}");
+        let submodules = if recurse_submodules {
             Vec::new()
-        });
+        } else {
+            git::list_submodules(git_root).unwrap_or_else(|e| {
+                error!("Failed to list git submodules: {e}");
+                Vec::new()
+            })
+        };
 
         ignore::WalkBuilder::new(root)
             .follow_links(false)
@@ -704,7 +768,7 @@ impl Workspace {
                         return WalkState::Skip;
                     }
 
-                    // Skip git submodules
+                    // Skip git submodules, unless recursing into them
                     if submodules
                         .iter()
                         .any(|submodule| entry.path().starts_with(submodule))
@@ -793,6 +857,11 @@ impl Workspace {
         &self.all_projects
     }
 
+    /// Whether the projects and files of git submodules are part of the workspace.
+    pub(crate) fn recurse_submodules(&self) -> bool {
+        self.recurse_submodules
+    }
+
     /// Initialize remote repositories for all projects.
     async fn init_repos(
         &mut self,
@@ -4,9 +4,12 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use std::fmt;
+
 use anyhow::Result;
 use bstr::ByteSlice;
 use libyaml::{Emitter, Encoding, Event, ScalarStyle};
+use serde::de::{self, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
 use serde_yaml::{Mapping, Sequence, Value};
 
 /// Serialize a YAML scalar while preserving the caller's quote style.
@@ -44,6 +47,192 @@ pub(crate) fn serialize_yaml_scalar(value: &str, quote: &str) -> Result<String>
     Ok(str::from_utf8(trimmed)?.to_owned())
 }
 
+/// A step in the path to a node of a YAML document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Format a path like `repos[0].hooks[1].files`.
+pub(crate) fn display_path(path: &[PathSegment]) -> String {
+    let mut out = String::new();
+    for segment in path {
+        match segment {
+            PathSegment::Key(key) if out.is_empty() => out.push_str(key),
+            PathSegment::Key(key) => {
+                out.push('.');
+                out.push_str(key);
+            }
+            PathSegment::Index(idx) => {
+                out.push('[');
+                out.push_str(&idx.to_string());
+                out.push(']');
+            }
+        }
+    }
+    out
+}
+
+/// Find the source location of a node in a YAML document.
+///
+/// For a path ending in a key, this is the location of the key, otherwise of the sequence item.
+/// Returns `None` if the path doesn't exist in the source, for example because it comes from a
+/// merge key.
+pub(crate) fn locate(content: &str, path: &[PathSegment]) -> Option<serde_yaml::Location> {
+    // Walk the document with a visitor that fails at the node, `serde_yaml` attaches
+    // the location of the node being deserialized to the error.
+    let de = serde_yaml::Deserializer::from_str(content);
+    Locate(path).deserialize(de).err()?.location()
+}
+
+struct Locate<'a>(&'a [PathSegment]);
+
+impl<'de> DeserializeSeed<'de> for Locate<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        if self.0.is_empty() {
+            return deserializer.deserialize_any(Found);
+        }
+        deserializer.deserialize_any(self)
+    }
+}
+
+/// A visitor rejecting any node, to fail with the location of the node.
+struct Found;
+
+impl Visitor<'_> for Found {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("nothing")
+    }
+}
+
+impl<'de> Visitor<'de> for Locate<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("any YAML value")
+    }
+
+    fn visit_bool<E>(self, _: bool) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_i64<E>(self, _: i64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_u64<E>(self, _: u64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_f64<E>(self, _: f64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_str<E>(self, _: &str) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_unit<E>(self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let (PathSegment::Index(target), rest) = (&self.0[0], &self.0[1..]) else {
+            while seq.next_element::<IgnoredAny>()?.is_some() {}
+            return Ok(());
+        };
+        let mut idx = 0;
+        loop {
+            let next = if idx == *target {
+                seq.next_element_seed(Locate(rest))?
+            } else {
+                seq.next_element::<IgnoredAny>()?.map(|_| ())
+            };
+            if next.is_none() {
+                return Ok(());
+            }
+            idx += 1;
+        }
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let (target, rest) = match &self.0[0] {
+            PathSegment::Key(key) => (Some(key.as_str()), &self.0[1..]),
+            PathSegment::Index(_) => (None, &[][..]),
+        };
+        while let Some(matched) = map.next_key_seed(Key {
+            target,
+            last: rest.is_empty(),
+        })? {
+            if matched {
+                map.next_value_seed(Locate(rest))?;
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A mapping key, failing if it's the last segment of the path being located.
+struct Key<'a> {
+    target: Option<&'a str>,
+    last: bool,
+}
+
+impl<'de> DeserializeSeed<'de> for Key<'_> {
+    type Value = bool;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<bool, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl Visitor<'_> for Key<'_> {
+    type Value = bool;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a mapping key")
+    }
+
+    fn visit_str<E: de::Error>(self, key: &str) -> Result<bool, E> {
+        let matched = self.target == Some(key);
+        if matched && self.last {
+            return Err(E::custom("found"));
+        }
+        Ok(matched)
+    }
+
+    fn visit_bool<E>(self, _: bool) -> Result<bool, E> {
+        Ok(false)
+    }
+
+    fn visit_i64<E>(self, _: i64) -> Result<bool, E> {
+        Ok(false)
+    }
+
+    fn visit_u64<E>(self, _: u64) -> Result<bool, E> {
+        Ok(false)
+    }
+
+    fn visit_f64<E>(self, _: f64) -> Result<bool, E> {
+        Ok(false)
+    }
+
+    fn visit_unit<E>(self) -> Result<bool, E> {
+        Ok(false)
+    }
+}
+
 // Adapted from https://crates.io/crates/yaml-merge-keys to remove `yaml-rust2` from dependency.
 
 /// Errors which may occur when performing the YAML merge key process.
@@ -135,7 +324,7 @@ pub fn merge_keys(doc: Value) -> Result<Value, MergeKeyError> {
 
 #[cfg(test)]
 mod tests {
-    use super::serialize_yaml_scalar;
+    use super::{PathSegment, locate, serialize_yaml_scalar};
 
     #[test]
     fn serialize_yaml_scalar_plain() {
@@ -158,4 +347,46 @@ mod tests {
         let rendered = serialize_yaml_scalar("a'b", "'").unwrap();
         assert_eq!(rendered, "'a''b'");
     }
+
+    #[test]
+    fn locate_node() {
+        let content = indoc::indoc! {r"
+            repos:
+              - repo: local
+                hooks:
+                  - id: a
+                    stages: [commit, push]
+                  - {id: b, files: x}
+        "};
+        let key = |k: &str| PathSegment::Key(k.to_string());
+        let position = |path: &[PathSegment]| {
+            locate(content, path).map(|location| (location.line(), location.column()))
+        };
+
+        assert_eq!(position(&[key("repos")]), Some((1, 1)));
+        assert_eq!(
+            position(&[key("repos"), PathSegment::Index(0), key("hooks")]),
+            Some((3, 5))
+        );
+        let hooks = [key("repos"), PathSegment::Index(0), key("hooks")];
+        assert_eq!(
+            position(
+                &[
+                    hooks.as_slice(),
+                    &[PathSegment::Index(0), key("stages"), PathSegment::Index(1)]
+                ]
+                .concat()
+            ),
+            Some((5, 26))
+        );
+        assert_eq!(
+            position(&[hooks.as_slice(), &[PathSegment::Index(1), key("files")]].concat()),
+            Some((6, 17))
+        );
+        assert_eq!(position(&[key("missing")]), None);
+        assert_eq!(
+            position(&[hooks.as_slice(), &[PathSegment::Index(2)]].concat()),
+            None
+        );
+    }
 }
@@ -0,0 +1,111 @@
+use std::process::Command;
+
+use assert_cmd::assert::OutputAssertExt;
+use assert_fs::fixture::{FileWriteStr, PathChild};
+use prek_consts::env_vars::EnvVars;
+
+use crate::common::{TestContext, cmd_snapshot};
+
+mod common;
+
+fn commit_no_verify(context: &TestContext, message: &str) {
+    Command::new("git")
+        .arg("commit")
+        .arg("--no-verify")
+        .arg("-m")
+        .arg(message)
+        .env(EnvVars::PREK_HOME, &**context.home_dir())
+        .current_dir(context.work_dir())
+        .assert()
+        .success();
+}
+
+/// Commits made with `git commit --no-verify` are logged by the next hook run or `prek audit`.
+#[test]
+fn audit_commits_skipping_hooks() -> anyhow::Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+    context.configure_git_author();
+    context.disable_auto_crlf();
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: echo
+                name: echo
+                language: system
+                entry: echo
+    "});
+    context.install().assert().success();
+
+    let filters = context
+        .filters()
+        .into_iter()
+        .chain([
+            (r#""detected_at": \d+"#, r#""detected_at": [TIME]"#),
+            (r"\b[0-9a-f]{40}\b", "[COMMIT]"),
+            (r"\b[0-9a-f]{10}\b", "[COMMIT]"),
+            (r"\d{4}-\d{2}-\d{2}T[\d:+\-Z]+", "[DATE]"),
+            (r"\d{4}-\d{2}-\d{2}", "[DATE]"),
+        ])
+        .collect::<Vec<_>>();
+
+    cmd_snapshot!(filters.clone(), context.command().arg("audit"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    No commits skipped the pre-commit hook
+
+    ----- stderr -----
+    ");
+
+    let file = context.work_dir().child("file.txt");
+    file.write_str("one\n")?;
+    context.git_add(".");
+    context.git_commit("Initial commit");
+
+    file.write_str("two\n")?;
+    context.git_add(".");
+    commit_no_verify(&context, "Skip the hooks");
+
+    // The hook run of the next commit finds the skipped one.
+    file.write_str("three\n")?;
+    context.git_add(".");
+    context.git_commit("Run the hooks");
+
+    file.write_str("four\n")?;
+    context.git_add(".");
+    commit_no_verify(&context, "Skip the hooks again");
+
+    cmd_snapshot!(filters.clone(), context.command().arg("audit"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    [COMMIT]  [DATE]  Prek Test  Skip the hooks again
+    [COMMIT]  [DATE]  Prek Test  Skip the hooks
+
+    ----- stderr -----
+    Logged in `.git/prek/audit.log`
+    ");
+
+    cmd_snapshot!(filters.clone(), context.command().args(["audit", "--limit", "1", "--output-format", "json"]), @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    [
+      {
+        "commit": "[COMMIT]",
+        "author": "Prek Test",
+        "date": "[DATE]",
+        "subject": "Skip the hooks again",
+        "detected_at": [TIME],
+        "detected_by": "audit"
+      }
+    ]
+
+    ----- stderr -----
+    "#);
+
+    Ok(())
+}
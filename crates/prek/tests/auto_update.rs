@@ -1149,7 +1149,88 @@ fn auto_update_with_invalid_config_file() -> Result<()> {
     ----- stderr -----
     error: Failed to parse `.pre-commit-config.yaml`
       caused by: did not find expected ',' or ']' at line 2 column 1, while parsing a flow sequence at line 1 column 15
+         --> .pre-commit-config.yaml:1:29
+          |
+        1 | invalid_yaml: [unclosed_list
+          |                             ^
     ");
 
     Ok(())
 }
+
+/// Only the repos declared in the config itself are updated, not the ones of the configs it
+/// extends, whose `rev:` lines are in other files.
+#[test]
+fn auto_update_with_extends() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    let base_repo = create_local_git_repo(&context, "base-repo", &["v1.0.0", "v2.0.0"])?;
+    let own_repo = create_local_git_repo(&context, "own-repo", &["v1.0.0", "v2.0.0"])?;
+
+    context
+        .work_dir()
+        .child("base.yaml")
+        .write_str(&indoc::formatdoc! {r"
+            repos:
+              - repo: {}
+                rev: v1.0.0
+                hooks:
+                  - id: test-hook
+        ", base_repo})?;
+    context.write_pre_commit_config(&indoc::formatdoc! {r"
+        extends: base.yaml
+        repos:
+          - repo: local
+            hooks:
+              - id: local-hook
+                name: Local Hook
+                language: system
+                entry: echo
+          - repo: {}
+            rev: v1.0.0
+            hooks:
+              - id: test-hook
+    ", own_repo});
+    context.git_add(".");
+
+    let filters = context.filters();
+
+    cmd_snapshot!(filters.clone(), context.auto_update().arg("--cooldown-days").arg("0"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    [[HOME]/test-repos/own-repo] updating v1.0.0 -> v2.0.0
+
+    ----- stderr -----
+    ");
+
+    insta::with_settings!(
+        { filters => filters.clone() },
+        {
+            assert_snapshot!(context.read(CONFIG_FILE), @r"
+            extends: base.yaml
+            repos:
+              - repo: local
+                hooks:
+                  - id: local-hook
+                    name: Local Hook
+                    language: system
+                    entry: echo
+              - repo: [HOME]/test-repos/own-repo
+                rev: v2.0.0
+                hooks:
+                  - id: test-hook
+            ");
+            assert_snapshot!(context.read("base.yaml"), @r"
+            repos:
+              - repo: [HOME]/test-repos/base-repo
+                rev: v1.0.0
+                hooks:
+                  - id: test-hook
+            ");
+        }
+    );
+
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use assert_fs::assert::PathAssert;
+use assert_fs::fixture::{FileWriteStr, PathChild};
+
+use crate::common::{TestContext, cmd_snapshot};
+
+mod common;
+
+/// `prek bisect` finds the files a hook fails on, and restores the files it modifies.
+#[test]
+fn bisect() -> anyhow::Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+    context.write_pre_commit_config(indoc::indoc! {r#"
+        repos:
+          - repo: local
+            hooks:
+              - id: pair
+                name: pair
+                language: system
+                entry: python3 -c 'import sys; contents = [open(f).read() for f in sys.argv[1:]]; sys.exit("a" in contents and "b" in contents)'
+                files: \.txt$
+                require_serial: true
+              - id: fixer
+                name: fixer
+                language: system
+                entry: python3 -c 'import sys; contents = [open(f).read() for f in sys.argv[1:]]; [open(f, "w").write("fixed") for f in sys.argv[1:]]; print("bad file"); sys.exit("bad" in contents)'
+                files: \.txt$
+                require_serial: true
+              - id: pass
+                name: pass
+                language: system
+                entry: python3 -c 'import sys'
+                files: \.txt$
+    "#});
+    let cwd = context.work_dir();
+    for (name, content) in [
+        ("0.txt", ""),
+        ("1.txt", ""),
+        ("2.txt", "a"),
+        ("3.txt", ""),
+        ("4.txt", "bad"),
+        ("5.txt", ""),
+        ("6.txt", ""),
+        ("7.txt", "b"),
+    ] {
+        cwd.child(name).write_str(content)?;
+    }
+    context.git_add(".");
+
+    // Neither file is enough on its own.
+    cmd_snapshot!(context.filters(), context.command().args(["bisect", "pair"]), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    2.txt
+    7.txt
+
+    ----- stderr -----
+    Run 1: 8 files, failed
+    Run 2: 4 files, passed
+    Run 3: 4 files, passed
+    Run 4: 6 files, passed
+    Run 5: 6 files, failed
+    Run 6: 5 files, failed
+    Run 7: 3 files, passed
+    Run 8: 3 files, failed
+    Run 9: 2 files, passed
+    Run 10: 2 files, failed
+    Hook `pair` fails on 2 of 8 files, found in 10 runs:
+    ");
+
+    cmd_snapshot!(context.filters(), context.command().args(["bisect", "fixer"]), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    4.txt
+
+    ----- stderr -----
+    Run 1: 8 files, failed
+    Run 2: 4 files, passed
+    Run 3: 4 files, failed
+    Run 4: 2 files, failed
+    Run 5: 1 files, failed
+    Hook `fixer` fails on 1 of 8 files, found in 5 runs:
+
+    Hook output:
+    bad file
+    ");
+    cwd.child("0.txt").assert("");
+    cwd.child("4.txt").assert("bad");
+
+    cmd_snapshot!(context.filters(), context.command().args(["bisect", "pass", "--files", "1.txt", "2.txt"]), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Run 1: 2 files, passed
+    Hook `pass` passes on all 2 files
+    ");
+
+    cmd_snapshot!(context.filters(), context.command().args(["bisect", "missing"]), @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    warning: selector `missing` did not match any hooks
+    error: No hook found for `missing`
+    ");
+
+    Ok(())
+}
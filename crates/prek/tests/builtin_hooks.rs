@@ -1,7 +1,9 @@
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
+use std::process::Command;
 
 use anyhow::Result;
+use assert_cmd::assert::OutputAssertExt;
 use assert_fs::prelude::*;
 use insta::assert_snapshot;
 use prek_consts::CONFIG_FILE;
@@ -441,6 +443,82 @@ fn mixed_line_ending_hook() -> Result<()> {
     Ok(())
 }
 
+/// Line endings rewritten by a fixer are hidden from git with `core.autocrlf=true`, and converted back
+/// on checkout. prek warns about it, and `--respect-gitattributes` follows the `eol` attribute instead.
+#[test]
+fn mixed_line_ending_autocrlf() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+    context.configure_git_author();
+    Command::new("git")
+        .arg("config")
+        .arg("core.autocrlf")
+        .arg("true")
+        .current_dir(context.work_dir())
+        .assert()
+        .success();
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: builtin
+            hooks:
+              - id: mixed-line-ending
+                args: ['--fix=lf']
+    "});
+    context
+        .work_dir()
+        .child("crlf.txt")
+        .write_str("line1\r\nline2\r\n")?;
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    mixed line ending........................................................Failed
+    - hook id: mixed-line-ending
+    - exit code: 1
+
+      Fixing crlf.txt
+
+    ----- stderr -----
+    warning: `mixed-line-ending` rewrote line endings that git converts back on checkout because `core.autocrlf` is `true`, so the files will be fixed again on every run
+    hint: Set `core.autocrlf` to `input`, or set `eol` in `.gitattributes` and add `--respect-gitattributes` to the hook args
+    ");
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: builtin
+            hooks:
+              - id: mixed-line-ending
+                args: ['--fix=lf', '--respect-gitattributes']
+    "});
+    context
+        .work_dir()
+        .child(".gitattributes")
+        .write_str("crlf.txt eol=crlf\n")?;
+    context
+        .work_dir()
+        .child("crlf.txt")
+        .write_str("line1\r\nline2\r\n")?;
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    mixed line ending........................................................Passed
+
+    ----- stderr -----
+    ");
+    assert_eq!(
+        fs_err::read(context.work_dir().child("crlf.txt"))?,
+        b"line1\r\nline2\r\n"
+    );
+
+    Ok(())
+}
+
 #[test]
 fn check_added_large_files_hook() -> Result<()> {
     let context = TestContext::new();
@@ -1951,6 +2029,48 @@ fn check_executables_have_shebangs_various_cases_win() -> Result<()> {
     Ok(())
 }
 
+/// Windows can't create these files, so the test only runs on Unix.
+#[cfg(unix)]
+#[test]
+fn check_illegal_windows_names_hook() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: builtin
+            hooks:
+              - id: check-illegal-windows-names
+    "});
+
+    let cwd = context.work_dir();
+    cwd.child("valid.txt").write_str("")?;
+    cwd.child("console.log").write_str("")?;
+    cwd.child("con.txt").write_str("")?;
+    cwd.child("aux").child("file.txt").write_str("")?;
+    cwd.child("what?.txt").write_str("")?;
+    cwd.child("trailing.").write_str("")?;
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    check illegal windows names..............................................Failed
+    - hook id: check-illegal-windows-names
+    - exit code: 1
+
+      aux/file.txt: Illegal Windows filename (reserved device name)
+      trailing.: Illegal Windows filename (ends with a dot or a space)
+      con.txt: Illegal Windows filename (reserved device name)
+      what?.txt: Illegal Windows filename (contains a character not allowed on Windows)
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
+
 fn is_case_sensitive_filesystem(context: &TestContext) -> Result<bool> {
     let test_lower = context.work_dir().child("case_test_file.txt");
     test_lower.write_str("test")?;
@@ -2185,3 +2305,372 @@ fn check_json5() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn check_jsonschema() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: builtin
+            hooks:
+              - id: check-jsonschema
+                args: [--schemafile, schemas/service.json]
+                files: ^services/
+    "});
+
+    let cwd = context.work_dir();
+    cwd.child("schemas/service.json")
+        .write_str(indoc::indoc! {r##"
+        {
+            "type": "object",
+            "required": ["name"],
+            "properties": {
+                "name": {"type": "string"},
+                "port": {"$ref": "#/definitions/port"}
+            },
+            "definitions": {
+                "port": {"type": "integer", "minimum": 1, "maximum": 65535}
+            }
+        }
+    "##})?;
+    cwd.child("services/web.yaml")
+        .write_str("name: web\nport: 8080\n")?;
+    cwd.child("services/db.toml").write_str("port = 0\n")?;
+    cwd.child("services/cache.json")
+        .write_str(r#"{"name": "cache", "port": 6379}"#)?;
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    check jsonschema.........................................................Failed
+    - hook id: check-jsonschema
+    - exit code: 1
+
+      services/db.toml::$: 'name' is a required property
+      services/db.toml::$.port: 0 is less than the minimum of 1
+
+    ----- stderr -----
+    ");
+
+    cwd.child("services/db.toml")
+        .write_str("name = \"db\"\nport = 5432\n")?;
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    check jsonschema.........................................................Passed
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
+
+#[test]
+fn check_byte_order_marker() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: builtin
+            hooks:
+              - id: check-byte-order-marker
+    "});
+
+    let cwd = context.work_dir();
+    cwd.child("with_bom.txt")
+        .write_binary(b"\xef\xbb\xbfHello, World!\n")?;
+    cwd.child("without_bom.txt").write_str("Hello, World!\n")?;
+    context.git_add(".");
+
+    // The hook only reports the marker, the file is left unchanged.
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    check for byte-order marker..............................................Failed
+    - hook id: check-byte-order-marker
+    - exit code: 1
+
+      with_bom.txt: Has a byte-order marker
+
+    ----- stderr -----
+    ");
+
+    assert_eq!(
+        fs_err::read(cwd.child("with_bom.txt"))?,
+        b"\xef\xbb\xbfHello, World!\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn check_docstring_first() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: builtin
+            hooks:
+              - id: check-docstring-first
+    "});
+
+    let cwd = context.work_dir();
+    cwd.child("good.py").write_str(indoc::indoc! {r#"
+        # comment
+        """Module docstring."""
+        import os
+
+
+        def f():
+            """Function docstring."""
+    "#})?;
+    cwd.child("after_code.py").write_str(indoc::indoc! {r#"
+        import os
+        """Module docstring."""
+    "#})?;
+    cwd.child("multiple.py").write_str(indoc::indoc! {r#"
+        """Module docstring."""
+        """Another docstring."""
+    "#})?;
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    check docstring is first.................................................Failed
+    - hook id: check-docstring-first
+    - exit code: 1
+
+      after_code.py:2: Module docstring appears after code (code seen on line 1).
+      multiple.py:2: Multiple module docstrings (first docstring on line 1).
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
+
+#[test]
+fn debug_statements() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: builtin
+            hooks:
+              - id: debug-statements
+    "});
+
+    let cwd = context.work_dir();
+    cwd.child("good.py").write_str(indoc::indoc! {r#"
+        # import pdb
+        text = "breakpoint()"
+        obj.breakpoint()
+    "#})?;
+    cwd.child("debug.py").write_str(indoc::indoc! {r"
+        import pdb
+        from ipdb import set_trace
+
+
+        def f():
+            breakpoint()
+    "})?;
+    cwd.child("invalid.py").write_str("x = (\n")?;
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    debug statements (python)................................................Failed
+    - hook id: debug-statements
+    - exit code: 1
+
+      debug.py:1:0: pdb imported
+      debug.py:2:0: ipdb imported
+      debug.py:6:4: breakpoint called
+      invalid.py - Could not parse ast
+
+      	SyntaxError: '(' was never closed (line 1)
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
+
+#[test]
+fn check_builtin_literals() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: builtin
+            hooks:
+              - id: check-builtin-literals
+                args: [--ignore=str, --no-allow-dict-kwargs]
+    "});
+
+    let cwd = context.work_dir();
+    cwd.child("good.py").write_str(indoc::indoc! {r#"
+        items = []
+        name = str()
+        numbers = list(range(3))
+        text = "dict()"
+    "#})?;
+    cwd.child("literals.py").write_str(indoc::indoc! {r"
+        items = list()
+        options = dict(verbose=True)
+
+
+        def f():
+            return tuple()
+    "})?;
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    check builtin type constructor use.......................................Failed
+    - hook id: check-builtin-literals
+    - exit code: 1
+
+      literals.py:1:8: replace list() with []
+      literals.py:2:10: replace dict() with {}
+      literals.py:6:11: replace tuple() with ()
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
+
+#[test]
+fn python_check_blanket_comments() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: builtin
+            hooks:
+              - id: python-check-blanket-noqa
+              - id: python-check-blanket-type-ignore
+    "});
+
+    let cwd = context.work_dir();
+    cwd.child("good.py").write_str(indoc::indoc! {r##"
+        import os  # noqa: F401
+        value = os.environ.get("X")  # type: ignore[union-attr]
+        text = "# noqa"
+    "##})?;
+    cwd.child("blanket.py").write_str(indoc::indoc! {r"
+        import os  # noqa
+        value = os.environ.get()  # type: ignore
+    "})?;
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    check blanket noqa.......................................................Failed
+    - hook id: python-check-blanket-noqa
+    - exit code: 1
+
+      blanket.py:1:11: use specific codes with `# noqa`, e.g. `# noqa: F401`
+    check blanket type ignore................................................Failed
+    - hook id: python-check-blanket-type-ignore
+    - exit code: 1
+
+      blanket.py:2:26: use specific error codes with `# type: ignore`, e.g. `# type: ignore[attr-defined]`
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
+
+#[test]
+fn check_commit_message() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: builtin
+            hooks:
+              - id: check-commit-message
+                args: [--scopes, 'cli,docs']
+    "});
+    context.git_add(".");
+
+    let cwd = context.work_dir();
+
+    // Not run in the pre-commit stage.
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    ");
+
+    cwd.child("MSG").write_str(indoc::indoc! {r"
+        feat(cli): add a flag
+
+        # Please enter the commit message for your changes.
+    "})?;
+    cmd_snapshot!(context.filters(), context.run().arg("--hook-stage").arg("commit-msg").arg("--commit-msg-filename").arg("MSG"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    check commit message.....................................................Passed
+
+    ----- stderr -----
+    ");
+
+    cwd.child("MSG").write_str("Add a flag\n")?;
+    cmd_snapshot!(context.filters(), context.run().arg("--hook-stage").arg("commit-msg").arg("--commit-msg-filename").arg("MSG"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    check commit message.....................................................Failed
+    - hook id: check-commit-message
+    - exit code: 1
+
+      MSG: commit message header `Add a flag` does not match `<type>(<scope>): <subject>`
+
+    ----- stderr -----
+    ");
+
+    cwd.child("MSG").write_str("feat(core): add a flag\n")?;
+    cmd_snapshot!(context.filters(), context.run().arg("--hook-stage").arg("commit-msg").arg("--commit-msg-filename").arg("MSG"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    check commit message.....................................................Failed
+    - hook id: check-commit-message
+    - exit code: 1
+
+      MSG: commit scope `core` is not one of: cli, docs
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
@@ -1,8 +1,9 @@
+use assert_cmd::assert::OutputAssertExt;
 use assert_fs::assert::PathAssert;
 use assert_fs::fixture::{PathChild, PathCreateDir};
 use assert_fs::prelude::FileWriteStr;
 
-use crate::common::{TestContext, cmd_snapshot};
+use crate::common::{TestContext, cmd_snapshot, create_git_hook_repo, git};
 
 mod common;
 
@@ -55,6 +56,88 @@ fn cache_clean() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn cache_clean_separate_cache_dir() -> anyhow::Result<()> {
+    let context = TestContext::new();
+
+    let data = context.work_dir().child("data");
+    let cache = context.work_dir().child("package-cache");
+    cache.child("uv").child("archive").write_str("hello")?;
+
+    let command = || {
+        let mut command = context.command();
+        command
+            .env_remove("PREK_HOME")
+            .env("PREK_DATA_DIR", &*data)
+            .env("PREK_CACHE_DIR", &*cache);
+        command
+    };
+
+    cmd_snapshot!(context.filters(), command().args(["cache", "dir"]), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    [TEMP_DIR]/data
+
+    ----- stderr -----
+    ");
+    cmd_snapshot!(context.filters(), command().args(["cache", "clean"]), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Cleaned `[TEMP_DIR]/data`
+    Cleaned `[TEMP_DIR]/package-cache`
+
+    ----- stderr -----
+    ");
+
+    data.assert(predicates::path::missing());
+    cache.assert(predicates::path::missing());
+
+    Ok(())
+}
+
+/// Without `PREK_HOME`, the store follows `XDG_DATA_HOME`, unless a store created before data
+/// and caches were split exists in `XDG_CACHE_HOME`.
+#[cfg(target_os = "linux")]
+#[test]
+fn cache_dir_xdg() -> anyhow::Result<()> {
+    let context = TestContext::new();
+
+    let xdg_data = context.work_dir().child("xdg-data");
+    let xdg_cache = context.work_dir().child("xdg-cache");
+    let command = || {
+        let mut command = context.command();
+        command
+            .env_remove("PREK_HOME")
+            .env("XDG_DATA_HOME", &*xdg_data)
+            .env("XDG_CACHE_HOME", &*xdg_cache);
+        command
+    };
+
+    cmd_snapshot!(context.filters(), command().args(["cache", "dir"]), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    [TEMP_DIR]/xdg-data/prek
+
+    ----- stderr -----
+    ");
+
+    std::fs::remove_dir_all(&xdg_data)?;
+    xdg_cache.child("prek").child("README").write_str("")?;
+    cmd_snapshot!(context.filters(), command().args(["cache", "dir"]), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    [TEMP_DIR]/xdg-cache/prek
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
+
 #[test]
 fn cache_size() -> anyhow::Result<()> {
     let context = TestContext::new().with_filtered_cache_size();
@@ -99,3 +182,723 @@ fn cache_size() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn cache_size_breakdown() -> anyhow::Result<()> {
+    let context = TestContext::new();
+
+    let home = context.work_dir().child("home");
+    let repo = home.child("repos").child("0123456789abcdef");
+    repo.child(".prek-repo.json")
+        .write_str(r#"{"repo":"https://github.com/pre-commit/pre-commit-hooks","rev":"v5.0.0"}"#)?;
+    repo.child("hooks.py").write_str(&"x".repeat(1000))?;
+
+    let env = home.child("hooks").child("python-abcdef");
+    env.child(".prek-hook.json").write_str(&format!(
+        r#"{{"language":"python","language_version":"3.12.1","dependencies":["ruff"],"env_path":"{}","toolchain":"/usr/bin/python3","extra":{{}}}}"#,
+        env.path().display().to_string().replace('\\', "/"),
+    ))?;
+    env.child("lib")
+        .child("ruff")
+        .write_str(&"x".repeat(4000))?;
+
+    home.child("tools")
+        .child("uv")
+        .child("uv")
+        .write_str(&"x".repeat(2000))?;
+    home.child("prek.log").write_str(&"x".repeat(100))?;
+
+    // Sizes depend on the length of the temporary paths and on the log file written by prek.
+    let filters = context
+        .filters()
+        .into_iter()
+        .chain([
+            (r"(?m)^([a-z-]+|  .+): \d+$", "$1: [SIZE]"),
+            (r#""(size|total)": \d+"#, r#""$1": [SIZE]"#),
+        ])
+        .collect::<Vec<_>>();
+
+    cmd_snapshot!(filters.clone(), context.command().args(["cache", "size", "--breakdown"]).env("PREK_HOME", &*home), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    repos: [SIZE]
+      https://github.com/pre-commit/pre-commit-hooks@v5.0.0: [SIZE]
+    repo-objects: [SIZE]
+    hook-envs: [SIZE]
+      python-abcdef (python 3.12.1 with ruff): [SIZE]
+    toolchains: [SIZE]
+      uv: [SIZE]
+    caches: [SIZE]
+    patches: [SIZE]
+    logs: [SIZE]
+    build-logs: [SIZE]
+    scratch: [SIZE]
+    other: [SIZE]
+    total: [SIZE]
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(filters, context.command().args(["cache", "size", "--breakdown", "--output-format", "json"]).env("PREK_HOME", &*home), @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    {
+      "total": [SIZE],
+      "categories": [
+        {
+          "name": "repos",
+          "path": "[TEMP_DIR]/home/repos",
+          "size": [SIZE],
+          "entries": [
+            {
+              "name": "https://github.com/pre-commit/pre-commit-hooks@v5.0.0",
+              "path": "[TEMP_DIR]/home/repos/0123456789abcdef",
+              "size": [SIZE]
+            }
+          ]
+        },
+        {
+          "name": "repo-objects",
+          "path": "[TEMP_DIR]/home/repo-objects",
+          "size": [SIZE]
+        },
+        {
+          "name": "hook-envs",
+          "path": "[TEMP_DIR]/home/hooks",
+          "size": [SIZE],
+          "entries": [
+            {
+              "name": "python-abcdef (python 3.12.1 with ruff)",
+              "path": "[TEMP_DIR]/home/hooks/python-abcdef",
+              "size": [SIZE],
+              "language": "python",
+              "language_version": "3.12.1",
+              "dependencies": [
+                "ruff"
+              ]
+            }
+          ]
+        },
+        {
+          "name": "toolchains",
+          "path": "[TEMP_DIR]/home/tools",
+          "size": [SIZE],
+          "entries": [
+            {
+              "name": "uv",
+              "path": "[TEMP_DIR]/home/tools/uv",
+              "size": [SIZE]
+            }
+          ]
+        },
+        {
+          "name": "caches",
+          "path": "[TEMP_DIR]/home/cache",
+          "size": [SIZE]
+        },
+        {
+          "name": "patches",
+          "path": "[TEMP_DIR]/home/patches",
+          "size": [SIZE]
+        },
+        {
+          "name": "logs",
+          "path": "[TEMP_DIR]/home/prek.log",
+          "size": [SIZE]
+        },
+        {
+          "name": "build-logs",
+          "path": "[TEMP_DIR]/home/logs",
+          "size": [SIZE]
+        },
+        {
+          "name": "scratch",
+          "path": "[TEMP_DIR]/home/scratch",
+          "size": [SIZE]
+        },
+        {
+          "name": "other",
+          "path": "[TEMP_DIR]/home",
+          "size": [SIZE]
+        }
+      ]
+    }
+
+    ----- stderr -----
+    "#);
+
+    Ok(())
+}
+
+#[test]
+fn cache_gc_managed_python() -> anyhow::Result<()> {
+    let context = TestContext::new();
+
+    let home = context.work_dir().child("home");
+    let python_dir = home.child("tools").child("python");
+    python_dir
+        .child("cpython-3.12.0-linux-x86_64-gnu")
+        .child("bin")
+        .child("python")
+        .write_str("hello")?;
+    python_dir.child(".lock").write_str("")?;
+    python_dir.child(".cache").create_dir_all()?;
+
+    cmd_snapshot!(context.filters(), context.command().arg("cache").arg("gc").env("PREK_HOME", &*home), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Removed 1 unused managed Python installation ([SIZE])
+
+    ----- stderr -----
+    ");
+
+    python_dir
+        .child("cpython-3.12.0-linux-x86_64-gnu")
+        .assert(predicates::path::missing());
+    python_dir
+        .child(".cache")
+        .assert(predicates::path::exists());
+
+    cmd_snapshot!(context.filters(), context.command().arg("cache").arg("gc").env("PREK_HOME", &*home), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Nothing to clean
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
+
+/// Set the modification time of a file to `days` ago.
+fn age_file(path: &std::path::Path, days: u64) -> anyhow::Result<()> {
+    let modified =
+        std::time::SystemTime::now() - std::time::Duration::from_secs(days * 24 * 60 * 60);
+    std::fs::File::options()
+        .write(true)
+        .open(path)?
+        .set_modified(modified)?;
+    Ok(())
+}
+
+#[test]
+fn cache_gc_interrupted_installs() -> anyhow::Result<()> {
+    let context = TestContext::new();
+
+    let home = context.work_dir().child("home");
+    let hooks = home.child("hooks");
+    hooks
+        .child("python-complete")
+        .child(".prek-hook.json")
+        .write_str("{}")?;
+    hooks
+        .child("python-incomplete")
+        .child("bin")
+        .child("python")
+        .write_str("hello")?;
+    let repos = home.child("repos");
+    repos
+        .child("complete")
+        .child(".prek-repo.json")
+        .write_str("{}")?;
+    repos
+        .child("incomplete")
+        .child("README.md")
+        .write_str("hello")?;
+
+    let tools = home.child("tools");
+    tools.child("node").child(".lock").write_str("")?;
+    age_file(&tools.child("node").child(".lock"), 2)?;
+    tools.child("go").child(".lock").write_str("")?;
+    let locks = home.child("locks");
+    locks.child("env-python-0123.lock").write_str("")?;
+    age_file(&locks.child("env-python-0123.lock"), 2)?;
+
+    let patches = home.child("patches");
+    patches.child("1000-1.patch").write_str("diff")?;
+    age_file(&patches.child("1000-1.patch"), 30)?;
+    patches.child("2000-2.patch").write_str("diff")?;
+
+    cmd_snapshot!(context.filters(), context.command().arg("cache").arg("gc").env("PREK_HOME", &*home), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Removed 1 incomplete hook environment ([SIZE])
+    Removed 1 incomplete repository clone ([SIZE])
+    Removed 2 stale lock files ([SIZE])
+    Removed 1 orphaned patch file ([SIZE])
+
+    ----- stderr -----
+    ");
+
+    hooks
+        .child("python-complete")
+        .assert(predicates::path::exists());
+    hooks
+        .child("python-incomplete")
+        .assert(predicates::path::missing());
+    repos.child("complete").assert(predicates::path::exists());
+    repos
+        .child("incomplete")
+        .assert(predicates::path::missing());
+    tools
+        .child("node")
+        .child(".lock")
+        .assert(predicates::path::missing());
+    tools
+        .child("go")
+        .child(".lock")
+        .assert(predicates::path::exists());
+    locks
+        .child("env-python-0123.lock")
+        .assert(predicates::path::missing());
+    patches
+        .child("1000-1.patch")
+        .assert(predicates::path::missing());
+    patches
+        .child("2000-2.patch")
+        .assert(predicates::path::exists());
+
+    Ok(())
+}
+
+#[test]
+fn repo_revisions_share_objects() -> anyhow::Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    let repo_dir = context.work_dir().child("hook-repo");
+    create_git_hook_repo(
+        &repo_dir,
+        indoc::indoc! {r"
+            - id: hello
+              name: hello
+              entry: echo hello
+              language: system
+              pass_filenames: false
+        "},
+    )?;
+    git(&repo_dir, &["tag", "v1"]);
+    repo_dir.child("README.md").write_str("hello")?;
+    git(&repo_dir, &["add", "."]);
+    git(&repo_dir, &["commit", "-m", "Add README"]);
+    git(&repo_dir, &["tag", "v2"]);
+
+    context.write_pre_commit_config(&indoc::formatdoc! {r"
+        repos:
+          - repo: {repo}
+            rev: v1
+            hooks:
+              - id: hello
+          - repo: {repo}
+            rev: v2
+            hooks:
+              - id: hello
+    ", repo = repo_dir.display()});
+    context.git_add(".");
+    context.run().assert().success();
+
+    let home = context.home_dir();
+    let objects = std::fs::read_dir(home.child("repo-objects"))?.collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(objects.len(), 1);
+    let clones = std::fs::read_dir(home.child("repos"))?.collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(clones.len(), 2);
+    for clone in &clones {
+        let alternates =
+            std::fs::read_to_string(clone.path().join(".git/objects/info/alternates"))?;
+        assert!(alternates.contains(&*objects[0].file_name().to_string_lossy()));
+    }
+
+    // A clone with objects of its own, as made before revisions shared an object store, and
+    // before the configs using each clone were recorded.
+    std::fs::remove_file(home.child("config-refs.json"))?;
+    let old_clone = home.child("repos").child("0123456789abcdef");
+    std::process::Command::new("git")
+        .arg("clone")
+        .arg("--quiet")
+        .arg(&*repo_dir)
+        .arg(&*old_clone)
+        .assert()
+        .success();
+    old_clone.child(".prek-repo.json").write_str(&format!(
+        r#"{{"repo":"{}","rev":"v2"}}"#,
+        repo_dir.display().to_string().replace('\\', "/")
+    ))?;
+
+    cmd_snapshot!(context.filters(), context.command().args(["cache", "gc"]), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Moved the objects of 1 repository clone into shared object stores
+
+    ----- stderr -----
+    ");
+    old_clone
+        .child(".git/objects/info/alternates")
+        .assert(predicates::path::exists());
+    std::process::Command::new("git")
+        .args(["fsck", "--connectivity-only"])
+        .current_dir(&old_clone)
+        .assert()
+        .success();
+
+    for clone in std::fs::read_dir(home.child("repos"))? {
+        std::fs::remove_dir_all(clone?.path())?;
+    }
+    cmd_snapshot!(context.filters(), context.command().args(["cache", "gc"]), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Removed 1 unused repository object store ([SIZE])
+
+    ----- stderr -----
+    ");
+    home.child("repo-objects")
+        .child(objects[0].file_name())
+        .assert(predicates::path::missing());
+
+    Ok(())
+}
+
+/// `prek cache gc --dry-run` shows the repository clones no local repository uses anymore, and
+/// the local repositories using the kept ones, without removing anything.
+#[test]
+fn cache_gc_dry_run() -> anyhow::Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    let repo_dir = context.work_dir().child("hook-repo");
+    create_git_hook_repo(
+        &repo_dir,
+        indoc::indoc! {r"
+            - id: hello
+              name: hello
+              entry: echo hello
+              language: system
+              pass_filenames: false
+        "},
+    )?;
+    git(&repo_dir, &["tag", "v1"]);
+    git(
+        &repo_dir,
+        &["commit", "--allow-empty", "-m", "Second commit"],
+    );
+    git(&repo_dir, &["tag", "v2"]);
+
+    let config = |rev: &str| {
+        indoc::formatdoc! {r"
+            repos:
+              - repo: {repo}
+                rev: {rev}
+                hooks:
+                  - id: hello
+        ", repo = repo_dir.display()}
+    };
+    context.write_pre_commit_config(&config("v1"));
+    context.git_add(".");
+    context.run().assert().success();
+    context.write_pre_commit_config(&config("v2"));
+    context.git_add(".");
+    context.run().assert().success();
+
+    // Another local repository still uses the first revision.
+    let other = context.work_dir().child("other");
+    other.create_dir_all()?;
+    std::process::Command::new("git")
+        .arg("init")
+        .current_dir(&other)
+        .assert()
+        .success();
+    other
+        .child(".pre-commit-config.yaml")
+        .write_str(&config("v1"))?;
+    std::process::Command::new("git")
+        .args(["add", "."])
+        .current_dir(&other)
+        .assert()
+        .success();
+    context
+        .command()
+        .arg("run")
+        .current_dir(&other)
+        .assert()
+        .success();
+
+    let mut filters = context.filters();
+    filters.push((r"repos/[0-9a-f]+", "repos/[DIGEST]"));
+
+    cmd_snapshot!(filters.clone(), context.command().args(["cache", "gc", "--dry-run"]), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Would keep entries used by 2 repositories:
+      [TEMP_DIR]/ (1 entry)
+      [TEMP_DIR]/other (1 entry)
+    Nothing to clean
+
+    ----- stderr -----
+    ");
+
+    std::fs::remove_dir_all(&other)?;
+
+    cmd_snapshot!(filters.clone(), context.command().args(["cache", "gc", "--dry-run"]), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Would drop the references of 1 deleted repository
+      [TEMP_DIR]/other
+    Would remove 1 unreferenced repository clone ([SIZE])
+      [HOME]/repos/[DIGEST]
+    Would keep entries used by 1 repository:
+      [TEMP_DIR]/ (1 entry)
+    Would reclaim [SIZE]
+
+    ----- stderr -----
+    ");
+
+    let clones = std::fs::read_dir(context.home_dir().child("repos"))?.count();
+    assert_eq!(clones, 2);
+
+    cmd_snapshot!(filters, context.command().args(["cache", "gc"]), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Dropped the references of 1 deleted repository
+    Removed 1 unreferenced repository clone ([SIZE])
+
+    ----- stderr -----
+    ");
+
+    let clones = std::fs::read_dir(context.home_dir().child("repos"))?.count();
+    assert_eq!(clones, 1);
+
+    Ok(())
+}
+
+/// Entries created before the local repositories were first recorded may be used by
+/// repositories that haven't run since, so `prek cache gc` keeps them.
+#[test]
+fn cache_gc_keeps_entries_from_before_tracking() -> anyhow::Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    // A clone and an environment made by a version that didn't record their users.
+    let home = context.home_dir();
+    let old_clone = home.child("repos").child("0123456789abcdef");
+    old_clone.child(".prek-repo.json").write_str("{}")?;
+    age_file(&old_clone.child(".prek-repo.json"), 1)?;
+    let old_env = home.child("hooks").child("python-0123456789abcdef");
+    old_env.child(".prek-hook.json").write_str("{}")?;
+    age_file(&old_env.child(".prek-hook.json"), 1)?;
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: hello
+                name: hello
+                language: system
+                entry: echo hello
+                pass_filenames: false
+    "});
+    context.git_add(".");
+    context.run().assert().success();
+    home.child("config-refs.json")
+        .assert(predicates::path::exists());
+
+    cmd_snapshot!(context.filters(), context.command().args(["cache", "gc"]), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Nothing to clean
+
+    ----- stderr -----
+    ");
+    old_clone.assert(predicates::path::exists());
+    old_env.assert(predicates::path::exists());
+
+    Ok(())
+}
+
+/// The output of a failed environment build is kept in a log, shown by `prek cache logs`.
+#[test]
+fn cache_logs() {
+    let context = TestContext::new();
+    context.init_project();
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: broken
+                name: broken
+                language: node
+                entry: echo
+                additional_dependencies: ['file:./does-not-exist']
+              - id: ok
+                name: ok
+                language: system
+                entry: echo
+    "});
+    context.git_add(".");
+
+    context
+        .run()
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "lines of the build log of `broken`:",
+        ))
+        .stderr(predicates::str::contains(
+            "error: Failed to install hook `broken`, see the build log at `",
+        ));
+
+    context
+        .command()
+        .args(["cache", "logs", "broken"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("npm install"))
+        .stdout(predicates::str::contains("does-not-exist"));
+
+    cmd_snapshot!(context.filters(), context.command().args(["cache", "logs", "ok"]), @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: No build log found for `ok`
+    ");
+}
+
+#[test]
+fn cache_export_import() -> anyhow::Result<()> {
+    let context = TestContext::new();
+
+    let home = context.work_dir().child("home");
+    let repo = home.child("repos").child("0123456789abcdef");
+    repo.child(".prek-repo.json")
+        .write_str(r#"{"repo":"https://github.com/pre-commit/pre-commit-hooks","rev":"v5.0.0"}"#)?;
+
+    let env = home.child("hooks").child("python-abcdef");
+    env.child(".prek-hook.json").write_str(&format!(
+        r#"{{"language":"python","language_version":"3.12.1","dependencies":[],"env_path":"{}","toolchain":"/usr/bin/python3","extra":{{}}}}"#,
+        env.path().display().to_string().replace('\\', "/"),
+    ))?;
+    env.child("bin")
+        .child("hook")
+        .write_str(&format!("#!{}/bin/python\n", env.path().display()))?;
+    home.child("tools")
+        .child("uv")
+        .child("uv")
+        .write_str("uv")?;
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(
+        home.child("tools").child("uv").child("uv").path(),
+        env.child("bin").child("uv").path(),
+    )?;
+
+    let archive = context.work_dir().child("export.tar.gz");
+    cmd_snapshot!(context.filters(), context.command().args(["cache", "export"]).arg(&*archive).env("PREK_HOME", &*home), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Exported 3 cache entries to `export.tar.gz`
+
+    ----- stderr -----
+    ");
+
+    let other = context.work_dir().child("other");
+    cmd_snapshot!(context.filters(), context.command().args(["cache", "import"]).arg(&*archive).env("PREK_HOME", &*other), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Imported 3 cache entries
+
+    ----- stderr -----
+    ");
+
+    let other_env = other.child("hooks").child("python-abcdef");
+    other
+        .child("repos")
+        .child("0123456789abcdef")
+        .child(".prek-repo.json")
+        .assert(predicates::path::exists());
+    other_env
+        .child(".prek-hook.json")
+        .assert(predicates::str::contains(
+            other_env.path().display().to_string().replace('\\', "/"),
+        ));
+    other_env
+        .child("bin")
+        .child("hook")
+        .assert(format!("#!{}/bin/python\n", other_env.path().display()));
+    other.child("tools").child("uv").child("uv").assert("uv");
+    #[cfg(unix)]
+    assert_eq!(
+        fs_err::read_link(other_env.child("bin").child("uv").path())?,
+        other.child("tools").child("uv").child("uv").path()
+    );
+
+    // Existing entries are kept.
+    cmd_snapshot!(context.filters(), context.command().args(["cache", "import"]).arg(&*archive).env("PREK_HOME", &*other), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Imported 0 cache entries, skipped 3 existing
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
+
+#[test]
+fn read_only_store_with_overlay() -> anyhow::Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    // A hook repository to clone.
+    let hook_repo = assert_fs::TempDir::new()?;
+    let rev = create_git_hook_repo(
+        &hook_repo,
+        indoc::indoc! {r"
+            - id: echo
+              name: echo
+              entry: echo
+              language: system
+        "},
+    )?;
+
+    context.write_pre_commit_config(&indoc::formatdoc! {r"
+        repos:
+          - repo: {}
+            rev: {}
+            hooks:
+              - id: echo
+    ", hook_repo.display(), rev});
+    context.git_add(".");
+
+    let base = context.home_dir().child("base");
+    let overlay = context.home_dir().child("overlay");
+
+    // Populate the shared store.
+    context.run().env("PREK_HOME", &*base).assert().success();
+    base.child("repos").assert(predicates::path::is_dir());
+
+    // The repo is used from the shared store, nothing is written to it.
+    cmd_snapshot!(context.filters(), context.run().env("PREK_HOME", &*base).env("PREK_HOME_OVERLAY", &*overlay), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    echo.....................................................................Passed
+
+    ----- stderr -----
+    ");
+    assert_eq!(std::fs::read_dir(overlay.child("repos"))?.count(), 0);
+
+    Ok(())
+}
@@ -155,6 +155,10 @@ impl TestContext {
             cmd.current_dir(self.work_dir());
             cmd.env(EnvVars::PREK_HOME, &**self.home_dir());
             cmd.env(EnvVars::PREK_INTERNAL__SORT_FILENAMES, "1");
+            // Don't detect the CI provider running the tests, tests opt in with `CI`.
+            cmd.env_remove(EnvVars::GITHUB_ACTIONS);
+            cmd.env_remove(EnvVars::GITLAB_CI);
+            cmd.env_remove(EnvVars::BITBUCKET_BUILD_NUMBER);
             cmd
         }
     }
@@ -458,3 +462,41 @@ pub(crate) fn remove_bin_from_path(bin: &str, path: Option<OsString>) -> anyhow:
 
     Ok(std::env::join_paths(new_path_entries)?)
 }
+
+/// Create a git repository in `dir`, committing as the test author, for example a hook
+/// repository to clone.
+pub(crate) fn init_git_repo(dir: &Path) -> anyhow::Result<()> {
+    fs_err::create_dir_all(dir)?;
+    git(dir, &["init"]);
+    git(dir, &["config", "user.name", "Prek Test"]);
+    git(dir, &["config", "user.email", "test@prek.dev"]);
+    git(dir, &["config", "core.autocrlf", "false"]);
+    Ok(())
+}
+
+/// Run `git` in `dir`, asserting that it succeeds, and return its output without the trailing
+/// newline.
+pub(crate) fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    String::from_utf8(output)
+        .expect("git output is not UTF-8")
+        .trim_end()
+        .to_string()
+}
+
+/// Create a hook repository in `dir` with `manifest` committed as its hook manifest, and
+/// return the revision of the commit.
+pub(crate) fn create_git_hook_repo(dir: &Path, manifest: &str) -> anyhow::Result<String> {
+    init_git_repo(dir)?;
+    fs_err::write(dir.join(prek_consts::MANIFEST_FILE), manifest)?;
+    git(dir, &["add", "."]);
+    git(dir, &["commit", "-m", "Initial commit"]);
+    Ok(git(dir, &["rev-parse", "HEAD"]))
+}
@@ -0,0 +1,119 @@
+use assert_fs::assert::PathAssert;
+use assert_fs::fixture::{FileWriteStr, PathChild, PathCreateDir};
+
+use crate::common::{TestContext, cmd_snapshot};
+
+mod common;
+
+/// `prek exec` runs the hook on the given files, outside of a git repository.
+#[test]
+fn exec() -> anyhow::Result<()> {
+    let context = TestContext::new();
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: echo
+                name: echo
+                language: system
+                entry: echo
+                alias: print
+                files: \.py$
+              - id: fail
+                name: fail
+                language: system
+                entry: sh -c 'echo failed; exit 1' --
+    "});
+    let cwd = context.work_dir();
+    cwd.child("src").create_dir_all()?;
+    cwd.child("src/main.txt").write_str("")?;
+
+    // Files are relative to the project root, and the hook's `files` pattern doesn't apply.
+    cmd_snapshot!(context.filters(), context.command().arg("exec").arg("print").arg("--").arg("main.txt").current_dir(cwd.child("src")), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    src/main.txt
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.command().arg("exec").arg("fail").arg("--").arg("src/main.txt"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    failed
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.command().arg("exec").arg("missing"), @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: No hook found with id or alias `missing` in `.pre-commit-config.yaml`
+    ");
+
+    Ok(())
+}
+
+/// `--env-dir` builds the hook environment into the given directory and reuses it from there.
+#[test]
+fn exec_env_dir() -> anyhow::Result<()> {
+    let context = TestContext::new();
+
+    context.write_pre_commit_config(indoc::indoc! {r#"
+        repos:
+          - repo: local
+            hooks:
+              - id: node-path
+                name: node-path
+                language: node
+                entry: node -e "console.log(process.env.NODE_PATH)"
+                pass_filenames: false
+    "#});
+    let cwd = context.work_dir();
+    cwd.child("src").child("main.js").write_str("")?;
+
+    cmd_snapshot!(context.filters(), context.command().args(["exec", "node-path", "--env-dir", "env"]), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    [TEMP_DIR]/env/lib/node_modules
+
+    ----- stderr -----
+    ");
+    cwd.child("env")
+        .child(".prek-hook.json")
+        .assert(predicates::path::exists());
+    let store_envs = std::fs::read_dir(context.home_dir().child("hooks"))?.count();
+    assert_eq!(store_envs, 0);
+
+    // Reused on the next run.
+    cmd_snapshot!(context.filters(), context.command().args(["exec", "node-path", "--env-dir", "env"]), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    [TEMP_DIR]/env/lib/node_modules
+
+    ----- stderr -----
+    ");
+
+    // Directories with other files are never replaced.
+    cmd_snapshot!(context.filters(), context.command().args(["exec", "node-path", "--env-dir", "src"]), @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: `[TEMP_DIR]/src` is not empty and doesn't contain a hook environment
+    ");
+    cwd.child("src")
+        .child("main.js")
+        .assert(predicates::path::exists());
+
+    Ok(())
+}
@@ -187,7 +187,275 @@ fn hook_impl_pre_push() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Hooks with `pass_push_refs: true` get the refs being pushed on stdin, others don't.
+#[test]
+fn hook_impl_pre_push_refs() {
+    let context = TestContext::new();
+    context.init_project();
+    context.configure_git_author();
+    context.write_pre_commit_config(indoc! { r#"
+        repos:
+        - repo: local
+          hooks:
+           - id: refs
+             name: refs
+             language: system
+             entry: python3 -c "import sys; print([line.split()[0] for line in sys.stdin])"
+             pass_push_refs: true
+             pass_filenames: false
+             always_run: true
+             verbose: true
+           - id: no-refs
+             name: no-refs
+             language: system
+             entry: python3 -c "import sys; print(sys.stdin.read() == '')"
+             pass_filenames: false
+             always_run: true
+             verbose: true
+    "#});
+    context.git_add(".");
+    context.git_commit("Initial commit");
+
+    context
+        .install()
+        .arg("--hook-type")
+        .arg("pre-push")
+        .assert()
+        .success();
+
+    let remote_repo_path = context.home_dir().join("remote.git");
+    Command::new("git")
+        .args(["init", "--bare"])
+        .arg(&remote_repo_path)
+        .assert()
+        .success();
+
+    let mut push = Command::new("git");
+    push.arg("push")
+        .arg(&remote_repo_path)
+        .arg("master")
+        .env(EnvVars::PREK_HOME, &**context.home_dir())
+        .current_dir(context.work_dir());
+
+    cmd_snapshot!(context.filters(), push, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    refs.....................................................................Passed
+    - hook id: refs
+    - duration: [TIME]
+
+      ['refs/heads/master']
+    no-refs..................................................................Passed
+    - hook id: no-refs
+    - duration: [TIME]
+
+      True
+
+    ----- stderr -----
+    To [HOME]/remote.git
+     * [new branch]      master -> master
+    ");
+}
+
+/// Hooks that passed are not run again when the same commits are pushed again.
+#[test]
+fn hook_impl_pre_push_cached() {
+    let context = TestContext::new();
+    context.init_project();
+    context.configure_git_author();
+    context.write_pre_commit_config(indoc! { r"
+        repos:
+        - repo: local
+          hooks:
+           - id: check
+             name: check
+             language: system
+             entry: echo checked
+             pass_filenames: false
+             verbose: true
+           - id: no-todo
+             name: no-todo
+             language: fail
+             entry: Remove the todo files
+             files: \.todo$
+    "});
+    context
+        .work_dir()
+        .child("a.todo")
+        .write_str("todo")
+        .unwrap();
+    context.git_add(".");
+    context.git_commit("Initial commit");
+
+    context
+        .install()
+        .arg("--hook-type")
+        .arg("pre-push")
+        .assert()
+        .success();
+
+    let remote_repo_path = context.home_dir().join("remote.git");
+    Command::new("git")
+        .args(["init", "--bare"])
+        .arg(&remote_repo_path)
+        .assert()
+        .success();
+
+    let push = || {
+        let mut push = Command::new("git");
+        push.arg("push")
+            .arg(&remote_repo_path)
+            .arg("master")
+            .env(EnvVars::PREK_HOME, &**context.home_dir())
+            .current_dir(context.work_dir());
+        push
+    };
+
+    cmd_snapshot!(context.filters(), push(), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    check....................................................................Passed
+    - hook id: check
+    - duration: [TIME]
+
+      checked
+    no-todo..................................................................Failed
+    - hook id: no-todo
+    - exit code: 1
+
+      Remove the todo files
+
+      a.todo
+
+    ----- stderr -----
+    error: failed to push some refs to '[HOME]/remote.git'
+    ");
+
+    // Only the hook that passed is cached, the failing one runs again.
+    cmd_snapshot!(context.filters(), push(), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    check............................................................(cached)Passed
+    no-todo..................................................................Failed
+    - hook id: no-todo
+    - exit code: 1
+
+      Remove the todo files
+
+      a.todo
+
+    ----- stderr -----
+    error: failed to push some refs to '[HOME]/remote.git'
+    ");
+
+    cmd_snapshot!(context.filters(), push().env(EnvVars::PREK_NO_PUSH_CACHE, "1"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    check....................................................................Passed
+    - hook id: check
+    - duration: [TIME]
+
+      checked
+    no-todo..................................................................Failed
+    - hook id: no-todo
+    - exit code: 1
+
+      Remove the todo files
+
+      a.todo
+
+    ----- stderr -----
+    error: failed to push some refs to '[HOME]/remote.git'
+    ");
+}
+
 /// Test prek hook runs in the correct worktree.
+/// Pushing new branches runs hooks on the files of the commits not on the remote yet, for all pushed refs.
+#[test]
+fn hook_impl_pre_push_new_branches() -> anyhow::Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+    context.configure_git_author();
+    context.write_pre_commit_config(indoc! { r"
+        repos:
+        - repo: local
+          hooks:
+           - id: files
+             name: files
+             language: system
+             entry: echo
+             verbose: true
+    "});
+    context.work_dir().child("base.txt").write_str("base")?;
+    context.git_add(".");
+    context.git_commit("Initial commit");
+
+    let remote_repo_path = context.home_dir().join("remote.git");
+    Command::new("git")
+        .args(["init", "--bare"])
+        .arg(&remote_repo_path)
+        .assert()
+        .success();
+    Command::new("git")
+        .args(["remote", "add", "origin"])
+        .arg(&remote_repo_path)
+        .current_dir(context.work_dir())
+        .assert()
+        .success();
+    Command::new("git")
+        .args(["push", "origin", "master"])
+        .current_dir(context.work_dir())
+        .assert()
+        .success();
+
+    context
+        .install()
+        .arg("--hook-type")
+        .arg("pre-push")
+        .assert()
+        .success();
+
+    context.git_branch("feature-a");
+    context.git_checkout("feature-a");
+    context.work_dir().child("a.txt").write_str("a")?;
+    context.git_add(".");
+    context.git_commit("Add a");
+
+    context.git_branch("feature-b");
+    context.git_checkout("feature-b");
+    context.work_dir().child("b.txt").write_str("b")?;
+    context.git_add(".");
+    context.git_commit("Add b");
+
+    let mut push = Command::new("git");
+    push.args(["push", "origin", "feature-a", "feature-b"])
+        .env(EnvVars::PREK_HOME, &**context.home_dir())
+        .env(EnvVars::PREK_INTERNAL__SORT_FILENAMES, "1")
+        .current_dir(context.work_dir());
+
+    cmd_snapshot!(context.filters(), push, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    files....................................................................Passed
+    - hook id: files
+    - duration: [TIME]
+
+      b.txt a.txt
+
+    ----- stderr -----
+    To [HOME]/remote.git
+     * [new branch]      feature-a -> feature-a
+     * [new branch]      feature-b -> feature-b
+    ");
+
+    Ok(())
+}
+
 #[test]
 fn run_worktree() -> anyhow::Result<()> {
     let context = TestContext::new();
@@ -793,3 +1061,53 @@ fn workspace_hook_impl_with_selectors() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// `reference-transaction` hooks get the state as an env var and the ref updates on stdin.
+#[test]
+fn hook_impl_reference_transaction() -> anyhow::Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+    context.configure_git_author();
+    context.disable_auto_crlf();
+
+    // Git runs the hook once per state, which ones depends on the git version.
+    context
+        .work_dir()
+        .child(CONFIG_FILE)
+        .write_str(indoc::indoc! {r#"
+        repos:
+          - repo: local
+            hooks:
+              - id: refs
+                name: refs
+                language: system
+                entry: python3 -c "import os, sys; state = os.environ['PRE_COMMIT_REFERENCE_TRANSACTION_STATE']; refs = [line.split()[2] for line in sys.stdin]; state == 'committed' and open('refs.log', 'a').write(' '.join([state, *refs]))"
+                stages: [ reference-transaction ]
+                always_run: true
+    "#})?;
+    context.git_add(".");
+    context.git_commit("Initial commit");
+
+    cmd_snapshot!(context.filters(), context.install().arg("--hook-type").arg("reference-transaction"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    prek installed at `.git/hooks/reference-transaction`
+
+    ----- stderr -----
+    ");
+
+    Command::new("git")
+        .current_dir(context.work_dir())
+        .env(EnvVars::PREK_HOME, &**context.home_dir())
+        .args(["update-ref", "refs/heads/topic", "HEAD"])
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs_err::read_to_string(context.work_dir().child("refs.log"))?,
+        "committed refs/heads/topic"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,135 @@
+use assert_fs::fixture::PathChild;
+
+use crate::common::{TestContext, cmd_snapshot, create_git_hook_repo, git};
+
+mod common;
+
+/// `prek info` shows where a hook comes from and where each of its options is set.
+#[test]
+fn info() -> anyhow::Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    // A hook repository to clone.
+    let hook_repo = context.home_dir().child("hook-repo");
+    create_git_hook_repo(
+        &hook_repo,
+        indoc::indoc! {r"
+            - id: echo
+              name: echo
+              entry: echo hello
+              language: system
+              files: ^src/
+              args: [--manifest]
+        "},
+    )?;
+    git(&hook_repo, &["tag", "v1.0"]);
+
+    context.write_pre_commit_config(&indoc::formatdoc! {r"
+        default_stages: [pre-commit, pre-push]
+        repos:
+          - repo: {}
+            rev: v1.0
+            exclude: ^vendor/
+            hooks:
+              - id: echo
+                args: [--config]
+                verbose: true
+          - repo: local
+            hooks:
+              - id: check
+                name: check
+                language: system
+                entry: sh -c 'exit 0'
+                pass_filenames: false
+    ", hook_repo.display()});
+
+    let mut filters = context.filters();
+    filters.push((r"repos/[0-9a-f]+/", "repos/[REPO]/"));
+
+    cmd_snapshot!(filters.clone(), context.command().args(["info", "echo"]), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    .:echo
+      Repo: [HOME]/hook-repo
+      Rev: v1.0
+      Manifest: [HOME]/repos/[REPO]/.pre-commit-hooks.yaml
+      Config: .pre-commit-config.yaml
+      Command: echo hello --config <files>
+      Environment: not needed
+      Options:
+        name: echo (manifest)
+        entry: echo hello (manifest)
+        language: system (manifest)
+        files: regex: ^src/ (manifest)
+        exclude: regex: ^vendor/ (repo)
+        types: [file] (default)
+        types_or: [] (default)
+        exclude_types: [] (default)
+        additional_dependencies: [] (default)
+        args: [--config] (config, manifest: [--manifest])
+        always_run: false (default)
+        fail_fast: false (default)
+        pass_filenames: true (default)
+        pass_push_refs: false (default)
+        success_exit_codes: [0] (default)
+        fail_on_output: false (default)
+        retries: 0 (default)
+        retry_delay: 0 (default)
+        language_version: default (default)
+        require_serial: false (default)
+        priority: 0 (default)
+        stages: pre-commit, pre-push (default_stages)
+        verbose: true (config)
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(filters.clone(), context.command().args(["info", ".:check"]), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    .:check
+      Repo: local
+      Config: .pre-commit-config.yaml
+      Command: sh -c 'exit 0'
+      Environment: not needed
+      Options:
+        name: check (config)
+        entry: sh -c 'exit 0' (config)
+        language: system (config)
+        types: [file] (default)
+        types_or: [] (default)
+        exclude_types: [] (default)
+        additional_dependencies: [] (default)
+        args: [] (default)
+        always_run: false (default)
+        fail_fast: false (default)
+        pass_filenames: false (config)
+        pass_push_refs: false (default)
+        success_exit_codes: [0] (default)
+        fail_on_output: false (default)
+        retries: 0 (default)
+        retry_delay: 0 (default)
+        language_version: default (default)
+        require_serial: false (default)
+        priority: 1 (default)
+        stages: pre-commit, pre-push (default_stages)
+        verbose: false (default)
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(filters, context.command().args(["info", "missing"]), @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    warning: selector `missing` did not match any hooks
+    error: No hook found for `missing`
+    ");
+
+    Ok(())
+}
@@ -165,6 +165,69 @@ fn install() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// `PREK_HOOK_TYPES` selects the hook types for `install` and `uninstall`, and `install` warns
+/// about hooks that none of the installed hook types trigger.
+#[test]
+fn install_hook_types_env() {
+    let context = TestContext::new();
+    context.init_project();
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: on-push
+                name: on-push
+                language: system
+                entry: echo
+                stages: [pre-push]
+              - id: manual
+                name: manual
+                language: system
+                entry: echo
+                stages: [manual]
+    "});
+
+    cmd_snapshot!(context.filters(), context.install(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    prek installed at `.git/hooks/pre-commit`
+
+    ----- stderr -----
+    warning: Hook `on-push` only runs in stages no installed hook type triggers: `pre-push`
+    hint: Install the hook types with `prek install -t pre-push`
+    ");
+
+    cmd_snapshot!(context.filters(), context.install().env("PREK_HOOK_TYPES", "pre-commit,pre-push"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    prek installed at `.git/hooks/pre-commit`
+    prek installed at `.git/hooks/pre-push`
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.uninstall().env("PREK_HOOK_TYPES", "pre-push"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Uninstalled `pre-push`
+
+    ----- stderr -----
+    ");
+
+    // The command line takes precedence.
+    cmd_snapshot!(context.filters(), context.uninstall().arg("-t").arg("pre-commit").env("PREK_HOOK_TYPES", "pre-push"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Uninstalled `pre-commit`
+
+    ----- stderr -----
+    ");
+}
+
 /// Run `prek install --install-hooks` to install the git hook and create prek hook environments.
 #[test]
 fn install_with_hooks() -> anyhow::Result<()> {
@@ -196,6 +259,7 @@ fn install_with_hooks() -> anyhow::Result<()> {
     exit_code: 0
     ----- stdout -----
     prek installed at `.git/hooks/pre-commit`
+    Hooks: 0 up-to-date, 2 to build
 
     ----- stderr -----
     "#);
@@ -258,6 +322,7 @@ fn install_hooks_only() -> anyhow::Result<()> {
     success: true
     exit_code: 0
     ----- stdout -----
+    Hooks: 0 up-to-date, 2 to build
 
     ----- stderr -----
     "#);
@@ -275,6 +340,65 @@ fn install_hooks_only() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// `prek install-hooks` only builds the environments that are missing.
+#[test]
+fn install_hooks_incremental() {
+    let context = TestContext::new();
+    context.init_project();
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: no-todo
+                name: no-todo
+                language: pygrep
+                entry: TODO
+              - id: echo
+                name: echo
+                language: system
+                entry: echo
+    "});
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.install_hooks().arg("--dry-run"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Hooks: 0 up-to-date, 1 to build
+    Would build `local:no-todo`
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.install_hooks(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Hooks: 0 up-to-date, 1 to build
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.install_hooks(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Hooks: 1 up-to-date, 0 to build
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.install_hooks().arg("--force").arg("--dry-run"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Hooks: 0 up-to-date, 1 to build
+    Would build `local:no-todo`
+
+    ----- stderr -----
+    ");
+}
+
 #[test]
 fn uninstall() -> anyhow::Result<()> {
     let context = TestContext::new();
@@ -459,6 +583,115 @@ fn init_template_dir() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// `prek init-template-dir --bootstrap` adds a stanza downloading prek to the hook script.
+#[test]
+fn init_template_dir_bootstrap() {
+    let context = TestContext::new();
+    context.init_project();
+
+    cmd_snapshot!(context.filters(), context.command().arg("init-template-dir").arg("--bootstrap").arg("--bootstrap-version").arg("0.2.0").arg("template"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    prek installed at `template/hooks/pre-commit`
+
+    ----- stderr -----
+    warning: git config `init.templateDir` not set to the target directory, try `git config --global init.templateDir 'template'`
+    ");
+
+    let script = context.read("template/hooks/pre-commit");
+    insta::with_settings!(
+        { filters => context.filters() },
+        {
+            assert_snapshot!(script, @r#"
+            #!/bin/sh
+            # File generated by prek: https://github.com/j178/prek
+            # ID: 182c10f181da4464a3eec51b83331688
+
+            HERE="$(cd "$(dirname "$0")" && pwd)"
+            PREK="[CURRENT_EXE]"
+
+            # Check if the full path to prek is executable, otherwise fallback to PATH
+            if [ ! -x "$PREK" ]; then
+                PREK="prek"
+            fi
+
+            # Download prek if it is not installed.
+            if ! command -v "$PREK" >/dev/null 2>&1; then
+                PREK_VERSION="0.2.0"
+                PREK_BIN_DIR="${PREK_HOME:-${XDG_CACHE_HOME:-$HOME/.cache}/prek}/bin"
+                PREK="$PREK_BIN_DIR/prek-$PREK_VERSION"
+                if [ ! -x "$PREK" ]; then
+                    case "$(uname -s)-$(uname -m)" in
+                        Linux-x86_64) TARGET="x86_64-unknown-linux-musl" ;;
+                        Linux-aarch64 | Linux-arm64) TARGET="aarch64-unknown-linux-musl" ;;
+                        Linux-armv7l) TARGET="armv7-unknown-linux-musleabihf" ;;
+                        Linux-i686 | Linux-i386) TARGET="i686-unknown-linux-musl" ;;
+                        Darwin-x86_64) TARGET="x86_64-apple-darwin" ;;
+                        Darwin-arm64) TARGET="aarch64-apple-darwin" ;;
+                        *)
+                            echo "prek: no prebuilt prek for $(uname -s) $(uname -m), install it manually" >&2
+                            exit 1
+                            ;;
+                    esac
+                    ARCHIVE="prek-$TARGET.tar.gz"
+                    URL="https://github.com/j178/prek/releases/download/v$PREK_VERSION/$ARCHIVE"
+                    TMP="$(mktemp -d)"
+                    echo "prek: downloading prek $PREK_VERSION from $URL" >&2
+                    if command -v curl >/dev/null 2>&1; then
+                        DOWNLOAD="curl -fsSL -o"
+                    else
+                        DOWNLOAD="wget -q -O"
+                    fi
+                    if ! $DOWNLOAD "$TMP/$ARCHIVE" "$URL" || ! $DOWNLOAD "$TMP/$ARCHIVE.sha256" "$URL.sha256"; then
+                        rm -rf "$TMP"
+                        echo "prek: failed to download $URL" >&2
+                        exit 1
+                    fi
+                    EXPECTED="$(cut -d ' ' -f 1 < "$TMP/$ARCHIVE.sha256")"
+                    if command -v sha256sum >/dev/null 2>&1; then
+                        ACTUAL="$(sha256sum "$TMP/$ARCHIVE" | cut -d ' ' -f 1)"
+                    else
+                        ACTUAL="$(shasum -a 256 "$TMP/$ARCHIVE" | cut -d ' ' -f 1)"
+                    fi
+                    if [ -z "$EXPECTED" ] || [ "$EXPECTED" != "$ACTUAL" ]; then
+                        rm -rf "$TMP"
+                        echo "prek: checksum mismatch for $ARCHIVE" >&2
+                        exit 1
+                    fi
+                    mkdir -p "$PREK_BIN_DIR"
+                    tar -xzf "$TMP/$ARCHIVE" -C "$TMP"
+                    mv "$TMP/prek-$TARGET/prek" "$PREK"
+                    rm -rf "$TMP"
+                fi
+            fi
+
+            exec "$PREK" hook-impl --hook-dir "$HERE" --script-version 4 --hook-type=pre-commit --skip-on-missing-config -- "$@"
+            "#);
+        }
+    );
+
+    #[cfg(unix)]
+    {
+        std::process::Command::new("sh")
+            .arg("-n")
+            .arg(context.work_dir().join("template/hooks/pre-commit"))
+            .assert()
+            .success();
+    }
+
+    // The version is interpolated into the script, so it must be a valid version.
+    cmd_snapshot!(context.filters(), context.command().arg("init-template-dir").arg("--bootstrap").arg("--bootstrap-version").arg("latest").arg("template"), @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: Invalid prek version `latest` to bootstrap
+      caused by: unexpected character 'l' while parsing major version number
+    ");
+}
+
 /// Tests `prek init-template-dir` in a non-git repository.
 #[test]
 fn init_template_dir_non_git_repo() {
@@ -689,10 +922,11 @@ fn workspace_install_hooks() -> anyhow::Result<()> {
     context.git_add(".");
 
     // Install by selectors
-    cmd_snapshot!(context.filters(), context.install_hooks().arg("project3").arg("--skip").arg("project3/project5/"), @r"
+    cmd_snapshot!(context.filters(), context.install_hooks().arg("project3/").arg("--skip").arg("project3/project5/"), @r"
     success: true
     exit_code: 0
     ----- stdout -----
+    Hooks: 0 up-to-date, 1 to build
 
     ----- stderr -----
     ");
@@ -702,6 +936,7 @@ fn workspace_install_hooks() -> anyhow::Result<()> {
     success: true
     exit_code: 0
     ----- stdout -----
+    Hooks: 5 up-to-date, 0 to build
 
     ----- stderr -----
     ");
@@ -883,3 +1118,71 @@ fn workspace_init_template_dir() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// `prek install --recursive` installs hooks into every git repository with a config file.
+#[test]
+fn install_recursive() -> anyhow::Result<()> {
+    let context = TestContext::new();
+    let cwd = context.work_dir();
+
+    let config = indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: echo
+                name: echo
+                language: system
+                entry: echo
+    "};
+    for (repo, git, has_config) in [
+        ("org/alpha", true, true),
+        ("org/beta", true, false),
+        ("org/nested/gamma", true, true),
+        ("org/hooks-path", true, true),
+        ("org/notes", false, true),
+    ] {
+        let dir = cwd.child(repo);
+        dir.create_dir_all()?;
+        if git {
+            std::process::Command::new("git")
+                .arg("init")
+                .current_dir(&dir)
+                .assert()
+                .success();
+        }
+        if has_config {
+            dir.child(CONFIG_FILE).write_str(config)?;
+        }
+    }
+    std::process::Command::new("git")
+        .args(["config", "core.hooksPath", "hooks"])
+        .current_dir(cwd.child("org/hooks-path"))
+        .assert()
+        .success();
+
+    cmd_snapshot!(context.filters(), context.install().arg("--recursive").arg("org"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    Repository    Status
+    alpha         installed
+    hooks-path    failed
+    nested/gamma  installed
+
+    ----- stderr -----
+    error: Failed to install hooks in `hooks-path`:
+    error: Cowardly refusing to install hooks with `core.hooksPath` set.
+    hint: Run these commands to remove core.hooksPath:
+    hint:   git config --unset-all --local core.hooksPath
+    hint:   git config --unset-all --global core.hooksPath
+    ");
+
+    cwd.child("org/alpha/.git/hooks/pre-commit")
+        .assert(predicates::path::exists());
+    cwd.child("org/nested/gamma/.git/hooks/pre-commit")
+        .assert(predicates::path::exists());
+    cwd.child("org/beta/.git/hooks/pre-commit")
+        .assert(predicates::path::missing());
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use crate::common::{TestContext, cmd_snapshot};
+
+/// Test installing a JVM app from `additional_dependencies`.
+// coursier is only installed in the Linux CI job.
+#[cfg(target_os = "linux")]
+#[test]
+fn additional_dependencies() {
+    let context = TestContext::new();
+    context.init_project();
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: scalafmt
+                name: scalafmt
+                language: coursier
+                entry: scalafmt --version
+                additional_dependencies: ['scalafmt:3.8.3']
+                always_run: true
+                verbose: true
+                pass_filenames: false
+    "});
+
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    scalafmt.................................................................Passed
+    - hook id: scalafmt
+    - duration: [TIME]
+
+      scalafmt 3.8.3
+
+    ----- stderr -----
+    ");
+}
+
+/// Test that a coursier hook without `.pre-commit-channel` or `additional_dependencies` fails to install.
+#[test]
+fn nothing_to_install() {
+    let context = TestContext::new();
+    context.init_project();
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: local
+                name: local
+                language: coursier
+                entry: scalafmt
+                always_run: true
+                pass_filenames: false
+    "});
+
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: Failed to install hook `local`
+      caused by: Expected a `.pre-commit-channel` directory or `additional_dependencies`
+    ");
+}
+
+/// Test that an invalid JDK `language_version` is rejected.
+#[test]
+fn invalid_language_version() {
+    let context = TestContext::new();
+    context.init_project();
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: local
+                name: local
+                language: coursier
+                entry: scalafmt
+                language_version: jdk-abc
+                additional_dependencies: ['scalafmt']
+                always_run: true
+                pass_filenames: false
+    "});
+
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: Failed to init hooks
+      caused by: Invalid hook `local`
+      caused by: Invalid `language_version` value: `jdk-abc`
+    ");
+}
@@ -324,3 +324,55 @@ fn local_additional_deps() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Local hooks install path dependencies from the project, with the build flags in `goflags`.
+#[test]
+fn local_goflags() -> anyhow::Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    let work_dir = context.work_dir();
+    work_dir.child("go.mod").write_str(indoc::indoc! {r"
+        module example.com/project
+    "})?;
+    work_dir.child("cmd").create_dir_all()?;
+    work_dir.child("cmd/main.go").write_str(indoc::indoc! {r#"
+        package main
+
+        var version = "dev"
+
+        func main() {
+            println("version", version)
+        }
+    "#})?;
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: versioned
+                name: versioned
+                language: golang
+                entry: cmd
+                additional_dependencies: [ ./cmd ]
+                goflags: [ '-ldflags=-X main.version=1.2.3' ]
+                always_run: true
+                pass_filenames: false
+                verbose: true
+    "});
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    versioned................................................................Passed
+    - hook id: versioned
+    - duration: [TIME]
+
+      version 1.2.3
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
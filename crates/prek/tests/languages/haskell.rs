@@ -0,0 +1,70 @@
+use crate::common::{TestContext, cmd_snapshot};
+
+/// Test installing a Haskell executable from `additional_dependencies`.
+// The GitHub Ubuntu runner ships with GHC and cabal.
+#[cfg(target_os = "linux")]
+#[test]
+fn additional_dependencies() {
+    let context = TestContext::new();
+    context.init_project();
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: hello
+                name: hello
+                language: haskell
+                entry: hello
+                additional_dependencies: ['hello']
+                always_run: true
+                verbose: true
+                pass_filenames: false
+    "});
+
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    hello....................................................................Passed
+    - hook id: hello
+    - duration: [TIME]
+
+      Hello, World!
+
+    ----- stderr -----
+    ");
+}
+
+/// Test that a Haskell hook without `.cabal` files or `additional_dependencies` fails to install.
+#[test]
+fn nothing_to_install() {
+    let context = TestContext::new();
+    context.init_project();
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: local
+                name: local
+                language: haskell
+                entry: hlint
+                always_run: true
+                pass_filenames: false
+    "});
+
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: Failed to install hook `local`
+      caused by: Expected `.cabal` files or `additional_dependencies`
+    ");
+}
@@ -1,16 +1,21 @@
 #[path = "../common/mod.rs"]
 mod common;
 
+mod coursier;
 #[cfg(all(feature = "docker", target_os = "linux"))]
 mod docker;
 #[cfg(all(feature = "docker", target_os = "linux"))]
 mod docker_image;
 mod fail;
 mod golang;
+mod haskell;
 mod lua;
 mod node;
 mod pygrep;
 mod python;
+// R is only installed in the Linux CI job.
+#[cfg(target_os = "linux")]
+mod r;
 mod ruby;
 mod rust;
 mod script;
@@ -2,7 +2,7 @@ use assert_fs::assert::PathAssert;
 use assert_fs::fixture::{FileWriteStr, PathChild};
 use prek_consts::env_vars::EnvVars;
 
-use crate::common::{TestContext, cmd_snapshot, remove_bin_from_path};
+use crate::common::{TestContext, cmd_snapshot, git, init_git_repo, remove_bin_from_path};
 
 /// Test `language_version` parsing and auto downloading works correctly.
 /// We use `setup-node` action to install node 20 in CI, so node 19 should be downloaded by prek.
@@ -280,3 +280,69 @@ fn npm_version() {
     ----- stderr -----
     ");
 }
+
+/// Remote repos are cloned without history, which is fetched when installing a hook fails,
+/// so that packages that derive their version from tags can be installed.
+#[test]
+fn remote_repo_history_fetched_on_install_failure() -> anyhow::Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    let repo_dir = context.home_dir().child("test-repos/tagged-hook");
+    init_git_repo(&repo_dir)?;
+    repo_dir.child("README.md").write_str("tagged-hook")?;
+    git(&repo_dir, &["add", "."]);
+    git(&repo_dir, &["commit", "-m", "Initial commit"]);
+    git(&repo_dir, &["tag", "v1.0.0"]);
+
+    repo_dir
+        .child(prek_consts::MANIFEST_FILE)
+        .write_str(indoc::indoc! {r"
+            - id: tagged-hook
+              name: tagged-hook
+              entry: tagged-hook
+              language: node
+              always_run: true
+              pass_filenames: false
+              verbose: true
+        "})?;
+    repo_dir.child("package.json").write_str(indoc::indoc! {r#"
+        {
+          "name": "tagged-hook",
+          "version": "0.0.0",
+          "bin": { "tagged-hook": "index.js" },
+          "scripts": { "prepare": "git describe --tags --abbrev=0 > version.txt" }
+        }
+    "#})?;
+    repo_dir.child("index.js").write_str(indoc::indoc! {r"
+        #!/usr/bin/env node
+        console.log(require('fs').readFileSync(__dirname + '/version.txt', 'utf8').trim());
+    "})?;
+    git(&repo_dir, &["add", "."]);
+    git(&repo_dir, &["commit", "-m", "Add hook"]);
+    let rev = git(&repo_dir, &["rev-parse", "HEAD"]);
+
+    context.write_pre_commit_config(&indoc::formatdoc! {r"
+        repos:
+          - repo: {}
+            rev: {}
+            hooks:
+              - id: tagged-hook
+    ", repo_dir.display(), rev});
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    tagged-hook..............................................................Passed
+    - hook id: tagged-hook
+    - duration: [TIME]
+
+      v1.0.0
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
@@ -191,10 +191,18 @@ fn can_not_download() {
     let mut filters = context
         .filters()
         .into_iter()
-        .chain([(
-            "managed installations, search path, or registry",
-            "managed installations or search path",
-        )])
+        .chain([
+            (
+                "managed installations, search path, or registry",
+                "managed installations or search path",
+            ),
+            // The build log depends on how uv was found.
+            (
+                r"(?m)^Last \d+ lines of the build log of `.+`:\n(  .*\n)*",
+                "",
+            ),
+            (r"see the build log at `.+`", "see the build log at `[LOG]`"),
+        ])
         .collect::<Vec<_>>();
     if cfg!(windows) {
         // Unix uses "exit status", Windows uses "exit code"
@@ -207,7 +215,7 @@ fn can_not_download() {
     ----- stdout -----
 
     ----- stderr -----
-    error: Failed to install hook `less-than-3.6`
+    error: Failed to install hook `less-than-3.6`, see the build log at `[LOG]`
       caused by: Failed to create Python virtual environment
       caused by: Command `create venv` exited with an error:
 
@@ -321,6 +329,112 @@ fn additional_dependencies_in_remote_repo() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Local hooks can install the project being committed in editable mode.
+#[test]
+fn editable_install_of_local_project() -> anyhow::Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    let cwd = context.work_dir();
+    context.write_pre_commit_config(indoc::indoc! {r#"
+        repos:
+          - repo: local
+            hooks:
+              - id: greet
+                name: greet
+                language: python
+                entry: greet
+                additional_dependencies: ["-e", "."]
+                always_run: true
+                verbose: true
+                pass_filenames: false
+    "#});
+    cwd.child("pyproject.toml").write_str(indoc::indoc! {r#"
+        [project]
+        name = "greeter"
+        version = "0.1.0"
+
+        [project.scripts]
+        greet = "greeter:main"
+
+        [build-system]
+        requires = ["setuptools"]
+        build-backend = "setuptools.build_meta"
+    "#})?;
+    cwd.child("greeter.py").write_str(indoc::indoc! {r#"
+        def main():
+            print("Hello")
+    "#})?;
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    greet....................................................................Passed
+    - hook id: greet
+    - duration: [TIME]
+
+      Hello
+
+    ----- stderr -----
+    ");
+
+    // Source changes are picked up without reinstalling.
+    cwd.child("greeter.py").write_str(indoc::indoc! {r#"
+        def main():
+            print("Hello again")
+    "#})?;
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    greet....................................................................Passed
+    - hook id: greet
+    - duration: [TIME]
+
+      Hello again
+
+    ----- stderr -----
+    ");
+
+    // Metadata changes rebuild the environment.
+    cwd.child("pyproject.toml").write_str(indoc::indoc! {r#"
+        [project]
+        name = "greeter"
+        version = "0.1.0"
+
+        [project.scripts]
+        greet = "greeter:shout"
+
+        [build-system]
+        requires = ["setuptools"]
+        build-backend = "setuptools.build_meta"
+    "#})?;
+    cwd.child("greeter.py").write_str(indoc::indoc! {r#"
+        def shout():
+            print("HELLO")
+    "#})?;
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    greet....................................................................Passed
+    - hook id: greet
+    - duration: [TIME]
+
+      HELLO
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
+
 /// Ensure that stderr from hooks is captured and shown to the user.
 #[test]
 fn hook_stderr() -> anyhow::Result<()> {
@@ -0,0 +1,166 @@
+use assert_fs::fixture::{FileWriteStr, PathChild};
+
+use crate::common::{TestContext, cmd_snapshot};
+
+#[test]
+fn health_check() {
+    let context = TestContext::new();
+    context.init_project();
+
+    context.write_pre_commit_config(indoc::indoc! {r#"
+        repos:
+          - repo: local
+            hooks:
+              - id: r
+                name: r
+                language: r
+                entry: Rscript -e 'cat("Hello from R!\n")'
+                always_run: true
+                verbose: true
+                pass_filenames: false
+    "#});
+
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    r........................................................................Passed
+    - hook id: r
+    - duration: [TIME]
+
+      Hello from R!
+
+    ----- stderr -----
+    ");
+
+    // Run again to check `health_check` works correctly.
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    r........................................................................Passed
+    - hook id: r
+    - duration: [TIME]
+
+      Hello from R!
+
+    ----- stderr -----
+    ");
+}
+
+/// Test that the hook library is isolated from the user library.
+#[test]
+fn isolated_library() {
+    let context = TestContext::new();
+    context.init_project();
+
+    context.write_pre_commit_config(indoc::indoc! {r#"
+        repos:
+          - repo: local
+            hooks:
+              - id: r
+                name: r
+                language: r
+                entry: Rscript -e 'cat(basename(.libPaths()[1]), "\n")'
+                always_run: true
+                verbose: true
+                pass_filenames: false
+    "#});
+
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    r........................................................................Passed
+    - hook id: r
+    - duration: [TIME]
+
+      library
+
+    ----- stderr -----
+    ");
+}
+
+/// Test running an R script with filenames passed as arguments.
+#[test]
+fn script_entry() -> anyhow::Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: r
+                name: r
+                language: r
+                entry: Rscript hook.R
+                files: \.txt$
+                verbose: true
+    "});
+    context
+        .work_dir()
+        .child("hook.R")
+        .write_str(indoc::indoc! {r#"
+        for (file in commandArgs(trailingOnly = TRUE)) {
+          cat("Processing", file, "\n")
+        }
+        quit(status = 1)
+    "#})?;
+    context.work_dir().child("a.txt").write_str("a")?;
+
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    r........................................................................Failed
+    - hook id: r
+    - duration: [TIME]
+    - exit code: 1
+
+      Processing a.txt
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
+
+/// Test that an unsatisfiable `language_version` fails to install.
+#[test]
+fn language_version_not_found() {
+    let context = TestContext::new();
+    context.init_project();
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: r
+                name: r
+                language: r
+                entry: Rscript --version
+                language_version: '1.0'
+                always_run: true
+                pass_filenames: false
+    "});
+
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: Failed to install hook `r`
+      caused by: Failed to find R
+      caused by: No suitable R installation found for request: 1.0
+    ");
+}
@@ -1,6 +1,6 @@
 use anyhow::Result;
 use assert_fs::assert::PathAssert;
-use assert_fs::fixture::PathChild;
+use assert_fs::fixture::{FileWriteStr, PathChild};
 use prek_consts::env_vars::EnvVars;
 
 use crate::common::{TestContext, cmd_snapshot};
@@ -272,3 +272,75 @@ fn remote_hooks_with_lib_deps() {
     ----- stderr -----
     ");
 }
+
+/// Test that local hooks install crates from paths, and rebuild them when their sources change.
+#[test]
+fn additional_dependencies_path() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    context.write_pre_commit_config(indoc::indoc! {r#"
+        repos:
+          - repo: local
+            hooks:
+              - id: path-crate
+                name: path-crate
+                language: rust
+                entry: local-echo
+                additional_dependencies: ["./tools/local-echo"]
+                always_run: true
+                verbose: true
+                pass_filenames: false
+    "#});
+
+    let krate = context.work_dir().child("tools").child("local-echo");
+    krate.child("Cargo.toml").write_str(indoc::indoc! {r#"
+        [package]
+        name = "local-echo"
+        version = "0.1.0"
+        edition = "2021"
+    "#})?;
+    krate
+        .child("src")
+        .child("main.rs")
+        .write_str(r#"fn main() { println!("first build"); }"#)?;
+    context
+        .work_dir()
+        .child(".gitignore")
+        .write_str("target/\n")?;
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    path-crate...............................................................Passed
+    - hook id: path-crate
+    - duration: [TIME]
+
+      first build
+
+    ----- stderr -----
+    ");
+
+    krate
+        .child("src")
+        .child("main.rs")
+        .write_str(r#"fn main() { println!("second build"); }"#)?;
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    path-crate...............................................................Passed
+    - hook id: path-crate
+    - duration: [TIME]
+
+      second build
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use assert_cmd::assert::OutputAssertExt;
+use assert_fs::fixture::{FileWriteStr, PathChild};
+
+use crate::common::{TestContext, cmd_snapshot};
+
+mod common;
+
+#[test]
+fn last_run_and_retry_failed() -> anyhow::Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    context.write_pre_commit_config(indoc::indoc! {r#"
+        repos:
+          - repo: local
+            hooks:
+              - id: no-bad
+                name: No bad
+                language: system
+                entry: python3 -c "import sys; bad = [f for f in sys.argv[1:] if 'bad' in open(f).read()]; print(*bad); sys.exit(bool(bad))"
+                files: \.txt$
+              - id: ok
+                name: Ok
+                language: system
+                entry: python3 -c "print('ok')"
+    "#});
+    context.work_dir().child("a.txt").write_str("good")?;
+    context.work_dir().child("b.txt").write_str("bad")?;
+    context.work_dir().child("c.txt").write_str("bad")?;
+    context.git_add(".");
+
+    let filters = context
+        .filters()
+        .into_iter()
+        .chain([(r"\d+\.\d+s", "[TIME]")])
+        .collect::<Vec<_>>();
+
+    cmd_snapshot!(filters.clone(), context.command().arg("last"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    No run recorded yet
+
+    ----- stderr -----
+    ");
+
+    context.run().arg("--all-files").assert().failure();
+
+    cmd_snapshot!(filters.clone(), context.command().arg("last"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Last run in `[TEMP_DIR]/` on 4 files (pre-commit)
+
+    Hook      Status                 Files   Duration
+    .:no-bad  failed (exit code 1)       3      [TIME]
+    .:ok      passed                     4      [TIME]
+
+    1 passed · 1 failed · 0 skipped · [TIME]
+
+    hint: Run `prek run --retry-failed` to run the failed hooks again
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(filters.clone(), context.command().args(["last", "--failures"]), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    b.txt
+    c.txt
+
+    ----- stderr -----
+    ");
+
+    // Only the failed hook runs, and only on the files it failed on.
+    context.work_dir().child("b.txt").write_str("fixed")?;
+    cmd_snapshot!(filters.clone(), context.run().arg("--retry-failed"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    No bad...................................................................Failed
+    - hook id: no-bad
+    - exit code: 1
+
+      c.txt
+
+    ----- stderr -----
+    ");
+
+    context.work_dir().child("c.txt").write_str("fixed")?;
+    context.run().arg("--retry-failed").assert().success();
+
+    cmd_snapshot!(filters.clone(), context.run().arg("--retry-failed"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    No hooks failed in the last run
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
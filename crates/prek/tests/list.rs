@@ -399,13 +399,16 @@ fn list_json_output() {
           "commit-msg",
           "post-checkout",
           "post-commit",
+          "post-index-change",
           "post-merge",
           "post-rewrite",
+          "pre-auto-gc",
           "pre-commit",
           "pre-merge-commit",
           "pre-push",
           "pre-rebase",
-          "prepare-commit-msg"
+          "prepare-commit-msg",
+          "reference-transaction"
         ]
       },
       {
@@ -420,13 +423,16 @@ fn list_json_output() {
           "commit-msg",
           "post-checkout",
           "post-commit",
+          "post-index-change",
           "post-merge",
           "post-rewrite",
+          "pre-auto-gc",
           "pre-commit",
           "pre-merge-commit",
           "pre-push",
           "pre-rebase",
-          "prepare-commit-msg"
+          "prepare-commit-msg",
+          "reference-transaction"
         ]
       }
     ]
@@ -452,13 +458,16 @@ fn list_json_output() {
           "commit-msg",
           "post-checkout",
           "post-commit",
+          "post-index-change",
           "post-merge",
           "post-rewrite",
+          "pre-auto-gc",
           "pre-commit",
           "pre-merge-commit",
           "pre-push",
           "pre-rebase",
-          "prepare-commit-msg"
+          "prepare-commit-msg",
+          "reference-transaction"
         ]
       }
     ]
@@ -527,13 +536,16 @@ fn workspace_list() -> anyhow::Result<()> {
           "commit-msg",
           "post-checkout",
           "post-commit",
+          "post-index-change",
           "post-merge",
           "post-rewrite",
+          "pre-auto-gc",
           "pre-commit",
           "pre-merge-commit",
           "pre-push",
           "pre-rebase",
-          "prepare-commit-msg"
+          "prepare-commit-msg",
+          "reference-transaction"
         ]
       },
       {
@@ -548,13 +560,16 @@ fn workspace_list() -> anyhow::Result<()> {
           "commit-msg",
           "post-checkout",
           "post-commit",
+          "post-index-change",
           "post-merge",
           "post-rewrite",
+          "pre-auto-gc",
           "pre-commit",
           "pre-merge-commit",
           "pre-push",
           "pre-rebase",
-          "prepare-commit-msg"
+          "prepare-commit-msg",
+          "reference-transaction"
         ]
       },
       {
@@ -569,13 +584,16 @@ fn workspace_list() -> anyhow::Result<()> {
           "commit-msg",
           "post-checkout",
           "post-commit",
+          "post-index-change",
           "post-merge",
           "post-rewrite",
+          "pre-auto-gc",
           "pre-commit",
           "pre-merge-commit",
           "pre-push",
           "pre-rebase",
-          "prepare-commit-msg"
+          "prepare-commit-msg",
+          "reference-transaction"
         ]
       },
       {
@@ -590,13 +608,16 @@ fn workspace_list() -> anyhow::Result<()> {
           "commit-msg",
           "post-checkout",
           "post-commit",
+          "post-index-change",
           "post-merge",
           "post-rewrite",
+          "pre-auto-gc",
           "pre-commit",
           "pre-merge-commit",
           "pre-push",
           "pre-rebase",
-          "prepare-commit-msg"
+          "prepare-commit-msg",
+          "reference-transaction"
         ]
       },
       {
@@ -611,13 +632,16 @@ fn workspace_list() -> anyhow::Result<()> {
           "commit-msg",
           "post-checkout",
           "post-commit",
+          "post-index-change",
           "post-merge",
           "post-rewrite",
+          "pre-auto-gc",
           "pre-commit",
           "pre-merge-commit",
           "pre-push",
           "pre-rebase",
-          "prepare-commit-msg"
+          "prepare-commit-msg",
+          "reference-transaction"
         ]
       }
     ]
@@ -804,3 +828,289 @@ fn list_with_selectors() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn list_manifest() -> anyhow::Result<()> {
+    use assert_cmd::assert::OutputAssertExt;
+    use assert_fs::prelude::*;
+    use std::process::Command;
+
+    let context = TestContext::new();
+    context.init_project();
+
+    let repo = context.home_dir().child("test-repos/hooks");
+    repo.create_dir_all()?;
+    repo.child(prek_consts::MANIFEST_FILE).write_str(indoc! {r"
+        - id: check-yaml
+          name: Check YAML
+          description: Checks yaml files for parseable syntax.
+          entry: check-yaml
+          language: python
+          types: [yaml]
+        - id: commit-msg-lint
+          name: Lint commit message
+          entry: lint
+          language: system
+          stages: [commit-msg]
+        - id: say-hello
+          name: Say hello
+          entry: echo hello
+          language: system
+    "})?;
+    for args in [
+        &["init"][..],
+        &["add", "."],
+        &[
+            "-c",
+            "user.name=Prek Test",
+            "-c",
+            "user.email=test@prek.dev",
+            "commit",
+            "-m",
+            "Initial commit",
+        ],
+    ] {
+        Command::new("git")
+            .args(args)
+            .current_dir(&repo)
+            .assert()
+            .success();
+    }
+
+    cmd_snapshot!(context.filters(), context.list().arg("--manifest").arg(&*repo), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    check-yaml       Checks yaml files for parseable syntax.
+    commit-msg-lint
+    say-hello
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.list().arg("--manifest").arg(&*repo).arg("--hook-stage").arg("pre-push").arg("--verbose"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    check-yaml
+      Name: Check YAML
+      Description: Checks yaml files for parseable syntax.
+      Language: python
+
+    say-hello
+      Name: Say hello
+      Language: system
+
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.list().arg("--manifest").arg(&*repo).arg("--language").arg("python").arg("--output-format").arg("json"), @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    [
+      {
+        "id": "check-yaml",
+        "full_id": "check-yaml",
+        "name": "Check YAML",
+        "alias": "",
+        "language": "python",
+        "description": "Checks yaml files for parseable syntax.",
+        "stages": [
+          "manual",
+          "commit-msg",
+          "post-checkout",
+          "post-commit",
+          "post-index-change",
+          "post-merge",
+          "post-rewrite",
+          "pre-auto-gc",
+          "pre-commit",
+          "pre-merge-commit",
+          "pre-push",
+          "pre-rebase",
+          "prepare-commit-msg",
+          "reference-transaction"
+        ]
+      }
+    ]
+
+    ----- stderr -----
+    "#);
+
+    cmd_snapshot!(context.filters(), context.list().arg("--manifest").arg(&*repo).arg("--rev").arg("not-a-rev"), @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: Command `git checkout` exited with an error:
+
+    [status]
+    exit status: 1
+
+    [stderr]
+    error: pathspec 'not-a-rev' did not match any file(s) known to git
+    ");
+
+    Ok(())
+}
+
+#[test]
+fn list_files() -> anyhow::Result<()> {
+    use assert_fs::prelude::*;
+
+    let context = TestContext::new();
+    context.init_project();
+
+    context.write_pre_commit_config(indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: python
+                name: python
+                entry: python3
+                language: system
+                types: [python]
+              - id: scripts
+                name: scripts
+                entry: python3
+                language: system
+                files: ^scripts/
+                exclude: \.txt$
+    "});
+    context.work_dir().child("main.py").write_str("print()\n")?;
+    context
+        .work_dir()
+        .child("scripts/build.py")
+        .write_str("print()\n")?;
+    context
+        .work_dir()
+        .child("scripts/notes.txt")
+        .write_str("notes\n")?;
+    context.git_add(".");
+    context.configure_git_author();
+    context.git_commit("Initial commit");
+
+    // Nothing is staged.
+    cmd_snapshot!(context.filters(), context.list().arg("--files"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    .:python   0 files
+    .:scripts  0 files
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.list().arg("--files").arg("--all-files").arg("--verbose"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    .:python
+      ID: python
+      Name: python
+      Language: system
+      Stages: all
+      Files: 2
+        main.py
+        scripts/build.py
+
+    .:scripts
+      ID: scripts
+      Name: scripts
+      Language: system
+      Stages: all
+      Files: 1
+        scripts/build.py
+
+
+    ----- stderr -----
+    ");
+
+    context
+        .work_dir()
+        .child("other.py")
+        .write_str("print()\n")?;
+    context.git_add(".");
+    context.git_commit("Add other.py");
+
+    cmd_snapshot!(context.filters(), context.list().arg("--files").arg("--from-ref").arg("HEAD~1").arg("--output-format=json"), @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    [
+      {
+        "id": "python",
+        "full_id": ".:python",
+        "name": "python",
+        "alias": "",
+        "language": "system",
+        "description": null,
+        "stages": [
+          "manual",
+          "commit-msg",
+          "post-checkout",
+          "post-commit",
+          "post-index-change",
+          "post-merge",
+          "post-rewrite",
+          "pre-auto-gc",
+          "pre-commit",
+          "pre-merge-commit",
+          "pre-push",
+          "pre-rebase",
+          "prepare-commit-msg",
+          "reference-transaction"
+        ],
+        "files": [
+          "other.py"
+        ]
+      },
+      {
+        "id": "scripts",
+        "full_id": ".:scripts",
+        "name": "scripts",
+        "alias": "",
+        "language": "system",
+        "description": null,
+        "stages": [
+          "manual",
+          "commit-msg",
+          "post-checkout",
+          "post-commit",
+          "post-index-change",
+          "post-merge",
+          "post-rewrite",
+          "pre-auto-gc",
+          "pre-commit",
+          "pre-merge-commit",
+          "pre-push",
+          "pre-rebase",
+          "prepare-commit-msg",
+          "reference-transaction"
+        ],
+        "files": []
+      }
+    ]
+
+    ----- stderr -----
+    "#);
+
+    cmd_snapshot!(context.filters(), context.list().arg("--all-files"), @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: the following required arguments were not provided:
+      --files
+
+    Usage: prek list --files --all-files [HOOK|PROJECT]...
+
+    For more information, try '--help'.
+    ");
+
+    Ok(())
+}
@@ -190,3 +190,102 @@ fn meta_hooks_workspace() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Meta hooks of a project also check the configurations of its nested projects, unless they run
+/// the meta hook themselves.
+#[test]
+fn meta_hooks_nested_projects() -> anyhow::Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: meta
+            hooks:
+              - id: check-hooks-apply
+              - id: check-useless-excludes
+    "});
+
+    let app = context.work_dir().child("app");
+    app.create_dir_all()?;
+    app.child(CONFIG_FILE).write_str(indoc::indoc! {r"
+        exclude:
+          glob: vendor/**
+        repos:
+          - repo: local
+            hooks:
+              - id: lint
+                name: lint
+                language: system
+                entry: python3 -c 'exit(0)'
+                exclude:
+                  glob: src/generated_*.py
+              - id: docs
+                name: docs
+                language: system
+                entry: python3 -c 'exit(0)'
+                files:
+                  glob: docs/**
+              - id: useful-exclude
+                name: useful exclude
+                language: system
+                entry: python3 -c 'exit(0)'
+                exclude:
+                  glob: src/*.py
+    "})?;
+    app.child("src/main.py").write_str("print()\n")?;
+
+    // Checks its own configuration.
+    let lib = context.work_dir().child("lib");
+    lib.create_dir_all()?;
+    lib.child(CONFIG_FILE).write_str(indoc::indoc! {r"
+        repos:
+          - repo: meta
+            hooks:
+              - id: check-useless-excludes
+          - repo: local
+            hooks:
+              - id: lint
+                name: lint
+                language: system
+                entry: python3 -c 'exit(0)'
+                exclude: ^nonexistent$
+    "})?;
+    lib.child("lib.py").write_str("print()\n")?;
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run().arg("--all-files"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    Running hooks for `app`:
+    lint.....................................................................Passed
+    docs.................................................(no files to check)Skipped
+    useful exclude...........................................................Passed
+
+    Running hooks for `lib`:
+    Check useless excludes...................................................Failed
+    - hook id: check-useless-excludes
+    - exit code: 1
+
+      The exclude pattern `regex: ^nonexistent$` for `lint` does not match any files
+    lint.....................................................................Passed
+
+    Running hooks for `.`:
+    Check hooks apply........................................................Failed
+    - hook id: check-hooks-apply
+    - exit code: 1
+
+      app: docs does not apply to this repository
+    Check useless excludes...................................................Failed
+    - hook id: check-useless-excludes
+    - exit code: 1
+
+      app: The global exclude pattern `glob: [vendor/**]` does not match any files
+      app: The exclude pattern `glob: [src/generated_*.py]` for `lint` does not match any files
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use assert_fs::fixture::{FileWriteStr, PathChild};
+
+use crate::common::{TestContext, cmd_snapshot};
+
+mod common;
+
+/// `extends` merges repos by `repo` and `rev`, hooks by `id`, and top-level keys.
+#[test]
+fn render_extends() -> anyhow::Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    let cwd = context.work_dir();
+    cwd.child("shared/base.yaml").write_str(indoc::indoc! {r"
+        default_stages: [pre-commit]
+        default_language_version:
+          python: python3.12
+          node: '22'
+        repos:
+          - repo: https://github.com/pre-commit/pre-commit-hooks
+            rev: v6.0.0
+            hooks:
+              - id: trailing-whitespace
+              - id: check-yaml
+                args: [--unsafe]
+          - repo: local
+            hooks:
+              - id: lint
+                name: lint
+                language: system
+                entry: echo lint
+    "})?;
+    context.write_pre_commit_config(indoc::indoc! {r"
+        extends: shared/base.yaml
+        default_language_version:
+          python: python3.13
+        repos:
+          - repo: https://github.com/pre-commit/pre-commit-hooks
+            rev: v6.0.0
+            hooks:
+              - id: check-yaml
+                args: []
+              - id: end-of-file-fixer
+          - repo: https://github.com/pre-commit/pre-commit-hooks
+            rev: v5.0.0
+            hooks:
+              - id: check-json
+          - repo: local
+            hooks:
+              - id: lint
+                entry: echo lint --strict
+    "});
+
+    cmd_snapshot!(context.filters(), context.command().arg("render"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    default_stages:
+    - pre-commit
+    default_language_version:
+      python: python3.13
+      node: '22'
+    repos:
+    - repo: https://github.com/pre-commit/pre-commit-hooks
+      rev: v6.0.0
+      hooks:
+      - id: trailing-whitespace
+      - id: check-yaml
+        args: []
+      - id: end-of-file-fixer
+    - repo: local
+      hooks:
+      - id: lint
+        name: lint
+        language: system
+        entry: echo lint --strict
+    - repo: https://github.com/pre-commit/pre-commit-hooks
+      rev: v5.0.0
+      hooks:
+      - id: check-json
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
+
+/// Hooks of extended configs are run, and cycles of `extends` are reported.
+#[test]
+fn run_extends() -> anyhow::Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    let cwd = context.work_dir();
+    cwd.child("shared/base.yaml").write_str(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: base
+                name: base
+                language: system
+                entry: echo base
+                pass_filenames: false
+    "})?;
+    context.write_pre_commit_config(indoc::indoc! {r"
+        extends: [shared/base.yaml]
+        repos:
+          - repo: local
+            hooks:
+              - id: own
+                name: own
+                language: system
+                entry: echo own
+                pass_filenames: false
+    "});
+    cwd.child("file.txt").write_str("content")?;
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    base.....................................................................Passed
+    own......................................................................Passed
+
+    ----- stderr -----
+    ");
+
+    cwd.child("shared/base.yaml").write_str(indoc::indoc! {r"
+        extends: ../.pre-commit-config.yaml
+        repos: []
+    "})?;
+
+    cmd_snapshot!(context.filters(), context.command().arg("render"), @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: Failed to load `shared/base.yaml`, extended by `.pre-commit-config.yaml`
+      caused by: Cyclic `extends`: `shared/base.yaml` extends `shared/../.pre-commit-config.yaml`, which already extends it
+    ");
+
+    Ok(())
+}
@@ -169,6 +169,10 @@ fn invalid_config() {
     ----- stderr -----
     error: Failed to parse `.pre-commit-config.yaml`
       caused by: missing field `repos`
+         --> .pre-commit-config.yaml:1:1
+          |
+        1 | invalid: config
+          | ^
     "#);
 
     context.write_pre_commit_config(indoc::indoc! {r#"
@@ -460,6 +464,82 @@ fn priorities_respected() {
     "#);
 }
 
+#[test]
+fn after_before_ordering() {
+    let context = TestContext::new();
+    context.init_project();
+
+    context.write_pre_commit_config(indoc::indoc! {r#"
+        repos:
+          - repo: local
+            hooks:
+              - id: lint
+                name: Lint
+                language: system
+                entry: python3 -c "print('lint')"
+                always_run: true
+                after: [format]
+              - id: format
+                name: Format
+                language: system
+                entry: python3 -c "print('format')"
+                always_run: true
+              - id: generate
+                name: Generate
+                language: system
+                entry: python3 -c "print('generate')"
+                always_run: true
+                before: [format]
+    "#});
+
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Generate.................................................................Passed
+    Format...................................................................Passed
+    Lint.....................................................................Passed
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn after_before_cycle() {
+    let context = TestContext::new();
+    context.init_project();
+
+    context.write_pre_commit_config(indoc::indoc! {r#"
+        repos:
+          - repo: local
+            hooks:
+              - id: lint
+                name: Lint
+                language: system
+                entry: python3 -c "print('lint')"
+                after: [format]
+              - id: format
+                name: Format
+                language: system
+                entry: python3 -c "print('format')"
+                after: [lint, missing]
+    "#});
+
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    warning: Hook `format` in `.pre-commit-config.yaml` is ordered relative to unknown hook `missing`
+    error: Hook ordering constraints in `.pre-commit-config.yaml` form a cycle: lint -> format -> lint
+    ");
+}
+
 #[test]
 fn priority_fail_fast_stops_later_groups() {
     let context = TestContext::new();
@@ -710,6 +790,7 @@ fn skips() {
     trailing-whitespace......................................................Failed
     - hook id: trailing-whitespace
     - exit code: 1
+    fix end of files.......................................(skipped by SKIP)Skipped
     check json...............................................................Failed
     - hook id: check-json
     - exit code: 1
@@ -721,6 +802,8 @@ fn skips() {
     success: false
     exit_code: 1
     ----- stdout -----
+    trailing-whitespace....................................(skipped by SKIP)Skipped
+    fix end of files.......................................(skipped by SKIP)Skipped
     check json...............................................................Failed
     - hook id: check-json
     - exit code: 1
@@ -729,6 +812,206 @@ fn skips() {
     ");
 }
 
+/// Skipped hooks are reported with the reason they were skipped.
+#[test]
+fn skip_reasons() {
+    let context = TestContext::new();
+    context.init_project();
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: runs
+                name: runs
+                language: system
+                entry: python3 -c 'pass'
+                always_run: true
+              - id: no-files
+                name: no-files
+                language: system
+                entry: python3 -c 'pass'
+                files: ^nothing$
+              - id: skipped
+                name: skipped
+                language: system
+                entry: python3 -c 'pass'
+                always_run: true
+              - id: push-only
+                name: push-only
+                language: system
+                entry: python3 -c 'pass'
+                always_run: true
+                stages: [pre-push]
+    "});
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run().env(EnvVars::SKIP, "skipped,push-only"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    runs.....................................................................Passed
+    no-files.............................................(no files to check)Skipped
+    skipped................................................(skipped by SKIP)Skipped
+
+    ----- stderr -----
+    ");
+
+    // Hooks of other stages are shown with `--verbose`.
+    cmd_snapshot!(context.filters(), context.run().arg("--skip").arg("skipped").arg("-v"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    runs.....................................................................Passed
+    - hook id: runs
+    - duration: [TIME]
+    no-files.............................................(no files to check)Skipped
+    skipped..................................................(skip selector)Skipped
+    push-only............................................(not in this stage)Skipped
+
+    ----- stderr -----
+    ");
+}
+
+/// Hooks in `default_skips` are skipped unless selected by id or run with `--no-default-skips`.
+#[test]
+fn default_skips() {
+    let context = TestContext::new();
+    context.init_project();
+    context.write_pre_commit_config(indoc::indoc! {r"
+        default_skips: [ci-*]
+        repos:
+          - repo: local
+            hooks:
+              - id: lint
+                name: lint
+                language: system
+                entry: python3 -c 'pass'
+                always_run: true
+              - id: ci-check
+                name: ci-check
+                language: system
+                entry: python3 -c 'pass'
+                always_run: true
+    "});
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    lint.....................................................................Passed
+    ci-check............................................(skipped by default)Skipped
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.run().arg("ci-check"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    ci-check.................................................................Passed
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.run().arg("--no-default-skips"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    lint.....................................................................Passed
+    ci-check.................................................................Passed
+
+    ----- stderr -----
+    ");
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        default_skips: [sub/]
+        repos: []
+    "});
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: Invalid `default_skips` in `.pre-commit-config.yaml`
+      caused by: Invalid selector: `sub/`
+      caused by: `default_skips` only select hooks of their own config
+    ");
+}
+
+/// `--stdin` runs the hooks on content from stdin as if it were the `--stdin-filename` file.
+#[test]
+fn run_stdin() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+    context.write_pre_commit_config(indoc::indoc! {r#"
+        repos:
+          - repo: local
+            hooks:
+              - id: fix-quotes
+                name: fix-quotes
+                language: system
+                entry: python3 fix.py
+                types: [python]
+              - id: no-todo
+                name: no-todo
+                language: system
+                entry: python3 -c 'import sys; sys.exit(any("TODO" in open(f).read() for f in sys.argv[1:]))'
+              - id: docs-only
+                name: docs-only
+                language: system
+                entry: python3 -c 'import sys; sys.exit(1)'
+                files: ^docs/
+    "#});
+    context
+        .work_dir()
+        .child("fix.py")
+        .write_str(indoc::indoc! {r#"
+        import sys
+
+        changed = False
+        for name in sys.argv[1:]:
+            with open(name) as f:
+                content = f.read()
+            fixed = content.replace("'", '"')
+            if fixed != content:
+                changed = True
+                with open(name, "w") as f:
+                    f.write(fixed)
+        sys.exit(1 if changed else 0)
+    "#})?;
+    context.work_dir().child("src").create_dir_all()?;
+    context.git_add(".");
+
+    // The fixer fails after modifying the content, which counts as fixed.
+    cmd_snapshot!(context.filters(), context.run().arg("--stdin").arg("--stdin-filename").arg("src/app.py").pass_stdin("print('hi')\n"), @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    print("hi")
+
+    ----- stderr -----
+    "#);
+
+    // A checker failing leaves the content untouched.
+    cmd_snapshot!(context.filters(), context.run().arg("--stdin").arg("--stdin-filename").arg("src/app.py").pass_stdin("# TODO\n"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    # TODO
+
+    ----- stderr -----
+    error: Hook `no-todo` failed with exit code 1
+    ");
+
+    // The temporary file is cleaned up.
+    assert_eq!(context.work_dir().child("src").read_dir()?.count(), 0);
+
+    Ok(())
+}
+
 /// Run hooks with matched `stage`.
 #[test]
 fn stage() {
@@ -972,6 +1255,84 @@ fn files_and_exclude() -> Result<()> {
     Ok(())
 }
 
+/// Repo level `files` and `exclude` apply to all hooks of the repo, on top of their own.
+#[test]
+fn repo_files_and_exclude() -> Result<()> {
+    let context = TestContext::new();
+
+    context.init_project();
+
+    let cwd = context.work_dir();
+    cwd.child("src/main.py").write_str("")?;
+    cwd.child("src/generated.py").write_str("")?;
+    cwd.child("src/lib.rs").write_str("")?;
+    cwd.child("tests/test_main.py").write_str("")?;
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            files: ^src/
+            exclude: generated
+            hooks:
+              - id: all
+                name: all
+                language: system
+                entry: python3 -c 'import sys; print(sys.argv[1:])'
+                verbose: true
+              - id: python
+                name: python
+                language: system
+                entry: python3 -c 'import sys; print(sys.argv[1:])'
+                files: \.py$
+                verbose: true
+              - id: no-main
+                name: no-main
+                language: system
+                entry: python3 -c 'import sys; print(sys.argv[1:])'
+                exclude: main
+                verbose: true
+          - repo: local
+            hooks:
+              - id: unrestricted
+                name: unrestricted
+                language: system
+                entry: python3 -c 'import sys; print(sys.argv[1:])'
+                files: \.py$
+                verbose: true
+    "});
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    all......................................................................Passed
+    - hook id: all
+    - duration: [TIME]
+
+      ['src/main.py', 'src/lib.rs']
+    python...................................................................Passed
+    - hook id: python
+    - duration: [TIME]
+
+      ['src/main.py']
+    no-main..................................................................Passed
+    - hook id: no-main
+    - duration: [TIME]
+
+      ['src/lib.rs']
+    unrestricted.............................................................Passed
+    - hook id: unrestricted
+    - duration: [TIME]
+
+      ['src/main.py', 'src/generated.py', 'tests/test_main.py']
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
+
 /// Test selecting files by type, `types`, `types_or`, and `exclude_types`.
 #[test]
 fn file_types() -> Result<()> {
@@ -1045,157 +1406,202 @@ fn file_types() -> Result<()> {
     Ok(())
 }
 
-/// Abort the run if a hook fails.
+/// With hyperlinks, failed file paths link to the files and a summary footer is shown.
 #[test]
-fn fail_fast() {
+fn hyperlinks_and_footer() -> Result<()> {
     let context = TestContext::new();
     context.init_project();
 
+    let cwd = context.work_dir();
+    cwd.child("a.txt").write_str("a\n")?;
+
     context.write_pre_commit_config(indoc::indoc! {r#"
         repos:
           - repo: local
             hooks:
-              - id: trailing-whitespace
-                name: trailing-whitespace
-                language: system
-                entry: python3 -c 'print("Fixing files"); exit(1)'
-                always_run: true
-                fail_fast: false
-              - id: trailing-whitespace
-                name: trailing-whitespace
+              - id: pass
+                name: pass
                 language: system
-                entry: python3 -c 'print("Fixing files"); exit(1)'
-                always_run: true
-                fail_fast: true
-              - id: trailing-whitespace
-                name: trailing-whitespace
+                entry: "true"
+              - id: fail
+                name: fail
                 language: system
-                entry: python3 -V
-                always_run: true
-              - id: trailing-whitespace
-                name: trailing-whitespace
+                entry: python3 -c 'import sys; print(sys.argv[1] + ":1:" + " bad"); sys.exit(1)'
+                files: \.txt$
+              - id: skip
+                name: skip
                 language: system
-                entry: python3 -V
-                always_run: true
+                entry: "true"
+                files: \.py$
     "#});
     context.git_add(".");
 
-    cmd_snapshot!(context.filters(), context.run(), @r"
+    let filters = [
+        (
+            r"\x1b\]8;;([^\x1b]*)\x1b\\(.*?)\x1b\]8;;\x1b\\",
+            "[LINK $1]$2[/LINK]",
+        ),
+        (r"\x1b\[[0-9;]*m", ""),
+    ]
+    .into_iter()
+    .chain(context.filters())
+    .collect::<Vec<_>>();
+
+    cmd_snapshot!(filters.clone(), context.run().env(EnvVars::PREK_HYPERLINKS, "1"), @r"
     success: false
     exit_code: 1
     ----- stdout -----
-    trailing-whitespace......................................................Failed
-    - hook id: trailing-whitespace
+    pass.....................................................................Passed
+    fail.....................................................................Failed
+    - hook id: fail
     - exit code: 1
 
-      Fixing files
-    trailing-whitespace......................................................Failed
-    - hook id: trailing-whitespace
-    - exit code: 1
+      a.txt:1: bad
+    skip.................................................(no files to check)Skipped
 
-      Fixing files
+    1 passed · 1 failed · 1 skipped · [TIME]
 
     ----- stderr -----
     ");
-}
-
-/// Test --fail-fast CLI flag stops execution after first failure.
-#[test]
-fn fail_fast_cli_flag() {
-    let context = TestContext::new();
-    context.init_project();
 
-    context.write_pre_commit_config(indoc::indoc! {r#"
+    // Links are only rendered with colors, as they are escape sequences too.
+    cmd_snapshot!(filters, context.run().env(EnvVars::PREK_HYPERLINKS, "1").arg("--color").arg("always"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    pass.....................................................................Passed
+    fail.....................................................................Failed
+    - hook id: fail
+    - exit code: 1
+
+      [LINK file://[TEMP_DIR]/a.txt]a.txt[/LINK]:1: bad
+    skip.................................................(no files to check)Skipped
+
+    1 passed · 1 failed · 1 skipped · [TIME]
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
+
+/// Skip files larger than `max_file_size`, from the config, a hook or `--max-file-size`.
+#[test]
+fn max_file_size() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    let cwd = context.work_dir();
+    cwd.child("small.txt").write_str("small\n")?;
+    cwd.child("large.txt").write_str(&"large\n".repeat(100))?;
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        max_file_size: 100
         repos:
           - repo: local
             hooks:
-              - id: failing-hook
-                name: failing-hook
+              - id: limited
+                name: limited
                 language: system
-                entry: python3 -c 'print("Failed"); exit(1)'
-                always_run: true
-              - id: passing-hook
-                name: passing-hook
+                entry: python3 -c 'import sys; print(sorted(sys.argv[1:]))'
+                files: \.txt$
+                verbose: true
+              - id: unlimited
+                name: unlimited
                 language: system
-                entry: python3 -c 'print("Passed")'
-                always_run: true
-    "#});
+                entry: python3 -c 'import sys; print(sorted(sys.argv[1:]))'
+                files: \.txt$
+                max_file_size: 0
+                verbose: true
+    "});
     context.git_add(".");
 
     cmd_snapshot!(context.filters(), context.run(), @r"
-    success: false
-    exit_code: 1
+    success: true
+    exit_code: 0
     ----- stdout -----
-    failing-hook.............................................................Failed
-    - hook id: failing-hook
-    - exit code: 1
+    limited..................................................................Passed
+    - hook id: limited
+    - duration: [TIME]
 
-      Failed
-    passing-hook.............................................................Passed
+      ['small.txt']
+    unlimited................................................................Passed
+    - hook id: unlimited
+    - duration: [TIME]
+
+      ['large.txt', 'small.txt']
 
     ----- stderr -----
+    warning: Skipped files larger than 100 bytes for hook `limited`: `large.txt`
+    Set `max_file_size` on the hook to change the limit for it.
     ");
 
-    cmd_snapshot!(context.filters(), context.run().arg("--fail-fast"), @r"
-    success: false
-    exit_code: 1
+    cmd_snapshot!(context.filters(), context.run().arg("--max-file-size").arg("1000"), @r"
+    success: true
+    exit_code: 0
     ----- stdout -----
-    failing-hook.............................................................Failed
-    - hook id: failing-hook
-    - exit code: 1
+    limited..................................................................Passed
+    - hook id: limited
+    - duration: [TIME]
 
-      Failed
+      ['large.txt', 'small.txt']
+    unlimited................................................................Passed
+    - hook id: unlimited
+    - duration: [TIME]
+
+      ['large.txt', 'small.txt']
 
     ----- stderr -----
     ");
+
+    Ok(())
 }
 
-/// Run from a subdirectory. File arguments should be fixed to be relative to the root.
+/// `binary_detection_bytes` sets how much of a file is read to classify it as text or binary.
 #[test]
-fn subdirectory() -> Result<()> {
+fn binary_detection_bytes() -> Result<()> {
     let context = TestContext::new();
     context.init_project();
 
-    let cwd = context.work_dir();
-    let child = cwd.child("foo/bar/baz");
-    child.create_dir_all()?;
-    child.child("file.txt").write_str("Hello, world!\n")?;
+    let mut contents = "a".repeat(2000);
+    contents.push('\0');
+    context.work_dir().child("data").write_str(&contents)?;
 
-    context.write_pre_commit_config(indoc::indoc! {r"
+    let config = indoc::indoc! {r"
         repos:
           - repo: local
             hooks:
-              - id: trailing-whitespace
-                name: trailing-whitespace
+              - id: binary
+                name: binary
                 language: system
-                entry: python3 -c 'import sys; print(sys.argv[1]); exit(1)'
-                always_run: true
-    "});
-
+                entry: python3 -c 'import sys; print(sys.argv[1:])'
+                types: [binary]
+                verbose: true
+    "};
+    context.write_pre_commit_config(config);
     context.git_add(".");
 
-    cmd_snapshot!(context.filters(), context.run().current_dir(&child).arg("--files").arg("file.txt"), @r"
-    success: false
-    exit_code: 1
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
     ----- stdout -----
-    trailing-whitespace......................................................Failed
-    - hook id: trailing-whitespace
-    - exit code: 1
-
-      foo/bar/baz/file.txt
+    binary...............................................(no files to check)Skipped
 
     ----- stderr -----
     ");
 
-    cmd_snapshot!(context.filters(), context.run().arg("--cd").arg(&*child).arg("--files").arg("file.txt"), @r"
-    success: false
-    exit_code: 1
+    context.write_pre_commit_config(&format!("binary_detection_bytes: 4096\n{config}"));
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
     ----- stdout -----
-    trailing-whitespace......................................................Failed
-    - hook id: trailing-whitespace
-    - exit code: 1
+    binary...................................................................Passed
+    - hook id: binary
+    - duration: [TIME]
 
-      foo/bar/baz/file.txt
+      ['data']
 
     ----- stderr -----
     ");
@@ -1203,9 +1609,9 @@ fn subdirectory() -> Result<()> {
     Ok(())
 }
 
-/// Test hook `log_file` option.
+/// Abort the run if a hook fails.
 #[test]
-fn log_file() {
+fn fail_fast() {
     let context = TestContext::new();
     context.init_project();
 
@@ -1218,11 +1624,27 @@ fn log_file() {
                 language: system
                 entry: python3 -c 'print("Fixing files"); exit(1)'
                 always_run: true
-                log_file: log.txt
+                fail_fast: false
+              - id: trailing-whitespace
+                name: trailing-whitespace
+                language: system
+                entry: python3 -c 'print("Fixing files"); exit(1)'
+                always_run: true
+                fail_fast: true
+              - id: trailing-whitespace
+                name: trailing-whitespace
+                language: system
+                entry: python3 -V
+                always_run: true
+              - id: trailing-whitespace
+                name: trailing-whitespace
+                language: system
+                entry: python3 -V
+                always_run: true
     "#});
     context.git_add(".");
 
-    cmd_snapshot!(context.filters(), context.run(), @r#"
+    cmd_snapshot!(context.filters(), context.run(), @r"
     success: false
     exit_code: 1
     ----- stdout -----
@@ -1230,195 +1652,302 @@ fn log_file() {
     - hook id: trailing-whitespace
     - exit code: 1
 
-    ----- stderr -----
-    "#);
+      Fixing files
+    trailing-whitespace......................................................Failed
+    - hook id: trailing-whitespace
+    - exit code: 1
 
-    let log = context.read("log.txt");
-    assert_eq!(log, "Fixing files");
+      Fixing files
+
+    ----- stderr -----
+    ");
 }
 
-/// Pass pre-commit environment variables to the hook.
+/// Test --fail-fast CLI flag stops execution after first failure.
 #[test]
-fn pass_env_vars() {
+fn fail_fast_cli_flag() {
     let context = TestContext::new();
-
     context.init_project();
 
     context.write_pre_commit_config(indoc::indoc! {r#"
         repos:
           - repo: local
             hooks:
-              - id: env-vars
-                name: Pass environment
+              - id: failing-hook
+                name: failing-hook
                 language: system
-                entry: python3 -c "import os, sys; print(os.getenv('PRE_COMMIT')); sys.exit(1)"
+                entry: python3 -c 'print("Failed"); exit(1)'
+                always_run: true
+              - id: passing-hook
+                name: passing-hook
+                language: system
+                entry: python3 -c 'print("Passed")'
                 always_run: true
     "#});
+    context.git_add(".");
 
     cmd_snapshot!(context.filters(), context.run(), @r"
     success: false
     exit_code: 1
     ----- stdout -----
-    Pass environment.........................................................Failed
-    - hook id: env-vars
+    failing-hook.............................................................Failed
+    - hook id: failing-hook
     - exit code: 1
 
-      1
+      Failed
+    passing-hook.............................................................Passed
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.run().arg("--fail-fast"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    failing-hook.............................................................Failed
+    - hook id: failing-hook
+    - exit code: 1
+
+      Failed
 
     ----- stderr -----
     ");
 }
 
+/// `fail_fast: group` cancels the running hooks of the same priority group, later groups still run.
 #[test]
-fn staged_files_only() -> Result<()> {
+fn fail_fast_group() {
     let context = TestContext::new();
     context.init_project();
+
     context.write_pre_commit_config(indoc::indoc! {r#"
         repos:
           - repo: local
             hooks:
-              - id: trailing-whitespace
-                name: trailing-whitespace
+              - id: failing-hook
+                name: failing-hook
                 language: system
-                entry: python3 -c 'print(open("file.txt", "rt").read())'
+                entry: python3 -c 'exit(1)'
+                always_run: true
+                priority: 0
+                fail_fast: group
+              - id: slow-hook
+                name: slow-hook
+                language: system
+                entry: python3 -c 'import time; time.sleep(5)'
+                always_run: true
+                priority: 0
+              - id: later-hook
+                name: later-hook
+                language: system
+                entry: python3 -c 'print("later ran")'
+                always_run: true
                 verbose: true
-                types: [text]
-   "#});
-
-    context
-        .work_dir()
-        .child("file.txt")
-        .write_str("Hello, world!")?;
+                priority: 1
+    "#});
     context.git_add(".");
 
-    // Non-staged files should be stashed and restored.
-    context
-        .work_dir()
-        .child("file.txt")
-        .write_str("Hello world again!")?;
-
-    let filters: Vec<_> = context
-        .filters()
-        .into_iter()
-        .chain([(r"/\d+-\d+.patch", "/[TIME]-[PID].patch")])
-        .collect();
-
-    cmd_snapshot!(filters, context.run(), @r"
-    success: true
-    exit_code: 0
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: false
+    exit_code: 1
     ----- stdout -----
-    trailing-whitespace......................................................Passed
-    - hook id: trailing-whitespace
+    failing-hook.............................................................Failed
+    - hook id: failing-hook
+    - exit code: 1
+    slow-hook.............................................................Cancelled
+    later-hook...............................................................Passed
+    - hook id: later-hook
     - duration: [TIME]
 
-      Hello, world!
+      later ran
 
     ----- stderr -----
-    Unstaged changes detected, stashing unstaged changes to `[HOME]/patches/[TIME]-[PID].patch`
-    Restored working tree changes from `[HOME]/patches/[TIME]-[PID].patch`
     ");
 
-    let content = context.read("file.txt");
-    assert_snapshot!(content, @"Hello world again!");
+    // The command line option takes precedence over the configuration.
+    cmd_snapshot!(context.filters(), context.run().arg("--fail-fast=project"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    failing-hook.............................................................Failed
+    - hook id: failing-hook
+    - exit code: 1
+    slow-hook................................................................Passed
 
-    Ok(())
+    ----- stderr -----
+    ");
 }
 
+/// The hooks cancelled by `fail_fast: group` are asked to terminate, like on an interrupt.
 #[cfg(unix)]
 #[test]
-fn restore_on_interrupt() -> Result<()> {
+fn fail_fast_group_terminates_hooks() -> Result<()> {
     let context = TestContext::new();
     context.init_project();
-    // The hook will sleep for 3 seconds.
-    context.write_pre_commit_config(indoc::indoc! {r#"
+    context.write_pre_commit_config(indoc::indoc! {r"
         repos:
           - repo: local
             hooks:
-              - id: trailing-whitespace
-                name: trailing-whitespace
+              - id: failing-hook
+                name: failing-hook
                 language: system
-                entry: python3 -c 'import time; open("out.txt", "wt").write(open("file.txt", "rt").read()); time.sleep(10)'
-                verbose: true
-                types: [text]
-   "#});
-
+                entry: python3 -c 'import time; time.sleep(1); exit(1)'
+                always_run: true
+                priority: 0
+                fail_fast: group
+              - id: slow-hook
+                name: slow-hook
+                language: system
+                entry: python3 hook.py
+                pass_filenames: false
+                always_run: true
+                priority: 0
+    "});
     context
         .work_dir()
-        .child("file.txt")
-        .write_str("Hello, world!")?;
-    context.git_add(".");
+        .child("hook.py")
+        .write_str(indoc::indoc! {r#"
+        import signal
+        import sys
+        import time
 
-    // Non-staged files should be stashed and restored.
+        def terminate(*_):
+            open("terminated.txt", "w").close()
+            sys.exit(1)
+
+        signal.signal(signal.SIGTERM, terminate)
+        time.sleep(60)
+    "#})?;
+    context.git_add(".");
+    // Both hooks need a batch slot to run at the same time.
     context
-        .work_dir()
-        .child("file.txt")
-        .write_str("Hello world again!")?;
+        .home_dir()
+        .child("config.toml")
+        .write_str("jobs = 2\n")?;
 
-    let mut child = context.run().spawn()?;
-    let child_id = child.id();
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    failing-hook.............................................................Failed
+    - hook id: failing-hook
+    - exit code: 1
+    slow-hook.............................................................Cancelled
 
-    // Send an interrupt signal to the process.
-    let handle = std::thread::spawn(move || {
-        std::thread::sleep(std::time::Duration::from_secs(1));
-        #[allow(clippy::cast_possible_wrap)]
-        unsafe {
-            libc::kill(child_id as i32, libc::SIGINT)
-        };
-    });
+    ----- stderr -----
+    ");
+    assert!(context.work_dir().child("terminated.txt").exists());
 
-    handle.join().unwrap();
-    child.wait()?;
+    Ok(())
+}
 
-    let content = context.read("out.txt");
-    assert_snapshot!(content, @"Hello, world!");
+/// Checkers of a later priority group start while the checkers of an earlier group are running.
+#[test]
+fn priority_checkers_start_ahead_of_their_group() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+    context.write_pre_commit_config(indoc::indoc! {r#"
+        repos:
+          - repo: local
+            hooks:
+              - id: slow-checker
+                name: slow-checker
+                language: system
+                entry: python3 -c 'import os, time; [time.sleep(0.1) for _ in range(100) if not os.path.exists("started.txt")]; exit(not os.path.exists("started.txt"))'
+                pass_filenames: false
+                always_run: true
+                kind: checker
+                priority: 0
+              - id: later-checker
+                name: later-checker
+                language: system
+                entry: python3 -c 'open("started.txt", "w").close()'
+                pass_filenames: false
+                always_run: true
+                kind: checker
+                priority: 1
+    "#});
+    context
+        .work_dir()
+        .child(".gitignore")
+        .write_str("*.txt\n")?;
+    context.git_add(".");
+    context
+        .home_dir()
+        .child("config.toml")
+        .write_str("jobs = 2\n")?;
 
-    let content = context.read("file.txt");
-    assert_snapshot!(content, @"Hello world again!");
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    slow-checker.............................................................Passed
+    later-checker............................................................Passed
+
+    ----- stderr -----
+    ");
 
     Ok(())
 }
 
-/// When in merge conflict, runs on files that have conflicts fixed.
+/// Hooks of a later priority group wait for the hooks of earlier groups that may modify files.
 #[test]
-fn merge_conflicts() -> Result<()> {
+fn priority_waits_for_hooks_that_may_modify_files() -> Result<()> {
     let context = TestContext::new();
     context.init_project();
-
-    // Create a merge conflict.
-    let cwd = context.work_dir();
-    cwd.child("file.txt").write_str("Hello, world!")?;
+    context.write_pre_commit_config(indoc::indoc! {r#"
+        repos:
+          - repo: local
+            hooks:
+              - id: slow-hook
+                name: slow-hook
+                language: system
+                entry: python3 -c 'import time; time.sleep(1); open("done.txt", "w").close()'
+                pass_filenames: false
+                always_run: true
+                priority: 0
+              - id: later-checker
+                name: later-checker
+                language: system
+                entry: python3 -c 'import os; exit(not os.path.exists("done.txt"))'
+                pass_filenames: false
+                always_run: true
+                kind: checker
+                priority: 1
+    "#});
+    context
+        .work_dir()
+        .child(".gitignore")
+        .write_str("*.txt\n")?;
     context.git_add(".");
-    context.configure_git_author();
-    context.git_commit("Initial commit");
+    context
+        .home_dir()
+        .child("config.toml")
+        .write_str("jobs = 2\n")?;
 
-    Command::new("git")
-        .arg("checkout")
-        .arg("-b")
-        .arg("feature")
-        .current_dir(cwd)
-        .assert()
-        .success();
-    cwd.child("file.txt").write_str("Hello, world again!")?;
-    context.git_add(".");
-    context.git_commit("Feature commit");
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    slow-hook................................................................Passed
+    later-checker............................................................Passed
 
-    Command::new("git")
-        .arg("checkout")
-        .arg("master")
-        .current_dir(cwd)
-        .assert()
-        .success();
-    cwd.child("file.txt")
-        .write_str("Hello, world from master!")?;
-    context.git_add(".");
-    context.git_commit("Master commit");
+    ----- stderr -----
+    ");
 
-    Command::new("git")
-        .arg("merge")
-        .arg("feature")
-        .current_dir(cwd)
-        .assert()
-        .code(1);
+    Ok(())
+}
+
+/// Run from a subdirectory. File arguments should be fixed to be relative to the root.
+#[test]
+fn subdirectory() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    let cwd = context.work_dir();
+    let child = cwd.child("foo/bar/baz");
+    child.create_dir_all()?;
+    child.child("file.txt").write_str("Hello, world!\n")?;
 
     context.write_pre_commit_config(indoc::indoc! {r"
         repos:
@@ -1427,31 +1956,34 @@ fn merge_conflicts() -> Result<()> {
               - id: trailing-whitespace
                 name: trailing-whitespace
                 language: system
-                entry: python3 -c 'import sys; print(sorted(sys.argv[1:]))'
-                verbose: true
+                entry: python3 -c 'import sys; print(sys.argv[1]); exit(1)'
+                always_run: true
     "});
 
-    // Abort on merge conflicts.
-    cmd_snapshot!(context.filters(), context.run(), @r#"
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run().current_dir(&child).arg("--files").arg("file.txt"), @r"
     success: false
-    exit_code: 2
+    exit_code: 1
     ----- stdout -----
+    trailing-whitespace......................................................Failed
+    - hook id: trailing-whitespace
+    - exit code: 1
+
+      foo/bar/baz/file.txt
 
     ----- stderr -----
-    error: You have unmerged paths. Resolve them before running prek
-    "#);
+    ");
 
-    // Fix the conflict and run again.
-    context.git_add(".");
-    cmd_snapshot!(context.filters(), context.run(), @r"
-    success: true
-    exit_code: 0
+    cmd_snapshot!(context.filters(), context.run().arg("--cd").arg(&*child).arg("--files").arg("file.txt"), @r"
+    success: false
+    exit_code: 1
     ----- stdout -----
-    trailing-whitespace......................................................Passed
+    trailing-whitespace......................................................Failed
     - hook id: trailing-whitespace
-    - duration: [TIME]
+    - exit code: 1
 
-      ['.pre-commit-config.yaml', 'file.txt']
+      foo/bar/baz/file.txt
 
     ----- stderr -----
     ");
@@ -1459,9 +1991,9 @@ fn merge_conflicts() -> Result<()> {
     Ok(())
 }
 
-/// Local python hook with no additional dependencies.
+/// Test hook `log_file` option.
 #[test]
-fn local_python_hook() {
+fn log_file() {
     let context = TestContext::new();
     context.init_project();
 
@@ -1469,581 +2001,650 @@ fn local_python_hook() {
         repos:
           - repo: local
             hooks:
-              - id: local-python-hook
-                name: local-python-hook
-                language: python
-                entry: python3 -c 'import sys; print("Hello, world!"); sys.exit(1)'
+              - id: trailing-whitespace
+                name: trailing-whitespace
+                language: system
+                entry: python3 -c 'print("Fixing files"); exit(1)'
+                always_run: true
+                log_file: log.txt
     "#});
-
     context.git_add(".");
 
-    cmd_snapshot!(context.filters(), context.run(), @r"
+    cmd_snapshot!(context.filters(), context.run(), @r#"
     success: false
     exit_code: 1
     ----- stdout -----
-    local-python-hook........................................................Failed
-    - hook id: local-python-hook
+    trailing-whitespace......................................................Failed
+    - hook id: trailing-whitespace
     - exit code: 1
 
-      Hello, world!
-
     ----- stderr -----
-    ");
+    "#);
+
+    let log = context.read("log.txt");
+    assert_eq!(log, "Fixing files");
 }
 
-/// Invalid `entry`
+/// Pass pre-commit environment variables to the hook.
 #[test]
-fn invalid_entry() {
+fn pass_env_vars() {
     let context = TestContext::new();
+
     context.init_project();
 
     context.write_pre_commit_config(indoc::indoc! {r#"
         repos:
           - repo: local
             hooks:
-              - id: entry
-                name: entry
-                language: python
-                entry: '"'
+              - id: env-vars
+                name: Pass environment
+                language: system
+                entry: python3 -c "import os, sys; print(os.getenv('PRE_COMMIT')); sys.exit(1)"
+                always_run: true
     "#});
 
-    context.git_add(".");
-
-    cmd_snapshot!(context.filters(), context.run(), @r#"
+    cmd_snapshot!(context.filters(), context.run(), @r"
     success: false
-    exit_code: 2
+    exit_code: 1
     ----- stdout -----
+    Pass environment.........................................................Failed
+    - hook id: env-vars
+    - exit code: 1
+
+      1
 
     ----- stderr -----
-    error: Failed to run hook `entry`
-      caused by: Invalid hook `entry`
-      caused by: Failed to parse entry `"` as commands
-    "#);
+    ");
 }
 
-/// Initialize a repo that does not exist.
 #[test]
-fn init_nonexistent_repo() {
+fn staged_files_only() -> Result<()> {
     let context = TestContext::new();
     context.init_project();
-    context.write_pre_commit_config(indoc::indoc! {r"
+    context.write_pre_commit_config(indoc::indoc! {r#"
         repos:
-          - repo: https://notexistentatallnevergonnahappen.com/nonexistent/repo
-            rev: v1.0.0
+          - repo: local
             hooks:
-              - id: nonexistent
-                name: nonexistent
-        "});
+              - id: trailing-whitespace
+                name: trailing-whitespace
+                language: system
+                entry: python3 -c 'print(open("file.txt", "rt").read())'
+                verbose: true
+                types: [text]
+   "#});
+
+    context
+        .work_dir()
+        .child("file.txt")
+        .write_str("Hello, world!")?;
     context.git_add(".");
 
-    let filters = context
-        .filters()
-        .into_iter()
-        .chain([(r"exit code: ", "exit status: "),
-            // Normalize Git error message to handle environment-specific variations
-            (
-                r"fatal: unable to access 'https://notexistentatallnevergonnahappen\.com/nonexistent/repo/':.*",
-                r"fatal: unable to access 'https://notexistentatallnevergonnahappen.com/nonexistent/repo/': [error]"
-            ),
-        ])
-        .collect::<Vec<_>>();
-
-    cmd_snapshot!(filters, context.run(), @r"
-    success: false
-    exit_code: 2
-    ----- stdout -----
-
-    ----- stderr -----
-    error: Failed to init hooks
-      caused by: Failed to initialize repo `https://notexistentatallnevergonnahappen.com/nonexistent/repo`
-      caused by: Command `git full clone` exited with an error:
-
-    [status]
-    exit status: 128
-
-    [stderr]
-    fatal: unable to access 'https://notexistentatallnevergonnahappen.com/nonexistent/repo/': [error]
-    ");
-}
-
-/// Test hooks that specifies `types: [directory]`.
-#[test]
-fn types_directory() -> Result<()> {
-    let context = TestContext::new();
-    context.init_project();
-    context.write_pre_commit_config(indoc::indoc! {r"
-        repos:
-          - repo: local
-            hooks:
-              - id: directory
-                name: directory
-                language: system
-                entry: echo
-                types: [directory]
-        "});
-    context.work_dir().child("dir").create_dir_all()?;
+    // Non-staged files should be stashed and restored.
     context
         .work_dir()
-        .child("dir/file.txt")
-        .write_str("Hello, world!")?;
-    context.git_add(".");
-
-    cmd_snapshot!(context.filters(), context.run(), @r#"
-    success: true
-    exit_code: 0
-    ----- stdout -----
-    directory............................................(no files to check)Skipped
+        .child("file.txt")
+        .write_str("Hello world again!")?;
 
-    ----- stderr -----
-    "#);
+    let filters: Vec<_> = context
+        .filters()
+        .into_iter()
+        .chain([(r"/\d+-\d+.patch", "/[TIME]-[PID].patch")])
+        .collect();
 
-    cmd_snapshot!(context.filters(), context.run().arg("--files").arg("dir"), @r#"
+    cmd_snapshot!(filters, context.run(), @r"
     success: true
     exit_code: 0
     ----- stdout -----
-    directory................................................................Passed
-
-    ----- stderr -----
-    "#);
+    trailing-whitespace......................................................Passed
+    - hook id: trailing-whitespace
+    - duration: [TIME]
 
-    cmd_snapshot!(context.filters(), context.run().arg("--all-files"), @r#"
-    success: true
-    exit_code: 0
-    ----- stdout -----
-    directory............................................(no files to check)Skipped
+      Hello, world!
 
     ----- stderr -----
-    "#);
+    Unstaged changes detected, stashing unstaged changes to `[HOME]/patches/[TIME]-[PID].patch`
+    Restored working tree changes from `[HOME]/patches/[TIME]-[PID].patch`
+    ");
 
-    cmd_snapshot!(context.filters(), context.run().arg("--files").arg("non-exist-files"), @r"
-    success: true
-    exit_code: 0
-    ----- stdout -----
-    directory............................................(no files to check)Skipped
+    let content = context.read("file.txt");
+    assert_snapshot!(content, @"Hello world again!");
 
-    ----- stderr -----
-    warning: This file does not exist and will be ignored: `non-exist-files`
-    ");
     Ok(())
 }
 
+#[cfg(unix)]
 #[test]
-fn run_last_commit() -> Result<()> {
+fn restore_on_interrupt() -> Result<()> {
     let context = TestContext::new();
     context.init_project();
-    context.configure_git_author();
-
-    let cwd = context.work_dir();
-    context.write_pre_commit_config(indoc::indoc! {r"
+    // The hook will sleep for 3 seconds.
+    context.write_pre_commit_config(indoc::indoc! {r#"
         repos:
-          - repo: https://github.com/pre-commit/pre-commit-hooks
-            rev: v5.0.0
+          - repo: local
             hooks:
               - id: trailing-whitespace
-              - id: end-of-file-fixer
-    "});
-
-    // Create initial files and make first commit
-    cwd.child("file1.txt").write_str("Hello, world!\n")?;
-    cwd.child("file2.txt")
-        .write_str("Initial content with trailing spaces   \n")?; // This has issues but won't be in last commit
-    context.git_add(".");
-    context.git_commit("Initial commit");
+                name: trailing-whitespace
+                language: system
+                entry: python3 -c 'import time; open("out.txt", "wt").write(open("file.txt", "rt").read()); time.sleep(10)'
+                verbose: true
+                types: [text]
+   "#});
 
-    // Modify files and make second commit with trailing whitespace
-    cwd.child("file1.txt").write_str("Hello, world!   \n")?; // trailing whitespace
-    cwd.child("file3.txt").write_str("New file")?; // missing newline
-    // Note: file2.txt is NOT modified in this commit, so it should be filtered out by --last-commit
+    context
+        .work_dir()
+        .child("file.txt")
+        .write_str("Hello, world!")?;
     context.git_add(".");
-    context.git_commit("Second commit with issues");
-
-    // Run with --last-commit should only check files from the last commit
-    // This should only process file1.txt and file3.txt, NOT file2.txt
-    cmd_snapshot!(context.filters(), context.run().arg("--last-commit"), @r"
-    success: false
-    exit_code: 1
-    ----- stdout -----
-    trim trailing whitespace.................................................Failed
-    - hook id: trailing-whitespace
-    - exit code: 1
-    - files were modified by this hook
-
-      Fixing file1.txt
-    fix end of files.........................................................Failed
-    - hook id: end-of-file-fixer
-    - exit code: 1
-    - files were modified by this hook
-
-      Fixing file3.txt
 
-    ----- stderr -----
-    ");
+    // Non-staged files should be stashed and restored.
+    context
+        .work_dir()
+        .child("file.txt")
+        .write_str("Hello world again!")?;
 
-    // Now reset the files to their problematic state for comparison
-    cwd.child("file1.txt").write_str("Hello, world!   \n")?; // trailing whitespace
-    cwd.child("file3.txt").write_str("New file")?; // missing newline
+    let mut child = context.run().spawn()?;
+    let child_id = child.id();
 
-    // Run with --all-files should check ALL files including file2.txt
-    // This demonstrates that file2.txt was indeed filtered out in the previous test
-    cmd_snapshot!(context.filters(), context.run().arg("--all-files"), @r"
-    success: false
-    exit_code: 1
-    ----- stdout -----
-    trim trailing whitespace.................................................Failed
-    - hook id: trailing-whitespace
-    - exit code: 1
-    - files were modified by this hook
+    // Send an interrupt signal to the process.
+    let handle = std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_secs(1));
+        #[allow(clippy::cast_possible_wrap)]
+        unsafe {
+            libc::kill(child_id as i32, libc::SIGINT)
+        };
+    });
 
-      Fixing file1.txt
-      Fixing file2.txt
-    fix end of files.........................................................Failed
-    - hook id: end-of-file-fixer
-    - exit code: 1
-    - files were modified by this hook
+    handle.join().unwrap();
+    child.wait()?;
 
-      Fixing file3.txt
+    let content = context.read("out.txt");
+    assert_snapshot!(content, @"Hello, world!");
 
-    ----- stderr -----
-    ");
+    let content = context.read("file.txt");
+    assert_snapshot!(content, @"Hello world again!");
 
     Ok(())
 }
 
-/// Test `prek run --files` with multiple files.
+/// On interrupt, running hooks are terminated, remaining hooks are skipped and a partial report is printed.
+#[cfg(unix)]
 #[test]
-fn run_multiple_files() -> Result<()> {
+fn graceful_shutdown_on_sigterm() -> Result<()> {
     let context = TestContext::new();
     context.init_project();
     context.write_pre_commit_config(indoc::indoc! {r"
         repos:
           - repo: local
             hooks:
-              - id: multiple-files
-                name: multiple-files
+              - id: quick
+                name: quick
                 language: system
                 entry: echo
-                verbose: true
-                types: [text]
-    "});
-    let cwd = context.work_dir();
-    cwd.child("file1.txt").write_str("Hello, world!")?;
-    cwd.child("file2.txt").write_str("Hello, world!")?;
+              - id: slow
+                name: slow
+                language: system
+                entry: python3 -c 'import time; time.sleep(60)'
+              - id: never
+                name: never
+                language: system
+                entry: echo
+   "});
     context.git_add(".");
-    // `--files` with multiple files
-    cmd_snapshot!(context.filters(), context.run().arg("--files").arg("file1.txt").arg("file2.txt"), @r"
-    success: true
-    exit_code: 0
-    ----- stdout -----
-    multiple-files...........................................................Passed
-    - hook id: multiple-files
-    - duration: [TIME]
 
-      file2.txt file1.txt
+    let start = std::time::Instant::now();
+    let child = context
+        .run()
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()?;
+    let child_id = child.id();
 
-    ----- stderr -----
+    std::thread::sleep(std::time::Duration::from_secs(2));
+    #[allow(clippy::cast_possible_wrap)]
+    unsafe {
+        libc::kill(child_id as i32, libc::SIGTERM)
+    };
+
+    let output = child.wait_with_output()?;
+    assert!(start.elapsed() < std::time::Duration::from_secs(30));
+    assert_eq!(output.status.code(), Some(130));
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_snapshot!(stdout, @r"
+    quick....................................................................Passed
+    slow..................................................................Cancelled
     ");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Interrupted, remaining hooks were not run"));
+
     Ok(())
 }
 
-/// Test `prek run --files` with no files.
+/// On interrupt, the processes spawned by running hooks are terminated too, and killed if they
+/// ignore the termination request.
+#[cfg(unix)]
 #[test]
-fn run_no_files() {
+fn graceful_shutdown_kills_hook_process_tree() -> Result<()> {
     let context = TestContext::new();
     context.init_project();
     context.write_pre_commit_config(indoc::indoc! {r"
         repos:
           - repo: local
             hooks:
-              - id: no-files
-                name: no-files
+              - id: spawner
+                name: spawner
                 language: system
-                entry: echo
-                verbose: true
-    "});
+                entry: python3 hook.py
+                pass_filenames: false
+                always_run: true
+   "});
+    context
+        .work_dir()
+        .child("hook.py")
+        .write_str(indoc::indoc! {r#"
+        import subprocess
+        import sys
+        import time
+
+        subprocess.Popen([sys.executable, "-c", """
+        import signal, time
+        signal.signal(signal.SIGTERM, signal.SIG_IGN)
+        time.sleep(6)
+        open("leaked.txt", "w").close()
+        """])
+        time.sleep(60)
+    "#})?;
     context.git_add(".");
-    // `--files` with no files
-    cmd_snapshot!(context.filters(), context.run().arg("--files"), @r"
-    success: true
-    exit_code: 0
-    ----- stdout -----
-    no-files.................................................................Passed
-    - hook id: no-files
-    - duration: [TIME]
 
-      .pre-commit-config.yaml
+    let start = std::time::Instant::now();
+    let child = context
+        .run()
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()?;
+    let child_id = child.id();
 
-    ----- stderr -----
-    ");
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    #[allow(clippy::cast_possible_wrap)]
+    unsafe {
+        libc::kill(child_id as i32, libc::SIGTERM)
+    };
+
+    let output = child.wait_with_output()?;
+    assert_eq!(output.status.code(), Some(130));
+
+    // Give a leaked process the time to write its file.
+    std::thread::sleep(std::time::Duration::from_secs(8).saturating_sub(start.elapsed()));
+    assert!(!context.work_dir().child("leaked.txt").exists());
+
+    Ok(())
 }
 
-/// Test `prek run --directory` flags.
+/// When in merge conflict, runs on files that have conflicts fixed.
 #[test]
-fn run_directory() -> Result<()> {
+fn merge_conflicts() -> Result<()> {
     let context = TestContext::new();
     context.init_project();
-    context.write_pre_commit_config(indoc::indoc! {r"
-        repos:
-          - repo: local
-            hooks:
-              - id: directory
-                name: directory
-                language: system
-                entry: echo
-                verbose: true
-    "});
 
+    // Create a merge conflict.
     let cwd = context.work_dir();
-    cwd.child("dir1").create_dir_all()?;
-    cwd.child("dir1/file.txt").write_str("Hello, world!")?;
-    cwd.child("dir2").create_dir_all()?;
-    cwd.child("dir2/file.txt").write_str("Hello, world!")?;
+    cwd.child("file.txt").write_str("Hello, world!")?;
     context.git_add(".");
+    context.configure_git_author();
+    context.git_commit("Initial commit");
 
-    // one `--directory`
-    cmd_snapshot!(context.filters(), context.run().arg("--directory").arg("dir1"), @r"
-    success: true
-    exit_code: 0
-    ----- stdout -----
-    directory................................................................Passed
-    - hook id: directory
-    - duration: [TIME]
+    Command::new("git")
+        .arg("checkout")
+        .arg("-b")
+        .arg("feature")
+        .current_dir(cwd)
+        .assert()
+        .success();
+    cwd.child("file.txt").write_str("Hello, world again!")?;
+    context.git_add(".");
+    context.git_commit("Feature commit");
 
-      dir1/file.txt
+    Command::new("git")
+        .arg("checkout")
+        .arg("master")
+        .current_dir(cwd)
+        .assert()
+        .success();
+    cwd.child("file.txt")
+        .write_str("Hello, world from master!")?;
+    context.git_add(".");
+    context.git_commit("Master commit");
 
-    ----- stderr -----
-    ");
+    Command::new("git")
+        .arg("merge")
+        .arg("feature")
+        .current_dir(cwd)
+        .assert()
+        .code(1);
 
-    // repeated `--directory`
-    cmd_snapshot!(context.filters(), context.run().arg("--directory").arg("dir1").arg("--directory").arg("dir1"), @r"
-    success: true
-    exit_code: 0
-    ----- stdout -----
-    directory................................................................Passed
-    - hook id: directory
-    - duration: [TIME]
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: trailing-whitespace
+                name: trailing-whitespace
+                language: system
+                entry: python3 -c 'import sys; print(sorted(sys.argv[1:]))'
+                verbose: true
+    "});
 
-      dir1/file.txt
+    // Abort on merge conflicts.
+    cmd_snapshot!(context.filters(), context.run(), @r#"
+    success: false
+    exit_code: 2
+    ----- stdout -----
 
     ----- stderr -----
-    ");
+    error: You have unmerged paths. Resolve them before running prek
+    "#);
 
-    // multiple `--directory`
-    cmd_snapshot!(context.filters(), context.run().arg("--directory").arg("dir1").arg("--directory").arg("dir2"), @r"
+    // Fix the conflict and run again.
+    context.git_add(".");
+    cmd_snapshot!(context.filters(), context.run(), @r"
     success: true
     exit_code: 0
     ----- stdout -----
-    directory................................................................Passed
-    - hook id: directory
+    trailing-whitespace......................................................Passed
+    - hook id: trailing-whitespace
     - duration: [TIME]
 
-      dir2/file.txt dir1/file.txt
+      ['.pre-commit-config.yaml', 'file.txt']
 
     ----- stderr -----
     ");
 
-    // non-existing directory
-    cmd_snapshot!(context.filters(), context.run().arg("--directory").arg("non-existing-dir"), @r"
-    success: true
-    exit_code: 0
-    ----- stdout -----
-    directory............................................(no files to check)Skipped
+    Ok(())
+}
 
-    ----- stderr -----
-    ");
+/// Local python hook with no additional dependencies.
+#[test]
+fn local_python_hook() {
+    let context = TestContext::new();
+    context.init_project();
 
-    // `--directory` with `--files`
-    cmd_snapshot!(context.filters(), context.run().arg("--directory").arg("dir1").arg("--files").arg("dir1/file.txt"), @r"
-    success: true
-    exit_code: 0
-    ----- stdout -----
-    directory................................................................Passed
-    - hook id: directory
-    - duration: [TIME]
+    context.write_pre_commit_config(indoc::indoc! {r#"
+        repos:
+          - repo: local
+            hooks:
+              - id: local-python-hook
+                name: local-python-hook
+                language: python
+                entry: python3 -c 'import sys; print("Hello, world!"); sys.exit(1)'
+    "#});
 
-      dir1/file.txt
+    context.git_add(".");
 
-    ----- stderr -----
-    ");
-    cmd_snapshot!(context.filters(), context.run().arg("--directory").arg("dir1").arg("--files").arg("dir2/file.txt"), @r"
-    success: true
-    exit_code: 0
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: false
+    exit_code: 1
     ----- stdout -----
-    directory................................................................Passed
-    - hook id: directory
-    - duration: [TIME]
+    local-python-hook........................................................Failed
+    - hook id: local-python-hook
+    - exit code: 1
 
-      dir2/file.txt dir1/file.txt
+      Hello, world!
 
     ----- stderr -----
     ");
+}
 
-    // run `--directory` inside a subdirectory
-    cmd_snapshot!(context.filters(), context.run().current_dir(cwd.join("dir1")).arg("--directory").arg("."), @r"
-    success: true
-    exit_code: 0
-    ----- stdout -----
-    directory................................................................Passed
-    - hook id: directory
-    - duration: [TIME]
+/// Invalid `entry`
+#[test]
+fn invalid_entry() {
+    let context = TestContext::new();
+    context.init_project();
 
-      dir1/file.txt
+    context.write_pre_commit_config(indoc::indoc! {r#"
+        repos:
+          - repo: local
+            hooks:
+              - id: entry
+                name: entry
+                language: python
+                entry: '"'
+    "#});
 
-    ----- stderr -----
-    ");
+    context.git_add(".");
 
-    cmd_snapshot!(context.filters(), context.run().arg("--cd").arg("dir1").arg("--directory").arg("."), @r"
-    success: true
-    exit_code: 0
+    cmd_snapshot!(context.filters(), context.run(), @r#"
+    success: false
+    exit_code: 2
     ----- stdout -----
-    directory................................................................Passed
-    - hook id: directory
-    - duration: [TIME]
-
-      dir1/file.txt
 
     ----- stderr -----
-    ");
-
-    Ok(())
+    error: Failed to run hook `entry`
+      caused by: Invalid hook `entry`
+      caused by: Failed to parse entry `"` as commands
+    "#);
 }
 
-/// Test `minimum_prek_version` option.
+/// Initialize a repo that does not exist.
 #[test]
-fn minimum_prek_version() {
+fn init_nonexistent_repo() {
     let context = TestContext::new();
     context.init_project();
     context.write_pre_commit_config(indoc::indoc! {r"
-        minimum_prek_version: 10.0.0
         repos:
-          - repo: local
+          - repo: https://notexistentatallnevergonnahappen.com/nonexistent/repo
+            rev: v1.0.0
             hooks:
-              - id: directory
-                name: directory
-                language: system
-                entry: echo
-                verbose: true
-    "});
+              - id: nonexistent
+                name: nonexistent
+        "});
     context.git_add(".");
 
     let filters = context
         .filters()
         .into_iter()
-        .chain([(
-            r"current version `\d+\.\d+\.\d+(?:-[0-9A-Za-z]+(?:\.[0-9A-Za-z]+)*)?`",
-            "current version `[CURRENT_VERSION]`",
-        )])
+        .chain([(r"exit code: ", "exit status: "),
+            // Normalize Git error message to handle environment-specific variations
+            (
+                r"fatal: unable to access 'https://notexistentatallnevergonnahappen\.com/nonexistent/repo/':.*",
+                r"fatal: unable to access 'https://notexistentatallnevergonnahappen.com/nonexistent/repo/': [error]"
+            ),
+        ])
         .collect::<Vec<_>>();
 
-    cmd_snapshot!(filters, context.run(), @r#"
+    cmd_snapshot!(filters, context.run(), @r"
     success: false
     exit_code: 2
     ----- stdout -----
 
     ----- stderr -----
-    error: Failed to parse `.pre-commit-config.yaml`
-      caused by: Required minimum prek version `10.0.0` is greater than current version `[CURRENT_VERSION]`. Please consider updating prek.
-    "#);
+    `git fetch` failed, retrying in [TIME] (attempt 2 of 4)
+    `git fetch` failed, retrying in [TIME] (attempt 3 of 4)
+    `git fetch` failed, retrying in [TIME] (attempt 4 of 4)
+    error: Failed to init hooks
+      caused by: Failed to initialize repo `https://notexistentatallnevergonnahappen.com/nonexistent/repo`
+      caused by: Command `git fetch` exited with an error:
+
+    [status]
+    exit status: 128
+
+    [stderr]
+    fatal: unable to access 'https://notexistentatallnevergonnahappen.com/nonexistent/repo/': [error]
+    ");
 }
 
-/// Run hooks that would echo color.
+/// With `--offline`, an uncached repo fails immediately instead of being cloned.
 #[test]
-#[cfg(not(windows))]
-fn color() -> Result<()> {
+fn offline_uncached_repo() {
     let context = TestContext::new();
     context.init_project();
     context.write_pre_commit_config(indoc::indoc! {r"
-      repos:
-        - repo: local
-          hooks:
-            - id: color
-              name: color
-              language: python
-              entry: python ./color.py
-              verbose: true
-              pass_filenames: false
-  "});
-
-    let script = indoc::indoc! {r"
-      import sys
-      if sys.stdout.isatty():
-          print('\033[1;32mHello, world!\033[0m')
-      else:
-          print('Hello, world!')
-  "};
-    context.work_dir().child("color.py").write_str(script)?;
-
+        repos:
+          - repo: https://notexistentatallnevergonnahappen.com/nonexistent/repo
+            rev: v1.0.0
+            hooks:
+              - id: nonexistent
+                name: nonexistent
+        "});
     context.git_add(".");
 
-    // Run default. In integration tests, we don't have a TTY.
-    // So this prints without color.
-    cmd_snapshot!(context.filters(), context.run(), @r"
+    cmd_snapshot!(context.filters(), context.run().arg("--offline"), @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: Failed to init hooks
+      caused by: Failed to initialize repo `https://notexistentatallnevergonnahappen.com/nonexistent/repo`
+      caused by: Repository `https://notexistentatallnevergonnahappen.com/nonexistent/repo@v1.0.0` is not cached, and network access is disabled by `--offline`
+    ");
+
+    cmd_snapshot!(context.filters(), context.run().env(EnvVars::PREK_OFFLINE, "1"), @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: Failed to init hooks
+      caused by: Failed to initialize repo `https://notexistentatallnevergonnahappen.com/nonexistent/repo`
+      caused by: Repository `https://notexistentatallnevergonnahappen.com/nonexistent/repo@v1.0.0` is not cached, and network access is disabled by `--offline`
+    ");
+}
+
+/// Test hooks that specifies `types: [directory]`.
+#[test]
+fn types_directory() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: directory
+                name: directory
+                language: system
+                entry: echo
+                types: [directory]
+        "});
+    context.work_dir().child("dir").create_dir_all()?;
+    context
+        .work_dir()
+        .child("dir/file.txt")
+        .write_str("Hello, world!")?;
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r#"
     success: true
     exit_code: 0
     ----- stdout -----
-    color....................................................................Passed
-    - hook id: color
-    - duration: [TIME]
+    directory............................................(no files to check)Skipped
 
-      Hello, world!
+    ----- stderr -----
+    "#);
+
+    cmd_snapshot!(context.filters(), context.run().arg("--files").arg("dir"), @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    directory................................................................Passed
 
     ----- stderr -----
-    ");
+    "#);
 
-    // Force color output
-    cmd_snapshot!(context.filters(), context.run().arg("--color=always"), @r"
+    cmd_snapshot!(context.filters(), context.run().arg("--all-files"), @r#"
     success: true
     exit_code: 0
     ----- stdout -----
-    color....................................................................[42mPassed[49m
-    [2m- hook id: color[0m
-    [2m- duration: [TIME][0m
+    directory............................................(no files to check)Skipped
 
-      [1;32mHello, world![0m
+    ----- stderr -----
+    "#);
+
+    cmd_snapshot!(context.filters(), context.run().arg("--files").arg("non-exist-files"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    directory............................................(no files to check)Skipped
 
     ----- stderr -----
+    warning: This file does not exist and will be ignored: `non-exist-files`
     ");
-
     Ok(())
 }
 
-/// Test running hook whose `entry` is script with shebang on Windows.
 #[test]
-fn shebang_script() -> Result<()> {
+fn run_last_commit() -> Result<()> {
     let context = TestContext::new();
     context.init_project();
+    context.configure_git_author();
 
-    // Create a script with shebang.
-    let script = indoc::indoc! {r"
-        #!/usr/bin/env python
-        import sys
-        print('Hello, world!')
-        sys.exit(0)
-    "};
-    context.work_dir().child("script.py").write_str(script)?;
-
+    let cwd = context.work_dir();
     context.write_pre_commit_config(indoc::indoc! {r"
-      repos:
-        - repo: local
-          hooks:
-            - id: shebang-script
-              name: shebang-script
-              language: python
-              entry: script.py
-              verbose: true
-              pass_filenames: false
-              always_run: true
+        repos:
+          - repo: https://github.com/pre-commit/pre-commit-hooks
+            rev: v5.0.0
+            hooks:
+              - id: trailing-whitespace
+              - id: end-of-file-fixer
     "});
+
+    // Create initial files and make first commit
+    cwd.child("file1.txt").write_str("Hello, world!\n")?;
+    cwd.child("file2.txt")
+        .write_str("Initial content with trailing spaces   \n")?; // This has issues but won't be in last commit
     context.git_add(".");
+    context.git_commit("Initial commit");
 
-    cmd_snapshot!(context.filters(), context.run(), @r"
-    success: true
-    exit_code: 0
+    // Modify files and make second commit with trailing whitespace
+    cwd.child("file1.txt").write_str("Hello, world!   \n")?; // trailing whitespace
+    cwd.child("file3.txt").write_str("New file")?; // missing newline
+    // Note: file2.txt is NOT modified in this commit, so it should be filtered out by --last-commit
+    context.git_add(".");
+    context.git_commit("Second commit with issues");
+
+    // Run with --last-commit should only check files from the last commit
+    // This should only process file1.txt and file3.txt, NOT file2.txt
+    cmd_snapshot!(context.filters(), context.run().arg("--last-commit"), @r"
+    success: false
+    exit_code: 1
     ----- stdout -----
-    shebang-script...........................................................Passed
-    - hook id: shebang-script
-    - duration: [TIME]
+    trim trailing whitespace.................................................Failed
+    - hook id: trailing-whitespace
+    - exit code: 1
+    - files were modified by this hook
 
-      Hello, world!
+      Fixing file1.txt
+    fix end of files.........................................................Failed
+    - hook id: end-of-file-fixer
+    - exit code: 1
+    - files were modified by this hook
+
+      Fixing file3.txt
+
+    ----- stderr -----
+    ");
+
+    // Now reset the files to their problematic state for comparison
+    cwd.child("file1.txt").write_str("Hello, world!   \n")?; // trailing whitespace
+    cwd.child("file3.txt").write_str("New file")?; // missing newline
+
+    // Run with --all-files should check ALL files including file2.txt
+    // This demonstrates that file2.txt was indeed filtered out in the previous test
+    cmd_snapshot!(context.filters(), context.run().arg("--all-files"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    trim trailing whitespace.................................................Failed
+    - hook id: trailing-whitespace
+    - exit code: 1
+    - files were modified by this hook
+
+      Fixing file1.txt
+      Fixing file2.txt
+    fix end of files.........................................................Failed
+    - hook id: end-of-file-fixer
+    - exit code: 1
+    - files were modified by this hook
+
+      Fixing file3.txt
 
     ----- stderr -----
     ");
@@ -2051,561 +2652,2360 @@ fn shebang_script() -> Result<()> {
     Ok(())
 }
 
-/// Test `git commit -a` works without `.git/index.lock exists` error.
+/// Test `prek run --changed-only` against the upstream branch and the CI base branch.
 #[test]
-fn git_commit_a() -> Result<()> {
+fn run_changed_only() -> Result<()> {
     let context = TestContext::new();
     context.init_project();
     context.configure_git_author();
-    context.disable_auto_crlf();
 
+    let cwd = context.work_dir();
     context.write_pre_commit_config(indoc::indoc! {r"
         repos:
           - repo: local
             hooks:
-              - id: echo
-                name: echo
+              - id: changed
+                name: changed
                 language: system
                 entry: echo
                 verbose: true
     "});
+    cwd.child("file1.txt").write_str("Hello, world!\n")?;
+    context.git_add(".");
+    context.git_commit("Initial commit");
 
-    // Create a file and commit it.
-    let cwd = context.work_dir();
-    let file = cwd.child("file.txt");
-    file.write_str("Hello, world!\n")?;
-
-    cmd_snapshot!(context.filters(), context.install(), @r#"
-    success: true
-    exit_code: 0
+    // Don't pick up the base branch of the CI running the tests.
+    let run = || {
+        let mut cmd = context.run();
+        cmd.arg("--changed-only")
+            .env_remove(EnvVars::GITHUB_BASE_REF)
+            .env_remove(EnvVars::CI_MERGE_REQUEST_TARGET_BRANCH_NAME)
+            .env_remove(EnvVars::BITBUCKET_PR_DESTINATION_BRANCH);
+        cmd
+    };
+
+    // No base branch to compare against.
+    cmd_snapshot!(context.filters(), run(), @r"
+    success: false
+    exit_code: 2
     ----- stdout -----
-    prek installed at `.git/hooks/pre-commit`
 
     ----- stderr -----
-    "#);
+    error: Could not detect the base branch for `--changed-only`: none of `GITHUB_BASE_REF`, `CI_MERGE_REQUEST_TARGET_BRANCH_NAME`, `BITBUCKET_PR_DESTINATION_BRANCH` is set and the current branch has no upstream. Use `--from-ref` to specify it
+    ");
 
+    // The upstream tracking branch is used by default.
+    Command::new("git")
+        .args(["checkout", "-b", "feature", "--track", "master"])
+        .current_dir(cwd)
+        .assert()
+        .success();
+    cwd.child("file2.txt").write_str("Hello, feature!\n")?;
     context.git_add(".");
-    context.git_commit("Initial commit");
+    context.git_commit("Feature commit");
+    cwd.child("file3.txt").write_str("Hello, again!\n")?;
+    context.git_add(".");
+    context.git_commit("Another feature commit");
 
-    // Edit the file
-    file.write_str("Hello, world again!\n")?;
+    cmd_snapshot!(context.filters(), run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    changed..................................................................Passed
+    - hook id: changed
+    - duration: [TIME]
 
-    let mut commit = Command::new("git");
-    commit
-        .arg("commit")
-        .arg("-a")
-        .arg("-m")
-        .arg("Update file")
-        .env(EnvVars::PREK_HOME, &**context.home_dir())
-        .current_dir(cwd);
+      file3.txt file2.txt
 
-    let filters = context
-        .filters()
-        .into_iter()
-        .chain([(r"\[master \w{7}\]", r"[master COMMIT]")])
-        .collect::<Vec<_>>();
+    ----- stderr -----
+    ");
 
-    cmd_snapshot!(filters, commit, @r"
+    // In CI, the base branch is fetched from `origin`.
+    let remote = assert_fs::TempDir::new()?;
+    Command::new("git")
+        .args(["clone", "--bare", "--branch", "master", "."])
+        .arg(remote.path())
+        .current_dir(cwd)
+        .assert()
+        .success();
+    Command::new("git")
+        .args(["remote", "add", "origin"])
+        .arg(remote.path())
+        .current_dir(cwd)
+        .assert()
+        .success();
+    Command::new("git")
+        .args(["checkout", "--detach"])
+        .current_dir(cwd)
+        .assert()
+        .success();
+
+    cmd_snapshot!(context.filters(), run().env(EnvVars::GITHUB_BASE_REF, "master"), @r"
     success: true
     exit_code: 0
     ----- stdout -----
-    [master COMMIT] Update file
-     1 file changed, 1 insertion(+), 1 deletion(-)
+    changed..................................................................Passed
+    - hook id: changed
+    - duration: [TIME]
+
+      file3.txt file2.txt
 
     ----- stderr -----
-    echo.....................................................................Passed
-    - hook id: echo
+    ");
+
+    // Bitbucket Pipelines names the target branch of pull requests differently.
+    cmd_snapshot!(context.filters(), run().env(EnvVars::BITBUCKET_PR_DESTINATION_BRANCH, "master"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    changed..................................................................Passed
+    - hook id: changed
     - duration: [TIME]
 
-      file.txt
+      file3.txt file2.txt
+
+    ----- stderr -----
     ");
 
     Ok(())
 }
 
-fn write_pre_commit_config(path: &Path, hooks: &[(&str, &str)]) -> Result<()> {
-    let mut yaml = String::from(indoc::indoc! {"
-        repos:
-          - repo: local
-            hooks:
-    "});
-    for (id, name) in hooks {
-        let hook = textwrap::indent(
-            &indoc::formatdoc! {"
-        - id: {}
-          name: {}
-          entry: echo
-          language: system
-        ", id, name
-            },
-            "      ",
-        );
-        yaml.push_str(&hook);
-    }
+/// Test `{staged_files_file}` in `args` passes the files in a file instead of as arguments.
+#[cfg(unix)]
+#[test]
+fn run_staged_files_file() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
 
-    std::fs::create_dir_all(path)?;
-    std::fs::write(path.join(CONFIG_FILE), yaml)?;
+    let cwd = context.work_dir();
+    context.write_pre_commit_config(indoc::indoc! {r#"
+        repos:
+          - repo: local
+            hooks:
+              - id: files-from
+                name: files-from
+                language: system
+                entry: sh -c 'echo "$# arguments"; tr "\000" "\n" < "${1#--files-from=}" | sort' --
+                args: ["--files-from={staged_files_file}"]
+                verbose: true
+    "#});
+    cwd.child("file1.txt").write_str("Hello, world!\n")?;
+    cwd.child("file2.txt").write_str("Hello, again!\n")?;
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    files-from...............................................................Passed
+    - hook id: files-from
+    - duration: [TIME]
+
+      1 arguments
+      .pre-commit-config.yaml
+      file1.txt
+      file2.txt
+
+    ----- stderr -----
+    ");
+
+    // Docker hooks can't read the file on the host.
+    context.write_pre_commit_config(indoc::indoc! {r#"
+        repos:
+          - repo: local
+            hooks:
+              - id: docker
+                name: docker
+                language: docker_image
+                entry: alpine cat
+                args: ["{staged_files_file}"]
+    "#});
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: Failed to init hooks
+      caused by: Invalid hook `docker`
+      caused by: Hook specified `{staged_files_file}` in `args` but the language `docker_image` does not support it
+    ");
 
     Ok(())
 }
 
-#[cfg(unix)]
+/// Test `prek run --files` with multiple files.
 #[test]
-fn selectors_completion() -> Result<()> {
+fn run_multiple_files() -> Result<()> {
     let context = TestContext::new();
+    context.init_project();
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: multiple-files
+                name: multiple-files
+                language: system
+                entry: echo
+                verbose: true
+                types: [text]
+    "});
     let cwd = context.work_dir();
+    cwd.child("file1.txt").write_str("Hello, world!")?;
+    cwd.child("file2.txt").write_str("Hello, world!")?;
+    context.git_add(".");
+    // `--files` with multiple files
+    cmd_snapshot!(context.filters(), context.run().arg("--files").arg("file1.txt").arg("file2.txt"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    multiple-files...........................................................Passed
+    - hook id: multiple-files
+    - duration: [TIME]
+
+      file2.txt file1.txt
+
+    ----- stderr -----
+    ");
+    Ok(())
+}
+
+/// Test `prek run --files` with no files.
+#[test]
+fn run_no_files() {
+    let context = TestContext::new();
     context.init_project();
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: no-files
+                name: no-files
+                language: system
+                entry: echo
+                verbose: true
+    "});
+    context.git_add(".");
+    // `--files` with no files
+    cmd_snapshot!(context.filters(), context.run().arg("--files"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    no-files.................................................................Passed
+    - hook id: no-files
+    - duration: [TIME]
 
-    // Root project with one hook
-    write_pre_commit_config(cwd, &[("root-hook", "Root Hook")])?;
+      .pre-commit-config.yaml
 
-    // Nested project at app/ with one hook
-    let app = cwd.join("app");
-    write_pre_commit_config(&app, &[("app-hook", "App Hook")])?;
+    ----- stderr -----
+    ");
+}
 
-    // Deeper nested project at app/lib/ with one hook
-    let app_lib = app.join("lib");
-    write_pre_commit_config(&app_lib, &[("lib-hook", "Lib Hook")])?;
+/// Test `prek run --directory` flags.
+#[test]
+fn run_directory() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: directory
+                name: directory
+                language: system
+                entry: echo
+                verbose: true
+    "});
 
-    // Unrelated non-project dir should not appear in subdir suggestions
-    cwd.child("scratch").create_dir_all()?;
+    let cwd = context.work_dir();
+    cwd.child("dir1").create_dir_all()?;
+    cwd.child("dir1/file.txt").write_str("Hello, world!")?;
+    cwd.child("dir2").create_dir_all()?;
+    cwd.child("dir2/file.txt").write_str("Hello, world!")?;
+    context.git_add(".");
 
-    cmd_snapshot!(context.filters(), context.run().env("COMPLETE", "fish").arg("--").arg("prek").arg(""), @r"
+    // one `--directory`
+    cmd_snapshot!(context.filters(), context.run().arg("--directory").arg("dir1"), @r"
     success: true
     exit_code: 0
     ----- stdout -----
-    install	Install the prek git hook
-    install-hooks	Create hook environments for all hooks used in the config file
-    run	Run hooks
-    list	List available hooks
-    uninstall	Uninstall the prek git hook
-    validate-config	Validate `.pre-commit-config.yaml` files
-    validate-manifest	Validate `.pre-commit-hooks.yaml` files
-    sample-config	Produce a sample `.pre-commit-config.yaml` file
-    auto-update	Auto-update pre-commit config to the latest repos' versions
-    cache	Manage the prek cache
-    init-template-dir	Install hook script in a directory intended for use with `git config init.templateDir`
-    try-repo	Try the pre-commit hooks in the current repo
-    self	`prek` self management
-    app/
-    app:
-    app-hook	App Hook
-    lib-hook	Lib Hook
-    root-hook	Root Hook
-    --skip	Skip the specified hooks or projects
-    --all-files	Run on all files in the repo
-    --files	Specific filenames to run hooks on
-    --directory	Run hooks on all files in the specified directories
-    --from-ref	The original ref in a `<from_ref>...<to_ref>` diff expression. Files changed in this diff will be run through the hooks
-    --to-ref	The destination ref in a `from_ref...to_ref` diff expression. Defaults to `HEAD` if `from_ref` is specified
-    --last-commit	Run hooks against the last commit. Equivalent to `--from-ref HEAD~1 --to-ref HEAD`
-    --hook-stage	The stage during which the hook is fired
-    --show-diff-on-failure	When hooks fail, run `git diff` directly afterward
-    --fail-fast	Stop running hooks after the first failure
-    --dry-run	Do not run the hooks, but print the hooks that would have been run
-    --config	Path to alternate config file
-    --cd	Change to directory before running
-    --color	Whether to use color in output
-    --refresh	Refresh all cached data
-    --help	Display the concise help for this command
-    --no-progress	Hide all progress outputs
-    --quiet	Use quiet output
-    --verbose	Use verbose output
-    --log-file	Write trace logs to the specified file. If not specified, trace logs will be written to `$PREK_HOME/prek.log`
-    --version	Display the prek version
+    directory................................................................Passed
+    - hook id: directory
+    - duration: [TIME]
+
+      dir1/file.txt
 
     ----- stderr -----
     ");
 
-    cmd_snapshot!(context.filters(), context.run().env("COMPLETE", "fish").arg("--").arg("prek").arg("ap"), @r"
+    // repeated `--directory`
+    cmd_snapshot!(context.filters(), context.run().arg("--directory").arg("dir1").arg("--directory").arg("dir1"), @r"
     success: true
     exit_code: 0
     ----- stdout -----
-    app/
-    app:
-    app-hook	App Hook
+    directory................................................................Passed
+    - hook id: directory
+    - duration: [TIME]
+
+      dir1/file.txt
 
     ----- stderr -----
     ");
 
-    cmd_snapshot!(context.filters(), context.run().env("COMPLETE", "fish").arg("--").arg("prek").arg("app:"), @r"
+    // multiple `--directory`
+    cmd_snapshot!(context.filters(), context.run().arg("--directory").arg("dir1").arg("--directory").arg("dir2"), @r"
     success: true
     exit_code: 0
     ----- stdout -----
-    app:app-hook	App Hook
+    directory................................................................Passed
+    - hook id: directory
+    - duration: [TIME]
+
+      dir2/file.txt dir1/file.txt
 
     ----- stderr -----
     ");
 
-    cmd_snapshot!(context.filters(), context.run().env("COMPLETE", "fish").arg("--").arg("prek").arg("app:app"), @r"
+    // non-existing directory
+    cmd_snapshot!(context.filters(), context.run().arg("--directory").arg("non-existing-dir"), @r"
     success: true
     exit_code: 0
     ----- stdout -----
-    app:app-hook	App Hook
+    directory............................................(no files to check)Skipped
 
     ----- stderr -----
     ");
 
-    cmd_snapshot!(context.filters(), context.run().env("COMPLETE", "fish").arg("--").arg("prek").arg("app/"), @r"
+    // `--directory` with `--files`
+    cmd_snapshot!(context.filters(), context.run().arg("--directory").arg("dir1").arg("--files").arg("dir1/file.txt"), @r"
     success: true
     exit_code: 0
     ----- stdout -----
-    app/lib/
-    app/lib:
+    directory................................................................Passed
+    - hook id: directory
+    - duration: [TIME]
+
+      dir1/file.txt
 
     ----- stderr -----
     ");
-    cmd_snapshot!(context.filters(), context.run().env("COMPLETE", "fish").arg("--").arg("prek").arg("app/li"), @r"
+    cmd_snapshot!(context.filters(), context.run().arg("--directory").arg("dir1").arg("--files").arg("dir2/file.txt"), @r"
     success: true
     exit_code: 0
     ----- stdout -----
-    app/lib/
-    app/lib:
+    directory................................................................Passed
+    - hook id: directory
+    - duration: [TIME]
+
+      dir2/file.txt dir1/file.txt
 
     ----- stderr -----
     ");
 
-    cmd_snapshot!(context.filters(), context.run().env("COMPLETE", "fish").arg("--").arg("prek").arg("app/lib:"), @r"
+    // run `--directory` inside a subdirectory
+    cmd_snapshot!(context.filters(), context.run().current_dir(cwd.join("dir1")).arg("--directory").arg("."), @r"
     success: true
     exit_code: 0
     ----- stdout -----
-    app/lib:lib-hook	Lib Hook
+    directory................................................................Passed
+    - hook id: directory
+    - duration: [TIME]
+
+      dir1/file.txt
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.run().arg("--cd").arg("dir1").arg("--directory").arg("."), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    directory................................................................Passed
+    - hook id: directory
+    - duration: [TIME]
+
+      dir1/file.txt
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
+
+/// Test `prek run --files` and `--directory` with untracked and ignored files.
+#[test]
+fn run_untracked_and_ignored_files() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: echo
+                name: echo
+                language: system
+                entry: echo
+                verbose: true
+    "});
+
+    let cwd = context.work_dir();
+    cwd.child(".gitignore").write_str("*.log\n")?;
+    cwd.child("dir/tracked.txt").write_str("Hello, world!")?;
+    context.git_add(".");
+    cwd.child("dir/untracked.txt").write_str("Hello, world!")?;
+    cwd.child("dir/ignored.log").write_str("Hello, world!")?;
+
+    // Untracked and ignored files are skipped with a warning.
+    cmd_snapshot!(context.filters(), context.run().arg("--files").arg("dir/tracked.txt").arg("dir/untracked.txt").arg("dir/ignored.log"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    echo.....................................................................Passed
+    - hook id: echo
+    - duration: [TIME]
+
+      dir/tracked.txt
+
+    ----- stderr -----
+    warning: This file is untracked and will be skipped, use `--include-untracked` to run hooks on it: `dir/untracked.txt`
+    warning: This file is ignored by git and will be skipped, use `--include-ignored` to run hooks on it: `dir/ignored.log`
+    ");
+
+    cmd_snapshot!(context.filters(), context.run().arg("--files").arg("dir/tracked.txt").arg("dir/untracked.txt").arg("dir/ignored.log").arg("--include-untracked").arg("--include-ignored"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    echo.....................................................................Passed
+    - hook id: echo
+    - duration: [TIME]
+
+      dir/tracked.txt dir/ignored.log dir/untracked.txt
+
+    ----- stderr -----
+    ");
+
+    // Only tracked files are collected from directories by default.
+    cmd_snapshot!(context.filters(), context.run().arg("--directory").arg("dir"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    echo.....................................................................Passed
+    - hook id: echo
+    - duration: [TIME]
+
+      dir/tracked.txt
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.run().arg("--directory").arg("dir").arg("--include-untracked"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    echo.....................................................................Passed
+    - hook id: echo
+    - duration: [TIME]
+
+      dir/untracked.txt dir/tracked.txt
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
+
+/// Test `minimum_prek_version` option.
+#[test]
+fn minimum_prek_version() {
+    let context = TestContext::new();
+    context.init_project();
+    context.write_pre_commit_config(indoc::indoc! {r"
+        minimum_prek_version: 10.0.0
+        repos:
+          - repo: local
+            hooks:
+              - id: directory
+                name: directory
+                language: system
+                entry: echo
+                verbose: true
+    "});
+    context.git_add(".");
+
+    let filters = context
+        .filters()
+        .into_iter()
+        .chain([(
+            r"current version `\d+\.\d+\.\d+(?:-[0-9A-Za-z]+(?:\.[0-9A-Za-z]+)*)?`",
+            "current version `[CURRENT_VERSION]`",
+        )])
+        .collect::<Vec<_>>();
+
+    cmd_snapshot!(filters, context.run(), @r#"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: Failed to parse `.pre-commit-config.yaml`
+      caused by: Required minimum prek version `10.0.0` is greater than current version `[CURRENT_VERSION]`. Please consider updating prek.
+         --> .pre-commit-config.yaml:1:1
+          |
+        1 | minimum_prek_version: 10.0.0
+          | ^
+    "#);
+}
+
+/// Run hooks that would echo color.
+#[test]
+#[cfg(not(windows))]
+fn color() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+    context.write_pre_commit_config(indoc::indoc! {r"
+      repos:
+        - repo: local
+          hooks:
+            - id: color
+              name: color
+              language: python
+              entry: python ./color.py
+              verbose: true
+              pass_filenames: false
+  "});
+
+    let script = indoc::indoc! {r"
+      import sys
+      if sys.stdout.isatty():
+          print('\033[1;32mHello, world!\033[0m')
+      else:
+          print('Hello, world!')
+  "};
+    context.work_dir().child("color.py").write_str(script)?;
+
+    context.git_add(".");
+
+    // Run default. In integration tests, we don't have a TTY.
+    // So this prints without color.
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    color....................................................................Passed
+    - hook id: color
+    - duration: [TIME]
+
+      Hello, world!
+
+    ----- stderr -----
+    ");
+
+    // Force color output
+    cmd_snapshot!(context.filters(), context.run().arg("--color=always"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    color....................................................................[42mPassed[49m
+    [2m- hook id: color[0m
+    [2m- duration: [TIME][0m
+
+      [1;32mHello, world![0m
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
+
+/// Test `--color` is applied to the output of hooks that don't detect it themselves.
+#[cfg(unix)]
+#[test]
+fn color_forwarded_to_hooks() {
+    let context = TestContext::new();
+    context.init_project();
+
+    context.write_pre_commit_config(indoc::indoc! {r#"
+        repos:
+          - repo: local
+            hooks:
+              - id: color
+                name: color
+                language: system
+                entry: sh -c 'printf "\033[31mred\033[0m FORCE_COLOR=%s CLICOLOR_FORCE=%s PY_COLORS=%s\n" "$FORCE_COLOR" "$CLICOLOR_FORCE" "$PY_COLORS"'
+                pass_filenames: false
+                verbose: true
+    "#});
+    context.git_add(".");
+
+    let run = || {
+        let mut cmd = context.run();
+        cmd.env_remove(EnvVars::FORCE_COLOR)
+            .env_remove(EnvVars::CLICOLOR_FORCE)
+            .env_remove(EnvVars::PY_COLORS);
+        cmd
+    };
+
+    // Colors are forced in hooks.
+    cmd_snapshot!(context.filters(), run().arg("--color=always"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    color....................................................................[42mPassed[49m
+    [2m- hook id: color[0m
+    [2m- duration: [TIME][0m
+
+      [31mred[0m FORCE_COLOR=1 CLICOLOR_FORCE=1 PY_COLORS=1
+
+    ----- stderr -----
+    ");
+
+    // Colors the hook insists on are stripped.
+    cmd_snapshot!(context.filters(), run().arg("--color=never"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    color....................................................................Passed
+    - hook id: color
+    - duration: [TIME]
+
+      red FORCE_COLOR= CLICOLOR_FORCE= PY_COLORS=
+
+    ----- stderr -----
+    ");
+}
+
+/// Test running hook whose `entry` is script with shebang on Windows.
+#[test]
+fn shebang_script() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    // Create a script with shebang.
+    let script = indoc::indoc! {r"
+        #!/usr/bin/env python
+        import sys
+        print('Hello, world!')
+        sys.exit(0)
+    "};
+    context.work_dir().child("script.py").write_str(script)?;
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+      repos:
+        - repo: local
+          hooks:
+            - id: shebang-script
+              name: shebang-script
+              language: python
+              entry: script.py
+              verbose: true
+              pass_filenames: false
+              always_run: true
+    "});
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    shebang-script...........................................................Passed
+    - hook id: shebang-script
+    - duration: [TIME]
+
+      Hello, world!
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
+
+/// Test `git commit -a` works without `.git/index.lock exists` error.
+#[test]
+fn git_commit_a() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+    context.configure_git_author();
+    context.disable_auto_crlf();
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: echo
+                name: echo
+                language: system
+                entry: echo
+                verbose: true
+    "});
+
+    // Create a file and commit it.
+    let cwd = context.work_dir();
+    let file = cwd.child("file.txt");
+    file.write_str("Hello, world!\n")?;
+
+    cmd_snapshot!(context.filters(), context.install(), @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    prek installed at `.git/hooks/pre-commit`
+
+    ----- stderr -----
+    "#);
+
+    context.git_add(".");
+    context.git_commit("Initial commit");
+
+    // Edit the file
+    file.write_str("Hello, world again!\n")?;
+
+    let mut commit = Command::new("git");
+    commit
+        .arg("commit")
+        .arg("-a")
+        .arg("-m")
+        .arg("Update file")
+        .env(EnvVars::PREK_HOME, &**context.home_dir())
+        .current_dir(cwd);
+
+    let filters = context
+        .filters()
+        .into_iter()
+        .chain([(r"\[master \w{7}\]", r"[master COMMIT]")])
+        .collect::<Vec<_>>();
+
+    cmd_snapshot!(filters, commit, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    [master COMMIT] Update file
+     1 file changed, 1 insertion(+), 1 deletion(-)
+
+    ----- stderr -----
+    echo.....................................................................Passed
+    - hook id: echo
+    - duration: [TIME]
+
+      file.txt
+    ");
+
+    Ok(())
+}
+
+fn write_pre_commit_config(path: &Path, hooks: &[(&str, &str)]) -> Result<()> {
+    let mut yaml = String::from(indoc::indoc! {"
+        repos:
+          - repo: local
+            hooks:
+    "});
+    for (id, name) in hooks {
+        let hook = textwrap::indent(
+            &indoc::formatdoc! {"
+        - id: {}
+          name: {}
+          entry: echo
+          language: system
+        ", id, name
+            },
+            "      ",
+        );
+        yaml.push_str(&hook);
+    }
+
+    std::fs::create_dir_all(path)?;
+    std::fs::write(path.join(CONFIG_FILE), yaml)?;
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn selectors_completion() -> Result<()> {
+    let context = TestContext::new();
+    let cwd = context.work_dir();
+    context.init_project();
+
+    // Root project with one hook
+    write_pre_commit_config(cwd, &[("root-hook", "Root Hook")])?;
+
+    // Nested project at app/ with one hook
+    let app = cwd.join("app");
+    write_pre_commit_config(&app, &[("app-hook", "App Hook")])?;
+
+    // Deeper nested project at app/lib/ with one hook
+    let app_lib = app.join("lib");
+    write_pre_commit_config(&app_lib, &[("lib-hook", "Lib Hook")])?;
+
+    // Unrelated non-project dir should not appear in subdir suggestions
+    cwd.child("scratch").create_dir_all()?;
+
+    cmd_snapshot!(context.filters(), context.run().env("COMPLETE", "fish").arg("--").arg("prek").arg(""), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    install	Install the prek git hook
+    install-hooks	Create hook environments for all hooks used in the config file
+    run	Run hooks
+    exec	Run a single hook directly on the given files
+    bisect	Find the smallest set of files a hook fails on
+    verify	Run the hooks on each commit of a revision range, reporting the commits that fail them
+    serve	Serve hook checks to editors over stdio
+    list	List available hooks
+    info	Show the details of a hook
+    uninstall	Uninstall the prek git hook
+    validate-config	Validate `.pre-commit-config.yaml` files
+    validate-manifest	Validate `.pre-commit-hooks.yaml` and `prek-hooks.toml` files
+    render	Print the config of the current project, with `extends` resolved
+    sample-config	Produce a sample `.pre-commit-config.yaml` file
+    auto-update	Auto-update pre-commit config to the latest repos' versions
+    cache	Manage the prek cache
+    stats	Show local statistics of hook durations and outcomes
+    last	Show the summary of the last run
+    skip	Manage the hooks skipped in the local repository
+    audit	Show the commits that skipped the `pre-commit` hook
+    init-template-dir	Install hook script in a directory intended for use with `git config init.templateDir`
+    try-repo	Try the pre-commit hooks in the current repo
+    self	`prek` self management
+    app/
+    app:
+    app-hook	App Hook
+    lib-hook	Lib Hook
+    root-hook	Root Hook
+    --skip	Skip the specified hooks or projects
+    --no-default-skips	Also run the hooks skipped by the `default_skips` of the config
+    --all-files	Run on all files in the repo
+    --files	Specific filenames to run hooks on
+    --directory	Run hooks on all files in the specified directories
+    --include-untracked	Run hooks on untracked files passed with `--files` or found in `--directory`
+    --include-ignored	Run hooks on files ignored by git that are passed with `--files` or found in `--directory`
+    --recurse-submodules	Discover the configs of initialized git submodules as projects, and run hooks on the files of the submodules
+    --from-ref	The original ref in a `<from_ref>...<to_ref>` diff expression. Files changed in this diff will be run through the hooks
+    --to-ref	The destination ref in a `from_ref...to_ref` diff expression. Defaults to `HEAD` if `from_ref` is specified
+    --last-commit	Run hooks against the last commit. Equivalent to `--from-ref HEAD~1 --to-ref HEAD`
+    --changed-only	Run hooks on the files changed since the branch the current changes will be merged into
+    --retry-failed	Run the hooks that failed in the last run again, on the files they failed on
+    --stdin	Run the hooks on content read from stdin, as if it were the file given by `--stdin-filename`
+    --stdin-filename	The path of the file whose content is read with `--stdin`
+    --hook-stage	The stage during which the hook is fired
+    --only-fixers	Only run hooks that fix files, i.e. hooks with `kind: fixer`
+    --only-checkers	Only run hooks that check files without modifying them, i.e. hooks with `kind: checker`
+    --show-diff-on-failure	When hooks fail, run `git diff` directly afterward
+    --diff-context	The number of context lines to show around changes in the diff printed by `--show-diff-on-failure`
+    --word-diff	Highlight the changed words instead of whole lines in the diff printed by `--show-diff-on-failure`
+    --show-fixes	After the run, list the files modified by each hook with the lines added and removed
+    --max-file-size	Skip files larger than the given number of bytes instead of passing them to hooks
+    --fail-fast	Stop running hooks after the first failure
+    --modified-exit-code	Exit with this code when the only failures are hooks that modified files
+    --autofix-stage	Stage the files modified by hooks and run the other hooks again on them, when the only failures are hooks that modified files
+    --serial-projects	Run the hooks of workspace projects one project at a time
+    --dry-run	Do not run the hooks, but print the hooks that would have been run
+    --write-summary	Write a summary of the run (status, hook counts, durations and prek version) to a file
+    --reporter	Stream the events of the run to an external reporter
+    --config	Path to alternate config file
+    --cd	Change to directory before running
+    --color	Whether to use color in output
+    --refresh	Refresh all cached data
+    --warnings	How to treat prek's own warnings, such as unexpected config keys or mutable revs
+    --retries	The number of times to retry network operations, such as cloning hook repositories or downloading toolchains, after a transient failure
+    --offline	Disable network access
+    --help	Display the concise help for this command
+    --no-progress	Hide all progress outputs
+    --quiet	Use quiet output
+    --verbose	Use verbose output
+    --log-file	Write trace logs to the specified file. If not specified, trace logs will be written to `$PREK_HOME/prek.log`
+    --version	Display the prek version
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.run().env("COMPLETE", "fish").arg("--").arg("prek").arg("ap"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    app/
+    app:
+    app-hook	App Hook
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.run().env("COMPLETE", "fish").arg("--").arg("prek").arg("app:"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    app:app-hook	App Hook
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.run().env("COMPLETE", "fish").arg("--").arg("prek").arg("app:app"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    app:app-hook	App Hook
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.run().env("COMPLETE", "fish").arg("--").arg("prek").arg("app/"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    app/lib/
+    app/lib:
+
+    ----- stderr -----
+    ");
+    cmd_snapshot!(context.filters(), context.run().env("COMPLETE", "fish").arg("--").arg("prek").arg("app/li"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    app/lib/
+    app/lib:
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.run().env("COMPLETE", "fish").arg("--").arg("prek").arg("app/lib:"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    app/lib:lib-hook	Lib Hook
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.run().env("COMPLETE", "fish").arg("--").arg("prek").arg("app/lib/"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    app/lib/
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.run().env("COMPLETE", "fish").arg("--").arg("prek").arg("!app:"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    !app:app-hook	App Hook
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.run().env("COMPLETE", "fish").arg("--").arg("prek").arg("lang"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    language:
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.run().env("COMPLETE", "fish").arg("--").arg("prek").arg("language:py"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    language:python
+    language:pygrep
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
+
+/// Test reusing hook environments only when dependencies are exactly same. (ignore order)
+#[test]
+fn reuse_env() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    let pkg_dir = context.work_dir().child("local_pkg");
+    pkg_dir.create_dir_all()?;
+    pkg_dir.child("setup.py").write_str(indoc::indoc! {r#"
+        from setuptools import setup
+
+        setup(
+            name="local-pkg",
+            version="0.1.0",
+            py_modules=["local_pkg"],
+        )
+    "#})?;
+    pkg_dir
+        .child("local_pkg.py")
+        .write_str("def hello():\n     print('hello')\n")?;
+
+    context.write_pre_commit_config(indoc::indoc! {r#"
+    repos:
+      - repo: local
+        hooks:
+          - id: reuse-env
+            name: reuse-env
+            language: python
+            entry: python -c "import local_pkg; local_pkg.hello()"
+            pass_filenames: false
+            additional_dependencies: ["./local_pkg"]
+            verbose: true
+    "#});
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    reuse-env................................................................Passed
+    - hook id: reuse-env
+    - duration: [TIME]
+
+      hello
+
+    ----- stderr -----
+    ");
+
+    // Remove dependencies, so the environment should not be reused.
+    context.write_pre_commit_config(indoc::indoc! {r#"
+    repos:
+      - repo: local
+        hooks:
+          - id: reuse-env
+            name: reuse-env
+            language: python
+            entry: python -c "print('ok')"
+            pass_filenames: false
+            verbose: true
+    "#});
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    reuse-env................................................................Passed
+    - hook id: reuse-env
+    - duration: [TIME]
+
+      ok
+
+    ----- stderr -----
+    ");
+
+    // There should be two hook environments.
+    assert_eq!(context.home_dir().child("hooks").read_dir()?.count(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn dry_run() {
+    let context = TestContext::new();
+    context.init_project();
+    context.write_pre_commit_config(indoc::indoc! {r#"
+        repos:
+          - repo: local
+            hooks:
+              - id: fail
+                name: fail
+                entry: fail
+                language: fail
+              - id: echo
+                name: echo
+                entry: echo "hello world"
+                args: [--flag]
+                language: system
+                env:
+                  FOO: bar
+    "#});
+    context.git_add(".");
+
+    // Run with `--dry-run`
+    cmd_snapshot!(context.filters(), context.run().arg("--dry-run").arg("-v"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    fail....................................................................Dry Run
+    - hook id: fail
+    - duration: [TIME]
+
+      command: fail
+      env:
+        PRE_COMMIT=1
+      `fail` would be run on 1 files in 1 batch:
+      - .pre-commit-config.yaml
+    echo....................................................................Dry Run
+    - hook id: echo
+    - duration: [TIME]
+
+      command: echo 'hello world' --flag
+      env:
+        PRE_COMMIT=1
+        FOO=bar
+      `echo` would be run on 1 files in 1 batch:
+      - .pre-commit-config.yaml
+
+    ----- stderr -----
+    ");
+}
+
+/// Supports reading `pre-commit-config.yml` as well.
+#[test]
+fn alternate_config_file() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    context
+        .work_dir()
+        .child(ALT_CONFIG_FILE)
+        .write_str(indoc::indoc! {r#"
+        repos:
+          - repo: local
+            hooks:
+              - id: local-python-hook
+                name: local-python-hook
+                language: python
+                entry: python3 -c 'import sys; print("Hello, world!")'
+    "#})?;
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run().arg("-v"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    local-python-hook........................................................Passed
+    - hook id: local-python-hook
+    - duration: [TIME]
+
+      Hello, world!
+
+    ----- stderr -----
+    ");
+
+    context
+        .work_dir()
+        .child(CONFIG_FILE)
+        .write_str(indoc::indoc! {r#"
+        repos:
+          - repo: local
+            hooks:
+              - id: local-python-hook
+                name: local-python-hook
+                language: python
+                entry: python3 -c 'import sys; print("Hello, world!")'
+    "#})?;
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run().arg("--refresh").arg("-v"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    local-python-hook........................................................Passed
+    - hook id: local-python-hook
+    - duration: [TIME]
+
+      Hello, world!
+
+    ----- stderr -----
+    warning: Both `[TEMP_DIR]/.pre-commit-config.yaml` and `[TEMP_DIR]/.pre-commit-config.yml` exist, using `[TEMP_DIR]/.pre-commit-config.yaml` only
+    ");
+
+    Ok(())
+}
+
+#[test]
+fn show_diff_on_failure() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+    context.disable_auto_crlf();
+
+    let config = indoc::indoc! {r#"
+        repos:
+          - repo: local
+            hooks:
+              - id: modify
+                name: modify
+                language: python
+                entry: python -c "import sys; open('file.txt', 'a').write('Added line\n')"
+                pass_filenames: false
+    "#};
+    context.write_pre_commit_config(config);
+    context
+        .work_dir()
+        .child("file.txt")
+        .write_str("Original line\n")?;
+    context.git_add(".");
+
+    let mut filters = context.filters();
+    filters.push((r"index \w{7}\.\.\w{7} \d{6}", "index [OLD]..[NEW] 100644"));
+
+    // When failed in CI environment
+    cmd_snapshot!(filters.clone(), context.run().env(EnvVars::CI, "1").arg("--show-diff-on-failure").arg("-v"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    modify...................................................................Failed
+    - hook id: modify
+    - duration: [TIME]
+    - files were modified by this hook
+
+    Hint: Some hooks made changes to the files.
+    If you are seeing this message in CI, reproduce locally with: `prek run --all-files`
+    To run prek as part of git workflow, use `prek install` to set up git hooks.
+
+    All changes made by hooks:
+    diff --git a/file.txt b/file.txt
+    index [OLD]..[NEW] 100644
+    --- a/file.txt
+    +++ b/file.txt
+    @@ -1 +1,2 @@
+     Original line
+    +Added line
+
+    ----- stderr -----
+    ");
+
+    context
+        .work_dir()
+        .child("file.txt")
+        .write_str("Original line\n")?;
+    context.git_add(".");
+    // When failed in non-CI environment
+    cmd_snapshot!(filters.clone(), context.run().env_remove(EnvVars::CI).arg("--show-diff-on-failure").arg("-v"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    modify...................................................................Failed
+    - hook id: modify
+    - duration: [TIME]
+    - files were modified by this hook
+    All changes made by hooks:
+    diff --git a/file.txt b/file.txt
+    index [OLD]..[NEW] 100644
+    --- a/file.txt
+    +++ b/file.txt
+    @@ -1 +1,2 @@
+     Original line
+    +Added line
+
+    ----- stderr -----
+    ");
+
+    // Run in the `app` subproject.
+    let app = context.work_dir().child("app");
+    app.create_dir_all()?;
+    app.child("file.txt").write_str("Original line\n")?;
+    app.child(CONFIG_FILE).write_str(config)?;
+
+    Command::new("git")
+        .arg("add")
+        .arg(".")
+        .current_dir(&app)
+        .assert()
+        .success();
+
+    cmd_snapshot!(filters.clone(), context.run().env_remove(EnvVars::CI).current_dir(&app).arg("--show-diff-on-failure"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    modify...................................................................Failed
+    - hook id: modify
+    - files were modified by this hook
+    All changes made by hooks:
+    diff --git a/app/file.txt b/app/file.txt
+    index [OLD]..[NEW] 100644
+    --- a/app/file.txt
+    +++ b/app/file.txt
+    @@ -1 +1,2 @@
+     Original line
+    +Added line
+
+    ----- stderr -----
+    ");
+
+    context.git_add(".");
+
+    // Run in the root
+    // Since we add a new subproject, use `--refresh` to find that.
+    cmd_snapshot!(filters.clone(), context.run().env_remove(EnvVars::CI).arg("--show-diff-on-failure").arg("--refresh"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    Running hooks for `app`:
+    modify...................................................................Failed
+    - hook id: modify
+    - files were modified by this hook
+
+    Running hooks for `.`:
+    modify...................................................................Failed
+    - hook id: modify
+    - files were modified by this hook
+    All changes made by hooks:
+    diff --git a/app/file.txt b/app/file.txt
+    index [OLD]..[NEW] 100644
+    --- a/app/file.txt
+    +++ b/app/file.txt
+    @@ -1,2 +1,3 @@
+     Original line
+     Added line
+    +Added line
+    diff --git a/file.txt b/file.txt
+    index [OLD]..[NEW] 100644
+    --- a/file.txt
+    +++ b/file.txt
+    @@ -1,2 +1,3 @@
+     Original line
+     Added line
+    +Added line
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
+
+/// `--diff-context` controls the context lines of the diff printed by `--show-diff-on-failure`.
+/// The hint to reproduce a failure names the CI provider and the diff that was checked.
+#[cfg(unix)]
+#[test]
+fn show_diff_on_failure_ci_hint() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+    context.configure_git_author();
+    context.disable_auto_crlf();
+
+    context.write_pre_commit_config(indoc::indoc! {r#"
+        repos:
+          - repo: local
+            hooks:
+              - id: modify
+                name: modify
+                language: system
+                entry: sh -c 'echo "Added line" >> file.txt'
+                pass_filenames: false
+                always_run: true
+    "#});
+    context
+        .work_dir()
+        .child("file.txt")
+        .write_str("Original line\n")?;
+    context.git_add(".");
+    context.git_commit("Initial commit");
+    context
+        .work_dir()
+        .child("other.txt")
+        .write_str("Other line\n")?;
+    context.git_add(".");
+    context.git_commit("Second commit");
+
+    let mut filters = context.filters();
+    filters.push((r"index \w{7}\.\.\w{7} \d{6}", "index [OLD]..[NEW] 100644"));
+
+    cmd_snapshot!(filters.clone(), context.run().env(EnvVars::BITBUCKET_BUILD_NUMBER, "1").arg("--show-diff-on-failure").arg("--last-commit"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    modify...................................................................Failed
+    - hook id: modify
+    - files were modified by this hook
+
+    Hint: Some hooks made changes to the files.
+    If you are seeing this message in Bitbucket Pipelines, reproduce locally with: `prek run --last-commit`
+    To run prek as part of git workflow, use `prek install` to set up git hooks.
+
+    All changes made by hooks:
+    diff --git a/file.txt b/file.txt
+    index [OLD]..[NEW] 100644
+    --- a/file.txt
+    +++ b/file.txt
+    @@ -1 +1,2 @@
+     Original line
+    +Added line
+
+    ----- stderr -----
+    ");
+
+    Command::new("git")
+        .args(["checkout", "--", "file.txt"])
+        .current_dir(context.work_dir())
+        .assert()
+        .success();
+
+    cmd_snapshot!(filters, context.run().env(EnvVars::GITLAB_CI, "true").arg("--show-diff-on-failure").arg("--from-ref").arg("HEAD").arg("--to-ref").arg("HEAD"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    modify...................................................................Failed
+    - hook id: modify
+    - files were modified by this hook
+
+    Hint: Some hooks made changes to the files.
+    If you are seeing this message in GitLab CI, reproduce locally with: `prek run --from-ref HEAD --to-ref HEAD`
+    To run prek as part of git workflow, use `prek install` to set up git hooks.
+
+    All changes made by hooks:
+    diff --git a/file.txt b/file.txt
+    index [OLD]..[NEW] 100644
+    --- a/file.txt
+    +++ b/file.txt
+    @@ -1 +1,2 @@
+     Original line
+    +Added line
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
+
+#[test]
+fn show_diff_on_failure_diff_context() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+    context.disable_auto_crlf();
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: builtin
+            hooks:
+              - id: trailing-whitespace
+    "});
+    context
+        .work_dir()
+        .child("file.txt")
+        .write_str("first line\nsecond line\nthird line  \nfourth line\nfifth line\n")?;
+    context.git_add(".");
+
+    let mut filters = context.filters();
+    filters.push((r"index \w{7}\.\.\w{7} \d{6}", "index [OLD]..[NEW] 100644"));
+
+    cmd_snapshot!(filters.clone(), context.run().env_remove(EnvVars::CI).arg("--show-diff-on-failure").arg("--diff-context").arg("0"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    trim trailing whitespace.................................................Failed
+    - hook id: trailing-whitespace
+    - exit code: 1
+    - files were modified by this hook
+
+      Fixing file.txt
+    All changes made by hooks:
+    diff --git a/file.txt b/file.txt
+    index [OLD]..[NEW] 100644
+    --- a/file.txt
+    +++ b/file.txt
+    @@ -3 +3 @@ second line
+    -third line  
+    +third line
+
+    ----- stderr -----
+    ");
+
+    // Both options require `--show-diff-on-failure`.
+    cmd_snapshot!(filters, context.run().arg("--word-diff"), @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: the following required arguments were not provided:
+      --show-diff-on-failure
+
+    Usage: prek run --show-diff-on-failure --word-diff [HOOK|PROJECT]...
+
+    For more information, try '--help'.
+    ");
+
+    Ok(())
+}
+
+/// `--show-fixes` lists the files modified by each hook, with the lines added and removed.
+#[cfg(unix)]
+#[test]
+fn show_fixes() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+    context.disable_auto_crlf();
+
+    context.write_pre_commit_config(indoc::indoc! {r#"
+        repos:
+          - repo: builtin
+            hooks:
+              - id: trailing-whitespace
+          - repo: local
+            hooks:
+              - id: append
+                name: append
+                language: system
+                entry: sh -c 'echo "appended" >> a.txt && echo "appended" >> b.txt'
+                pass_filenames: false
+              - id: check
+                name: check
+                language: system
+                entry: "true"
+    "#});
+    context
+        .work_dir()
+        .child("a.txt")
+        .write_str("first  \nsecond  \nthird\n")?;
+    context.work_dir().child("b.txt").write_str("first\n")?;
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run().arg("--show-fixes"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    trim trailing whitespace.................................................Failed
+    - hook id: trailing-whitespace
+    - exit code: 1
+    - files were modified by this hook
+
+      Fixing a.txt
+    append...................................................................Failed
+    - hook id: append
+    - files were modified by this hook
+    check....................................................................Passed
+
+    Files modified by hooks:
+      trailing-whitespace
+        a.txt +3 -2
+      append
+        a.txt +3 -2
+        b.txt +1 -0
+
+    ----- stderr -----
+    ");
+
+    // Nothing to list when no hook modifies files.
+    context.git_add(".");
+    cmd_snapshot!(context.filters(), context.run().arg("--show-fixes").arg("check"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    check....................................................................Passed
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
+
+/// `--only-fixers` and `--only-checkers` run hooks by their `kind`.
+#[test]
+fn only_fixers_and_checkers() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: builtin
+            hooks:
+              - id: trailing-whitespace
+              - id: check-json
+          - repo: local
+            hooks:
+              - id: custom-checker
+                name: custom-checker
+                language: fail
+                entry: custom checker ran
+                kind: checker
+              - id: unclassified
+                name: unclassified
+                language: fail
+                entry: unclassified ran
+    "});
+    context.work_dir().child("file.json").write_str("{}  \n")?;
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run().arg("--only-fixers"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    trim trailing whitespace.................................................Failed
+    - hook id: trailing-whitespace
+    - exit code: 1
+    - files were modified by this hook
+
+      Fixing file.json
+
+    ----- stderr -----
+    ");
+
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run().arg("--only-checkers"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    check json...............................................................Passed
+    custom-checker...........................................................Failed
+    - hook id: custom-checker
+    - exit code: 1
+
+      custom checker ran
+
+      file.json
+      .pre-commit-config.yaml
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.run().arg("--only-fixers").arg("--only-checkers"), @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: the argument '--only-fixers' cannot be used with '--only-checkers'
+
+    Usage: prek run --only-fixers [HOOK|PROJECT]...
+
+    For more information, try '--help'.
+    ");
+
+    Ok(())
+}
+
+/// `--modified-exit-code` is used when the only failures are hooks that modified files.
+#[test]
+fn modified_exit_code() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: builtin
+            hooks:
+              - id: trailing-whitespace
+    "});
+    context.work_dir().child("file.txt").write_str("line  \n")?;
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run().arg("--modified-exit-code=3"), @r"
+    success: false
+    exit_code: 3
+    ----- stdout -----
+    trim trailing whitespace.................................................Failed
+    - hook id: trailing-whitespace
+    - exit code: 1
+    - files were modified by this hook
+
+      Fixing file.txt
+
+    ----- stderr -----
+    ");
+
+    // A failing check takes precedence over the modifications.
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: builtin
+            hooks:
+              - id: trailing-whitespace
+          - repo: local
+            hooks:
+              - id: checker
+                name: checker
+                language: fail
+                entry: checker failed
+    "});
+    context.work_dir().child("file.txt").write_str("line  \n")?;
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run().arg("--modified-exit-code=3"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    trim trailing whitespace.................................................Failed
+    - hook id: trailing-whitespace
+    - exit code: 1
+    - files were modified by this hook
+
+      Fixing file.txt
+    checker..................................................................Failed
+    - hook id: checker
+    - exit code: 1
+
+      checker failed
+
+      file.txt
+      .pre-commit-config.yaml
+
+    ----- stderr -----
+    ");
+
+    // A hook that failed alongside hooks that modified files is only attributed to the
+    // modifications with `kind: fixer`.
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: builtin
+            hooks:
+              - id: trailing-whitespace
+                priority: 0
+          - repo: local
+            hooks:
+              - id: lint
+                name: lint
+                language: fail
+                entry: lint failed
+                priority: 0
+    "});
+    context.work_dir().child("file.txt").write_str("line  \n")?;
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run().arg("--modified-exit-code=3"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    Files were modified by following hooks...................................Failed
+      ┌ trim trailing whitespace.............................................Failed
+      │ - hook id: trailing-whitespace
+      │ - exit code: 1
+      │
+      │ Fixing file.txt
+      └ lint.................................................................Failed
+      │ - hook id: lint
+      │ - exit code: 1
+      │
+      │ lint failed
+      │
+      │ file.txt
+      │ .pre-commit-config.yaml
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.run().arg("--modified-exit-code=1"), @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: invalid value '1' for '--modified-exit-code <N>': 1 is not in 2..=255
+
+    For more information, try '--help'.
+    ");
+
+    Ok(())
+}
+
+/// `--autofix-stage` stages the fixes of hooks, but not unrelated unstaged changes.
+#[test]
+fn autofix_stage() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    context.write_pre_commit_config(indoc::indoc! {r#"
+        repos:
+          - repo: builtin
+            hooks:
+              - id: trailing-whitespace
+          - repo: local
+            hooks:
+              - id: no-trailing-whitespace
+                name: no-trailing-whitespace
+                language: system
+                entry: python3 -c 'import sys; sys.exit(any(l.rstrip("\n").endswith(" ") for f in sys.argv[1:] for l in open(f)))'
+                kind: checker
+    "#});
+    let cwd = context.work_dir();
+    cwd.child("file.txt").write_str("line  \n")?;
+    cwd.child("other.txt").write_str("other\n")?;
+    context.git_add(".");
+    // An unrelated unstaged change.
+    cwd.child("other.txt").write_str("other  \n")?;
+
+    let filters: Vec<_> = context
+        .filters()
+        .into_iter()
+        .chain([(r"/\d+-\d+.patch", "/[TIME]-[PID].patch")])
+        .collect();
+
+    cmd_snapshot!(filters.clone(), context.run().arg("--autofix-stage"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    trim trailing whitespace.................................................Failed
+    - hook id: trailing-whitespace
+    - exit code: 1
+    - files were modified by this hook
+
+      Fixing file.txt
+    no-trailing-whitespace...................................................Passed
+
+    Staged the fixes of 1 file
+
+    Running the hooks again on the fixed files:
+    no-trailing-whitespace...................................................Passed
+
+    ----- stderr -----
+    Unstaged changes detected, stashing unstaged changes to `[HOME]/patches/[TIME]-[PID].patch`
+    Restored working tree changes from `[HOME]/patches/[TIME]-[PID].patch`
+    ");
+
+    let staged = Command::new("git")
+        .args(["diff", "--cached", "--name-only"])
+        .current_dir(cwd)
+        .output()?;
+    assert_snapshot!(String::from_utf8_lossy(&staged.stdout), @r"
+    .pre-commit-config.yaml
+    file.txt
+    other.txt
+    ");
+    assert_eq!(context.read("other.txt"), "other  \n");
+
+    // Fixes of files with unstaged changes are not staged.
+    cwd.child("file.txt").write_str("line  \nmore\n")?;
+    context.git_add(".");
+    cwd.child("file.txt").write_str("line  \nmore  \n")?;
+
+    cmd_snapshot!(filters, context.run().arg("--autofix-stage"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    trim trailing whitespace.................................................Failed
+    - hook id: trailing-whitespace
+    - exit code: 1
+    - files were modified by this hook
 
-    ----- stderr -----
-    ");
+      Fixing file.txt
+      Fixing other.txt
+    no-trailing-whitespace...................................................Passed
 
-    cmd_snapshot!(context.filters(), context.run().env("COMPLETE", "fish").arg("--").arg("prek").arg("app/lib/"), @r"
-    success: true
-    exit_code: 0
-    ----- stdout -----
-    app/lib/
+    Staged the fixes of 1 file
 
     ----- stderr -----
+    Unstaged changes detected, stashing unstaged changes to `[HOME]/patches/[TIME]-[PID].patch`
+    warning: Not staging the fixes of files with unstaged changes: `file.txt`
+    Stashed changes conflicted with changes made by hook, rolling back the hook changes
+    Restored working tree changes from `[HOME]/patches/[TIME]-[PID].patch`
     ");
 
     Ok(())
 }
 
-/// Test reusing hook environments only when dependencies are exactly same. (ignore order)
+/// `success_exit_codes` and `fail_on_output` decide whether a hook passed.
 #[test]
-fn reuse_env() -> Result<()> {
+fn success_exit_codes_and_fail_on_output() {
     let context = TestContext::new();
     context.init_project();
+    context.write_pre_commit_config(indoc::indoc! {r#"
+        repos:
+          - repo: local
+            hooks:
+              - id: warns
+                name: warns
+                language: system
+                entry: python3 -c "import sys; print('warning'); sys.exit(1)"
+                pass_filenames: false
+                success_exit_codes: [0, 1]
+              - id: errors
+                name: errors
+                language: system
+                entry: python3 -c "import sys; sys.exit(2)"
+                pass_filenames: false
+                success_exit_codes: [0, 1]
+              - id: noisy
+                name: noisy
+                language: system
+                entry: python3 -c "print('something')"
+                pass_filenames: false
+                fail_on_output: true
+              - id: quiet
+                name: quiet
+                language: system
+                entry: python3 -c "pass"
+                pass_filenames: false
+                fail_on_output: true
+    "#});
+    context.git_add(".");
 
-    let pkg_dir = context.work_dir().child("local_pkg");
-    pkg_dir.create_dir_all()?;
-    pkg_dir.child("setup.py").write_str(indoc::indoc! {r#"
-        from setuptools import setup
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    warns....................................................................Passed
+    errors...................................................................Failed
+    - hook id: errors
+    - exit code: 2 (expected 0, 1)
+    noisy....................................................................Failed
+    - hook id: noisy
+    - failed because the hook printed output (`fail_on_output`)
 
-        setup(
-            name="local-pkg",
-            version="0.1.0",
-            py_modules=["local_pkg"],
-        )
-    "#})?;
-    pkg_dir
-        .child("local_pkg.py")
-        .write_str("def hello():\n     print('hello')\n")?;
+      something
+    quiet....................................................................Passed
+
+    ----- stderr -----
+    ");
+}
 
+/// `max_files_per_invocation` limits the files of each invocation, and the output of the
+/// invocations is reported in the order they started, even when `a.txt` finishes last.
+#[test]
+fn max_files_per_invocation() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
     context.write_pre_commit_config(indoc::indoc! {r#"
-    repos:
-      - repo: local
-        hooks:
-          - id: reuse-env
-            name: reuse-env
-            language: python
-            entry: python -c "import local_pkg; local_pkg.hello()"
-            pass_filenames: false
-            additional_dependencies: ["./local_pkg"]
-            verbose: true
+        repos:
+          - repo: local
+            hooks:
+              - id: per-file
+                name: per-file
+                language: system
+                entry: python3 -c "import sys, time; time.sleep(0.5 if sys.argv[1] == 'a.txt' else 0); print(sys.argv[1:])"
+                files: \.txt$
+                max_files_per_invocation: 1
+                verbose: true
     "#});
+    for name in ["a.txt", "b.txt", "c.txt", "d.txt", "e.txt"] {
+        context.work_dir().child(name).write_str("content")?;
+    }
     context.git_add(".");
 
     cmd_snapshot!(context.filters(), context.run(), @r"
     success: true
     exit_code: 0
     ----- stdout -----
-    reuse-env................................................................Passed
-    - hook id: reuse-env
+    per-file.................................................................Passed
+    - hook id: per-file
     - duration: [TIME]
 
-      hello
+      ['b.txt']
+      ['a.txt']
+      ['e.txt']
+      ['c.txt']
+      ['d.txt']
 
     ----- stderr -----
     ");
 
-    // Remove dependencies, so the environment should not be reused.
-    context.write_pre_commit_config(indoc::indoc! {r#"
-    repos:
-      - repo: local
-        hooks:
-          - id: reuse-env
-            name: reuse-env
-            language: python
-            entry: python -c "print('ok')"
-            pass_filenames: false
-            verbose: true
-    "#});
+    Ok(())
+}
+
+/// Failed hooks are retried up to `retries` times before being reported as failed.
+#[test]
+fn hook_retries() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: flaky
+                name: flaky
+                language: system
+                entry: python3 flaky.py
+                pass_filenames: false
+                retries: 2
+                verbose: true
+              - id: broken
+                name: broken
+                language: system
+                entry: python3 -c 'import sys; sys.exit(1)'
+                pass_filenames: false
+                retries: 1
+                retry_delay: 0.1
+    "});
+    context
+        .work_dir()
+        .child("flaky.py")
+        .write_str(indoc::indoc! {r"
+        import pathlib
+        import sys
+
+        counter = pathlib.Path('.git/attempts')
+        attempt = int(counter.read_text()) + 1 if counter.exists() else 1
+        counter.write_text(str(attempt))
+        print(f'attempt {attempt}')
+        sys.exit(0 if attempt >= 2 else 1)
+    "})?;
     context.git_add(".");
 
     cmd_snapshot!(context.filters(), context.run(), @r"
-    success: true
-    exit_code: 0
+    success: false
+    exit_code: 1
     ----- stdout -----
-    reuse-env................................................................Passed
-    - hook id: reuse-env
+    flaky....................................................................Passed
+    - hook id: flaky
     - duration: [TIME]
+    - attempts: 2 of 3
 
-      ok
+      attempt 2
+    broken...................................................................Failed
+    - hook id: broken
+    - attempts: 2 of 2
+    - exit code: 1
 
     ----- stderr -----
     ");
 
-    // There should be two hook environments.
-    assert_eq!(context.home_dir().child("hooks").read_dir()?.count(), 2);
-
     Ok(())
 }
 
+/// An invalid `retry_delay` is rejected.
 #[test]
-fn dry_run() {
+fn hook_retry_delay_invalid() {
     let context = TestContext::new();
     context.init_project();
     context.write_pre_commit_config(indoc::indoc! {r"
         repos:
           - repo: local
             hooks:
-              - id: fail
-                name: fail
-                entry: fail
-                language: fail
+              - id: flaky
+                name: flaky
+                language: system
+                entry: python3 -c 'pass'
+                retries: 1
+                retry_delay: -1
     "});
     context.git_add(".");
 
-    // Run with `--dry-run`
-    cmd_snapshot!(context.filters(), context.run().arg("--dry-run").arg("-v"), @r"
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: Failed to init hooks
+      caused by: Invalid hook `flaky`
+      caused by: Invalid `retry_delay: -1`, expected a non-negative number of seconds
+    ");
+
+    // Too large to be a duration.
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: flaky
+                name: flaky
+                language: system
+                entry: python3 -c 'pass'
+                retries: 1
+                retry_delay: 1e20
+    "});
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: Failed to init hooks
+      caused by: Invalid hook `flaky`
+      caused by: Invalid `retry_delay: 100000000000000000000`, expected a non-negative number of seconds
+    ");
+}
+
+/// Hooks restricted to other platforms with `os` / `arch` are skipped instead of run.
+#[test]
+fn hook_platform_constraints() {
+    let context = TestContext::new();
+    context.init_project();
+    context.write_pre_commit_config(indoc::indoc! {r#"
+        repos:
+          - repo: local
+            hooks:
+              - id: everywhere
+                name: everywhere
+                language: system
+                entry: python3 -c 'print("ran")'
+                pass_filenames: false
+                os: [linux, macos, windows]
+                verbose: true
+              - id: legacy
+                name: legacy
+                language: system
+                entry: python3 -c 'import sys; sys.exit(1)'
+                pass_filenames: false
+                arch: [x86, arm]
+    "#});
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
     success: true
     exit_code: 0
     ----- stdout -----
-    fail....................................................................Dry Run
-    - hook id: fail
+    everywhere...............................................................Passed
+    - hook id: everywhere
     - duration: [TIME]
 
-      `fail` would be run on 1 files:
-      - .pre-commit-config.yaml
+      ran
+    legacy............................................(unsupported platform)Skipped
+
+    ----- stderr -----
+    ");
+
+    // Only the skipped hooks, nothing to run.
+    cmd_snapshot!(context.filters(), context.run().arg("legacy"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    legacy............................................(unsupported platform)Skipped
 
     ----- stderr -----
     ");
 }
 
-/// Supports reading `pre-commit-config.yml` as well.
+/// Files marked `skip-worktree` or `assume-unchanged` are reported to fixers, and skipped with
+/// `exclude_skip_worktree`.
 #[test]
-fn alternate_config_file() -> Result<()> {
+fn skip_worktree_files() -> Result<()> {
     let context = TestContext::new();
     context.init_project();
 
-    context
-        .work_dir()
-        .child(ALT_CONFIG_FILE)
-        .write_str(indoc::indoc! {r#"
+    let config = indoc::indoc! {r"
         repos:
           - repo: local
             hooks:
-              - id: local-python-hook
-                name: local-python-hook
-                language: python
-                entry: python3 -c 'import sys; print("Hello, world!")'
-    "#})?;
+              - id: fixer
+                name: fixer
+                language: fail
+                entry: fixer ran
+                files: '\.txt$'
+                kind: fixer
+    "};
+    context.write_pre_commit_config(config);
+    let cwd = context.work_dir();
+    cwd.child("normal.txt").write_str("normal")?;
+    cwd.child("sparse.txt").write_str("sparse")?;
+    cwd.child("unchanged.txt").write_str("unchanged")?;
     context.git_add(".");
 
-    cmd_snapshot!(context.filters(), context.run().arg("-v"), @r"
-    success: true
-    exit_code: 0
+    for (flag, file) in [
+        ("--skip-worktree", "sparse.txt"),
+        ("--assume-unchanged", "unchanged.txt"),
+    ] {
+        Command::new("git")
+            .arg("update-index")
+            .arg(flag)
+            .arg(file)
+            .current_dir(cwd)
+            .assert()
+            .success();
+    }
+
+    cmd_snapshot!(context.filters(), context.run().arg("--all-files"), @r"
+    success: false
+    exit_code: 1
     ----- stdout -----
-    local-python-hook........................................................Passed
-    - hook id: local-python-hook
-    - duration: [TIME]
+    fixer....................................................................Failed
+    - hook id: fixer
+    - exit code: 1
 
-      Hello, world!
+      fixer ran
+
+      sparse.txt
+      normal.txt
+      unchanged.txt
 
     ----- stderr -----
+    warning: Hook `fixer` may modify files marked `skip-worktree` or `assume-unchanged`, git will not notice changes to them: `sparse.txt, unchanged.txt`
+    Set `exclude_skip_worktree: true` in the config to skip these files.
     ");
 
-    context
-        .work_dir()
-        .child(CONFIG_FILE)
-        .write_str(indoc::indoc! {r#"
+    context.write_pre_commit_config(&format!("exclude_skip_worktree: true\n{config}"));
+    context.git_add(".pre-commit-config.yaml");
+
+    cmd_snapshot!(context.filters(), context.run().arg("--all-files"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    fixer....................................................................Failed
+    - hook id: fixer
+    - exit code: 1
+
+      fixer ran
+
+      normal.txt
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
+
+/// Submodule gitlink entries are not passed to hooks.
+#[test]
+fn submodules_excluded() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+    context.configure_git_author();
+
+    let submodule = TestContext::new_at(context.work_dir().child("lib").to_path_buf());
+    submodule.init_project();
+    submodule.configure_git_author();
+    submodule.work_dir().child("lib.txt").write_str("lib")?;
+    submodule.git_add(".");
+    submodule.git_commit("Initial commit");
+
+    Command::new("git")
+        .args([
+            "-c",
+            "protocol.file.allow=always",
+            "submodule",
+            "add",
+            "./lib",
+        ])
+        .current_dir(context.work_dir())
+        .assert()
+        .success();
+
+    context.write_pre_commit_config(indoc::indoc! {r"
         repos:
           - repo: local
             hooks:
-              - id: local-python-hook
-                name: local-python-hook
-                language: python
-                entry: python3 -c 'import sys; print("Hello, world!")'
-    "#})?;
+              - id: directories
+                name: directories
+                language: fail
+                entry: got directories
+                types: [directory]
+              - id: files
+                name: files
+                language: fail
+                entry: got files
+    "});
     context.git_add(".");
 
-    cmd_snapshot!(context.filters(), context.run().arg("--refresh").arg("-v"), @r"
-    success: true
-    exit_code: 0
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: false
+    exit_code: 1
     ----- stdout -----
-    local-python-hook........................................................Passed
-    - hook id: local-python-hook
-    - duration: [TIME]
+    directories..........................................(no files to check)Skipped
+    files....................................................................Failed
+    - hook id: files
+    - exit code: 1
 
-      Hello, world!
+      got files
+
+      .pre-commit-config.yaml
+      .gitmodules
 
     ----- stderr -----
-    warning: Both `[TEMP_DIR]/.pre-commit-config.yaml` and `[TEMP_DIR]/.pre-commit-config.yml` exist, using `[TEMP_DIR]/.pre-commit-config.yaml` only
     ");
 
     Ok(())
 }
 
+/// With `--recurse-submodules`, the configs of submodules are projects of the workspace, and the
+/// files of submodules are passed to hooks.
 #[test]
-fn show_diff_on_failure() -> Result<()> {
+fn recurse_submodules() -> Result<()> {
     let context = TestContext::new();
     context.init_project();
-    context.disable_auto_crlf();
+    context.configure_git_author();
 
-    let config = indoc::indoc! {r#"
+    let submodule = TestContext::new_at(context.work_dir().child("lib").to_path_buf());
+    submodule.init_project();
+    submodule.configure_git_author();
+    submodule.work_dir().child("lib.txt").write_str("lib")?;
+    submodule.write_pre_commit_config(indoc::indoc! {r"
         repos:
           - repo: local
             hooks:
-              - id: modify
-                name: modify
-                language: python
-                entry: python -c "import sys; open('file.txt', 'a').write('Added line\n')"
-                pass_filenames: false
-    "#};
-    context.write_pre_commit_config(config);
-    context
-        .work_dir()
-        .child("file.txt")
-        .write_str("Original line\n")?;
-    context.git_add(".");
-
-    let mut filters = context.filters();
-    filters.push((r"index \w{7}\.\.\w{7} \d{6}", "index [OLD]..[NEW] 100644"));
-
-    // When failed in CI environment
-    cmd_snapshot!(filters.clone(), context.run().env(EnvVars::CI, "1").arg("--show-diff-on-failure").arg("-v"), @r"
-    success: false
-    exit_code: 1
-    ----- stdout -----
-    modify...................................................................Failed
-    - hook id: modify
-    - duration: [TIME]
-    - files were modified by this hook
-
-    Hint: Some hooks made changes to the files.
-    If you are seeing this message in CI, reproduce locally with: `prek run --all-files`
-    To run prek as part of git workflow, use `prek install` to set up git hooks.
-
-    All changes made by hooks:
-    diff --git a/file.txt b/file.txt
-    index [OLD]..[NEW] 100644
-    --- a/file.txt
-    +++ b/file.txt
-    @@ -1 +1,2 @@
-     Original line
-    +Added line
+              - id: lib-files
+                name: lib-files
+                language: fail
+                entry: got lib files
+    "});
+    submodule.git_add(".");
+    submodule.git_commit("Initial commit");
 
-    ----- stderr -----
-    ");
+    Command::new("git")
+        .args([
+            "-c",
+            "protocol.file.allow=always",
+            "submodule",
+            "add",
+            "./lib",
+        ])
+        .current_dir(context.work_dir())
+        .assert()
+        .success();
 
-    context
-        .work_dir()
-        .child("file.txt")
-        .write_str("Original line\n")?;
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: files
+                name: files
+                language: fail
+                entry: got files
+    "});
     context.git_add(".");
-    // When failed in non-CI environment
-    cmd_snapshot!(filters.clone(), context.run().env_remove(EnvVars::CI).arg("--show-diff-on-failure").arg("-v"), @r"
+
+    cmd_snapshot!(context.filters(), context.run().arg("--all-files").arg("--recurse-submodules"), @r"
     success: false
     exit_code: 1
     ----- stdout -----
-    modify...................................................................Failed
-    - hook id: modify
-    - duration: [TIME]
-    - files were modified by this hook
-    All changes made by hooks:
-    diff --git a/file.txt b/file.txt
-    index [OLD]..[NEW] 100644
-    --- a/file.txt
-    +++ b/file.txt
-    @@ -1 +1,2 @@
-     Original line
-    +Added line
+    Running hooks for `lib`:
+    lib-files................................................................Failed
+    - hook id: lib-files
+    - exit code: 1
 
-    ----- stderr -----
-    ");
+      got lib files
 
-    // Run in the `app` subproject.
-    let app = context.work_dir().child("app");
-    app.create_dir_all()?;
-    app.child("file.txt").write_str("Original line\n")?;
-    app.child(CONFIG_FILE).write_str(config)?;
+      lib.txt
+      .pre-commit-config.yaml
 
-    Command::new("git")
-        .arg("add")
-        .arg(".")
-        .current_dir(&app)
-        .assert()
-        .success();
+    Running hooks for `.`:
+    files....................................................................Failed
+    - hook id: files
+    - exit code: 1
 
-    cmd_snapshot!(filters.clone(), context.run().env_remove(EnvVars::CI).current_dir(&app).arg("--show-diff-on-failure"), @r"
-    success: false
-    exit_code: 1
-    ----- stdout -----
-    modify...................................................................Failed
-    - hook id: modify
-    - files were modified by this hook
-    All changes made by hooks:
-    diff --git a/app/file.txt b/app/file.txt
-    index [OLD]..[NEW] 100644
-    --- a/app/file.txt
-    +++ b/app/file.txt
-    @@ -1 +1,2 @@
-     Original line
-    +Added line
+      got files
+
+      .pre-commit-config.yaml
+      .gitmodules
+      lib/lib.txt
+      lib/.pre-commit-config.yaml
 
     ----- stderr -----
     ");
 
+    // Only the files staged in the submodule are passed to hooks without `--all-files`.
+    context.write_pre_commit_config(indoc::indoc! {r"
+        recurse_submodules: true
+        repos:
+          - repo: local
+            hooks:
+              - id: files
+                name: files
+                language: fail
+                entry: got files
+    "});
     context.git_add(".");
+    context
+        .work_dir()
+        .child("lib/staged.txt")
+        .write_str("staged")?;
+    context
+        .work_dir()
+        .child("lib/unstaged.txt")
+        .write_str("unstaged")?;
+    submodule.git_add("staged.txt");
 
-    // Run in the root
-    // Since we add a new subproject, use `--refresh` to find that.
-    cmd_snapshot!(filters.clone(), context.run().env_remove(EnvVars::CI).arg("--show-diff-on-failure").arg("--refresh"), @r"
+    cmd_snapshot!(context.filters(), context.run(), @r"
     success: false
     exit_code: 1
     ----- stdout -----
-    Running hooks for `app`:
-    modify...................................................................Failed
-    - hook id: modify
-    - files were modified by this hook
+    Running hooks for `lib`:
+    lib-files................................................................Failed
+    - hook id: lib-files
+    - exit code: 1
+
+      got lib files
+
+      staged.txt
 
     Running hooks for `.`:
-    modify...................................................................Failed
-    - hook id: modify
-    - files were modified by this hook
-    All changes made by hooks:
-    diff --git a/app/file.txt b/app/file.txt
-    index [OLD]..[NEW] 100644
-    --- a/app/file.txt
-    +++ b/app/file.txt
-    @@ -1,2 +1,3 @@
-     Original line
-     Added line
-    +Added line
-    diff --git a/file.txt b/file.txt
-    index [OLD]..[NEW] 100644
-    --- a/file.txt
-    +++ b/file.txt
-    @@ -1,2 +1,3 @@
-     Original line
-     Added line
-    +Added line
+    files....................................................................Failed
+    - hook id: files
+    - exit code: 1
+
+      got files
+
+      .pre-commit-config.yaml
+      .gitmodules
+      lib/staged.txt
 
     ----- stderr -----
     ");
@@ -2715,6 +5115,219 @@ fn run_log_file() {
         .assert(predicate::path::exists());
 }
 
+/// Test `prek run --write-summary <file>` flag.
+#[test]
+fn run_write_summary() {
+    let context = TestContext::new();
+    context.init_project();
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: success
+                name: success
+                entry: echo
+                language: system
+              - id: fail
+                name: fail
+                entry: fail
+                language: fail
+              - id: no-files
+                name: no-files
+                entry: echo
+                language: system
+                files: \.rs$
+    "});
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run().arg("--write-summary").arg("summary/run.json"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    success..................................................................Passed
+    fail.....................................................................Failed
+    - hook id: fail
+    - exit code: 1
+
+      fail
+
+      .pre-commit-config.yaml
+    no-files.............................................(no files to check)Skipped
+
+    ----- stderr -----
+    ");
+
+    let filters = context
+        .filters()
+        .into_iter()
+        .chain([
+            (r#""duration": [\d.e-]+"#, r#""duration": [DURATION]"#),
+            (env!("CARGO_PKG_VERSION"), "[VERSION]"),
+        ])
+        .collect::<Vec<_>>();
+    insta::with_settings!(
+        { filters => filters.clone() },
+        {
+            assert_snapshot!(context.read("summary/run.json"), @r#"
+            {
+              "version": "[VERSION]",
+              "status": "failed",
+              "duration": [DURATION],
+              "counts": {
+                "passed": 1,
+                "failed": 1,
+                "skipped": 1,
+                "dry_run": 0,
+                "cancelled": 0
+              },
+              "hooks": [
+                {
+                  "id": "success",
+                  "name": "success",
+                  "project": ".",
+                  "status": "passed",
+                  "exit_code": 0,
+                  "duration": [DURATION]
+                },
+                {
+                  "id": "fail",
+                  "name": "fail",
+                  "project": ".",
+                  "status": "failed",
+                  "exit_code": 1,
+                  "duration": [DURATION]
+                },
+                {
+                  "id": "no-files",
+                  "name": "no-files",
+                  "project": ".",
+                  "status": "skipped",
+                  "skip_reason": "no-files",
+                  "exit_code": 0,
+                  "duration": [DURATION]
+                }
+              ]
+            }
+            "#);
+        }
+    );
+
+    cmd_snapshot!(context.filters(), context.run().arg("--write-summary").arg("summary.md"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    success..................................................................Passed
+    fail.....................................................................Failed
+    - hook id: fail
+    - exit code: 1
+
+      fail
+
+      .pre-commit-config.yaml
+    no-files.............................................(no files to check)Skipped
+
+    ----- stderr -----
+    ");
+    insta::with_settings!(
+        { filters => filters },
+        {
+            assert_snapshot!(context.read("summary.md"), @r"
+            ## prek run: failed
+
+            1 passed, 1 failed, 1 skipped in [TIME] (prek [VERSION])
+
+            | Hook | Project | Status | Exit code | Duration |
+            | --- | --- | --- | --- | --- |
+            | `success` | `.` | passed | 0 | [TIME] |
+            | `fail` | `.` | failed | 1 | [TIME] |
+            | `no-files` | `.` | skipped (no-files) | 0 | [TIME] |
+            ");
+        }
+    );
+
+    // A relative path is relative to the current directory, not to the workspace root.
+    let sub = context.work_dir().child("sub");
+    sub.create_dir_all().unwrap();
+    context
+        .run()
+        .current_dir(&sub)
+        .arg("--write-summary")
+        .arg("out.json")
+        .assert()
+        .failure();
+    sub.child("out.json").assert(predicate::path::exists());
+    context
+        .work_dir()
+        .child("out.json")
+        .assert(predicate::path::missing());
+}
+
+/// Test `prek run --reporter exec:<command>` streams events to the command.
+#[cfg(unix)]
+#[test]
+fn run_exec_reporter() {
+    let context = TestContext::new();
+    context.init_project();
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: success
+                name: success
+                entry: echo
+                language: system
+              - id: no-files
+                name: no-files
+                entry: echo
+                language: system
+                files: \.rs$
+    "});
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run().arg("--reporter").arg("exec:sh -c 'cat > events.jsonl'"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    success..................................................................Passed
+    no-files.............................................(no files to check)Skipped
+
+    ----- stderr -----
+    ");
+
+    let filters = context
+        .filters()
+        .into_iter()
+        .chain([
+            (r#""duration":[\d.e-]+"#, r#""duration":[DURATION]"#),
+            (env!("CARGO_PKG_VERSION"), "[VERSION]"),
+        ])
+        .collect::<Vec<_>>();
+    insta::with_settings!(
+        { filters => filters },
+        {
+            assert_snapshot!(context.read("events.jsonl"), @r#"
+            {"event":"run-started","version":"[VERSION]","hooks":2,"files":1}
+            {"event":"hook-started","id":"success","name":"success","project":".","files":1}
+            {"event":"hook-finished","id":"success","name":"success","project":".","status":"passed","exit_code":0,"duration":[DURATION],"files":1}
+            {"event":"hook-finished","id":"no-files","name":"no-files","project":".","status":"skipped","skip_reason":"no-files","exit_code":0,"duration":[DURATION],"files":0}
+            {"event":"run-finished","version":"[VERSION]","status":"passed","duration":[DURATION],"counts":{"passed":1,"failed":0,"skipped":1,"dry_run":0,"cancelled":0},"hooks":[{"id":"success","name":"success","project":".","status":"passed","exit_code":0,"duration":[DURATION]},{"id":"no-files","name":"no-files","project":".","status":"skipped","skip_reason":"no-files","exit_code":0,"duration":[DURATION]}]}
+            "#);
+        }
+    );
+
+    // The reporter must be given as `exec:<command>`.
+    cmd_snapshot!(context.filters(), context.run().arg("--reporter").arg("slack"), @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: invalid value 'slack' for '--reporter <exec:COMMAND>': expected `exec:<command>`
+
+    For more information, try '--help'.
+    ");
+}
+
 /// Test `language_version: system` works and disables downloading.
 #[test]
 fn system_language_version() {
@@ -0,0 +1,87 @@
+use assert_fs::fixture::{FileWriteStr, PathChild};
+
+use crate::common::TestContext;
+
+mod common;
+
+fn frame(message: &serde_json::Value) -> String {
+    let content = message.to_string();
+    format!("Content-Length: {}\r\n\r\n{content}", content.len())
+}
+
+/// Split the framed messages, one JSON message per line.
+fn unframe(stdout: &[u8]) -> String {
+    let stdout = String::from_utf8_lossy(stdout);
+    let mut rest = stdout.as_ref();
+    let mut messages = Vec::new();
+    while let Some((header, body)) = rest.split_once("\r\n\r\n") {
+        let length: usize = header
+            .strip_prefix("Content-Length: ")
+            .expect("a `Content-Length` header")
+            .parse()
+            .expect("a valid length");
+        messages.push(&body[..length]);
+        rest = &body[length..];
+    }
+    messages.join("\n")
+}
+
+#[test]
+fn serve_check() -> anyhow::Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    context.write_pre_commit_config(indoc::indoc! {r#"
+        repos:
+          - repo: local
+            hooks:
+              - id: no-todo
+                name: no-todo
+                language: system
+                entry: sh -c '! grep -n TODO "$@"' --
+                types: [text]
+              - id: python
+                name: python
+                language: system
+                entry: echo
+                files: \.py$
+    "#});
+    let cwd = context.work_dir();
+    cwd.child("todo.txt").write_str("hello\nTODO: fix\n")?;
+    cwd.child("done.txt").write_str("hello\n")?;
+
+    let saved = url::Url::from_file_path(cwd.child("todo.txt").path()).unwrap();
+    let input = [
+        serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "prek/check", "params": {"files": ["todo.txt", "done.txt"]}}),
+        serde_json::json!({"jsonrpc": "2.0", "method": "textDocument/didSave", "params": {"textDocument": {"uri": saved}}}),
+        serde_json::json!({"jsonrpc": "2.0", "id": 2, "method": "unknown"}),
+        serde_json::json!({"jsonrpc": "2.0", "id": 3, "method": "shutdown"}),
+        serde_json::json!({"jsonrpc": "2.0", "method": "exit"}),
+    ]
+    .iter()
+    .map(frame)
+    .collect::<String>();
+
+    let mut cmd = context.command();
+    cmd.arg("serve").arg("--check-on-save");
+    let output = assert_cmd::Command::from_std(cmd)
+        .write_stdin(input)
+        .assert()
+        .success();
+    let responses = unframe(&output.get_output().stdout);
+
+    let mut settings = insta::Settings::clone_current();
+    for (matcher, replacement) in context.filters() {
+        settings.add_filter(matcher, replacement);
+    }
+    settings.bind(|| {
+        insta::assert_snapshot!(responses, @r#"
+        {"id":1,"jsonrpc":"2.0","result":{"hooks":[{"exit_code":1,"files":["todo.txt","done.txt"],"id":"no-todo","name":"no-todo","output":"todo.txt:2:TODO: fix/n","passed":false,"project":"."}]}}
+        {"jsonrpc":"2.0","method":"textDocument/publishDiagnostics","params":{"diagnostics":[{"code":"no-todo","message":"no-todo failed/n2:TODO: fix","range":{"end":{"character":0,"line":0},"start":{"character":0,"line":0}},"severity":1,"source":"prek"}],"uri":"file://[TEMP_DIR]/todo.txt"}}
+        {"error":{"code":-32601,"message":"Unknown method `unknown`"},"id":2,"jsonrpc":"2.0"}
+        {"id":3,"jsonrpc":"2.0","result":null}
+        "#);
+    });
+
+    Ok(())
+}
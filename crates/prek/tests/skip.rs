@@ -0,0 +1,141 @@
+use assert_fs::fixture::{FileWriteStr, PathChild, PathCreateDir};
+
+use crate::common::{TestContext, cmd_snapshot};
+
+mod common;
+
+#[test]
+fn skip_add_remove_list() -> anyhow::Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    let config = indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: lint
+                name: Lint
+                language: system
+                entry: echo
+                pass_filenames: false
+              - id: mypy
+                name: Mypy
+                language: system
+                entry: echo
+                pass_filenames: false
+    "};
+    context.work_dir().child("app").create_dir_all()?;
+    context.setup_workspace(&["app"], config)?;
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.command().args(["skip", "list"]), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    No hooks are skipped in this repository, add skips with `prek skip add`
+    ");
+
+    cmd_snapshot!(context.filters(), context.command().args(["skip", "add", "mypy", "app/"]), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Skipping `mypy`
+    Skipping `app/`
+
+    ----- stderr -----
+    ");
+
+    // Project paths are resolved against the current directory.
+    cmd_snapshot!(context.filters(), context.command().args(["skip", "add", "./", "mypy"]).current_dir(context.work_dir().child("app")), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    `app/` is already skipped
+    `mypy` is already skipped
+
+    ----- stderr -----
+    ");
+
+    insta::assert_snapshot!(context.read(".git/prek/skips"), @r"
+    mypy
+    app/
+    ");
+
+    cmd_snapshot!(context.filters(), context.command().args(["skip", "list"]), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    mypy
+    app/
+
+    ----- stderr -----
+    Stored in `.git/prek/skips`
+    ");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lint.....................................................................Passed
+    Mypy...................................................(skipped locally)Skipped
+
+    ----- stderr -----
+    ");
+
+    // Skips apply in addition to `SKIP`, which skips the remaining hook here.
+    cmd_snapshot!(context.filters(), context.run().env("SKIP", "lint"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    error: No hooks found after filtering with the given selectors
+    ");
+
+    cmd_snapshot!(context.filters(), context.command().args(["skip", "remove", "mypy", "black"]), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    No longer skipping `mypy`
+
+    ----- stderr -----
+    warning: `black` is not skipped, see `prek skip list`
+    ");
+
+    cmd_snapshot!(context.filters(), context.command().args(["skip", "remove", "--all"]), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    No longer skipping `app/`
+
+    ----- stderr -----
+    ");
+
+    assert!(!context.work_dir().child(".git/prek/skips").exists());
+
+    // Hand-edited files may contain comments and invalid selectors.
+    context
+        .work_dir()
+        .child(".git/prek/skips")
+        .write_str("# Slow locally\nlint\n!mypy\n")?;
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Running hooks for `app`:
+    Lint...................................................(skipped locally)Skipped
+    Mypy.....................................................................Passed
+
+    Running hooks for `.`:
+    Lint...................................................(skipped locally)Skipped
+    Mypy.....................................................................Passed
+
+    ----- stderr -----
+    warning: Ignoring invalid skip `!mypy` in `.git/prek/skips`: skip selectors can't be negated
+    ");
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use assert_cmd::assert::OutputAssertExt;
+use assert_fs::assert::PathAssert;
+use assert_fs::fixture::{FileWriteStr, PathChild};
+
+use crate::common::{TestContext, cmd_snapshot};
+
+mod common;
+
+#[test]
+fn stats() -> anyhow::Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    context.write_pre_commit_config(indoc::indoc! {r#"
+        repos:
+          - repo: local
+            hooks:
+              - id: stable
+                name: Stable
+                language: system
+                entry: python3 -c "print('ok')"
+                always_run: true
+              - id: flaky
+                name: Flaky
+                language: system
+                entry: python3 -c "import os, sys; sys.exit(os.path.exists('fail'))"
+                always_run: true
+                pass_filenames: false
+    "#});
+    context.git_add(".");
+
+    let filters = context
+        .filters()
+        .into_iter()
+        .chain([
+            (r"\d+\.\d{2}s", "[TIME]"),
+            (r#""(mean|max|total)": [\d.e-]+"#, r#""$1": [TIME]"#),
+        ])
+        .collect::<Vec<_>>();
+
+    cmd_snapshot!(filters.clone(), context.command().arg("stats"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    No hook runs recorded yet, set `PREK_STATS=1` to record them
+
+    ----- stderr -----
+    ");
+
+    // Runs are not recorded unless enabled.
+    context.run().assert().success();
+    context.run().env("PREK_STATS", "1").assert().success();
+    context.work_dir().child("fail").write_str("")?;
+    context.run().env("PREK_STATS", "1").assert().failure();
+    fs_err::remove_file(context.work_dir().child("fail"))?;
+    context.run().env("PREK_STATS", "1").assert().success();
+
+    // The default order depends on the durations, list flaky hooks only to keep it stable.
+    cmd_snapshot!(filters.clone(), context.command().args(["stats", "--flaky"]), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Hook       Runs  Failed   Flips       Mean        Max      Total
+    .:flaky       3       1       2      [TIME]      [TIME]      [TIME]
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(filters.clone(), context.command().args(["stats", "--flaky", "--output-format", "json"]), @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    [
+      {
+        "project": ".",
+        "hook": "flaky",
+        "runs": 3,
+        "failed": 1,
+        "flips": 2,
+        "mean": [TIME],
+        "max": [TIME],
+        "total": [TIME]
+      }
+    ]
+
+    ----- stderr -----
+    "#);
+
+    cmd_snapshot!(filters.clone(), context.command().args(["stats", "--clear"]), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Cleared hook run history
+
+    ----- stderr -----
+    ");
+
+    context
+        .home_dir()
+        .child("stats.jsonl")
+        .assert(predicates::path::missing());
+
+    Ok(())
+}
@@ -7,7 +7,7 @@ use std::process::Command;
 
 use crate::common::{TestContext, cmd_snapshot};
 use assert_fs::fixture::ChildPath;
-use prek_consts::MANIFEST_FILE;
+use prek_consts::{MANIFEST_FILE, TOML_MANIFEST_FILE};
 
 fn create_hook_repo(context: &TestContext, repo_name: &str) -> Result<PathBuf> {
     let repo_dir = context.home_dir().child(format!("test-repos/{repo_name}"));
@@ -204,6 +204,60 @@ fn try_repo_failing_hook() -> Result<()> {
     Ok(())
 }
 
+/// `prek-hooks.toml` is preferred over `.pre-commit-hooks.yaml` when a repo has both.
+#[test]
+fn try_repo_toml_manifest() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+    context.configure_git_author();
+    context.disable_auto_crlf();
+
+    context.work_dir().child("test.txt").write_str("test")?;
+    context.git_add(".");
+
+    let repo_path = create_failing_hook_repo(&context, "try-repo-toml")?;
+    ChildPath::new(&repo_path)
+        .child(TOML_MANIFEST_FILE)
+        .write_str(indoc::indoc! {r#"
+            [[hooks]]
+            id = "toml-hook"
+            name = "TOML Hook"
+            entry = "echo"
+            language = "system"
+            files = "\\.txt$"
+        "#})?;
+    Command::new("git")
+        .args(["add", "."])
+        .current_dir(&repo_path)
+        .assert()
+        .success();
+    Command::new("git")
+        .args(["commit", "-m", "Add TOML manifest"])
+        .current_dir(&repo_path)
+        .assert()
+        .success();
+
+    let mut filters = context.filters();
+    filters.extend([(r"[a-f0-9]{40}", "[COMMIT_SHA]")]);
+
+    cmd_snapshot!(filters, context.try_repo().arg(&repo_path), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Using config:
+    repos:
+      - repo: [HOME]/test-repos/try-repo-toml
+        rev: [COMMIT_SHA]
+        hooks:
+          - id: toml-hook
+    TOML Hook................................................................Passed
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
+
 #[test]
 fn try_repo_specific_hook() -> Result<()> {
     let context = TestContext::new();
@@ -55,6 +55,136 @@ fn validate_config() -> anyhow::Result<()> {
     ----- stderr -----
     error: Failed to parse `config-1.yaml`
       caused by: Invalid remote repo: missing field `rev`
+         --> config-1.yaml:2:3
+          |
+        2 |   - repo: https://github.com/pre-commit/pre-commit-hooks
+          |   ^
+    ");
+
+    context
+        .work_dir()
+        .child("config-2.yaml")
+        .write_str(indoc::indoc! {r"
+            repos:
+              - repo: local
+                hooks:
+                  - id: local
+                    name: local
+                    language: system
+                    entry: echo
+                    stages: [pre-commit, on-commit]
+        "})?;
+
+    // Errors inside a repo point at the repo entry.
+    cmd_snapshot!(context.filters(), context.validate_config().arg("config-2.yaml"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    error: Failed to parse `config-2.yaml`
+      caused by: Invalid local repo: unknown variant `on-commit`, expected one of `manual`, `commit-msg`, `post-checkout`, `post-commit`, `post-index-change`, `post-merge`, `post-rewrite`, `pre-auto-gc`, `commit`, `pre-commit`, `merge-commit`, `pre-merge-commit`, `pre-push`, `push`, `pre-rebase`, `prepare-commit-msg`, `reference-transaction`
+         --> config-2.yaml:2:3
+          |
+        2 |   - repo: local
+          |   ^
+    ");
+
+    Ok(())
+}
+
+#[test]
+fn validate_config_strict() -> anyhow::Result<()> {
+    let context = TestContext::new();
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        default_stages: [commit]
+        repos:
+          - repo: https://github.com/pre-commit/pre-commit-hooks
+            rev: main
+            hooks:
+              - id: trailing-whitespace
+                stages: [pre-commit, push]
+                unknown: true
+          - repo: local
+            hooks:
+              - id: local
+                name: local
+                language: system
+                entry: echo
+    "});
+
+    // Without `--strict`, these are only warnings.
+    cmd_snapshot!(context.filters(), context.validate_config().arg(CONFIG_FILE), @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    warning: Ignored unexpected keys in `.pre-commit-config.yaml`: `repos[0].hooks[0].unknown`
+    warning: The following repos have mutable `rev` fields (moving tag / branch):
+    https://github.com/pre-commit/pre-commit-hooks: main
+    Mutable references are never updated after first install and are not supported.
+    See https://pre-commit.com/#using-the-latest-version-for-a-repository for more details.
+    Hint: `prek auto-update` often fixes this",
+
+    success: All configs are valid
+    "#);
+
+    cmd_snapshot!(context.filters(), context.validate_config().arg("--strict").arg(CONFIG_FILE), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    error: .pre-commit-config.yaml:1:18: Stage `commit` is deprecated, use `pre-commit` instead
+    error: .pre-commit-config.yaml:4:5: Repo `https://github.com/pre-commit/pre-commit-hooks` has a mutable `rev: main` (moving tag / branch)
+    error: .pre-commit-config.yaml:7:30: Stage `push` is deprecated, use `pre-push` instead
+    error: .pre-commit-config.yaml:8:9: Unexpected key `repos[0].hooks[0].unknown`
+    ");
+
+    context
+        .work_dir()
+        .child("config-1.yaml")
+        .write_str(indoc::indoc! {r"
+            exclude: '('
+            repos:
+              - repo: local
+                hooks:
+                  - id: local
+                    name: local
+                    language: system
+                    entry: echo
+                    files: '[a-'
+        "})?;
+
+    // Invalid file patterns are reported with their location.
+    cmd_snapshot!(context.filters(), context.validate_config().arg("--strict").arg("config-1.yaml"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    error: config-1.yaml:1:1: Invalid file pattern: Parsing error at position 1: Opening parenthesis without closing parenthesis
+    error: config-1.yaml:9:9: Invalid file pattern: Parsing error at position 3: Invalid character class
+    ");
+
+    // A valid config passes.
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: https://github.com/pre-commit/pre-commit-hooks
+            rev: v5.0.0
+            hooks:
+              - id: trailing-whitespace
+                stages: [pre-commit, pre-push]
+    "});
+    cmd_snapshot!(context.filters(), context.validate_config().arg("--strict").arg(CONFIG_FILE), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    success: All configs are valid
     ");
 
     Ok(())
@@ -117,6 +247,53 @@ fn validate_manifest() -> anyhow::Result<()> {
     ----- stderr -----
     error: Failed to parse `hooks-1.yaml`
       caused by: .[0]: missing field `entry` at line 1 column 5
+         --> hooks-1.yaml:1:5
+          |
+        1 | -   id: check-added-large-files
+          |     ^
+    ");
+
+    Ok(())
+}
+
+#[test]
+fn validate_toml_manifest() -> anyhow::Result<()> {
+    let context = TestContext::new();
+
+    context
+        .work_dir()
+        .child("prek-hooks.toml")
+        .write_str(indoc::indoc! {r#"
+            [[hooks]]
+            id = "check-added-large-files"
+            name = "check for added large files"
+            description = "prevents giant files from being committed."
+            entry = "check-added-large-files"
+            language = "python"
+            stages = ["pre-commit", "pre-push", "manual"]
+        "#})?;
+    context
+        .work_dir()
+        .child("hooks-1.toml")
+        .write_str(indoc::indoc! {r#"
+            [[hooks]]
+            id = "check-added-large-files"
+            name = "check for added large files"
+            language = "python"
+        "#})?;
+
+    cmd_snapshot!(context.filters(), context.validate_manifest().arg("prek-hooks.toml").arg("hooks-1.toml"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    error: Failed to parse `hooks-1.toml`
+      caused by: TOML parse error at line 1, column 1
+      |
+    1 | [[hooks]]
+      | ^^^^^^^^^
+    missing field `entry`
     ");
 
     Ok(())
@@ -185,3 +362,119 @@ fn unexpected_keys_warning() {
     success: All configs are valid
     ");
 }
+
+/// Misspelled keys get a suggestion of the key they are likely a typo of.
+#[test]
+fn unexpected_keys_suggestion() {
+    let context = TestContext::new();
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        fail-fast: true
+        repos:
+          - repo: local
+            hooks:
+              - id: test-hook
+                name: Test Hook
+                entry: echo test
+                language: system
+                typs: [python]
+                pass_filename: false
+                color: red
+    "});
+
+    cmd_snapshot!(context.filters(), context.validate_config().arg(CONFIG_FILE), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    warning: Ignored unexpected keys in `.pre-commit-config.yaml`:
+      - `fail-fast` (did you mean `fail_fast`?)
+      - `repos[0].hooks[0].color`
+      - `repos[0].hooks[0].pass_filename` (did you mean `pass_filenames`?)
+      - `repos[0].hooks[0].typs` (did you mean `types`?)
+    success: All configs are valid
+    ");
+
+    cmd_snapshot!(context.filters(), context.validate_config().arg("--strict").arg(CONFIG_FILE), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    error: .pre-commit-config.yaml:1:1: Unexpected key `fail-fast`, did you mean `fail_fast`?
+    error: .pre-commit-config.yaml:9:9: Unexpected key `repos[0].hooks[0].typs`, did you mean `types`?
+    error: .pre-commit-config.yaml:10:9: Unexpected key `repos[0].hooks[0].pass_filename`, did you mean `pass_filenames`?
+    error: .pre-commit-config.yaml:11:9: Unexpected key `repos[0].hooks[0].color`
+    ");
+}
+
+#[test]
+fn warnings_level() {
+    let context = TestContext::new();
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: test-hook
+                name: Test Hook
+                entry: echo test
+                language: system
+        unexpected_top_level_key: some_value
+    "});
+
+    cmd_snapshot!(context.filters(), context.validate_config().arg(CONFIG_FILE).arg("--warnings").arg("error"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    error: Ignored unexpected keys in `.pre-commit-config.yaml`: `unexpected_top_level_key`
+    success: All configs are valid
+    error: Warnings were treated as errors
+    ");
+
+    cmd_snapshot!(context.filters(), context.validate_config().arg(CONFIG_FILE).arg("--warnings").arg("ignore"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    success: All configs are valid
+    ");
+
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: test-hook
+                name: Test Hook
+                entry: echo test
+                language: system
+        unexpected_top_level_key: some_value
+        warnings: error
+    "});
+
+    cmd_snapshot!(context.filters(), context.validate_config().arg(CONFIG_FILE), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    error: Ignored unexpected keys in `.pre-commit-config.yaml`: `unexpected_top_level_key`
+    success: All configs are valid
+    error: Warnings were treated as errors
+    ");
+
+    // The command line option takes precedence over the config.
+    cmd_snapshot!(context.filters(), context.validate_config().arg(CONFIG_FILE).arg("--warnings").arg("warn"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    warning: Ignored unexpected keys in `.pre-commit-config.yaml`: `unexpected_top_level_key`
+    success: All configs are valid
+    ");
+}
@@ -0,0 +1,82 @@
+use assert_fs::fixture::{FileWriteStr, PathChild};
+
+use crate::common::{TestContext, cmd_snapshot};
+
+mod common;
+
+/// `prek verify` runs the hooks on each commit of a range, with the config of that commit.
+#[test]
+fn verify() -> anyhow::Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+    context.configure_git_author();
+    context.write_pre_commit_config(indoc::indoc! {r"
+        repos:
+          - repo: local
+            hooks:
+              - id: no-todo
+                name: no-todo
+                language: fail
+                entry: Remove the todo files
+                files: \.todo$
+    "});
+    let cwd = context.work_dir();
+    cwd.child("a.txt").write_str("a")?;
+    context.git_add(".");
+    context.git_commit("Initial commit");
+    context.git_tag("base");
+
+    cwd.child("b.todo").write_str("b")?;
+    context.git_add(".");
+    context.git_commit("Add a todo file");
+
+    cwd.child("a.txt").write_str("aa")?;
+    context.git_add(".");
+    context.git_commit("Update a.txt");
+
+    let filters = context
+        .filters()
+        .into_iter()
+        .chain([(r"\b[0-9a-f]{10}\b", "[COMMIT]")])
+        .collect::<Vec<_>>();
+
+    cmd_snapshot!(filters.clone(), context.command().args(["verify", "base.."]), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    [COMMIT]  Add a todo file  failed: no-todo
+    [COMMIT]  Update a.txt  passed
+
+    ----- stderr -----
+    1 of 2 commits would have failed the hooks
+    ");
+
+    cmd_snapshot!(filters.clone(), context.command().args(["verify", "base..HEAD", "--combined"]), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    base..HEAD  2 commits combined  failed: no-todo
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(filters.clone(), context.command().args(["verify", "HEAD~1..HEAD"]), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    [COMMIT]  Update a.txt  passed
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(filters, context.command().args(["verify", "HEAD"]), @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: Expected a revision range like `main..HEAD`, got `HEAD`
+    ");
+
+    Ok(())
+}
@@ -822,6 +822,10 @@ fn skips() -> Result<()> {
     ----- stderr -----
     error: Failed to parse `project3/.pre-commit-config.yaml`
       caused by: did not find expected node content at line 2 column 1, while parsing a flow node
+         --> project3/.pre-commit-config.yaml:1:16
+          |
+        1 | invalid_yaml: [
+          |                ^
     ");
 
     // Should skip the invalid config
@@ -851,6 +855,92 @@ fn skips() -> Result<()> {
     Ok(())
 }
 
+/// Test glob, negated and language selectors.
+#[test]
+fn glob_negated_and_language_selectors() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    let config = indoc! {r"
+    repos:
+      - repo: local
+        hooks:
+        - id: lint-a
+          name: Lint A
+          language: system
+          entry: echo
+          pass_filenames: false
+        - id: lint-b
+          name: Lint B
+          language: system
+          entry: echo
+          pass_filenames: false
+        - id: check
+          name: Check
+          language: fail
+          entry: check failed
+    "};
+
+    context.setup_workspace(&["legacy"], config)?;
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run().arg("lint-*"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Running hooks for `legacy`:
+    Lint A...................................................................Passed
+    Lint B...................................................................Passed
+
+    Running hooks for `.`:
+    Lint A...................................................................Passed
+    Lint B...................................................................Passed
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.run().arg("!legacy/").arg("!check"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lint A...................................................................Passed
+    Lint B...................................................................Passed
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.run().arg("language:system").arg("!legacy:").arg("!*-b"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lint A...................................................................Passed
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.run().arg("--skip").arg("!lint-a"), @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: Invalid selector: `!lint-a`
+      caused by: skip selectors can't be negated
+    ");
+
+    cmd_snapshot!(context.filters(), context.run().arg("language:cobol"), @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: Invalid selector: `language:cobol`
+      caused by: unknown language `cobol`, expected one of: conda, coursier, dart, docker, docker_image, dotnet, fail, golang, haskell, lua, node, perl, python, r, ruby, rust, swift, pygrep, script, system
+    ");
+
+    Ok(())
+}
+
 #[test]
 fn workspace_no_projects() {
     let context = TestContext::new();
@@ -1290,3 +1380,157 @@ fn orphan_projects() -> Result<()> {
 
     Ok(())
 }
+
+/// Projects run concurrently by default, but their output is printed per project in workspace order.
+#[test]
+fn concurrent_projects() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    let config = indoc! {r"
+    repos:
+      - repo: local
+        hooks:
+        - id: echo
+          name: echo
+          language: system
+          entry: echo
+          verbose: true
+    "};
+
+    context.setup_workspace(&["a", "b", "b/c"], config)?;
+    let cwd = context.work_dir();
+    cwd.child("a/file.txt").write_str("a")?;
+    cwd.child("b/file.txt").write_str("b")?;
+    cwd.child("b/c/file.txt").write_str("c")?;
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run(), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Running hooks for `b/c`:
+    echo.....................................................................Passed
+    - hook id: echo
+    - duration: [TIME]
+
+      file.txt .pre-commit-config.yaml
+
+    Running hooks for `a`:
+    echo.....................................................................Passed
+    - hook id: echo
+    - duration: [TIME]
+
+      file.txt .pre-commit-config.yaml
+
+    Running hooks for `b`:
+    echo.....................................................................Passed
+    - hook id: echo
+    - duration: [TIME]
+
+      c/.pre-commit-config.yaml .pre-commit-config.yaml file.txt c/file.txt
+
+    Running hooks for `.`:
+    echo.....................................................................Passed
+    - hook id: echo
+    - duration: [TIME]
+
+      a/.pre-commit-config.yaml .pre-commit-config.yaml b/c/.pre-commit-config.yaml a/file.txt b/.pre-commit-config.yaml b/c/file.txt b/file.txt
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.run().arg("--serial-projects"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Running hooks for `b/c`:
+    echo.....................................................................Passed
+    - hook id: echo
+    - duration: [TIME]
+
+      file.txt .pre-commit-config.yaml
+
+    Running hooks for `a`:
+    echo.....................................................................Passed
+    - hook id: echo
+    - duration: [TIME]
+
+      file.txt .pre-commit-config.yaml
+
+    Running hooks for `b`:
+    echo.....................................................................Passed
+    - hook id: echo
+    - duration: [TIME]
+
+      c/.pre-commit-config.yaml .pre-commit-config.yaml file.txt c/file.txt
+
+    Running hooks for `.`:
+    echo.....................................................................Passed
+    - hook id: echo
+    - duration: [TIME]
+
+      a/.pre-commit-config.yaml .pre-commit-config.yaml b/c/.pre-commit-config.yaml a/file.txt b/.pre-commit-config.yaml b/c/file.txt b/file.txt
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}
+
+/// `--fail-fast=project` skips the remaining hooks of the failing project only.
+#[test]
+fn fail_fast_project() -> Result<()> {
+    let context = TestContext::new();
+    context.init_project();
+
+    let config = indoc! {r#"
+    repos:
+      - repo: local
+        hooks:
+        - id: failing-hook
+          name: failing-hook
+          language: system
+          entry: python3 -c 'exit(1)'
+          always_run: true
+        - id: passing-hook
+          name: passing-hook
+          language: system
+          entry: python3 -c 'print("passed")'
+          always_run: true
+    "#};
+
+    context.setup_workspace(&["project2"], config)?;
+    context.git_add(".");
+
+    cmd_snapshot!(context.filters(), context.run().arg("--fail-fast=project"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    Running hooks for `project2`:
+    failing-hook.............................................................Failed
+    - hook id: failing-hook
+    - exit code: 1
+
+    Running hooks for `.`:
+    failing-hook.............................................................Failed
+    - hook id: failing-hook
+    - exit code: 1
+
+    ----- stderr -----
+    ");
+
+    cmd_snapshot!(context.filters(), context.run().arg("--serial-projects").arg("--fail-fast"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    Running hooks for `project2`:
+    failing-hook.............................................................Failed
+    - hook id: failing-hook
+    - exit code: 1
+
+    ----- stderr -----
+    ");
+
+    Ok(())
+}